@@ -1,7 +1,16 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
+use sp1_core::air::MachineAir;
 use sp1_core::io::SP1Stdin;
-use sp1_core::runtime::{Program, Runtime};
+use sp1_core::runtime::{ExecutionRecord, Instruction, Opcode, Program, Runtime, SyscallCode};
+use sp1_core::stark::{
+    BatchedLogUp, LocalProver, LogUp, LookupArgument, RiscvAir, StarkGenericConfig,
+};
+use sp1_core::syscall::precompiles::poseidon2::Poseidon2CompressChip;
+use sp1_core::syscall::precompiles::uint256::{Uint256MulChip, Uint256SqrChip};
 use sp1_core::utils::{prove, BabyBearPoseidon2, SP1CoreOpts};
+use std::sync::Arc;
 
 #[allow(unreachable_code)]
 pub fn criterion_benchmark(c: &mut Criterion) {
@@ -33,5 +42,157 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+pub fn lookup_argument_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lookup");
+    group.sample_size(10);
+    let elf_path = "../tests/uint256-mul/elf/riscv32im-succinct-zkvm-elf";
+    let program = Program::from_elf(elf_path);
+    let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+    runtime.run().unwrap();
+    let backends: [Arc<dyn LookupArgument>; 2] = [Arc::new(BatchedLogUp), Arc::new(LogUp)];
+    for lookup in backends {
+        let name = lookup.name();
+        let machine = RiscvAir::machine_with_lookup_argument(BabyBearPoseidon2::new(), lookup);
+        let (pk, _) = machine.setup(runtime.program.as_ref());
+        group.bench_function(format!("uint256-mul:{}", name), |b| {
+            b.iter(|| {
+                let mut challenger = machine.config().challenger();
+                machine.prove::<LocalProver<_, _>>(
+                    &pk,
+                    black_box(runtime.record.clone()),
+                    &mut challenger,
+                    SP1CoreOpts::default(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+pub fn poseidon2_trace_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("poseidon2-trace");
+    group.sample_size(10);
+    const NUM_PERMUTATIONS: usize = 10_000;
+
+    // Compress two digests in place over and over.
+    let mut instructions = vec![];
+    for i in 0..16 {
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 29, 0, i * 0x0765_4321, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 0x1000 + i * 4, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+        ]);
+    }
+    for _ in 0..NUM_PERMUTATIONS {
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::POSEIDON2_COMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+    }
+    let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+    runtime.run().unwrap();
+
+    let chip = Poseidon2CompressChip::new();
+    group.bench_function(format!("serial:{}", NUM_PERMUTATIONS), |b| {
+        b.iter(|| {
+            chip.generate_trace_in_chunks::<BabyBear>(
+                black_box(&runtime.record),
+                &mut ExecutionRecord::default(),
+                NUM_PERMUTATIONS,
+            )
+        })
+    });
+    group.bench_function(format!("parallel:{}", NUM_PERMUTATIONS), |b| {
+        b.iter(|| {
+            MachineAir::<BabyBear>::generate_trace(
+                &chip,
+                black_box(&runtime.record),
+                &mut ExecutionRecord::default(),
+            )
+        })
+    });
+    group.finish();
+}
+
+pub fn uint256_sqr_trace_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("uint256-sqr-trace");
+    group.sample_size(10);
+    const NUM_SQUARES: usize = 10_000;
+    const X_PTR: u32 = 0x1000;
+    const MODULUS_PTR: u32 = 0x2000;
+
+    // Square x in place over and over, with `uint256_mul` and the modulus after x, and with
+    // `uint256_sqr` and the modulus on its own.
+    let x: [u32; 8] = core::array::from_fn(|i| 0x1234_5678 ^ i as u32);
+    let modulus: [u32; 8] = core::array::from_fn(|i| 0xffff_fff0 | i as u32);
+    let square_program = |code: SyscallCode, arg2: u32, modulus_ptr: u32| {
+        let mut instructions = vec![];
+        for (ptr, words) in [(X_PTR, x), (modulus_ptr, modulus)] {
+            for (i, word) in words.into_iter().enumerate() {
+                instructions.extend(vec![
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, ptr + i as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]);
+            }
+        }
+        for _ in 0..NUM_SQUARES {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        runtime.record
+    };
+    let mul_record = square_program(SyscallCode::UINT256_MUL, X_PTR, X_PTR + 32);
+    let sqr_record = square_program(SyscallCode::UINT256_SQR, MODULUS_PTR, MODULUS_PTR);
+
+    // The names report the columns of each chip. Both reductions have the same witness columns,
+    // so the square only saves the columns of the second operand. It also computes half the limb
+    // products when its constraints are evaluated, which trace generation does not measure.
+    let mul_chip = Uint256MulChip::new();
+    let mul_width = BaseAir::<BabyBear>::width(&mul_chip);
+    group.bench_function(format!("mul:{}:cols={}", NUM_SQUARES, mul_width), |b| {
+        b.iter(|| {
+            MachineAir::<BabyBear>::generate_trace(
+                &mul_chip,
+                black_box(&mul_record),
+                &mut ExecutionRecord::default(),
+            )
+        })
+    });
+    let sqr_chip = Uint256SqrChip::new();
+    let sqr_width = BaseAir::<BabyBear>::width(&sqr_chip);
+    group.bench_function(format!("sqr:{}:cols={}", NUM_SQUARES, sqr_width), |b| {
+        b.iter(|| {
+            MachineAir::<BabyBear>::generate_trace(
+                &sqr_chip,
+                black_box(&sqr_record),
+                &mut ExecutionRecord::default(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    lookup_argument_benchmark,
+    poseidon2_trace_benchmark,
+    uint256_sqr_trace_benchmark
+);
 criterion_main!(benches);
@@ -599,6 +599,49 @@ pub trait MemoryAirBuilder: BaseAirBuilder {
     }
 }
 
+/// A trait which contains methods related to the lookup tables of the program in an AIR.
+pub trait LookupTableAirBuilder: BaseAirBuilder {
+    /// Sends a lookup of `key` in the table `table_id`, which maps it to `value`.
+    fn send_table_lookup(
+        &mut self,
+        table_id: impl Into<Self::Expr>,
+        key: Word<impl Into<Self::Expr>>,
+        value: Word<impl Into<Self::Expr>>,
+        multiplicity: impl Into<Self::Expr>,
+    ) {
+        let values = once(table_id.into())
+            .chain(key.into_iter().map(|x| x.into()))
+            .chain(value.into_iter().map(|x| x.into()))
+            .collect();
+
+        self.send(AirInteraction::new(
+            values,
+            multiplicity.into(),
+            InteractionKind::LookupTable,
+        ));
+    }
+
+    /// Receives a lookup of `key` in the table `table_id`, which maps it to `value`.
+    fn receive_table_lookup(
+        &mut self,
+        table_id: impl Into<Self::Expr>,
+        key: Word<impl Into<Self::Expr>>,
+        value: Word<impl Into<Self::Expr>>,
+        multiplicity: impl Into<Self::Expr>,
+    ) {
+        let values = once(table_id.into())
+            .chain(key.into_iter().map(|x| x.into()))
+            .chain(value.into_iter().map(|x| x.into()))
+            .collect();
+
+        self.receive(AirInteraction::new(
+            values,
+            multiplicity.into(),
+            InteractionKind::LookupTable,
+        ));
+    }
+}
+
 /// A trait which contains methods related to program interactions in an AIR.
 pub trait ProgramAirBuilder: BaseAirBuilder {
     /// Sends an instruction.
@@ -705,6 +748,7 @@ pub trait SP1AirBuilder:
     + AluAirBuilder
     + MemoryAirBuilder
     + ProgramAirBuilder
+    + LookupTableAirBuilder
 {
 }
 
@@ -724,6 +768,7 @@ impl<AB: BaseAirBuilder> WordAirBuilder for AB {}
 impl<AB: BaseAirBuilder> AluAirBuilder for AB {}
 impl<AB: BaseAirBuilder> MemoryAirBuilder for AB {}
 impl<AB: BaseAirBuilder> ProgramAirBuilder for AB {}
+impl<AB: BaseAirBuilder> LookupTableAirBuilder for AB {}
 impl<AB: BaseAirBuilder> ExtensionAirBuilder for AB {}
 impl<AB: BaseAirBuilder + AirBuilderWithPublicValues> MachineAirBuilder for AB {}
 impl<AB: BaseAirBuilder + AirBuilderWithPublicValues> SP1AirBuilder for AB {}
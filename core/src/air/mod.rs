@@ -2,6 +2,7 @@ mod builder;
 mod extension;
 mod interaction;
 mod machine;
+mod packed;
 mod polynomial;
 mod public_values;
 mod sub_builder;
@@ -11,6 +12,7 @@ pub use builder::*;
 pub use extension::*;
 pub use interaction::*;
 pub use machine::*;
+pub use packed::*;
 pub use polynomial::*;
 pub use public_values::*;
 pub use sub_builder::*;
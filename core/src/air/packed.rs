@@ -0,0 +1,132 @@
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use super::MachineAir;
+
+/// The order in which the values of a trace are written to a [PackedTrace].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceLayout {
+    /// The values of each row, one row after the other, as in a `RowMajorMatrix`.
+    RowMajor,
+    /// The values of each column, one column after the other.
+    ColumnMajor,
+    /// The rows in blocks of `block_height` rows, each written column by column, so that a block
+    /// of a column is contiguous. The last block has the remaining rows.
+    Interleaved { block_height: usize },
+}
+
+/// A trace written as canonical `u32` values in a [TraceLayout], a flat buffer which can be
+/// uploaded to a GPU as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedTrace {
+    pub layout: TraceLayout,
+    pub width: usize,
+    pub height: usize,
+    pub values: Vec<u32>,
+}
+
+impl PackedTrace {
+    /// Writes `trace` into a buffer in `layout`.
+    pub fn pack<F: PrimeField32>(trace: &RowMajorMatrix<F>, layout: TraceLayout) -> Self {
+        if let TraceLayout::Interleaved { block_height } = layout {
+            assert!(block_height > 0, "block height must be nonzero");
+        }
+        let mut packed = Self {
+            layout,
+            width: trace.width(),
+            height: trace.height(),
+            values: vec![0; trace.values.len()],
+        };
+        for (row, values) in trace.values.chunks_exact(packed.width.max(1)).enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                let index = packed.index(row, col);
+                packed.values[index] = value.as_canonical_u32();
+            }
+        }
+        packed
+    }
+
+    /// Reads the trace back from the buffer, in the standard layout of the verifier.
+    pub fn unpack<F: PrimeField32>(&self) -> RowMajorMatrix<F> {
+        let mut values = Vec::with_capacity(self.values.len());
+        for row in 0..self.height {
+            for col in 0..self.width {
+                values.push(F::from_canonical_u32(self.values[self.index(row, col)]));
+            }
+        }
+        RowMajorMatrix::new(values, self.width)
+    }
+
+    /// Returns the index in the buffer of the value at `row` and `col`.
+    pub fn index(&self, row: usize, col: usize) -> usize {
+        match self.layout {
+            TraceLayout::RowMajor => row * self.width + col,
+            TraceLayout::ColumnMajor => col * self.height + row,
+            TraceLayout::Interleaved { block_height } => {
+                let block_start = row - row % block_height;
+                let block_rows = block_height.min(self.height - block_start);
+                block_start * self.width + col * block_rows + row % block_height
+            }
+        }
+    }
+}
+
+/// A chip whose trace can be generated into a [PackedTrace], e.g. for GPU trace commitment.
+///
+/// The default implementation packs the trace of [MachineAir::generate_trace], so the trace of the
+/// CPU prover is unchanged, and a chip may override it to write its rows into the buffer directly.
+pub trait PackedTraceAir<F: PrimeField32>: MachineAir<F> {
+    /// Generates the trace for a given execution record into a buffer in `layout`.
+    fn generate_packed_trace(
+        &self,
+        input: &Self::Record,
+        output: &mut Self::Record,
+        layout: TraceLayout,
+    ) -> PackedTrace {
+        PackedTrace::pack(&self.generate_trace(input, output), layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    use super::{PackedTrace, TraceLayout};
+
+    #[test]
+    fn test_packed_trace_layouts() {
+        // A trace of 5 rows and 3 columns whose value at row `r` and column `c` is `10 r + c`.
+        let values = (0..5)
+            .flat_map(|r| (0..3).map(move |c| BabyBear::from_canonical_u32(10 * r + c)))
+            .collect::<Vec<_>>();
+        let trace = RowMajorMatrix::new(values, 3);
+
+        let packed = PackedTrace::pack(&trace, TraceLayout::RowMajor);
+        assert_eq!(packed.values[..6], [0, 1, 2, 10, 11, 12]);
+
+        let packed = PackedTrace::pack(&trace, TraceLayout::ColumnMajor);
+        assert_eq!(packed.values[..7], [0, 10, 20, 30, 40, 1, 11]);
+
+        let layout = TraceLayout::Interleaved { block_height: 2 };
+        let packed = PackedTrace::pack(&trace, layout);
+        assert_eq!(
+            packed.values,
+            [0, 10, 1, 11, 2, 12, 20, 30, 21, 31, 22, 32, 40, 41, 42]
+        );
+
+        for layout in [
+            TraceLayout::RowMajor,
+            TraceLayout::ColumnMajor,
+            TraceLayout::Interleaved { block_height: 2 },
+            TraceLayout::Interleaved { block_height: 8 },
+        ] {
+            let packed = PackedTrace::pack(&trace, layout);
+            let unpacked = packed.unpack::<BabyBear>();
+            assert_eq!(unpacked.width, trace.width);
+            assert_eq!(unpacked.values, trace.values);
+        }
+    }
+}
@@ -71,6 +71,25 @@ impl<T> Polynomial<T> {
             coefficients: result,
         }
     }
+
+    /// Computes the square of the polynomial.
+    ///
+    /// Each product `a_i * a_j` with `i != j` appears twice in the square, so it is computed once
+    /// and doubled, which takes `n (n + 1) / 2` products of coefficients instead of `n^2`.
+    pub fn square(&self) -> Self
+    where
+        T: AbstractField,
+    {
+        let len = self.coefficients.len();
+        let mut result = vec![T::zero(); 2 * len - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            result[2 * i] = result[2 * i].clone() + a.clone() * a.clone();
+            for (j, b) in self.coefficients.iter().enumerate().skip(i + 1) {
+                result[i + j] = result[i + j].clone() + (a.clone() * b.clone()).double();
+            }
+        }
+        Self::new(result)
+    }
 }
 
 impl<T> FromIterator<T> for Polynomial<T> {
@@ -40,6 +40,9 @@ pub struct PublicValues<W, T> {
 
     /// The shard number.
     pub shard: T,
+
+    /// The number of cycles executed in the shard.
+    pub num_cycles: T,
 }
 
 impl PublicValues<u32, u32> {
@@ -60,6 +63,7 @@ impl PublicValues<u32, u32> {
             .chain(once(F::from_canonical_u32(self.next_pc)))
             .chain(once(F::from_canonical_u32(self.exit_code)))
             .chain(once(F::from_canonical_u32(self.shard)))
+            .chain(once(F::from_canonical_u32(self.num_cycles)))
             .collect_vec();
 
         assert!(
@@ -89,16 +93,19 @@ impl<T: Clone + Debug> PublicValues<Word<T>, T> {
             .unwrap();
 
         // Collecting the remaining items into a tuple.  Note that it is only getting the first
-        // four items, as the rest would be padded values.
+        // five items, as the rest would be padded values.
         let remaining_items = iter.collect_vec();
-        if remaining_items.len() < 4 {
+        if remaining_items.len() < 5 {
             panic!("Invalid number of items in the serialized vector.");
         }
 
-        let [start_pc, next_pc, exit_code, shard] = match &remaining_items.as_slice()[0..4] {
-            [start_pc, next_pc, exit_code, shard] => [start_pc, next_pc, exit_code, shard],
-            _ => unreachable!(),
-        };
+        let [start_pc, next_pc, exit_code, shard, num_cycles] =
+            match &remaining_items.as_slice()[0..5] {
+                [start_pc, next_pc, exit_code, shard, num_cycles] => {
+                    [start_pc, next_pc, exit_code, shard, num_cycles]
+                }
+                _ => unreachable!(),
+            };
 
         Self {
             committed_value_digest,
@@ -107,6 +114,7 @@ impl<T: Clone + Debug> PublicValues<Word<T>, T> {
             next_pc: next_pc.to_owned(),
             exit_code: exit_code.to_owned(),
             shard: shard.to_owned(),
+            num_cycles: num_cycles.to_owned(),
         }
     }
 }
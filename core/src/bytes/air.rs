@@ -86,6 +86,34 @@ impl<AB: SP1AirBuilder + PairBuilder> Air<AB> for ByteChip<AB::F> {
                         channel_f,
                         mult,
                     ),
+                    ByteOpcode::AesSbox => builder.receive_byte(
+                        field_op,
+                        local.sbox,
+                        local.b,
+                        AB::F::zero(),
+                        shard,
+                        channel_f,
+                        mult,
+                    ),
+                    ByteOpcode::HexByte => builder.receive_byte_pair(
+                        field_op,
+                        local.hex_hi,
+                        local.hex_lo,
+                        local.b,
+                        AB::F::zero(),
+                        shard,
+                        channel_f,
+                        mult,
+                    ),
+                    ByteOpcode::Base64Char => builder.receive_byte(
+                        field_op,
+                        local.base64,
+                        local.b,
+                        AB::F::zero(),
+                        shard,
+                        channel_f,
+                        mult,
+                    ),
                 }
             }
         }
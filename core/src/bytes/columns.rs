@@ -42,6 +42,16 @@ pub struct BytePreprocessedCols<T> {
 
     /// A u16 value used for `U16Range`.
     pub value_u16: T,
+
+    /// The AES S-box substitution of `b`.
+    pub sbox: T,
+
+    /// The hex digits of the high and low nibbles of `b`.
+    pub hex_hi: T,
+    pub hex_lo: T,
+
+    /// The base64 character of `b`, or zero if `b` is not a 6-bit group.
+    pub base64: T,
 }
 
 /// For each byte operation in the preprocessed table, a corresponding ByteMultCols row tracks the
@@ -17,11 +17,11 @@ use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 
 use self::columns::{BytePreprocessedCols, NUM_BYTE_PREPROCESSED_COLS};
-use self::utils::shr_carry;
+use self::utils::{base64_char, shr_carry, AES_SBOX, HEX_DIGITS};
 use crate::bytes::trace::NUM_ROWS;
 
 /// The number of different byte operations.
-pub const NUM_BYTE_OPS: usize = 9;
+pub const NUM_BYTE_OPS: usize = 12;
 
 /// The number of different byte lookup channels.
 pub const NUM_BYTE_LOOKUP_CHANNELS: u32 = 16;
@@ -134,6 +134,29 @@ impl<F: Field> ByteChip<F> {
                             col.value_u16 = F::from_canonical_u32(v);
                             ByteLookupEvent::new(shard, channel, *opcode, v, 0, 0, 0)
                         }
+                        ByteOpcode::AesSbox => {
+                            let sbox = AES_SBOX[b as usize];
+                            col.sbox = F::from_canonical_u8(sbox);
+                            ByteLookupEvent::new(
+                                shard, channel, *opcode, sbox as u32, 0, b as u32, 0,
+                            )
+                        }
+                        ByteOpcode::HexByte => {
+                            let hi = HEX_DIGITS[(b >> 4) as usize];
+                            let lo = HEX_DIGITS[(b & 0xf) as usize];
+                            col.hex_hi = F::from_canonical_u8(hi);
+                            col.hex_lo = F::from_canonical_u8(lo);
+                            ByteLookupEvent::new(
+                                shard, channel, *opcode, hi as u32, lo as u32, b as u32, 0,
+                            )
+                        }
+                        ByteOpcode::Base64Char => {
+                            let base64 = base64_char(b);
+                            col.base64 = F::from_canonical_u8(base64);
+                            ByteLookupEvent::new(
+                                shard, channel, *opcode, base64 as u32, 0, b as u32, 0,
+                            )
+                        }
                     };
                     event_map.insert(event, (row_index, i));
                 }
@@ -32,6 +32,15 @@ pub enum ByteOpcode {
 
     /// U16 Range check.
     U16Range = 8,
+
+    /// The AES S-box substitution of the given byte.
+    AesSbox = 9,
+
+    /// The lowercase hex digits of the high and low nibbles of the given byte.
+    HexByte = 10,
+
+    /// The base64 character of the given 6-bit group.
+    Base64Char = 11,
 }
 
 impl ByteOpcode {
@@ -47,6 +56,9 @@ impl ByteOpcode {
             ByteOpcode::LTU,
             ByteOpcode::MSB,
             ByteOpcode::U16Range,
+            ByteOpcode::AesSbox,
+            ByteOpcode::HexByte,
+            ByteOpcode::Base64Char,
         ];
         assert_eq!(opcodes.len(), NUM_BYTE_OPS);
         opcodes
@@ -77,16 +77,37 @@ impl CpuChip {
             ecall_cols.is_hint_len.result
         };
 
+        // Compute whether this ecall is HAS_PRECOMPILE.
+        let is_has_precompile = {
+            IsZeroOperation::<AB::F>::eval(
+                builder,
+                syscall_id
+                    - AB::Expr::from_canonical_u32(SyscallCode::HAS_PRECOMPILE.syscall_id()),
+                ecall_cols.is_has_precompile,
+                is_ecall_instruction.clone(),
+            );
+            ecall_cols.is_has_precompile.result
+        };
+
         // When syscall_id is ENTER_UNCONSTRAINED, the new value of op_a should be 0.
         let zero_word = Word::<AB::F>::from(0);
         builder
             .when(is_ecall_instruction.clone() * is_enter_unconstrained)
             .assert_word_eq(local.op_a_val(), zero_word);
 
-        // When the syscall is not one of ENTER_UNCONSTRAINED or HINT_LEN, op_a shouldn't change.
+        // When syscall_id is HAS_PRECOMPILE, the new value of op_a should be a boolean.
+        let op_a_val = local.op_a_val();
+        let mut has_precompile = builder.when(is_ecall_instruction.clone() * is_has_precompile);
+        has_precompile.assert_bool(op_a_val[0]);
+        for limb in op_a_val.0[1..].iter() {
+            has_precompile.assert_zero(*limb);
+        }
+
+        // When the syscall is not one of ENTER_UNCONSTRAINED, HINT_LEN or HAS_PRECOMPILE, op_a
+        // shouldn't change.
         builder
             .when(is_ecall_instruction.clone())
-            .when_not(is_enter_unconstrained + is_hint_len)
+            .when_not(is_enter_unconstrained + is_hint_len + is_has_precompile)
             .assert_word_eq(local.op_a_val(), local.op_a_access.prev_value);
     }
 
@@ -404,6 +404,22 @@ impl CpuChip {
             .when_last_row()
             .when(local.is_real)
             .assert_eq(public_values.next_pc.clone(), local.next_pc);
+
+        // Count the cycles of the shard, from one on the first row, and verify the public value's
+        // number of cycles against the count of the last real row, in the same two cases.
+        builder.when_first_row().assert_one(local.cycle);
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .assert_eq(local.cycle + AB::Expr::one(), next.cycle);
+        builder
+            .when_transition()
+            .when(local.is_real - next.is_real)
+            .assert_eq(public_values.num_cycles.clone(), local.cycle);
+        builder
+            .when_last_row()
+            .when(local.is_real)
+            .assert_eq(public_values.num_cycles.clone(), local.cycle);
     }
 
     /// Constraints related to the is_real column.
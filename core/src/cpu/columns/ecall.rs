@@ -14,6 +14,9 @@ pub struct EcallCols<T> {
     /// Whether the current ecall is HINT_LEN.
     pub is_hint_len: IsZeroOperation<T>,
 
+    /// Whether the current ecall is HAS_PRECOMPILE.
+    pub is_has_precompile: IsZeroOperation<T>,
+
     /// Whether the current ecall is HALT.
     pub is_halt: IsZeroOperation<T>,
 
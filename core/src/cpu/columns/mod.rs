@@ -55,6 +55,9 @@ pub struct CpuCols<T: Copy> {
     /// The expected next program counter value.
     pub next_pc: T,
 
+    /// The number of cycles executed in the shard up to this one, counting from one.
+    pub cycle: T,
+
     /// Columns related to the instruction.
     pub instruction: InstructionCols<T>,
 
@@ -52,19 +52,23 @@ impl<F: PrimeField32> MachineAir<F> for CpuChip {
         rows_with_events.sort_unstable_by_key(|(event, _, _)| event[CPU_COL_MAP.clk]);
 
         let mut rows = Vec::<F>::new();
-        rows_with_events.into_iter().for_each(|row_with_events| {
-            let (row, alu_events, blu_events) = row_with_events;
-            rows.extend(row);
-            for (key, value) in alu_events {
-                new_alu_events
-                    .entry(key)
-                    .and_modify(|op_new_events: &mut Vec<AluEvent>| {
-                        op_new_events.extend(value.clone())
-                    })
-                    .or_insert(value);
-            }
-            new_blu_events.extend(blu_events);
-        });
+        rows_with_events
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, row_with_events)| {
+                let (mut row, alu_events, blu_events) = row_with_events;
+                row[CPU_COL_MAP.cycle] = F::from_canonical_usize(i + 1);
+                rows.extend(row);
+                for (key, value) in alu_events {
+                    new_alu_events
+                        .entry(key)
+                        .and_modify(|op_new_events: &mut Vec<AluEvent>| {
+                            op_new_events.extend(value.clone())
+                        })
+                        .or_insert(value);
+                }
+                new_blu_events.extend(blu_events);
+            });
 
         // Add the dependency events to the shard.
         for (_, value) in new_alu_events.iter_mut() {
@@ -710,6 +714,11 @@ impl CpuChip {
                 syscall_id - F::from_canonical_u32(SyscallCode::HINT_LEN.syscall_id()),
             );
 
+            // Populate `is_has_precompile`.
+            ecall_cols.is_has_precompile.populate_from_field_element(
+                syscall_id - F::from_canonical_u32(SyscallCode::HAS_PRECOMPILE.syscall_id()),
+            );
+
             // Populate `is_halt`.
             ecall_cols.is_halt.populate_from_field_element(
                 syscall_id - F::from_canonical_u32(SyscallCode::HALT.syscall_id()),
@@ -1,11 +1,13 @@
 use std::cmp::min;
 use std::collections::BTreeMap;
 
-use elf::abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD};
+use elf::abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD, STT_FUNC};
 use elf::endian::LittleEndian;
 use elf::file::Class;
 use elf::ElfBytes;
 
+use crate::runtime::{ManifestError, SyscallManifest, MANIFEST_SECTION_NAME};
+
 /// The maximum size of the memory in bytes.
 pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 
@@ -26,6 +28,9 @@ pub struct Elf {
 
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
+
+    /// The precompiles the program declares it uses, if the ELF has a manifest section.
+    pub manifest: Option<SyscallManifest>,
 }
 
 impl Elf {
@@ -35,12 +40,14 @@ impl Elf {
         pc_start: u32,
         pc_base: u32,
         memory_image: BTreeMap<u32, u32>,
+        manifest: Option<SyscallManifest>,
     ) -> Self {
         Self {
             instructions,
             pc_start,
             pc_base,
             memory_image,
+            manifest,
         }
     }
 
@@ -151,6 +158,67 @@ impl Elf {
             }
         }
 
-        Elf::new(instructions, entry, base_address, image)
+        // Read the manifest of declared precompiles, if there is one.
+        let manifest = Self::decode_manifest(&elf).expect("failed to decode the manifest");
+
+        Elf::new(instructions, entry, base_address, image, manifest)
+    }
+
+    /// Read the manifest of declared precompiles from the ELF file, if it has a manifest section.
+    pub fn decode_manifest(
+        elf: &ElfBytes<LittleEndian>,
+    ) -> Result<Option<SyscallManifest>, ManifestError> {
+        let Some(header) = elf.section_header_by_name(MANIFEST_SECTION_NAME)? else {
+            return Ok(None);
+        };
+        let (data, _) = elf.section_data(&header)?;
+        SyscallManifest::from_bytes(data).map(Some)
+    }
+}
+
+/// The function symbols of an ELF file, used to attribute program counters to the functions
+/// containing them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolTable {
+    /// The name and the size in bytes of each function, keyed by its start address.
+    functions: BTreeMap<u32, (String, u32)>,
+}
+
+impl SymbolTable {
+    /// Create a table from the name, the start address and the size in bytes of each function.
+    pub fn new(functions: impl IntoIterator<Item = (String, u32, u32)>) -> Self {
+        Self {
+            functions: functions
+                .into_iter()
+                .map(|(name, start, size)| (start, (name, size)))
+                .collect(),
+        }
+    }
+
+    /// Read the function symbols of the ELF file. The table is empty if the file is stripped.
+    pub fn decode(input: &[u8]) -> Self {
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input).expect("failed to parse elf");
+        let mut functions = Vec::new();
+        let symbols = elf.symbol_table().expect("failed to parse the symbol table");
+        if let Some((symtab, strtab)) = symbols {
+            for symbol in symtab.iter() {
+                if symbol.st_symtype() != STT_FUNC || symbol.st_size == 0 {
+                    continue;
+                }
+                let name = strtab
+                    .get(symbol.st_name as usize)
+                    .expect("failed to read a symbol name");
+                let start = symbol.st_value.try_into().expect("symbol larger than 32 bits");
+                let size = symbol.st_size.try_into().expect("symbol larger than 32 bits");
+                functions.push((name.to_string(), start, size));
+            }
+        }
+        Self::new(functions)
+    }
+
+    /// Returns the name of the function containing `pc`, if there is one.
+    pub fn lookup(&self, pc: u32) -> Option<&str> {
+        let (start, (name, size)) = self.functions.range(..=pc).next_back()?;
+        (pc - start < *size).then_some(name.as_str())
     }
 }
@@ -6,6 +6,9 @@ pub use instruction::*;
 
 use std::{collections::BTreeMap, fs::File, io::Read};
 
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField32;
+
 use crate::runtime::{Instruction, Program};
 
 impl Program {
@@ -16,9 +19,29 @@ impl Program {
             pc_start,
             pc_base,
             memory_image: BTreeMap::new(),
+            manifest: None,
+            lookup_tables: BTreeMap::new(),
         }
     }
 
+    /// Adds a lookup table that the program can query with the `TABLE_LOOKUP` precompile.
+    ///
+    /// The entries are committed to in the proving and verifying keys, so a lookup is proven to
+    /// return the value of its key in the table. The table id must be a BabyBear element.
+    pub fn with_lookup_table(
+        mut self,
+        table_id: u32,
+        entries: impl IntoIterator<Item = (u32, u32)>,
+    ) -> Self {
+        assert!(
+            table_id < BabyBear::ORDER_U32,
+            "lookup table id {} is not a field element",
+            table_id
+        );
+        self.lookup_tables.insert(table_id, entries.into_iter().collect());
+        self
+    }
+
     /// Disassemble a RV32IM ELF to a program that be executed by the VM.
     pub fn from(input: &[u8]) -> Self {
         // Decode the bytes as an ELF.
@@ -33,6 +56,8 @@ impl Program {
             pc_start: elf.pc_start,
             pc_base: elf.pc_base,
             memory_image: elf.memory_image,
+            manifest: elf.manifest,
+            lookup_tables: BTreeMap::new(),
         }
     }
 
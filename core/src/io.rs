@@ -5,6 +5,41 @@ use crate::{
 use k256::sha2::{Digest, Sha256};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+pub use sp1_primitives::io::{CommitmentScheme, FooterEntryKind, FOOTER_MAGIC, FOOTER_VERSION};
+use sp1_primitives::poseidon2_hash_bytes;
+use thiserror::Error;
+use tiny_keccak::{Hasher, Keccak};
+
+/// The number of bytes in a schema id committed with `commit_with_schema`.
+pub const SCHEMA_ID_SIZE: usize = 32;
+
+/// The values a program committed through the footer of its public values.
+///
+/// The footer is the entries, followed by the length of the entries and `FOOTER_VERSION` as
+/// little-endian u32s, and `FOOTER_MAGIC`. Each entry is its kind and the length of its payload as
+/// little-endian u32s, followed by the payload, and the entries are in the order of their kinds.
+///
+/// Only programs built with the `footer` feature of `sp1-zkvm` have a footer, which `syscall_halt`
+/// writes after everything the program committed, so the program cannot forge its entries. Whether
+/// a program has a footer is a property of the program, which the verifier must know: the public
+/// values of a program without one can end with bytes which decode as a footer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublicValuesFooter {
+    /// The schema id set with `commit_with_schema`.
+    pub schema_id: Option<[u8; SCHEMA_ID_SIZE]>,
+    /// The nonce committed with `commit_nonce`.
+    pub nonce: Option<u64>,
+    /// The digest of the inputs committed with `commit_input_digest`.
+    pub input_digest: Option<[u8; 32]>,
+    /// The tags of the invariants checked with `prove_assert`, in the order they were checked.
+    pub invariants: Vec<u32>,
+    /// The error code set with `set_error_code`, or registered for a panic message with
+    /// `register_panic_code`.
+    pub error_code: Option<u32>,
+    /// Whether the program panicked after registering a code with `register_panic_code`, in which
+    /// case it halts successfully and `error_code` is the code of the panic.
+    pub panicked: bool,
+}
 
 /// Standard input for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -13,6 +48,10 @@ pub struct SP1Stdin {
     /// a vec of bytes at a time.
     pub buffer: Vec<Vec<u8>>,
     pub ptr: usize,
+    /// Private hints read with `read_hint`, which are not part of the input digest committed by
+    /// the program.
+    #[serde(default)]
+    pub hints: Vec<Vec<u8>>,
     pub proofs: Vec<(
         ShardProof<BabyBearPoseidon2>,
         StarkVerifyingKey<BabyBearPoseidon2>,
@@ -25,12 +64,199 @@ pub struct SP1PublicValues {
     buffer: Buffer,
 }
 
+/// An error returned when the public values do not end with a valid footer.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FooterError {
+    #[error("public values do not end with a footer")]
+    MissingFooter,
+    #[error("unsupported footer version {0}")]
+    UnsupportedVersion(u32),
+    #[error("footer entries of {0} bytes are longer than the public values")]
+    EntriesTooLong(usize),
+    #[error("malformed footer entry at offset {0}")]
+    MalformedEntry(usize),
+    #[error("unknown footer entry kind {0}")]
+    UnknownEntryKind(u32),
+    #[error("footer entry {0:?} is duplicated or out of order")]
+    MisorderedEntry(FooterEntryKind),
+}
+
+/// An error returned when the public values were not committed with the expected schema.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SchemaError {
+    #[error("invalid footer: {0}")]
+    Footer(FooterError),
+    #[error("public values do not contain a schema id")]
+    MissingSchemaId,
+    #[error(
+        "schema id mismatch: expected 0x{}, found 0x{}",
+        hex::encode(.expected),
+        hex::encode(.found)
+    )]
+    SchemaIdMismatch {
+        expected: [u8; SCHEMA_ID_SIZE],
+        found: [u8; SCHEMA_ID_SIZE],
+    },
+}
+
+/// An error returned when the public values were not committed with the expected nonce.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum NonceError {
+    #[error("public values do not contain a nonce")]
+    MissingNonce,
+    #[error("nonce mismatch: expected {expected}, found {found}")]
+    NonceMismatch { expected: u64, found: u64 },
+}
+
+/// An error returned when the public values are not the encoding of the expected type.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("failed to deserialize public values: {0}")]
+    Deserialize(String),
+    #[error("public values are not the canonical encoding of the decoded value")]
+    EncodingMismatch,
+    #[error("invalid footer: {0}")]
+    Footer(FooterError),
+}
+
+impl PublicValuesFooter {
+    /// The length of the fields following the entries: their length, the version and the magic.
+    const TRAILER_LEN: usize = 8 + FOOTER_MAGIC.len();
+
+    /// Splits `data` into the bytes before the footer, and the decoded footer.
+    ///
+    /// The footer must be canonical: every entry has a known kind and the length of its payload,
+    /// the kinds are strictly increasing, and the entries exactly fill their declared length.
+    pub fn decode(data: &[u8]) -> Result<(&[u8], Self), FooterError> {
+        if data.len() < Self::TRAILER_LEN {
+            return Err(FooterError::MissingFooter);
+        }
+        let (rest, trailer) = data.split_at(data.len() - Self::TRAILER_LEN);
+        let (entries_len, trailer) = trailer.split_at(4);
+        let (version, magic) = trailer.split_at(4);
+        if magic != FOOTER_MAGIC {
+            return Err(FooterError::MissingFooter);
+        }
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != FOOTER_VERSION {
+            return Err(FooterError::UnsupportedVersion(version));
+        }
+        let entries_len = u32::from_le_bytes(entries_len.try_into().unwrap()) as usize;
+        if entries_len > rest.len() {
+            return Err(FooterError::EntriesTooLong(entries_len));
+        }
+        let (committed, entries) = rest.split_at(rest.len() - entries_len);
+
+        let mut footer = Self::default();
+        let mut last_kind = 0;
+        let mut offset = 0;
+        while offset < entries.len() {
+            let malformed = FooterError::MalformedEntry(offset);
+            let header = entries.get(offset..offset + 8).ok_or(malformed)?;
+            let kind_id = u32::from_le_bytes(header[..4].try_into().unwrap());
+            let len = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+            let end = (offset + 8).checked_add(len).ok_or(malformed)?;
+            let payload = entries.get(offset + 8..end).ok_or(malformed)?;
+
+            let kind =
+                FooterEntryKind::from_id(kind_id).ok_or(FooterError::UnknownEntryKind(kind_id))?;
+            if kind_id <= last_kind {
+                return Err(FooterError::MisorderedEntry(kind));
+            }
+            last_kind = kind_id;
+
+            match kind {
+                FooterEntryKind::SchemaId => {
+                    footer.schema_id = Some(payload.try_into().map_err(|_| malformed)?);
+                }
+                FooterEntryKind::Nonce => {
+                    let nonce = payload.try_into().map_err(|_| malformed)?;
+                    footer.nonce = Some(u64::from_le_bytes(nonce));
+                }
+                FooterEntryKind::InputDigest => {
+                    footer.input_digest = Some(payload.try_into().map_err(|_| malformed)?);
+                }
+                FooterEntryKind::Invariants => {
+                    if payload.is_empty() || payload.len() % 4 != 0 {
+                        return Err(malformed);
+                    }
+                    footer.invariants = payload
+                        .chunks_exact(4)
+                        .map(|tag| u32::from_le_bytes(tag.try_into().unwrap()))
+                        .collect();
+                }
+                FooterEntryKind::ErrorCode => {
+                    let code = payload.try_into().map_err(|_| malformed)?;
+                    footer.error_code = Some(u32::from_le_bytes(code));
+                }
+                FooterEntryKind::Panicked => {
+                    if !payload.is_empty() {
+                        return Err(malformed);
+                    }
+                    footer.panicked = true;
+                }
+            }
+            offset = end;
+        }
+        Ok((committed, footer))
+    }
+
+    /// Encodes the footer the way `syscall_halt` does, with no entries for unset values.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut entries = Vec::new();
+        let mut push = |kind: FooterEntryKind, payload: &[u8]| {
+            entries.extend_from_slice(&kind.id().to_le_bytes());
+            entries.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            entries.extend_from_slice(payload);
+        };
+        if let Some(schema_id) = &self.schema_id {
+            push(FooterEntryKind::SchemaId, schema_id);
+        }
+        if let Some(nonce) = self.nonce {
+            push(FooterEntryKind::Nonce, &nonce.to_le_bytes());
+        }
+        if let Some(input_digest) = &self.input_digest {
+            push(FooterEntryKind::InputDigest, input_digest);
+        }
+        if !self.invariants.is_empty() {
+            let tags = self.invariants.iter().flat_map(|tag| tag.to_le_bytes());
+            push(FooterEntryKind::Invariants, &tags.collect::<Vec<_>>());
+        }
+        if let Some(code) = self.error_code {
+            push(FooterEntryKind::ErrorCode, &code.to_le_bytes());
+        }
+        if self.panicked {
+            push(FooterEntryKind::Panicked, &[]);
+        }
+
+        let entries_len = entries.len() as u32;
+        entries.extend_from_slice(&entries_len.to_le_bytes());
+        entries.extend_from_slice(&FOOTER_VERSION.to_le_bytes());
+        entries.extend_from_slice(&FOOTER_MAGIC);
+        entries
+    }
+
+    /// Checks that the program committed `expected` with `commit_nonce`.
+    ///
+    /// The program reads the nonce from its inputs, so a verifier which supplies a fresh nonce for
+    /// each proof it requests rejects a proof generated for an earlier request, e.g. one replayed
+    /// to an on-chain verifier.
+    pub fn verify_nonce(&self, expected: u64) -> Result<(), NonceError> {
+        match self.nonce {
+            None => Err(NonceError::MissingNonce),
+            Some(found) if found != expected => Err(NonceError::NonceMismatch { expected, found }),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
 impl SP1Stdin {
     /// Create a new `SP1Stdin`.
     pub const fn new() -> Self {
         Self {
             buffer: Vec::new(),
             ptr: 0,
+            hints: Vec::new(),
             proofs: Vec::new(),
         }
     }
@@ -40,6 +266,7 @@ impl SP1Stdin {
         Self {
             buffer: vec![data.to_vec()],
             ptr: 0,
+            hints: Vec::new(),
             proofs: Vec::new(),
         }
     }
@@ -74,6 +301,18 @@ impl SP1Stdin {
         self.buffer.push(vec);
     }
 
+    /// Write a private hint, which the program reads with `read_hint` and must verify itself.
+    pub fn write_hint<T: Serialize>(&mut self, data: &T) {
+        let mut tmp = Vec::new();
+        bincode::serialize_into(&mut tmp, data).expect("serialization failed");
+        self.hints.push(tmp);
+    }
+
+    /// Write a private hint as a slice of bytes.
+    pub fn write_hint_slice(&mut self, slice: &[u8]) {
+        self.hints.push(slice.to_vec());
+    }
+
     pub fn write_proof(
         &mut self,
         proof: ShardProof<BabyBearPoseidon2>,
@@ -81,6 +320,26 @@ impl SP1Stdin {
     ) {
         self.proofs.push((proof, vk));
     }
+
+    /// Returns the digest that a program which reads all of the inputs commits with
+    /// `commit_input_digest`.
+    pub fn input_digest(&self) -> [u8; 32] {
+        self.input_digest_of(self.buffer.len())
+    }
+
+    /// Returns the digest that a program which reads the first `count` inputs commits with
+    /// `commit_input_digest`.
+    ///
+    /// Every input is hashed as its length, as a little-endian u32, followed by its bytes, so the
+    /// digest depends on how the inputs are split and not only on their concatenation.
+    pub fn input_digest_of(&self, count: usize) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for input in self.buffer.iter().take(count) {
+            hasher.update((input.len() as u32).to_le_bytes());
+            hasher.update(input);
+        }
+        hasher.finalize().into()
+    }
 }
 
 impl SP1PublicValues {
@@ -136,12 +395,19 @@ impl SP1PublicValues {
     /// ```solidity
     /// sha256(publicValues) & bytes32(uint256((1 << 253) - 1));
     /// ```
+    ///
+    /// This is the hash of the public values of programs with the default commitment scheme. Use
+    /// `hash_with` for programs which select another one.
     pub fn hash(&self) -> BigUint {
+        self.hash_with(CommitmentScheme::default())
+    }
+
+    /// Hash the public values with the hash function of `scheme`, mask the top 3 bits and return
+    /// a BigUint. Matches `hashPublicValuesKeccak` in the Solidity verifier for
+    /// `CommitmentScheme::Keccak256`.
+    pub fn hash_with(&self, scheme: CommitmentScheme) -> BigUint {
         // Hash the public values.
-        let mut hasher = Sha256::new();
-        hasher.update(self.buffer.data.as_slice());
-        let hash_result = hasher.finalize();
-        let mut hash = hash_result.to_vec();
+        let mut hash = self.digest(scheme).to_vec();
 
         // Mask the top 3 bits.
         hash[0] &= 0b00011111;
@@ -149,6 +415,98 @@ impl SP1PublicValues {
         // Return the masked hash as a BigUint.
         BigUint::from_bytes_be(&hash)
     }
+
+    /// Returns the digest of the public values which a program with the commitment scheme
+    /// `scheme` commits to.
+    pub fn digest(&self, scheme: CommitmentScheme) -> [u8; 32] {
+        let data = self.buffer.data.as_slice();
+        match scheme {
+            CommitmentScheme::Sha256 => Sha256::digest(data).into(),
+            CommitmentScheme::Poseidon2 => poseidon2_hash_bytes(data),
+            CommitmentScheme::Keccak256 => {
+                let mut hasher = Keccak::v256();
+                hasher.update(data);
+                let mut digest = [0u8; 32];
+                hasher.finalize(&mut digest);
+                digest
+            }
+        }
+    }
+
+    /// Returns the commitment scheme which the program selected, given the digest it committed to,
+    /// or `None` if the public values do not hash to `digest` under any scheme.
+    ///
+    /// The digest is bound by the proof, so the scheme needs no other commitment: public values
+    /// which hash to the same digest under another scheme would be a collision between two hash
+    /// functions.
+    pub fn commitment_scheme(&self, digest: &[u8; 32]) -> Option<CommitmentScheme> {
+        CommitmentScheme::ALL
+            .into_iter()
+            .find(|scheme| self.digest(*scheme) == *digest)
+    }
+
+    /// Returns the commitment scheme under which [Self::hash_with] is `hash`, the masked digest of
+    /// the public values in the public inputs of a PLONK proof, if any.
+    pub fn commitment_scheme_of_hash(&self, hash: &BigUint) -> Option<CommitmentScheme> {
+        CommitmentScheme::ALL
+            .into_iter()
+            .find(|scheme| self.hash_with(*scheme) == *hash)
+    }
+
+    /// Splits the public values of a program built with the footer into the bytes it committed,
+    /// and its footer.
+    ///
+    /// Only call this for programs built with the `footer` feature of `sp1-zkvm`, whose public
+    /// values always end with a footer written by `syscall_halt`. See [PublicValuesFooter].
+    pub fn split_footer(&self) -> Result<(&[u8], PublicValuesFooter), FooterError> {
+        PublicValuesFooter::decode(self.buffer.data.as_slice())
+    }
+
+    /// Returns the footer of the public values of a program built with the footer.
+    pub fn footer(&self) -> Result<PublicValuesFooter, FooterError> {
+        self.split_footer().map(|(_, footer)| footer)
+    }
+
+    /// Checks that the public values were committed with `commit_with_schema` under the expected
+    /// schema id, and returns the bytes committed by the program before the footer.
+    pub fn verify_schema(&self, expected: &[u8; SCHEMA_ID_SIZE]) -> Result<&[u8], SchemaError> {
+        let (committed, footer) = self.split_footer().map_err(SchemaError::Footer)?;
+        let found = footer.schema_id.ok_or(SchemaError::MissingSchemaId)?;
+        if &found != expected {
+            return Err(SchemaError::SchemaIdMismatch {
+                expected: *expected,
+                found,
+            });
+        }
+        Ok(committed)
+    }
+
+    /// Decodes the public values as a single value of type `T`.
+    ///
+    /// Unlike `read`, this fails unless the public values are exactly the encoding of the decoded
+    /// value, so that extra or non-canonical bytes cannot be ignored by the host. For programs
+    /// built with the footer, use `decode_committed` instead.
+    pub fn decode<T: Serialize + DeserializeOwned>(&self) -> Result<T, DecodeError> {
+        Self::decode_exact(self.buffer.data.as_slice())
+    }
+
+    /// Decodes the bytes committed by a program built with the footer, before its footer, as a
+    /// single value of type `T`, like `decode`.
+    pub fn decode_committed<T: Serialize + DeserializeOwned>(&self) -> Result<T, DecodeError> {
+        let (committed, _) = self.split_footer().map_err(DecodeError::Footer)?;
+        Self::decode_exact(committed)
+    }
+
+    /// Decodes `bytes` as a single value of type `T`, which must be exactly its encoding.
+    fn decode_exact<T: Serialize + DeserializeOwned>(bytes: &[u8]) -> Result<T, DecodeError> {
+        let value: T =
+            bincode::deserialize(bytes).map_err(|err| DecodeError::Deserialize(err.to_string()))?;
+        let encoding = bincode::serialize(&value).expect("serialization failed");
+        if encoding != bytes {
+            return Err(DecodeError::EncodingMismatch);
+        }
+        Ok(value)
+    }
 }
 
 impl AsRef<[u8]> for SP1PublicValues {
@@ -198,6 +556,10 @@ pub mod proof_serde {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, execute_only, prove, run_test, BabyBearPoseidon2, SP1CoreOpts};
+    use sp1_zkvm::io::{panic_code, register_panic_code, DEFAULT_PANIC_CODE};
 
     #[test]
     fn test_hash_public_values() {
@@ -213,4 +575,495 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    /// Returns public values which commit `committed` followed by the footer encoded the way
+    /// `syscall_halt` encodes it.
+    fn with_footer(committed: &[u8], footer: &sp1_zkvm::io::PublicValuesFooter) -> SP1PublicValues {
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_slice(committed);
+        public_values.write_slice(&footer.encode());
+        public_values
+    }
+
+    #[test]
+    fn test_footer() {
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            schema_id: Some([1; 32]),
+            nonce: Some(7),
+            input_digest: Some(SP1Stdin::new().input_digest()),
+            invariants: vec![7, 9],
+            error_code: Some(3),
+            panicked: true,
+        };
+        let public_values = with_footer(&[1, 2, 3, 4], &footer);
+        let (committed, decoded) = public_values.split_footer().unwrap();
+        assert_eq!(committed, &[1, 2, 3, 4]);
+        assert_eq!(
+            decoded,
+            PublicValuesFooter {
+                schema_id: footer.schema_id,
+                nonce: footer.nonce,
+                input_digest: footer.input_digest,
+                invariants: footer.invariants.clone(),
+                error_code: footer.error_code,
+                panicked: footer.panicked,
+            }
+        );
+
+        // The host encodes the footer like the guest.
+        assert_eq!(decoded.encode(), footer.encode());
+
+        // Unset values have no entry.
+        let public_values = with_footer(&[], &sp1_zkvm::io::PublicValuesFooter::new());
+        assert_eq!(public_values.as_slice().len(), 16);
+        assert_eq!(public_values.footer(), Ok(PublicValuesFooter::default()));
+    }
+
+    #[test]
+    fn test_invalid_footer() {
+        let entry = |kind: u32, payload: &[u8]| {
+            [
+                &kind.to_le_bytes()[..],
+                &(payload.len() as u32).to_le_bytes(),
+                payload,
+            ]
+            .concat()
+        };
+        let footer = |entries: &[u8], entries_len: u32, version: u32| {
+            let trailer = [
+                &entries_len.to_le_bytes()[..],
+                &version.to_le_bytes(),
+                &FOOTER_MAGIC,
+            ];
+            SP1PublicValues::from(&[entries, &trailer.concat()].concat())
+        };
+        let nonce = entry(2, &7u64.to_le_bytes());
+        let code = entry(5, &3u32.to_le_bytes());
+
+        assert_eq!(
+            SP1PublicValues::from(&[0; 32]).footer(),
+            Err(FooterError::MissingFooter)
+        );
+        assert_eq!(
+            footer(&nonce, 16, 2).footer(),
+            Err(FooterError::UnsupportedVersion(2))
+        );
+        assert_eq!(
+            footer(&nonce, 17, 1).footer(),
+            Err(FooterError::EntriesTooLong(17))
+        );
+        assert_eq!(
+            footer(&nonce, 12, 1).footer(),
+            Err(FooterError::MalformedEntry(0))
+        );
+        assert_eq!(
+            footer(&entry(2, &7u32.to_le_bytes()), 12, 1).footer(),
+            Err(FooterError::MalformedEntry(0))
+        );
+        assert_eq!(
+            footer(&entry(6, &[0; 4]), 12, 1).footer(),
+            Err(FooterError::MalformedEntry(0))
+        );
+        assert_eq!(
+            footer(&entry(7, &[]), 8, 1).footer(),
+            Err(FooterError::UnknownEntryKind(7))
+        );
+        let misordered = [&code[..], &nonce].concat();
+        assert_eq!(
+            footer(&misordered, misordered.len() as u32, 1).footer(),
+            Err(FooterError::MisorderedEntry(FooterEntryKind::Nonce))
+        );
+        let duplicated = [&code[..], &code].concat();
+        assert_eq!(
+            footer(&duplicated, duplicated.len() as u32, 1).footer(),
+            Err(FooterError::MisorderedEntry(FooterEntryKind::ErrorCode))
+        );
+        let entries = [&nonce[..], &code].concat();
+        assert_eq!(
+            footer(&entries, entries.len() as u32, 1).footer(),
+            Ok(PublicValuesFooter {
+                nonce: Some(7),
+                error_code: Some(3),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_forged_footer() {
+        // A program which commits bytes that look like a footer does not set its entries: the
+        // footer written on halt always comes last and declares the length of its own entries.
+        let forged = sp1_zkvm::io::PublicValuesFooter {
+            nonce: Some(8),
+            error_code: Some(0),
+            ..Default::default()
+        }
+        .encode();
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            nonce: Some(7),
+            ..Default::default()
+        };
+        let public_values = with_footer(&forged, &footer);
+        let (committed, decoded) = public_values.split_footer().unwrap();
+        assert_eq!(committed, forged.as_slice());
+        assert_eq!(decoded.nonce, Some(7));
+        assert_eq!(decoded.error_code, None);
+    }
+
+    #[test]
+    fn test_verify_schema() {
+        let schema_id: [u8; SCHEMA_ID_SIZE] = Sha256::digest(b"struct Output { a: u32 }").into();
+        let other_schema_id: [u8; SCHEMA_ID_SIZE] =
+            Sha256::digest(b"struct Output { a: u64 }").into();
+
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            schema_id: Some(schema_id),
+            ..Default::default()
+        };
+        let public_values = with_footer(&[1, 2, 3, 4], &footer);
+
+        assert_eq!(
+            public_values.verify_schema(&schema_id),
+            Ok(&[1, 2, 3, 4][..])
+        );
+        assert_eq!(
+            public_values.verify_schema(&other_schema_id),
+            Err(SchemaError::SchemaIdMismatch {
+                expected: other_schema_id,
+                found: schema_id,
+            })
+        );
+
+        // Committing the schema id as data does not set it.
+        let public_values = with_footer(&schema_id, &sp1_zkvm::io::PublicValuesFooter::new());
+        assert_eq!(
+            public_values.verify_schema(&schema_id),
+            Err(SchemaError::MissingSchemaId)
+        );
+        assert_eq!(
+            SP1PublicValues::from(&[0; 16]).verify_schema(&schema_id),
+            Err(SchemaError::Footer(FooterError::MissingFooter))
+        );
+    }
+
+    #[test]
+    fn test_input_digest() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        stdin.write_slice(b"input");
+
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            input_digest: Some(stdin.input_digest()),
+            error_code: Some(7),
+            ..Default::default()
+        };
+        let public_values = with_footer(&bincode::serialize(&42u64).unwrap(), &footer);
+        let decoded = public_values.footer().unwrap();
+        assert_eq!(decoded.input_digest, Some(stdin.input_digest()));
+        assert_eq!(decoded.error_code, Some(7));
+
+        // The digest depends on how the inputs are split.
+        let mut other_stdin = SP1Stdin::new();
+        other_stdin.write_slice(&[&10u32.to_le_bytes()[..], b"input"].concat());
+        assert_ne!(other_stdin.input_digest(), stdin.input_digest());
+
+        // A program which commits the digest after reading some of the inputs commits the digest
+        // of those inputs only.
+        let mut hasher = Sha256::new();
+        hasher.update(4u32.to_le_bytes());
+        hasher.update(10u32.to_le_bytes());
+        assert_eq!(
+            stdin.input_digest_of(1),
+            <[u8; 32]>::from(hasher.finalize())
+        );
+        assert_ne!(stdin.input_digest_of(1), stdin.input_digest());
+        assert_eq!(stdin.input_digest_of(2), stdin.input_digest());
+        assert_eq!(
+            stdin.input_digest_of(0),
+            <[u8; 32]>::from(Sha256::digest(b""))
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let value = (42u64, vec![1u8, 2, 3]);
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            error_code: Some(3),
+            ..Default::default()
+        };
+        let public_values = with_footer(&bincode::serialize(&value).unwrap(), &footer);
+
+        // The footer written on halt is not part of the decoded value.
+        assert_eq!(public_values.decode_committed(), Ok(value.clone()));
+        assert_eq!(
+            public_values.decode::<(u64, Vec<u8>)>(),
+            Err(DecodeError::EncodingMismatch)
+        );
+
+        // The committed bytes must be exactly the encoding of the value.
+        assert_eq!(
+            public_values.decode_committed::<u64>(),
+            Err(DecodeError::EncodingMismatch)
+        );
+        assert!(matches!(
+            public_values.decode_committed::<(u64, [u8; 16])>(),
+            Err(DecodeError::Deserialize(_))
+        ));
+
+        // Without a footer, all of the public values are decoded.
+        let mut public_values = SP1PublicValues::new();
+        public_values.write(&value);
+        assert_eq!(public_values.decode(), Ok(value));
+        assert_eq!(
+            public_values.decode_committed::<(u64, Vec<u8>)>(),
+            Err(DecodeError::Footer(FooterError::MissingFooter))
+        );
+    }
+
+    /// A program which commits `committed` to the public values and their digest under `scheme`,
+    /// the way `syscall_halt` does for a program which selected `scheme`.
+    fn commitment_scheme_program(committed: &[u8], scheme: CommitmentScheme) -> Program {
+        let digest = SP1PublicValues::from(committed).digest(scheme);
+        let mut program = public_values_program(committed, 0);
+        for (i, word) in digest.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes(word.try_into().unwrap());
+            program.instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::COMMIT as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, i as u32, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, word, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        program
+    }
+
+    #[test]
+    fn test_commitment_scheme() {
+        utils::setup_logger();
+        let value = (42u64, vec![1u8, 2, 3, 4, 5, 6, 7, 8]);
+        let committed = bincode::serialize(&value).unwrap();
+        assert_eq!(committed.len() % 4, 0);
+
+        let mut digests = vec![];
+        for scheme in CommitmentScheme::ALL {
+            let program = commitment_scheme_program(&committed, scheme);
+            let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+            let digest: [u8; 32] = runtime
+                .record
+                .public_values
+                .committed_value_digest
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            run_test(program).unwrap();
+
+            // The host finds the scheme the program selected from the digest it committed to, and
+            // decodes the same values under every scheme.
+            assert_eq!(public_values.commitment_scheme(&digest), Some(scheme));
+            let hash = public_values.hash_with(scheme);
+            assert_eq!(public_values.commitment_scheme_of_hash(&hash), Some(scheme));
+            assert_eq!(public_values.decode(), Ok(value.clone()));
+            digests.push(digest);
+        }
+        assert_eq!(
+            digests[0],
+            <[u8; 32]>::from(Sha256::digest(committed.as_slice()))
+        );
+        assert_ne!(digests[0], digests[1]);
+        assert_ne!(digests[0], digests[2]);
+        assert_ne!(digests[1], digests[2]);
+        assert_eq!(
+            SP1PublicValues::from(&committed).commitment_scheme(&[0; 32]),
+            None
+        );
+
+        // The Keccak256 digest matches that of Ethereum.
+        let mut empty_keccak = [0u8; 32];
+        hex::decode_to_slice(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            &mut empty_keccak,
+        )
+        .unwrap();
+        assert_eq!(
+            SP1PublicValues::new().digest(CommitmentScheme::Keccak256),
+            empty_keccak
+        );
+    }
+
+    #[test]
+    fn test_nonce() {
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            nonce: Some(7),
+            invariants: vec![7, 9],
+            error_code: Some(3),
+            ..Default::default()
+        };
+        let decoded = with_footer(&[], &footer).footer().unwrap();
+        assert_eq!(decoded.verify_nonce(7), Ok(()));
+        assert_eq!(
+            decoded.verify_nonce(6),
+            Err(NonceError::NonceMismatch {
+                expected: 6,
+                found: 7,
+            })
+        );
+        assert_eq!(decoded.invariants, vec![7, 9]);
+        assert_eq!(decoded.error_code, Some(3));
+
+        let decoded = with_footer(&[], &sp1_zkvm::io::PublicValuesFooter::new())
+            .footer()
+            .unwrap();
+        assert_eq!(decoded.verify_nonce(7), Err(NonceError::MissingNonce));
+    }
+
+    /// A program which writes `committed` followed by the footer, the way `syscall_halt` does, to
+    /// the public values, and halts with `exit_code` if it is non-zero.
+    fn footer_program(
+        committed: &[u8],
+        footer: &sp1_zkvm::io::PublicValuesFooter,
+        exit_code: u32,
+    ) -> Program {
+        public_values_program(&[committed, &footer.encode()].concat(), exit_code)
+    }
+
+    /// A program which commits an error code to the footer of the public values, and halts with
+    /// `exit_code` if it is non-zero.
+    fn error_code_program(code: u32, exit_code: u32) -> Program {
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            error_code: Some(code),
+            ..Default::default()
+        };
+        footer_program(&[], &footer, exit_code)
+    }
+
+    /// A program which writes `trailer`, a whole number of words, to the public values, and halts
+    /// with `exit_code` if it is non-zero.
+    fn public_values_program(trailer: &[u8], exit_code: u32) -> Program {
+        let ptr = 0x1000;
+        let words = trailer
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()));
+        let mut instructions = store_words(ptr, words);
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, ptr, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, trailer.len() as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        if exit_code != 0 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, exit_code, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_error_code_is_proven() {
+        utils::setup_logger();
+        let program = error_code_program(3, 0);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+        assert_eq!(public_values.footer().unwrap().error_code, Some(3));
+
+        // The program halts successfully, so it still has a valid proof.
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    fn test_nonce_is_proven() {
+        utils::setup_logger();
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            nonce: Some(7),
+            ..Default::default()
+        };
+        let program = footer_program(&[], &footer, 0);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+
+        // The proof of the program only verifies against the nonce it committed, so a stale nonce
+        // is rejected.
+        run_test(program).unwrap();
+        let footer = public_values.footer().unwrap();
+        assert_eq!(footer.verify_nonce(7), Ok(()));
+        assert_eq!(
+            footer.verify_nonce(8),
+            Err(NonceError::NonceMismatch {
+                expected: 8,
+                found: 7,
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_only() {
+        utils::setup_logger();
+        let program = error_code_program(3, 0);
+        let stdin = SP1Stdin::new();
+        let result = execute_only(program.clone(), &stdin).unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.public_values.footer().unwrap().error_code, Some(3));
+        assert!(result.cycles > 0);
+        assert!(!result.record.cpu_events.is_empty());
+
+        // The public values are those of a full proof.
+        let (_, public_values) = prove(
+            program,
+            &stdin,
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+        )
+        .unwrap();
+        assert_eq!(result.public_values.as_slice(), public_values.as_slice());
+
+        // A non-zero exit code is part of the result.
+        let result = execute_only(error_code_program(5, 2), &stdin).unwrap();
+        assert_eq!(result.exit_code, 2);
+        assert_eq!(result.public_values.footer().unwrap().error_code, Some(5));
+    }
+
+    #[test]
+    fn test_panic_code() {
+        utils::setup_logger();
+        assert_eq!(
+            panic_code("panicked at src/main.rs:7:5:\nsignature invalid"),
+            None
+        );
+
+        register_panic_code("signature invalid", 17);
+        register_panic_code("nonce reused", 18);
+        let msg = "panicked at src/main.rs:7:5:\nsignature invalid";
+        let code = panic_code(msg).unwrap();
+        assert_eq!(code, 17);
+        assert_eq!(
+            panic_code("panicked at src/main.rs:9:5:\nnonce reused"),
+            Some(18)
+        );
+        assert_eq!(panic_code("index out of bounds"), Some(DEFAULT_PANIC_CODE));
+
+        // The guest panic handler commits the code of the message with the panic marker, and halts
+        // with exit code 0, so the panic has a valid proof.
+        let footer = sp1_zkvm::io::PublicValuesFooter {
+            error_code: Some(code),
+            panicked: true,
+            ..Default::default()
+        };
+        let program = footer_program(&[], &footer, 0);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+        let footer = public_values.footer().unwrap();
+        assert_eq!(footer.error_code, Some(17));
+        assert!(footer.panicked);
+        run_test(program).unwrap();
+    }
 }
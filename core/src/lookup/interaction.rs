@@ -37,6 +37,9 @@ pub enum InteractionKind {
 
     /// Interaction with a syscall.
     Syscall = 8,
+
+    /// Interaction with the lookup tables of the program.
+    LookupTable = 9,
 }
 
 impl InteractionKind {
@@ -50,6 +53,7 @@ impl InteractionKind {
             InteractionKind::Range,
             InteractionKind::Field,
             InteractionKind::Syscall,
+            InteractionKind::LookupTable,
         ]
     }
 }
@@ -93,6 +97,7 @@ impl Display for InteractionKind {
             InteractionKind::Range => write!(f, "Range"),
             InteractionKind::Field => write!(f, "Field"),
             InteractionKind::Syscall => write!(f, "Syscall"),
+            InteractionKind::LookupTable => write!(f, "LookupTable"),
         }
     }
 }
@@ -0,0 +1,140 @@
+//! An operation to check that the memory regions of two operands do not overlap.
+//!
+//! The regions `[a, a + a_len)` and `[b, b + b_len)` are disjoint when one ends before the other
+//! starts, so the lower region is selected and the gap between its end and the start of the other
+//! one is range checked to be below `2^30`, with two u16 limbs of which the upper one is checked
+//! again after a multiplication by four. For overlapping regions the gap is negative, and as long
+//! as the lengths sum to less than `p - 2^30` it wraps around to a field element above `2^30`, so
+//! the check fails. The regions must accordingly be less than `2^30` bytes apart.
+//!
+//! Operands which may alias exactly, such as the inputs of `uint256_mul`, opt out of the check when
+//! their pointers are equal.
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field};
+use sp1_derive::AlignedBorrow;
+
+use crate::air::SP1AirBuilder;
+use crate::bytes::event::ByteRecord;
+use crate::bytes::ByteOpcode;
+use crate::operations::IsZeroOperation;
+
+/// The bound on the gap between two disjoint regions.
+pub const MAX_REGION_GAP: u32 = 1 << 30;
+
+/// A set of columns needed to check that two memory regions do not overlap.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DisjointRangesOperation<T> {
+    /// Whether the pointers of the regions are equal.
+    pub is_equal: IsZeroOperation<T>,
+
+    /// Whether the regions are checked to be disjoint, which they are unless the pointers are equal
+    /// and allowed to be.
+    pub is_checked: T,
+
+    /// Whether the first region is below the second one.
+    pub first_is_lower: T,
+
+    /// The gap between the end of the lower region and the start of the other one, in u16 limbs.
+    pub gap: [T; 2],
+}
+
+impl<F: Field> DisjointRangesOperation<F> {
+    /// Populates the columns for the regions `[a, a + a_len)` and `[b, b + b_len)`, and returns
+    /// whether they are disjoint or, if `allow_equal` is set, start at the same pointer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn populate(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        a: u32,
+        a_len: u32,
+        b: u32,
+        b_len: u32,
+        allow_equal: bool,
+    ) -> bool {
+        let is_equal = self
+            .is_equal
+            .populate_from_field_element(F::from_canonical_u32(a) - F::from_canonical_u32(b));
+        if allow_equal && is_equal == 1 {
+            return true;
+        }
+        self.is_checked = F::one();
+
+        let first_is_lower = a < b;
+        self.first_is_lower = F::from_bool(first_is_lower);
+        let gap = if first_is_lower {
+            b.wrapping_sub(a).wrapping_sub(a_len)
+        } else {
+            a.wrapping_sub(b).wrapping_sub(b_len)
+        };
+        let limbs = [gap & 0xffff, gap >> 16];
+        self.gap = limbs.map(F::from_canonical_u32);
+
+        // The limbs of an overlap do not reconstruct the negative gap, so no lookups are added.
+        if gap >= MAX_REGION_GAP {
+            return false;
+        }
+        record.add_u16_range_checks(shard, channel, &[limbs[0], limbs[1], limbs[1] * 4]);
+        true
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        a: AB::Expr,
+        a_len: u32,
+        b: AB::Expr,
+        b_len: u32,
+        allow_equal: bool,
+        cols: DisjointRangesOperation<AB::Var>,
+        shard: AB::Var,
+        channel: AB::Var,
+        is_real: AB::Expr,
+    ) {
+        // Check the regions unless the pointers are equal and allowed to be.
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            a.clone() - b.clone(),
+            cols.is_equal,
+            is_real.clone(),
+        );
+        if allow_equal {
+            builder.assert_eq(
+                cols.is_checked,
+                is_real.clone() * (AB::Expr::one() - cols.is_equal.result),
+            );
+        } else {
+            builder.assert_eq(cols.is_checked, is_real.clone());
+        }
+
+        // Assert that the gap after the lower region is the sum of its limbs.
+        builder.when(is_real).assert_bool(cols.first_is_lower);
+        let gap_after_a = b.clone() - a.clone() - AB::F::from_canonical_u32(a_len);
+        let gap_after_b = a - b - AB::F::from_canonical_u32(b_len);
+        let gap = gap_after_a * cols.first_is_lower
+            + gap_after_b * (AB::Expr::one() - cols.first_is_lower);
+        builder.when(cols.is_checked).assert_eq(
+            cols.gap[0] + cols.gap[1] * AB::F::from_canonical_u32(1 << 16),
+            gap,
+        );
+
+        // Range check the limbs, so that the gap is below `2^30`.
+        for limb in [
+            cols.gap[0].into(),
+            cols.gap[1].into(),
+            cols.gap[1] * AB::F::from_canonical_u32(4),
+        ] {
+            builder.send_byte(
+                ByteOpcode::U16Range.as_field::<AB::F>(),
+                limb,
+                AB::Expr::zero(),
+                AB::Expr::zero(),
+                shard,
+                channel,
+                cols.is_checked,
+            );
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use generic_array::ArrayLength;
+use num::BigUint;
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, PrimeField32};
+use sp1_derive::AlignedBorrow;
+
+use super::params::Limbs;
+use crate::air::SP1AirBuilder;
+
+/// A set of columns to check whether an emulated field element is zero.
+///
+/// The limbs of the element are bytes, so their sum does not overflow and is zero if and only if
+/// every limb is. The sum is then checked with its inverse: `1 - inverse * sum` is one exactly when
+/// the sum is zero, and `is_zero * sum = 0` prevents a zero inverse from claiming a nonzero sum is
+/// zero.
+///
+/// *Safety*: The input (not included in the operation columns) is assumed to be reduced, that is
+/// within the range `[0, modulus)`. Otherwise the modulus itself, which is zero in the field, is
+/// flagged as nonzero.
+#[derive(Debug, Clone, Copy, AlignedBorrow)]
+#[repr(C)]
+pub struct IsZeroCols<T> {
+    /// The inverse of the sum of the limbs of the input, or zero if the sum is zero.
+    pub inverse: T,
+
+    /// Whether the input is zero.
+    pub is_zero: T,
+}
+
+impl<F: PrimeField32> IsZeroCols<F> {
+    /// Populates the columns for the element `a`, and returns whether it is zero.
+    pub fn populate(&mut self, a: &BigUint) -> bool {
+        let limb_sum = a.to_bytes_le().iter().map(|b| *b as u32).sum::<u32>();
+        let limb_sum = F::from_canonical_u32(limb_sum);
+        if limb_sum == F::zero() {
+            self.inverse = F::zero();
+            self.is_zero = F::one();
+        } else {
+            self.inverse = limb_sum.inverse();
+            self.is_zero = F::zero();
+        }
+        limb_sum == F::zero()
+    }
+}
+
+impl<V: Copy> IsZeroCols<V> {
+    pub fn eval<AB: SP1AirBuilder<Var = V>, N: ArrayLength>(
+        &self,
+        builder: &mut AB,
+        a: &Limbs<V, N>,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+    {
+        let limb_sum = a
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb.into());
+
+        let inverse: AB::Expr = self.inverse.into();
+        let is_zero: AB::Expr = self.is_zero.into();
+
+        let mut builder_is_real = builder.when(is_real);
+        builder_is_real.assert_bool(is_zero.clone());
+        builder_is_real.assert_eq(is_zero.clone(), AB::Expr::one() - inverse * limb_sum.clone());
+        builder_is_real.assert_zero(is_zero * limb_sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::{BigUint, One, Zero};
+    use p3_air::BaseAir;
+    use p3_field::{Field, PrimeField32};
+
+    use super::{IsZeroCols, Limbs};
+
+    use crate::air::MachineAir;
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::runtime::Program;
+    #[cfg(feature = "debug")]
+    use crate::stark::debug_trace_constraints;
+    use crate::stark::StarkGenericConfig;
+    use crate::utils::ec::weierstrass::bn254::Bn254BaseField;
+    use crate::utils::{pad_to_power_of_two, BabyBearPoseidon2};
+    use crate::utils::{uni_stark_prove as prove, uni_stark_verify as verify};
+    use crate::{air::SP1AirBuilder, runtime::ExecutionRecord};
+    use core::borrow::{Borrow, BorrowMut};
+    use core::mem::size_of;
+    use num::bigint::RandBigInt;
+    use p3_air::Air;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::Matrix;
+    use rand::thread_rng;
+    use sp1_derive::AlignedBorrow;
+
+    #[derive(AlignedBorrow, Debug)]
+    pub struct TestCols<T, P: FieldParameters> {
+        pub a: Limbs<T, P::Limbs>,
+        pub is_zero: IsZeroCols<T>,
+    }
+
+    pub const NUM_TEST_COLS: usize = size_of::<TestCols<u8, Bn254BaseField>>();
+
+    struct FieldIsZeroTestChip<P: FieldParameters> {
+        pub _phantom: std::marker::PhantomData<P>,
+    }
+
+    impl<P: FieldParameters> FieldIsZeroTestChip<P> {
+        pub const fn new() -> Self {
+            Self {
+                _phantom: std::marker::PhantomData,
+            }
+        }
+    }
+
+    impl<F: PrimeField32, P: FieldParameters> MachineAir<F> for FieldIsZeroTestChip<P> {
+        type Record = ExecutionRecord;
+
+        type Program = Program;
+
+        fn name(&self) -> String {
+            "FieldIsZeroTestChip".to_string()
+        }
+
+        fn generate_trace(
+            &self,
+            _: &ExecutionRecord,
+            _: &mut ExecutionRecord,
+        ) -> RowMajorMatrix<F> {
+            let mut rng = thread_rng();
+            let num_rows = 1 << 8;
+            let mut operands: Vec<BigUint> = (0..num_rows - 3)
+                .map(|_| rng.gen_biguint(256) % &P::modulus())
+                .collect();
+
+            // Hardcoded edge cases.
+            operands.extend(vec![
+                BigUint::zero(),
+                BigUint::one(),
+                P::modulus() - BigUint::one(),
+            ]);
+
+            let rows = operands
+                .iter()
+                .map(|a| {
+                    let mut row = [F::zero(); NUM_TEST_COLS];
+                    let cols: &mut TestCols<F, P> = row.as_mut_slice().borrow_mut();
+                    cols.a = P::to_limbs_field::<F, _>(a);
+                    let is_zero = cols.is_zero.populate(a);
+                    assert_eq!(is_zero, a.is_zero());
+                    row
+                })
+                .collect::<Vec<_>>();
+            // Convert the trace to a row major matrix.
+            let mut trace = RowMajorMatrix::new(
+                rows.into_iter().flatten().collect::<Vec<_>>(),
+                NUM_TEST_COLS,
+            );
+
+            // Pad the trace to a power of two.
+            pad_to_power_of_two::<NUM_TEST_COLS, F>(&mut trace.values);
+
+            trace
+        }
+
+        fn included(&self, _: &Self::Record) -> bool {
+            true
+        }
+    }
+
+    impl<F: Field, P: FieldParameters> BaseAir<F> for FieldIsZeroTestChip<P> {
+        fn width(&self) -> usize {
+            NUM_TEST_COLS
+        }
+    }
+
+    impl<AB, P: FieldParameters> Air<AB> for FieldIsZeroTestChip<P>
+    where
+        AB: SP1AirBuilder,
+    {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0);
+            let local: &TestCols<AB::Var, P> = (*local).borrow();
+            local.is_zero.eval(builder, &local.a, AB::F::one());
+        }
+    }
+
+    #[test]
+    fn prove_babybear() {
+        let config = BabyBearPoseidon2::new();
+        let mut challenger = config.challenger();
+
+        let chip: FieldIsZeroTestChip<Bn254BaseField> = FieldIsZeroTestChip::new();
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&ExecutionRecord::default(), &mut ExecutionRecord::default());
+        let proof = prove::<BabyBearPoseidon2, _>(&config, &chip, &mut challenger, trace);
+
+        let mut challenger = config.challenger();
+        verify(&config, &chip, &mut challenger, &proof).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "debug")]
+    #[should_panic(expected = "does not satisfy its constraints")]
+    fn lying_is_zero_bit() {
+        let chip: FieldIsZeroTestChip<Bn254BaseField> = FieldIsZeroTestChip::new();
+        let mut trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&ExecutionRecord::default(), &mut ExecutionRecord::default());
+
+        // Claim that the nonzero element one is zero, with a zero inverse so that
+        // `1 - inverse * sum` agrees with the claim.
+        let row = trace.height() - 2;
+        let cols: &mut TestCols<BabyBear, Bn254BaseField> =
+            trace.values[row * NUM_TEST_COLS..(row + 1) * NUM_TEST_COLS].borrow_mut();
+        assert_eq!(cols.is_zero.is_zero, BabyBear::zero());
+        cols.is_zero.inverse = BabyBear::zero();
+        cols.is_zero.is_zero = BabyBear::one();
+
+        debug_trace_constraints(&chip, &trace);
+    }
+}
@@ -61,13 +61,33 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
             FieldOperation::Sub | FieldOperation::Div => unreachable!(),
         };
         debug_assert!(&result < modulus);
-        debug_assert!(&carry < modulus);
+        // The carry only needs to fit in the limbs, which allows operands above the modulus.
+        debug_assert!(carry.bits() <= P::nb_bits() as u64);
         match op {
             FieldOperation::Add => debug_assert_eq!(&carry * modulus, a + b - &result),
             FieldOperation::Mul => debug_assert_eq!(&carry * modulus, a * b - &result),
             FieldOperation::Sub | FieldOperation::Div => unreachable!(),
         }
 
+        // Compute the vanishing polynomial.
+        let p_op = match op {
+            FieldOperation::Add => &p_a + &p_b,
+            FieldOperation::Mul => &p_a * &p_b,
+            FieldOperation::Sub | FieldOperation::Div => unreachable!(),
+        };
+        self.populate_witness(&p_op, &result, &carry, modulus);
+
+        result
+    }
+
+    /// Populates the result, carry and witness columns of `p_op = result + carry * modulus`.
+    fn populate_witness(
+        &mut self,
+        p_op: &Polynomial<F>,
+        result: &BigUint,
+        carry: &BigUint,
+        modulus: &BigUint,
+    ) {
         // Here we have special logic for p_modulus because to_limbs_field only works for numbers in
         // the field, but modulus can == the field modulus so it can have 1 extra limb (ex. uint256).
         let p_modulus_limbs = modulus
@@ -76,16 +96,11 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
             .map(|x| F::from_canonical_u8(*x))
             .collect::<Vec<F>>();
         let p_modulus: Polynomial<F> = p_modulus_limbs.iter().into();
-        let p_result: Polynomial<F> = P::to_limbs_field::<F, _>(&result).into();
-        let p_carry: Polynomial<F> = P::to_limbs_field::<F, _>(&carry).into();
+        let p_result: Polynomial<F> = P::to_limbs_field::<F, _>(result).into();
+        let p_carry: Polynomial<F> = P::to_limbs_field::<F, _>(carry).into();
 
         // Compute the vanishing polynomial.
-        let p_op = match op {
-            FieldOperation::Add => &p_a + &p_b,
-            FieldOperation::Mul => &p_a * &p_b,
-            FieldOperation::Sub | FieldOperation::Div => unreachable!(),
-        };
-        let p_vanishing: Polynomial<F> = &p_op - &p_result - &p_carry * &p_modulus;
+        let p_vanishing: Polynomial<F> = p_op - &p_result - &p_carry * &p_modulus;
 
         let p_witness = compute_root_quotient_and_shift(
             &p_vanishing,
@@ -102,8 +117,6 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
         p_witness_high.resize(P::Witness::USIZE, F::zero());
         self.witness_low = Limbs(p_witness_low.try_into().unwrap());
         self.witness_high = Limbs(p_witness_high.try_into().unwrap());
-
-        result
     }
 
     /// Populate these columns with a specified modulus. This is useful in the `mulmod` precompile
@@ -181,6 +194,89 @@ impl<F: PrimeField32, P: FieldParameters> FieldOpCols<F, P> {
     ) -> BigUint {
         self.populate_with_modulus(record, shard, channel, a, b, &P::modulus(), op)
     }
+
+    /// Populate these columns for the multiplication `a * b = q * modulus + result` with a given
+    /// `result`, which need not be reduced. This is useful to check that `result` is congruent to
+    /// the product, e.g. when it is equal to the modulus.
+    ///
+    /// The product minus `result` must be a nonnegative multiple of the modulus. If the modulus is
+    /// zero, `result` must be the product itself.
+    #[allow(clippy::too_many_arguments)]
+    pub fn populate_mul_with_result(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        a: &BigUint,
+        b: &BigUint,
+        modulus: &BigUint,
+        result: &BigUint,
+    ) {
+        let product = a * b;
+        assert!(&product >= result, "the result is larger than the product");
+        let carry = if modulus.is_zero() {
+            assert_eq!(&product, result, "the result is not the product");
+            BigUint::zero()
+        } else {
+            let difference = &product - result;
+            assert!(
+                (&difference % modulus).is_zero(),
+                "the result is not congruent to the product"
+            );
+            difference / modulus
+        };
+        debug_assert!(carry.bits() <= P::nb_bits() as u64);
+
+        let p_a: Polynomial<F> = P::to_limbs_field::<F, _>(a).into();
+        let p_b: Polynomial<F> = P::to_limbs_field::<F, _>(b).into();
+        self.populate_witness(&(&p_a * &p_b), result, &carry, modulus);
+
+        // Range checks
+        record.add_u8_range_checks_field(shard, channel, &self.result.0);
+        record.add_u8_range_checks_field(shard, channel, &self.carry.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_low.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_high.0);
+    }
+
+    /// Populate these columns for the square `a * a = q * modulus + result` with a specified
+    /// modulus, and return the result. The columns are the same as for `FieldOperation::Mul` with
+    /// `b = a`, but the product polynomial is computed with [`Polynomial::square`].
+    pub fn populate_square_with_modulus(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        a: &BigUint,
+        modulus: &BigUint,
+    ) -> BigUint {
+        let square = a * a;
+        let result = &square % modulus;
+        let carry = (&square - &result) / modulus;
+        debug_assert!(carry.bits() <= P::nb_bits() as u64);
+
+        let p_a: Polynomial<F> = P::to_limbs_field::<F, _>(a).into();
+        self.populate_witness(&p_a.square(), &result, &carry, modulus);
+
+        // Range checks
+        record.add_u8_range_checks_field(shard, channel, &self.result.0);
+        record.add_u8_range_checks_field(shard, channel, &self.carry.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_low.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_high.0);
+
+        result
+    }
+}
+
+impl<T, P: FieldParameters> FieldOpCols<T, P> {
+    /// The quotient `q` of the modular reduction, as little-endian byte limbs.
+    ///
+    /// For `Add` and `Mul` it satisfies `a op b = q * M + result`. For `Sub` and `Div` the
+    /// operation is evaluated in reverse, so it satisfies `result op' b = q * M + a`, where `op'`
+    /// is `Add` and `Mul` respectively. The limbs are range checked as bytes by [`Self::eval`],
+    /// and chips may add their own constraints on them, e.g. to bound the quotient further.
+    pub const fn quotient(&self) -> &Limbs<T, P::Limbs> {
+        &self.carry
+    }
 }
 
 impl<V: Copy, P: FieldParameters> FieldOpCols<V, P> {
@@ -207,13 +303,63 @@ impl<V: Copy, P: FieldParameters> FieldOpCols<V, P> {
             FieldOperation::Add | FieldOperation::Mul => (p_a_param, self.result.into()),
             FieldOperation::Sub | FieldOperation::Div => (self.result.into(), p_a_param),
         };
-        let p_carry: Polynomial<<AB as AirBuilder>::Expr> = self.carry.into();
         let p_op = match op {
             FieldOperation::Add | FieldOperation::Sub => p_a + p_b,
             FieldOperation::Mul | FieldOperation::Div => p_a * p_b,
         };
+        self.eval_reduction::<AB>(builder, p_op, p_result, &p_modulus, shard, channel, is_real);
+    }
+
+    /// Evaluates the square `a * a = q * modulus + result`, the same relation as
+    /// [`Self::eval_with_modulus`] with `FieldOperation::Mul` and `b = a`, but with the product
+    /// polynomial computed by [`Polynomial::square`], which takes about half the limb products to
+    /// evaluate. The witness columns are the same as for the product.
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_square_with_modulus<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        a: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        modulus: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        shard: impl Into<AB::Expr> + Clone,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+        Limbs<V, P::Limbs>: Copy,
+    {
+        let p_a: Polynomial<AB::Expr> = (a).clone().into();
+        let p_modulus: Polynomial<AB::Expr> = (modulus).clone().into();
+        let p_result: Polynomial<AB::Expr> = self.result.into();
+        self.eval_reduction::<AB>(
+            builder,
+            p_a.square(),
+            p_result,
+            &p_modulus,
+            shard,
+            channel,
+            is_real,
+        );
+    }
+
+    /// Evaluates `p_op = result + carry * modulus` with the witness columns, and range checks the
+    /// result, carry and witness columns.
+    #[allow(clippy::too_many_arguments)]
+    fn eval_reduction<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        p_op: Polynomial<AB::Expr>,
+        p_result: Polynomial<AB::Expr>,
+        p_modulus: &Polynomial<AB::Expr>,
+        shard: impl Into<AB::Expr> + Clone,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+        Limbs<V, P::Limbs>: Copy,
+    {
+        let p_carry: Polynomial<<AB as AirBuilder>::Expr> = self.carry.into();
         let p_op_minus_result: Polynomial<AB::Expr> = p_op - &p_result;
-        let p_vanishing = p_op_minus_result - &(&p_carry * &p_modulus);
+        let p_vanishing = p_op_minus_result - &(&p_carry * p_modulus);
         let p_witness_low = self.witness_low.0.iter().into();
         let p_witness_high = self.witness_high.0.iter().into();
         eval_field_operation::<AB, P>(builder, &p_vanishing, &p_witness_low, &p_witness_high);
@@ -277,8 +423,11 @@ mod tests {
     use crate::bytes::event::ByteRecord;
     use crate::operations::field::params::FieldParameters;
     use crate::runtime::Program;
+    #[cfg(feature = "debug")]
+    use crate::stark::debug_trace_constraints;
     use crate::stark::StarkGenericConfig;
     use crate::utils::ec::edwards::ed25519::Ed25519BaseField;
+    use crate::utils::ec::uint256::U256Field;
     use crate::utils::ec::weierstrass::secp256k1::Secp256k1BaseField;
     use crate::utils::{
         pad_to_power_of_two, uni_stark_prove as prove, uni_stark_verify as verify,
@@ -376,6 +525,11 @@ mod tests {
             // Pad the trace to a power of two.
             pad_to_power_of_two::<NUM_TEST_COLS, F>(&mut trace.values);
 
+            // Check the rows populated by `FieldOpCols::populate` against the constraints when
+            // debugging.
+            #[cfg(feature = "debug")]
+            debug_trace_constraints(self, &trace);
+
             trace
         }
 
@@ -429,6 +583,38 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "debug")]
+    fn test_populated_traces_satisfy_constraints() {
+        let ops = [
+            FieldOperation::Add,
+            FieldOperation::Sub,
+            FieldOperation::Mul,
+            FieldOperation::Div,
+        ];
+        for op in ops {
+            let _: RowMajorMatrix<BabyBear> = FieldOpChip::<Ed25519BaseField>::new(op)
+                .generate_trace(&ExecutionRecord::default(), &mut ExecutionRecord::default());
+            let _: RowMajorMatrix<BabyBear> = FieldOpChip::<Secp256k1BaseField>::new(op)
+                .generate_trace(&ExecutionRecord::default(), &mut ExecutionRecord::default());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "debug")]
+    #[should_panic(expected = "row 0 of chip FieldOpMul does not satisfy its constraints")]
+    fn test_mispopulated_result() {
+        let chip: FieldOpChip<Secp256k1BaseField> = FieldOpChip::new(FieldOperation::Mul);
+        let mut trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&ExecutionRecord::default(), &mut ExecutionRecord::default());
+
+        // Change the result of the first row without changing its witness.
+        let cols: &mut TestCols<BabyBear, Secp256k1BaseField> =
+            trace.values[..NUM_TEST_COLS].borrow_mut();
+        cols.a_op_b.result[0] += BabyBear::one();
+        debug_trace_constraints(&chip, &trace);
+    }
+
     #[test]
     fn prove_babybear() {
         let config = BabyBearPoseidon2::new();
@@ -455,4 +641,34 @@ mod tests {
             verify(&config, &chip, &mut challenger, &proof).unwrap();
         }
     }
+
+    #[test]
+    fn test_quotient() {
+        let mut rng = thread_rng();
+        let to_biguint = |limbs: &Limbs<BabyBear, _>| {
+            let bytes = limbs.0.iter().map(|x| x.as_canonical_u32() as u8);
+            BigUint::from_bytes_le(&bytes.collect::<Vec<_>>())
+        };
+        for _ in 0..16 {
+            let modulus = rng.gen_biguint(256) | BigUint::from(1u32) << 255;
+            let a = rng.gen_biguint(256) % &modulus;
+            let b = rng.gen_biguint(256) % &modulus;
+
+            let mut row = [BabyBear::zero(); size_of::<FieldOpCols<u8, U256Field>>()];
+            let cols: &mut FieldOpCols<BabyBear, U256Field> = row.as_mut_slice().borrow_mut();
+            let result = cols.populate_with_modulus(
+                &mut Vec::new(),
+                1,
+                0,
+                &a,
+                &b,
+                &modulus,
+                FieldOperation::Mul,
+            );
+
+            let quotient = to_biguint(cols.quotient());
+            assert_eq!(to_biguint(&cols.result), result);
+            assert_eq!(quotient * &modulus + result, a * b);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 pub mod field_den;
 pub mod field_inner_product;
+pub mod field_is_zero;
 pub mod field_op;
 pub mod field_sqrt;
 pub mod params;
@@ -10,6 +10,7 @@ mod add5;
 mod and;
 mod baby_bear_range;
 mod baby_bear_word;
+mod disjoint_ranges;
 pub mod field;
 mod fixed_rotate_right;
 mod fixed_shift_right;
@@ -26,6 +27,7 @@ pub use add5::*;
 pub use and::*;
 pub use baby_bear_range::*;
 pub use baby_bear_word::*;
+pub use disjoint_ranges::*;
 pub use fixed_rotate_right::*;
 pub use fixed_shift_right::*;
 pub use is_equal_word::*;
@@ -209,6 +209,8 @@ mod tests {
                 pc_start: 0,
                 pc_base: 0,
                 memory_image: BTreeMap::new(),
+                manifest: None,
+                lookup_tables: BTreeMap::new(),
             }),
             ..Default::default()
         };
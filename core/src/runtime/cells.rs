@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
+
+use super::{MemoryRecordEnum, Opcode, SyscallCode};
+use crate::air::MachineAir;
+use crate::cpu::CpuEvent;
+use crate::stark::RiscvAir;
+use crate::syscall::precompiles::uint256::UINT256_TO_DECIMAL_ROWS;
+
+/// Every opcode of the RISC-V instruction set executed by the runtime.
+const OPCODES: [Opcode; 38] = [
+    Opcode::ADD,
+    Opcode::SUB,
+    Opcode::XOR,
+    Opcode::OR,
+    Opcode::AND,
+    Opcode::SLL,
+    Opcode::SRL,
+    Opcode::SRA,
+    Opcode::SLT,
+    Opcode::SLTU,
+    Opcode::LB,
+    Opcode::LH,
+    Opcode::LW,
+    Opcode::LBU,
+    Opcode::LHU,
+    Opcode::SB,
+    Opcode::SH,
+    Opcode::SW,
+    Opcode::BEQ,
+    Opcode::BNE,
+    Opcode::BLT,
+    Opcode::BGE,
+    Opcode::BLTU,
+    Opcode::BGEU,
+    Opcode::JAL,
+    Opcode::JALR,
+    Opcode::AUIPC,
+    Opcode::ECALL,
+    Opcode::EBREAK,
+    Opcode::MUL,
+    Opcode::MULH,
+    Opcode::MULHU,
+    Opcode::MULHSU,
+    Opcode::DIV,
+    Opcode::DIVU,
+    Opcode::REM,
+    Opcode::REMU,
+    Opcode::UNIMP,
+];
+
+/// The chips, besides the CPU, that receive a row when an instruction with `opcode` is executed.
+fn opcode_chips(opcode: Opcode) -> &'static [&'static str] {
+    match opcode {
+        Opcode::ADD | Opcode::SUB => &["AddSub"],
+        Opcode::XOR | Opcode::OR | Opcode::AND => &["Bitwise"],
+        Opcode::SLL => &["ShiftLeft"],
+        Opcode::SRL | Opcode::SRA => &["ShiftRight"],
+        Opcode::SLT | Opcode::SLTU => &["Lt"],
+        Opcode::MUL | Opcode::MULH | Opcode::MULHU | Opcode::MULHSU => &["Mul"],
+        // Division is checked with a multiplication, an addition and a comparison.
+        Opcode::DIV | Opcode::DIVU | Opcode::REM | Opcode::REMU => {
+            &["DivRem", "Mul", "AddSub", "Lt"]
+        }
+        // Memory instructions compute their address with an addition.
+        Opcode::LB | Opcode::LH | Opcode::LW | Opcode::LBU | Opcode::LHU => &["AddSub"],
+        Opcode::SB | Opcode::SH | Opcode::SW => &["AddSub"],
+        // Branches compare their operands and compute the target with an addition.
+        Opcode::BEQ
+        | Opcode::BNE
+        | Opcode::BLT
+        | Opcode::BGE
+        | Opcode::BLTU
+        | Opcode::BGEU => &["Lt", "AddSub"],
+        Opcode::JAL | Opcode::JALR | Opcode::AUIPC => &["AddSub"],
+        Opcode::ECALL | Opcode::EBREAK | Opcode::UNIMP => &[],
+    }
+}
+
+/// The approximate number of rows a single call to the syscall `code` takes in its chip.
+fn syscall_rows(code: SyscallCode) -> u64 {
+    match code {
+        SyscallCode::SHA_EXTEND => 48,
+        SyscallCode::SHA_COMPRESS => 80,
+        SyscallCode::KECCAK_PERMUTE => 24,
+        SyscallCode::UINT256_TO_DECIMAL => UINT256_TO_DECIMAL_ROWS as u64,
+        _ => 1,
+    }
+}
+
+/// A budget on the number of trace cells of a shard, used to size shards by their proving cost
+/// rather than by their number of cycles.
+///
+/// Each executed cycle is charged the width of the CPU chip, plus the widths of the chips that
+/// receive rows for its opcode, plus the cells of the precompile it calls, if any. A shard is
+/// closed before the first cycle that would overflow its budget, so shards dominated by wide
+/// precompiles contain fewer cycles and shards of plain ALU code contain more. Shards are only
+/// closed between cycles, so the memory accesses of a precompile always stay in its shard.
+#[derive(Debug, Clone)]
+pub struct ShardCellBudget {
+    /// The maximum number of cells in a shard.
+    pub budget: u64,
+
+    /// The cells of a cycle executing each opcode, including the CPU row.
+    opcode_cells: HashMap<Opcode, u64>,
+
+    /// The cells of the precompile rows of each syscall.
+    syscall_cells: HashMap<SyscallCode, u64>,
+}
+
+impl ShardCellBudget {
+    /// Creates a budget of `budget` cells per shard, with the cell costs derived from the widths of
+    /// the RISC-V chips.
+    pub fn new(budget: u64) -> Self {
+        let airs = RiscvAir::<BabyBear>::get_all();
+        let widths = airs
+            .iter()
+            .map(|air| (air.name(), air.width() as u64))
+            .collect::<HashMap<_, _>>();
+
+        let cpu_cells = widths["CPU"];
+        let opcode_cells = OPCODES
+            .into_iter()
+            .map(|opcode| {
+                let chips = opcode_chips(opcode).iter().map(|name| widths[*name]);
+                (opcode, cpu_cells + chips.sum::<u64>())
+            })
+            .collect::<HashMap<_, _>>();
+
+        // The lookup table chip has a fixed height, so it is not charged per call.
+        let syscall_cells = airs
+            .iter()
+            .filter(|air| !matches!(air, RiscvAir::LookupTable(_)))
+            .filter_map(|air| {
+                let code = air.syscall_code()?;
+                Some((code, air.width() as u64 * syscall_rows(code)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        Self {
+            budget,
+            opcode_cells,
+            syscall_cells,
+        }
+    }
+
+    /// The cells of a cycle executing `opcode`, which calls `syscall` if it is an `ECALL`.
+    pub fn cycle_cells(&self, opcode: Opcode, syscall: Option<SyscallCode>) -> u64 {
+        let syscall_cells = syscall.map_or(0, |code| self.syscall_cells(code));
+        self.opcode_cells[&opcode] + syscall_cells
+    }
+
+    /// The cells of the precompile rows of a call to `syscall`.
+    pub fn syscall_cells(&self, syscall: SyscallCode) -> u64 {
+        self.syscall_cells.get(&syscall).copied().unwrap_or(0)
+    }
+
+    /// The cells of the cycle that emitted the CPU event `event`.
+    pub fn event_cells(&self, event: &CpuEvent) -> u64 {
+        let opcode = event.instruction.opcode;
+        // The syscall id is the value of t0 before the `ECALL` writes its result to it.
+        let syscall = match (opcode, &event.a_record) {
+            (Opcode::ECALL, Some(MemoryRecordEnum::Write(record))) => {
+                SyscallCode::from_u32(record.prev_value).ok()
+            }
+            _ => None,
+        };
+        self.cycle_cells(opcode, syscall)
+    }
+
+    /// Whether a shard with `used` cells can fit another `cells` cells.
+    pub fn fits(&self, used: u64, cells: u64) -> bool {
+        used + cells <= self.budget
+    }
+
+    /// The most cycles a shard with `used` cells can still fit, assuming every cycle is the
+    /// cheapest one.
+    pub fn max_cycles(&self, used: u64) -> u64 {
+        let cheapest = self.opcode_cells.values().copied().min().unwrap_or(1).max(1);
+        self.budget.saturating_sub(used) / cheapest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::ShardCellBudget;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, SP1CoreOpts};
+
+    const NUM_CALLS: usize = 64;
+    const NUM_ADDS: usize = 4096;
+
+    /// A program that converts a number to decimal `NUM_CALLS` times, then runs `NUM_ADDS` adds.
+    fn mixed_program() -> Program {
+        let mut instructions = vec![];
+        for _ in 0..NUM_CALLS {
+            let code = SyscallCode::UINT256_TO_DECIMAL as u32;
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, code, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, 0x2000, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        for i in 0..NUM_ADDS {
+            instructions.push(Instruction::new(Opcode::ADD, 29, 29, i as u32, false, true));
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Runs the program and returns the number of cycles and cells of each shard.
+    fn shard_cells(opts: SP1CoreOpts, budget: &ShardCellBudget) -> BTreeMap<u32, (usize, u64)> {
+        let mut runtime = Runtime::new(mixed_program(), opts);
+        runtime.run().unwrap();
+        let mut shards = BTreeMap::<u32, (usize, u64)>::new();
+        for event in runtime.record.cpu_events.iter() {
+            let (cycles, cells) = shards.entry(event.shard).or_default();
+            *cycles += 1;
+            *cells += budget.event_cells(event);
+        }
+        shards
+    }
+
+    #[test]
+    fn test_shard_cell_budget() {
+        utils::setup_logger();
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 20;
+
+        // Allow the cells of a handful of conversions per shard.
+        let heavy_cycle = ShardCellBudget::new(0)
+            .cycle_cells(Opcode::ECALL, Some(SyscallCode::UINT256_TO_DECIMAL));
+        let budget = ShardCellBudget::new(8 * heavy_cycle);
+
+        // Without the heuristic, all the cycles fit in one shard that overflows the budget.
+        let unbounded = shard_cells(opts, &budget);
+        assert_eq!(unbounded.len(), 1);
+        assert!(unbounded.values().all(|&(_, cells)| cells > budget.budget));
+
+        // With it, every shard stays within the budget, and the shards made of conversions have
+        // fewer cycles than the shards made of adds.
+        opts.shard_cell_budget = Some(budget.budget as usize);
+        let bounded = shard_cells(opts, &budget);
+        assert!(bounded.len() > 1);
+        assert!(bounded.values().all(|&(_, cells)| cells <= budget.budget));
+        let (first_cycles, _) = bounded.values().next().unwrap();
+        let (last_full_cycles, _) = bounded.values().rev().nth(1).unwrap();
+        assert!(first_cycles < last_full_cycles);
+
+        // The shards cover the same cycles and cells.
+        let total = |shards: &BTreeMap<u32, (usize, u64)>| {
+            shards
+                .values()
+                .fold((0, 0), |(cycles, cells), &(c, n)| (cycles + c, cells + n))
+        };
+        assert_eq!(total(&unbounded), total(&bounded));
+    }
+}
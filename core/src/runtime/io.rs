@@ -4,10 +4,19 @@ use crate::stark::{ShardProof, StarkVerifyingKey};
 use crate::utils::BabyBearPoseidon2;
 
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::Runtime;
 
+/// The inputs consumed by an execution, which can be fed back to a new runtime to reproduce it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputTape {
+    /// The slices of the input stream read by the program, in order.
+    pub input: Vec<Vec<u8>>,
+    /// The slices of the private hint stream read by the program, in order.
+    pub hints: Vec<Vec<u8>>,
+}
+
 impl<'a> Read for Runtime<'a> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         self.read_public_values_slice(buf);
@@ -32,6 +41,32 @@ impl<'a> Runtime<'a> {
         }
     }
 
+    pub fn write_hints(&mut self, hints: &[Vec<u8>]) {
+        for hint in hints {
+            self.state.hint_stream.push(hint.clone());
+        }
+    }
+
+    /// Returns the slices of the input and hint streams that the program has read so far.
+    ///
+    /// Slices written to the input stream during execution, e.g. by hooks, are captured as well, so
+    /// replaying the tape does not depend on the host which ran the original execution.
+    pub fn capture_input_tape(&self) -> InputTape {
+        InputTape {
+            input: self.state.input_stream[..self.state.input_stream_ptr].to_vec(),
+            hints: self.state.hint_stream[..self.state.hint_stream_ptr].to_vec(),
+        }
+    }
+
+    /// Replaces the input and hint streams with a tape captured by `capture_input_tape`.
+    pub fn with_replay_tape(mut self, tape: InputTape) -> Self {
+        self.state.input_stream = tape.input;
+        self.state.input_stream_ptr = 0;
+        self.state.hint_stream = tape.hints;
+        self.state.hint_stream_ptr = 0;
+        self
+    }
+
     pub fn write_proof(
         &mut self,
         proof: ShardProof<BabyBearPoseidon2>,
@@ -58,7 +93,7 @@ impl<'a> Runtime<'a> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::runtime::Program;
+    use crate::runtime::{Instruction, Opcode, Program, Register, SyscallCode};
     use crate::utils::tests::IO_ELF;
     use crate::utils::{self, prove_simple, BabyBearBlake3, SP1CoreOpts};
     use serde::Deserialize;
@@ -117,4 +152,60 @@ pub mod tests {
         let config = BabyBearBlake3::new();
         prove_simple(config, runtime).unwrap();
     }
+
+    /// A program which reads a word from the input stream and one from the private hints, and
+    /// commits their sum to the public values.
+    fn input_tape_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_LEN as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_READ as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::READ_HINT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1004, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::LW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::LW, 30, 0, 0x1004, false, true),
+            Instruction::new(Opcode::ADD, 31, 29, 30, false, false),
+            Instruction::new(Opcode::ADD, 28, 0, 0x1008, false, true),
+            Instruction::new(Opcode::SW, 31, 28, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1008, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_input_tape_replay() {
+        utils::setup_logger();
+        let mut runtime = Runtime::new(input_tape_program(), SP1CoreOpts::default());
+        runtime.write_stdin_slice(&7u32.to_le_bytes());
+        runtime.write_stdin_slice(&[0xff; 8]);
+        runtime.write_hints(&[35u32.to_le_bytes().to_vec()]);
+        runtime.run().unwrap();
+
+        // Only the slices which were read are captured.
+        let tape = runtime.capture_input_tape();
+        assert_eq!(tape.input, vec![7u32.to_le_bytes().to_vec()]);
+        assert_eq!(tape.hints, vec![35u32.to_le_bytes().to_vec()]);
+
+        let tape: InputTape = bincode::deserialize(&bincode::serialize(&tape).unwrap()).unwrap();
+        let mut replay =
+            Runtime::new(input_tape_program(), SP1CoreOpts::default()).with_replay_tape(tape);
+        replay.run().unwrap();
+
+        assert_eq!(replay.registers(), runtime.registers());
+        assert_eq!(replay.state.global_clk, runtime.state.global_clk);
+        assert_eq!(
+            replay.state.public_values_stream,
+            runtime.state.public_values_stream
+        );
+        assert_eq!(replay.read_public_values::<u32>(), 42);
+        assert_eq!(replay.capture_input_tape(), runtime.capture_input_tape());
+    }
 }
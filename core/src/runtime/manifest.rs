@@ -0,0 +1,64 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::SyscallCode;
+
+/// The name of the ELF section in which a guest program declares the precompiles it uses.
+pub const MANIFEST_SECTION_NAME: &str = ".sp1_manifest";
+
+/// An error decoding the manifest of an ELF.
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("failed to read the manifest section: {0}")]
+    Elf(#[from] elf::ParseError),
+    #[error("manifest section length {0} is not a multiple of 4")]
+    InvalidLength(usize),
+    #[error("manifest declares an invalid syscall number {0:#x}")]
+    InvalidSyscall(u32),
+}
+
+/// The set of precompiles a program declares it uses.
+///
+/// The manifest is encoded in the ELF as a sequence of little-endian `u32` syscall codes. Only
+/// precompiles, i.e. syscalls which are proven in a separate table, are restricted by the manifest:
+/// system syscalls such as `HALT` or `WRITE` are always allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyscallManifest {
+    syscalls: BTreeSet<SyscallCode>,
+}
+
+impl SyscallManifest {
+    /// Create a manifest declaring the given syscalls.
+    pub fn new(syscalls: impl IntoIterator<Item = SyscallCode>) -> Self {
+        Self {
+            syscalls: syscalls.into_iter().collect(),
+        }
+    }
+
+    /// Decode a manifest from the contents of the manifest section of an ELF.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ManifestError> {
+        if bytes.len() % 4 != 0 {
+            return Err(ManifestError::InvalidLength(bytes.len()));
+        }
+        let syscalls = bytes
+            .chunks_exact(4)
+            .map(|chunk| {
+                let value = u32::from_le_bytes(chunk.try_into().unwrap());
+                SyscallCode::from_u32(value).map_err(|_| ManifestError::InvalidSyscall(value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(syscalls))
+    }
+
+    /// The syscalls declared by the manifest.
+    pub fn syscalls(&self) -> impl Iterator<Item = SyscallCode> + '_ {
+        self.syscalls.iter().copied()
+    }
+
+    /// Whether the manifest allows the program to invoke the given syscall.
+    pub fn allows(&self, syscall: SyscallCode) -> bool {
+        syscall.should_send() == 0 || self.syscalls.contains(&syscall)
+    }
+}
@@ -1,26 +1,35 @@
+mod cells;
 mod hooks;
 mod instruction;
 mod io;
+mod manifest;
 mod memory;
 mod opcode;
 mod program;
 mod record;
 mod register;
+mod replay;
 mod report;
+mod sampler;
 mod state;
 mod syscall;
 #[macro_use]
 mod utils;
 mod subproof;
 
+pub use cells::*;
 pub use hooks::*;
 pub use instruction::*;
+pub use io::InputTape;
+pub use manifest::*;
 pub use memory::*;
 pub use opcode::*;
 pub use program::*;
 pub use record::*;
 pub use register::*;
+pub use replay::*;
 pub use report::*;
+pub use sampler::*;
 pub use state::*;
 pub use subproof::*;
 pub use syscall::*;
@@ -31,6 +40,7 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use thiserror::Error;
@@ -42,6 +52,14 @@ use crate::memory::MemoryInitializeFinalizeEvent;
 use crate::utils::SP1CoreOpts;
 use crate::{alu::AluEvent, cpu::CpuEvent};
 
+/// The initial stack pointer of guest programs, from which the stack grows down. Make sure this
+/// matches `STACK_TOP` in `zkvm/entrypoint/src/lib.rs`.
+pub const STACK_TOP: u32 = 0x0020_0400;
+
+/// The default lowest address the stack pointer may reach. The stack grows down towards the
+/// registers, which are at the lowest addresses of memory, so this keeps a guard region above them.
+pub const DEFAULT_STACK_LIMIT: u32 = 0x400;
+
 /// An implementation of a runtime for the SP1 RISC-V zkVM.
 ///
 /// The runtime is responsible for executing a user program and tracing important events which occur
@@ -67,6 +85,13 @@ pub struct Runtime<'a> {
 
     pub shard_batch_size: u32,
 
+    /// The budget of trace cells of each shard, if shards are also sized by their cells.
+    pub shard_cell_budget: Option<ShardCellBudget>,
+
+    /// The maximum number of calls to each precompile in a shard, if shards are also closed by
+    /// them.
+    pub precompile_shard_cap: Option<usize>,
+
     /// A counter for the number of cycles that have been executed in certain functions.
     pub cycle_tracker: HashMap<String, (u64, u32)>,
 
@@ -101,6 +126,33 @@ pub struct Runtime<'a> {
 
     /// Registry of hooks, to be invoked by writing to certain file descriptors.
     pub hook_registry: HookRegistry<'a>,
+
+    /// A journal of all memory writes, recorded if enabled with `enable_replay_log`.
+    pub replay_log: Option<ReplayLog>,
+
+    /// A sampled profile of the program counter, recorded if enabled with `enable_pc_sampling`.
+    pub pc_sampler: Option<PcSampler>,
+
+    /// The lowest address the stack pointer may reach, below which execution fails with
+    /// `ExecutionError::StackOverflow` instead of silently overwriting other memory.
+    pub stack_limit: u32,
+
+    /// The lowest value of the stack pointer during the execution.
+    pub lowest_stack_pointer: u32,
+
+    /// Whether the program requested to end the current shard after the current instruction.
+    pub shard_boundary_requested: bool,
+
+    /// The value read from memory which was never written, instead of zero, if set with
+    /// `set_uninit_fill`.
+    pub uninit_fill: Option<u32>,
+
+    /// Whether reading memory which was never written fails with
+    /// `ExecutionError::UninitializedRead`.
+    pub fault_on_uninit_read: bool,
+
+    /// The first address of never written memory read by the current instruction.
+    uninit_read: Option<u32>,
 }
 
 #[derive(Error, Debug)]
@@ -111,6 +163,22 @@ pub enum ExecutionError {
     InvalidMemoryAccess(Opcode, u32),
     #[error("unimplemented syscall {0}")]
     UnsupportedSyscall(u32),
+    #[error("syscall {0} is not declared in the program manifest")]
+    UndeclaredSyscall(SyscallCode),
+    #[error("syscall {0} can only be called inside an unconstrained block")]
+    UnconstrainedOnlySyscall(SyscallCode),
+    #[error("syscall pointer {0:#x} is not aligned to {1} bytes")]
+    UnalignedSyscallPointer(u32, u32),
+    #[error("syscall operands at {0:#x} and {1:#x} overlap")]
+    AliasingSyscallPointers(u32, u32),
+    #[error("syscall operands at {0:#x} and {1:#x} are at least 2^30 bytes apart")]
+    DistantSyscallPointers(u32, u32),
+    #[error("syscall operand at {0:#x} is not reduced modulo the modulus at {1:#x}")]
+    UnreducedSyscallOperand(u32, u32),
+    #[error("stack pointer {0:#x} is below the stack limit {1:#x}")]
+    StackOverflow(u32, u32),
+    #[error("read of uninitialized memory at {0:#x}")]
+    UninitializedRead(u32),
     #[error("breakpoint encountered")]
     Breakpoint(),
     #[error("got unimplemented as opcode")]
@@ -152,6 +220,10 @@ impl<'a> Runtime<'a> {
             memory_accesses: MemoryAccessRecord::default(),
             shard_size: (opts.shard_size as u32) * 4,
             shard_batch_size: opts.shard_batch_size as u32,
+            shard_cell_budget: opts
+                .shard_cell_budget
+                .map(|budget| ShardCellBudget::new(budget as u64)),
+            precompile_shard_cap: opts.precompile_shard_cap.map(NonZeroUsize::get),
             cycle_tracker: HashMap::new(),
             io_buf: HashMap::new(),
             trace_buf,
@@ -164,9 +236,53 @@ impl<'a> Runtime<'a> {
             print_report: false,
             subproof_verifier: Arc::new(DefaultSubproofVerifier::new()),
             hook_registry: HookRegistry::default(),
+            replay_log: None,
+            pc_sampler: None,
+            stack_limit: DEFAULT_STACK_LIMIT,
+            lowest_stack_pointer: u32::MAX,
+            shard_boundary_requested: false,
+            uninit_fill: None,
+            fault_on_uninit_read: false,
+            uninit_read: None,
         }
     }
 
+    /// Record every memory write of the execution into a replay log, from which the memory at any
+    /// timestamp can be reconstructed with `MemorySnapshot::at`.
+    pub fn enable_replay_log(&mut self) {
+        self.replay_log = Some(ReplayLog::new(self.program.memory_image.clone()));
+    }
+
+    /// Sample the program counter once every `interval` cycles into a `PcSampler`, to profile
+    /// where the cycles of the execution go. Cycles in unconstrained blocks are not sampled.
+    pub fn enable_pc_sampling(&mut self, interval: u64) {
+        self.pc_sampler = Some(PcSampler::new(interval));
+    }
+
+    /// Sets the lowest address the stack pointer may reach, for example `STACK_TOP - size` to bound
+    /// the stack of the program to `size` bytes.
+    pub fn set_stack_limit(&mut self, limit: u32) {
+        self.stack_limit = limit;
+    }
+
+    /// Fills memory which is read before it was ever written with `fill`, e.g. `0xDEADBEEF`, so
+    /// that programs which depend on uninitialized memory being zero misbehave visibly. Registers
+    /// and values supplied by hints are not affected.
+    pub fn set_uninit_fill(&mut self, fill: u32) {
+        self.uninit_fill = Some(fill);
+    }
+
+    /// Makes reading memory which was never written fail with `ExecutionError::UninitializedRead`.
+    pub fn enable_uninit_read_faults(&mut self) {
+        self.fault_on_uninit_read = true;
+    }
+
+    /// Returns the number of bytes of the stack used so far, from `STACK_TOP` down to the lowest
+    /// stack pointer.
+    pub fn stack_usage(&self) -> u32 {
+        STACK_TOP.saturating_sub(self.lowest_stack_pointer)
+    }
+
     /// Invokes the hook corresponding to the given file descriptor `fd` with the data `buf`,
     /// returning the resulting data.
     pub fn hook(&self, fd: u32, buf: &[u8]) -> Vec<Vec<u8>> {
@@ -241,8 +357,38 @@ impl<'a> Runtime<'a> {
         self.state.channel
     }
 
+    /// Checks that a read of `addr` at `timestamp` is ordered after the last access to it.
+    ///
+    /// A read at or before the last access in the same shard would produce an inconsistent
+    /// `prev_timestamp`, so it panics with the reading instruction, or the syscall if the read
+    /// comes from a precompile, and the clock. This covers both CPU loads and precompile reads.
+    #[cfg(feature = "debug")]
+    fn check_read(&self, addr: u32, shard: u32, timestamp: u32) {
+        let Some(record) = self.state.memory.get(&addr) else {
+            return;
+        };
+        if record.shard == shard && record.timestamp >= timestamp {
+            let index = self.state.pc.wrapping_sub(self.program.pc_base) / 4;
+            let reader = match self.program.instructions.get(index as usize) {
+                Some(instruction) if instruction.opcode == Opcode::ECALL => {
+                    SyscallCode::from_u32(self.register(Register::X5))
+                        .map_or_else(|err| err.to_string(), |syscall| syscall.to_string())
+                }
+                Some(instruction) => format!("{:?} at pc {:#x}", instruction.opcode, self.state.pc),
+                None => format!("pc {:#x}", self.state.pc),
+            };
+            panic!(
+                "{} at clk {} reads address {:#x} at or before its last access at clk {}",
+                reader, timestamp, addr, record.timestamp
+            );
+        }
+    }
+
     /// Read a word from memory and create an access record.
     pub fn mr(&mut self, addr: u32, shard: u32, timestamp: u32) -> MemoryReadRecord {
+        #[cfg(feature = "debug")]
+        self.check_read(addr, shard, timestamp);
+
         // Get the memory record entry.
         let entry = self.state.memory.entry(addr);
 
@@ -263,8 +409,18 @@ impl<'a> Runtime<'a> {
         let record: &mut MemoryRecord = match entry {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
-                // If addr has a specific value to be initialized with, use that, otherwise 0.
-                let value = self.state.uninitialized_memory.remove(&addr).unwrap_or(0);
+                // If addr has a specific value to be initialized with, use that. Otherwise the
+                // address was never written, so use the fill value, or 0.
+                let value = match self.state.uninitialized_memory.remove(&addr) {
+                    Some(value) => value,
+                    None if addr < 32 => 0,
+                    None => {
+                        if self.fault_on_uninit_read {
+                            self.uninit_read.get_or_insert(addr);
+                        }
+                        self.uninit_fill.unwrap_or(0)
+                    }
+                };
 
                 // Do not emit memory initialize events for address 0 as that is done in initialize.
                 if addr != 0 {
@@ -272,6 +428,14 @@ impl<'a> Runtime<'a> {
                         .memory_initialize_events
                         .push(MemoryInitializeFinalizeEvent::initialize(addr, value, true));
                 }
+
+                // Values loaded from uninitialized memory first become visible at this read.
+                if let Some(log) = self.replay_log.as_mut() {
+                    if value != 0 && !self.unconstrained {
+                        log.record(shard, timestamp, addr, value);
+                    }
+                }
+
                 entry.insert(MemoryRecord {
                     value,
                     shard: 0,
@@ -284,6 +448,7 @@ impl<'a> Runtime<'a> {
         let prev_timestamp = record.timestamp;
         record.shard = shard;
         record.timestamp = timestamp;
+        self.count_memory_access(shard, MemoryAccessCounts::READ);
 
         // Construct the memory read record.
         MemoryReadRecord::new(value, shard, timestamp, prev_shard, prev_timestamp)
@@ -333,6 +498,13 @@ impl<'a> Runtime<'a> {
         record.value = value;
         record.shard = shard;
         record.timestamp = timestamp;
+        self.count_memory_access(shard, MemoryAccessCounts::WRITE);
+
+        if let Some(log) = self.replay_log.as_mut() {
+            if !self.unconstrained {
+                log.record(shard, timestamp, addr, value);
+            }
+        }
 
         // Construct the memory write record.
         MemoryWriteRecord::new(
@@ -345,6 +517,19 @@ impl<'a> Runtime<'a> {
         )
     }
 
+    /// Counts a memory access of `shard` in the record, if events are emitted, and in the report.
+    fn count_memory_access(&mut self, shard: u32, access: MemoryAccessCounts) {
+        if self.unconstrained {
+            return;
+        }
+        if self.emit_events {
+            *self.record.memory_access_counts.entry(shard).or_default() += access;
+        }
+        if self.print_report {
+            *self.report.memory_access_counts.entry(shard).or_default() += access;
+        }
+    }
+
     /// Read from memory, assuming that all addresses are aligned.
     pub fn mr_cpu(&mut self, addr: u32, position: MemoryAccessPosition) -> u32 {
         // Assert that the address is aligned.
@@ -815,7 +1000,13 @@ impl<'a> Runtime<'a> {
                 let syscall_id = self.register(t0);
                 c = self.rr(Register::X11, MemoryAccessPosition::C);
                 b = self.rr(Register::X10, MemoryAccessPosition::B);
-                let syscall = SyscallCode::from_u32(syscall_id);
+                let syscall = SyscallCode::from_u32(syscall_id)?;
+
+                if let Some(manifest) = &self.program.manifest {
+                    if !manifest.allows(syscall) {
+                        return Err(ExecutionError::UndeclaredSyscall(syscall));
+                    }
+                }
 
                 if self.print_report && !self.unconstrained {
                     self.report
@@ -833,6 +1024,9 @@ impl<'a> Runtime<'a> {
                         // Executing a syscall optionally returns a value to write to the t0 register.
                         // If it returns None, we just keep the syscall_id in t0.
                         let res = syscall_impl.execute(&mut precompile_rt, b, c);
+                        if let Some(err) = precompile_rt.error.take() {
+                            return Err(err);
+                        }
                         if let Some(val) = res {
                             a = val;
                         } else {
@@ -976,18 +1170,77 @@ impl<'a> Runtime<'a> {
         // Log the current state of the runtime.
         self.log(&instruction);
 
+        // Sample the program counter of the cycle, if sampling is enabled.
+        if let (Some(sampler), false) = (self.pc_sampler.as_mut(), self.unconstrained) {
+            sampler.sample(self.state.global_clk, self.state.pc);
+        }
+
+        // If this cycle calls a precompile which reached its cap of calls in the shard, move to the
+        // next shard before executing it.
+        let precompile = self
+            .precompile_shard_cap
+            .filter(|_| instruction.opcode == Opcode::ECALL && !self.unconstrained)
+            .and_then(|cap| {
+                let syscall = SyscallCode::from_u32(self.register(Register::X5)).ok()?;
+                Some((cap, syscall))
+            })
+            .filter(|(_, syscall)| syscall.should_send() == 1);
+        if let Some((cap, syscall)) = precompile {
+            let calls = self.state.shard_precompile_calls.get(&syscall).copied();
+            if self.state.clk > 0 && calls.unwrap_or(0) >= cap {
+                self.start_next_shard();
+            }
+        }
+
+        // If the shard has a cell budget that this cycle would overflow, move to the next shard
+        // before executing it. Precompiles execute within their cycle, so their memory accesses are
+        // never split across shards.
+        if let (Some(budget), false) = (&self.shard_cell_budget, self.unconstrained) {
+            let syscall = (instruction.opcode == Opcode::ECALL)
+                .then(|| SyscallCode::from_u32(self.register(Register::X5)).ok())
+                .flatten();
+            let cells = budget.cycle_cells(instruction.opcode, syscall);
+            if self.state.clk > 0 && !budget.fits(self.state.shard_cells, cells) {
+                self.start_next_shard();
+            }
+            self.state.shard_cells += cells;
+        }
+        if let Some((_, syscall)) = precompile {
+            *self
+                .state
+                .shard_precompile_calls
+                .entry(syscall)
+                .or_default() += 1;
+        }
+
         // Execute the instruction.
         self.execute_instruction(instruction)?;
 
+        // If the instruction read memory which was never written, fail if configured to.
+        if let Some(addr) = self.uninit_read.take() {
+            return Err(ExecutionError::UninitializedRead(addr));
+        }
+
+        // If the instruction may have moved the stack pointer, check that the stack did not grow
+        // past its limit.
+        if instruction.op_a == Register::X2 as u32 {
+            let sp = self.register(Register::X2);
+            self.lowest_stack_pointer = self.lowest_stack_pointer.min(sp);
+            if sp < self.stack_limit {
+                return Err(ExecutionError::StackOverflow(sp, self.stack_limit));
+            }
+        }
+
         // Increment the clock.
         self.state.global_clk += 1;
 
-        // If there's not enough cycles left for another instruction, move to the next shard.
-        // We multiply by 4 because clk is incremented by 4 for each normal instruction.
-        if !self.unconstrained && self.max_syscall_cycles + self.state.clk >= self.shard_size {
-            self.state.current_shard += 1;
-            self.state.clk = 0;
-            self.state.channel = 0;
+        // If there's not enough cycles left for another instruction, or the program requested a
+        // shard boundary, move to the next shard. We multiply by 4 because clk is incremented by 4
+        // for each normal instruction.
+        let shard_full = self.max_syscall_cycles + self.state.clk >= self.shard_size;
+        let boundary_requested = std::mem::take(&mut self.shard_boundary_requested);
+        if !self.unconstrained && (shard_full || boundary_requested) {
+            self.start_next_shard();
         }
 
         Ok(self.state.pc.wrapping_sub(self.program.pc_base)
@@ -1011,6 +1264,15 @@ impl<'a> Runtime<'a> {
         Ok((state, done))
     }
 
+    /// Closes the current shard, so the next cycle starts a new one.
+    fn start_next_shard(&mut self) {
+        self.state.current_shard += 1;
+        self.state.clk = 0;
+        self.state.channel = 0;
+        self.state.shard_cells = 0;
+        self.state.shard_precompile_calls.clear();
+    }
+
     fn initialize(&mut self) {
         self.state.clk = 0;
         self.state.channel = 0;
@@ -1047,6 +1309,28 @@ impl<'a> Runtime<'a> {
         Ok(())
     }
 
+    /// Executes the program with events until `clk_limit` cycles have been executed in total or
+    /// it halts, returning whether it has finished.
+    ///
+    /// If the program stops at the limit, the memory is finalized with its values at that point,
+    /// so the record proves the execution up to the checkpoint. The checkpoint is committed to by
+    /// [ExecutionState::memory_digest], and a later run resumes from it with [Runtime::recover].
+    pub fn run_until(&mut self, clk_limit: u64) -> Result<bool, ExecutionError> {
+        self.emit_events = true;
+        self.print_report = true;
+        if self.state.global_clk == 0 {
+            self.initialize();
+        }
+        while self.state.global_clk < clk_limit {
+            if self.execute_cycle()? {
+                self.postprocess();
+                return Ok(true);
+            }
+        }
+        self.finalize_memory();
+        Ok(false)
+    }
+
     pub fn dry_run(&mut self) {
         self.emit_events = false;
         while !self.execute().unwrap() {}
@@ -1113,8 +1397,16 @@ impl<'a> Runtime<'a> {
         if self.state.input_stream_ptr != self.state.input_stream.len() {
             log::warn!("Not all input bytes were read.");
         }
+        if self.state.hint_stream_ptr != self.state.hint_stream.len() {
+            log::warn!("Not all private hints were read.");
+        }
 
-        // SECTION: Set up all MemoryInitializeFinalizeEvents needed for memory argument.
+        self.finalize_memory();
+    }
+
+    /// Sets up all the memory finalize events needed for the memory argument, from the current
+    /// values of the memory.
+    fn finalize_memory(&mut self) {
         let memory_finalize_events = &mut self.record.memory_finalize_events;
 
         // We handle the addr = 0 case separately, as we constrain it to be 0 in the first row
@@ -1155,15 +1447,28 @@ impl<'a> Runtime<'a> {
 #[cfg(test)]
 pub mod tests {
 
+    use std::sync::Arc;
+
+    use p3_baby_bear::BabyBear;
+
     use crate::{
+        air::MachineAir,
         runtime::Register,
+        stark::{
+            BatchedLogUp, Poseidon2CompressChip, Poseidon2PermuteChip, RiscvAir, Uint256MulChip,
+        },
+        io::SP1Stdin,
         utils::{
-            tests::{FIBONACCI_ELF, PANIC_ELF, SSZ_WITHDRAWALS_ELF},
-            SP1CoreOpts,
+            prove_until, run_test_machine, setup_logger,
+            tests::{store_words, FIBONACCI_ELF, PANIC_ELF, SSZ_WITHDRAWALS_ELF},
+            BabyBearPoseidon2, SP1CoreOpts,
         },
     };
 
-    use super::{Instruction, Opcode, Program, Runtime};
+    use super::{
+        ExecutionError, Instruction, ManifestError, Opcode, Program, Runtime, SyscallCode,
+        SyscallManifest,
+    };
 
     pub fn simple_program() -> Program {
         let instructions = vec![
@@ -1811,4 +2116,240 @@ pub mod tests {
         assert_eq!(runtime.register(Register::X12), 0x12346525);
         assert_eq!(runtime.register(Register::X11), 0x65256525);
     }
+
+    fn ecall_program(syscall: SyscallCode) -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, syscall as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x2000, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program.manifest = Some(SyscallManifest::new([SyscallCode::UINT256_MUL]));
+        program
+    }
+
+    #[test]
+    fn test_manifest_rejects_undeclared_syscall() {
+        for syscall in [
+            SyscallCode::POSEIDON2_COMPRESS,
+            SyscallCode::POSEIDON2_PERMUTE,
+        ] {
+            let mut runtime = Runtime::new(ecall_program(syscall), SP1CoreOpts::default());
+            let result = runtime.run();
+            assert!(
+                matches!(result, Err(ExecutionError::UndeclaredSyscall(code)) if code == syscall)
+            );
+        }
+    }
+
+    #[test]
+    fn test_manifest_machine() {
+        let program = ecall_program(SyscallCode::UINT256_MUL);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let machine = RiscvAir::machine_for_program(
+            BabyBearPoseidon2::new(),
+            &runtime.program,
+            Arc::new(BatchedLogUp),
+        );
+        let chip_names = machine
+            .chips()
+            .iter()
+            .map(|chip| chip.name())
+            .collect::<Vec<_>>();
+        let uint256_mul = RiscvAir::<BabyBear>::Uint256Mul(Uint256MulChip::default());
+        let poseidon2_compress =
+            RiscvAir::<BabyBear>::Poseidon2Compress(Poseidon2CompressChip::default());
+        let poseidon2_permute =
+            RiscvAir::<BabyBear>::Poseidon2Permute(Poseidon2PermuteChip::default());
+        assert!(chip_names.contains(&uint256_mul.name()));
+        assert!(!chip_names.contains(&poseidon2_compress.name()));
+        assert!(!chip_names.contains(&poseidon2_permute.name()));
+
+        let (pk, vk) = machine.setup(runtime.program.as_ref());
+        run_test_machine(runtime.record, machine, pk, vk).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_encoding() {
+        let bytes = [SyscallCode::UINT256_MUL as u32, SyscallCode::SHA_EXTEND as u32]
+            .into_iter()
+            .flat_map(u32::to_le_bytes)
+            .collect::<Vec<_>>();
+        let manifest = SyscallManifest::from_bytes(&bytes).unwrap();
+        assert!(manifest.allows(SyscallCode::UINT256_MUL));
+        assert!(manifest.allows(SyscallCode::SHA_EXTEND));
+        assert!(manifest.allows(SyscallCode::HALT));
+        assert!(manifest.allows(SyscallCode::WRITE));
+        assert!(!manifest.allows(SyscallCode::POSEIDON2_COMPRESS));
+
+        // Truncated sections and unknown syscall numbers are rejected.
+        assert!(matches!(
+            SyscallManifest::from_bytes(&bytes[..5]),
+            Err(ManifestError::InvalidLength(5))
+        ));
+        assert!(matches!(
+            SyscallManifest::from_bytes(&u32::MAX.to_le_bytes()),
+            Err(ManifestError::InvalidSyscall(u32::MAX))
+        ));
+    }
+
+    /// A program storing the words `1..=num_words` to consecutive addresses, with three cycles per
+    /// word.
+    fn store_words_program(num_words: u32) -> Program {
+        Program::new(store_words(0x1000, 1..=num_words), 0, 0)
+    }
+
+    #[test]
+    fn test_run_until_checkpoint() {
+        setup_logger();
+        let program = store_words_program(16);
+        let mut full = Runtime::new(program.clone(), SP1CoreOpts::default());
+        full.run().unwrap();
+
+        // Prove the execution up to a checkpoint in the middle of the program.
+        let (proof, checkpoint) = prove_until(
+            program.clone(),
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+            20,
+        )
+        .unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+        // The first six words are stored at the checkpoint, and the digest commits to them.
+        assert!(!checkpoint.done);
+        assert_eq!(checkpoint.state.global_clk, 20);
+        assert_eq!(checkpoint.memory_digest, checkpoint.state.memory_digest());
+        let mut runtime =
+            Runtime::recover(program.clone(), checkpoint.state, SP1CoreOpts::default());
+        for i in 0..16 {
+            let expected = if i < 6 { i + 1 } else { 0 };
+            assert_eq!(runtime.word(0x1000 + i * 4), expected);
+        }
+        let mut other = Runtime::new(program.clone(), SP1CoreOpts::default());
+        assert!(!other.run_until(20).unwrap());
+        assert_eq!(other.state.memory_digest(), checkpoint.memory_digest);
+        let mut other = Runtime::new(program.clone(), SP1CoreOpts::default());
+        assert!(!other.run_until(21).unwrap());
+        assert_ne!(other.state.memory_digest(), checkpoint.memory_digest);
+
+        // Resuming from the checkpoint reaches the state of the full run.
+        runtime.run().unwrap();
+        assert_eq!(runtime.state.memory_digest(), full.state.memory_digest());
+        for i in 0..16 {
+            assert_eq!(runtime.word(0x1000 + i * 4), i + 1);
+        }
+    }
+
+    /// A program whose function calls itself forever, pushing a 16-byte frame each time.
+    fn unbounded_recursion_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 2, 0, 0x2000, false, true),
+            // Push a frame with the return address, and call the function again.
+            Instruction::new(Opcode::SUB, 2, 2, 16, false, true),
+            Instruction::new(Opcode::SW, 1, 2, 12, false, true),
+            Instruction::new(Opcode::JAL, 1, -8i32 as u32, 0, true, true),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_stack_overflow() {
+        let mut runtime = Runtime::new(unbounded_recursion_program(), SP1CoreOpts::default());
+        let result = runtime.run();
+        assert!(matches!(
+            result,
+            Err(ExecutionError::StackOverflow(0x3f0, super::DEFAULT_STACK_LIMIT))
+        ));
+        assert_eq!(runtime.stack_usage(), super::STACK_TOP - 0x3f0);
+    }
+
+    #[test]
+    fn test_stack_overflow_configured_limit() {
+        let mut runtime = Runtime::new(unbounded_recursion_program(), SP1CoreOpts::default());
+        runtime.set_stack_limit(0x1000);
+        let result = runtime.run();
+        assert!(matches!(result, Err(ExecutionError::StackOverflow(0xff0, 0x1000))));
+        assert_eq!(runtime.lowest_stack_pointer, 0xff0);
+    }
+
+    #[test]
+    fn test_stack_usage_without_stack() {
+        let mut runtime = Runtime::new(simple_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.stack_usage(), 0);
+    }
+
+    /// A program storing a word to 0x1000 and loading it back into x29, then loading the never
+    /// written word at 0x2000 into x30.
+    fn uninit_read_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 7, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::LW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::LW, 30, 0, 0x2000, false, true),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_uninit_fill() {
+        let mut runtime = Runtime::new(uninit_read_program(), SP1CoreOpts::default());
+        runtime.set_uninit_fill(0xDEADBEEF);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X29), 7);
+        assert_eq!(runtime.register(Register::X30), 0xDEADBEEF);
+    }
+
+    #[test]
+    fn test_uninit_read_fault() {
+        let mut runtime = Runtime::new(uninit_read_program(), SP1CoreOpts::default());
+        runtime.set_uninit_fill(0xDEADBEEF);
+        runtime.enable_uninit_read_faults();
+        let result = runtime.run();
+        assert!(matches!(
+            result,
+            Err(ExecutionError::UninitializedRead(0x2000))
+        ));
+        assert_eq!(runtime.register(Register::X29), 7);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_programs_read_in_order() {
+        use crate::utils::tests::{KECCAK_PERMUTE_ELF, SECP256K1_ADD_ELF, UINT256_MUL_ELF};
+
+        // Under the debug feature every CPU load and precompile read checks its ordering, so these
+        // programs would panic if any of their reads were misordered.
+        setup_logger();
+        for elf in [
+            SSZ_WITHDRAWALS_ELF,
+            KECCAK_PERMUTE_ELF,
+            SECP256K1_ADD_ELF,
+            UINT256_MUL_ELF,
+        ] {
+            let mut runtime = Runtime::new(Program::from(elf), SP1CoreOpts::default());
+            runtime.run().unwrap();
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    #[should_panic(expected = "LW at pc 0x0 at clk 0 reads address 0x1000 at or before its last")]
+    fn test_misordered_load_is_flagged() {
+        let instructions = vec![Instruction::new(Opcode::LW, 30, 0, 0x1000, false, true)];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+
+        // Write the word at a later clock than the load, which misorders the accesses.
+        let shard = runtime.shard();
+        runtime.mw(0x1000, 1, shard, 1 << 10);
+        runtime.run().unwrap();
+    }
 }
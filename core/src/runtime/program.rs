@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use super::Instruction;
+use super::{Instruction, SyscallManifest};
 
 /// A program that can be executed by the VM.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,4 +17,12 @@ pub struct Program {
 
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
+
+    /// The precompiles the program declares it uses, if it has a manifest.
+    #[serde(default)]
+    pub manifest: Option<SyscallManifest>,
+
+    /// The lookup tables of the program, mapping keys to values, by table id.
+    #[serde(default)]
+    pub lookup_tables: BTreeMap<u32, BTreeMap<u32, u32>>,
 }
@@ -1,30 +1,92 @@
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::mem::take;
+use std::num::NonZeroUsize;
+use std::ops::AddAssign;
 use std::sync::Arc;
 
 use itertools::Itertools;
+use p3_air::BaseAir;
+use p3_baby_bear::BabyBear;
 use p3_field::AbstractField;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use typenum::Unsigned;
 
 use super::program::Program;
 use super::Opcode;
+use crate::air::MachineAir;
 use crate::air::PublicValues;
+use crate::air::WORD_SIZE;
 use crate::alu::AluEvent;
 use crate::bytes::event::ByteRecord;
+use crate::bytes::trace::NUM_ROWS as NUM_BYTE_ROWS;
 use crate::bytes::ByteLookupEvent;
 use crate::cpu::CpuEvent;
+use crate::operations::field::params::{NumLimbs, NumWords};
 use crate::runtime::MemoryInitializeFinalizeEvent;
 use crate::runtime::MemoryRecordEnum;
 use crate::stark::MachineRecord;
+use crate::stark::RiscvAir;
+use crate::syscall::precompiles::aes::{AesEncryptBlockEvent, AES_BLOCK_WORDS};
+use crate::syscall::precompiles::babybear::{
+    BytesToFieldsEvent, NUM_PACKED_BYTES, NUM_PACKED_ELEMENTS,
+};
+use crate::syscall::precompiles::bn254::Bn254FieldInvEvent;
+use crate::syscall::precompiles::bn254::Bn254Fp2MulEvent;
+use crate::syscall::precompiles::chacha20::{
+    self, ChaCha20BlockEvent, CHACHA20_BLOCK_HEADER_WORDS,
+};
+use crate::syscall::precompiles::crt::{CrtCombineEvent, CRT_COMBINE_INPUT_WORDS};
 use crate::syscall::precompiles::edwards::EdDecompressEvent;
-use crate::syscall::precompiles::keccak256::KeccakPermuteEvent;
+use crate::syscall::precompiles::encode::{
+    EncodeEvent, BASE64_ROW_BYTES, ENCODE_HEADER_WORDS, HEX_ROW_BYTES,
+};
+use crate::syscall::precompiles::field::FieldBatchInvEvent;
+use crate::syscall::precompiles::field::FieldIsZeroEvent;
+use crate::syscall::precompiles::field::{FieldAffineEvent, FIELD_AFFINE_HEADER_WORDS};
+use crate::syscall::precompiles::field::{FieldMacEvent, FIELD_MAC_HEADER_WORDS};
+use crate::syscall::precompiles::field::FieldMontgomeryReduceEvent;
+use crate::syscall::precompiles::field::FieldMulSmallEvent;
+use crate::syscall::precompiles::field::NttButterflyEvent;
+use crate::syscall::precompiles::hash_input::{
+    PrepareHashInputEvent, HASH_INPUT_LENGTH_PREFIX, HASH_INPUT_PAD,
+};
+use crate::syscall::precompiles::keccak256::{KeccakPermuteEvent, STATE_SIZE};
+use crate::syscall::precompiles::memcmp::{CtMemcmpEvent, CT_MEMCMP_HEADER_WORDS};
+use crate::syscall::precompiles::memcpy::MemcpyEvent;
+use crate::syscall::precompiles::poseidon2::{
+    Poseidon2Event, Poseidon2PermuteEvent, DIGEST_SIZE, WIDTH as POSEIDON2_WIDTH,
+};
+use crate::syscall::precompiles::secp256k1::{Secp256k1CompressEvent, NUM_COMPRESSED_WORDS};
+use crate::syscall::precompiles::secp256k1::Secp256k1NormalizeSEvent;
 use crate::syscall::precompiles::sha256::{ShaCompressEvent, ShaExtendEvent};
+use crate::syscall::precompiles::table::{CtLookupEvent, CT_LOOKUP_HEADER_WORDS};
+use crate::syscall::precompiles::table::TableLookupEvent;
+use crate::syscall::precompiles::uint256::ScalarInRangeEvent;
+use crate::syscall::precompiles::uint256::{ScalarToNafEvent, NUM_NAF_WORDS};
+use crate::syscall::precompiles::uint256::Uint256CmovEvent;
+use crate::syscall::precompiles::uint256::Uint256GcdEvent;
+use crate::syscall::precompiles::uint256::Uint256ModSmallEvent;
 use crate::syscall::precompiles::uint256::Uint256MulEvent;
+use crate::syscall::precompiles::uint256::Uint256ShiftEvent;
+use crate::syscall::precompiles::uint256::Uint256SqrEvent;
+use crate::syscall::precompiles::uint256::{Uint256WnafEvent, NUM_WNAF_WORDS};
+use crate::syscall::precompiles::uint256::{
+    Uint256ToDecimalEvent, NUM_DECIMAL_DIGITS, UINT256_TO_DECIMAL_ROWS,
+};
 use crate::syscall::precompiles::ECDecompressEvent;
 use crate::syscall::precompiles::{ECAddEvent, ECDoubleEvent};
+use crate::utils::ec::edwards::ed25519::Ed25519BaseField;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::ec::weierstrass::bls12_381::Bls12381BaseField;
+use crate::utils::ec::weierstrass::bn254::Bn254BaseField;
+use crate::utils::ec::weierstrass::secp256k1::Secp256k1BaseField;
 use crate::utils::SP1CoreOpts;
 
+/// The number of words of a uint256, the operand size of most precompiles.
+const NUM_WORDS_IN_UINT256: usize = <U256Field as NumWords>::WordsFieldElement::USIZE;
+
 /// A record of the execution of a program. Contains event data for everything that happened during
 /// the execution of the shard.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -98,12 +160,176 @@ pub struct ExecutionRecord {
 
     pub bls12381_decompress_events: Vec<ECDecompressEvent>,
 
+    pub field_mul_small_events: Vec<FieldMulSmallEvent>,
+
+    pub bn254_fr_inv_events: Vec<Bn254FieldInvEvent>,
+
+    pub aes_encrypt_block_events: Vec<AesEncryptBlockEvent>,
+
+    pub crt_combine_events: Vec<CrtCombineEvent>,
+
+    pub uint256_cmov_events: Vec<Uint256CmovEvent>,
+
+    pub poseidon2_compress_events: Vec<Poseidon2Event>,
+
+    pub poseidon2_permute_events: Vec<Poseidon2PermuteEvent>,
+
+    pub field_batch_inv_events: Vec<FieldBatchInvEvent>,
+
+    pub bytes_to_fields_events: Vec<BytesToFieldsEvent>,
+
+    pub table_lookup_events: Vec<TableLookupEvent>,
+
+    pub uint256_to_decimal_events: Vec<Uint256ToDecimalEvent>,
+
+    pub secp256k1_normalize_s_events: Vec<Secp256k1NormalizeSEvent>,
+
+    pub uint256_mod_small_events: Vec<Uint256ModSmallEvent>,
+
+    pub uint256_wnaf_events: Vec<Uint256WnafEvent>,
+
+    pub secp256k1_compress_events: Vec<Secp256k1CompressEvent>,
+
+    pub memcpy_events: Vec<MemcpyEvent>,
+
+    pub hex_encode_events: Vec<EncodeEvent>,
+
+    pub base64_encode_events: Vec<EncodeEvent>,
+
+    pub prepare_hash_input_events: Vec<PrepareHashInputEvent>,
+
+    pub field_montgomery_reduce_events: Vec<FieldMontgomeryReduceEvent>,
+
+    pub ntt_butterfly_events: Vec<NttButterflyEvent>,
+
+    pub scalar_in_range_events: Vec<ScalarInRangeEvent>,
+
+    pub field_is_zero_events: Vec<FieldIsZeroEvent>,
+
+    pub uint256_shl_events: Vec<Uint256ShiftEvent>,
+
+    pub uint256_shr_events: Vec<Uint256ShiftEvent>,
+
+    pub uint256_gcd_events: Vec<Uint256GcdEvent>,
+
+    pub ct_lookup_events: Vec<CtLookupEvent>,
+
+    pub scalar_to_naf_events: Vec<ScalarToNafEvent>,
+
+    pub bn254_fp_inv_events: Vec<Bn254FieldInvEvent>,
+
+    pub field_mac_events: Vec<FieldMacEvent>,
+
+    pub field_affine_events: Vec<FieldAffineEvent>,
+
+    pub chacha20_block_events: Vec<ChaCha20BlockEvent>,
+
+    pub ct_memcmp_events: Vec<CtMemcmpEvent>,
+
+    pub uint256_sqr_events: Vec<Uint256SqrEvent>,
+
+    pub bn254_fp2_mul_events: Vec<Bn254Fp2MulEvent>,
+
+    /// The memory reads and writes of all chips, keyed by the shard during whose execution they
+    /// were recorded.
+    pub memory_access_counts: BTreeMap<u32, MemoryAccessCounts>,
+
     /// The public values.
     pub public_values: PublicValues<u32, u32>,
 
     pub nonce_lookup: HashMap<usize, u32>,
 }
 
+/// The number of memory accesses recorded during execution, registers included, each of which adds
+/// to the memory argument.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccessCounts {
+    /// The number of memory reads.
+    pub reads: u64,
+
+    /// The number of memory writes.
+    pub writes: u64,
+}
+
+impl MemoryAccessCounts {
+    pub const READ: Self = Self {
+        reads: 1,
+        writes: 0,
+    };
+
+    pub const WRITE: Self = Self {
+        reads: 0,
+        writes: 1,
+    };
+
+    /// The total number of memory accesses.
+    pub fn total(&self) -> u64 {
+        self.reads + self.writes
+    }
+}
+
+impl AddAssign for MemoryAccessCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.reads += rhs.reads;
+        self.writes += rhs.writes;
+    }
+}
+
+/// An estimate of the cost of proving an execution record, derived from its event counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProveEstimate {
+    /// The padded number of rows of each chip included in the record, keyed by chip name.
+    pub chip_rows: BTreeMap<String, usize>,
+
+    /// The number of rows of each chip filled by events, before padding, keyed by chip name.
+    pub chip_real_rows: BTreeMap<String, usize>,
+
+    /// The total number of rows over all chips.
+    pub total_rows: usize,
+
+    /// The total number of main trace cells over all chips.
+    pub total_cells: usize,
+
+    /// An estimate of the FRI cost, as the sum over chips of `cells * log2(rows)`.
+    ///
+    /// This is proportional to the work of the low-degree extensions and commitments, up to a
+    /// constant factor depending on the blowup, so it is meant to compare records with each other.
+    pub fri_cost: usize,
+}
+
+/// An event of an [ExecutionRecord] which does not have the shape its chip expects, found by
+/// [ExecutionRecord::validate].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecordValidationError {
+    #[error(
+        "{events}[{index}] at shard {shard} and clk {clk} is before the previous event at shard \
+         {prev_shard} and clk {prev_clk}"
+    )]
+    ClkOutOfOrder {
+        events: &'static str,
+        index: usize,
+        shard: u32,
+        clk: u32,
+        prev_shard: u32,
+        prev_clk: u32,
+    },
+    #[error("{events}[{index}].{field} = {ptr:#x} is not word aligned")]
+    UnalignedPointer {
+        events: &'static str,
+        index: usize,
+        field: &'static str,
+        ptr: u32,
+    },
+    #[error("{events}[{index}].{field} has {len} entries, but {expected} are expected")]
+    LengthMismatch {
+        events: &'static str,
+        index: usize,
+        field: &'static str,
+        len: usize,
+        expected: usize,
+    },
+}
+
 pub struct ShardingConfig {
     pub shard_size: usize,
     pub add_len: usize,
@@ -126,9 +352,38 @@ pub struct ShardingConfig {
 }
 
 impl ShardingConfig {
+    /// The sharding config of the runtime options, whose precompile events are chunked by
+    /// [SP1CoreOpts::precompile_shard_cap] if it is set.
+    pub fn new(opts: &SP1CoreOpts) -> Self {
+        let config = Self::default();
+        match opts.precompile_shard_cap {
+            Some(cap) => config.with_precompile_shard_cap(cap),
+            None => config,
+        }
+    }
+
     pub const fn shard_size(&self) -> usize {
         self.shard_size
     }
+
+    /// Chunks the events of each precompile chunked across shards into `cap` events per shard.
+    ///
+    /// The events are dealt to the shards in order, `cap` at a time, regardless of the shard which
+    /// called them, so a shard whose calls stayed under the cap moves the events of the following
+    /// shards into earlier tables. Since the runtime closes shards under the same cap, no shard
+    /// makes more than `cap` calls, so the shards have room for all the events.
+    pub const fn with_precompile_shard_cap(mut self, cap: NonZeroUsize) -> Self {
+        let cap = cap.get();
+        self.keccak_len = cap;
+        self.secp256k1_add_len = cap;
+        self.secp256k1_double_len = cap;
+        self.bn254_add_len = cap;
+        self.bn254_double_len = cap;
+        self.bls12381_add_len = cap;
+        self.bls12381_double_len = cap;
+        self.uint256_mul_len = cap;
+        self
+    }
 }
 
 impl Default for ShardingConfig {
@@ -236,6 +491,143 @@ impl MachineRecord for ExecutionRecord {
             "bls12381_decompress_events".to_string(),
             self.bls12381_decompress_events.len(),
         );
+        stats.insert(
+            "field_mul_small_events".to_string(),
+            self.field_mul_small_events.len(),
+        );
+        stats.insert(
+            "bn254_fr_inv_events".to_string(),
+            self.bn254_fr_inv_events.len(),
+        );
+        stats.insert(
+            "aes_encrypt_block_events".to_string(),
+            self.aes_encrypt_block_events.len(),
+        );
+        stats.insert(
+            "crt_combine_events".to_string(),
+            self.crt_combine_events.len(),
+        );
+        stats.insert(
+            "uint256_cmov_events".to_string(),
+            self.uint256_cmov_events.len(),
+        );
+        stats.insert(
+            "poseidon2_compress_events".to_string(),
+            self.poseidon2_compress_events.len(),
+        );
+        stats.insert(
+            "poseidon2_permute_events".to_string(),
+            self.poseidon2_permute_events.len(),
+        );
+        stats.insert(
+            "field_batch_inv_events".to_string(),
+            self.field_batch_inv_events.len(),
+        );
+        stats.insert(
+            "bytes_to_fields_events".to_string(),
+            self.bytes_to_fields_events.len(),
+        );
+        stats.insert(
+            "table_lookup_events".to_string(),
+            self.table_lookup_events.len(),
+        );
+        stats.insert(
+            "uint256_to_decimal_events".to_string(),
+            self.uint256_to_decimal_events.len(),
+        );
+        stats.insert(
+            "secp256k1_normalize_s_events".to_string(),
+            self.secp256k1_normalize_s_events.len(),
+        );
+        stats.insert(
+            "uint256_mod_small_events".to_string(),
+            self.uint256_mod_small_events.len(),
+        );
+        stats.insert(
+            "uint256_wnaf_events".to_string(),
+            self.uint256_wnaf_events.len(),
+        );
+        stats.insert(
+            "secp256k1_compress_events".to_string(),
+            self.secp256k1_compress_events.len(),
+        );
+        stats.insert(
+            "memcpy_events".to_string(),
+            self.memcpy_events.len(),
+        );
+        stats.insert(
+            "hex_encode_events".to_string(),
+            self.hex_encode_events.len(),
+        );
+        stats.insert(
+            "base64_encode_events".to_string(),
+            self.base64_encode_events.len(),
+        );
+        stats.insert(
+            "prepare_hash_input_events".to_string(),
+            self.prepare_hash_input_events.len(),
+        );
+        stats.insert(
+            "field_montgomery_reduce_events".to_string(),
+            self.field_montgomery_reduce_events.len(),
+        );
+        stats.insert(
+            "ntt_butterfly_events".to_string(),
+            self.ntt_butterfly_events.len(),
+        );
+        stats.insert(
+            "scalar_in_range_events".to_string(),
+            self.scalar_in_range_events.len(),
+        );
+        stats.insert(
+            "field_is_zero_events".to_string(),
+            self.field_is_zero_events.len(),
+        );
+        stats.insert(
+            "uint256_shl_events".to_string(),
+            self.uint256_shl_events.len(),
+        );
+        stats.insert(
+            "uint256_shr_events".to_string(),
+            self.uint256_shr_events.len(),
+        );
+        stats.insert(
+            "uint256_gcd_events".to_string(),
+            self.uint256_gcd_events.len(),
+        );
+        stats.insert("ct_lookup_events".to_string(), self.ct_lookup_events.len());
+        stats.insert(
+            "scalar_to_naf_events".to_string(),
+            self.scalar_to_naf_events.len(),
+        );
+        stats.insert(
+            "bn254_fp_inv_events".to_string(),
+            self.bn254_fp_inv_events.len(),
+        );
+        stats.insert(
+            "field_mac_events".to_string(),
+            self.field_mac_events.len(),
+        );
+        stats.insert(
+            "field_affine_events".to_string(),
+            self.field_affine_events.len(),
+        );
+        stats.insert(
+            "chacha20_block_events".to_string(),
+            self.chacha20_block_events.len(),
+        );
+        stats.insert(
+            "ct_memcmp_events".to_string(),
+            self.ct_memcmp_events.len(),
+        );
+        stats.insert(
+            "uint256_sqr_events".to_string(),
+            self.uint256_sqr_events.len(),
+        );
+        stats.insert(
+            "bn254_fp2_mul_events".to_string(),
+            self.bn254_fp2_mul_events.len(),
+        );
         stats
     }
 
@@ -275,6 +667,71 @@ impl MachineRecord for ExecutionRecord {
             .append(&mut other.uint256_mul_events);
         self.bls12381_decompress_events
             .append(&mut other.bls12381_decompress_events);
+        self.field_mul_small_events
+            .append(&mut other.field_mul_small_events);
+        self.bn254_fr_inv_events
+            .append(&mut other.bn254_fr_inv_events);
+        self.aes_encrypt_block_events
+            .append(&mut other.aes_encrypt_block_events);
+        self.crt_combine_events
+            .append(&mut other.crt_combine_events);
+        self.uint256_cmov_events
+            .append(&mut other.uint256_cmov_events);
+        self.poseidon2_compress_events
+            .append(&mut other.poseidon2_compress_events);
+        self.poseidon2_permute_events
+            .append(&mut other.poseidon2_permute_events);
+        self.field_batch_inv_events
+            .append(&mut other.field_batch_inv_events);
+        self.bytes_to_fields_events
+            .append(&mut other.bytes_to_fields_events);
+        self.table_lookup_events
+            .append(&mut other.table_lookup_events);
+        self.uint256_to_decimal_events
+            .append(&mut other.uint256_to_decimal_events);
+        self.secp256k1_normalize_s_events
+            .append(&mut other.secp256k1_normalize_s_events);
+        self.uint256_mod_small_events
+            .append(&mut other.uint256_mod_small_events);
+        self.uint256_wnaf_events
+            .append(&mut other.uint256_wnaf_events);
+        self.secp256k1_compress_events
+            .append(&mut other.secp256k1_compress_events);
+        self.memcpy_events.append(&mut other.memcpy_events);
+        self.hex_encode_events.append(&mut other.hex_encode_events);
+        self.base64_encode_events
+            .append(&mut other.base64_encode_events);
+        self.prepare_hash_input_events
+            .append(&mut other.prepare_hash_input_events);
+        self.field_montgomery_reduce_events
+            .append(&mut other.field_montgomery_reduce_events);
+        self.ntt_butterfly_events
+            .append(&mut other.ntt_butterfly_events);
+        self.scalar_in_range_events
+            .append(&mut other.scalar_in_range_events);
+        self.field_is_zero_events
+            .append(&mut other.field_is_zero_events);
+        self.uint256_shl_events
+            .append(&mut other.uint256_shl_events);
+        self.uint256_shr_events
+            .append(&mut other.uint256_shr_events);
+        self.uint256_gcd_events
+            .append(&mut other.uint256_gcd_events);
+        self.ct_lookup_events.append(&mut other.ct_lookup_events);
+        self.scalar_to_naf_events
+            .append(&mut other.scalar_to_naf_events);
+        self.bn254_fp_inv_events
+            .append(&mut other.bn254_fp_inv_events);
+        self.field_mac_events.append(&mut other.field_mac_events);
+        self.field_affine_events
+            .append(&mut other.field_affine_events);
+        self.chacha20_block_events
+            .append(&mut other.chacha20_block_events);
+        self.ct_memcmp_events.append(&mut other.ct_memcmp_events);
+        self.uint256_sqr_events
+            .append(&mut other.uint256_sqr_events);
+        self.bn254_fp2_mul_events
+            .append(&mut other.bn254_fp2_mul_events);
 
         // Merge the byte lookups.
         for (shard, events_map) in std::mem::take(&mut other.byte_lookups).into_iter() {
@@ -292,6 +749,11 @@ impl MachineRecord for ExecutionRecord {
             }
         }
 
+        // Merge the memory access counts.
+        for (shard, counts) in std::mem::take(&mut other.memory_access_counts) {
+            *self.memory_access_counts.entry(shard).or_default() += counts;
+        }
+
         self.memory_initialize_events
             .append(&mut other.memory_initialize_events);
         self.memory_finalize_events
@@ -325,6 +787,11 @@ impl MachineRecord for ExecutionRecord {
                 shard
                     .byte_lookups
                     .insert(current_shard, current_byte_lookups);
+
+                // So are the memory access counts.
+                if let Some(counts) = self.memory_access_counts.remove(&current_shard) {
+                    shard.memory_access_counts.insert(current_shard, counts);
+                }
                 let last_shard_cpu_event = shard.cpu_events.last().unwrap();
 
                 // Set the public_values_digest for all shards.  For the vast majority of the time, only the last shard
@@ -340,6 +807,7 @@ impl MachineRecord for ExecutionRecord {
                 shard.public_values.start_pc = shard.cpu_events[0].pc;
                 shard.public_values.next_pc = last_shard_cpu_event.next_pc;
                 shard.public_values.exit_code = last_shard_cpu_event.exit_code;
+                shard.public_values.num_cycles = shard.cpu_events.len() as u32;
                 shards.push(shard);
 
                 if !(at_last_event) {
@@ -527,6 +995,17 @@ impl MachineRecord for ExecutionRecord {
             }
         }
 
+        // Uint256 mul arithmetic events.
+        for (uint256_mul_chunk, shard) in take(&mut self.uint256_mul_events)
+            .chunks_mut(config.uint256_mul_len)
+            .zip(shards.iter_mut())
+        {
+            shard.uint256_mul_events.extend_from_slice(uint256_mul_chunk);
+            for (i, event) in uint256_mul_chunk.iter().enumerate() {
+                self.nonce_lookup.insert(event.lookup_id, i as u32);
+            }
+        }
+
         // Put the precompile events in the first shard.
         let first = shards.first_mut().unwrap();
 
@@ -560,18 +1039,244 @@ impl MachineRecord for ExecutionRecord {
             self.nonce_lookup.insert(event.lookup_id, i as u32);
         }
 
-        // Uint256 mul arithmetic events.
-        first.uint256_mul_events = std::mem::take(&mut self.uint256_mul_events);
-        for (i, event) in first.uint256_mul_events.iter().enumerate() {
-            self.nonce_lookup.insert(event.lookup_id, i as u32);
-        }
-
         // Bls12-381 decompress events .
         first.bls12381_decompress_events = std::mem::take(&mut self.bls12381_decompress_events);
         for (i, event) in first.bls12381_decompress_events.iter().enumerate() {
             self.nonce_lookup.insert(event.lookup_id, i as u32);
         }
 
+        // Field mul small events.
+        first.field_mul_small_events = std::mem::take(&mut self.field_mul_small_events);
+        for (i, event) in first.field_mul_small_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Bn254 scalar field inversion events.
+        first.bn254_fr_inv_events = std::mem::take(&mut self.bn254_fr_inv_events);
+        for (i, event) in first.bn254_fr_inv_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // AES encrypt block events.
+        first.aes_encrypt_block_events = std::mem::take(&mut self.aes_encrypt_block_events);
+        for (i, event) in first.aes_encrypt_block_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // CRT combine events.
+        first.crt_combine_events = std::mem::take(&mut self.crt_combine_events);
+        for (i, event) in first.crt_combine_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256 cmov events.
+        first.uint256_cmov_events = std::mem::take(&mut self.uint256_cmov_events);
+        for (i, event) in first.uint256_cmov_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Poseidon2 compress events.
+        first.poseidon2_compress_events = std::mem::take(&mut self.poseidon2_compress_events);
+        for (i, event) in first.poseidon2_compress_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Poseidon2 permute events.
+        first.poseidon2_permute_events = std::mem::take(&mut self.poseidon2_permute_events);
+        for (i, event) in first.poseidon2_permute_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Field batch inv events.
+        // The events have one row per element, so the nonce is the offset of their first row.
+        first.field_batch_inv_events = std::mem::take(&mut self.field_batch_inv_events);
+        let mut num_rows = 0;
+        for event in first.field_batch_inv_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.len;
+        }
+
+        // Bytes to fields events.
+        first.bytes_to_fields_events = std::mem::take(&mut self.bytes_to_fields_events);
+        for (i, event) in first.bytes_to_fields_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Table lookup events.
+        first.table_lookup_events = std::mem::take(&mut self.table_lookup_events);
+        for (i, event) in first.table_lookup_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256 to decimal events.
+        // The events have a fixed number of rows, so the nonce is the offset of their first row.
+        first.uint256_to_decimal_events = std::mem::take(&mut self.uint256_to_decimal_events);
+        for (i, event) in first.uint256_to_decimal_events.iter().enumerate() {
+            self.nonce_lookup
+                .insert(event.lookup_id, (i * UINT256_TO_DECIMAL_ROWS) as u32);
+        }
+
+        // Secp256k1 normalize s events.
+        first.secp256k1_normalize_s_events = std::mem::take(&mut self.secp256k1_normalize_s_events);
+        for (i, event) in first.secp256k1_normalize_s_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256ModSmall events.
+        first.uint256_mod_small_events = std::mem::take(&mut self.uint256_mod_small_events);
+        for (i, event) in first.uint256_mod_small_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256Wnaf events.
+        first.uint256_wnaf_events = std::mem::take(&mut self.uint256_wnaf_events);
+        for (i, event) in first.uint256_wnaf_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Secp256k1 compress events.
+        first.secp256k1_compress_events = std::mem::take(&mut self.secp256k1_compress_events);
+        for (i, event) in first.secp256k1_compress_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Memcpy events.
+        // The events have one row per word, so the nonce is the offset of their first row.
+        first.memcpy_events = std::mem::take(&mut self.memcpy_events);
+        let mut num_rows = 0;
+        for event in first.memcpy_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.len;
+        }
+
+        // Hex encode events.
+        first.hex_encode_events = std::mem::take(&mut self.hex_encode_events);
+        let mut num_rows = 0;
+        for event in first.hex_encode_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.num_rows(HEX_ROW_BYTES) as u32;
+        }
+
+        // Base64 encode events.
+        first.base64_encode_events = std::mem::take(&mut self.base64_encode_events);
+        let mut num_rows = 0;
+        for event in first.base64_encode_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.num_rows(BASE64_ROW_BYTES) as u32;
+        }
+
+        // Prepare hash input events.
+        // The events have one row per output word, so the nonce is the offset of their first row.
+        first.prepare_hash_input_events = std::mem::take(&mut self.prepare_hash_input_events);
+        let mut num_rows = 0;
+        for event in first.prepare_hash_input_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.out_memory_records.len() as u32;
+        }
+
+        // Field montgomery reduce events.
+        first.field_montgomery_reduce_events =
+            std::mem::take(&mut self.field_montgomery_reduce_events);
+        for (i, event) in first.field_montgomery_reduce_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // NTT butterfly events.
+        first.ntt_butterfly_events = std::mem::take(&mut self.ntt_butterfly_events);
+        for (i, event) in first.ntt_butterfly_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Scalar in range events.
+        first.scalar_in_range_events = std::mem::take(&mut self.scalar_in_range_events);
+        for (i, event) in first.scalar_in_range_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Field is zero events.
+        first.field_is_zero_events = std::mem::take(&mut self.field_is_zero_events);
+        for (i, event) in first.field_is_zero_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256 shl events.
+        first.uint256_shl_events = std::mem::take(&mut self.uint256_shl_events);
+        for (i, event) in first.uint256_shl_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256 shr events.
+        first.uint256_shr_events = std::mem::take(&mut self.uint256_shr_events);
+        for (i, event) in first.uint256_shr_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Uint256 gcd events.
+        first.uint256_gcd_events = std::mem::take(&mut self.uint256_gcd_events);
+        for (i, event) in first.uint256_gcd_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Ct lookup events.
+        // The events have one row per entry, so the nonce is the offset of their first row.
+        first.ct_lookup_events = std::mem::take(&mut self.ct_lookup_events);
+        let mut num_rows = 0;
+        for event in first.ct_lookup_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.len;
+        }
+
+        // Scalar to NAF events.
+        first.scalar_to_naf_events = std::mem::take(&mut self.scalar_to_naf_events);
+        for (i, event) in first.scalar_to_naf_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Bn254FpInv events.
+        first.bn254_fp_inv_events = std::mem::take(&mut self.bn254_fp_inv_events);
+        for (i, event) in first.bn254_fp_inv_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // FieldMac events.
+        first.field_mac_events = std::mem::take(&mut self.field_mac_events);
+        for (i, event) in first.field_mac_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // FieldAffine events.
+        first.field_affine_events = std::mem::take(&mut self.field_affine_events);
+        for (i, event) in first.field_affine_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // ChaCha20Block events.
+        first.chacha20_block_events = std::mem::take(&mut self.chacha20_block_events);
+        for (i, event) in first.chacha20_block_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // CtMemcmp events.
+        // The events have one row per word, so the nonce is the offset of their first row.
+        first.ct_memcmp_events = std::mem::take(&mut self.ct_memcmp_events);
+        let mut num_rows = 0;
+        for event in first.ct_memcmp_events.iter() {
+            self.nonce_lookup.insert(event.lookup_id, num_rows);
+            num_rows += event.num_words() as u32;
+        }
+
+        // Uint256Sqr events.
+        first.uint256_sqr_events = std::mem::take(&mut self.uint256_sqr_events);
+        for (i, event) in first.uint256_sqr_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
+        // Bn254Fp2Mul events.
+        first.bn254_fp2_mul_events = std::mem::take(&mut self.bn254_fp2_mul_events);
+        for (i, event) in first.bn254_fp2_mul_events.iter().enumerate() {
+            self.nonce_lookup.insert(event.lookup_id, i as u32);
+        }
+
         first
             .memory_initialize_events
             .extend_from_slice(&self.memory_initialize_events);
@@ -603,6 +1308,741 @@ impl ExecutionRecord {
         }
     }
 
+    /// The number of events of each chip with one row per event.
+    fn single_row_event_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("AddSub", self.add_events.len() + self.sub_events.len()),
+            ("Mul", self.mul_events.len()),
+            ("Bitwise", self.bitwise_events.len()),
+            ("ShiftLeft", self.shift_left_events.len()),
+            ("ShiftRight", self.shift_right_events.len()),
+            ("DivRem", self.divrem_events.len()),
+            ("Lt", self.lt_events.len()),
+            ("MemoryInit", self.memory_initialize_events.len()),
+            ("MemoryFinalize", self.memory_finalize_events.len()),
+            ("EdAddAssign", self.ed_add_events.len()),
+            ("EdDecompress", self.ed_decompress_events.len()),
+            ("Secp256k1AddAssign", self.secp256k1_add_events.len()),
+            ("Secp256k1DoubleAssign", self.secp256k1_double_events.len()),
+            ("Secp256k1Decompress", self.k256_decompress_events.len()),
+            ("Bn254AddAssign", self.bn254_add_events.len()),
+            ("Bn254DoubleAssign", self.bn254_double_events.len()),
+            ("Bls12381AddAssign", self.bls12381_add_events.len()),
+            ("Bls12381DoubleAssign", self.bls12381_double_events.len()),
+            ("Bls12381Decompress", self.bls12381_decompress_events.len()),
+            ("Uint256MulMod", self.uint256_mul_events.len()),
+            ("FieldMulSmall", self.field_mul_small_events.len()),
+            ("Bn254FrInv", self.bn254_fr_inv_events.len()),
+            ("AesEncryptBlock", self.aes_encrypt_block_events.len()),
+            ("CrtCombine", self.crt_combine_events.len()),
+            ("Uint256Cmov", self.uint256_cmov_events.len()),
+            ("Poseidon2Compress", self.poseidon2_compress_events.len()),
+            ("Poseidon2Permute", self.poseidon2_permute_events.len()),
+            ("BytesToFields", self.bytes_to_fields_events.len()),
+            ("TableLookup", self.table_lookup_events.len()),
+            ("Secp256k1NormalizeS", self.secp256k1_normalize_s_events.len()),
+            ("Uint256ModSmall", self.uint256_mod_small_events.len()),
+            ("Uint256Wnaf", self.uint256_wnaf_events.len()),
+            ("Secp256k1Compress", self.secp256k1_compress_events.len()),
+            ("FieldMontgomeryReduce", self.field_montgomery_reduce_events.len()),
+            ("NttButterfly", self.ntt_butterfly_events.len()),
+            ("ScalarInRange", self.scalar_in_range_events.len()),
+            ("FieldIsZero", self.field_is_zero_events.len()),
+            ("Uint256Shl", self.uint256_shl_events.len()),
+            ("Uint256Shr", self.uint256_shr_events.len()),
+            ("Uint256Gcd", self.uint256_gcd_events.len()),
+            ("ScalarToNaf", self.scalar_to_naf_events.len()),
+            ("Bn254FpInv", self.bn254_fp_inv_events.len()),
+            ("FieldMac", self.field_mac_events.len()),
+            ("FieldAffine", self.field_affine_events.len()),
+            ("ChaCha20Block", self.chacha20_block_events.len()),
+            ("Uint256Sqr", self.uint256_sqr_events.len()),
+            ("Bn254Fp2Mul", self.bn254_fp2_mul_events.len()),
+        ]
+    }
+
+    /// Returns the number of events of each chip with events in this record, keyed by chip name.
+    ///
+    /// Some chips prove an event with several rows, e.g. a `KeccakPermute` event with 24 rows, so
+    /// these are not the rows of [`ExecutionRecord::estimate_prove_cost`].
+    pub fn event_counts(&self) -> BTreeMap<String, usize> {
+        // The CPU, and the chips which prove an event with several rows.
+        let other_event_counts = [
+            ("CPU", self.cpu_events.len()),
+            ("ShaExtend", self.sha_extend_events.len()),
+            ("ShaCompress", self.sha_compress_events.len()),
+            ("KeccakPermute", self.keccak_permute_events.len()),
+            ("FieldBatchInv", self.field_batch_inv_events.len()),
+            ("Memcpy", self.memcpy_events.len()),
+            ("HexEncode", self.hex_encode_events.len()),
+            ("Base64Encode", self.base64_encode_events.len()),
+            ("PrepareHashInput", self.prepare_hash_input_events.len()),
+            ("Uint256ToDecimal", self.uint256_to_decimal_events.len()),
+            ("CtLookup", self.ct_lookup_events.len()),
+            ("CtMemcmp", self.ct_memcmp_events.len()),
+        ];
+        self.single_row_event_counts()
+            .into_iter()
+            .chain(other_event_counts)
+            .filter(|&(_, num_events)| num_events > 0)
+            .map(|(name, num_events)| (name.to_string(), num_events))
+            .collect()
+    }
+
+    /// Returns the number of events of the chip named `chip_name`.
+    pub fn event_count(&self, chip_name: &str) -> usize {
+        self.event_counts().get(chip_name).copied().unwrap_or(0)
+    }
+
+    /// Estimates the cost of proving this record from its event counts, without generating traces.
+    ///
+    /// The rows of each chip follow the padding of its trace generation, so they match the heights
+    /// of the generated traces once the record is sharded.
+    pub fn estimate_prove_cost(&self) -> ProveEstimate {
+        fn padded(rows: usize, min_rows: usize) -> usize {
+            rows.next_power_of_two().max(min_rows)
+        }
+
+        let mut chip_rows = BTreeMap::new();
+        let mut chip_real_rows = BTreeMap::new();
+        let mut insert = |name: &str, num_events: usize, rows_per_event: usize, min_rows: usize| {
+            if num_events > 0 {
+                let rows = num_events * rows_per_event;
+                chip_rows.insert(name.to_string(), padded(rows, min_rows));
+                chip_real_rows.insert(name.to_string(), rows);
+            }
+        };
+
+        // Chips that are always included, whether or not they have events.
+        insert("CPU", self.cpu_events.len().max(1), 1, 16);
+        insert("Program", self.program.instructions.len().max(1), 1, 16);
+        insert("MemoryProgram", self.program.memory_image.len().max(1), 1, 16);
+        insert("Byte", NUM_BYTE_ROWS, 1, NUM_BYTE_ROWS);
+
+        // Chips with one row per event.
+        for (name, num_events) in self.single_row_event_counts() {
+            insert(name, num_events, 1, 16);
+        }
+
+        // Chips with several rows per event.
+        insert("ShaExtend", self.sha_extend_events.len(), 48, 4);
+        insert("ShaCompress", self.sha_compress_events.len(), 80, 16);
+        insert("KeccakPermute", self.keccak_permute_events.len(), 24, 4);
+        let field_batch_inv_rows = self
+            .field_batch_inv_events
+            .iter()
+            .map(|event| event.len as usize)
+            .sum();
+        insert("FieldBatchInv", field_batch_inv_rows, 1, 16);
+        let memcpy_rows = self
+            .memcpy_events
+            .iter()
+            .map(|event| event.len as usize)
+            .sum();
+        insert("Memcpy", memcpy_rows, 1, 16);
+        let hex_encode_rows = self
+            .hex_encode_events
+            .iter()
+            .map(|event| event.num_rows(HEX_ROW_BYTES))
+            .sum();
+        insert("HexEncode", hex_encode_rows, 1, 16);
+        let base64_encode_rows = self
+            .base64_encode_events
+            .iter()
+            .map(|event| event.num_rows(BASE64_ROW_BYTES))
+            .sum();
+        insert("Base64Encode", base64_encode_rows, 1, 16);
+        let prepare_hash_input_rows = self
+            .prepare_hash_input_events
+            .iter()
+            .map(|event| event.out_memory_records.len())
+            .sum();
+        insert("PrepareHashInput", prepare_hash_input_rows, 1, 16);
+        insert(
+            "Uint256ToDecimal",
+            self.uint256_to_decimal_events.len(),
+            UINT256_TO_DECIMAL_ROWS,
+            16,
+        );
+        let ct_lookup_rows = self
+            .ct_lookup_events
+            .iter()
+            .map(|event| event.len as usize)
+            .sum();
+        insert("CtLookup", ct_lookup_rows, 1, 16);
+        let ct_memcmp_rows = self
+            .ct_memcmp_events
+            .iter()
+            .map(|event| event.num_words())
+            .sum();
+        insert("CtMemcmp", ct_memcmp_rows, 1, 16);
+
+        // The lookup tables have one row per entry, and are included with the lookups into them.
+        if !self.table_lookup_events.is_empty() {
+            let num_entries = self.program.lookup_tables.values().map(|t| t.len()).sum();
+            insert("LookupTable", num_entries, 1, 16);
+        }
+
+        let widths = RiscvAir::<BabyBear>::get_all()
+            .into_iter()
+            .map(|chip| (chip.name(), chip.width()))
+            .collect::<HashMap<_, _>>();
+        let mut estimate = ProveEstimate::default();
+        for (name, &rows) in chip_rows.iter() {
+            let cells = rows * widths[name];
+            estimate.total_rows += rows;
+            estimate.total_cells += cells;
+            estimate.fri_cost += cells * rows.ilog2() as usize;
+        }
+        estimate.chip_rows = chip_rows;
+        estimate.chip_real_rows = chip_real_rows;
+        estimate
+    }
+
+    /// Checks the invariants of the events that trace generation relies on: that the CPU and
+    /// precompile events of each kind are ordered by shard and clk, that the pointers of the
+    /// precompile events are word aligned, and that their values and memory records have the
+    /// lengths their chips expect.
+    ///
+    /// This is a cheap pass meant to run before the traces are generated, which would otherwise
+    /// fail on a malformed event with a less descriptive panic. The prover runs it on each record
+    /// before sharding it, and returns its error as [`SP1CoreProverError::InvalidRecord`].
+    ///
+    /// [`SP1CoreProverError::InvalidRecord`]: crate::utils::SP1CoreProverError::InvalidRecord
+    pub fn validate(&self) -> Result<(), RecordValidationError> {
+        fn check_ec_add(
+            c: &mut EventChecker,
+            e: &ECAddEvent,
+            num_words: usize,
+        ) -> Result<(), RecordValidationError> {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("p_ptr", e.p_ptr)?;
+            c.ptr("q_ptr", e.q_ptr)?;
+            c.len("p", e.p.len(), num_words)?;
+            c.len("q", e.q.len(), num_words)?;
+            c.len("p_memory_records", e.p_memory_records.len(), num_words)?;
+            c.len("q_memory_records", e.q_memory_records.len(), num_words)
+        }
+
+        fn check_ec_double(
+            c: &mut EventChecker,
+            e: &ECDoubleEvent,
+            num_words: usize,
+        ) -> Result<(), RecordValidationError> {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("p_ptr", e.p_ptr)?;
+            c.len("p", e.p.len(), num_words)?;
+            c.len("p_memory_records", e.p_memory_records.len(), num_words)
+        }
+
+        fn check_ec_decompress(
+            c: &mut EventChecker,
+            e: &ECDecompressEvent,
+            num_limbs: usize,
+        ) -> Result<(), RecordValidationError> {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("ptr", e.ptr)?;
+            c.len("x_bytes", e.x_bytes.len(), num_limbs)?;
+            c.len("decompressed_y_bytes", e.decompressed_y_bytes.len(), num_limbs)?;
+            c.len("x_memory_records", e.x_memory_records.len(), num_limbs / WORD_SIZE)?;
+            c.len("y_memory_records", e.y_memory_records.len(), num_limbs / WORD_SIZE)
+        }
+
+        fn check_encode(
+            c: &mut EventChecker,
+            e: &EncodeEvent,
+            row_bytes: usize,
+            out_words: usize,
+        ) -> Result<(), RecordValidationError> {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("out_ptr", e.out_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("data_ptr", e.data_ptr)?;
+            let header_len = e.header_memory_records.len();
+            c.len("header_memory_records", header_len, ENCODE_HEADER_WORDS)?;
+            let data_words = e.num_rows(row_bytes) * row_bytes / WORD_SIZE;
+            c.len("data_memory_records", e.data_memory_records.len(), data_words)?;
+            c.len("out_memory_records", e.out_memory_records.len(), out_words)
+        }
+
+        const N: usize = NUM_WORDS_IN_UINT256;
+
+        EventChecker::check_all("cpu_events", &self.cpu_events, |c, e| c.clk(e.shard, e.clk))?;
+
+        EventChecker::check_all("sha_extend_events", &self.sha_extend_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("w_ptr", e.w_ptr)?;
+            c.len("w_i_minus_15_reads", e.w_i_minus_15_reads.len(), 48)?;
+            c.len("w_i_minus_2_reads", e.w_i_minus_2_reads.len(), 48)?;
+            c.len("w_i_minus_16_reads", e.w_i_minus_16_reads.len(), 48)?;
+            c.len("w_i_minus_7_reads", e.w_i_minus_7_reads.len(), 48)?;
+            c.len("w_i_writes", e.w_i_writes.len(), 48)
+        })?;
+        EventChecker::check_all("sha_compress_events", &self.sha_compress_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("w_ptr", e.w_ptr)?;
+            c.ptr("h_ptr", e.h_ptr)?;
+            c.len("w", e.w.len(), 64)?;
+            c.len("w_i_read_records", e.w_i_read_records.len(), 64)
+        })?;
+        EventChecker::check_all("keccak_permute_events", &self.keccak_permute_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("state_addr", e.state_addr)?;
+            c.len("state_read_records", e.state_read_records.len(), 2 * STATE_SIZE)?;
+            c.len("state_write_records", e.state_write_records.len(), 2 * STATE_SIZE)
+        })?;
+
+        let ed25519_words = <Ed25519BaseField as NumWords>::WordsCurvePoint::USIZE;
+        let secp256k1_words = <Secp256k1BaseField as NumWords>::WordsCurvePoint::USIZE;
+        let bn254_words = <Bn254BaseField as NumWords>::WordsCurvePoint::USIZE;
+        let bls12381_words = <Bls12381BaseField as NumWords>::WordsCurvePoint::USIZE;
+        EventChecker::check_all("ed_add_events", &self.ed_add_events, |c, e| {
+            check_ec_add(c, e, ed25519_words)
+        })?;
+        EventChecker::check_all("ed_decompress_events", &self.ed_decompress_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("ptr", e.ptr)
+        })?;
+        EventChecker::check_all("secp256k1_add_events", &self.secp256k1_add_events, |c, e| {
+            check_ec_add(c, e, secp256k1_words)
+        })?;
+        EventChecker::check_all(
+            "secp256k1_double_events",
+            &self.secp256k1_double_events,
+            |c, e| check_ec_double(c, e, secp256k1_words),
+        )?;
+        EventChecker::check_all("bn254_add_events", &self.bn254_add_events, |c, e| {
+            check_ec_add(c, e, bn254_words)
+        })?;
+        EventChecker::check_all("bn254_double_events", &self.bn254_double_events, |c, e| {
+            check_ec_double(c, e, bn254_words)
+        })?;
+        EventChecker::check_all("bls12381_add_events", &self.bls12381_add_events, |c, e| {
+            check_ec_add(c, e, bls12381_words)
+        })?;
+        EventChecker::check_all(
+            "bls12381_double_events",
+            &self.bls12381_double_events,
+            |c, e| check_ec_double(c, e, bls12381_words),
+        )?;
+        EventChecker::check_all(
+            "k256_decompress_events",
+            &self.k256_decompress_events,
+            |c, e| check_ec_decompress(c, e, <Secp256k1BaseField as NumLimbs>::Limbs::USIZE),
+        )?;
+        EventChecker::check_all(
+            "bls12381_decompress_events",
+            &self.bls12381_decompress_events,
+            |c, e| check_ec_decompress(c, e, <Bls12381BaseField as NumLimbs>::Limbs::USIZE),
+        )?;
+
+        EventChecker::check_all("uint256_mul_events", &self.uint256_mul_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("y_ptr", e.y_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("y", e.y.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)?;
+            c.len("y_memory_records", e.y_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("field_mul_small_events", &self.field_mul_small_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("io_ptr", e.io_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("bn254_fr_inv_events", &self.bn254_fr_inv_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)
+        })?;
+        EventChecker::check_all(
+            "aes_encrypt_block_events",
+            &self.aes_encrypt_block_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("state_ptr", e.state_ptr)?;
+                c.ptr("round_keys_ptr", e.round_keys_ptr)?;
+                let round_keys_words = (e.num_rounds as usize + 1) * AES_BLOCK_WORDS;
+                c.len("state", e.state.len(), AES_BLOCK_WORDS)?;
+                c.len("round_keys", e.round_keys.len(), round_keys_words)?;
+                let state_len = e.state_memory_records.len();
+                c.len("state_memory_records", state_len, AES_BLOCK_WORDS)?;
+                let round_keys_len = e.round_keys_memory_records.len();
+                c.len("round_keys_memory_records", round_keys_len, round_keys_words)
+            },
+        )?;
+        EventChecker::check_all("crt_combine_events", &self.crt_combine_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("out_ptr", e.out_ptr)?;
+            c.ptr("input_ptr", e.input_ptr)?;
+            c.len("input", e.input.len(), CRT_COMBINE_INPUT_WORDS)?;
+            c.len("x", e.x.len(), N)?;
+            let input_len = e.input_memory_records.len();
+            c.len("input_memory_records", input_len, CRT_COMBINE_INPUT_WORDS)?;
+            c.len("out_memory_records", e.out_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("uint256_cmov_events", &self.uint256_cmov_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("dst_ptr", e.dst_ptr)?;
+            c.len("dst", e.dst.len(), N)?;
+            c.len("src", e.src.len(), N)?;
+            c.len("dst_memory_records", e.dst_memory_records.len(), N)?;
+            c.len("src_memory_records", e.src_memory_records.len(), N)
+        })?;
+        EventChecker::check_all(
+            "poseidon2_compress_events",
+            &self.poseidon2_compress_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("input_ptr", e.input_ptr)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                c.len("input", e.input.len(), POSEIDON2_WIDTH)?;
+                c.len("output", e.output.len(), DIGEST_SIZE)?;
+                let input_len = e.input_memory_records.len();
+                c.len("input_memory_records", input_len, POSEIDON2_WIDTH)?;
+                let output_len = e.output_memory_records.len();
+                c.len("output_memory_records", output_len, DIGEST_SIZE)
+            },
+        )?;
+        EventChecker::check_all(
+            "poseidon2_permute_events",
+            &self.poseidon2_permute_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("state_ptr", e.state_ptr)?;
+                c.len("input", e.input.len(), POSEIDON2_WIDTH)?;
+                c.len("output", e.output.len(), POSEIDON2_WIDTH)?;
+                let state_len = e.state_memory_records.len();
+                c.len("state_memory_records", state_len, POSEIDON2_WIDTH)
+            },
+        )?;
+        EventChecker::check_all("field_batch_inv_events", &self.field_batch_inv_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("ptr", e.ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            // The header holds the modulus followed by the number of elements.
+            let x_words = e.len as usize * N;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("x", e.x.len(), x_words)?;
+            c.len("prefix_products", e.prefix_products.len(), x_words)?;
+            c.len("inverse", e.inverse.len(), N)?;
+            c.len("header_memory_records", e.header_memory_records.len(), N + 1)?;
+            c.len("x_memory_records", e.x_memory_records.len(), x_words)
+        })?;
+        EventChecker::check_all("bytes_to_fields_events", &self.bytes_to_fields_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("input_ptr", e.input_ptr)?;
+            c.ptr("out_ptr", e.out_ptr)?;
+            let input_words = NUM_PACKED_BYTES / WORD_SIZE;
+            c.len("input", e.input.len(), input_words)?;
+            c.len("output", e.output.len(), NUM_PACKED_ELEMENTS)?;
+            c.len("input_memory_records", e.input_memory_records.len(), input_words)?;
+            let output_len = e.output_memory_records.len();
+            c.len("output_memory_records", output_len, NUM_PACKED_ELEMENTS)
+        })?;
+        EventChecker::check_all("table_lookup_events", &self.table_lookup_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("ptr", e.ptr)
+        })?;
+        EventChecker::check_all(
+            "uint256_to_decimal_events",
+            &self.uint256_to_decimal_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("x_ptr", e.x_ptr)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                let out_words = NUM_DECIMAL_DIGITS / WORD_SIZE;
+                c.len("x", e.x.len(), N)?;
+                c.len("out", e.out.len(), out_words)?;
+                c.len("x_memory_records", e.x_memory_records.len(), N)?;
+                c.len("out_memory_records", e.out_memory_records.len(), out_words)
+            },
+        )?;
+        EventChecker::check_all(
+            "secp256k1_normalize_s_events",
+            &self.secp256k1_normalize_s_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("s_ptr", e.s_ptr)?;
+                c.len("s", e.s.len(), N)?;
+                c.len("s_memory_records", e.s_memory_records.len(), N)
+            },
+        )?;
+        EventChecker::check_all(
+            "uint256_mod_small_events",
+            &self.uint256_mod_small_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("x_ptr", e.x_ptr)?;
+                c.ptr("m_ptr", e.m_ptr)?;
+                c.len("x", e.x.len(), N)?;
+                c.len("x_memory_records", e.x_memory_records.len(), N)
+            },
+        )?;
+        EventChecker::check_all("uint256_wnaf_events", &self.uint256_wnaf_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("scalar_ptr", e.scalar_ptr)?;
+            c.ptr("digits_ptr", e.digits_ptr)?;
+            c.len("scalar", e.scalar.len(), N)?;
+            c.len("scalar_memory_records", e.scalar_memory_records.len(), N)?;
+            let digits_len = e.digits_memory_records.len();
+            c.len("digits_memory_records", digits_len, NUM_WNAF_WORDS)
+        })?;
+        EventChecker::check_all(
+            "secp256k1_compress_events",
+            &self.secp256k1_compress_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("point_ptr", e.point_ptr)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                c.len("point", e.point.len(), 2 * N)?;
+                c.len("point_memory_records", e.point_memory_records.len(), 2 * N)?;
+                let out_len = e.out_memory_records.len();
+                c.len("out_memory_records", out_len, NUM_COMPRESSED_WORDS)
+            },
+        )?;
+        EventChecker::check_all("memcpy_events", &self.memcpy_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("dst_ptr", e.dst_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("src_ptr", e.src_ptr)?;
+            // The header holds the source pointer followed by the number of words.
+            c.len("header_memory_records", e.header_memory_records.len(), 2)?;
+            let len = e.len as usize;
+            c.len("src_memory_records", e.src_memory_records.len(), len)?;
+            c.len("dst_memory_records", e.dst_memory_records.len(), len)
+        })?;
+        EventChecker::check_all("ct_lookup_events", &self.ct_lookup_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("out_ptr", e.out_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("table_ptr", e.table_ptr)?;
+            let header_len = e.header_memory_records.len();
+            c.len("header_memory_records", header_len, CT_LOOKUP_HEADER_WORDS)?;
+            c.len("table_memory_records", e.table_memory_records.len(), e.len as usize)
+        })?;
+        EventChecker::check_all("ct_memcmp_events", &self.ct_memcmp_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("a_ptr", e.a_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("b_ptr", e.b_ptr)?;
+            let header_len = e.header_memory_records.len();
+            c.len("header_memory_records", header_len, CT_MEMCMP_HEADER_WORDS)?;
+            c.len("a_memory_records", e.a_memory_records.len(), e.num_words())?;
+            c.len("b_memory_records", e.b_memory_records.len(), e.num_words())
+        })?;
+        EventChecker::check_all("uint256_sqr_events", &self.uint256_sqr_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("bn254_fp2_mul_events", &self.bn254_fp2_mul_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("y_ptr", e.y_ptr)?;
+            c.len("x", e.x.len(), 2 * N)?;
+            c.len("y", e.y.len(), 2 * N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), 2 * N)?;
+            c.len("y_memory_records", e.y_memory_records.len(), 2 * N)
+        })?;
+        EventChecker::check_all("scalar_to_naf_events", &self.scalar_to_naf_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("scalar_ptr", e.scalar_ptr)?;
+            c.ptr("naf_ptr", e.naf_ptr)?;
+            c.len("scalar", e.scalar.len(), N)?;
+            c.len("scalar_memory_records", e.scalar_memory_records.len(), N)?;
+            c.len("naf_memory_records", e.naf_memory_records.len(), NUM_NAF_WORDS)
+        })?;
+        EventChecker::check_all("bn254_fp_inv_events", &self.bn254_fp_inv_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("field_mac_events", &self.field_mac_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("acc_ptr", e.acc_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("a_ptr", e.a_ptr)?;
+            c.ptr("b_ptr", e.b_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("acc", e.acc.len(), N)?;
+            c.len("a", e.a.len(), N)?;
+            c.len("b", e.b.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("header_memory_records", e.header_memory_records.len(), FIELD_MAC_HEADER_WORDS)?;
+            c.len("acc_memory_records", e.acc_memory_records.len(), N)?;
+            c.len("a_memory_records", e.a_memory_records.len(), N)?;
+            c.len("b_memory_records", e.b_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("field_affine_events", &self.field_affine_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("a_ptr", e.a_ptr)?;
+            c.ptr("b_ptr", e.b_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("a", e.a.len(), N)?;
+            c.len("b", e.b.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len(
+                "header_memory_records",
+                e.header_memory_records.len(),
+                FIELD_AFFINE_HEADER_WORDS,
+            )?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)?;
+            c.len("a_memory_records", e.a_memory_records.len(), N)?;
+            c.len("b_memory_records", e.b_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all(
+            "chacha20_block_events",
+            &self.chacha20_block_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                c.ptr("header_ptr", e.header_ptr)?;
+                c.ptr("key_ptr", e.key_ptr)?;
+                c.ptr("iv_ptr", e.iv_ptr)?;
+                c.len("key", e.key.len(), chacha20::KEY_SIZE)?;
+                c.len("iv", e.iv.len(), chacha20::NONCE_SIZE)?;
+                c.len("out", e.out.len(), chacha20::STATE_SIZE)?;
+                let header_len = e.header_memory_records.len();
+                c.len("header_memory_records", header_len, CHACHA20_BLOCK_HEADER_WORDS)?;
+                let key_len = e.key_memory_records.len();
+                c.len("key_memory_records", key_len, chacha20::KEY_SIZE)?;
+                let iv_len = e.iv_memory_records.len();
+                c.len("iv_memory_records", iv_len, chacha20::NONCE_SIZE)?;
+                let out_len = e.out_memory_records.len();
+                c.len("out_memory_records", out_len, chacha20::STATE_SIZE)
+            },
+        )?;
+        EventChecker::check_all("hex_encode_events", &self.hex_encode_events, |c, e| {
+            // Each byte of data is encoded as two digits.
+            let out_words = (2 * e.len as usize).div_ceil(WORD_SIZE);
+            check_encode(c, e, HEX_ROW_BYTES, out_words)
+        })?;
+        EventChecker::check_all("base64_encode_events", &self.base64_encode_events, |c, e| {
+            // Each group of three bytes of data is encoded as a word of four characters.
+            let out_words = (e.len as usize).div_ceil(3);
+            check_encode(c, e, BASE64_ROW_BYTES, out_words)
+        })?;
+        EventChecker::check_all(
+            "prepare_hash_input_events",
+            &self.prepare_hash_input_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                c.ptr("header_ptr", e.header_ptr)?;
+                c.ptr("input_ptr", e.input_ptr)?;
+                // The header holds the input pointer, the length and the mode.
+                c.len("header_memory_records", e.header_memory_records.len(), 3)?;
+                let input_words = (e.len as usize).div_ceil(WORD_SIZE);
+                c.len("input_memory_records", e.input_memory_records.len(), input_words)?;
+                let mut out_bytes = e.len as usize;
+                if e.mode & HASH_INPUT_LENGTH_PREFIX != 0 {
+                    out_bytes += WORD_SIZE;
+                }
+                out_bytes = out_bytes.next_multiple_of(WORD_SIZE);
+                let block_len = (e.mode >> 8) as usize;
+                if e.mode & HASH_INPUT_PAD != 0 && block_len > 0 {
+                    out_bytes = out_bytes.next_multiple_of(block_len);
+                }
+                let out_len = e.out_memory_records.len();
+                c.len("out_memory_records", out_len, out_bytes / WORD_SIZE)
+            },
+        )?;
+        EventChecker::check_all(
+            "field_montgomery_reduce_events",
+            &self.field_montgomery_reduce_events,
+            |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("out_ptr", e.out_ptr)?;
+                c.ptr("header_ptr", e.header_ptr)?;
+                c.ptr("t_ptr", e.t_ptr)?;
+                c.ptr("modulus_ptr", e.modulus_ptr)?;
+                c.len("t", e.t.len(), 2 * N)?;
+                c.len("modulus", e.modulus.len(), N)?;
+                c.len("result", e.result.len(), N)?;
+                // The header holds the pointers of t and the modulus, and n'.
+                c.len("header_memory_records", e.header_memory_records.len(), 3)?;
+                c.len("t_memory_records", e.t_memory_records.len(), 2 * N)?;
+                c.len("modulus_memory_records", e.modulus_memory_records.len(), N)?;
+                c.len("out_memory_records", e.out_memory_records.len(), N)
+            },
+        )?;
+        EventChecker::check_all("ntt_butterfly_events", &self.ntt_butterfly_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("a_ptr", e.a_ptr)?;
+            c.ptr("header_ptr", e.header_ptr)?;
+            c.ptr("b_ptr", e.b_ptr)?;
+            c.ptr("twiddle_ptr", e.twiddle_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("a", e.a.len(), N)?;
+            c.len("b", e.b.len(), N)?;
+            c.len("twiddle", e.twiddle.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            // The header holds the pointers of b, the twiddle factor and the modulus.
+            c.len("header_memory_records", e.header_memory_records.len(), 3)?;
+            c.len("twiddle_memory_records", e.twiddle_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)?;
+            c.len("a_memory_records", e.a_memory_records.len(), N)?;
+            c.len("b_memory_records", e.b_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("scalar_in_range_events", &self.scalar_in_range_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("io_ptr", e.io_ptr)?;
+            c.ptr("modulus_ptr", e.modulus_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("modulus", e.modulus.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)?;
+            c.len("modulus_memory_records", e.modulus_memory_records.len(), N)
+        })?;
+        EventChecker::check_all("field_is_zero_events", &self.field_is_zero_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("x_ptr", e.x_ptr)?;
+            c.ptr("result_ptr", e.result_ptr)?;
+            c.len("x", e.x.len(), N)?;
+            c.len("x_memory_records", e.x_memory_records.len(), N)
+        })?;
+        for (name, events) in [
+            ("uint256_shl_events", &self.uint256_shl_events),
+            ("uint256_shr_events", &self.uint256_shr_events),
+        ] {
+            EventChecker::check_all(name, events, |c, e| {
+                c.clk(e.shard, e.clk)?;
+                c.ptr("x_ptr", e.x_ptr)?;
+                c.len("x", e.x.len(), N)?;
+                c.len("x_memory_records", e.x_memory_records.len(), N)
+            })?;
+        }
+        EventChecker::check_all("uint256_gcd_events", &self.uint256_gcd_events, |c, e| {
+            c.clk(e.shard, e.clk)?;
+            c.ptr("input_ptr", e.input_ptr)?;
+            c.ptr("out_ptr", e.out_ptr)?;
+            // The input holds x and y, and the output holds the gcd and the Bezout coefficients.
+            c.len("input", e.input.len(), 2 * N)?;
+            c.len("output", e.output.len(), 3 * N)?;
+            c.len("input_memory_records", e.input_memory_records.len(), 2 * N)?;
+            c.len("output_memory_records", e.output_memory_records.len(), 3 * N)
+        })
+    }
+
     pub fn add_mul_event(&mut self, mul_event: AluEvent) {
         self.mul_events.push(mul_event);
     }
@@ -646,6 +2086,84 @@ impl ExecutionRecord {
     }
 }
 
+/// Walks the events of one kind of an [ExecutionRecord] for [ExecutionRecord::validate], keeping
+/// track of the event being checked to describe the errors.
+struct EventChecker {
+    events: &'static str,
+    index: usize,
+    prev: Option<(u32, u32)>,
+}
+
+impl EventChecker {
+    /// Checks the events in order with `check`, which is given the checker of each event.
+    fn check_all<E>(
+        name: &'static str,
+        events: &[E],
+        mut check: impl FnMut(&mut Self, &E) -> Result<(), RecordValidationError>,
+    ) -> Result<(), RecordValidationError> {
+        let mut checker = Self {
+            events: name,
+            index: 0,
+            prev: None,
+        };
+        for (index, event) in events.iter().enumerate() {
+            checker.index = index;
+            check(&mut checker, event)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the event is not before the previous one.
+    fn clk(&mut self, shard: u32, clk: u32) -> Result<(), RecordValidationError> {
+        if let Some((prev_shard, prev_clk)) = self.prev {
+            if (shard, clk) < (prev_shard, prev_clk) {
+                return Err(RecordValidationError::ClkOutOfOrder {
+                    events: self.events,
+                    index: self.index,
+                    shard,
+                    clk,
+                    prev_shard,
+                    prev_clk,
+                });
+            }
+        }
+        self.prev = Some((shard, clk));
+        Ok(())
+    }
+
+    /// Checks that the pointer `field` is word aligned.
+    fn ptr(&self, field: &'static str, ptr: u32) -> Result<(), RecordValidationError> {
+        if ptr % WORD_SIZE as u32 != 0 {
+            return Err(RecordValidationError::UnalignedPointer {
+                events: self.events,
+                index: self.index,
+                field,
+                ptr,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that `field` has `expected` entries.
+    fn len(
+        &self,
+        field: &'static str,
+        len: usize,
+        expected: usize,
+    ) -> Result<(), RecordValidationError> {
+        if len != expected {
+            return Err(RecordValidationError::LengthMismatch {
+                events: self.events,
+                index: self.index,
+                field,
+                len,
+                expected,
+            });
+        }
+        Ok(())
+    }
+}
+
 impl ByteRecord for ExecutionRecord {
     fn add_byte_lookup_event(&mut self, blu_event: ByteLookupEvent) {
         *self
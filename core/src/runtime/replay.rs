@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// A single memory write in a [`ReplayLog`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    /// The shard in which the write happened.
+    pub shard: u32,
+    /// The timestamp of the write within the shard.
+    pub clk: u32,
+    /// The address written to.
+    pub addr: u32,
+    /// The value written.
+    pub value: u32,
+}
+
+/// A journal of all memory writes of an execution, in the order they happened.
+///
+/// Together with the initial memory image of the program, the journal is enough to reconstruct
+/// the memory of the guest at any point of the execution with [`MemorySnapshot::at`]. Writes made
+/// inside unconstrained blocks are not recorded since they are reverted when the block exits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayLog {
+    /// The memory image of the program before execution.
+    pub initial: BTreeMap<u32, u32>,
+    /// The memory writes of the execution.
+    pub entries: Vec<ReplayEntry>,
+}
+
+impl ReplayLog {
+    /// Create an empty log starting from the given memory image.
+    pub const fn new(initial: BTreeMap<u32, u32>) -> Self {
+        Self {
+            initial,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record a write of `value` to `addr` at the given shard and timestamp.
+    #[inline]
+    pub fn record(&mut self, shard: u32, clk: u32, addr: u32, value: u32) {
+        self.entries.push(ReplayEntry {
+            shard,
+            clk,
+            addr,
+            value,
+        });
+    }
+}
+
+/// The memory of the guest at a point of the execution, reconstructed from a [`ReplayLog`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    memory: HashMap<u32, u32>,
+}
+
+impl MemorySnapshot {
+    /// Reconstruct the memory right after all writes at or before `clk` in `shard` happened.
+    ///
+    /// Timestamps are ordered first by shard and then by clk, since clk restarts at every shard.
+    pub fn at(log: &ReplayLog, shard: u32, clk: u32) -> Self {
+        let mut memory: HashMap<u32, u32> =
+            log.initial.iter().map(|(addr, value)| (*addr, *value)).collect();
+        for entry in log
+            .entries
+            .iter()
+            .filter(|entry| (entry.shard, entry.clk) <= (shard, clk))
+        {
+            memory.insert(entry.addr, entry.value);
+        }
+        Self { memory }
+    }
+
+    /// The word at `addr`, or zero if it was never written.
+    pub fn word(&self, addr: u32) -> u32 {
+        self.memory.get(&addr).copied().unwrap_or(0)
+    }
+
+    /// The `len` consecutive words starting at `addr`.
+    pub fn words(&self, addr: u32, len: usize) -> Vec<u32> {
+        (0..len).map(|i| self.word(addr + i as u32 * 4)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{bytes_to_words_le_vec, words_to_bytes_le_vec, SP1CoreOpts};
+
+    use super::MemorySnapshot;
+
+    #[test]
+    fn test_replay_uint256_mul() {
+        let x_ptr = 0x1000;
+        let y_ptr = 0x1100;
+        let x = (0..8).map(|i| 0x1111_1111 * (i + 1)).collect::<Vec<u32>>();
+        let y = (0..8).map(|i| 0x0101_0101 * (i + 3)).collect::<Vec<u32>>();
+
+        // Store x, y and a zero modulus (i.e. 2^256), then multiply y into x.
+        let mut instructions = store_words(x_ptr, x.iter().copied());
+        instructions.extend(store_words(y_ptr, y.iter().copied()));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::UINT256_MUL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, x_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, y_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 29, 0, 0, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+        ]);
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.enable_replay_log();
+        runtime.run().unwrap();
+        let log = runtime.replay_log.as_ref().unwrap();
+
+        let event = &runtime.record.uint256_mul_events[0];
+        let before = MemorySnapshot::at(log, event.shard, event.clk - 1);
+        let after = MemorySnapshot::at(log, event.shard, event.clk);
+        assert_eq!(before.words(x_ptr, 8), x);
+        let written = event
+            .x_memory_records
+            .iter()
+            .map(|record| record.value)
+            .collect::<Vec<_>>();
+        assert_eq!(after.words(x_ptr, 8), written);
+
+        let product = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x))
+            * BigUint::from_bytes_le(&words_to_bytes_le_vec(&y))
+            % (BigUint::from(1u32) << 256);
+        let mut product_bytes = product.to_bytes_le();
+        product_bytes.resize(32, 0);
+        assert_eq!(after.words(x_ptr, 8), bytes_to_words_le_vec(&product_bytes));
+
+        // The last store overwrites the final word of y after the precompile.
+        assert_eq!(after.word(y_ptr + 28), y[7]);
+        let end = MemorySnapshot::at(log, event.shard, u32::MAX);
+        assert_eq!(end.word(y_ptr + 28), 0);
+    }
+}
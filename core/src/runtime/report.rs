@@ -1,5 +1,5 @@
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::hash::Hash;
 use std::ops::{Add, AddAssign};
@@ -10,6 +10,7 @@ use super::*;
 pub struct ExecutionReport {
     pub opcode_counts: HashMap<Opcode, u64>,
     pub syscall_counts: HashMap<SyscallCode, u64>,
+    pub memory_access_counts: BTreeMap<u32, MemoryAccessCounts>,
 }
 
 impl ExecutionReport {
@@ -23,6 +24,11 @@ impl ExecutionReport {
         self.syscall_counts.values().sum()
     }
 
+    /// Compute the total number of memory accesses over all shards.
+    pub fn total_memory_access_count(&self) -> u64 {
+        self.memory_access_counts.values().map(|counts| counts.total()).sum()
+    }
+
     /// Returns sorted and formatted rows of a table of counts (e.g. `opcode_counts`).
     ///
     /// The table is sorted first by count (descending) and then by label (ascending).
@@ -76,6 +82,9 @@ impl AddAssign for ExecutionReport {
     fn add_assign(&mut self, rhs: Self) {
         hashmap_add_assign(&mut self.opcode_counts, rhs.opcode_counts);
         hashmap_add_assign(&mut self.syscall_counts, rhs.syscall_counts);
+        for (shard, counts) in rhs.memory_access_counts {
+            *self.memory_access_counts.entry(shard).or_default() += counts;
+        }
     }
 }
 
@@ -108,6 +117,19 @@ impl Display for ExecutionReport {
             writeln!(f, "  {line}")?;
         }
 
+        writeln!(
+            f,
+            "memory accesses per shard ({} total memory accesses):",
+            self.total_memory_access_count()
+        )?;
+        for (shard, counts) in self.memory_access_counts.iter() {
+            writeln!(
+                f,
+                "  shard {shard}: {} reads, {} writes",
+                counts.reads, counts.writes
+            )?;
+        }
+
         Ok(())
     }
 }
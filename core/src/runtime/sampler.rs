@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::disassembler::SymbolTable;
+
+/// A statistical profile of an execution, made of the program counter sampled every `interval`
+/// cycles.
+///
+/// Unlike the opcode counts of the execution report, which count every instruction, sampling only
+/// records one cycle out of `interval`, so it stays cheap for very long programs. Attributing the
+/// samples to the functions containing them with [`PcSampler::by_symbol`] shows where the cycles
+/// go, without having to annotate the guest like `#[cycle_tracker]` does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PcSampler {
+    /// The number of cycles between two samples.
+    pub interval: u64,
+
+    /// The number of samples taken at each program counter.
+    pub samples: HashMap<u32, u64>,
+}
+
+impl PcSampler {
+    /// Create a sampler taking a sample every `interval` cycles.
+    pub fn new(interval: u64) -> Self {
+        assert!(interval > 0, "the sampling interval must be nonzero");
+        Self {
+            interval,
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Record the program counter `pc` of the cycle `global_clk`, if the cycle is sampled.
+    #[inline]
+    pub fn sample(&mut self, global_clk: u64, pc: u32) {
+        if global_clk % self.interval == 0 {
+            *self.samples.entry(pc).or_default() += 1;
+        }
+    }
+
+    /// The total number of samples taken.
+    pub fn total_samples(&self) -> u64 {
+        self.samples.values().sum()
+    }
+
+    /// The program counter with the most samples, the lowest one on ties, if any were taken.
+    pub fn hottest_pc(&self) -> Option<u32> {
+        self.samples
+            .iter()
+            .max_by_key(|&(&pc, &count)| (count, std::cmp::Reverse(pc)))
+            .map(|(&pc, _)| pc)
+    }
+
+    /// Returns the number of samples in each function of `symbols`, keyed by function name.
+    ///
+    /// The samples outside of any function are keyed by their program counter, in hexadecimal.
+    pub fn by_symbol(&self, symbols: &SymbolTable) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for (&pc, &count) in self.samples.iter() {
+            let name = symbols
+                .lookup(pc)
+                .map_or_else(|| format!("{:#x}", pc), str::to_string);
+            *counts.entry(name).or_default() += count;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::disassembler::SymbolTable;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime};
+    use crate::utils::SP1CoreOpts;
+
+    /// The start of the loop of `hot_loop_program`.
+    const LOOP_PC: u32 = 84;
+
+    /// Runs 20 instructions, then a loop of two instructions `iterations` times, then 20 more
+    /// instructions.
+    fn hot_loop_program(iterations: u32) -> Program {
+        let straight = || Instruction::new(Opcode::ADD, 28, 28, 1, false, true);
+        let mut instructions = vec![straight(); 20];
+        instructions.extend([
+            Instruction::new(Opcode::ADD, 29, 0, iterations, false, true),
+            Instruction::new(Opcode::SUB, 29, 29, 1, false, true),
+            Instruction::new(Opcode::BNE, 29, 0, -4i32 as u32, false, true),
+        ]);
+        instructions.extend(vec![straight(); 20]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_pc_sampler_hot_loop() {
+        let mut runtime = Runtime::new(hot_loop_program(1000), SP1CoreOpts::default());
+        runtime.enable_pc_sampling(10);
+        runtime.run().unwrap();
+        let cycles = runtime.state.global_clk;
+        assert_eq!(cycles, 20 + 1 + 2 * 1000 + 20);
+
+        // One cycle out of every ten is sampled, starting with the first.
+        let sampler = runtime.pc_sampler.unwrap();
+        assert_eq!(sampler.total_samples(), (cycles - 1) / 10 + 1);
+
+        // The loop is where the samples are.
+        let hottest = sampler.hottest_pc().unwrap();
+        assert!(hottest == LOOP_PC || hottest == LOOP_PC + 4);
+        let symbols = SymbolTable::new([
+            ("main".to_string(), 0, LOOP_PC),
+            ("hot_loop".to_string(), LOOP_PC, 8),
+        ]);
+        let by_symbol = sampler.by_symbol(&symbols);
+        assert!(by_symbol["hot_loop"] * 10 > sampler.total_samples() * 9);
+        assert_eq!(by_symbol.values().sum::<u64>(), sampler.total_samples());
+
+        // The instructions after the loop are outside of any function.
+        assert!(by_symbol.keys().any(|name| name.starts_with("0x")));
+    }
+
+    #[test]
+    fn test_pc_sampler_interval() {
+        let run = |interval| {
+            let mut runtime = Runtime::new(hot_loop_program(100), SP1CoreOpts::default());
+            runtime.enable_pc_sampling(interval);
+            runtime.run().unwrap();
+            runtime.pc_sampler.unwrap()
+        };
+
+        // Sampling every cycle counts every instruction.
+        let every_cycle = run(1);
+        assert_eq!(every_cycle.total_samples(), 20 + 1 + 2 * 100 + 20);
+        assert_eq!(every_cycle.samples[&LOOP_PC], 100);
+        assert_eq!(every_cycle.samples[&0], 1);
+        assert_eq!(run(1000).total_samples(), 1);
+    }
+}
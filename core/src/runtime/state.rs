@@ -9,7 +9,7 @@ use crate::{
     utils::BabyBearPoseidon2,
 };
 
-use super::{ExecutionRecord, MemoryAccessRecord, MemoryRecord};
+use super::{ExecutionRecord, MemoryAccessRecord, MemoryRecord, SyscallCode};
 
 /// Holds data describing the current state of a program's execution.
 #[serde_as]
@@ -25,6 +25,16 @@ pub struct ExecutionState {
     /// executed in this shard.
     pub clk: u32,
 
+    /// The estimated number of trace cells used by the current shard, tracked when the runtime
+    /// has a [super::ShardCellBudget].
+    #[serde(default)]
+    pub shard_cells: u64,
+
+    /// The number of calls to each precompile in the current shard, tracked when the runtime has
+    /// a cap on them.
+    #[serde(default)]
+    pub shard_precompile_calls: HashMap<SyscallCode, usize>,
+
     /// The channel alternates between 0 and [crate::bytes::NUM_BYTE_LOOKUP_CHANNELS],
     /// used to controll byte lookup multiplicity.
     pub channel: u32,
@@ -46,6 +56,15 @@ pub struct ExecutionState {
     /// A ptr to the current position in the input stream incremented by HINT_READ opcode.
     pub input_stream_ptr: usize,
 
+    /// A stream of private hints, which are read by the program but not committed to the digest of
+    /// its inputs.
+    #[serde(default)]
+    pub hint_stream: Vec<Vec<u8>>,
+
+    /// A ptr to the current position in the hint stream, incremented by READ_HINT.
+    #[serde(default)]
+    pub hint_stream_ptr: usize,
+
     /// A stream of proofs inputted to the program.
     pub proof_stream: Vec<(
         ShardProof<BabyBearPoseidon2>,
@@ -69,18 +88,44 @@ impl ExecutionState {
             // Start at shard 1 since shard 0 is reserved for memory initialization.
             current_shard: 1,
             clk: 0,
+            shard_cells: 0,
+            shard_precompile_calls: HashMap::new(),
             channel: 0,
             pc: pc_start,
             memory: HashMap::default(),
             uninitialized_memory: HashMap::default(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
+            hint_stream: Vec::new(),
+            hint_stream_ptr: 0,
             public_values_stream: Vec::new(),
             public_values_stream_ptr: 0,
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
         }
     }
+
+    /// A digest of the program counter and the memory, including the registers.
+    ///
+    /// The digest is over the nonzero words in order of address, so it does not depend on which
+    /// words were accessed, only on their values.
+    pub fn memory_digest(&self) -> [u8; 32] {
+        let mut words = self
+            .memory
+            .iter()
+            .filter(|(_, record)| record.value != 0)
+            .map(|(addr, record)| (*addr, record.value))
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.pc.to_le_bytes());
+        for (addr, value) in words {
+            hasher.update(&addr.to_le_bytes());
+            hasher.update(&value.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
 }
 
 /// Holds data to track changes made to the runtime since a fork point.
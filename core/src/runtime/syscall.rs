@@ -2,24 +2,61 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::runtime::{Register, Runtime};
+use crate::operations::MAX_REGION_GAP;
+use crate::runtime::{ExecutionError, Register, Runtime};
+use crate::syscall::precompiles::aes::AesEncryptBlockChip;
+use crate::syscall::precompiles::babybear::BytesToFieldsChip;
+use crate::syscall::precompiles::bn254::Bn254FieldInvChip;
+use crate::syscall::precompiles::bn254::Bn254Fp2MulChip;
+use crate::syscall::precompiles::chacha20::ChaCha20BlockChip;
+use crate::syscall::precompiles::crt::CrtCombineChip;
 use crate::syscall::precompiles::edwards::EdAddAssignChip;
 use crate::syscall::precompiles::edwards::EdDecompressChip;
+use crate::syscall::precompiles::encode::Base64EncodeChip;
+use crate::syscall::precompiles::encode::HexEncodeChip;
+use crate::syscall::precompiles::field::FieldAffineChip;
+use crate::syscall::precompiles::field::FieldBatchInvChip;
+use crate::syscall::precompiles::field::FieldIsZeroChip;
+use crate::syscall::precompiles::field::FieldMacChip;
+use crate::syscall::precompiles::field::FieldMontgomeryReduceChip;
+use crate::syscall::precompiles::field::FieldMulSmallChip;
+use crate::syscall::precompiles::field::NttButterflyChip;
+use crate::syscall::precompiles::hash_input::PrepareHashInputChip;
 use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+use crate::syscall::precompiles::memcmp::CtMemcmpChip;
+use crate::syscall::precompiles::memcpy::MemcpyChip;
+use crate::syscall::precompiles::poseidon2::Poseidon2CompressChip;
+use crate::syscall::precompiles::poseidon2::Poseidon2PermuteChip;
+use crate::syscall::precompiles::secp256k1::Secp256k1CompressChip;
+use crate::syscall::precompiles::secp256k1::Secp256k1NormalizeSChip;
 use crate::syscall::precompiles::sha256::{ShaCompressChip, ShaExtendChip};
+use crate::syscall::precompiles::table::CtLookupChip;
+use crate::syscall::precompiles::table::TableLookupChip;
+use crate::syscall::precompiles::uint256::ScalarInRangeChip;
+use crate::syscall::precompiles::uint256::ScalarToNafChip;
+use crate::syscall::precompiles::uint256::Uint256CmovChip;
+use crate::syscall::precompiles::uint256::Uint256GcdChip;
+use crate::syscall::precompiles::uint256::Uint256ModSmallChip;
 use crate::syscall::precompiles::uint256::Uint256MulChip;
+use crate::syscall::precompiles::uint256::Uint256ShiftChip;
+use crate::syscall::precompiles::uint256::Uint256SqrChip;
+use crate::syscall::precompiles::uint256::Uint256ToDecimalChip;
+use crate::syscall::precompiles::uint256::Uint256WnafChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDecompressChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
 use crate::syscall::{
-    SyscallCommit, SyscallCommitDeferred, SyscallEnterUnconstrained, SyscallExitUnconstrained,
-    SyscallHalt, SyscallHintLen, SyscallHintRead, SyscallVerifySP1Proof, SyscallWrite,
+    SyscallCommit, SyscallCommitDeferred, SyscallCyclesRemaining, SyscallEnterUnconstrained,
+    SyscallExitUnconstrained, SyscallHalt, SyscallHasPrecompile, SyscallHintLen, SyscallHintRead,
+    SyscallReadHint, SyscallShardBoundary, SyscallVerifySP1Proof, SyscallWrite,
 };
 use crate::utils::ec::edwards::ed25519::{Ed25519, Ed25519Parameters};
 use crate::utils::ec::weierstrass::bls12_381::Bls12381;
-use crate::utils::ec::weierstrass::{bn254::Bn254, secp256k1::Secp256k1};
+use crate::utils::ec::weierstrass::bn254::{Bn254, Bn254BaseField, Bn254ScalarField};
+use crate::utils::ec::weierstrass::secp256k1::Secp256k1;
 use crate::{runtime::ExecutionRecord, runtime::MemoryReadRecord, runtime::MemoryWriteRecord};
 
 /// A system call is invoked by the the `ecall` instruction with a specific value in register t0.
@@ -28,7 +65,9 @@ use crate::{runtime::ExecutionRecord, runtime::MemoryReadRecord, runtime::Memory
 /// - The second byte is 0/1 depending on whether the syscall has a separate table. This is used
 /// in the CPU table to determine whether to lookup the syscall using the syscall interaction.
 /// - The third byte is the number of additional cycles the syscall uses.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, Ord, PartialOrd)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, Ord, PartialOrd, Serialize, Deserialize,
+)]
 #[allow(non_camel_case_types)]
 pub enum SyscallCode {
     /// Halts the program.
@@ -91,6 +130,18 @@ pub enum SyscallCode {
     /// Executes the `HINT_READ` precompile.
     HINT_READ = 0x00_00_00_F1,
 
+    /// Returns the number of clock cycles remaining in the current shard.
+    CYCLES_REMAINING = 0x00_00_00_F2,
+
+    /// Reads the next slice of the private hint stream.
+    READ_HINT = 0x00_00_00_F3,
+
+    /// Ends the current shard after the syscall.
+    SHARD_BOUNDARY = 0x00_00_00_F4,
+
+    /// Returns whether the runtime supports a given syscall.
+    HAS_PRECOMPILE = 0x00_00_00_F5,
+
     /// Executes the `UINT256_MUL` precompile.
     UINT256_MUL = 0x00_00_01_1D,
 
@@ -99,12 +150,117 @@ pub enum SyscallCode {
 
     /// Executes the `BLS12381_DOUBLE` precompile.
     BLS12381_DOUBLE = 0x00_00_01_1F,
+
+    /// Executes the `FIELD_MUL_SMALL` precompile.
+    FIELD_MUL_SMALL = 0x00_00_01_20,
+
+    /// Executes the `BN254_FR_INV` precompile.
+    BN254_FR_INV = 0x00_00_01_21,
+
+    /// Executes the `AES_ENCRYPT_BLOCK` precompile.
+    AES_ENCRYPT_BLOCK = 0x00_00_01_22,
+
+    /// Executes the `CRT_COMBINE` precompile.
+    CRT_COMBINE = 0x00_00_01_23,
+
+    /// Executes the `UINT256_CMOV` precompile.
+    UINT256_CMOV = 0x00_00_01_24,
+
+    /// Executes the `POSEIDON2_COMPRESS` precompile.
+    POSEIDON2_COMPRESS = 0x00_00_01_25,
+
+    /// Executes the `FIELD_BATCH_INV` precompile.
+    FIELD_BATCH_INV = 0x00_00_01_26,
+
+    /// Executes the `BYTES_TO_FIELDS` precompile.
+    BYTES_TO_FIELDS = 0x00_00_01_27,
+
+    /// Executes the `TABLE_LOOKUP` precompile.
+    TABLE_LOOKUP = 0x00_00_01_28,
+
+    /// Executes the `UINT256_TO_DECIMAL` precompile.
+    UINT256_TO_DECIMAL = 0x00_00_01_29,
+
+    /// Executes the `SECP256K1_NORMALIZE_S` precompile.
+    SECP256K1_NORMALIZE_S = 0x00_00_01_2A,
+
+    /// Executes the `UINT256_MOD_SMALL` precompile.
+    UINT256_MOD_SMALL = 0x00_00_01_2B,
+
+    /// Executes the `UINT256_WNAF` precompile.
+    UINT256_WNAF = 0x00_00_01_2C,
+
+    /// Executes the `SECP256K1_COMPRESS` precompile.
+    SECP256K1_COMPRESS = 0x00_01_01_2D,
+
+    /// Executes the `MEMCPY` precompile.
+    MEMCPY = 0x00_01_01_2E,
+
+    /// Executes the `HEX_ENCODE` precompile.
+    HEX_ENCODE = 0x00_01_01_2F,
+
+    /// Executes the `BASE64_ENCODE` precompile.
+    BASE64_ENCODE = 0x00_01_01_30,
+
+    /// Executes the `PREPARE_HASH_INPUT` precompile.
+    PREPARE_HASH_INPUT = 0x00_01_01_31,
+
+    /// Executes the `MONTGOMERY_REDUCE` precompile.
+    MONTGOMERY_REDUCE = 0x00_01_01_32,
+
+    /// Executes the `NTT_BUTTERFLY` precompile.
+    NTT_BUTTERFLY = 0x00_01_01_33,
+
+    /// Executes the `SCALAR_IN_RANGE` precompile.
+    SCALAR_IN_RANGE = 0x00_00_01_34,
+
+    /// Executes the `FIELD_IS_ZERO` precompile.
+    FIELD_IS_ZERO = 0x00_00_01_35,
+
+    /// Executes the `UINT256_SHL` precompile.
+    UINT256_SHL = 0x00_00_01_36,
+
+    /// Executes the `UINT256_SHR` precompile.
+    UINT256_SHR = 0x00_00_01_37,
+
+    /// Executes the `UINT256_GCD` precompile.
+    UINT256_GCD = 0x00_00_01_38,
+
+    /// Executes the `CT_LOOKUP` precompile.
+    CT_LOOKUP = 0x00_00_01_39,
+
+    /// Executes the `SCALAR_TO_NAF` precompile.
+    SCALAR_TO_NAF = 0x00_00_01_3A,
+
+    /// Executes the `BN254_FP_INV` precompile.
+    BN254_FP_INV = 0x00_00_01_3B,
+
+    /// Executes the `FIELD_MAC` precompile.
+    FIELD_MAC = 0x00_00_01_3C,
+
+    /// Executes the `POSEIDON2_PERMUTE` precompile.
+    POSEIDON2_PERMUTE = 0x00_00_01_3D,
+
+    /// Executes the `FIELD_AFFINE` precompile.
+    FIELD_AFFINE = 0x00_00_01_3E,
+
+    /// Executes the `CHACHA20_BLOCK` precompile.
+    CHACHA20_BLOCK = 0x00_00_01_3F,
+
+    /// Executes the `CT_MEMCMP` precompile.
+    CT_MEMCMP = 0x00_01_01_40,
+
+    /// Executes the `UINT256_SQR` precompile.
+    UINT256_SQR = 0x00_00_01_41,
+
+    /// Executes the `BN254_FP2_MUL` precompile.
+    BN254_FP2_MUL = 0x00_00_01_42,
 }
 
 impl SyscallCode {
-    /// Create a syscall from a u32.
-    pub fn from_u32(value: u32) -> Self {
-        match value {
+    /// Create a syscall from a u32, or return an error if it is not a syscall number.
+    pub fn from_u32(value: u32) -> Result<Self, ExecutionError> {
+        let code = match value {
             0x00_00_00_00 => SyscallCode::HALT,
             0x00_00_00_02 => SyscallCode::WRITE,
             0x00_00_00_03 => SyscallCode::ENTER_UNCONSTRAINED,
@@ -126,10 +282,50 @@ impl SyscallCode {
             0x00_00_00_1B => SyscallCode::VERIFY_SP1_PROOF,
             0x00_00_00_F0 => SyscallCode::HINT_LEN,
             0x00_00_00_F1 => SyscallCode::HINT_READ,
+            0x00_00_00_F2 => SyscallCode::CYCLES_REMAINING,
+            0x00_00_00_F3 => SyscallCode::READ_HINT,
+            0x00_00_00_F4 => SyscallCode::SHARD_BOUNDARY,
+            0x00_00_00_F5 => SyscallCode::HAS_PRECOMPILE,
             0x00_00_01_1D => SyscallCode::UINT256_MUL,
             0x00_00_01_1C => SyscallCode::BLS12381_DECOMPRESS,
-            _ => panic!("invalid syscall number: {}", value),
-        }
+            0x00_00_01_20 => SyscallCode::FIELD_MUL_SMALL,
+            0x00_00_01_21 => SyscallCode::BN254_FR_INV,
+            0x00_00_01_22 => SyscallCode::AES_ENCRYPT_BLOCK,
+            0x00_00_01_23 => SyscallCode::CRT_COMBINE,
+            0x00_00_01_24 => SyscallCode::UINT256_CMOV,
+            0x00_00_01_25 => SyscallCode::POSEIDON2_COMPRESS,
+            0x00_00_01_26 => SyscallCode::FIELD_BATCH_INV,
+            0x00_00_01_27 => SyscallCode::BYTES_TO_FIELDS,
+            0x00_00_01_28 => SyscallCode::TABLE_LOOKUP,
+            0x00_00_01_29 => SyscallCode::UINT256_TO_DECIMAL,
+            0x00_00_01_2A => SyscallCode::SECP256K1_NORMALIZE_S,
+            0x00_00_01_2B => SyscallCode::UINT256_MOD_SMALL,
+            0x00_00_01_2C => SyscallCode::UINT256_WNAF,
+            0x00_01_01_2D => SyscallCode::SECP256K1_COMPRESS,
+            0x00_01_01_2E => SyscallCode::MEMCPY,
+            0x00_01_01_2F => SyscallCode::HEX_ENCODE,
+            0x00_01_01_30 => SyscallCode::BASE64_ENCODE,
+            0x00_01_01_31 => SyscallCode::PREPARE_HASH_INPUT,
+            0x00_01_01_32 => SyscallCode::MONTGOMERY_REDUCE,
+            0x00_01_01_33 => SyscallCode::NTT_BUTTERFLY,
+            0x00_00_01_34 => SyscallCode::SCALAR_IN_RANGE,
+            0x00_00_01_35 => SyscallCode::FIELD_IS_ZERO,
+            0x00_00_01_36 => SyscallCode::UINT256_SHL,
+            0x00_00_01_37 => SyscallCode::UINT256_SHR,
+            0x00_00_01_38 => SyscallCode::UINT256_GCD,
+            0x00_00_01_39 => SyscallCode::CT_LOOKUP,
+            0x00_00_01_3A => SyscallCode::SCALAR_TO_NAF,
+            0x00_00_01_3B => SyscallCode::BN254_FP_INV,
+            0x00_00_01_3C => SyscallCode::FIELD_MAC,
+            0x00_00_01_3D => SyscallCode::POSEIDON2_PERMUTE,
+            0x00_00_01_3E => SyscallCode::FIELD_AFFINE,
+            0x00_00_01_3F => SyscallCode::CHACHA20_BLOCK,
+            0x00_01_01_40 => SyscallCode::CT_MEMCMP,
+            0x00_00_01_41 => SyscallCode::UINT256_SQR,
+            0x00_00_01_42 => SyscallCode::BN254_FP2_MUL,
+            _ => return Err(ExecutionError::UnsupportedSyscall(value)),
+        };
+        Ok(code)
     }
 
     pub fn syscall_id(&self) -> u32 {
@@ -164,8 +360,23 @@ pub trait Syscall: Send + Sync {
     fn num_extra_cycles(&self) -> u32 {
         0
     }
+
+    /// The alignment in bytes that the syscall requires of the pointers it is passed, which it
+    /// should check with [`SyscallContext::require_aligned`]. Precompiles access memory by words,
+    /// so this is at least the word size.
+    fn alignment(&self) -> u32 {
+        4
+    }
 }
 
+/// The start of the scratch region, in which syscalls allocate temporary buffers with
+/// [`SyscallContext::scratch`]. The guest heap ends where it starts, so it does not alias guest
+/// memory.
+pub const SCRATCH_START: u32 = 0x0C00_0000;
+
+/// The size in bytes of the scratch region.
+pub const SCRATCH_SIZE: u32 = 0x0100_0000;
+
 /// A runtime for syscalls that is protected so that developers cannot arbitrarily modify the runtime.
 pub struct SyscallContext<'a, 'b: 'a> {
     current_shard: u32,
@@ -176,6 +387,10 @@ pub struct SyscallContext<'a, 'b: 'a> {
     pub(crate) exit_code: u32,
     pub(crate) rt: &'a mut Runtime<'b>,
     pub syscall_lookup_id: usize,
+    /// The next free address of the scratch region.
+    scratch_ptr: u32,
+    /// The error the syscall failed with, returned by the runtime once it completes.
+    pub(crate) error: Option<ExecutionError>,
 }
 
 impl<'a, 'b> SyscallContext<'a, 'b> {
@@ -189,6 +404,8 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
             exit_code: 0,
             rt: runtime,
             syscall_lookup_id: 0,
+            scratch_ptr: SCRATCH_START,
+            error: None,
         }
     }
 
@@ -204,11 +421,68 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
         self.rt.state.channel
     }
 
+    /// Checks that `ptr` is a multiple of `align`, which must be a power of two.
+    pub fn require_aligned(&self, ptr: u32, align: u32) -> Result<(), ExecutionError> {
+        debug_assert!(align.is_power_of_two());
+        if ptr & (align - 1) != 0 {
+            return Err(ExecutionError::UnalignedSyscallPointer(ptr, align));
+        }
+        Ok(())
+    }
+
+    /// Checks that the operands `[a, a + a_len)` and `[b, b + b_len)` do not overlap and are less
+    /// than [`MAX_REGION_GAP`] bytes apart, as checked in circuit by
+    /// [`crate::operations::DisjointRangesOperation`].
+    pub fn require_disjoint(
+        &self,
+        a: u32,
+        a_len: u32,
+        b: u32,
+        b_len: u32,
+    ) -> Result<(), ExecutionError> {
+        // The lower region is selected the same way as in circuit.
+        let (lower, lower_len, upper) = if a < b { (a, a_len, b) } else { (b, b_len, a) };
+        let lower_end = lower as u64 + lower_len as u64;
+        if (upper as u64) < lower_end {
+            return Err(ExecutionError::AliasingSyscallPointers(a, b));
+        }
+        if upper as u64 - lower_end >= MAX_REGION_GAP as u64 {
+            return Err(ExecutionError::DistantSyscallPointers(a, b));
+        }
+        Ok(())
+    }
+
+    /// Fails the syscall with `err`, which the runtime returns once the syscall completes.
+    pub fn set_error(&mut self, err: ExecutionError) {
+        self.error.get_or_insert(err);
+    }
+
     pub fn mr(&mut self, addr: u32) -> (MemoryReadRecord, u32) {
+        #[cfg(feature = "debug")]
+        self.check_read(addr);
         let record = self.rt.mr(addr, self.current_shard, self.clk);
         (record, record.value)
     }
 
+    /// Logs reads of addresses that were never written.
+    ///
+    /// These are not errors, since zeroed static memory is read this way legitimately, but they
+    /// often point at a wrong pointer. The ordering of the read is checked by [`Runtime::mr`].
+    #[cfg(feature = "debug")]
+    fn check_read(&self, addr: u32) {
+        if !self.rt.state.memory.contains_key(&addr)
+            && !self.rt.state.uninitialized_memory.contains_key(&addr)
+        {
+            let syscall = SyscallCode::from_u32(self.rt.register(Register::X5)).unwrap();
+            tracing::warn!(
+                "{} at clk {} reads address {:#x}, which was never written",
+                syscall,
+                self.clk,
+                addr
+            );
+        }
+    }
+
     pub fn mr_slice(&mut self, addr: u32, len: usize) -> (Vec<MemoryReadRecord>, Vec<u32>) {
         let mut records = Vec::new();
         let mut values = Vec::new();
@@ -233,6 +507,25 @@ impl<'a, 'b> SyscallContext<'a, 'b> {
         records
     }
 
+    /// Allocates a temporary buffer of `len` bytes, rounded up to words, in the scratch region and
+    /// returns a pointer to it.
+    ///
+    /// The buffer is accessed with [`Self::mr`] and [`Self::mw`] like guest memory, so its accesses
+    /// are part of the memory argument and must be constrained by the chip of the syscall.
+    /// Allocations restart at [`SCRATCH_START`] in every syscall, so the addresses are deterministic
+    /// and a buffer holds the values left by earlier syscalls until it is written.
+    pub fn scratch(&mut self, len: usize) -> u32 {
+        let len = len.next_multiple_of(4) as u32;
+        let ptr = self.scratch_ptr;
+        assert!(
+            len <= SCRATCH_START + SCRATCH_SIZE - ptr,
+            "the scratch region of {} bytes is exhausted",
+            SCRATCH_SIZE
+        );
+        self.scratch_ptr += len;
+        ptr
+    }
+
     /// Get the current value of a register, but doesn't use a memory record.
     /// This is generally unconstrained, so you must be careful using it.
     pub fn register_unsafe(&self, register: Register) -> u32 {
@@ -330,19 +623,277 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
     );
     syscall_map.insert(SyscallCode::HINT_LEN, Arc::new(SyscallHintLen::new()));
     syscall_map.insert(SyscallCode::HINT_READ, Arc::new(SyscallHintRead::new()));
+    syscall_map.insert(
+        SyscallCode::CYCLES_REMAINING,
+        Arc::new(SyscallCyclesRemaining::new()),
+    );
+    syscall_map.insert(SyscallCode::READ_HINT, Arc::new(SyscallReadHint::new()));
+    syscall_map.insert(
+        SyscallCode::SHARD_BOUNDARY,
+        Arc::new(SyscallShardBoundary::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::HAS_PRECOMPILE,
+        Arc::new(SyscallHasPrecompile::new()),
+    );
     syscall_map.insert(
         SyscallCode::BLS12381_DECOMPRESS,
         Arc::new(WeierstrassDecompressChip::<Bls12381>::new()),
     );
     syscall_map.insert(SyscallCode::UINT256_MUL, Arc::new(Uint256MulChip::new()));
+    syscall_map.insert(
+        SyscallCode::FIELD_MUL_SMALL,
+        Arc::new(FieldMulSmallChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::BN254_FR_INV,
+        Arc::new(Bn254FieldInvChip::<Bn254ScalarField>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::AES_ENCRYPT_BLOCK,
+        Arc::new(AesEncryptBlockChip::new()),
+    );
+    syscall_map.insert(SyscallCode::CRT_COMBINE, Arc::new(CrtCombineChip::new()));
+    syscall_map.insert(SyscallCode::UINT256_CMOV, Arc::new(Uint256CmovChip::new()));
+    syscall_map.insert(
+        SyscallCode::POSEIDON2_COMPRESS,
+        Arc::new(Poseidon2CompressChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::FIELD_BATCH_INV,
+        Arc::new(FieldBatchInvChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::BYTES_TO_FIELDS,
+        Arc::new(BytesToFieldsChip::new()),
+    );
+    syscall_map.insert(SyscallCode::TABLE_LOOKUP, Arc::new(TableLookupChip::new()));
+    syscall_map.insert(
+        SyscallCode::UINT256_TO_DECIMAL,
+        Arc::new(Uint256ToDecimalChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256K1_NORMALIZE_S,
+        Arc::new(Secp256k1NormalizeSChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT256_MOD_SMALL,
+        Arc::new(Uint256ModSmallChip::new()),
+    );
+    syscall_map.insert(SyscallCode::UINT256_WNAF, Arc::new(Uint256WnafChip::new()));
+    syscall_map.insert(
+        SyscallCode::SECP256K1_COMPRESS,
+        Arc::new(Secp256k1CompressChip::new()),
+    );
+    syscall_map.insert(SyscallCode::MEMCPY, Arc::new(MemcpyChip::new()));
+    syscall_map.insert(SyscallCode::HEX_ENCODE, Arc::new(HexEncodeChip::new()));
+    syscall_map.insert(
+        SyscallCode::BASE64_ENCODE,
+        Arc::new(Base64EncodeChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::PREPARE_HASH_INPUT,
+        Arc::new(PrepareHashInputChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::MONTGOMERY_REDUCE,
+        Arc::new(FieldMontgomeryReduceChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::NTT_BUTTERFLY,
+        Arc::new(NttButterflyChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SCALAR_IN_RANGE,
+        Arc::new(ScalarInRangeChip::new()),
+    );
+    syscall_map.insert(SyscallCode::FIELD_IS_ZERO, Arc::new(FieldIsZeroChip::new()));
+    syscall_map.insert(SyscallCode::UINT256_SHL, Arc::new(Uint256ShiftChip::shl()));
+    syscall_map.insert(SyscallCode::UINT256_SHR, Arc::new(Uint256ShiftChip::shr()));
+    syscall_map.insert(SyscallCode::UINT256_GCD, Arc::new(Uint256GcdChip::new()));
+    syscall_map.insert(SyscallCode::CT_LOOKUP, Arc::new(CtLookupChip::new()));
+    syscall_map.insert(SyscallCode::SCALAR_TO_NAF, Arc::new(ScalarToNafChip::new()));
+    syscall_map.insert(
+        SyscallCode::BN254_FP_INV,
+        Arc::new(Bn254FieldInvChip::<Bn254BaseField>::new()),
+    );
+    syscall_map.insert(SyscallCode::FIELD_MAC, Arc::new(FieldMacChip::new()));
+    syscall_map.insert(
+        SyscallCode::POSEIDON2_PERMUTE,
+        Arc::new(Poseidon2PermuteChip::new()),
+    );
+    syscall_map.insert(SyscallCode::FIELD_AFFINE, Arc::new(FieldAffineChip::new()));
+    syscall_map.insert(
+        SyscallCode::CHACHA20_BLOCK,
+        Arc::new(ChaCha20BlockChip::new()),
+    );
+    syscall_map.insert(SyscallCode::CT_MEMCMP, Arc::new(CtMemcmpChip::new()));
+    syscall_map.insert(SyscallCode::UINT256_SQR, Arc::new(Uint256SqrChip::new()));
+    syscall_map.insert(SyscallCode::BN254_FP2_MUL, Arc::new(Bn254Fp2MulChip::new()));
 
     syscall_map
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{default_syscall_map, SyscallCode};
+    use std::sync::Arc;
+
+    use super::{
+        default_syscall_map, KeccakPermuteChip, Syscall, SyscallCode, SyscallContext, SCRATCH_SIZE,
+        SCRATCH_START,
+    };
+    use crate::operations::MAX_REGION_GAP;
+    use crate::runtime::{ExecutionError, Instruction, Opcode, Program, Runtime};
+    use crate::utils::{self, run_test_core, SP1CoreOpts};
     use strum::IntoEnumIterator;
+    use tiny_keccak::keccakf;
+
+    #[cfg(feature = "debug")]
+    #[test]
+    #[should_panic(expected = "reads address 0x1000 at or before its last access")]
+    fn test_read_before_write_is_flagged() {
+        // Leave a syscall code in t0, as the runtime does while a syscall executes.
+        let instructions = vec![Instruction::new(
+            Opcode::ADD,
+            5,
+            0,
+            SyscallCode::UINT256_MUL as u32,
+            false,
+            true,
+        )];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Write a word and read it back at the same clock, which misorders the accesses.
+        let mut rt = SyscallContext::new(&mut runtime);
+        rt.mw(0x1000, 1);
+        rt.mr(0x1000);
+    }
+
+    /// A syscall that requires 8-byte aligned pointers, and returns whether its first argument is.
+    struct DoubleWordAlignedSyscall;
+
+    impl Syscall for DoubleWordAlignedSyscall {
+        fn execute(&self, ctx: &mut SyscallContext, arg1: u32, _: u32) -> Option<u32> {
+            Some(ctx.require_aligned(arg1, self.alignment()).is_ok() as u32)
+        }
+
+        fn alignment(&self) -> u32 {
+            8
+        }
+    }
+
+    #[test]
+    fn test_require_aligned() {
+        let mut runtime = Runtime::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+        let mut ctx = SyscallContext::new(&mut runtime);
+        let syscall = DoubleWordAlignedSyscall;
+        assert_eq!(syscall.execute(&mut ctx, 0x1008, 0), Some(1));
+        assert_eq!(syscall.execute(&mut ctx, 0x1004, 0), Some(0));
+        assert!(matches!(
+            ctx.require_aligned(0x1004, 8),
+            Err(ExecutionError::UnalignedSyscallPointer(0x1004, 8))
+        ));
+        assert!(ctx.require_aligned(0x1004, 4).is_ok());
+    }
+
+    #[test]
+    fn test_require_disjoint() {
+        let mut runtime = Runtime::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+        let ctx = SyscallContext::new(&mut runtime);
+        assert!(ctx.require_disjoint(0x1000, 32, 0x1020, 64).is_ok());
+        assert!(ctx.require_disjoint(0x1040, 64, 0x1000, 64).is_ok());
+        assert!(matches!(
+            ctx.require_disjoint(0x1000, 32, 0x101c, 64),
+            Err(ExecutionError::AliasingSyscallPointers(0x1000, 0x101c))
+        ));
+        assert!(ctx.require_disjoint(0x1010, 32, 0x1000, 64).is_err());
+
+        // Regions at least `MAX_REGION_GAP` bytes apart are rejected, as they are in circuit.
+        assert!(ctx
+            .require_disjoint(0x1000, 32, 0x1020 + MAX_REGION_GAP - 4, 64)
+            .is_ok());
+        assert!(matches!(
+            ctx.require_disjoint(0x1000 + MAX_REGION_GAP + 32, 64, 0x1000, 32),
+            Err(ExecutionError::DistantSyscallPointers(_, 0x1000))
+        ));
+        assert!(matches!(
+            ctx.require_disjoint(0xffff_ffe0, 32, 0, 64),
+            Err(ExecutionError::DistantSyscallPointers(0xffff_ffe0, 0))
+        ));
+    }
+
+    #[test]
+    fn test_scratch() {
+        let mut runtime = Runtime::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+        let mut ctx = SyscallContext::new(&mut runtime);
+        assert_eq!(ctx.scratch(5), SCRATCH_START);
+        assert_eq!(ctx.scratch(8), SCRATCH_START + 8);
+        assert_eq!(ctx.scratch(SCRATCH_SIZE as usize - 16), SCRATCH_START + 16);
+
+        // Each syscall allocates from the start of the region.
+        let mut ctx = SyscallContext::new(&mut runtime);
+        assert_eq!(ctx.scratch(4), SCRATCH_START);
+    }
+
+    #[test]
+    #[should_panic(expected = "the scratch region of 16777216 bytes is exhausted")]
+    fn test_scratch_exhausted() {
+        let mut runtime = Runtime::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+        let mut ctx = SyscallContext::new(&mut runtime);
+        ctx.scratch(SCRATCH_SIZE as usize);
+        ctx.scratch(1);
+    }
+
+    /// A keccak permutation of a state in scratch memory, whose pointer the program passes as the
+    /// argument so that the permutation chip receives it.
+    struct ScratchKeccakSyscall;
+
+    impl Syscall for ScratchKeccakSyscall {
+        fn execute(&self, ctx: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+            let state_ptr = ctx.scratch(25 * 8);
+            assert_eq!(arg1, state_ptr);
+            KeccakPermuteChip::new().execute(ctx, state_ptr, arg2)
+        }
+    }
+
+    #[test]
+    fn test_scratch_prove() {
+        utils::setup_logger();
+        let guest_ptr = 0x1000;
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 7, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, guest_ptr, false, true),
+            Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::KECCAK_PERMUTE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, SCRATCH_START, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime
+            .syscall_map
+            .insert(SyscallCode::KECCAK_PERMUTE, Arc::new(ScratchKeccakSyscall));
+        runtime.run().unwrap();
+
+        let mut expected = [0u64; 25];
+        keccakf(&mut expected);
+        for (i, lane) in expected.into_iter().enumerate() {
+            let addr = SCRATCH_START + i as u32 * 8;
+            assert_eq!(runtime.word(addr), lane as u32);
+            assert_eq!(runtime.word(addr + 4), (lane >> 32) as u32);
+        }
+        assert_eq!(runtime.word(guest_ptr), 7);
+
+        run_test_core(runtime).unwrap();
+    }
 
     #[test]
     fn test_syscalls_in_default_map() {
@@ -363,7 +914,10 @@ mod tests {
     #[test]
     fn test_encoding_roundtrip() {
         for (syscall_code, _) in default_syscall_map().iter() {
-            assert_eq!(SyscallCode::from_u32(*syscall_code as u32), *syscall_code);
+            assert_eq!(
+                SyscallCode::from_u32(*syscall_code as u32).unwrap(),
+                *syscall_code
+            );
         }
     }
 
@@ -422,9 +976,124 @@ mod tests {
                 }
                 SyscallCode::HINT_LEN => assert_eq!(code as u32, sp1_zkvm::syscalls::HINT_LEN),
                 SyscallCode::HINT_READ => assert_eq!(code as u32, sp1_zkvm::syscalls::HINT_READ),
+                SyscallCode::CYCLES_REMAINING => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CYCLES_REMAINING)
+                }
+                SyscallCode::READ_HINT => assert_eq!(code as u32, sp1_zkvm::syscalls::READ_HINT),
+                SyscallCode::SHARD_BOUNDARY => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SHARD_BOUNDARY)
+                }
+                SyscallCode::HAS_PRECOMPILE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::HAS_PRECOMPILE)
+                }
                 SyscallCode::BLS12381_DECOMPRESS => {
                     assert_eq!(code as u32, sp1_zkvm::syscalls::BLS12381_DECOMPRESS)
                 }
+                SyscallCode::FIELD_MUL_SMALL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::FIELD_MUL_SMALL)
+                }
+                SyscallCode::BN254_FR_INV => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BN254_FR_INV)
+                }
+                SyscallCode::AES_ENCRYPT_BLOCK => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::AES_ENCRYPT_BLOCK)
+                }
+                SyscallCode::CRT_COMBINE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CRT_COMBINE)
+                }
+                SyscallCode::UINT256_CMOV => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_CMOV)
+                }
+                SyscallCode::POSEIDON2_COMPRESS => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::POSEIDON2_COMPRESS)
+                }
+                SyscallCode::FIELD_BATCH_INV => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::FIELD_BATCH_INV)
+                }
+                SyscallCode::BYTES_TO_FIELDS => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BYTES_TO_FIELDS)
+                }
+                SyscallCode::TABLE_LOOKUP => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::TABLE_LOOKUP)
+                }
+                SyscallCode::UINT256_TO_DECIMAL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_TO_DECIMAL)
+                }
+                SyscallCode::SECP256K1_NORMALIZE_S => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SECP256K1_NORMALIZE_S)
+                }
+                SyscallCode::UINT256_MOD_SMALL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_MOD_SMALL)
+                }
+                SyscallCode::UINT256_WNAF => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_WNAF)
+                }
+                SyscallCode::SECP256K1_COMPRESS => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SECP256K1_COMPRESS)
+                }
+                SyscallCode::MEMCPY => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::MEMCPY)
+                }
+                SyscallCode::HEX_ENCODE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::HEX_ENCODE)
+                }
+                SyscallCode::BASE64_ENCODE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BASE64_ENCODE)
+                }
+                SyscallCode::PREPARE_HASH_INPUT => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::PREPARE_HASH_INPUT)
+                }
+                SyscallCode::MONTGOMERY_REDUCE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::MONTGOMERY_REDUCE)
+                }
+                SyscallCode::NTT_BUTTERFLY => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::NTT_BUTTERFLY)
+                }
+                SyscallCode::SCALAR_IN_RANGE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SCALAR_IN_RANGE)
+                }
+                SyscallCode::FIELD_IS_ZERO => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::FIELD_IS_ZERO)
+                }
+                SyscallCode::UINT256_SHL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_SHL)
+                }
+                SyscallCode::UINT256_SHR => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_SHR)
+                }
+                SyscallCode::UINT256_GCD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_GCD)
+                }
+                SyscallCode::CT_LOOKUP => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CT_LOOKUP)
+                }
+                SyscallCode::SCALAR_TO_NAF => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SCALAR_TO_NAF)
+                }
+                SyscallCode::BN254_FP_INV => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BN254_FP_INV)
+                }
+                SyscallCode::FIELD_MAC => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::FIELD_MAC)
+                }
+                SyscallCode::POSEIDON2_PERMUTE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::POSEIDON2_PERMUTE)
+                }
+                SyscallCode::FIELD_AFFINE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::FIELD_AFFINE)
+                }
+                SyscallCode::CHACHA20_BLOCK => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CHACHA20_BLOCK)
+                }
+                SyscallCode::CT_MEMCMP => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CT_MEMCMP)
+                }
+                SyscallCode::UINT256_SQR => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT256_SQR)
+                }
+                SyscallCode::BN254_FP2_MUL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BN254_FP2_MUL)
+                }
             }
         }
     }
@@ -2,10 +2,12 @@ use super::StarkMachine;
 pub use crate::air::SP1AirBuilder;
 use crate::air::{MachineAir, SP1_PROOF_NUM_PV_ELTS};
 use crate::memory::{MemoryChipType, MemoryProgramChip};
-use crate::stark::Chip;
+use crate::runtime::{Program, SyscallCode, SyscallManifest};
+use crate::stark::{BatchedLogUp, Chip, LookupArgument};
 use crate::StarkGenericConfig;
 use p3_field::PrimeField32;
 pub use riscv_chips::*;
+use std::sync::Arc;
 use tracing::instrument;
 
 /// A module for importing all the different RISC-V chips.
@@ -21,19 +23,55 @@ pub(crate) mod riscv_chips {
     pub use crate::cpu::CpuChip;
     pub use crate::memory::MemoryChip;
     pub use crate::program::ProgramChip;
+    pub use crate::syscall::precompiles::aes::AesEncryptBlockChip;
+    pub use crate::syscall::precompiles::babybear::BytesToFieldsChip;
+    pub use crate::syscall::precompiles::bn254::Bn254FieldInvChip;
+    pub use crate::syscall::precompiles::bn254::Bn254Fp2MulChip;
+    pub use crate::syscall::precompiles::chacha20::ChaCha20BlockChip;
+    pub use crate::syscall::precompiles::crt::CrtCombineChip;
     pub use crate::syscall::precompiles::edwards::EdAddAssignChip;
     pub use crate::syscall::precompiles::edwards::EdDecompressChip;
+    pub use crate::syscall::precompiles::encode::Base64EncodeChip;
+    pub use crate::syscall::precompiles::encode::HexEncodeChip;
+    pub use crate::syscall::precompiles::field::FieldAffineChip;
+    pub use crate::syscall::precompiles::field::FieldBatchInvChip;
+    pub use crate::syscall::precompiles::field::FieldIsZeroChip;
+    pub use crate::syscall::precompiles::field::FieldMacChip;
+    pub use crate::syscall::precompiles::field::FieldMontgomeryReduceChip;
+    pub use crate::syscall::precompiles::field::FieldMulSmallChip;
+    pub use crate::syscall::precompiles::field::NttButterflyChip;
+    pub use crate::syscall::precompiles::hash_input::PrepareHashInputChip;
     pub use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+    pub use crate::syscall::precompiles::memcmp::CtMemcmpChip;
+    pub use crate::syscall::precompiles::memcpy::MemcpyChip;
+    pub use crate::syscall::precompiles::poseidon2::Poseidon2CompressChip;
+    pub use crate::syscall::precompiles::poseidon2::Poseidon2PermuteChip;
+    pub use crate::syscall::precompiles::secp256k1::Secp256k1CompressChip;
+    pub use crate::syscall::precompiles::secp256k1::Secp256k1NormalizeSChip;
     pub use crate::syscall::precompiles::sha256::ShaCompressChip;
     pub use crate::syscall::precompiles::sha256::ShaExtendChip;
+    pub use crate::syscall::precompiles::table::CtLookupChip;
+    pub use crate::syscall::precompiles::table::LookupTableChip;
+    pub use crate::syscall::precompiles::table::TableLookupChip;
+    pub use crate::syscall::precompiles::uint256::ScalarInRangeChip;
+    pub use crate::syscall::precompiles::uint256::ScalarToNafChip;
+    pub use crate::syscall::precompiles::uint256::Uint256CmovChip;
+    pub use crate::syscall::precompiles::uint256::Uint256GcdChip;
+    pub use crate::syscall::precompiles::uint256::Uint256ModSmallChip;
     pub use crate::syscall::precompiles::uint256::Uint256MulChip;
+    pub use crate::syscall::precompiles::uint256::Uint256ShiftChip;
+    pub use crate::syscall::precompiles::uint256::Uint256SqrChip;
+    pub use crate::syscall::precompiles::uint256::Uint256ToDecimalChip;
+    pub use crate::syscall::precompiles::uint256::Uint256WnafChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDecompressChip;
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
     pub use crate::utils::ec::edwards::ed25519::Ed25519Parameters;
     pub use crate::utils::ec::edwards::EdwardsCurve;
     pub use crate::utils::ec::weierstrass::bls12_381::Bls12381Parameters;
+    pub use crate::utils::ec::weierstrass::bn254::Bn254BaseField;
     pub use crate::utils::ec::weierstrass::bn254::Bn254Parameters;
+    pub use crate::utils::ec::weierstrass::bn254::Bn254ScalarField;
     pub use crate::utils::ec::weierstrass::secp256k1::Secp256k1Parameters;
     pub use crate::utils::ec::weierstrass::SwCurve;
 }
@@ -99,18 +137,201 @@ pub enum RiscvAir<F: PrimeField32> {
     Uint256Mul(Uint256MulChip),
     /// A precompile for decompressing a point on the BLS12-381 curve.
     Bls12381Decompress(WeierstrassDecompressChip<SwCurve<Bls12381Parameters>>),
+    /// A precompile for multiplying a field element by a small constant.
+    FieldMulSmall(FieldMulSmallChip),
+    /// A precompile for inverting an element of the BN254 scalar field.
+    Bn254FrInv(Bn254FieldInvChip<Bn254ScalarField>),
+    /// A precompile for encrypting a block with AES.
+    AesEncryptBlock(AesEncryptBlockChip),
+    /// A precompile for recombining two residues with the Chinese remainder theorem.
+    CrtCombine(CrtCombineChip),
+    /// A precompile for conditionally moving a uint256 in constant time.
+    Uint256Cmov(Uint256CmovChip),
+    /// A precompile for Poseidon2 2-to-1 compression.
+    Poseidon2Compress(Poseidon2CompressChip),
+    /// A precompile for the Poseidon2 permutation of a state in place.
+    Poseidon2Permute(Poseidon2PermuteChip),
+    /// A precompile for batch inverting field elements with Montgomery's trick.
+    FieldBatchInv(FieldBatchInvChip),
+    /// A precompile for packing bytes into BabyBear elements.
+    BytesToFields(BytesToFieldsChip),
+    /// A precompile for looking up keys in the lookup tables of the program.
+    TableLookup(TableLookupChip),
+    /// A preprocessed table with the entries of the lookup tables of the program.
+    LookupTable(LookupTableChip),
+    /// A precompile for converting uint256 values to decimal digits.
+    Uint256ToDecimal(Uint256ToDecimalChip),
+    /// A precompile for normalizing secp256k1 signatures to low-s.
+    Secp256k1NormalizeS(Secp256k1NormalizeSChip),
+    /// A precompile for reducing a uint256 modulo a small word.
+    Uint256ModSmall(Uint256ModSmallChip),
+    /// A precompile for recoding a uint256 scalar into signed window digits.
+    Uint256Wnaf(Uint256WnafChip),
+    /// A precompile for compressing secp256k1 points.
+    Secp256k1Compress(Secp256k1CompressChip),
+    /// A precompile for copying words between possibly overlapping buffers.
+    Memcpy(MemcpyChip),
+    /// A precompile for hex encoding buffers.
+    HexEncode(HexEncodeChip),
+    /// A precompile for base64 encoding buffers.
+    Base64Encode(Base64EncodeChip),
+    /// A precompile for preparing hash inputs by reversing, length-prefixing and padding them.
+    PrepareHashInput(PrepareHashInputChip),
+    /// A precompile for the Montgomery reduction of 512-bit values modulo uint256 moduli.
+    FieldMontgomeryReduce(FieldMontgomeryReduceChip),
+    /// A precompile for the butterflies of number theoretic transforms modulo uint256 moduli.
+    NttButterfly(NttButterflyChip),
+    /// A precompile for scalars strictly between zero and a uint256 modulus.
+    ScalarInRange(ScalarInRangeChip),
+    /// A precompile checking whether a field element is zero.
+    FieldIsZero(FieldIsZeroChip),
+    /// A precompile for left shifts of uint256 values.
+    Uint256Shl(Uint256ShiftChip),
+    /// A precompile for right shifts of uint256 values.
+    Uint256Shr(Uint256ShiftChip),
+    /// A precompile for the gcd of uint256 values.
+    Uint256Gcd(Uint256GcdChip),
+    /// A precompile for reading a table entry with an access pattern independent of the index.
+    CtLookup(CtLookupChip),
+    /// A precompile for the width-4 non-adjacent form of a uint256 scalar.
+    ScalarToNaf(ScalarToNafChip),
+    /// A precompile for inversion in the Bn254 base field.
+    Bn254FpInv(Bn254FieldInvChip<Bn254BaseField>),
+    /// A precompile for the multiply-accumulate `acc + a * b` modulo a uint256 modulus.
+    FieldMac(FieldMacChip),
+    /// A precompile for the affine combination `a * x + b` modulo a uint256 modulus.
+    FieldAffine(FieldAffineChip),
+    /// A precompile for the ChaCha20 block function of RFC 8439.
+    ChaCha20Block(ChaCha20BlockChip),
+    /// A precompile for the constant-time comparison of two buffers of bytes.
+    CtMemcmp(CtMemcmpChip),
+    /// A precompile for squaring a uint256 modulo another one.
+    Uint256Sqr(Uint256SqrChip),
+    /// A precompile for multiplication in the quadratic extension of the Bn254 base field.
+    Bn254Fp2Mul(Bn254Fp2MulChip),
 }
 
 impl<F: PrimeField32> RiscvAir<F> {
-    #[instrument("construct RiscvAir machine", level = "debug", skip_all)]
     pub fn machine<SC: StarkGenericConfig<Val = F>>(config: SC) -> StarkMachine<SC, Self> {
+        Self::machine_with_lookup_argument(config, Arc::new(BatchedLogUp))
+    }
+
+    /// Constructs the RISC-V machine with all chips proving their interactions with the given
+    /// lookup argument.
+    #[instrument("construct RiscvAir machine", level = "debug", skip_all)]
+    pub fn machine_with_lookup_argument<SC: StarkGenericConfig<Val = F>>(
+        config: SC,
+        lookup: Arc<dyn LookupArgument>,
+    ) -> StarkMachine<SC, Self> {
         let chips = Self::get_all()
             .into_iter()
-            .map(Chip::new)
+            .map(|air| Chip::new_with_lookup_argument(air, lookup.clone()))
+            .collect::<Vec<_>>();
+        StarkMachine::new(config, chips, SP1_PROOF_NUM_PV_ELTS)
+    }
+
+    /// Constructs a RISC-V machine with exactly the chips needed to prove the given program, with
+    /// all chips proving their interactions with the given lookup argument.
+    ///
+    /// If the program declares a manifest of the precompiles it uses, the chips of all other
+    /// precompiles are left out of the machine. Otherwise, the machine contains all chips.
+    pub fn machine_for_program<SC: StarkGenericConfig<Val = F>>(
+        config: SC,
+        program: &Program,
+        lookup: Arc<dyn LookupArgument>,
+    ) -> StarkMachine<SC, Self> {
+        let airs = match &program.manifest {
+            Some(manifest) => Self::get_all_for_manifest(manifest),
+            None => Self::get_all(),
+        };
+        let chips = airs
+            .into_iter()
+            .map(|air| Chip::new_with_lookup_argument(air, lookup.clone()))
             .collect::<Vec<_>>();
         StarkMachine::new(config, chips, SP1_PROOF_NUM_PV_ELTS)
     }
 
+    /// Get the RISC-V AIRs needed by a program with the given manifest.
+    pub fn get_all_for_manifest(manifest: &SyscallManifest) -> Vec<Self> {
+        Self::get_all()
+            .into_iter()
+            .filter(|air| {
+                air.syscall_code()
+                    .map_or(true, |code| manifest.allows(code))
+            })
+            .collect()
+    }
+
+    /// The syscall proven by this AIR, if it is a precompile.
+    pub fn syscall_code(&self) -> Option<SyscallCode> {
+        let code = match self {
+            RiscvAir::Sha256Extend(_) => SyscallCode::SHA_EXTEND,
+            RiscvAir::Sha256Compress(_) => SyscallCode::SHA_COMPRESS,
+            RiscvAir::Ed25519Add(_) => SyscallCode::ED_ADD,
+            RiscvAir::Ed25519Decompress(_) => SyscallCode::ED_DECOMPRESS,
+            RiscvAir::K256Decompress(_) => SyscallCode::SECP256K1_DECOMPRESS,
+            RiscvAir::Secp256k1Add(_) => SyscallCode::SECP256K1_ADD,
+            RiscvAir::Secp256k1Double(_) => SyscallCode::SECP256K1_DOUBLE,
+            RiscvAir::KeccakP(_) => SyscallCode::KECCAK_PERMUTE,
+            RiscvAir::Bn254Add(_) => SyscallCode::BN254_ADD,
+            RiscvAir::Bn254Double(_) => SyscallCode::BN254_DOUBLE,
+            RiscvAir::Bls12381Add(_) => SyscallCode::BLS12381_ADD,
+            RiscvAir::Bls12381Double(_) => SyscallCode::BLS12381_DOUBLE,
+            RiscvAir::Uint256Mul(_) => SyscallCode::UINT256_MUL,
+            RiscvAir::Bls12381Decompress(_) => SyscallCode::BLS12381_DECOMPRESS,
+            RiscvAir::FieldMulSmall(_) => SyscallCode::FIELD_MUL_SMALL,
+            RiscvAir::Bn254FrInv(_) => SyscallCode::BN254_FR_INV,
+            RiscvAir::AesEncryptBlock(_) => SyscallCode::AES_ENCRYPT_BLOCK,
+            RiscvAir::CrtCombine(_) => SyscallCode::CRT_COMBINE,
+            RiscvAir::Uint256Cmov(_) => SyscallCode::UINT256_CMOV,
+            RiscvAir::Poseidon2Compress(_) => SyscallCode::POSEIDON2_COMPRESS,
+            RiscvAir::Poseidon2Permute(_) => SyscallCode::POSEIDON2_PERMUTE,
+            RiscvAir::FieldBatchInv(_) => SyscallCode::FIELD_BATCH_INV,
+            RiscvAir::BytesToFields(_) => SyscallCode::BYTES_TO_FIELDS,
+            RiscvAir::TableLookup(_) => SyscallCode::TABLE_LOOKUP,
+            RiscvAir::LookupTable(_) => SyscallCode::TABLE_LOOKUP,
+            RiscvAir::Uint256ToDecimal(_) => SyscallCode::UINT256_TO_DECIMAL,
+            RiscvAir::Secp256k1NormalizeS(_) => SyscallCode::SECP256K1_NORMALIZE_S,
+            RiscvAir::Uint256ModSmall(_) => SyscallCode::UINT256_MOD_SMALL,
+            RiscvAir::Uint256Wnaf(_) => SyscallCode::UINT256_WNAF,
+            RiscvAir::Secp256k1Compress(_) => SyscallCode::SECP256K1_COMPRESS,
+            RiscvAir::Memcpy(_) => SyscallCode::MEMCPY,
+            RiscvAir::HexEncode(_) => SyscallCode::HEX_ENCODE,
+            RiscvAir::Base64Encode(_) => SyscallCode::BASE64_ENCODE,
+            RiscvAir::PrepareHashInput(_) => SyscallCode::PREPARE_HASH_INPUT,
+            RiscvAir::FieldMontgomeryReduce(_) => SyscallCode::MONTGOMERY_REDUCE,
+            RiscvAir::NttButterfly(_) => SyscallCode::NTT_BUTTERFLY,
+            RiscvAir::ScalarInRange(_) => SyscallCode::SCALAR_IN_RANGE,
+            RiscvAir::FieldIsZero(_) => SyscallCode::FIELD_IS_ZERO,
+            RiscvAir::Uint256Shl(_) => SyscallCode::UINT256_SHL,
+            RiscvAir::Uint256Shr(_) => SyscallCode::UINT256_SHR,
+            RiscvAir::Uint256Gcd(_) => SyscallCode::UINT256_GCD,
+            RiscvAir::CtLookup(_) => SyscallCode::CT_LOOKUP,
+            RiscvAir::ScalarToNaf(_) => SyscallCode::SCALAR_TO_NAF,
+            RiscvAir::Bn254FpInv(_) => SyscallCode::BN254_FP_INV,
+            RiscvAir::FieldMac(_) => SyscallCode::FIELD_MAC,
+            RiscvAir::FieldAffine(_) => SyscallCode::FIELD_AFFINE,
+            RiscvAir::ChaCha20Block(_) => SyscallCode::CHACHA20_BLOCK,
+            RiscvAir::CtMemcmp(_) => SyscallCode::CT_MEMCMP,
+            RiscvAir::Uint256Sqr(_) => SyscallCode::UINT256_SQR,
+            RiscvAir::Bn254Fp2Mul(_) => SyscallCode::BN254_FP2_MUL,
+            RiscvAir::Program(_)
+            | RiscvAir::Cpu(_)
+            | RiscvAir::Add(_)
+            | RiscvAir::Bitwise(_)
+            | RiscvAir::Mul(_)
+            | RiscvAir::DivRem(_)
+            | RiscvAir::Lt(_)
+            | RiscvAir::ShiftLeft(_)
+            | RiscvAir::ShiftRight(_)
+            | RiscvAir::ByteLookup(_)
+            | RiscvAir::MemoryInit(_)
+            | RiscvAir::MemoryFinal(_)
+            | RiscvAir::ProgramMemory(_) => return None,
+        };
+        Some(code)
+    }
+
     /// Get all the different RISC-V AIRs.
     pub fn get_all() -> Vec<Self> {
         // The order of the chips is important, as it is used to determine the order of trace
@@ -149,6 +370,78 @@ impl<F: PrimeField32> RiscvAir<F> {
         chips.push(RiscvAir::Uint256Mul(uint256_mul));
         let bls12381_decompress = WeierstrassDecompressChip::<SwCurve<Bls12381Parameters>>::new();
         chips.push(RiscvAir::Bls12381Decompress(bls12381_decompress));
+        let field_mul_small = FieldMulSmallChip::default();
+        chips.push(RiscvAir::FieldMulSmall(field_mul_small));
+        let bn254_fr_inv = Bn254FieldInvChip::<Bn254ScalarField>::new();
+        chips.push(RiscvAir::Bn254FrInv(bn254_fr_inv));
+        let aes_encrypt_block = AesEncryptBlockChip::default();
+        chips.push(RiscvAir::AesEncryptBlock(aes_encrypt_block));
+        let crt_combine = CrtCombineChip::default();
+        chips.push(RiscvAir::CrtCombine(crt_combine));
+        let uint256_cmov = Uint256CmovChip::default();
+        chips.push(RiscvAir::Uint256Cmov(uint256_cmov));
+        let poseidon2_compress = Poseidon2CompressChip::default();
+        chips.push(RiscvAir::Poseidon2Compress(poseidon2_compress));
+        let poseidon2_permute = Poseidon2PermuteChip::default();
+        chips.push(RiscvAir::Poseidon2Permute(poseidon2_permute));
+        let field_batch_inv = FieldBatchInvChip::default();
+        chips.push(RiscvAir::FieldBatchInv(field_batch_inv));
+        let bytes_to_fields = BytesToFieldsChip::default();
+        chips.push(RiscvAir::BytesToFields(bytes_to_fields));
+        let table_lookup = TableLookupChip::default();
+        chips.push(RiscvAir::TableLookup(table_lookup));
+        let lookup_table = LookupTableChip::default();
+        chips.push(RiscvAir::LookupTable(lookup_table));
+        let uint256_to_decimal = Uint256ToDecimalChip::default();
+        chips.push(RiscvAir::Uint256ToDecimal(uint256_to_decimal));
+        let secp256k1_normalize_s = Secp256k1NormalizeSChip::default();
+        chips.push(RiscvAir::Secp256k1NormalizeS(secp256k1_normalize_s));
+        let uint256_mod_small = Uint256ModSmallChip::default();
+        chips.push(RiscvAir::Uint256ModSmall(uint256_mod_small));
+        let uint256_wnaf = Uint256WnafChip::default();
+        chips.push(RiscvAir::Uint256Wnaf(uint256_wnaf));
+        let secp256k1_compress = Secp256k1CompressChip::default();
+        chips.push(RiscvAir::Secp256k1Compress(secp256k1_compress));
+        let memcpy = MemcpyChip::default();
+        chips.push(RiscvAir::Memcpy(memcpy));
+        let hex_encode = HexEncodeChip::default();
+        chips.push(RiscvAir::HexEncode(hex_encode));
+        let base64_encode = Base64EncodeChip::default();
+        chips.push(RiscvAir::Base64Encode(base64_encode));
+        let prepare_hash_input = PrepareHashInputChip::default();
+        chips.push(RiscvAir::PrepareHashInput(prepare_hash_input));
+        let field_montgomery_reduce = FieldMontgomeryReduceChip::default();
+        chips.push(RiscvAir::FieldMontgomeryReduce(field_montgomery_reduce));
+        let ntt_butterfly = NttButterflyChip::default();
+        chips.push(RiscvAir::NttButterfly(ntt_butterfly));
+        let scalar_in_range = ScalarInRangeChip::default();
+        chips.push(RiscvAir::ScalarInRange(scalar_in_range));
+        let field_is_zero = FieldIsZeroChip::default();
+        chips.push(RiscvAir::FieldIsZero(field_is_zero));
+        let uint256_shl = Uint256ShiftChip::shl();
+        chips.push(RiscvAir::Uint256Shl(uint256_shl));
+        let uint256_shr = Uint256ShiftChip::shr();
+        chips.push(RiscvAir::Uint256Shr(uint256_shr));
+        let uint256_gcd = Uint256GcdChip::default();
+        chips.push(RiscvAir::Uint256Gcd(uint256_gcd));
+        let ct_lookup = CtLookupChip::default();
+        chips.push(RiscvAir::CtLookup(ct_lookup));
+        let scalar_to_naf = ScalarToNafChip::default();
+        chips.push(RiscvAir::ScalarToNaf(scalar_to_naf));
+        let bn254_fp_inv = Bn254FieldInvChip::<Bn254BaseField>::new();
+        chips.push(RiscvAir::Bn254FpInv(bn254_fp_inv));
+        let field_mac = FieldMacChip::default();
+        chips.push(RiscvAir::FieldMac(field_mac));
+        let field_affine = FieldAffineChip::default();
+        chips.push(RiscvAir::FieldAffine(field_affine));
+        let chacha20_block = ChaCha20BlockChip::default();
+        chips.push(RiscvAir::ChaCha20Block(chacha20_block));
+        let ct_memcmp = CtMemcmpChip::default();
+        chips.push(RiscvAir::CtMemcmp(ct_memcmp));
+        let uint256_sqr = Uint256SqrChip::default();
+        chips.push(RiscvAir::Uint256Sqr(uint256_sqr));
+        let bn254_fp2_mul = Bn254Fp2MulChip::default();
+        chips.push(RiscvAir::Bn254Fp2Mul(bn254_fp2_mul));
         let div_rem = DivRemChip::default();
         chips.push(RiscvAir::DivRem(div_rem));
         let add = AddSubChip::default();
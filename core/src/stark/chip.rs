@@ -1,4 +1,4 @@
-use std::hash::Hash;
+use std::{hash::Hash, sync::Arc};
 
 use p3_air::{Air, BaseAir, PairBuilder};
 use p3_field::{ExtensionField, Field, PrimeField, PrimeField32};
@@ -13,7 +13,7 @@ use crate::{
 
 use super::{
     eval_permutation_constraints, generate_permutation_trace, permutation_trace_width,
-    PROOF_MAX_NUM_PVS,
+    BatchedLogUp, LookupArgument, PROOF_MAX_NUM_PVS,
 };
 
 /// An Air that encodes lookups based on interactions.
@@ -26,6 +26,8 @@ pub struct Chip<F: Field, A> {
     receives: Vec<Interaction<F>>,
     /// The relative log degree of the quotient polynomial, i.e. `log2(max_constraint_degree - 1)`.
     log_quotient_degree: usize,
+    /// The lookup argument used to prove the interactions of the chip.
+    lookup: Arc<dyn LookupArgument>,
 }
 
 impl<F: Field, A> Chip<F, A> {
@@ -43,6 +45,11 @@ impl<F: Field, A> Chip<F, A> {
     pub const fn log_quotient_degree(&self) -> usize {
         self.log_quotient_degree
     }
+
+    /// The lookup argument used to prove the interactions of the chip.
+    pub fn lookup_argument(&self) -> &dyn LookupArgument {
+        self.lookup.as_ref()
+    }
 }
 
 impl<F: PrimeField32, A: MachineAir<F>> Chip<F, A> {
@@ -58,6 +65,14 @@ where
 {
     /// Records the interactions and constraint degree from the air and crates a new chip.
     pub fn new(air: A) -> Self
+    where
+        A: MachineAir<F> + Air<InteractionBuilder<F>> + Air<SymbolicAirBuilder<F>>,
+    {
+        Self::new_with_lookup_argument(air, Arc::new(BatchedLogUp))
+    }
+
+    /// Creates a new chip whose interactions are proven with the given lookup argument.
+    pub fn new_with_lookup_argument(air: A, lookup: Arc<dyn LookupArgument>) -> Self
     where
         A: MachineAir<F> + Air<InteractionBuilder<F>> + Air<SymbolicAirBuilder<F>>,
     {
@@ -92,6 +107,7 @@ where
             sends,
             receives,
             log_quotient_degree,
+            lookup,
         }
     }
 
@@ -144,8 +160,8 @@ where
     }
 
     #[inline]
-    pub const fn logup_batch_size(&self) -> usize {
-        1 << self.log_quotient_degree
+    pub fn logup_batch_size(&self) -> usize {
+        self.lookup.batch_size(self.log_quotient_degree)
     }
 }
 
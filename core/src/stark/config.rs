@@ -1,7 +1,7 @@
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{ExtensionField, Field, PrimeField};
-use serde::{de::DeserializeOwned, Serialize};
+use p3_field::{AbstractField, ExtensionField, Field, PrimeField};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
@@ -46,6 +46,28 @@ pub type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
 pub type Challenge<SC> = <SC as StarkGenericConfig>::Challenge;
 pub type Challenger<SC> = <SC as StarkGenericConfig>::Challenger;
 
+/// The parameters of a Poseidon2 permutation, which determine the transcript and the Merkle trees
+/// of a configuration hashing with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Poseidon2Params {
+    /// The width of the state.
+    pub width: usize,
+
+    /// The number of external (full) rounds.
+    pub rounds_f: usize,
+
+    /// The number of internal (partial) rounds.
+    pub rounds_p: usize,
+}
+
+impl Poseidon2Params {
+    /// The parameters as field elements, in the order they are observed by the challenger and
+    /// hashed into the digest of a verifying key.
+    pub fn to_field_elements<F: AbstractField>(&self) -> [F; 3] {
+        [self.width, self.rounds_f, self.rounds_p].map(F::from_canonical_usize)
+    }
+}
+
 pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone {
     type Val: PrimeField;
 
@@ -67,6 +89,12 @@ pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone
 
     /// Initialize a new challenger.
     fn challenger(&self) -> Self::Challenger;
+
+    /// The parameters of the Poseidon2 permutation of the challenger and the commitments, if the
+    /// configuration uses one.
+    fn poseidon2_params(&self) -> Option<Poseidon2Params> {
+        None
+    }
 }
 
 pub struct UniConfig<SC>(pub SC);
@@ -1,4 +1,5 @@
 use std::borrow::Borrow;
+use std::fmt::Debug;
 use std::panic::{self, AssertUnwindSafe};
 use std::process::exit;
 
@@ -85,6 +86,7 @@ pub fn debug_constraints<SC, A>(
             is_last_row: Val::<SC>::zero(),
             is_transition: Val::<SC>::one(),
             public_values: &public_values,
+            failures: None,
         };
         if i == 0 {
             builder.is_first_row = Val::<SC>::one();
@@ -105,6 +107,97 @@ pub fn debug_constraints<SC, A>(
     });
 }
 
+/// Checks that every row of a trace generated by `air` satisfies its constraints, and panics with
+/// the first row which does not.
+///
+/// Trace generation and `eval` are written separately, so a row populated differently from the way
+/// the AIR constrains it is otherwise only caught when the shard is proven. Chips call this at the
+/// end of `generate_trace` to catch the drift as soon as the trace is generated. Interactions are
+/// not checked, since they depend on the other chips of the shard.
+#[cfg(feature = "debug")]
+pub fn debug_trace_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>)
+where
+    F: PrimeField32,
+    A: MachineAir<F> + for<'a> Air<DebugConstraintBuilder<'a, F, F>>,
+{
+    let height = main.height();
+    for i in 0..height {
+        let main_local = main.row_slice(i);
+        let main_local = &(*main_local);
+        let main_next = main.row_slice((i + 1) % height);
+        let main_next = &(*main_next);
+
+        let mut builder = DebugConstraintBuilder {
+            preprocessed: VerticalPair::new(
+                RowMajorMatrixView::new_row(&[]),
+                RowMajorMatrixView::new_row(&[]),
+            ),
+            main: VerticalPair::new(
+                RowMajorMatrixView::new_row(main_local),
+                RowMajorMatrixView::new_row(main_next),
+            ),
+            perm: VerticalPair::new(
+                RowMajorMatrixView::new_row(&[]),
+                RowMajorMatrixView::new_row(&[]),
+            ),
+            perm_challenges: &[],
+            cumulative_sum: F::zero(),
+            is_first_row: F::from_bool(i == 0),
+            is_last_row: F::from_bool(i == height - 1),
+            is_transition: F::from_bool(i != height - 1),
+            public_values: &[],
+            failures: Some(Vec::new()),
+        };
+        air.eval(&mut builder);
+        let failures = builder.failures.unwrap_or_default();
+        if !failures.is_empty() {
+            panic!(
+                "row {} of chip {} does not satisfy its constraints ({}): {:?}",
+                i,
+                air.name(),
+                failures.join(", "),
+                main_local
+            );
+        }
+    }
+}
+
+/// Returns the interval at which `generate_trace` logs the rows it generates, read from the
+/// `TRACE_SAMPLE_INTERVAL` environment variable. Sampling is disabled if the variable is unset or
+/// zero.
+pub fn trace_sample_interval() -> Option<usize> {
+    std::env::var("TRACE_SAMPLE_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+        .filter(|&interval| interval > 0)
+}
+
+/// Logs every `interval`-th of the first `num_rows` rows of `main`, and returns the number of rows
+/// logged.
+///
+/// Each row is borrowed as the column struct `C` of the chip and pretty-printed, so that values are
+/// shown next to the names of their columns instead of as a flat slice of field elements.
+pub fn log_sampled_rows<F, C>(
+    name: &str,
+    main: &RowMajorMatrix<F>,
+    num_rows: usize,
+    interval: usize,
+) -> usize
+where
+    F: Field,
+    C: Debug,
+    [F]: Borrow<C>,
+{
+    let mut num_sampled = 0;
+    for i in (0..num_rows.min(main.height())).step_by(interval) {
+        let row = main.row_slice(i);
+        let cols: &C = (*row).borrow();
+        tracing::info!("row {} of chip {}: {:#?}", i, name, cols);
+        num_sampled += 1;
+    }
+    num_sampled
+}
+
 fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::thread::Result<R> {
     let prev_hook = panic::take_hook();
     panic::set_hook(Box::new(|_| {}));
@@ -135,6 +228,8 @@ pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     pub(crate) is_last_row: F,
     pub(crate) is_transition: F,
     pub(crate) public_values: &'a [F],
+    /// The failed constraints, which are recorded instead of panicking if this is set.
+    pub(crate) failures: Option<Vec<String>>,
 }
 
 impl<'a, F, EF> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF>
@@ -150,7 +245,14 @@ where
     where
         I: Into<Self::ExprEF>,
     {
-        assert_eq!(x.into(), EF::zero(), "constraints must evaluate to zero");
+        let x = x.into();
+        if let Some(failures) = self.failures.as_mut() {
+            if x != EF::zero() {
+                failures.push(format!("{:?} != 0", x));
+            }
+            return;
+        }
+        assert_eq!(x, EF::zero(), "constraints must evaluate to zero");
     }
 }
 
@@ -188,8 +290,12 @@ where
     EF: ExtensionField<F>,
 {
     #[inline]
-    fn debug_constraint(&self, x: F, y: F) {
+    fn debug_constraint(&mut self, x: F, y: F) {
         if x != y {
+            if let Some(failures) = self.failures.as_mut() {
+                failures.push(format!("{:?} != {:?}", x, y));
+                return;
+            }
             let backtrace = std::backtrace::Backtrace::force_capture();
             eprintln!("constraint failed: {:?} != {:?}\n{}", x, y, backtrace);
             panic!();
@@ -243,6 +349,10 @@ where
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
         if x != F::zero() && x != F::one() {
+            if let Some(failures) = self.failures.as_mut() {
+                failures.push(format!("{:?} is not a bool", x));
+                return;
+            }
             let backtrace = std::backtrace::Backtrace::force_capture();
             eprintln!("constraint failed: {:?} is not a bool\n{}", x, backtrace);
             panic!();
@@ -1,5 +1,6 @@
 use itertools::Itertools;
 use p3_air::Air;
+use p3_air::BaseAir;
 use p3_challenger::CanObserve;
 use p3_challenger::FieldChallenger;
 use p3_commit::Pcs;
@@ -14,8 +15,12 @@ use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use thiserror::Error;
 use tracing::instrument;
 
 use super::debug_constraints;
@@ -36,6 +41,7 @@ use super::Chip;
 use super::Com;
 use super::MachineProof;
 use super::PcsProverData;
+use super::Poseidon2Params;
 use super::Prover;
 use super::StarkGenericConfig;
 use super::Val;
@@ -74,29 +80,177 @@ pub struct StarkProvingKey<SC: StarkGenericConfig> {
     pub traces: Vec<RowMajorMatrix<Val<SC>>>,
     pub data: PcsProverData<SC>,
     pub chip_ordering: HashMap<String, usize>,
+    /// The Poseidon2 parameters of the configuration the key was set up with, observed with the
+    /// commitment like those of the verifying key.
+    pub poseidon2_params: Option<Poseidon2Params>,
 }
 
 impl<SC: StarkGenericConfig> StarkProvingKey<SC> {
     pub fn observe_into(&self, challenger: &mut SC::Challenger) {
         challenger.observe(self.commit.clone());
         challenger.observe(self.pc_start);
+        observe_poseidon2_params::<SC>(self.poseidon2_params, challenger);
+    }
+
+    /// A digest of the whole key: the commitment to the preprocessed traces, the starting pc, the
+    /// traces, their prover data, the chip ordering and the Poseidon2 parameters.
+    ///
+    /// The chip ordering is hashed sorted by name, so that the digest does not depend on the
+    /// iteration order of its map.
+    pub fn digest(&self) -> [u8; 32]
+    where
+        PcsProverData<SC>: Serialize,
+    {
+        let chip_ordering = self.chip_ordering.iter().collect::<BTreeMap<_, _>>();
+        let bytes = bincode::serialize(&(
+            &self.commit,
+            self.pc_start,
+            &self.traces,
+            &self.data,
+            chip_ordering,
+            &self.poseidon2_params,
+        ))
+        .unwrap();
+        *blake3::hash(&bytes).as_bytes()
+    }
+}
+
+/// Observes the Poseidon2 parameters of a key after its commitment and starting pc, so that the
+/// challenges of a proof depend on them.
+fn observe_poseidon2_params<SC: StarkGenericConfig>(
+    params: Option<Poseidon2Params>,
+    challenger: &mut SC::Challenger,
+) {
+    if let Some(params) = params {
+        for value in params.to_field_elements::<Val<SC>>() {
+            challenger.observe(value);
+        }
     }
 }
 
+/// The version of the format of the proving keys saved by `StarkMachine::save_pkey`.
+pub const PKEY_FORMAT_VERSION: u32 = 3;
+
+/// The header of a saved proving key.
+#[derive(Serialize, Deserialize)]
+struct PkeyHeader {
+    version: u32,
+    fingerprint: [u8; 32],
+    key_digest: [u8; 32],
+    vk_digest: [u8; 32],
+}
+
+#[derive(Error, Debug)]
+pub enum PkeyCacheError {
+    #[error("io error: {0}")]
+    Io(std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(bincode::Error),
+    #[error("proving key format version {found} does not match {expected}")]
+    VersionMismatch { expected: u32, found: u32 },
+    #[error("proving key was set up by another machine")]
+    FingerprintMismatch,
+    #[error("proving key does not match the digest of its header")]
+    DigestMismatch,
+    #[error("proving key was not set up with the given verifying key")]
+    VkMismatch,
+}
+
+/// The version of the format of serialized verifying keys.
+pub const VK_FORMAT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+#[error("verifying key format version {found} does not match {expected}")]
+pub struct VkVersionMismatch {
+    pub expected: u32,
+    pub found: u32,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound(serialize = "Dom<SC>: Serialize"))]
 #[serde(bound(deserialize = "Dom<SC>: DeserializeOwned"))]
+#[serde(into = "VersionedVerifyingKey<SC>")]
+#[serde(try_from = "VersionedVerifyingKey<SC>")]
 pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
     pub commit: Com<SC>,
     pub pc_start: Val<SC>,
     pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
     pub chip_ordering: HashMap<String, usize>,
+    /// The Poseidon2 parameters of the configuration the key was set up with. Proofs are only
+    /// verified against the key by machines with the same parameters.
+    pub poseidon2_params: Option<Poseidon2Params>,
+}
+
+/// A verifying key as it is serialized, preceded by [VK_FORMAT_VERSION] so that keys serialized
+/// in another format are rejected instead of being misread.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "Dom<SC>: Serialize"))]
+#[serde(bound(deserialize = "Dom<SC>: DeserializeOwned"))]
+struct VersionedVerifyingKey<SC: StarkGenericConfig> {
+    version: u32,
+    commit: Com<SC>,
+    pc_start: Val<SC>,
+    chip_information: Vec<(String, Dom<SC>, Dimensions)>,
+    chip_ordering: HashMap<String, usize>,
+    poseidon2_params: Option<Poseidon2Params>,
+}
+
+impl<SC: StarkGenericConfig> From<StarkVerifyingKey<SC>> for VersionedVerifyingKey<SC> {
+    fn from(vk: StarkVerifyingKey<SC>) -> Self {
+        Self {
+            version: VK_FORMAT_VERSION,
+            commit: vk.commit,
+            pc_start: vk.pc_start,
+            chip_information: vk.chip_information,
+            chip_ordering: vk.chip_ordering,
+            poseidon2_params: vk.poseidon2_params,
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> TryFrom<VersionedVerifyingKey<SC>> for StarkVerifyingKey<SC> {
+    type Error = VkVersionMismatch;
+
+    fn try_from(vk: VersionedVerifyingKey<SC>) -> Result<Self, Self::Error> {
+        if vk.version != VK_FORMAT_VERSION {
+            return Err(VkVersionMismatch {
+                expected: VK_FORMAT_VERSION,
+                found: vk.version,
+            });
+        }
+        Ok(Self {
+            commit: vk.commit,
+            pc_start: vk.pc_start,
+            chip_information: vk.chip_information,
+            chip_ordering: vk.chip_ordering,
+            poseidon2_params: vk.poseidon2_params,
+        })
+    }
 }
 
 impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
     pub fn observe_into(&self, challenger: &mut SC::Challenger) {
         challenger.observe(self.commit.clone());
         challenger.observe(self.pc_start);
+        observe_poseidon2_params::<SC>(self.poseidon2_params, challenger);
+    }
+
+    /// A digest of the whole key, with the chip ordering hashed sorted by name like
+    /// `StarkProvingKey::digest`.
+    pub fn digest(&self) -> [u8; 32]
+    where
+        Dom<SC>: Serialize,
+    {
+        let chip_ordering = self.chip_ordering.iter().collect::<BTreeMap<_, _>>();
+        let bytes = bincode::serialize(&(
+            &self.commit,
+            self.pc_start,
+            &self.chip_information,
+            chip_ordering,
+            &self.poseidon2_params,
+        ))
+        .unwrap();
+        *blake3::hash(&bytes).as_bytes()
     }
 }
 
@@ -229,16 +383,113 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 traces,
                 data,
                 chip_ordering: chip_ordering.clone(),
+                poseidon2_params: self.config.poseidon2_params(),
             },
             StarkVerifyingKey {
                 commit,
                 pc_start,
                 chip_information,
                 chip_ordering,
+                poseidon2_params: self.config.poseidon2_params(),
             },
         )
     }
 
+    /// A fingerprint of the machine, which changes whenever its chips or their layouts change.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&PKEY_FORMAT_VERSION.to_le_bytes());
+        hasher.update(&(self.num_pv_elts as u64).to_le_bytes());
+        for chip in self.chips() {
+            let name = chip.name();
+            hasher.update(&(name.len() as u64).to_le_bytes());
+            hasher.update(name.as_bytes());
+            for size in [
+                chip.width(),
+                chip.preprocessed_width(),
+                chip.permutation_width(),
+                chip.log_quotient_degree(),
+                chip.num_interactions(),
+            ] {
+                hasher.update(&(size as u64).to_le_bytes());
+            }
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Saves a proving key set up by this machine, so that it can be loaded instead of running the
+    /// setup again.
+    ///
+    /// The key is preceded by a header with the format version, the fingerprint of the machine, the
+    /// digest of the whole key and the digest of the verifying key set up with it, which
+    /// `load_pkey` checks.
+    pub fn save_pkey(
+        &self,
+        pk: &StarkProvingKey<SC>,
+        vk: &StarkVerifyingKey<SC>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), PkeyCacheError>
+    where
+        PcsProverData<SC>: Serialize,
+        Dom<SC>: Serialize,
+    {
+        let header = PkeyHeader {
+            version: PKEY_FORMAT_VERSION,
+            fingerprint: self.fingerprint(),
+            key_digest: pk.digest(),
+            vk_digest: vk.digest(),
+        };
+        let mut writer = BufWriter::new(File::create(path).map_err(PkeyCacheError::Io)?);
+        bincode::serialize_into(&mut writer, &header).map_err(PkeyCacheError::Serialization)?;
+        bincode::serialize_into(&mut writer, pk).map_err(PkeyCacheError::Serialization)?;
+        writer.flush().map_err(PkeyCacheError::Io)
+    }
+
+    /// Loads a proving key saved by `save_pkey`.
+    ///
+    /// The key is rejected if it was saved in another format, by a machine with another
+    /// fingerprint, with another verifying key than `vk`, or if it does not match the digest of its
+    /// header.
+    pub fn load_pkey(
+        &self,
+        path: impl AsRef<Path>,
+        vk: &StarkVerifyingKey<SC>,
+    ) -> Result<StarkProvingKey<SC>, PkeyCacheError>
+    where
+        PcsProverData<SC>: Serialize + DeserializeOwned,
+        Dom<SC>: Serialize,
+    {
+        let mut reader = BufReader::new(File::open(path).map_err(PkeyCacheError::Io)?);
+        let header: PkeyHeader =
+            bincode::deserialize_from(&mut reader).map_err(PkeyCacheError::Serialization)?;
+        if header.version != PKEY_FORMAT_VERSION {
+            return Err(PkeyCacheError::VersionMismatch {
+                expected: PKEY_FORMAT_VERSION,
+                found: header.version,
+            });
+        }
+        if header.fingerprint != self.fingerprint() {
+            return Err(PkeyCacheError::FingerprintMismatch);
+        }
+        if header.vk_digest != vk.digest() {
+            return Err(PkeyCacheError::VkMismatch);
+        }
+        let pk: StarkProvingKey<SC> =
+            bincode::deserialize_from(&mut reader).map_err(PkeyCacheError::Serialization)?;
+        if pk.digest() != header.key_digest {
+            return Err(PkeyCacheError::DigestMismatch);
+        }
+
+        // The key shares its commitment and starting pc with the verifying key it was set up with.
+        let shared = |commit: &Com<SC>, pc_start: Val<SC>| {
+            bincode::serialize(&(commit, pc_start)).map_err(PkeyCacheError::Serialization)
+        };
+        if shared(&pk.commit, pk.pc_start)? != shared(&vk.commit, vk.pc_start)? {
+            return Err(PkeyCacheError::VkMismatch);
+        }
+        Ok(pk)
+    }
+
     pub fn shard(
         &self,
         mut record: A::Record,
@@ -305,6 +556,11 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
+        // A proof made with other Poseidon2 parameters has a different transcript.
+        if vk.poseidon2_params != self.config.poseidon2_params() {
+            return Err(MachineVerificationError::ConfigMismatch);
+        }
+
         // Observe the preprocessed commitment.
         vk.observe_into(challenger);
         tracing::debug_span!("observe challenges for all shards").in_scope(|| {
@@ -352,6 +608,53 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         })
     }
 
+    /// Verify each shard proof independently, reporting the first shard whose proof is invalid.
+    ///
+    /// Unlike [`Self::verify`], a failure is reported as
+    /// [`MachineVerificationError::InvalidShardProof`] with the index of the shard proof, and the
+    /// name of the failing chip can be read with [`VerificationError::chip`]. The constraints of a
+    /// chip are folded into a single evaluation, so locating the failing row and constraint
+    /// requires [`Self::debug_constraints`] on the execution record. The cumulative sum is not
+    /// checked.
+    #[instrument("verify shards", level = "info", skip_all)]
+    pub fn verify_shards(
+        &self,
+        vk: &StarkVerifyingKey<SC>,
+        proof: &MachineProof<SC>,
+        challenger: &mut SC::Challenger,
+    ) -> Result<(), MachineVerificationError<SC>>
+    where
+        SC::Challenger: Clone,
+        A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        if proof.shard_proofs.is_empty() {
+            return Err(MachineVerificationError::EmptyProof);
+        }
+
+        // Observe the preprocessed commitment and the challenges of all shards, as the prover did.
+        vk.observe_into(challenger);
+        for shard_proof in proof.shard_proofs.iter() {
+            challenger.observe(shard_proof.commitment.main_commit.clone());
+            challenger.observe_slice(&shard_proof.public_values[0..self.num_pv_elts()]);
+        }
+
+        for (i, shard_proof) in proof.shard_proofs.iter().enumerate() {
+            let chips = self
+                .shard_chips_ordered(&shard_proof.chip_ordering)
+                .collect::<Vec<_>>();
+            Verifier::verify_shard(
+                &self.config,
+                vk,
+                &chips,
+                &mut challenger.clone(),
+                shard_proof,
+            )
+            .map_err(|e| MachineVerificationError::InvalidShardProof(i, e))?;
+        }
+
+        Ok(())
+    }
+
     #[instrument("debug constraints", level = "debug", skip_all)]
     pub fn debug_constraints(
         &self,
@@ -467,6 +770,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
 
 pub enum MachineVerificationError<SC: StarkGenericConfig> {
     InvalidSegmentProof(VerificationError<SC>),
+    InvalidShardProof(usize, VerificationError<SC>),
     InvalidGlobalProof(VerificationError<SC>),
     NonZeroCumulativeSum,
     InvalidPublicValuesDigest,
@@ -475,6 +779,7 @@ pub enum MachineVerificationError<SC: StarkGenericConfig> {
     InvalidPublicValues(&'static str),
     TooManyShards,
     InvalidChipOccurence(String),
+    ConfigMismatch,
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
@@ -483,6 +788,9 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidSegmentProof(e) => {
                 write!(f, "Invalid segment proof: {:?}", e)
             }
+            MachineVerificationError::InvalidShardProof(shard, e) => {
+                write!(f, "Invalid proof of shard {}: {:?}", shard, e)
+            }
             MachineVerificationError::InvalidGlobalProof(e) => {
                 write!(f, "Invalid global proof: {:?}", e)
             }
@@ -507,6 +815,9 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidChipOccurence(s) => {
                 write!(f, "Invalid chip occurence: {}", s)
             }
+            MachineVerificationError::ConfigMismatch => {
+                write!(f, "Verifying key was set up with another configuration")
+            }
         }
     }
 }
@@ -523,23 +834,66 @@ impl<SC: StarkGenericConfig> std::error::Error for MachineVerificationError<SC>
 #[allow(non_snake_case)]
 pub mod tests {
 
+    use crate::air::MachineAir;
+    use crate::air::PublicValues;
+    use crate::air::Word;
+    use crate::air::SP1_PROOF_NUM_PV_ELTS;
     use crate::io::SP1Stdin;
     use crate::runtime::tests::fibonacci_program;
     use crate::runtime::tests::simple_memory_program;
     use crate::runtime::tests::simple_program;
     use crate::runtime::tests::ssz_withdrawals_program;
+    use crate::runtime::ExecutionRecord;
     use crate::runtime::Instruction;
     use crate::runtime::Opcode;
     use crate::runtime::Program;
+    use crate::runtime::Runtime;
+    use crate::runtime::ShardingConfig;
+    use crate::stark::BatchedLogUp;
+    use crate::stark::Chip;
+    use crate::stark::LocalProver;
+    use crate::stark::LogUp;
+    use crate::stark::LookupArgument;
+    use crate::stark::MachineRecord;
+    use crate::stark::MachineVerificationError;
+    use crate::stark::PkeyCacheError;
+    use crate::stark::Poseidon2Params;
+    use crate::stark::Prover;
     use crate::stark::RiscvAir;
+    use crate::stark::StarkGenericConfig;
+    use crate::stark::StarkMachine;
     use crate::stark::StarkProvingKey;
     use crate::stark::StarkVerifyingKey;
+    use crate::stark::PKEY_FORMAT_VERSION;
+    use crate::stark::VK_FORMAT_VERSION;
     use crate::utils;
     use crate::utils::prove;
+    use crate::utils::prove_empty;
     use crate::utils::run_test;
+    use crate::utils::run_test_machine;
     use crate::utils::setup_logger;
     use crate::utils::BabyBearPoseidon2;
     use crate::utils::SP1CoreOpts;
+    use crate::utils::SP1CoreProverError;
+    use p3_baby_bear::BabyBear;
+    use p3_challenger::{CanSample, DuplexChallenger};
+    use p3_commit::{ExtensionMmcs, TwoAdicMultiplicativeCoset};
+    use p3_dft::Radix2DitParallel;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::{AbstractField, Field, PrimeField32};
+    use p3_fri::{FriConfig, TwoAdicFriPcs};
+    use p3_matrix::Matrix;
+    use p3_merkle_tree::FieldMerkleTreeMmcs;
+    use p3_poseidon2::{
+        matmul_internal, DiffusionPermutation, Poseidon2, Poseidon2ExternalMatrixGeneral,
+    };
+    use p3_symmetric::{PaddingFreeSponge, Permutation, TruncatedPermutation};
+    use serde::Serialize;
+    use sp1_primitives::RC_16_30;
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    use super::PkeyHeader;
 
     #[test]
     fn test_simple_prove() {
@@ -696,6 +1050,503 @@ pub mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_max_shards() {
+        setup_logger();
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 1000, false, true),
+            Instruction::new(Opcode::ADD, 29, 29, -1i32 as u32, false, true),
+            Instruction::new(Opcode::BNE, 29, 0, -4i32 as u32, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        for shard_batch_size in [0, 2] {
+            let mut opts = SP1CoreOpts::default();
+            opts.shard_size = 256;
+            opts.shard_batch_size = shard_batch_size;
+            opts.max_shards = Some(2);
+            let result = prove(
+                program.clone(),
+                &SP1Stdin::new(),
+                BabyBearPoseidon2::new(),
+                opts,
+            );
+            match result {
+                Err(SP1CoreProverError::TooManyShards { produced, limit }) => {
+                    assert!(produced > limit);
+                    assert_eq!(limit, 2);
+                }
+                _ => panic!("expected a TooManyShards error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_shards_counts_the_same_shards() {
+        setup_logger();
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 1000, false, true),
+            Instruction::new(Opcode::ADD, 29, 29, -1i32 as u32, false, true),
+            Instruction::new(Opcode::BNE, 29, 0, -4i32 as u32, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 256;
+
+        // The shards the machine proves for the whole execution.
+        let mut runtime = Runtime::new(program.clone(), opts);
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let num_shards = machine
+            .shard(runtime.record, &ShardingConfig::default())
+            .len();
+        assert!(num_shards > 1);
+
+        // Proving without checkpoints, and with a single checkpoint holding every shard, report
+        // the same number of shards.
+        for shard_batch_size in [0, 64] {
+            let mut opts = opts;
+            opts.shard_batch_size = shard_batch_size;
+            opts.max_shards = Some(1);
+            let result = prove(
+                program.clone(),
+                &SP1Stdin::new(),
+                BabyBearPoseidon2::new(),
+                opts,
+            );
+            match result {
+                Err(SP1CoreProverError::TooManyShards { produced, limit }) => {
+                    assert_eq!(produced, num_shards);
+                    assert_eq!(limit, 1);
+                }
+                _ => panic!("expected a TooManyShards error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_shards_reports_failing_shard() {
+        setup_logger();
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 300, false, true),
+            Instruction::new(Opcode::ADD, 29, 29, -1i32 as u32, false, true),
+            Instruction::new(Opcode::BNE, 29, 0, -4i32 as u32, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 256;
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, vk) = machine.setup(runtime.program.as_ref());
+        let mut shards = machine.shard(runtime.record, &ShardingConfig::default());
+        assert!(shards.len() > 2);
+
+        // Claim a wrong next pc for the second shard, which breaks the constraints of its CPU chip.
+        shards[1].public_values.next_pc += 4;
+        let mut challenger = machine.config().challenger();
+        let proof = LocalProver::prove_shards(&machine, &pk, shards, &mut challenger, opts);
+
+        let mut challenger = machine.config().challenger();
+        match machine.verify_shards(&vk, &proof, &mut challenger) {
+            Err(MachineVerificationError::InvalidShardProof(shard, e)) => {
+                assert_eq!(shard, 1);
+                assert_eq!(e.chip(), Some("CPU"));
+            }
+            _ => panic!("expected an InvalidShardProof error"),
+        }
+    }
+
+    #[test]
+    fn test_prove_shards_bounded() {
+        setup_logger();
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 300, false, true),
+            Instruction::new(Opcode::ADD, 29, 29, -1i32 as u32, false, true),
+            Instruction::new(Opcode::BNE, 29, 0, -4i32 as u32, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 256;
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, vk) = machine.setup(runtime.program.as_ref());
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        assert!(shards.len() > 2);
+        let public_values = shards
+            .iter()
+            .map(|shard| shard.public_values::<BabyBear>())
+            .collect::<Vec<_>>();
+
+        let mut challenger = machine.config().challenger();
+        let proof = LocalProver::prove_shards_bounded(&machine, &pk, shards, &mut challenger, 2);
+
+        // The proofs are returned in shard order.
+        assert_eq!(proof.shard_proofs.len(), public_values.len());
+        for (shard_proof, public_values) in proof.shard_proofs.iter().zip(public_values) {
+            assert_eq!(shard_proof.public_values, public_values);
+        }
+        machine
+            .verify(&vk, &proof, &mut machine.config().challenger())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_prove_shard_with_timings() {
+        setup_logger();
+        let mut runtime = Runtime::new(fibonacci_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, _) = machine.setup(runtime.program.as_ref());
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        let shard = &shards[0];
+
+        let config = machine.config();
+        let data = LocalProver::commit_main(config, &machine, shard, shard.index() as usize);
+        let chips = machine
+            .shard_chips_ordered(&data.chip_ordering)
+            .collect::<Vec<_>>();
+        let mut challenger = config.challenger();
+        let (_, timings) =
+            LocalProver::prove_shard_with_timings(config, &pk, &chips, data, &mut challenger);
+
+        // There is a timing for each chip included in the shard.
+        let chip_names = machine
+            .shard_chips(shard)
+            .map(|chip| chip.name())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            timings.0.keys().cloned().collect::<BTreeSet<_>>(),
+            chip_names
+        );
+        let slowest = timings.slowest();
+        assert_eq!(slowest.len(), chip_names.len());
+        assert!(slowest.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_prove_empty() {
+        setup_logger();
+        let (proof, vk) = prove_empty(BabyBearPoseidon2::new()).unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        machine
+            .verify(&vk, &proof, &mut machine.config().challenger())
+            .unwrap();
+
+        // The program halts with exit code zero, without committing anything.
+        assert_eq!(proof.shard_proofs.len(), 1);
+        let public_values = PublicValues::<Word<BabyBear>, BabyBear>::from_vec(
+            proof.shard_proofs[0].public_values.clone(),
+        );
+        assert_eq!(public_values.exit_code, BabyBear::zero());
+        for word in public_values.committed_value_digest {
+            assert_eq!(word, Word([BabyBear::zero(); 4]));
+        }
+
+        // Proving is deterministic.
+        let (other, _) = prove_empty(BabyBearPoseidon2::new()).unwrap();
+        assert_eq!(
+            bincode::serialize(&proof).unwrap(),
+            bincode::serialize(&other).unwrap()
+        );
+    }
+
+    /// The internal diagonal of the width-8 Poseidon2 of [BabyBearPoseidon2Width8], which is only
+    /// used to check that proofs depend on the Poseidon2 parameters.
+    const WIDTH_8_INTERNAL_DIAG: [u32; 8] = [BabyBear::ORDER_U32 - 2, 1, 2, 4, 8, 16, 32, 64];
+
+    /// The internal linear layer of a width-8 Poseidon2 over BabyBear.
+    #[derive(Clone, Default)]
+    struct DiffusionMatrixBabyBear8;
+
+    impl<AF: AbstractField<F = BabyBear>> Permutation<[AF; 8]> for DiffusionMatrixBabyBear8 {
+        fn permute_mut(&self, state: &mut [AF; 8]) {
+            matmul_internal(
+                state,
+                WIDTH_8_INTERNAL_DIAG.map(BabyBear::from_canonical_u32),
+            );
+        }
+    }
+
+    impl<AF: AbstractField<F = BabyBear>> DiffusionPermutation<AF, 8> for DiffusionMatrixBabyBear8 {}
+
+    type Perm8 =
+        Poseidon2<BabyBear, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear8, 8, 7>;
+    type ValMmcs8 = FieldMerkleTreeMmcs<
+        <BabyBear as Field>::Packing,
+        <BabyBear as Field>::Packing,
+        PaddingFreeSponge<Perm8, 8, 4, 4>,
+        TruncatedPermutation<Perm8, 2, 4, 8>,
+        4,
+    >;
+    type Challenge8 = BinomialExtensionField<BabyBear, 4>;
+    type ChallengeMmcs8 = ExtensionMmcs<BabyBear, Challenge8, ValMmcs8>;
+    type Pcs8 = TwoAdicFriPcs<BabyBear, Radix2DitParallel, ValMmcs8, ChallengeMmcs8>;
+
+    /// A configuration hashing with a width-8 Poseidon2, with the round constants of the first
+    /// eight state elements of [BabyBearPoseidon2].
+    #[derive(serde::Deserialize)]
+    #[serde(from = "std::marker::PhantomData<BabyBearPoseidon2Width8>")]
+    struct BabyBearPoseidon2Width8 {
+        perm: Perm8,
+        pcs: Pcs8,
+    }
+
+    impl BabyBearPoseidon2Width8 {
+        const PARAMS: Poseidon2Params = Poseidon2Params {
+            width: 8,
+            rounds_f: 8,
+            rounds_p: 13,
+        };
+
+        fn new() -> Self {
+            let rounds_f = Self::PARAMS.rounds_f;
+            let rounds_p = Self::PARAMS.rounds_p;
+            let internal = rounds_f / 2..rounds_f / 2 + rounds_p;
+            let external_round_constants = RC_16_30
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !internal.contains(i))
+                .map(|(_, rc)| core::array::from_fn(|j| rc[j]))
+                .collect();
+            let internal_round_constants = RC_16_30[internal].iter().map(|rc| rc[0]).collect();
+            let perm = Perm8::new(
+                rounds_f,
+                external_round_constants,
+                Poseidon2ExternalMatrixGeneral,
+                rounds_p,
+                internal_round_constants,
+                DiffusionMatrixBabyBear8,
+            );
+            let val_mmcs = ValMmcs8::new(
+                PaddingFreeSponge::new(perm.clone()),
+                TruncatedPermutation::new(perm.clone()),
+            );
+            let fri_config = FriConfig {
+                log_blowup: 1,
+                num_queries: 100,
+                proof_of_work_bits: 16,
+                mmcs: ChallengeMmcs8::new(val_mmcs.clone()),
+            };
+            let pcs = Pcs8::new(27, Radix2DitParallel {}, val_mmcs, fri_config);
+            Self { perm, pcs }
+        }
+    }
+
+    impl Clone for BabyBearPoseidon2Width8 {
+        fn clone(&self) -> Self {
+            Self::new()
+        }
+    }
+
+    impl serde::Serialize for BabyBearPoseidon2Width8 {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            std::marker::PhantomData::<BabyBearPoseidon2Width8>.serialize(serializer)
+        }
+    }
+
+    impl From<std::marker::PhantomData<BabyBearPoseidon2Width8>> for BabyBearPoseidon2Width8 {
+        fn from(_: std::marker::PhantomData<BabyBearPoseidon2Width8>) -> Self {
+            Self::new()
+        }
+    }
+
+    impl StarkGenericConfig for BabyBearPoseidon2Width8 {
+        type Val = BabyBear;
+        type Domain = TwoAdicMultiplicativeCoset<BabyBear>;
+        type Pcs = Pcs8;
+        type Challenge = Challenge8;
+        type Challenger = DuplexChallenger<BabyBear, Perm8, 8, 4>;
+
+        fn pcs(&self) -> &Self::Pcs {
+            &self.pcs
+        }
+
+        fn challenger(&self) -> Self::Challenger {
+            DuplexChallenger::new(self.perm.clone())
+        }
+
+        fn poseidon2_params(&self) -> Option<Poseidon2Params> {
+            Some(Self::PARAMS)
+        }
+    }
+
+    #[test]
+    fn test_verify_poseidon2_config_mismatch() {
+        setup_logger();
+        let (proof, vk) = prove_empty(BabyBearPoseidon2Width8::new()).unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2Width8::new());
+        machine
+            .verify(&vk, &proof, &mut machine.config().challenger())
+            .unwrap();
+
+        // A key claiming other parameters does not verify the proof, and has another digest.
+        let mut other_vk = vk.clone();
+        other_vk.poseidon2_params = BabyBearPoseidon2::new().poseidon2_params();
+        assert_ne!(other_vk.digest(), vk.digest());
+        let result = machine.verify(&other_vk, &proof, &mut machine.config().challenger());
+        assert!(matches!(
+            result,
+            Err(MachineVerificationError::ConfigMismatch)
+        ));
+
+        // The parameters are observed by the challenger as well, so a proof made against a key
+        // with other parameters has other challenges.
+        let mut challenger = machine.config().challenger();
+        other_vk.observe_into(&mut challenger);
+        let mut expected = machine.config().challenger();
+        vk.observe_into(&mut expected);
+        assert_ne!(
+            CanSample::<BabyBear>::sample(&mut challenger),
+            CanSample::<BabyBear>::sample(&mut expected)
+        );
+    }
+
+    #[test]
+    fn test_verifying_key_format_version() {
+        let (_, vk) = prove_empty(BabyBearPoseidon2::new()).unwrap();
+        let bytes = bincode::serialize(&vk).unwrap();
+        let decoded: StarkVerifyingKey<BabyBearPoseidon2> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.digest(), vk.digest());
+
+        // The version is serialized first, so a key in another format is rejected.
+        let mut bytes = bytes;
+        bytes[0] ^= 1;
+        let err = bincode::deserialize::<StarkVerifyingKey<BabyBearPoseidon2>>(&bytes).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "verifying key format version {} does not match {}",
+                VK_FORMAT_VERSION ^ 1,
+                VK_FORMAT_VERSION
+            )
+        );
+    }
+
+    #[test]
+    fn test_estimate_prove_cost() {
+        setup_logger();
+        let mut runtime = Runtime::new(fibonacci_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        for shard in shards.iter() {
+            let estimate = shard.estimate_prove_cost();
+            let chips = machine.shard_chips(shard).collect::<Vec<_>>();
+            assert_eq!(estimate.chip_rows.len(), chips.len());
+            for chip in chips {
+                let trace = chip.generate_trace(shard, &mut ExecutionRecord::default());
+                assert_eq!(estimate.chip_rows[&chip.name()], trace.height());
+            }
+            assert!(estimate.total_cells > estimate.total_rows);
+            assert!(estimate.fri_cost > estimate.total_cells);
+        }
+    }
+
+    #[test]
+    fn test_pkey_cache() {
+        setup_logger();
+        let program = fibonacci_program();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, vk) = machine.setup(&program);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        machine.save_pkey(&pk, &vk, file.path()).unwrap();
+        let loaded_pk = machine.load_pkey(file.path(), &vk).unwrap();
+        assert_eq!(loaded_pk.digest(), pk.digest());
+
+        // Proving with the loaded key gives the same proof as with a fresh setup.
+        let prove_with = |pk: &StarkProvingKey<BabyBearPoseidon2>| {
+            let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let mut challenger = machine.config().challenger();
+            let opts = SP1CoreOpts::default();
+            machine.prove::<LocalProver<_, _>>(pk, runtime.record, &mut challenger, opts)
+        };
+        let fresh_proof = prove_with(&pk);
+        let cached_proof = prove_with(&loaded_pk);
+        assert_eq!(
+            bincode::serialize(&fresh_proof).unwrap(),
+            bincode::serialize(&cached_proof).unwrap()
+        );
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &cached_proof, &mut challenger).unwrap();
+
+        // A key saved by a machine with other chips is rejected.
+        let chips = RiscvAir::get_all()
+            .into_iter()
+            .skip(1)
+            .map(Chip::new)
+            .collect::<Vec<_>>();
+        let other_machine =
+            StarkMachine::new(BabyBearPoseidon2::new(), chips, SP1_PROOF_NUM_PV_ELTS);
+        assert!(matches!(
+            other_machine.load_pkey(file.path(), &vk),
+            Err(PkeyCacheError::FingerprintMismatch)
+        ));
+
+        // A key is rejected with the verifying key of another program.
+        let (other_pk, other_vk) = machine.setup(&simple_memory_program());
+        assert!(matches!(
+            machine.load_pkey(file.path(), &other_vk),
+            Err(PkeyCacheError::VkMismatch)
+        ));
+
+        // A key whose traces were changed after it was saved is rejected, even if the commitment
+        // it shares with the verifying key is unchanged.
+        let mut tampered_pk = pk.clone();
+        tampered_pk.traces[0].values[0] += BabyBear::one();
+        let header = PkeyHeader {
+            version: PKEY_FORMAT_VERSION,
+            fingerprint: machine.fingerprint(),
+            key_digest: pk.digest(),
+            vk_digest: vk.digest(),
+        };
+        let tampered_file = tempfile::NamedTempFile::new().unwrap();
+        let mut bytes = bincode::serialize(&header).unwrap();
+        bytes.extend(bincode::serialize(&tampered_pk).unwrap());
+        std::fs::write(tampered_file.path(), bytes).unwrap();
+        assert!(matches!(
+            machine.load_pkey(tampered_file.path(), &vk),
+            Err(PkeyCacheError::DigestMismatch)
+        ));
+
+        // A key saved with another verifying key than its own is rejected.
+        machine
+            .save_pkey(&other_pk, &vk, tampered_file.path())
+            .unwrap();
+        assert!(matches!(
+            machine.load_pkey(tampered_file.path(), &vk),
+            Err(PkeyCacheError::VkMismatch)
+        ));
+
+        // A key saved in another format is rejected. The version is the first field of the header.
+        let mut bytes = std::fs::read(file.path()).unwrap();
+        bytes[0] ^= 1;
+        std::fs::write(file.path(), bytes).unwrap();
+        assert!(matches!(
+            machine.load_pkey(file.path(), &vk),
+            Err(PkeyCacheError::VersionMismatch {
+                expected: 3,
+                found: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_lookup_argument_backends() {
+        setup_logger();
+        let backends: [Arc<dyn LookupArgument>; 2] = [Arc::new(BatchedLogUp), Arc::new(LogUp)];
+        for lookup in backends {
+            let mut runtime = Runtime::new(fibonacci_program(), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let machine = RiscvAir::machine_with_lookup_argument(BabyBearPoseidon2::new(), lookup);
+            let (pk, vk) = machine.setup(runtime.program.as_ref());
+            run_test_machine(runtime.record, machine, pk, vk).unwrap();
+        }
+    }
+
     #[test]
     fn test_simple_memory_program_prove() {
         let program = simple_memory_program();
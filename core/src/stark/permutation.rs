@@ -9,6 +9,56 @@ use rayon_scan::ScanParallelIterator;
 
 use crate::{air::MultiTableAirBuilder, lookup::Interaction};
 
+/// A backend for the lookup argument that connects the interactions of all chips.
+///
+/// Both backends provided here are log-derivative arguments: every interaction contributes a
+/// fraction `m / rlc` to a running sum which must vanish across the whole machine. They differ in
+/// how many fractions are combined into a single column of the permutation trace, trading the
+/// width of the permutation trace against the degree of the constraints checking each column.
+pub trait LookupArgument: Send + Sync {
+    /// The name of the lookup argument backend.
+    fn name(&self) -> &'static str;
+
+    /// The number of interactions whose fractions are summed into a single permutation column,
+    /// given the relative log degree of the chip's quotient polynomial.
+    fn batch_size(&self, log_quotient_degree: usize) -> usize;
+}
+
+/// The default lookup argument.
+///
+/// Interactions are batched so that each column of the permutation trace sums as many fractions
+/// as the quotient degree of the chip allows, minimizing the width of the permutation trace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchedLogUp;
+
+impl LookupArgument for BatchedLogUp {
+    fn name(&self) -> &'static str {
+        "BatchedLogUp"
+    }
+
+    fn batch_size(&self, log_quotient_degree: usize) -> usize {
+        1 << log_quotient_degree
+    }
+}
+
+/// A plain log-derivative lookup argument with one permutation column per interaction.
+///
+/// Each column is checked by a degree two constraint, which makes the permutation trace wider but
+/// cheaper to generate and evaluate for chips with many high-multiplicity interactions, such as
+/// the byte lookups sent by the precompiles.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogUp;
+
+impl LookupArgument for LogUp {
+    fn name(&self) -> &'static str {
+        "LogUp"
+    }
+
+    fn batch_size(&self, _: usize) -> usize {
+        1
+    }
+}
+
 #[inline]
 #[allow(clippy::too_many_arguments)]
 pub fn populate_permutation_row<F: PrimeField, EF: ExtensionField<F>>(
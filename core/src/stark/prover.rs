@@ -1,8 +1,11 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::cmp::Reverse;
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use p3_air::Air;
@@ -94,6 +97,7 @@ where
         });
 
         let finished = AtomicU32::new(0);
+        let constraint_timings = Mutex::new(ConstraintTimings::default());
 
         // Generate a proof for each segment. Note that we clone the challenger so we can observe
         // identical global challenges across the segments.
@@ -125,13 +129,14 @@ where
                                     let ordering = data.chip_ordering.clone();
                                     let chips =
                                         machine.shard_chips_ordered(&ordering).collect::<Vec<_>>();
-                                    let proof = Self::prove_shard(
+                                    let (proof, timings) = Self::prove_shard_with_timings(
                                         config,
                                         pk,
                                         &chips,
                                         data,
                                         &mut challenger.clone(),
                                     );
+                                    constraint_timings.lock().unwrap().merge(&timings);
                                     finished.fetch_add(1, Ordering::Relaxed);
                                     proof
                                 })
@@ -141,11 +146,53 @@ where
                 .flatten()
                 .collect::<Vec<_>>()
         });
+        constraint_timings.into_inner().unwrap().log();
 
         MachineProof { shard_proofs }
     }
 }
 
+/// The time spent evaluating the constraints of each chip on its quotient domain while proving,
+/// keyed by chip name.
+///
+/// The quotient computation dominates the proving time of high-degree chips, so this tells which
+/// chips are worth optimizing, next to the trace generation spans.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstraintTimings(pub BTreeMap<String, Duration>);
+
+impl ConstraintTimings {
+    /// Adds the timings of another shard to these.
+    pub fn merge(&mut self, other: &Self) {
+        for (chip_name, duration) in other.0.iter() {
+            *self.0.entry(chip_name.clone()).or_default() += *duration;
+        }
+    }
+
+    /// Returns the chips and their timings, from the slowest to the fastest.
+    pub fn slowest(&self) -> Vec<(&str, Duration)> {
+        let mut timings = self
+            .0
+            .iter()
+            .map(|(chip_name, duration)| (chip_name.as_str(), *duration))
+            .collect::<Vec<_>>();
+        timings.sort_by_key(|&(_, duration)| Reverse(duration));
+        timings
+    }
+
+    /// Returns the total time spent evaluating constraints.
+    pub fn total(&self) -> Duration {
+        self.0.values().sum()
+    }
+
+    /// Logs the timings, from the slowest chip to the fastest.
+    pub fn log(&self) {
+        tracing::debug!("constraint evaluation took {:?} in total", self.total());
+        for (chip_name, duration) in self.slowest() {
+            tracing::debug!("constraint evaluation of chip {}: {:?}", chip_name, duration);
+        }
+    }
+}
+
 pub struct LocalProver<SC, A>(PhantomData<SC>, PhantomData<A>);
 
 impl<SC, A> LocalProver<SC, A>
@@ -228,9 +275,29 @@ where
         config: &SC,
         pk: &StarkProvingKey<SC>,
         chips: &[&MachineChip<SC, A>],
-        mut shard_data: ShardMainData<SC>,
+        shard_data: ShardMainData<SC>,
         challenger: &mut SC::Challenger,
     ) -> ShardProof<SC>
+    where
+        Val<SC>: PrimeField32,
+        SC: Send + Sync,
+        ShardMainData<SC>: DeserializeOwned,
+        A: for<'a> Air<ProverConstraintFolder<'a, SC>>
+            + Air<InteractionBuilder<Val<SC>>>
+            + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        Self::prove_shard_with_timings(config, pk, chips, shard_data, challenger).0
+    }
+
+    /// Proves the shard like [`Self::prove_shard`], and also returns the time spent evaluating
+    /// the constraints of each chip.
+    pub fn prove_shard_with_timings(
+        config: &SC,
+        pk: &StarkProvingKey<SC>,
+        chips: &[&MachineChip<SC, A>],
+        mut shard_data: ShardMainData<SC>,
+        challenger: &mut SC::Challenger,
+    ) -> (ShardProof<SC>, ConstraintTimings)
     where
         Val<SC>: PrimeField32,
         SC: Send + Sync,
@@ -349,52 +416,59 @@ where
         // Compute the quotient values.
         let alpha: SC::Challenge = challenger.sample_ext_element::<SC::Challenge>();
         let parent_span = tracing::debug_span!("compute quotient values");
-        let quotient_values = parent_span.in_scope(|| {
+        let (quotient_values, timings): (Vec<_>, Vec<_>) = parent_span.in_scope(|| {
             quotient_domains
                 .into_par_iter()
                 .enumerate()
                 .map(|(i, quotient_domain)| {
-                    tracing::debug_span!(parent: &parent_span, "compute quotient values for domain")
-                        .in_scope(|| {
-                            let preprocessed_trace_on_quotient_domains = pk
-                                .chip_ordering
-                                .get(&chips[i].name())
-                                .map(|&index| {
-                                    pcs.get_evaluations_on_domain(&pk.data, index, *quotient_domain)
-                                        .to_row_major_matrix()
-                                })
-                                .unwrap_or_else(|| {
-                                    RowMajorMatrix::new_col(vec![
-                                        SC::Val::zero();
-                                        quotient_domain.size()
-                                    ])
-                                });
-                            let main_trace_on_quotient_domains = pcs
-                                .get_evaluations_on_domain(
-                                    &shard_data.main_data,
-                                    i,
-                                    *quotient_domain,
-                                )
-                                .to_row_major_matrix();
-                            let permutation_trace_on_quotient_domains = pcs
-                                .get_evaluations_on_domain(&permutation_data, i, *quotient_domain)
-                                .to_row_major_matrix();
-                            quotient_values(
-                                chips[i],
-                                cumulative_sums[i],
-                                trace_domains[i],
-                                *quotient_domain,
-                                preprocessed_trace_on_quotient_domains,
-                                main_trace_on_quotient_domains,
-                                permutation_trace_on_quotient_domains,
-                                &packed_perm_challenges,
-                                alpha,
-                                &shard_data.public_values,
-                            )
-                        })
+                    let chip_name = chips[i].name();
+                    tracing::debug_span!(
+                        parent: &parent_span,
+                        "compute quotient values for chip",
+                        %chip_name
+                    )
+                    .in_scope(|| {
+                        let preprocessed_trace_on_quotient_domains = pk
+                            .chip_ordering
+                            .get(&chip_name)
+                            .map(|&index| {
+                                pcs.get_evaluations_on_domain(&pk.data, index, *quotient_domain)
+                                    .to_row_major_matrix()
+                            })
+                            .unwrap_or_else(|| {
+                                RowMajorMatrix::new_col(vec![
+                                    SC::Val::zero();
+                                    quotient_domain.size()
+                                ])
+                            });
+                        let main_trace_on_quotient_domains = pcs
+                            .get_evaluations_on_domain(&shard_data.main_data, i, *quotient_domain)
+                            .to_row_major_matrix();
+                        let permutation_trace_on_quotient_domains = pcs
+                            .get_evaluations_on_domain(&permutation_data, i, *quotient_domain)
+                            .to_row_major_matrix();
+
+                        // Only the evaluation of the constraints is timed, not the evaluation of
+                        // the traces on the quotient domain.
+                        let start = Instant::now();
+                        let values = quotient_values(
+                            chips[i],
+                            cumulative_sums[i],
+                            trace_domains[i],
+                            *quotient_domain,
+                            preprocessed_trace_on_quotient_domains,
+                            main_trace_on_quotient_domains,
+                            permutation_trace_on_quotient_domains,
+                            &packed_perm_challenges,
+                            alpha,
+                            &shard_data.public_values,
+                        );
+                        (values, (chip_name, start.elapsed()))
+                    })
                 })
-                .collect::<Vec<_>>()
+                .unzip()
         });
+        let constraint_timings = ConstraintTimings(timings.into_iter().collect());
 
         // Split the quotient values and commit to them.
         let quotient_domains_and_chunks = quotient_domains
@@ -527,7 +601,7 @@ where
             )
             .collect::<Vec<_>>();
 
-        ShardProof::<SC> {
+        let proof = ShardProof::<SC> {
             commitment: ShardCommitment {
                 main_commit: shard_data.main_commit.clone(),
                 permutation_commit,
@@ -539,7 +613,8 @@ where
             opening_proof,
             chip_ordering: shard_data.chip_ordering,
             public_values: shard_data.public_values,
-        }
+        };
+        (proof, constraint_timings)
     }
 
     pub fn commit_shards<F, EF>(
@@ -596,4 +671,84 @@ where
 
         (commitments, shard_main_data)
     }
+
+    /// Proves the shards like [`Prover::prove_shards`], with at most `max_concurrent` shards
+    /// committed or proven at a time, and returns the proofs in shard order.
+    ///
+    /// The main data of a shard is only kept while its proof is in flight, so the traces of at
+    /// most `max_concurrent` shards are in memory at once. The traces are generated twice, once to
+    /// observe the commitments of all the shards, and once again to prove each shard.
+    pub fn prove_shards_bounded(
+        machine: &StarkMachine<SC, A>,
+        pk: &StarkProvingKey<SC>,
+        shards: Vec<A::Record>,
+        challenger: &mut SC::Challenger,
+        max_concurrent: usize,
+    ) -> MachineProof<SC>
+    where
+        Val<SC>: PrimeField32,
+        SC: Send + Sync,
+        OpeningProof<SC>: Send + Sync,
+        A: for<'a> Air<ProverConstraintFolder<'a, SC>>
+            + Air<InteractionBuilder<Val<SC>>>
+            + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        assert!(max_concurrent > 0, "max_concurrent must be positive");
+        let config = machine.config();
+
+        // Observe the preprocessed commitment, then the commitment and public values of each shard.
+        pk.observe_into(challenger);
+        let commitments = map_bounded(shards.iter().collect(), max_concurrent, |shard| {
+            Self::commit_main(config, machine, shard, shard.index() as usize).main_commit
+        });
+        for (commitment, shard) in commitments.into_iter().zip(shards.iter()) {
+            challenger.observe(commitment);
+            challenger.observe_slice(&shard.public_values::<SC::Val>()[0..machine.num_pv_elts()]);
+        }
+
+        // Prove each shard with a clone of the challenger, recommitting to its main data.
+        let challenger = &*challenger;
+        let shard_proofs = map_bounded(shards, max_concurrent, |shard| {
+            let data = Self::commit_main(config, machine, &shard, shard.index() as usize);
+            let chips = machine
+                .shard_chips_ordered(&data.chip_ordering)
+                .collect::<Vec<_>>();
+            Self::prove_shard(config, pk, &chips, data, &mut challenger.clone())
+        });
+
+        MachineProof { shard_proofs }
+    }
+}
+
+/// Maps `f` over `items` on `max_concurrent` threads, each of which takes the next item once it
+/// is done with the previous one, and returns the results in the order of the items.
+fn map_bounded<T, R, F>(items: Vec<T>, max_concurrent: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let num_items = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new((0..num_items).map(|_| None).collect::<Vec<_>>());
+    std::thread::scope(|scope| {
+        for _ in 0..max_concurrent.min(num_items) {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                match next {
+                    Some((i, item)) => {
+                        let result = f(item);
+                        results.lock().unwrap()[i] = Some(result);
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.unwrap())
+        .collect()
 }
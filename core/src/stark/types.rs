@@ -6,6 +6,7 @@ use std::{
 };
 
 use bincode::{deserialize_from, Error};
+use p3_field::PrimeField32;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
@@ -14,6 +15,7 @@ use size::Size;
 use tracing::trace;
 
 use super::{Challenge, Com, OpeningProof, PcsProverData, StarkGenericConfig, Val};
+use crate::air::PublicValues;
 
 pub type QuotientOpenedValues<T> = Vec<T>;
 
@@ -136,6 +138,17 @@ pub struct ShardProof<SC: StarkGenericConfig> {
     pub public_values: Vec<Val<SC>>,
 }
 
+impl<SC: StarkGenericConfig> ShardProof<SC>
+where
+    Val<SC>: PrimeField32,
+{
+    /// The number of cycles of the shard, committed in its public values.
+    pub fn num_cycles(&self) -> u64 {
+        let public_values = PublicValues::from_vec(self.public_values.clone());
+        public_values.num_cycles.as_canonical_u32() as u64
+    }
+}
+
 impl<SC: StarkGenericConfig> Debug for ShardProof<SC> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ShardProof").finish()
@@ -166,6 +179,19 @@ pub struct MachineProof<SC: StarkGenericConfig> {
     pub shard_proofs: Vec<ShardProof<SC>>,
 }
 
+impl<SC: StarkGenericConfig> MachineProof<SC>
+where
+    Val<SC>: PrimeField32,
+{
+    /// The total number of cycles of the proven execution, summed over its shards.
+    ///
+    /// The number of cycles of each shard is a public value constrained by the CPU chip to its
+    /// number of real rows, so it cannot be forged in a proof which verifies.
+    pub fn total_cycles(&self) -> u64 {
+        self.shard_proofs.iter().map(ShardProof::num_cycles).sum()
+    }
+}
+
 impl<SC: StarkGenericConfig> Debug for MachineProof<SC> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Proof")
@@ -468,6 +468,19 @@ impl Display for OpeningShapeError {
     }
 }
 
+impl<SC: StarkGenericConfig> VerificationError<SC> {
+    /// The name of the chip the error was found in, if it is specific to a chip.
+    pub fn chip(&self) -> Option<&str> {
+        match self {
+            VerificationError::OodEvaluationMismatch(chip)
+            | VerificationError::OpeningShapeError(chip, _) => Some(chip),
+            VerificationError::InvalidopeningArgument(_) | VerificationError::MissingCpuChip => {
+                None
+            }
+        }
+    }
+}
+
 impl<SC: StarkGenericConfig> Debug for VerificationError<SC> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -0,0 +1,181 @@
+use crate::runtime::{ExecutionError, Syscall, SyscallCode, SyscallContext};
+
+/// SyscallCyclesRemaining returns an upper bound on the number of clock cycles left before the
+/// runtime starts a new shard.
+///
+/// The runtime moves to the next shard once the clock, plus the cycles of the most expensive
+/// syscall, reaches the shard size, and the bound is the distance to that point. Shards can still
+/// close earlier than the returned value.
+///
+/// The value is not constrained by any chip, and it changes with the prover configuration, so the
+/// syscall can only be called inside an unconstrained block. Programs that want to act on it must
+/// pass it back through the hint stream, and may only use it for scheduling decisions that do not
+/// affect their outputs.
+pub struct SyscallCyclesRemaining;
+
+impl SyscallCyclesRemaining {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallCyclesRemaining {
+    fn execute(&self, ctx: &mut SyscallContext, _arg1: u32, _arg2: u32) -> Option<u32> {
+        if !ctx.rt.unconstrained {
+            ctx.set_error(ExecutionError::UnconstrainedOnlySyscall(
+                SyscallCode::CYCLES_REMAINING,
+            ));
+            return None;
+        }
+        let budget = ctx.rt.shard_size.saturating_sub(ctx.rt.max_syscall_cycles);
+        let mut remaining = budget.saturating_sub(ctx.clk);
+        if let Some(cell_budget) = &ctx.rt.shard_cell_budget {
+            let cycles = cell_budget.max_cycles(ctx.rt.state.shard_cells);
+            let clk = u32::try_from(cycles.saturating_mul(4)).unwrap_or(u32::MAX);
+            remaining = remaining.min(clk);
+        }
+        Some(remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{
+        ExecutionError, Instruction, Opcode, Program, Runtime, ShardCellBudget, SyscallCode,
+    };
+    use crate::utils::SP1CoreOpts;
+
+    const HINT_PTR: u32 = 0x1000;
+
+    /// Instructions that read the remaining cycles inside an unconstrained block, and write them
+    /// to the hint stream. The value is read at the 5th cycle of the block.
+    fn cycles_remaining_hint() -> Vec<Instruction> {
+        vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::ENTER_UNCONSTRAINED as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            // Skip the block once the runtime leaves it and resets t0.
+            Instruction::new(Opcode::BEQ, 5, 0, 48, false, true),
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CYCLES_REMAINING as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 29, 0, HINT_PTR, false, true),
+            Instruction::new(Opcode::SW, 5, 29, 0, false, true),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HINT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::EXIT_UNCONSTRAINED as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]
+    }
+
+    fn hint(runtime: &Runtime, index: usize) -> u32 {
+        u32::from_le_bytes(runtime.state.input_stream[index][..].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_cycles_remaining() {
+        // Read the remaining cycles at the start of the shard, run a few instructions, and read
+        // them again.
+        let num_padding = 42;
+        let mut instructions = cycles_remaining_hint();
+        for _ in 0..num_padding {
+            instructions.push(Instruction::new(Opcode::ADD, 29, 29, 1, false, true));
+        }
+        instructions.extend(cycles_remaining_hint());
+        let program = Program::new(instructions, 0, 0);
+
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 64;
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+
+        // The first read is the 5th cycle of the first shard.
+        let budget = runtime.shard_size - runtime.max_syscall_cycles;
+        let start = hint(&runtime, 0);
+        assert_eq!(start, budget - 16);
+
+        // Only the first three instructions of a block run outside of it, so the second read is
+        // `num_padding + 3` cycles later, near the end of the shard.
+        let end = hint(&runtime, 1);
+        assert_eq!(end, start - 4 * (num_padding + 3));
+        assert!(end < 4 * 4);
+        assert_eq!(runtime.state.current_shard, 1);
+    }
+
+    #[test]
+    fn test_cycles_remaining_cell_budget() {
+        // Read the remaining cycles, then run adds until the cell budget closes the shard.
+        let mut instructions = cycles_remaining_hint();
+        for _ in 0..256 {
+            instructions.push(Instruction::new(Opcode::ADD, 29, 29, 1, false, true));
+        }
+        let program = Program::new(instructions, 0, 0);
+
+        let add_cells = ShardCellBudget::new(0).cycle_cells(Opcode::ADD, None);
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 20;
+        opts.shard_cell_budget = Some(64 * add_cells as usize);
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+
+        // The hint is below the clock-based one, and bounds the cycles the shard actually ran
+        // after the two cycles that entered the unconstrained block.
+        let hint = hint(&runtime, 0);
+        let budget = runtime.shard_size - runtime.max_syscall_cycles;
+        assert!(hint < budget - 16);
+        let first_shard = runtime.record.cpu_events[0].shard;
+        let cycles = runtime
+            .record
+            .cpu_events
+            .iter()
+            .filter(|event| event.shard == first_shard)
+            .count() as u32;
+        assert!(cycles > 2);
+        assert!(hint >= 4 * (cycles - 2));
+    }
+
+    #[test]
+    fn test_cycles_remaining_constrained() {
+        let instructions = vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CYCLES_REMAINING as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::UnconstrainedOnlySyscall(
+                SyscallCode::CYCLES_REMAINING
+            ))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::runtime::{Syscall, SyscallCode, SyscallContext};
+
+/// SyscallHasPrecompile returns 1 if the runtime supports the syscall whose code is in `arg1`, and
+/// 0 otherwise.
+///
+/// A syscall is supported if the runtime implements it and, if the program declares a manifest,
+/// the manifest allows it. This lets a single program run on prover configurations with different
+/// sets of precompiles, falling back to software when one is missing. Codes which are not syscall
+/// numbers are reported as unsupported rather than failing execution.
+///
+/// The CPU table only constrains the result to be a boolean: like `HINT_LEN`, its value is supplied
+/// by the prover, so programs must be correct on either path.
+pub struct SyscallHasPrecompile;
+
+impl SyscallHasPrecompile {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallHasPrecompile {
+    fn execute(&self, ctx: &mut SyscallContext, arg1: u32, _: u32) -> Option<u32> {
+        let supported = SyscallCode::from_u32(arg1).is_ok_and(|code| {
+            ctx.rt.syscall_map.contains_key(&code)
+                && ctx
+                    .rt
+                    .program
+                    .manifest
+                    .as_ref()
+                    .map_or(true, |manifest| manifest.allows(code))
+        });
+        Some(supported as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{
+        Instruction, Opcode, Program, Register, Runtime, SyscallCode, SyscallManifest,
+    };
+    use crate::utils::{run_test, SP1CoreOpts};
+
+    /// Queries whether each of `codes` is supported, storing the results in x20, x21, ...
+    fn has_precompile_program(codes: &[u32]) -> Program {
+        let mut instructions = vec![];
+        for (i, &code) in codes.iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::HAS_PRECOMPILE as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, code, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+                Instruction::new(Opcode::ADD, 20 + i as u32, 5, 0, false, true),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_has_precompile() {
+        let codes = [SyscallCode::UINT256_MUL as u32, 0x00_00_01_FF];
+        let mut runtime = Runtime::new(has_precompile_program(&codes), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X20), 1);
+        assert_eq!(runtime.register(Register::X21), 0);
+    }
+
+    #[test]
+    fn test_has_precompile_respects_manifest() {
+        let codes = [
+            SyscallCode::UINT256_MUL as u32,
+            SyscallCode::KECCAK_PERMUTE as u32,
+        ];
+        let mut program = has_precompile_program(&codes);
+        program.manifest = Some(SyscallManifest::new([SyscallCode::UINT256_MUL]));
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X20), 1);
+        assert_eq!(runtime.register(Register::X21), 0);
+    }
+
+    #[test]
+    fn test_has_precompile_prove() {
+        let codes = [SyscallCode::SHA_EXTEND as u32, 0x00_00_01_FF];
+        run_test(has_precompile_program(&codes)).unwrap();
+    }
+}
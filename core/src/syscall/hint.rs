@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use nohash_hasher::BuildNoHashHasher;
+
 use crate::runtime::{Syscall, SyscallContext};
 
 pub struct SyscallHintLen;
@@ -43,39 +47,76 @@ impl Syscall for SyscallHintRead {
             len,
             "hint input stream read length mismatch"
         );
-        assert_eq!(ptr % 4, 0, "hint read address not aligned to 4 bytes");
-        // Iterate through the vec in 4-byte chunks
-        for i in (0..len).step_by(4) {
-            // Get each byte in the chunk
-            let b1 = vec[i as usize];
-            // In case the vec is not a multiple of 4, right-pad with 0s. This is fine because we
-            // are assuming the word is uninitialized, so filling it with 0s makes sense.
-            let b2 = vec.get(i as usize + 1).copied().unwrap_or(0);
-            let b3 = vec.get(i as usize + 2).copied().unwrap_or(0);
-            let b4 = vec.get(i as usize + 3).copied().unwrap_or(0);
-            let word = u32::from_le_bytes([b1, b2, b3, b4]);
-
-            // Save the data into runtime state so the runtime will use the desired data instead of
-            // 0 when first reading/writing from this address.
-            ctx.rt
-                .state
-                .uninitialized_memory
-                .entry(ptr + i)
-                .and_modify(|_| panic!("hint read address is initialized already"))
-                .or_insert(word);
+        write_uninitialized(&mut ctx.rt.state.uninitialized_memory, ptr, vec);
+        None
+    }
+}
+
+pub struct SyscallReadHint;
+
+/// SyscallReadHint reads the next slice of the private hint stream into uninitialized memory.
+///
+/// Unlike the input stream, hints are not hashed into the digest of the inputs that the program
+/// commits, so they can carry nondeterministic advice, e.g. a square root, without revealing it.
+/// Nothing constrains the value of a hint: the program must check it, e.g. by squaring the root.
+impl SyscallReadHint {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallReadHint {
+    fn execute(&self, ctx: &mut SyscallContext, ptr: u32, len: u32) -> Option<u32> {
+        if ctx.rt.state.hint_stream_ptr >= ctx.rt.state.hint_stream.len() {
+            panic!("not enough vecs in private hint stream");
         }
+        let vec = &ctx.rt.state.hint_stream[ctx.rt.state.hint_stream_ptr];
+        ctx.rt.state.hint_stream_ptr += 1;
+        assert!(
+            !ctx.rt.unconstrained,
+            "read hint should not be used in a unconstrained block"
+        );
+        assert_eq!(vec.len() as u32, len, "private hint read length mismatch");
+        write_uninitialized(&mut ctx.rt.state.uninitialized_memory, ptr, vec);
         None
     }
 }
 
+/// Writes `vec` into the uninitialized memory starting at the word-aligned `ptr`.
+fn write_uninitialized(
+    uninitialized_memory: &mut HashMap<u32, u32, BuildNoHashHasher<u32>>,
+    ptr: u32,
+    vec: &[u8],
+) {
+    assert_eq!(ptr % 4, 0, "hint read address not aligned to 4 bytes");
+    // Iterate through the vec in 4-byte chunks
+    for i in (0..vec.len() as u32).step_by(4) {
+        // Get each byte in the chunk
+        let b1 = vec[i as usize];
+        // In case the vec is not a multiple of 4, right-pad with 0s. This is fine because we
+        // are assuming the word is uninitialized, so filling it with 0s makes sense.
+        let b2 = vec.get(i as usize + 1).copied().unwrap_or(0);
+        let b3 = vec.get(i as usize + 2).copied().unwrap_or(0);
+        let b4 = vec.get(i as usize + 3).copied().unwrap_or(0);
+        let word = u32::from_le_bytes([b1, b2, b3, b4]);
+
+        // Save the data into runtime state so the runtime will use the desired data instead of
+        // 0 when first reading/writing from this address.
+        uninitialized_memory
+            .entry(ptr + i)
+            .and_modify(|_| panic!("hint read address is initialized already"))
+            .or_insert(word);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::RngCore;
 
     use crate::{
         io::SP1Stdin,
-        runtime::Program,
-        utils::{prove, setup_logger, BabyBearPoseidon2, SP1CoreOpts},
+        runtime::{ExecutionError, Instruction, Opcode, Program, Register, Runtime, SyscallCode},
+        utils::{prove, run_test_io, setup_logger, BabyBearPoseidon2, SP1CoreOpts},
     };
 
     const HINT_IO_ELF: &[u8] =
@@ -98,4 +139,52 @@ mod tests {
         let config = BabyBearPoseidon2::new();
         prove(program, &stdin, config, SP1CoreOpts::default()).unwrap();
     }
+
+    const SQUARE: u32 = 152_399_025;
+
+    /// A program which reads a square root of `SQUARE` from the private hints, and hits a
+    /// breakpoint unless it squares to `SQUARE`.
+    fn sqrt_hint_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::READ_HINT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::LW, 30, 10, 0, false, true),
+            Instruction::new(Opcode::MUL, 31, 30, 30, false, false),
+            Instruction::new(Opcode::ADD, 29, 0, SQUARE, false, true),
+            Instruction::new(Opcode::BEQ, 31, 29, 8, false, true),
+            Instruction::new(Opcode::EBREAK, 0, 0, 0, false, false),
+            Instruction::new(Opcode::ADD, 28, 0, 1, false, true),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_read_hint() {
+        setup_logger();
+
+        // A valid square root is accepted, and is not read from the input stream.
+        let mut runtime = Runtime::new(sqrt_hint_program(), SP1CoreOpts::default());
+        runtime.write_stdin(&0u32);
+        runtime.write_hints(&[12345u32.to_le_bytes().to_vec()]);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X30), 12345);
+        assert_eq!(runtime.register(Register::X28), 1);
+        assert_eq!(runtime.state.hint_stream_ptr, 1);
+        assert_eq!(runtime.state.input_stream_ptr, 0);
+
+        // An invalid one is rejected by the program's own check.
+        let mut runtime = Runtime::new(sqrt_hint_program(), SP1CoreOpts::default());
+        runtime.write_hints(&[12346u32.to_le_bytes().to_vec()]);
+        assert!(matches!(runtime.run(), Err(ExecutionError::Breakpoint())));
+    }
+
+    #[test]
+    fn test_read_hint_prove() {
+        setup_logger();
+        let mut stdin = SP1Stdin::new();
+        stdin.write_hint_slice(&12345u32.to_le_bytes());
+        run_test_io(sqrt_hint_program(), stdin).unwrap();
+    }
 }
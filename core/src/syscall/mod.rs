@@ -1,14 +1,20 @@
 mod commit;
+mod cycles;
 mod halt;
+mod has_precompile;
 mod hint;
+mod shard;
 pub mod precompiles;
 mod unconstrained;
 mod verify;
 mod write;
 
 pub use commit::*;
+pub use cycles::*;
 pub use halt::*;
+pub use has_precompile::*;
 pub use hint::*;
+pub use shard::*;
 pub use unconstrained::*;
 pub use verify::*;
 pub use write::*;
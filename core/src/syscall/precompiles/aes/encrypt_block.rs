@@ -0,0 +1,702 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::AES_SBOX;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{bytes_to_words_le, pad_rows, words_to_bytes_le, words_to_bytes_le_vec};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the AesEncryptBlockCols.
+const NUM_COLS: usize = size_of::<AesEncryptBlockCols<u8>>();
+
+/// The number of words in an AES block.
+pub const AES_BLOCK_WORDS: usize = 4;
+
+/// The number of bytes in an AES block.
+pub const AES_BLOCK_BYTES: usize = AES_BLOCK_WORDS * 4;
+
+/// The maximum number of rounds, used by AES-256.
+pub const AES_MAX_ROUNDS: usize = 14;
+
+/// The source index of each byte of the state after `ShiftRows`.
+///
+/// The state is stored column by column, so byte `r + 4c` is row `r` of column `c`, and row `r` is
+/// rotated left by `r` positions.
+const SHIFT_ROWS: [usize; AES_BLOCK_BYTES] = [0, 5, 10, 15, 4, 9, 14, 3, 8, 13, 2, 7, 12, 1, 6, 11];
+
+/// Multiplies a byte by `x` in GF(2^8) modulo the AES polynomial `x^8 + x^4 + x^3 + x + 1`.
+pub const fn xtime(a: u8) -> u8 {
+    (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Encrypts a block with an expanded key schedule holding the `num_rounds + 1` round keys.
+pub fn aes_encrypt_block(block: [u8; AES_BLOCK_BYTES], round_keys: &[u8]) -> [u8; AES_BLOCK_BYTES] {
+    let num_rounds = round_keys.len() / AES_BLOCK_BYTES - 1;
+    let mut state = block;
+    for (s, k) in state.iter_mut().zip(round_keys.iter()) {
+        *s ^= k;
+    }
+    for round in 1..=num_rounds {
+        let sbox = state.map(|b| AES_SBOX[b as usize]);
+        let mut shifted = SHIFT_ROWS.map(|i| sbox[i]);
+        if round != num_rounds {
+            for column in shifted.chunks_exact_mut(4) {
+                let s: [u8; 4] = column.try_into().unwrap();
+                let sum = s[0] ^ s[1] ^ s[2] ^ s[3];
+                for k in 0..4 {
+                    column[k] = s[k] ^ sum ^ xtime(s[k] ^ s[(k + 1) % 4]);
+                }
+            }
+        }
+        let round_key = &round_keys[round * AES_BLOCK_BYTES..(round + 1) * AES_BLOCK_BYTES];
+        for (s, (b, k)) in state.iter_mut().zip(shifted.iter().zip(round_key.iter())) {
+            *s = b ^ k;
+        }
+    }
+    state
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AesEncryptBlockEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub state_ptr: u32,
+    pub round_keys_ptr: u32,
+    pub num_rounds: u32,
+    pub state: Vec<u32>,
+    pub round_keys: Vec<u32>,
+    pub state_memory_records: Vec<MemoryWriteRecord>,
+    pub num_rounds_memory_record: MemoryReadRecord,
+    pub round_keys_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A precompile encrypting a single block with AES-128, AES-192 or AES-256.
+///
+/// The state is read from `state_ptr` and overwritten with the ciphertext. The buffer at
+/// `round_keys_ptr` holds the number of rounds (10, 12 or 14) followed by the expanded key schedule
+/// of `num_rounds + 1` round keys. All rounds are laid out in a single row, and the rounds that are
+/// not used by the key size are left empty.
+#[derive(Default)]
+pub struct AesEncryptBlockChip;
+
+impl AesEncryptBlockChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the `MixColumns` step on one column of the state.
+///
+/// With `t = s_0 ^ s_1 ^ s_2 ^ s_3`, each output byte is `s_k ^ t ^ xtime(s_k ^ s_{k+1})`.
+#[derive(Debug, Clone, Copy, AlignedBorrow)]
+#[repr(C)]
+pub struct MixColumnCols<T> {
+    /// The sums `s_k ^ s_{k+1}` of adjacent bytes.
+    pub pairs: [T; 4],
+
+    /// The most significant bit of each of the pairs.
+    pub msb: [T; 4],
+
+    /// The products of the pairs with `x` in GF(2^8).
+    pub xtime: [T; 4],
+
+    /// The sum of the bytes of the column, computed as `pairs[0] ^ pairs[2]`.
+    pub sum: T,
+
+    /// The sums `s_k ^ t`.
+    pub partial: [T; 4],
+
+    /// The output bytes of the column.
+    pub output: [T; 4],
+}
+
+/// A set of columns for a single AES round.
+#[derive(Debug, Clone, Copy, AlignedBorrow)]
+#[repr(C)]
+pub struct AesRoundCols<T> {
+    /// Whether the round is used by the key size of the operation.
+    pub is_active: T,
+
+    /// The memory columns of the round key.
+    pub round_key_memory: [MemoryReadCols<T>; AES_BLOCK_WORDS],
+
+    /// The result of `SubBytes` on the input state.
+    pub sbox: [T; AES_BLOCK_BYTES],
+
+    /// The `MixColumns` columns for each column of the state after `ShiftRows`.
+    pub mix_columns: [MixColumnCols<T>; 4],
+
+    /// The state before `AddRoundKey`, which skips `MixColumns` in the final round.
+    pub pre_key: [T; AES_BLOCK_BYTES],
+
+    /// The output state of the round.
+    pub output: [T; AES_BLOCK_BYTES],
+}
+
+/// A set of columns for the AesEncryptBlock operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct AesEncryptBlockCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the state, which is overwritten with the ciphertext.
+    pub state_ptr: T,
+
+    /// The pointer to the number of rounds followed by the round keys.
+    pub round_keys_ptr: T,
+
+    /// Flags for the number of rounds being 10, 12 or 14 respectively.
+    pub is_aes128: T,
+    pub is_aes192: T,
+    pub is_aes256: T,
+
+    /// The memory column of the number of rounds.
+    pub num_rounds_memory: MemoryReadCols<T>,
+
+    /// The memory columns of the state, which is written to with the ciphertext.
+    pub state_memory: [MemoryWriteCols<T>; AES_BLOCK_WORDS],
+
+    /// The memory columns of the initial round key.
+    pub initial_key_memory: [MemoryReadCols<T>; AES_BLOCK_WORDS],
+
+    /// The state after the initial `AddRoundKey`.
+    pub initial: [T; AES_BLOCK_BYTES],
+
+    /// The columns of each round.
+    pub rounds: [AesRoundCols<T>; AES_MAX_ROUNDS],
+
+    pub is_real: T,
+}
+
+/// Records a byte lookup and returns its result.
+fn byte_lookup(
+    blu: &mut impl ByteRecord,
+    shard: u32,
+    channel: u32,
+    opcode: ByteOpcode,
+    b: u8,
+    c: u8,
+) -> u8 {
+    let a = match opcode {
+        ByteOpcode::XOR => b ^ c,
+        ByteOpcode::MSB => b >> 7,
+        ByteOpcode::AesSbox => AES_SBOX[b as usize],
+        _ => unreachable!(),
+    };
+    blu.add_byte_lookup_event(ByteLookupEvent::new(
+        shard, channel, opcode, a as u32, 0, b as u32, c as u32,
+    ));
+    a
+}
+
+impl<F: PrimeField32> MixColumnCols<F> {
+    pub fn populate(
+        &mut self,
+        blu: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        s: &[u8],
+    ) -> [u8; 4] {
+        let mut output = [0u8; 4];
+        let pairs: [u8; 4] = core::array::from_fn(|k| {
+            byte_lookup(blu, shard, channel, ByteOpcode::XOR, s[k], s[(k + 1) % 4])
+        });
+        let sum = byte_lookup(blu, shard, channel, ByteOpcode::XOR, pairs[0], pairs[2]);
+        self.sum = F::from_canonical_u8(sum);
+        for k in 0..4 {
+            let msb = byte_lookup(blu, shard, channel, ByteOpcode::MSB, pairs[k], 0);
+            let doubled = pairs[k] << 1;
+            let xtime = byte_lookup(blu, shard, channel, ByteOpcode::XOR, doubled, msb * 0x1b);
+            let partial = byte_lookup(blu, shard, channel, ByteOpcode::XOR, s[k], sum);
+            output[k] = byte_lookup(blu, shard, channel, ByteOpcode::XOR, partial, xtime);
+
+            self.pairs[k] = F::from_canonical_u8(pairs[k]);
+            self.msb[k] = F::from_canonical_u8(msb);
+            self.xtime[k] = F::from_canonical_u8(xtime);
+            self.partial[k] = F::from_canonical_u8(partial);
+            self.output[k] = F::from_canonical_u8(output[k]);
+        }
+        output
+    }
+}
+
+impl<F: PrimeField32> AesRoundCols<F> {
+    pub fn populate(
+        &mut self,
+        blu: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        input: [u8; AES_BLOCK_BYTES],
+        round_key: &[u8],
+        is_final: bool,
+    ) -> [u8; AES_BLOCK_BYTES] {
+        self.is_active = F::one();
+
+        let sbox = input.map(|b| byte_lookup(blu, shard, channel, ByteOpcode::AesSbox, b, 0));
+        let shifted = SHIFT_ROWS.map(|i| sbox[i]);
+        let mut mixed = [0u8; AES_BLOCK_BYTES];
+        for (c, cols) in self.mix_columns.iter_mut().enumerate() {
+            let column = cols.populate(blu, shard, channel, &shifted[4 * c..4 * c + 4]);
+            mixed[4 * c..4 * c + 4].copy_from_slice(&column);
+        }
+        let pre_key = if is_final { shifted } else { mixed };
+
+        let mut output = [0u8; AES_BLOCK_BYTES];
+        for j in 0..AES_BLOCK_BYTES {
+            output[j] = byte_lookup(
+                blu,
+                shard,
+                channel,
+                ByteOpcode::XOR,
+                pre_key[j],
+                round_key[j],
+            );
+            self.sbox[j] = F::from_canonical_u8(sbox[j]);
+            self.pre_key[j] = F::from_canonical_u8(pre_key[j]);
+            self.output[j] = F::from_canonical_u8(output[j]);
+        }
+        output
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for AesEncryptBlockChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "AesEncryptBlock".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .aes_encrypt_block_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut AesEncryptBlockCols<F> = row.as_mut_slice().borrow_mut();
+                let blu = &mut new_byte_lookup_events;
+                let (shard, channel) = (event.shard, event.channel);
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(shard);
+                cols.channel = F::from_canonical_u32(channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+                cols.round_keys_ptr = F::from_canonical_u32(event.round_keys_ptr);
+                cols.is_aes128 = F::from_bool(event.num_rounds == 10);
+                cols.is_aes192 = F::from_bool(event.num_rounds == 12);
+                cols.is_aes256 = F::from_bool(event.num_rounds == 14);
+
+                // Populate memory columns.
+                cols.num_rounds_memory
+                    .populate(channel, event.num_rounds_memory_record, blu);
+                for i in 0..AES_BLOCK_WORDS {
+                    cols.state_memory[i].populate(channel, event.state_memory_records[i], blu);
+                    cols.initial_key_memory[i].populate(
+                        channel,
+                        event.round_keys_memory_records[i],
+                        blu,
+                    );
+                }
+
+                // Populate the initial AddRoundKey and the rounds.
+                let round_keys = words_to_bytes_le_vec(&event.round_keys);
+                let mut state: [u8; AES_BLOCK_BYTES] = words_to_bytes_le(&event.state);
+                for j in 0..AES_BLOCK_BYTES {
+                    state[j] = byte_lookup(
+                        blu,
+                        shard,
+                        channel,
+                        ByteOpcode::XOR,
+                        state[j],
+                        round_keys[j],
+                    );
+                    cols.initial[j] = F::from_canonical_u8(state[j]);
+                }
+                let num_rounds = event.num_rounds as usize;
+                for round in 1..=num_rounds {
+                    let round_cols = &mut cols.rounds[round - 1];
+                    for i in 0..AES_BLOCK_WORDS {
+                        round_cols.round_key_memory[i].populate(
+                            channel,
+                            event.round_keys_memory_records[round * AES_BLOCK_WORDS + i],
+                            blu,
+                        );
+                    }
+                    let round_key =
+                        &round_keys[round * AES_BLOCK_BYTES..(round + 1) * AES_BLOCK_BYTES];
+                    state = round_cols.populate(
+                        blu,
+                        shard,
+                        channel,
+                        state,
+                        round_key,
+                        round == num_rounds,
+                    );
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut AesEncryptBlockCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.aes_encrypt_block_events.is_empty()
+    }
+}
+
+impl Syscall for AesEncryptBlockChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let state_ptr = arg1;
+        if state_ptr % 4 != 0 {
+            panic!();
+        }
+        let round_keys_ptr = arg2;
+        if round_keys_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the state with slice_unsafe since it is overwritten with the ciphertext.
+        let state = rt.slice_unsafe(state_ptr, AES_BLOCK_WORDS);
+
+        let (num_rounds_memory_record, num_rounds) = rt.mr(round_keys_ptr);
+        if !matches!(num_rounds, 10 | 12 | 14) {
+            panic!("invalid number of AES rounds: {}", num_rounds);
+        }
+        let (round_keys_memory_records, round_keys) = rt.mr_slice(
+            round_keys_ptr + 4,
+            (num_rounds as usize + 1) * AES_BLOCK_WORDS,
+        );
+
+        let ciphertext = aes_encrypt_block(
+            words_to_bytes_le(&state),
+            &words_to_bytes_le_vec(&round_keys),
+        );
+        let ciphertext = bytes_to_words_le::<AES_BLOCK_WORDS>(&ciphertext);
+
+        // Write the ciphertext to the state and keep track of the memory records.
+        let state_memory_records = rt.mw_slice(state_ptr, &ciphertext);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .aes_encrypt_block_events
+            .push(AesEncryptBlockEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                state_ptr,
+                round_keys_ptr,
+                num_rounds,
+                state,
+                round_keys,
+                state_memory_records,
+                num_rounds_memory_record,
+                round_keys_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for AesEncryptBlockChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for AesEncryptBlockChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &AesEncryptBlockCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &AesEncryptBlockCols<AB::Var> = (*next).borrow();
+
+        let xor = ByteOpcode::XOR.as_field::<AB::F>();
+        let msb = ByteOpcode::MSB.as_field::<AB::F>();
+        let sbox = ByteOpcode::AesSbox.as_field::<AB::F>();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Exactly one of the round count flags is set on real rows.
+        builder.assert_bool(local.is_aes128);
+        builder.assert_bool(local.is_aes192);
+        builder.assert_bool(local.is_aes256);
+        builder.assert_eq(
+            local.is_aes128 + local.is_aes192 + local.is_aes256,
+            local.is_real,
+        );
+
+        // The number of rounds read from memory matches the flags.
+        let num_rounds = local.num_rounds_memory.value();
+        builder.when(local.is_real).assert_eq(
+            num_rounds[0],
+            local.is_aes128 * AB::F::from_canonical_u32(10)
+                + local.is_aes192 * AB::F::from_canonical_u32(12)
+                + local.is_aes256 * AB::F::from_canonical_u32(14),
+        );
+        for limb in num_rounds.0[1..].iter() {
+            builder.when(local.is_real).assert_zero(*limb);
+        }
+
+        // The initial AddRoundKey.
+        for j in 0..AES_BLOCK_BYTES {
+            builder.send_byte(
+                xor,
+                local.initial[j],
+                local.state_memory[j / 4].prev_value()[j % 4],
+                local.initial_key_memory[j / 4].value()[j % 4],
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        let mut input = local.initial.map(Into::<AB::Expr>::into);
+        for (i, round) in local.rounds.iter().enumerate() {
+            let round_number = i + 1;
+
+            // A round is active if the key size uses it, and it is final for the last round.
+            let (is_active, is_final): (AB::Expr, AB::Expr) = match round_number {
+                1..=9 => (local.is_real.into(), AB::Expr::zero()),
+                10 => (local.is_real.into(), local.is_aes128.into()),
+                11 => (local.is_aes192 + local.is_aes256, AB::Expr::zero()),
+                12 => (local.is_aes192 + local.is_aes256, local.is_aes192.into()),
+                13 => (local.is_aes256.into(), AB::Expr::zero()),
+                _ => (local.is_aes256.into(), local.is_aes256.into()),
+            };
+            builder.assert_eq(round.is_active, is_active);
+
+            // SubBytes.
+            for j in 0..AES_BLOCK_BYTES {
+                builder.send_byte(
+                    sbox,
+                    round.sbox[j],
+                    input[j].clone(),
+                    AB::Expr::zero(),
+                    local.shard,
+                    local.channel,
+                    round.is_active,
+                );
+            }
+
+            // ShiftRows is a fixed permutation of the bytes.
+            let shifted = SHIFT_ROWS.map(|j| round.sbox[j]);
+
+            // MixColumns.
+            for (c, cols) in round.mix_columns.iter().enumerate() {
+                let s = &shifted[4 * c..4 * c + 4];
+                for k in 0..4 {
+                    builder.send_byte(
+                        xor,
+                        cols.pairs[k],
+                        s[k],
+                        s[(k + 1) % 4],
+                        local.shard,
+                        local.channel,
+                        round.is_active,
+                    );
+
+                    // xtime(a) = (2a mod 256) ^ (0x1b if the msb of a is set).
+                    builder.send_byte(
+                        msb,
+                        cols.msb[k],
+                        cols.pairs[k],
+                        AB::Expr::zero(),
+                        local.shard,
+                        local.channel,
+                        round.is_active,
+                    );
+                    let doubled = (cols.pairs[k] - cols.msb[k] * AB::F::from_canonical_u32(128))
+                        * AB::F::two();
+                    builder.send_byte(
+                        xor,
+                        cols.xtime[k],
+                        doubled,
+                        cols.msb[k] * AB::F::from_canonical_u32(0x1b),
+                        local.shard,
+                        local.channel,
+                        round.is_active,
+                    );
+
+                    builder.send_byte(
+                        xor,
+                        cols.partial[k],
+                        s[k],
+                        cols.sum,
+                        local.shard,
+                        local.channel,
+                        round.is_active,
+                    );
+                    builder.send_byte(
+                        xor,
+                        cols.output[k],
+                        cols.partial[k],
+                        cols.xtime[k],
+                        local.shard,
+                        local.channel,
+                        round.is_active,
+                    );
+                }
+                builder.send_byte(
+                    xor,
+                    cols.sum,
+                    cols.pairs[0],
+                    cols.pairs[2],
+                    local.shard,
+                    local.channel,
+                    round.is_active,
+                );
+            }
+
+            // The final round skips MixColumns.
+            for j in 0..AES_BLOCK_BYTES {
+                let mixed = round.mix_columns[j / 4].output[j % 4];
+                builder.assert_eq(
+                    round.pre_key[j],
+                    mixed + is_final.clone() * (shifted[j] - mixed),
+                );
+            }
+
+            // AddRoundKey.
+            for j in 0..AES_BLOCK_BYTES {
+                builder.send_byte(
+                    xor,
+                    round.output[j],
+                    round.pre_key[j],
+                    round.round_key_memory[j / 4].value()[j % 4],
+                    local.shard,
+                    local.channel,
+                    round.is_active,
+                );
+            }
+
+            // Read the round key.
+            builder.eval_memory_access_slice(
+                local.shard,
+                local.channel,
+                local.clk.into(),
+                local.round_keys_ptr
+                    + AB::F::from_canonical_usize(4 + round_number * AES_BLOCK_BYTES),
+                &round.round_key_memory,
+                round.is_active,
+            );
+
+            input = round.output.map(Into::into);
+        }
+
+        // Assert that the output of the final round is being written to the state.
+        for j in 0..AES_BLOCK_BYTES {
+            builder.when(local.is_real).assert_eq(
+                local.state_memory[j / 4].value()[j % 4],
+                local.is_aes128 * local.rounds[9].output[j]
+                    + local.is_aes192 * local.rounds[11].output[j]
+                    + local.is_aes256 * local.rounds[13].output[j],
+            );
+        }
+
+        // Read the number of rounds and the initial round key, and read and write the state.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.round_keys_ptr,
+            &local.num_rounds_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.round_keys_ptr + AB::F::from_canonical_u32(4),
+            &local.initial_key_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.state_ptr,
+            &local.state_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::AES_ENCRYPT_BLOCK.syscall_id()),
+            local.state_ptr,
+            local.round_keys_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
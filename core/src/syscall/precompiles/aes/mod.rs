@@ -0,0 +1,109 @@
+mod encrypt_block;
+
+pub use encrypt_block::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::utils::AES_SBOX;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    use super::{aes_encrypt_block, xtime, AES_BLOCK_WORDS};
+
+    const STATE_PTR: u32 = 0x1000;
+    const ROUND_KEYS_PTR: u32 = 0x2000;
+
+    /// The plaintext of the FIPS-197 Appendix C examples.
+    const PLAINTEXT: &str = "00112233445566778899aabbccddeeff";
+
+    /// Expands a 16, 24 or 32 byte key into its round keys with the FIPS-197 key schedule.
+    fn expand_key(key: &[u8]) -> Vec<u8> {
+        let nk = key.len() / 4;
+        let num_rounds = nk + 6;
+        let mut words = key
+            .chunks_exact(4)
+            .map(|word| [word[0], word[1], word[2], word[3]])
+            .collect::<Vec<_>>();
+        let mut rcon = 1u8;
+        for i in nk..4 * (num_rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| AES_SBOX[b as usize]);
+                temp[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(|b| AES_SBOX[b as usize]);
+            }
+            let word: [u8; 4] = core::array::from_fn(|j| words[i - nk][j] ^ temp[j]);
+            words.push(word);
+        }
+        words.concat()
+    }
+
+    /// Stores the block at `STATE_PTR` and the round count followed by the round keys at
+    /// `ROUND_KEYS_PTR`, and encrypts the block in place.
+    pub fn aes_encrypt_block_program(block: &[u8], key: &[u8]) -> Program {
+        let round_keys = expand_key(key);
+        let num_rounds = (round_keys.len() / 16 - 1) as u32;
+        let round_key_words = bytes_to_words_le_vec(&round_keys);
+        let mut instructions = store_words(STATE_PTR, bytes_to_words_le_vec(block));
+        instructions.extend(store_words(
+            ROUND_KEYS_PTR,
+            [num_rounds].into_iter().chain(round_key_words),
+        ));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::AES_ENCRYPT_BLOCK as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, STATE_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, ROUND_KEYS_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    fn execute_encrypt_block(block: &[u8], key: &[u8]) -> Vec<u8> {
+        let program = aes_encrypt_block_program(block, key);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..AES_BLOCK_WORDS as u32)
+            .map(|i| runtime.word(STATE_PTR + i * 4))
+            .collect::<Vec<_>>();
+        words_to_bytes_le_vec(&result)
+    }
+
+    #[test]
+    fn test_aes_encrypt_block_fips197() {
+        utils::setup_logger();
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+        for (key_len, ciphertext) in [
+            (16, "69c4e0d86a7b0430d8cdb78070b4c55a"),
+            (24, "dda97ca4864cdfe06eaf70a0ec0d7191"),
+            (32, "8ea2b7ca516745bfeafc49904b496089"),
+        ] {
+            let key = (0..key_len).collect::<Vec<u8>>();
+            let expected = hex::decode(ciphertext).unwrap();
+            let round_keys = expand_key(&key);
+            let block = plaintext.clone().try_into().unwrap();
+            assert_eq!(aes_encrypt_block(block, &round_keys).to_vec(), expected);
+            assert_eq!(execute_encrypt_block(&plaintext, &key), expected);
+        }
+    }
+
+    #[test]
+    fn test_aes_encrypt_block_prove() {
+        utils::setup_logger();
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+        for key_len in [16, 24, 32] {
+            let key = (0..key_len).collect::<Vec<u8>>();
+            run_test(aes_encrypt_block_program(&plaintext, &key)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,112 @@
+mod pack;
+
+pub use pack::*;
+
+/// The number of bytes packed into each BabyBear element.
+///
+/// Three bytes are always below `2^24`, which is smaller than the BabyBear prime `15 * 2^27 + 1`,
+/// so a packed element never overflows the field. A fourth byte would not fit.
+pub const BYTES_PER_ELEMENT: usize = 3;
+
+/// The number of elements produced by one call of the precompile, which is the width of the
+/// Poseidon2 state.
+pub const NUM_PACKED_ELEMENTS: usize = 16;
+
+/// The number of bytes consumed by one call of the precompile.
+pub const NUM_PACKED_BYTES: usize = NUM_PACKED_ELEMENTS * BYTES_PER_ELEMENT;
+
+/// Packs bytes into BabyBear elements, three little-endian bytes per element.
+///
+/// If the length is not a multiple of three, the last element is padded with zero bytes, so the
+/// output has `len.div_ceil(3)` elements. The length of the input is not encoded in the output,
+/// and must be known to unpack it with [`fields_to_bytes`].
+pub fn bytes_to_fields(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(BYTES_PER_ELEMENT)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(0u32, |acc, &byte| (acc << 8) | byte as u32)
+        })
+        .collect()
+}
+
+/// Unpacks the first `len` bytes from elements produced by [`bytes_to_fields`].
+pub fn fields_to_bytes(fields: &[u32], len: usize) -> Vec<u8> {
+    assert!(
+        len <= fields.len() * BYTES_PER_ELEMENT,
+        "not enough elements to unpack {} bytes",
+        len
+    );
+    fields
+        .iter()
+        .flat_map(|field| field.to_le_bytes().into_iter().take(BYTES_PER_ELEMENT))
+        .take(len)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, SP1CoreOpts};
+
+    use super::{bytes_to_fields, fields_to_bytes, NUM_PACKED_BYTES, NUM_PACKED_ELEMENTS};
+
+    const INPUT_PTR: u32 = 0x1000;
+    const OUT_PTR: u32 = 0x2000;
+
+    /// Stores `bytes` at `INPUT_PTR` and packs them into `OUT_PTR`.
+    fn bytes_to_fields_program(bytes: &[u8; NUM_PACKED_BYTES]) -> Program {
+        let mut instructions = store_words(INPUT_PTR, bytes_to_words_le_vec(bytes));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::BYTES_TO_FIELDS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, INPUT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_bytes_to_fields_round_trip() {
+        for len in 0..10 {
+            let bytes = (0..len).map(|i| 0xff - i as u8).collect::<Vec<_>>();
+            let fields = bytes_to_fields(&bytes);
+            assert_eq!(fields.len(), len.div_ceil(3));
+            assert!(fields.iter().all(|&x| x < 1 << 24));
+            assert_eq!(fields_to_bytes(&fields, len), bytes);
+        }
+    }
+
+    #[test]
+    fn test_bytes_to_fields_execute() {
+        utils::setup_logger();
+        let bytes: [u8; NUM_PACKED_BYTES] = core::array::from_fn(|i| (i * 37 + 200) as u8);
+        let program = bytes_to_fields_program(&bytes);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let fields = (0..NUM_PACKED_ELEMENTS)
+            .map(|i| runtime.word(OUT_PTR + i as u32 * 4))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, bytes_to_fields(&bytes));
+        assert_eq!(fields_to_bytes(&fields, NUM_PACKED_BYTES), bytes);
+        assert_eq!(runtime.record.bytes_to_fields_events.len(), 1);
+    }
+
+    #[test]
+    fn test_bytes_to_fields_prove() {
+        utils::setup_logger();
+        let bytes: [u8; NUM_PACKED_BYTES] = core::array::from_fn(|i| 0xff - i as u8);
+        run_test(bytes_to_fields_program(&bytes)).unwrap();
+    }
+}
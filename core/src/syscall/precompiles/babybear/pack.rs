@@ -0,0 +1,278 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{pad_rows, words_to_bytes_le_vec};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{bytes_to_fields, BYTES_PER_ELEMENT, NUM_PACKED_BYTES, NUM_PACKED_ELEMENTS};
+
+/// The number of columns in the BytesToFieldsCols.
+const NUM_COLS: usize = size_of::<BytesToFieldsCols<u8>>();
+
+/// The number of words read by one call of the precompile.
+const NUM_INPUT_WORDS: usize = NUM_PACKED_BYTES / WORD_SIZE;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BytesToFieldsEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub input_ptr: u32,
+    pub out_ptr: u32,
+    pub input: Vec<u32>,
+    pub output: Vec<u32>,
+    pub input_memory_records: Vec<MemoryReadRecord>,
+    pub output_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile for packing 48 bytes into 16 BabyBear elements.
+///
+/// Every element holds three little-endian bytes of the input and a zero top byte, so it is
+/// always a canonical field element. The input bytes are range checked with the byte lookup
+/// table, which makes the output usable as a Poseidon2 state without further checks.
+#[derive(Default)]
+pub struct BytesToFieldsChip;
+
+impl BytesToFieldsChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the BytesToFields operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct BytesToFieldsCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the bytes to pack.
+    pub input_ptr: T,
+
+    /// The pointer to the packed elements.
+    pub out_ptr: T,
+
+    pub input_memory: [MemoryReadCols<T>; NUM_INPUT_WORDS],
+    pub output_memory: [MemoryWriteCols<T>; NUM_PACKED_ELEMENTS],
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for BytesToFieldsChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "BytesToFields".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .bytes_to_fields_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut BytesToFieldsCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+
+                // Populate memory columns.
+                for i in 0..NUM_INPUT_WORDS {
+                    cols.input_memory[i].populate(
+                        event.channel,
+                        event.input_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..NUM_PACKED_ELEMENTS {
+                    cols.output_memory[i].populate(
+                        event.channel,
+                        event.output_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Range check the input bytes.
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &words_to_bytes_le_vec(&event.input),
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut BytesToFieldsCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.bytes_to_fields_events.is_empty()
+    }
+}
+
+impl Syscall for BytesToFieldsChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let input_ptr = arg1;
+        if input_ptr % 4 != 0 {
+            panic!();
+        }
+        let out_ptr = arg2;
+        if out_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let (input_memory_records, input) = rt.mr_slice(input_ptr, NUM_INPUT_WORDS);
+        let output = bytes_to_fields(&words_to_bytes_le_vec(&input));
+        let output_memory_records = rt.mw_slice(out_ptr, &output);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().bytes_to_fields_events.push(BytesToFieldsEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            input_ptr,
+            out_ptr,
+            input,
+            output,
+            input_memory_records,
+            output_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for BytesToFieldsChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for BytesToFieldsChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &BytesToFieldsCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &BytesToFieldsCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Range check the input bytes.
+        let input_bytes = local
+            .input_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        builder.slice_range_check_u8(&input_bytes, local.shard, local.channel, local.is_real);
+
+        // Every element is made of the next three input bytes, and its top byte is zero.
+        for (i, output) in local.output_memory.iter().enumerate() {
+            let value = output.value();
+            for j in 0..BYTES_PER_ELEMENT {
+                builder
+                    .when(local.is_real)
+                    .assert_eq(value[j], input_bytes[i * BYTES_PER_ELEMENT + j]);
+            }
+            builder.when(local.is_real).assert_zero(value[WORD_SIZE - 1]);
+        }
+
+        // Read the input bytes.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.input_ptr,
+            &local.input_memory,
+            local.is_real,
+        );
+
+        // Write the packed elements.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.out_ptr,
+            &local.output_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::BYTES_TO_FIELDS.syscall_id()),
+            local.input_ptr,
+            local.out_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
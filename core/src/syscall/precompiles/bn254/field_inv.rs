@@ -0,0 +1,361 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::memory::{value_as_limbs, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, MemoryWriteRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::weierstrass::bn254::{Bn254BaseField, Bn254ScalarField};
+use crate::utils::{
+    bytes_to_words_le_vec, limbs_from_prev_access, pad_rows, words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use typenum::Unsigned;
+
+pub const fn num_bn254_field_inv_cols<P: FieldParameters + NumWords>() -> usize {
+    size_of::<Bn254FieldInvCols<u8, P>>()
+}
+
+/// The BN254 fields with an inversion precompile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bn254FieldType {
+    Base,
+    Scalar,
+}
+
+/// A BN254 field that [`Bn254FieldInvChip`] can invert elements of.
+pub trait Bn254InvField: FieldParameters + NumWords {
+    const FIELD_TYPE: Bn254FieldType;
+}
+
+impl Bn254InvField for Bn254BaseField {
+    const FIELD_TYPE: Bn254FieldType = Bn254FieldType::Base;
+}
+
+impl Bn254InvField for Bn254ScalarField {
+    const FIELD_TYPE: Bn254FieldType = Bn254FieldType::Scalar;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254FieldInvEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile computing the inverse of an element of a BN254 field, either the base field or
+/// the scalar field.
+///
+/// The inverse `x^{-1}` is constrained by `x^{-1} * x = 1` modulo the field modulus. Zero has no
+/// inverse, so it is mapped to zero instead, which is constrained by `0 * 0 = 0` together with the
+/// result being zero.
+#[derive(Default)]
+pub struct Bn254FieldInvChip<P> {
+    _marker: PhantomData<P>,
+}
+
+impl<P: Bn254InvField> Bn254FieldInvChip<P> {
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn syscall_code() -> SyscallCode {
+        match P::FIELD_TYPE {
+            Bn254FieldType::Base => SyscallCode::BN254_FP_INV,
+            Bn254FieldType::Scalar => SyscallCode::BN254_FR_INV,
+        }
+    }
+
+    fn events(record: &ExecutionRecord) -> &Vec<Bn254FieldInvEvent> {
+        match P::FIELD_TYPE {
+            Bn254FieldType::Base => &record.bn254_fp_inv_events,
+            Bn254FieldType::Scalar => &record.bn254_fr_inv_events,
+        }
+    }
+}
+
+/// A set of columns for the Bn254FieldInv operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Bn254FieldInvCols<T, P: FieldParameters + NumWords> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the input, which is overwritten with its inverse.
+    pub x_ptr: T,
+
+    /// The memory columns of x, which is written to with the result.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, P::WordsFieldElement>,
+
+    /// Columns for checking if x is zero, in which case the result is zero.
+    pub x_is_zero: IsZeroOperation<T>,
+
+    /// The inverse of x, computed as `(1 - x_is_zero) / x`.
+    pub inverse: FieldOpCols<T, P>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32, P: Bn254InvField> MachineAir<F> for Bn254FieldInvChip<P> {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match P::FIELD_TYPE {
+            Bn254FieldType::Base => "Bn254FpInv".to_string(),
+            Bn254FieldType::Scalar => "Bn254FrInv".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let num_cols = num_bn254_field_inv_cols::<P>();
+        let mut rows = Self::events(input)
+            .iter()
+            .map(|event| {
+                let mut row = vec![F::zero(); num_cols];
+                let cols: &mut Bn254FieldInvCols<F, P> = row.as_mut_slice().borrow_mut();
+
+                let x_bytes = words_to_bytes_le_vec(&event.x);
+                let x = BigUint::from_bytes_le(&x_bytes);
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+
+                // Populate memory columns.
+                for i in 0..cols.x_memory.len() {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let x_byte_sum = x_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.x_is_zero, x_byte_sum);
+
+                // Populate the inverse, dividing zero by zero if x is zero.
+                let numerator = if x.is_zero() {
+                    BigUint::zero()
+                } else {
+                    BigUint::one()
+                };
+                cols.inverse.populate(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &numerator,
+                    &x,
+                    FieldOperation::Div,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row = vec![F::zero(); num_cols];
+            let cols: &mut Bn254FieldInvCols<F, P> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            IsZeroOperation::populate(&mut cols.x_is_zero, 0);
+            cols.inverse
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Div);
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), num_cols);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Bn254FieldInvCols<F, P> =
+                trace.values[i * num_cols..(i + 1) * num_cols].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !Self::events(shard).is_empty()
+    }
+}
+
+impl<P: Bn254InvField> Syscall for Bn254FieldInvChip<P> {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, _: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read x with slice_unsafe since it is overwritten with the result.
+        let num_words = <P as NumWords>::WordsFieldElement::USIZE;
+        let x = rt.slice_unsafe(x_ptr, num_words);
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let modulus = P::modulus();
+        if uint256_x >= modulus {
+            panic!("bn254 field inversion input is not reduced modulo the field modulus");
+        }
+
+        // Zero has no inverse and is mapped to zero.
+        let result = uint256_x.modinv(&modulus).unwrap_or_else(BigUint::zero);
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(num_words * 4, 0u8);
+        let result = bytes_to_words_le_vec(&result_bytes);
+
+        // Write the result to x and keep track of the memory records.
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        let event = Bn254FieldInvEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            x,
+            x_memory_records,
+        };
+        match P::FIELD_TYPE {
+            Bn254FieldType::Base => rt.record_mut().bn254_fp_inv_events.push(event),
+            Bn254FieldType::Scalar => rt.record_mut().bn254_fr_inv_events.push(event),
+        }
+
+        None
+    }
+}
+
+impl<F, P: Bn254InvField> BaseAir<F> for Bn254FieldInvChip<P> {
+    fn width(&self) -> usize {
+        num_bn254_field_inv_cols::<P>()
+    }
+}
+
+impl<AB, P: Bn254InvField> Air<AB> for Bn254FieldInvChip<P>
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <P as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Bn254FieldInvCols<AB::Var, P> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Bn254FieldInvCols<AB::Var, P> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The value of x is stored in the "prev_value" of the x_memory, since we write to it later.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+
+        // Evaluate whether x is zero by summing its bytes. The sum will not overflow because we
+        // are summing 32 bytes.
+        let x_byte_sum = x_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(builder, x_byte_sum, local.x_is_zero, local.is_real.into());
+
+        // Constrain inverse * x = 1 - x_is_zero modulo the field modulus.
+        let x_is_zero = local.x_is_zero.result;
+        let numerator: Polynomial<AB::Expr> =
+            Polynomial::from_coefficients(&[AB::Expr::one() - x_is_zero]);
+        local.inverse.eval(
+            builder,
+            &numerator,
+            &x_limbs,
+            FieldOperation::Div,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // The division constraint holds for any result when x is zero, so pin it to zero.
+        for limb in local.inverse.result.0.iter() {
+            builder.when(local.is_real * x_is_zero).assert_zero(*limb);
+        }
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.inverse.result, value_as_limbs(&local.x_memory));
+
+        // Read and write x.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(Self::syscall_code().syscall_id()),
+            local.x_ptr,
+            AB::Expr::zero(),
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,443 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::DisjointRangesOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::weierstrass::bn254::{Bn254BaseField, Bn254Fp2};
+use crate::utils::{limbs_from_access, limbs_from_prev_access, pad_rows};
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Bn254Fp2MulCols.
+const NUM_COLS: usize = size_of::<Bn254Fp2MulCols<u8>>();
+
+/// The number of bytes of an Fp2 element. The regions of x and y must not overlap unless the
+/// pointers are equal, which squares x.
+const FP2_BYTES: u32 = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254Fp2MulEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub y_ptr: u32,
+    pub y: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub y_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A precompile multiplying two elements of the quadratic extension of the BN254 base field by
+/// `i^2 = -1`, writing the product over the first one.
+///
+/// The product `(a + b i)(c + d i) = (ac - bd) + (ad + bc) i` is constrained with four products
+/// and a difference and a sum modulo the base field modulus.
+#[derive(Default)]
+pub struct Bn254Fp2MulChip;
+
+impl Bn254Fp2MulChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+type WordsFieldElement = <Bn254BaseField as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// A set of columns for the Bn254Fp2Mul operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Bn254Fp2MulCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x, which is overwritten with the product.
+    pub x_ptr: T,
+
+    /// The pointer to y.
+    pub y_ptr: T,
+
+    /// The memory columns of the coefficients of x, which are written to with the product.
+    pub x0_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub x1_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// The memory columns of the coefficients of y.
+    pub y0_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+    pub y1_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// Columns for checking that x does not overlap y, unless x_ptr == y_ptr.
+    pub ptrs_disjoint: DisjointRangesOperation<T>,
+
+    /// The products of the coefficients of x and y.
+    pub a_mul_c: FieldOpCols<T, Bn254BaseField>,
+    pub b_mul_d: FieldOpCols<T, Bn254BaseField>,
+    pub a_mul_d: FieldOpCols<T, Bn254BaseField>,
+    pub b_mul_c: FieldOpCols<T, Bn254BaseField>,
+
+    /// The coefficients of the product, `ac - bd` and `ad + bc`.
+    pub c0: FieldOpCols<T, Bn254BaseField>,
+    pub c1: FieldOpCols<T, Bn254BaseField>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> Bn254Fp2MulCols<F> {
+    /// Populates the operation columns of the product of `x` and `y`, and returns it.
+    fn populate_operations(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        x: &Bn254Fp2,
+        y: &Bn254Fp2,
+    ) -> Bn254Fp2 {
+        let ac = self
+            .a_mul_c
+            .populate(record, shard, channel, &x.c0, &y.c0, FieldOperation::Mul);
+        let bd = self
+            .b_mul_d
+            .populate(record, shard, channel, &x.c1, &y.c1, FieldOperation::Mul);
+        let ad = self
+            .a_mul_d
+            .populate(record, shard, channel, &x.c0, &y.c1, FieldOperation::Mul);
+        let bc = self
+            .b_mul_c
+            .populate(record, shard, channel, &x.c1, &y.c0, FieldOperation::Mul);
+        let c0 = self
+            .c0
+            .populate(record, shard, channel, &ac, &bd, FieldOperation::Sub);
+        let c1 = self
+            .c1
+            .populate(record, shard, channel, &ad, &bc, FieldOperation::Add);
+        Bn254Fp2::new(c0, c1)
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Bn254Fp2MulChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Bn254Fp2Mul".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .bn254_fp2_mul_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Bn254Fp2MulCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+                let disjoint = cols.ptrs_disjoint.populate(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    event.x_ptr,
+                    FP2_BYTES,
+                    event.y_ptr,
+                    FP2_BYTES,
+                    true,
+                );
+                assert!(
+                    disjoint,
+                    "bn254_fp2_mul operands at {:#x} and {:#x} overlap or are too far apart",
+                    event.x_ptr, event.y_ptr
+                );
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    let j = i + WORDS_FIELD_ELEMENT;
+                    cols.x0_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.x1_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[j],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.y0_memory[i].populate(
+                        event.channel,
+                        event.y_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.y1_memory[i].populate(
+                        event.channel,
+                        event.y_memory_records[j],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                cols.populate_operations(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &Bn254Fp2::from_words_le(&event.x),
+                    &Bn254Fp2::from_words_le(&event.y),
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut Bn254Fp2MulCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = Bn254Fp2::new(BigUint::zero(), BigUint::zero());
+            cols.populate_operations(&mut vec![], 0, 0, &zero, &zero);
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Bn254Fp2MulCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.bn254_fp2_mul_events.is_empty()
+    }
+}
+
+impl Syscall for Bn254Fp2MulChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn alignment(&self) -> u32 {
+        4
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        rt.require_aligned(x_ptr, self.alignment()).unwrap();
+        let y_ptr = arg2;
+        rt.require_aligned(y_ptr, self.alignment()).unwrap();
+        if x_ptr != y_ptr {
+            rt.require_disjoint(x_ptr, FP2_BYTES, y_ptr, FP2_BYTES)
+                .unwrap();
+        }
+
+        let start_clk = rt.clk;
+
+        // Read x with slice_unsafe since it is overwritten with the result, and then y.
+        let x = rt.slice_unsafe(x_ptr, Bn254Fp2::NUM_WORDS);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, Bn254Fp2::NUM_WORDS);
+
+        let modulus = Bn254BaseField::modulus();
+        let (fp2_x, fp2_y) = (Bn254Fp2::from_words_le(&x), Bn254Fp2::from_words_le(&y));
+        for c in [&fp2_x.c0, &fp2_x.c1, &fp2_y.c0, &fp2_y.c1] {
+            if c >= &modulus {
+                panic!("bn254_fp2_mul input is not reduced modulo the base field modulus");
+            }
+        }
+        let result = fp2_x.mul(&fp2_y);
+
+        // Write the result to x a cycle later, since x and y could be the same, and keep track of
+        // the memory records.
+        rt.clk += 1;
+        let x_memory_records = rt.mw_slice(x_ptr, &result.to_words_le());
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().bn254_fp2_mul_events.push(Bn254Fp2MulEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk: start_clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            x_memory_records,
+            y_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Bn254Fp2MulChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Bn254Fp2MulChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <Bn254BaseField as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Bn254Fp2MulCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Bn254Fp2MulCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The value of x is stored in the "prev_value" of the x memory, since we write to it later.
+        let a: Limbs<AB::Var, <Bn254BaseField as NumLimbs>::Limbs> =
+            limbs_from_prev_access(&local.x0_memory);
+        let b: Limbs<AB::Var, <Bn254BaseField as NumLimbs>::Limbs> =
+            limbs_from_prev_access(&local.x1_memory);
+        let c: Limbs<AB::Var, <Bn254BaseField as NumLimbs>::Limbs> =
+            limbs_from_access(&local.y0_memory);
+        let d: Limbs<AB::Var, <Bn254BaseField as NumLimbs>::Limbs> =
+            limbs_from_access(&local.y1_memory);
+
+        // Evaluate the four products of the coefficients.
+        for (cols, lhs, rhs) in [
+            (&local.a_mul_c, &a, &c),
+            (&local.b_mul_d, &b, &d),
+            (&local.a_mul_d, &a, &d),
+            (&local.b_mul_c, &b, &c),
+        ] {
+            cols.eval(
+                builder,
+                lhs,
+                rhs,
+                FieldOperation::Mul,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        // Since i^2 = -1, the real part is ac - bd and the imaginary part ad + bc.
+        local.c0.eval(
+            builder,
+            &local.a_mul_c.result,
+            &local.b_mul_d.result,
+            FieldOperation::Sub,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.c1.eval(
+            builder,
+            &local.a_mul_d.result,
+            &local.b_mul_c.result,
+            FieldOperation::Add,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to the x memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.c0.result, value_as_limbs(&local.x0_memory));
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.c1.result, value_as_limbs(&local.x1_memory));
+
+        // Assert that x does not overlap y, unless they are the same operand.
+        DisjointRangesOperation::<AB::F>::eval(
+            builder,
+            local.x_ptr.into(),
+            FP2_BYTES,
+            local.y_ptr.into(),
+            FP2_BYTES,
+            true,
+            local.ptrs_disjoint,
+            local.shard,
+            local.channel,
+            local.is_real.into(),
+        );
+
+        // Read and write x, a cycle after y is read since x and y could be the same.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.x_ptr,
+            &[local.x0_memory, local.x1_memory].concat(),
+            local.is_real,
+        );
+
+        // Read y.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.y_ptr,
+            &[local.y0_memory, local.y1_memory].concat(),
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::BN254_FP2_MUL.syscall_id()),
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
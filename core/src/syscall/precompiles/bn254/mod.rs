@@ -0,0 +1,196 @@
+mod field_inv;
+mod fp2_mul;
+
+pub use field_inv::*;
+pub use fp2_mul::*;
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::RandBigInt;
+    use num::{BigUint, One, Zero};
+    use rand::thread_rng;
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::ec::weierstrass::bn254::{Bn254BaseField, Bn254Fp2, Bn254ScalarField};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    const X_PTR: u32 = 0x1000;
+    const Y_PTR: u32 = 0x2000;
+
+    /// Stores x at `X_PTR` and inverts it in place with the given inversion precompile.
+    pub fn bn254_inv_program(syscall: SyscallCode, x: &BigUint) -> Program {
+        let mut bytes = x.to_bytes_le();
+        bytes.resize(32, 0);
+        let mut instructions = store_words(X_PTR, bytes_to_words_le_vec(&bytes));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, syscall as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores x at `X_PTR` and inverts it in place in the scalar field.
+    pub fn bn254_fr_inv_program(x: &BigUint) -> Program {
+        bn254_inv_program(SyscallCode::BN254_FR_INV, x)
+    }
+
+    /// Stores x at `X_PTR` and inverts it in place in the base field.
+    pub fn bn254_fp_inv_program(x: &BigUint) -> Program {
+        bn254_inv_program(SyscallCode::BN254_FP_INV, x)
+    }
+
+    /// Stores x at `X_PTR` and y at `y_ptr`, and multiplies x by y in place in Fp2. With `y_ptr`
+    /// equal to `X_PTR`, y is ignored and x is squared.
+    pub fn bn254_fp2_mul_program(x: &Bn254Fp2, y: &Bn254Fp2, y_ptr: u32) -> Program {
+        let mut instructions = store_words(X_PTR, x.to_words_le());
+        if y_ptr != X_PTR {
+            instructions.extend(store_words(y_ptr, y.to_words_le()));
+        }
+        let syscall = SyscallCode::BN254_FP2_MUL as u32;
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, syscall, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, y_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    fn execute_fp2_mul(program: Program) -> Bn254Fp2 {
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..Bn254Fp2::NUM_WORDS as u32)
+            .map(|i| runtime.word(X_PTR + i * 4))
+            .collect::<Vec<_>>();
+        Bn254Fp2::from_words_le(&result)
+    }
+
+    fn random_fp2() -> Bn254Fp2 {
+        let p = Bn254BaseField::modulus();
+        let mut rng = thread_rng();
+        Bn254Fp2::new(rng.gen_biguint_below(&p), rng.gen_biguint_below(&p))
+    }
+
+    fn execute_inverse(program: Program) -> BigUint {
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..8)
+            .map(|i| runtime.word(X_PTR + i * 4))
+            .collect::<Vec<_>>();
+        BigUint::from_bytes_le(&words_to_bytes_le_vec(&result))
+    }
+
+    #[test]
+    fn test_bn254_fr_inv_execute() {
+        utils::setup_logger();
+        let r = Bn254ScalarField::modulus();
+        for x in [
+            BigUint::one(),
+            BigUint::from(2u32),
+            BigUint::from(0xdeadbeefu32) << 128,
+            &r - BigUint::one(),
+        ] {
+            let inverse = execute_inverse(bn254_fr_inv_program(&x));
+            assert_eq!(inverse, x.modinv(&r).unwrap());
+            assert_eq!((inverse * &x) % &r, BigUint::one());
+        }
+    }
+
+    #[test]
+    fn test_bn254_fr_inv_zero() {
+        utils::setup_logger();
+        let inverse = execute_inverse(bn254_fr_inv_program(&BigUint::zero()));
+        assert_eq!(inverse, BigUint::zero());
+    }
+
+    #[test]
+    fn test_bn254_fr_inv_prove() {
+        utils::setup_logger();
+        let r = Bn254ScalarField::modulus();
+        run_test(bn254_fr_inv_program(&(&r - BigUint::from(12345u32)))).unwrap();
+        run_test(bn254_fr_inv_program(&BigUint::zero())).unwrap();
+    }
+
+    #[test]
+    fn test_bn254_fp_inv_execute() {
+        utils::setup_logger();
+        let p = Bn254BaseField::modulus();
+        let mut rng = thread_rng();
+        let random = (0..16).map(|_| rng.gen_biguint_below(&p));
+        let edge_cases = [BigUint::one(), BigUint::from(2u32), &p - BigUint::one()];
+        for x in edge_cases.into_iter().chain(random) {
+            if x.is_zero() {
+                continue;
+            }
+            let inverse = execute_inverse(bn254_fp_inv_program(&x));
+            assert!(inverse < p);
+            assert_eq!((inverse * &x) % &p, BigUint::one());
+        }
+    }
+
+    #[test]
+    fn test_bn254_fp_inv_zero() {
+        utils::setup_logger();
+        let inverse = execute_inverse(bn254_fp_inv_program(&BigUint::zero()));
+        assert_eq!(inverse, BigUint::zero());
+    }
+
+    #[test]
+    fn test_bn254_fp_inv_prove() {
+        utils::setup_logger();
+        let p = Bn254BaseField::modulus();
+        let x = thread_rng().gen_biguint_below(&p);
+        run_test(bn254_fp_inv_program(&x)).unwrap();
+        run_test(bn254_fp_inv_program(&(&p - BigUint::one()))).unwrap();
+        run_test(bn254_fp_inv_program(&BigUint::zero())).unwrap();
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul_execute() {
+        utils::setup_logger();
+        let p = Bn254BaseField::modulus();
+        let u = Bn254Fp2::new(BigUint::zero(), BigUint::one());
+        let max = Bn254Fp2::new(&p - BigUint::one(), &p - BigUint::one());
+        let zero = Bn254Fp2::new(BigUint::zero(), BigUint::zero());
+        let edge_cases = [(u.clone(), u), (max.clone(), max), (zero, random_fp2())];
+        let random = (0..16).map(|_| (random_fp2(), random_fp2()));
+        for (x, y) in edge_cases.into_iter().chain(random) {
+            let result = execute_fp2_mul(bn254_fp2_mul_program(&x, &y, Y_PTR));
+            assert!(result.c0 < p && result.c1 < p);
+            assert_eq!(result, x.mul(&y));
+        }
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul_square() {
+        utils::setup_logger();
+        for _ in 0..8 {
+            let x = random_fp2();
+            let result = execute_fp2_mul(bn254_fp2_mul_program(&x, &x, X_PTR));
+            assert_eq!(result, x.mul(&x));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "bn254_fp2_mul input is not reduced")]
+    fn test_bn254_fp2_mul_unreduced() {
+        utils::setup_logger();
+        let x = Bn254Fp2::new(Bn254BaseField::modulus(), BigUint::one());
+        execute_fp2_mul(bn254_fp2_mul_program(&x, &random_fp2(), Y_PTR));
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul_prove() {
+        utils::setup_logger();
+        let p = Bn254BaseField::modulus();
+        let x = random_fp2();
+        run_test(bn254_fp2_mul_program(&x, &random_fp2(), Y_PTR)).unwrap();
+        run_test(bn254_fp2_mul_program(&x, &x, X_PTR)).unwrap();
+        let max = Bn254Fp2::new(&p - BigUint::one(), &p - BigUint::one());
+        run_test(bn254_fp2_mul_program(&max, &max, Y_PTR)).unwrap();
+    }
+}
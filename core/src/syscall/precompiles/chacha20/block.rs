@@ -0,0 +1,574 @@
+use crate::air::{MachineAir, SP1AirBuilder, Word, WordAirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::{AddOperation, FixedRotateRightOperation, XorOperation};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{
+    chacha20_block, chacha20_initial_state, CHACHA20_CONSTANTS, KEY_SIZE, NONCE_SIZE,
+    NUM_QUARTER_ROUNDS, QUARTER_ROUND_INDICES, STATE_SIZE,
+};
+
+/// The number of columns in the ChaCha20BlockCols.
+const NUM_COLS: usize = size_of::<ChaCha20BlockCols<u8>>();
+
+/// The number of words of the header, which contains the pointers to the key and the nonce, and
+/// the block counter.
+pub const CHACHA20_BLOCK_HEADER_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaCha20BlockEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub header_ptr: u32,
+    pub key_ptr: u32,
+    pub iv_ptr: u32,
+    pub counter: u32,
+    pub key: Vec<u32>,
+    pub iv: Vec<u32>,
+    pub out: Vec<u32>,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub key_memory_records: Vec<MemoryReadRecord>,
+    pub iv_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile computing the ChaCha20 block function of RFC 8439, writing a block of keystream.
+///
+/// The whole block is computed in a single row: each of the 80 quarter rounds is four additions,
+/// four XORs and four rotations, where the XORs and the bit rotations by 12 and 7 use the byte
+/// lookup table, and the rotations by 16 and 8 are a permutation of the bytes. The nonce of the
+/// block is called the IV here, so as not to be confused with the nonce of the operation.
+#[derive(Default)]
+pub struct ChaCha20BlockChip;
+
+impl ChaCha20BlockChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for a quarter round `(a, b, c, d)` of the block function.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct QuarterRoundCols<T> {
+    /// `a += b; d ^= a; d <<<= 16`.
+    pub a_add_b_0: AddOperation<T>,
+    pub d_xor_a_0: XorOperation<T>,
+
+    /// `c += d; b ^= c; b <<<= 12`.
+    pub c_add_d_0: AddOperation<T>,
+    pub b_xor_c_0: XorOperation<T>,
+    pub b_rotate_12: FixedRotateRightOperation<T>,
+
+    /// `a += b; d ^= a; d <<<= 8`.
+    pub a_add_b_1: AddOperation<T>,
+    pub d_xor_a_1: XorOperation<T>,
+
+    /// `c += d; b ^= c; b <<<= 7`.
+    pub c_add_d_1: AddOperation<T>,
+    pub b_xor_c_1: XorOperation<T>,
+    pub b_rotate_7: FixedRotateRightOperation<T>,
+}
+
+/// A set of columns for the ChaCha20Block operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct ChaCha20BlockCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output block.
+    pub out_ptr: T,
+
+    /// The pointer to the header, which contains the pointers to the key and the IV, and the
+    /// block counter.
+    pub header_ptr: T,
+
+    /// The pointers read from the header.
+    pub key_ptr: T,
+    pub iv_ptr: T,
+
+    pub header_memory: [MemoryReadCols<T>; CHACHA20_BLOCK_HEADER_WORDS],
+    pub key_memory: [MemoryReadCols<T>; KEY_SIZE],
+    pub iv_memory: [MemoryReadCols<T>; NONCE_SIZE],
+    pub out_memory: [MemoryWriteCols<T>; STATE_SIZE],
+
+    /// The initial state: the constants, the key, the counter and the IV.
+    pub initial_state: [Word<T>; STATE_SIZE],
+
+    /// The quarter rounds, in the order they are applied.
+    pub quarter_rounds: [QuarterRoundCols<T>; NUM_QUARTER_ROUNDS],
+
+    /// The sum of the initial state and the state after the rounds, which is the output.
+    pub final_add: [AddOperation<T>; STATE_SIZE],
+
+    pub is_real: T,
+}
+
+/// Rotates a word left by a whole number of bytes, which only permutes its bytes.
+fn rotate_left_bytes<T: Copy>(word: Word<T>, bytes: usize) -> Word<T> {
+    Word(core::array::from_fn(|i| word[(i + 4 - bytes) % 4]))
+}
+
+impl<F: PrimeField32> MachineAir<F> for ChaCha20BlockChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "ChaCha20Block".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let num_events = input.chacha20_block_events.len();
+        let chunk_size = std::cmp::max(num_events / num_cpus::get(), 1);
+
+        // Generate the trace rows & corresponding records for each chunk of events in parallel.
+        let rows_and_records = input
+            .chacha20_block_events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut record = ExecutionRecord::default();
+                let rows = events
+                    .iter()
+                    .map(|event| populate_row(event, &mut record))
+                    .collect::<Vec<_>>();
+                (rows, record)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = Vec::new();
+        for mut row_and_record in rows_and_records {
+            rows.extend(row_and_record.0);
+            output.append(&mut row_and_record.1);
+        }
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut ChaCha20BlockCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.chacha20_block_events.is_empty()
+    }
+}
+
+/// Populates the row of a block event, except for its nonce.
+fn populate_row<F: PrimeField32>(
+    event: &ChaCha20BlockEvent,
+    record: &mut ExecutionRecord,
+) -> [F; NUM_COLS] {
+    let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+    let cols: &mut ChaCha20BlockCols<F> = row.as_mut_slice().borrow_mut();
+    let (shard, channel) = (event.shard, event.channel);
+
+    // Assign basic values to the columns.
+    cols.is_real = F::one();
+    cols.shard = F::from_canonical_u32(shard);
+    cols.channel = F::from_canonical_u32(channel);
+    cols.clk = F::from_canonical_u32(event.clk);
+    cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+    cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+    cols.key_ptr = F::from_canonical_u32(event.key_ptr);
+    cols.iv_ptr = F::from_canonical_u32(event.iv_ptr);
+
+    // Populate memory columns.
+    let mut new_byte_lookup_events = Vec::new();
+    for i in 0..CHACHA20_BLOCK_HEADER_WORDS {
+        cols.header_memory[i].populate(
+            channel,
+            event.header_memory_records[i],
+            &mut new_byte_lookup_events,
+        );
+    }
+    for i in 0..KEY_SIZE {
+        cols.key_memory[i].populate(
+            channel,
+            event.key_memory_records[i],
+            &mut new_byte_lookup_events,
+        );
+    }
+    for i in 0..NONCE_SIZE {
+        cols.iv_memory[i].populate(
+            channel,
+            event.iv_memory_records[i],
+            &mut new_byte_lookup_events,
+        );
+    }
+    for i in 0..STATE_SIZE {
+        cols.out_memory[i].populate(
+            channel,
+            event.out_memory_records[i],
+            &mut new_byte_lookup_events,
+        );
+    }
+    record.add_byte_lookup_events(new_byte_lookup_events);
+
+    // Populate the rounds.
+    let key = event.key.clone().try_into().unwrap();
+    let iv = event.iv.clone().try_into().unwrap();
+    let initial_state = chacha20_initial_state(&key, &iv, event.counter);
+    let mut state = initial_state;
+    for i in 0..STATE_SIZE {
+        cols.initial_state[i] = Word::from(initial_state[i]);
+    }
+    for r in 0..NUM_QUARTER_ROUNDS {
+        let [a, b, c, d] = QUARTER_ROUND_INDICES[r % 8];
+        let qr = &mut cols.quarter_rounds[r];
+
+        state[a] = qr
+            .a_add_b_0
+            .populate(record, shard, channel, state[a], state[b]);
+        state[d] = qr
+            .d_xor_a_0
+            .populate(record, shard, channel, state[d], state[a])
+            .rotate_left(16);
+        state[c] = qr
+            .c_add_d_0
+            .populate(record, shard, channel, state[c], state[d]);
+        let b_xor_c = qr
+            .b_xor_c_0
+            .populate(record, shard, channel, state[b], state[c]);
+        state[b] = qr.b_rotate_12.populate(record, shard, channel, b_xor_c, 20);
+
+        state[a] = qr
+            .a_add_b_1
+            .populate(record, shard, channel, state[a], state[b]);
+        state[d] = qr
+            .d_xor_a_1
+            .populate(record, shard, channel, state[d], state[a])
+            .rotate_left(8);
+        state[c] = qr
+            .c_add_d_1
+            .populate(record, shard, channel, state[c], state[d]);
+        let b_xor_c = qr
+            .b_xor_c_1
+            .populate(record, shard, channel, state[b], state[c]);
+        state[b] = qr.b_rotate_7.populate(record, shard, channel, b_xor_c, 25);
+    }
+    for i in 0..STATE_SIZE {
+        let out = cols.final_add[i].populate(record, shard, channel, initial_state[i], state[i]);
+        debug_assert_eq!(out, event.out[i]);
+    }
+
+    row
+}
+
+impl Syscall for ChaCha20BlockChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let out_ptr = arg1;
+        let header_ptr = arg2;
+        if out_ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let start_clk = rt.clk;
+
+        // Read the header, which contains the pointers to the key and the IV, and the counter.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, CHACHA20_BLOCK_HEADER_WORDS);
+        let (key_ptr, iv_ptr, counter) = (header[0], header[1], header[2]);
+        if key_ptr % 4 != 0 || iv_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the key and the IV a cycle apart, since they could share their memory.
+        let (key_memory_records, key) = rt.mr_slice(key_ptr, KEY_SIZE);
+        rt.clk += 1;
+        let (iv_memory_records, iv) = rt.mr_slice(iv_ptr, NONCE_SIZE);
+
+        let out = chacha20_block(
+            &key.clone().try_into().unwrap(),
+            &iv.clone().try_into().unwrap(),
+            counter,
+        )
+        .to_vec();
+
+        // Write the block after the reads, since it could overwrite the key or the IV.
+        rt.clk += 1;
+        let out_memory_records = rt.mw_slice(out_ptr, &out);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .chacha20_block_events
+            .push(ChaCha20BlockEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk: start_clk,
+                out_ptr,
+                header_ptr,
+                key_ptr,
+                iv_ptr,
+                counter,
+                key,
+                iv,
+                out,
+                header_memory_records,
+                key_memory_records,
+                iv_memory_records,
+                out_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for ChaCha20BlockChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for ChaCha20BlockChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &ChaCha20BlockCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &ChaCha20BlockCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The pointers to the key and the IV are the first words of the header.
+        let header_key_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_iv_ptr = local.header_memory[1].value().reduce::<AB>();
+        builder
+            .when(local.is_real)
+            .assert_eq(local.key_ptr, header_key_ptr);
+        builder
+            .when(local.is_real)
+            .assert_eq(local.iv_ptr, header_iv_ptr);
+
+        // The initial state is the constants, the key, the counter and the IV.
+        for i in 0..4 {
+            let constant = Word::<AB::F>::from(CHACHA20_CONSTANTS[i]);
+            for j in 0..4 {
+                builder
+                    .when(local.is_real)
+                    .assert_eq(local.initial_state[i][j], constant[j]);
+            }
+        }
+        for i in 0..KEY_SIZE {
+            builder
+                .when(local.is_real)
+                .assert_word_eq(local.initial_state[4 + i], *local.key_memory[i].value());
+        }
+        builder
+            .when(local.is_real)
+            .assert_word_eq(local.initial_state[12], *local.header_memory[2].value());
+        for i in 0..NONCE_SIZE {
+            builder
+                .when(local.is_real)
+                .assert_word_eq(local.initial_state[13 + i], *local.iv_memory[i].value());
+        }
+
+        // Constrain the quarter rounds, keeping track of the columns holding each word of the
+        // state.
+        let mut state = local.initial_state;
+        for r in 0..NUM_QUARTER_ROUNDS {
+            let [a, b, c, d] = QUARTER_ROUND_INDICES[r % 8];
+            let qr = &local.quarter_rounds[r];
+            self.eval_add(builder, local, &mut state, a, b, qr.a_add_b_0);
+            self.eval_xor(builder, local, &mut state, d, a, qr.d_xor_a_0);
+            state[d] = rotate_left_bytes(state[d], 2);
+            self.eval_add(builder, local, &mut state, c, d, qr.c_add_d_0);
+            self.eval_xor(builder, local, &mut state, b, c, qr.b_xor_c_0);
+            self.eval_rotate(builder, local, &mut state, b, 20, qr.b_rotate_12);
+
+            self.eval_add(builder, local, &mut state, a, b, qr.a_add_b_1);
+            self.eval_xor(builder, local, &mut state, d, a, qr.d_xor_a_1);
+            state[d] = rotate_left_bytes(state[d], 1);
+            self.eval_add(builder, local, &mut state, c, d, qr.c_add_d_1);
+            self.eval_xor(builder, local, &mut state, b, c, qr.b_xor_c_1);
+            self.eval_rotate(builder, local, &mut state, b, 25, qr.b_rotate_7);
+        }
+
+        // The output is the sum of the initial state and the state after the rounds.
+        for i in 0..STATE_SIZE {
+            AddOperation::<AB::F>::eval(
+                builder,
+                local.initial_state[i],
+                state[i],
+                local.final_add[i],
+                local.shard,
+                local.channel,
+                local.is_real.into(),
+            );
+            builder
+                .when(local.is_real)
+                .assert_word_eq(local.final_add[i].value, *local.out_memory[i].value());
+        }
+
+        // Read the header and the key, then the IV a cycle later.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.key_ptr,
+            &local.key_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.iv_ptr,
+            &local.iv_memory,
+            local.is_real,
+        );
+
+        // Write the block after all the reads.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(2),
+            local.out_ptr,
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::CHACHA20_BLOCK.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
+
+impl ChaCha20BlockChip {
+    /// Constrains `state[x] += state[y]`.
+    fn eval_add<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20BlockCols<AB::Var>,
+        state: &mut [Word<AB::Var>; STATE_SIZE],
+        x: usize,
+        y: usize,
+        cols: AddOperation<AB::Var>,
+    ) {
+        AddOperation::<AB::F>::eval(
+            builder,
+            state[x],
+            state[y],
+            cols,
+            local.shard,
+            local.channel,
+            local.is_real.into(),
+        );
+        state[x] = cols.value;
+    }
+
+    /// Constrains `state[x] ^= state[y]`.
+    fn eval_xor<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20BlockCols<AB::Var>,
+        state: &mut [Word<AB::Var>; STATE_SIZE],
+        x: usize,
+        y: usize,
+        cols: XorOperation<AB::Var>,
+    ) {
+        XorOperation::<AB::F>::eval(
+            builder,
+            state[x],
+            state[y],
+            cols,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        state[x] = cols.value;
+    }
+
+    /// Constrains the rotation of `state[x]` right by `rotation` bits.
+    fn eval_rotate<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20BlockCols<AB::Var>,
+        state: &mut [Word<AB::Var>; STATE_SIZE],
+        x: usize,
+        rotation: usize,
+        cols: FixedRotateRightOperation<AB::Var>,
+    ) {
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            state[x],
+            rotation,
+            cols,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        state[x] = cols.value;
+    }
+}
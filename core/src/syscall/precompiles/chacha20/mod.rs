@@ -0,0 +1,195 @@
+mod block;
+
+pub use block::*;
+
+/// The number of words of a ChaCha20 state and of a block of keystream.
+pub const STATE_SIZE: usize = 16;
+
+/// The number of words of a ChaCha20 key.
+pub const KEY_SIZE: usize = 8;
+
+/// The number of words of a ChaCha20 nonce.
+pub const NONCE_SIZE: usize = 3;
+
+/// The number of quarter rounds of the ChaCha20 block function: 10 double rounds of 8 each.
+pub const NUM_QUARTER_ROUNDS: usize = 80;
+
+/// The words "expand 32-byte k" which start the state.
+pub const CHACHA20_CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// The indices of the state words of each quarter round of a double round: four column rounds
+/// followed by four diagonal rounds.
+pub const QUARTER_ROUND_INDICES: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Returns the initial state of the block function of `key`, `nonce` and `counter`.
+pub fn chacha20_initial_state(
+    key: &[u32; KEY_SIZE],
+    nonce: &[u32; NONCE_SIZE],
+    counter: u32,
+) -> [u32; STATE_SIZE] {
+    let mut state = [0u32; STATE_SIZE];
+    state[..4].copy_from_slice(&CHACHA20_CONSTANTS);
+    state[4..12].copy_from_slice(key);
+    state[12] = counter;
+    state[13..].copy_from_slice(nonce);
+    state
+}
+
+/// Computes the ChaCha20 block function of RFC 8439, returning the block of keystream as words.
+pub fn chacha20_block(
+    key: &[u32; KEY_SIZE],
+    nonce: &[u32; NONCE_SIZE],
+    counter: u32,
+) -> [u32; STATE_SIZE] {
+    let initial_state = chacha20_initial_state(key, nonce, counter);
+    let mut state = initial_state;
+    for r in 0..NUM_QUARTER_ROUNDS {
+        let [a, b, c, d] = QUARTER_ROUND_INDICES[r % 8];
+        state[a] = state[a].wrapping_add(state[b]);
+        state[d] = (state[d] ^ state[a]).rotate_left(16);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_left(12);
+        state[a] = state[a].wrapping_add(state[b]);
+        state[d] = (state[d] ^ state[a]).rotate_left(8);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_left(7);
+    }
+    core::array::from_fn(|i| state[i].wrapping_add(initial_state[i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, SP1CoreOpts};
+
+    use super::{chacha20_block, KEY_SIZE, NONCE_SIZE, STATE_SIZE};
+
+    const OUT_PTR: u32 = 0x1000;
+    const HEADER_PTR: u32 = 0x1100;
+    const KEY_PTR: u32 = 0x1200;
+    const NONCE_PTR: u32 = 0x1300;
+
+    /// The key, nonce, counter and serialized block of the test vector of RFC 8439, section 2.3.2.
+    fn rfc_8439_vector() -> ([u32; KEY_SIZE], [u32; NONCE_SIZE], u32, Vec<u8>) {
+        let key = bytes_to_words_le_vec(&(0..32).collect::<Vec<u8>>());
+        let nonce = bytes_to_words_le_vec(&hex::decode("000000090000004a00000000").unwrap());
+        let block = hex::decode(
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+             d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e",
+        )
+        .unwrap();
+        (key.try_into().unwrap(), nonce.try_into().unwrap(), 1, block)
+    }
+
+    /// The all-zero key and nonce of the test vectors of RFC 8439, appendix A.1, and the blocks of
+    /// counters 0 and 1.
+    fn rfc_8439_zero_vectors() -> Vec<(u32, Vec<u8>)> {
+        vec![
+            (
+                0,
+                hex::decode(
+                    "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+                     da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586",
+                )
+                .unwrap(),
+            ),
+            (
+                1,
+                hex::decode(
+                    "9f07e7be5551387a98ba977c732d080dcb0f29a048e3656912c6533e32ee7aed\
+                     29b721769ce64e43d57133b074d839d531ed1f28510afb45ace10a1f4b794d6f",
+                )
+                .unwrap(),
+            ),
+        ]
+    }
+
+    fn to_bytes(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    /// Stores the key at `KEY_PTR`, the nonce at `NONCE_PTR` and the header at `HEADER_PTR`, and
+    /// writes the block of each counter to `OUT_PTR` in turn.
+    fn chacha20_block_program(
+        key: &[u32; KEY_SIZE],
+        nonce: &[u32; NONCE_SIZE],
+        counters: &[u32],
+    ) -> Program {
+        let mut instructions = vec![];
+        for (ptr, words) in [(KEY_PTR, key.to_vec()), (NONCE_PTR, nonce.to_vec())] {
+            instructions.extend(store_words(ptr, words));
+        }
+        for &counter in counters {
+            instructions.extend(store_words(HEADER_PTR, [KEY_PTR, NONCE_PTR, counter]));
+            instructions.extend(vec![
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::CHACHA20_BLOCK as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    fn read_block(runtime: &Runtime) -> Vec<u8> {
+        let words = (0..STATE_SIZE as u32)
+            .map(|i| runtime.word(OUT_PTR + i * 4))
+            .collect::<Vec<_>>();
+        to_bytes(&words)
+    }
+
+    #[test]
+    fn test_chacha20_block_rfc_8439() {
+        let (key, nonce, counter, block) = rfc_8439_vector();
+        assert_eq!(to_bytes(&chacha20_block(&key, &nonce, counter)), block);
+        for (counter, block) in rfc_8439_zero_vectors() {
+            assert_eq!(to_bytes(&chacha20_block(&[0; 8], &[0; 3], counter)), block);
+        }
+    }
+
+    #[test]
+    fn test_chacha20_block_execute() {
+        utils::setup_logger();
+        let (key, nonce, counter, block) = rfc_8439_vector();
+        let program = chacha20_block_program(&key, &nonce, &[counter]);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(read_block(&runtime), block);
+
+        // The block of the last counter is left in the output.
+        let program = chacha20_block_program(&[0; 8], &[0; 3], &[0, 1]);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(read_block(&runtime), rfc_8439_zero_vectors()[1].1);
+        assert_eq!(runtime.record.chacha20_block_events.len(), 2);
+    }
+
+    #[test]
+    fn test_chacha20_block_prove() {
+        utils::setup_logger();
+        let (key, nonce, counter, _) = rfc_8439_vector();
+        run_test(chacha20_block_program(
+            &key,
+            &nonce,
+            &[counter, counter + 1],
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,510 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{bytes_to_words_le, limbs_from_access, pad_rows, words_to_bytes_le_vec};
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the CrtCombineCols.
+const NUM_COLS: usize = size_of::<CrtCombineCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+const NUM_LIMBS: usize = U256Field::NB_LIMBS;
+
+/// The number of words read from the input pointer: `r1`, `m1`, `r2` and `m2` in that order.
+pub const CRT_COMBINE_INPUT_WORDS: usize = 4 * WORDS_FIELD_ELEMENT;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrtCombineEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub input_ptr: u32,
+    pub input: Vec<u32>,
+    pub x: Vec<u32>,
+    pub input_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Computes the unique `x < m1 * m2` with `x = r1 (mod m1)` and `x = r2 (mod m2)`.
+///
+/// Panics unless the residues are reduced, the moduli are coprime and their product fits in 256
+/// bits, since otherwise there is no unique solution.
+pub fn crt_combine(r1: &BigUint, m1: &BigUint, r2: &BigUint, m2: &BigUint) -> BigUint {
+    assert!(r1 < m1, "crt_combine residue r1 is not reduced modulo m1");
+    assert!(r2 < m2, "crt_combine residue r2 is not reduced modulo m2");
+    assert!(m1 * m2 < BigUint::one() << 256, "crt_combine moduli product overflows 256 bits");
+    let m1_inv = m1.modinv(m2).expect("crt_combine moduli are not coprime");
+
+    // Garner's formula: x = r1 + m1 * ((r2 - r1) * m1^-1 mod m2).
+    let h = ((r2 + m2 - r1 % m2) * m1_inv) % m2;
+    r1 + m1 * h
+}
+
+/// Decodes the input words into `[r1, m1, r2, m2]`.
+fn decode_input(input: &[u32]) -> [BigUint; 4] {
+    core::array::from_fn(|i| {
+        let words = &input[i * WORDS_FIELD_ELEMENT..(i + 1) * WORDS_FIELD_ELEMENT];
+        BigUint::from_bytes_le(&words_to_bytes_le_vec(words))
+    })
+}
+
+/// A precompile recombining two residues with the Chinese remainder theorem.
+///
+/// The solution `x` is written to the output, and the quotients `h = (x - r1) / m1` and
+/// `k = (x - r2) / m2` are provided as advice. The chip checks the exact identities
+/// `x = r1 + m1 * h` and `x = r2 + m2 * k` over the integers, which give both congruences, and
+/// `x < m1 * m2`, which makes the solution unique when the moduli are coprime.
+#[derive(Default)]
+pub struct CrtCombineChip;
+
+impl CrtCombineChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the CrtCombine operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct CrtCombineCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer the result is written to.
+    pub out_ptr: T,
+
+    /// The pointer to the input, which contains `r1`, `m1`, `r2` and `m2` in that order.
+    pub input_ptr: T,
+
+    // Memory columns.
+    pub input_memory: [MemoryReadCols<T>; CRT_COMBINE_INPUT_WORDS],
+    pub out_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// The advice quotient `h = (x - r1) / m1`.
+    pub h: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    /// The advice quotient `k = (x - r2) / m2`.
+    pub k: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    // The integer operations. Every carry is constrained to zero, so none of them wraps.
+    pub modulus_product: FieldOpCols<T, U256Field>,
+    pub m1_h: FieldOpCols<T, U256Field>,
+    pub x_from_r1: FieldOpCols<T, U256Field>,
+    pub m2_k: FieldOpCols<T, U256Field>,
+    pub x_from_r2: FieldOpCols<T, U256Field>,
+
+    /// Flags marking the most significant byte in which `x` is smaller than `m1 * m2`.
+    pub lt_flags: [T; NUM_LIMBS],
+
+    /// The byte of `x` selected by the flags.
+    pub x_comparison_byte: T,
+
+    /// The byte of `m1 * m2` selected by the flags.
+    pub product_comparison_byte: T,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for CrtCombineChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "CrtCombine".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let modulus = BigUint::one() << 256;
+
+        let mut rows = input
+            .crt_combine_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut CrtCombineCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Decode the uint256 values.
+                let [r1, m1, r2, m2] = decode_input(&event.input);
+                let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.x));
+                let h = (&x - &r1) / &m1;
+                let k = (&x - &r2) / &m2;
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+
+                // Populate memory columns.
+                for i in 0..CRT_COMBINE_INPUT_WORDS {
+                    cols.input_memory[i].populate(
+                        event.channel,
+                        event.input_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.out_memory[i].populate(
+                        event.channel,
+                        event.out_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate and range check the advice quotients.
+                cols.h = U256Field::to_limbs_field::<F, _>(&h);
+                cols.k = U256Field::to_limbs_field::<F, _>(&k);
+                new_byte_lookup_events.add_u8_range_checks_field(
+                    event.shard,
+                    event.channel,
+                    &cols.h.0,
+                );
+                new_byte_lookup_events.add_u8_range_checks_field(
+                    event.shard,
+                    event.channel,
+                    &cols.k.0,
+                );
+
+                // Populate the integer operations.
+                let product = cols.modulus_product.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &m1,
+                    &m2,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+                let m1_h = cols.m1_h.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &m1,
+                    &h,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+                cols.x_from_r1.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &m1_h,
+                    &r1,
+                    &modulus,
+                    FieldOperation::Add,
+                );
+                let m2_k = cols.m2_k.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &m2,
+                    &k,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+                cols.x_from_r2.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &m2_k,
+                    &r2,
+                    &modulus,
+                    FieldOperation::Add,
+                );
+
+                // Populate the comparison of x against m1 * m2.
+                let x_bytes = U256Field::to_limbs(&x);
+                let product_bytes = U256Field::to_limbs(&product);
+                for i in (0..NUM_LIMBS).rev() {
+                    if x_bytes[i] != product_bytes[i] {
+                        assert!(x_bytes[i] < product_bytes[i]);
+                        cols.lt_flags[i] = F::one();
+                        cols.x_comparison_byte = F::from_canonical_u8(x_bytes[i]);
+                        cols.product_comparison_byte = F::from_canonical_u8(product_bytes[i]);
+                        new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                            opcode: ByteOpcode::LTU,
+                            shard: event.shard,
+                            channel: event.channel,
+                            a1: 1,
+                            a2: 0,
+                            b: x_bytes[i] as u32,
+                            c: product_bytes[i] as u32,
+                        });
+                        break;
+                    }
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut CrtCombineCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            for op in [&mut cols.modulus_product, &mut cols.m1_h, &mut cols.m2_k] {
+                op.populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            }
+            for op in [&mut cols.x_from_r1, &mut cols.x_from_r2] {
+                op.populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Add);
+            }
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut CrtCombineCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.crt_combine_events.is_empty()
+    }
+}
+
+impl Syscall for CrtCombineChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let out_ptr = arg1;
+        if out_ptr % 4 != 0 {
+            panic!();
+        }
+        let input_ptr = arg2;
+        if input_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the residues and moduli.
+        let (input_memory_records, input) = rt.mr_slice(input_ptr, CRT_COMBINE_INPUT_WORDS);
+        let [r1, m1, r2, m2] = decode_input(&input);
+
+        let result = crt_combine(&r1, &m1, &r2, &m2);
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8); // Pad the result to 32 bytes.
+
+        // Convert the result to little endian u32 words.
+        let x = bytes_to_words_le::<8>(&result_bytes);
+
+        // Write the result to the output and keep track of the memory records.
+        let out_memory_records = rt.mw_slice(out_ptr, &x);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().crt_combine_events.push(CrtCombineEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            out_ptr,
+            input_ptr,
+            input,
+            x: x.to_vec(),
+            input_memory_records,
+            out_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for CrtCombineChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for CrtCombineChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &CrtCombineCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &CrtCombineCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let input_limbs: [Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>; 4] =
+            core::array::from_fn(|i| {
+                limbs_from_access(
+                    &local.input_memory[i * WORDS_FIELD_ELEMENT..(i + 1) * WORDS_FIELD_ELEMENT],
+                )
+            });
+        let [r1, m1, r2, m2] = input_limbs;
+
+        // Range check the advice quotients.
+        builder.slice_range_check_u8(&local.h.0, local.shard, local.channel, local.is_real);
+        builder.slice_range_check_u8(&local.k.0, local.shard, local.channel, local.is_real);
+
+        // All operations are taken modulo 2^256, and their carries are constrained to zero below,
+        // so each one is an exact identity over the integers.
+        let mut coeff_2_256 = Vec::new();
+        coeff_2_256.resize(32, AB::Expr::zero());
+        coeff_2_256.push(AB::Expr::one());
+        let p_modulus: Polynomial<AB::Expr> = Polynomial::from_coefficients(&coeff_2_256);
+
+        let operations = [
+            (&local.modulus_product, m1, m2, FieldOperation::Mul),
+            (&local.m1_h, m1, local.h, FieldOperation::Mul),
+            (&local.x_from_r1, local.m1_h.result, r1, FieldOperation::Add),
+            (&local.m2_k, m2, local.k, FieldOperation::Mul),
+            (&local.x_from_r2, local.m2_k.result, r2, FieldOperation::Add),
+        ];
+        for (op, a, b, operation) in operations {
+            op.eval_with_modulus(
+                builder,
+                &a,
+                &b,
+                &p_modulus,
+                operation,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            for &carry in op.carry.0.iter() {
+                builder.assert_zero(carry);
+            }
+        }
+
+        // Both identities give the same x, which is the value written to the output.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.x_from_r1.result, local.x_from_r2.result);
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.x_from_r1.result, value_as_limbs(&local.out_memory));
+
+        // Assert that x < m1 * m2. Exactly one flag is set on real rows, at the most significant
+        // byte in which the two differ, and all bytes above it are equal.
+        let x = local.x_from_r1.result;
+        let product = local.modulus_product.result;
+        let mut sum_flags = AB::Expr::zero();
+        let mut is_inequality_visited = AB::Expr::zero();
+        let mut x_comparison_byte = AB::Expr::zero();
+        let mut product_comparison_byte = AB::Expr::zero();
+        for i in (0..NUM_LIMBS).rev() {
+            let flag = local.lt_flags[i];
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            is_inequality_visited += flag.into();
+
+            x_comparison_byte += x[i] * flag;
+            product_comparison_byte += product[i] * flag;
+
+            builder
+                .when(local.is_real)
+                .when_not(is_inequality_visited.clone())
+                .assert_eq(x[i], product[i]);
+        }
+        builder.assert_eq(sum_flags, local.is_real);
+        builder.assert_eq(local.x_comparison_byte, x_comparison_byte);
+        builder.assert_eq(local.product_comparison_byte, product_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.x_comparison_byte,
+            local.product_comparison_byte,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Read the residues and moduli.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.input_ptr,
+            &local.input_memory,
+            local.is_real,
+        );
+
+        // Write the result.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.out_ptr,
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::CRT_COMBINE.syscall_id()),
+            local.out_ptr,
+            local.input_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,90 @@
+mod combine;
+
+pub use combine::*;
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    use super::crt_combine;
+
+    const OUT_PTR: u32 = 0x1000;
+    const INPUT_PTR: u32 = 0x2000;
+
+    fn to_words(value: &BigUint) -> Vec<u32> {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(32, 0);
+        bytes_to_words_le_vec(&bytes)
+    }
+
+    /// Stores `r1`, `m1`, `r2` and `m2` at `INPUT_PTR` and recombines them into `OUT_PTR`.
+    pub fn crt_combine_program(r1: &BigUint, m1: &BigUint, r2: &BigUint, m2: &BigUint) -> Program {
+        let words = [r1, m1, r2, m2].into_iter().flat_map(to_words);
+        let mut instructions = store_words(INPUT_PTR, words);
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CRT_COMBINE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, INPUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Two coprime moduli of 127 and 128 bits and residues for them.
+    fn test_inputs() -> (BigUint, BigUint, BigUint, BigUint) {
+        let m1 = (BigUint::from(1u32) << 127) - 1u32;
+        let m2 = (BigUint::from(1u32) << 128) - 159u32;
+        let r1 = BigUint::from(0x1234_5678_9abc_def0u64);
+        let r2 = &m2 - 12345u32;
+        (r1, m1, r2, m2)
+    }
+
+    #[test]
+    fn test_crt_combine_execute() {
+        utils::setup_logger();
+        let (r1, m1, r2, m2) = test_inputs();
+        let program = crt_combine_program(&r1, &m1, &r2, &m2);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let result = (0..8)
+            .map(|i| runtime.word(OUT_PTR + i * 4))
+            .collect::<Vec<_>>();
+        let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&result));
+        assert_eq!(&x % &m1, r1);
+        assert_eq!(&x % &m2, r2);
+        assert!(x < &m1 * &m2);
+        assert_eq!(x, crt_combine(&r1, &m1, &r2, &m2));
+    }
+
+    #[test]
+    #[should_panic(expected = "crt_combine moduli are not coprime")]
+    fn test_crt_combine_not_coprime() {
+        crt_combine(
+            &BigUint::from(1u32),
+            &BigUint::from(6u32),
+            &BigUint::from(2u32),
+            &BigUint::from(9u32),
+        );
+    }
+
+    #[test]
+    fn test_crt_combine_prove() {
+        utils::setup_logger();
+        let (r1, m1, r2, m2) = test_inputs();
+        run_test(crt_combine_program(&r1, &m1, &r2, &m2)).unwrap();
+        let [r1, m1, r2, m2] = [2u32, 3, 3, 5].map(BigUint::from);
+        run_test(crt_combine_program(&r1, &m1, &r2, &m2)).unwrap();
+    }
+}
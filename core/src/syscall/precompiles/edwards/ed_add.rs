@@ -422,10 +422,167 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use num::BigUint;
+    use sp1_zkvm::precompiles::ed25519::{
+        edwards_mul, scalar_from_bytes, scalar_is_canonical, EdwardsPoint, ED25519_GROUP_ORDER,
+    };
+
+    use crate::runtime::{Instruction, Opcode, Runtime, SyscallCode};
     use crate::utils;
-    use crate::utils::tests::{ED25519_ELF, ED_ADD_ELF};
+    use crate::utils::ec::edwards::ed25519::{decompress, Ed25519};
+    use crate::utils::ec::{AffinePoint, EllipticCurve};
+    use crate::utils::tests::{store_words, ED25519_ELF, ED_ADD_ELF};
+    use crate::utils::SP1CoreOpts;
     use crate::Program;
 
+    /// The RFC 8032 test vector of the empty message: the public key `A`, the signature `(R, S)`,
+    /// and the scalar `k = SHA-512(R || A) mod L`.
+    const PUBKEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const SIGNATURE: &str = concat!(
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155",
+        "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    );
+    const MSG_HASH: &str = "86eabc8e4c96193d290504e7c600df6cf8d8256131ec2c138a3e7e162e525404";
+
+    /// The buffer to which a point is copied to be doubled.
+    const TEMP_PTR: u32 = 0x1000;
+
+    /// The instructions of a test program and the next free point buffer in its memory.
+    struct PointProgram {
+        instructions: Vec<Instruction>,
+        next_ptr: u32,
+    }
+
+    /// An ed25519 point in the memory of a test program, which emits the precompile calls made on
+    /// it along with its reference value, so that the guest verification equation can be run with
+    /// the precompiles.
+    struct ProgramPoint {
+        ptr: u32,
+        value: AffinePoint<Ed25519>,
+        program: Rc<RefCell<PointProgram>>,
+    }
+
+    fn copy_point(instructions: &mut Vec<Instruction>, src: u32, dst: u32) {
+        for i in 0..16 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 30, 0, src + i * 4, false, true),
+                Instruction::new(Opcode::LW, 29, 30, 0, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, dst + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+    }
+
+    fn ed_add(instructions: &mut Vec<Instruction>, p: u32, q: u32) {
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::ED_ADD as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, p, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, q, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+    }
+
+    impl ProgramPoint {
+        /// Stores `value` in a new buffer of the program.
+        fn new(program: &Rc<RefCell<PointProgram>>, value: AffinePoint<Ed25519>) -> Self {
+            let mut state = program.borrow_mut();
+            let ptr = state.next_ptr;
+            state.next_ptr += 64;
+            state
+                .instructions
+                .extend(store_words(ptr, value.to_words_le()));
+            drop(state);
+            Self {
+                ptr,
+                value,
+                program: program.clone(),
+            }
+        }
+    }
+
+    impl Clone for ProgramPoint {
+        fn clone(&self) -> Self {
+            let mut state = self.program.borrow_mut();
+            let ptr = state.next_ptr;
+            state.next_ptr += 64;
+            copy_point(&mut state.instructions, self.ptr, ptr);
+            Self {
+                ptr,
+                value: self.value.clone(),
+                program: self.program.clone(),
+            }
+        }
+    }
+
+    impl EdwardsPoint for ProgramPoint {
+        fn add_assign(&mut self, other: &Self) {
+            let mut state = self.program.borrow_mut();
+            ed_add(&mut state.instructions, self.ptr, other.ptr);
+            drop(state);
+            self.value = self.value.ed_add(&other.value);
+        }
+
+        fn double(&mut self) {
+            let mut state = self.program.borrow_mut();
+            copy_point(&mut state.instructions, self.ptr, TEMP_PTR);
+            ed_add(&mut state.instructions, self.ptr, TEMP_PTR);
+            drop(state);
+            self.value = self.value.ed_double();
+        }
+
+        fn is_equal(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+    }
+
+    /// Verifies the signature `sig` by `pubkey` of the message with the scalar `msg_hash` the same
+    /// way as `syscall_ed25519_verify`, with the guest scalar multiplication and the twisted
+    /// Edwards precompiles.
+    ///
+    /// Returns whether the signature is valid, after checking that the two sides of the equation
+    /// computed by the program agree with the reference arithmetic.
+    fn check_ed25519_verify(sig: &[u8; 64], pubkey: &[u8; 32], msg_hash: &[u8; 32]) -> bool {
+        let s = scalar_from_bytes(sig[32..].try_into().unwrap());
+        if !scalar_is_canonical(&s) {
+            return false;
+        }
+        let k = scalar_from_bytes(msg_hash);
+        let r = decompress(&CompressedEdwardsY(sig[..32].try_into().unwrap()));
+        let a = decompress(&CompressedEdwardsY(*pubkey));
+
+        let program = Rc::new(RefCell::new(PointProgram {
+            instructions: vec![],
+            next_ptr: TEMP_PTR + 64,
+        }));
+        let identity = ProgramPoint::new(&program, Ed25519::neutral());
+        let base = ProgramPoint::new(&program, Ed25519::ec_generator());
+        let r = ProgramPoint::new(&program, r);
+        let a = ProgramPoint::new(&program, a);
+        let lhs = edwards_mul(&identity, &base, &s);
+        let mut rhs = edwards_mul(&identity, &a, &k);
+        rhs.add_assign(&r);
+        let valid = lhs.is_equal(&rhs);
+        let (lhs_ptr, rhs_ptr) = (lhs.ptr, rhs.ptr);
+        let (lhs_value, rhs_value) = (lhs.value.to_words_le(), rhs.value.to_words_le());
+        drop((identity, base, r, a, lhs, rhs));
+
+        let instructions = Rc::try_unwrap(program).ok().unwrap().into_inner().instructions;
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let read_point = |ptr: u32| {
+            (0..16)
+                .map(|i| runtime.word(ptr + i * 4))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(read_point(lhs_ptr), lhs_value);
+        assert_eq!(read_point(rhs_ptr), rhs_value);
+        valid
+    }
+
     #[test]
     fn test_ed_add_simple() {
         utils::setup_logger();
@@ -439,4 +596,27 @@ mod tests {
         let program = Program::from(ED25519_ELF);
         utils::run_test(program).unwrap();
     }
+
+    #[test]
+    fn test_ed25519_verify() {
+        utils::setup_logger();
+        let pubkey: [u8; 32] = hex::decode(PUBKEY).unwrap().try_into().unwrap();
+        let sig: [u8; 64] = hex::decode(SIGNATURE).unwrap().try_into().unwrap();
+        let msg_hash: [u8; 32] = hex::decode(MSG_HASH).unwrap().try_into().unwrap();
+        assert!(check_ed25519_verify(&sig, &pubkey, &msg_hash));
+
+        // A tampered signature or message is rejected.
+        let mut tampered_sig = sig;
+        tampered_sig[32] ^= 1;
+        assert!(!check_ed25519_verify(&tampered_sig, &pubkey, &msg_hash));
+        let mut tampered_hash = msg_hash;
+        tampered_hash[0] ^= 1;
+        assert!(!check_ed25519_verify(&sig, &pubkey, &tampered_hash));
+
+        // A signature with `S + L` in place of `S` is rejected, although it satisfies the equation.
+        let s = BigUint::from_bytes_le(&sig[32..]) + BigUint::from_slice(&ED25519_GROUP_ORDER);
+        let mut malleated_sig = sig;
+        malleated_sig[32..].copy_from_slice(&s.to_bytes_le());
+        assert!(!check_ed25519_verify(&malleated_sig, &pubkey, &msg_hash));
+    }
 }
@@ -0,0 +1,512 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::{base64_char, shr_carry, BASE64_PAD};
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{read_encode_input, ENCODE_HEADER_WORDS};
+
+/// The number of columns in the Base64EncodeCols.
+const NUM_COLS: usize = size_of::<Base64EncodeCols<u8>>();
+
+/// The number of groups of three bytes encoded by each row, each into one output word.
+const GROUPS: usize = 4;
+
+/// The number of bytes of data encoded by each row.
+pub const BASE64_ROW_BYTES: usize = 3 * GROUPS;
+
+/// The number of words of data read by each row.
+const DATA_WORDS: usize = BASE64_ROW_BYTES / WORD_SIZE;
+
+/// The shifts splitting each byte of a group between two 6-bit groups.
+const SHIFTS: [u8; 3] = [2, 4, 6];
+
+/// A precompile writing the base64 encoding of a buffer, with the standard alphabet and padding.
+///
+/// Each row encodes three words of data into four output words, each holding the four characters
+/// of a group of three bytes. The bytes are split into 6-bit groups with shift lookups, whose
+/// characters are then looked up. The last group of the data is padded with `=` if it has fewer
+/// than three bytes, so the output is always whole words. The data is read at the clock of the
+/// syscall and the output written a cycle later.
+#[derive(Default)]
+pub struct Base64EncodeChip;
+
+impl Base64EncodeChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for a group of three bytes of the Base64Encode operation.
+#[derive(Debug, Clone, Copy, AlignedBorrow)]
+#[repr(C)]
+pub struct Base64GroupCols<T> {
+    /// Whether the group has three, two or one bytes of data. A group with no data has none set.
+    pub is_full: T,
+    pub is_two: T,
+    pub is_one: T,
+
+    /// The bytes of data of the group, which are zero past the data.
+    pub bytes: [T; 3],
+
+    /// The high and low bits of each byte, split by `SHIFTS`.
+    pub shifted: [T; 3],
+    pub carries: [T; 3],
+
+    /// The characters of the four 6-bit groups, before padding.
+    pub chars: [T; 4],
+}
+
+/// A set of columns for three words of the Base64Encode operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Base64EncodeCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output.
+    pub out_ptr: T,
+
+    /// The pointer to the header, which contains the data pointer followed by its length.
+    pub header_ptr: T,
+
+    /// The pointer to the data.
+    pub data_ptr: T,
+
+    /// The number of bytes of data.
+    pub len: T,
+
+    /// The index of the row in the call.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; ENCODE_HEADER_WORDS],
+
+    /// The words of data, of which the last row may read past the data.
+    pub data_memory: [MemoryReadCols<T>; DATA_WORDS],
+
+    /// The output words, one per group with data.
+    pub out_memory: [MemoryWriteCols<T>; GROUPS],
+
+    /// The columns of each group.
+    pub groups: [Base64GroupCols<T>; GROUPS],
+
+    pub is_real: T,
+}
+
+/// Returns the four 6-bit groups of three bytes.
+fn sextets(bytes: [u8; 3]) -> [u8; 4] {
+    [
+        bytes[0] >> 2,
+        ((bytes[0] & 0x3) << 4) | (bytes[1] >> 4),
+        ((bytes[1] & 0xf) << 2) | (bytes[2] >> 6),
+        bytes[2] & 0x3f,
+    ]
+}
+
+/// Returns the base64 encoding of a group of one to three bytes, padded to four characters.
+fn encode_group(group: &[u8]) -> [u8; 4] {
+    let mut bytes = [0u8; 3];
+    bytes[..group.len()].copy_from_slice(group);
+    let mut chars = sextets(bytes).map(base64_char);
+    chars[group.len() + 1..].fill(BASE64_PAD);
+    chars
+}
+
+impl<F: PrimeField32> Base64GroupCols<F> {
+    pub fn populate(
+        &mut self,
+        blu: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        group: &[u8],
+    ) {
+        self.is_full = F::from_bool(group.len() == 3);
+        self.is_two = F::from_bool(group.len() == 2);
+        self.is_one = F::from_bool(group.len() == 1);
+
+        let mut bytes = [0u8; 3];
+        bytes[..group.len()].copy_from_slice(group);
+        for (t, &byte) in bytes.iter().enumerate() {
+            let (shifted, carry) = shr_carry(byte, SHIFTS[t]);
+            blu.add_byte_lookup_event(ByteLookupEvent::new(
+                shard,
+                channel,
+                ByteOpcode::ShrCarry,
+                shifted as u32,
+                carry as u32,
+                byte as u32,
+                SHIFTS[t] as u32,
+            ));
+            self.bytes[t] = F::from_canonical_u8(byte);
+            self.shifted[t] = F::from_canonical_u8(shifted);
+            self.carries[t] = F::from_canonical_u8(carry);
+        }
+        for (i, sextet) in sextets(bytes).into_iter().enumerate() {
+            let base64 = base64_char(sextet);
+            blu.add_byte_lookup_event(ByteLookupEvent::new(
+                shard,
+                channel,
+                ByteOpcode::Base64Char,
+                base64 as u32,
+                0,
+                sextet as u32,
+                0,
+            ));
+            self.chars[i] = F::from_canonical_u8(base64);
+        }
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Base64EncodeChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Base64Encode".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.base64_encode_events.iter() {
+            let len = event.len as usize;
+            let num_rows = event.data_memory_records.len() / DATA_WORDS;
+            let mut out_records = event.out_memory_records.iter();
+            for i in 0..num_rows {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Base64EncodeCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.data_ptr = F::from_canonical_u32(event.data_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == num_rows - 1);
+
+                // Populate memory columns. The header is only read on the first row.
+                if i == 0 {
+                    for j in 0..ENCODE_HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                let data_records = &event.data_memory_records[i * DATA_WORDS..(i + 1) * DATA_WORDS];
+                for (data_memory, record) in cols.data_memory.iter_mut().zip(data_records) {
+                    data_memory.populate(event.channel, *record, &mut new_byte_lookup_events);
+                }
+
+                // Populate the groups with data, and the output words they write.
+                let num_bytes = (len - i * BASE64_ROW_BYTES).min(BASE64_ROW_BYTES);
+                let data = data_records
+                    .iter()
+                    .flat_map(|record| record.value.to_le_bytes())
+                    .take(num_bytes)
+                    .collect::<Vec<_>>();
+                for (g, group) in data.chunks(3).enumerate() {
+                    cols.groups[g].populate(
+                        &mut new_byte_lookup_events,
+                        event.shard,
+                        event.channel,
+                        group,
+                    );
+                    cols.out_memory[g].populate(
+                        event.channel,
+                        *out_records.next().unwrap(),
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Base64EncodeCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.base64_encode_events.is_empty()
+    }
+}
+
+impl Syscall for Base64EncodeChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let (mut event, data) = read_encode_input(rt, arg1, arg2, BASE64_ROW_BYTES);
+
+        // Write the characters a cycle after the data is read, so that the output may overlap it.
+        let encoded = data
+            .chunks(3)
+            .map(|group| u32::from_le_bytes(encode_group(group)))
+            .collect::<Vec<_>>();
+        rt.clk += 1;
+        event.out_memory_records = rt.mw_slice(event.out_ptr, &encoded);
+
+        rt.record_mut().base64_encode_events.push(event);
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Base64EncodeChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Base64EncodeChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Base64EncodeCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Base64EncodeCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued.clone(), next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        let header_data_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_len = local.header_memory[1].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.data_ptr, header_data_ptr);
+        first.assert_eq(local.len, header_len);
+        first.assert_zero(local.index);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.out_ptr, local.out_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.data_ptr, local.data_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+
+        // The groups with data are a nonempty prefix of the groups, all full but the last one. The
+        // row holds a whole row of data except on the last row, where it ends the data.
+        let mut num_bytes = AB::Expr::zero();
+        for g in 0..GROUPS {
+            let group = &local.groups[g];
+            builder.assert_bool(group.is_full);
+            builder.assert_bool(group.is_two);
+            builder.assert_bool(group.is_one);
+            let is_active = group.is_full + group.is_two + group.is_one;
+            builder.assert_bool(is_active.clone());
+            if g == 0 {
+                builder.assert_eq(is_active, local.is_real);
+            } else {
+                builder
+                    .when(is_active)
+                    .assert_one(local.groups[g - 1].is_full);
+            }
+            num_bytes = num_bytes
+                + group.is_full * AB::F::from_canonical_u32(3)
+                + group.is_two * AB::F::two()
+                + group.is_one;
+        }
+        builder
+            .when(is_continued)
+            .assert_eq(num_bytes.clone(), AB::F::from_canonical_usize(BASE64_ROW_BYTES));
+        builder.when(local.is_last).assert_eq(
+            local.index * AB::F::from_canonical_usize(BASE64_ROW_BYTES) + num_bytes,
+            local.len,
+        );
+
+        let data = local
+            .data_memory
+            .iter()
+            .flat_map(|memory| memory.value().0)
+            .collect::<Vec<_>>();
+        let pad = AB::F::from_canonical_u8(BASE64_PAD);
+        for g in 0..GROUPS {
+            let group = &local.groups[g];
+            let is_active = group.is_full + group.is_two + group.is_one;
+
+            // Assert that the bytes of the group are those of the data, and zero past it.
+            let is_data = [
+                is_active.clone(),
+                group.is_full + group.is_two,
+                group.is_full.into(),
+            ];
+            for t in 0..3 {
+                builder.assert_eq(group.bytes[t], data[3 * g + t] * is_data[t].clone());
+            }
+
+            // Split the bytes into 6-bit groups, and look up their characters.
+            for t in 0..3 {
+                builder.send_byte_pair(
+                    ByteOpcode::ShrCarry.as_field::<AB::F>(),
+                    group.shifted[t],
+                    group.carries[t],
+                    group.bytes[t],
+                    AB::F::from_canonical_u8(SHIFTS[t]),
+                    local.shard,
+                    local.channel,
+                    is_active.clone(),
+                );
+            }
+            let sextets: [AB::Expr; 4] = [
+                group.shifted[0].into(),
+                group.carries[0] * AB::F::from_canonical_u32(16) + group.shifted[1],
+                group.carries[1] * AB::F::from_canonical_u32(4) + group.shifted[2],
+                group.carries[2].into(),
+            ];
+            for (base64, sextet) in group.chars.iter().zip(sextets) {
+                builder.send_byte(
+                    ByteOpcode::Base64Char.as_field::<AB::F>(),
+                    *base64,
+                    sextet,
+                    AB::Expr::zero(),
+                    local.shard,
+                    local.channel,
+                    is_active.clone(),
+                );
+            }
+
+            // Assert that the output word holds the characters, padded past the data.
+            let out = local.out_memory[g].value();
+            let mut when_active = builder.when(is_active.clone());
+            when_active.assert_eq(out[0], group.chars[0]);
+            when_active.assert_eq(out[1], group.chars[1]);
+            when_active.assert_eq(
+                out[2],
+                group.chars[2] * (group.is_full + group.is_two) + group.is_one * pad,
+            );
+            when_active.assert_eq(
+                out[3],
+                group.chars[3] * group.is_full + (group.is_two + group.is_one) * pad,
+            );
+        }
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the words of data, and write the output words a cycle later.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.data_ptr + local.index * AB::F::from_canonical_usize(BASE64_ROW_BYTES),
+            &local.data_memory,
+            local.is_real,
+        );
+        for g in 0..GROUPS {
+            let group = &local.groups[g];
+            let offset = local.index * AB::F::from_canonical_usize(GROUPS * WORD_SIZE)
+                + AB::F::from_canonical_usize(g * WORD_SIZE);
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk + AB::F::one(),
+                local.out_ptr + offset,
+                &local.out_memory[g],
+                group.is_full + group.is_two + group.is_one,
+            );
+        }
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::BASE64_ENCODE.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
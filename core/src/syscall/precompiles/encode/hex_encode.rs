@@ -0,0 +1,398 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::HEX_DIGITS;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{read_encode_input, ENCODE_HEADER_WORDS};
+
+/// The number of columns in the HexEncodeCols.
+const NUM_COLS: usize = size_of::<HexEncodeCols<u8>>();
+
+/// The number of bytes of data encoded by each row.
+pub const HEX_ROW_BYTES: usize = WORD_SIZE;
+
+/// The number of output words written by each row.
+const OUT_WORDS: usize = 2;
+
+/// A precompile writing the lowercase hex encoding of a buffer.
+///
+/// Each row encodes one word of data into two output words, with a byte lookup per byte giving the
+/// digits of both its nibbles. The data is read at the clock of the syscall and the output written
+/// a cycle later. The encoding of an odd number of bytes ends in the middle of a word, whose other
+/// half keeps its previous value.
+#[derive(Default)]
+pub struct HexEncodeChip;
+
+impl HexEncodeChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one word of the HexEncode operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct HexEncodeCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output.
+    pub out_ptr: T,
+
+    /// The pointer to the header, which contains the data pointer followed by its length.
+    pub header_ptr: T,
+
+    /// The pointer to the data.
+    pub data_ptr: T,
+
+    /// The number of bytes of data.
+    pub len: T,
+
+    /// The index of the word of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// Whether each byte of the word is part of the data, which holds for a prefix of the bytes.
+    pub is_data: [T; WORD_SIZE],
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; ENCODE_HEADER_WORDS],
+
+    /// The word of data.
+    pub data_memory: MemoryReadCols<T>,
+
+    /// The output words, the second of which is only written if the data has more than two bytes.
+    pub out_memory: [MemoryWriteCols<T>; OUT_WORDS],
+
+    /// The hex digits of the high and low nibbles of each byte.
+    pub hi_digits: [T; WORD_SIZE],
+    pub lo_digits: [T; WORD_SIZE],
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for HexEncodeChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "HexEncode".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.hex_encode_events.iter() {
+            let len = event.len as usize;
+            let mut out_records = event.out_memory_records.iter();
+            for (i, data_record) in event.data_memory_records.iter().enumerate() {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut HexEncodeCols<F> = row.as_mut_slice().borrow_mut();
+                let num_bytes = (len - i * HEX_ROW_BYTES).min(HEX_ROW_BYTES);
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.data_ptr = F::from_canonical_u32(event.data_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == event.data_memory_records.len() - 1);
+
+                // Populate memory columns. The header is only read on the first row.
+                if i == 0 {
+                    for j in 0..ENCODE_HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                cols.data_memory.populate(
+                    event.channel,
+                    *data_record,
+                    &mut new_byte_lookup_events,
+                );
+                for out_memory in cols.out_memory[..num_bytes.div_ceil(2)].iter_mut() {
+                    out_memory.populate(
+                        event.channel,
+                        *out_records.next().unwrap(),
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Look up the digits of each byte of data.
+                for (k, byte) in data_record.value.to_le_bytes()[..num_bytes].iter().enumerate() {
+                    let hi = HEX_DIGITS[(byte >> 4) as usize];
+                    let lo = HEX_DIGITS[(byte & 0xf) as usize];
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                        event.shard,
+                        event.channel,
+                        ByteOpcode::HexByte,
+                        hi as u32,
+                        lo as u32,
+                        *byte as u32,
+                        0,
+                    ));
+                    cols.is_data[k] = F::one();
+                    cols.hi_digits[k] = F::from_canonical_u8(hi);
+                    cols.lo_digits[k] = F::from_canonical_u8(lo);
+                }
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut HexEncodeCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.hex_encode_events.is_empty()
+    }
+}
+
+impl Syscall for HexEncodeChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let (mut event, data) = read_encode_input(rt, arg1, arg2, HEX_ROW_BYTES);
+
+        // Write the digits a cycle after the data is read, so that the output may overlap it. The
+        // bytes of the last word past the encoding keep their previous value.
+        let encoded = data
+            .iter()
+            .flat_map(|byte| [HEX_DIGITS[(byte >> 4) as usize], HEX_DIGITS[(byte & 0xf) as usize]])
+            .collect::<Vec<_>>();
+        rt.clk += 1;
+        for (i, chunk) in encoded.chunks(WORD_SIZE).enumerate() {
+            let addr = event.out_ptr + (i * WORD_SIZE) as u32;
+            let mut word = rt.slice_unsafe(addr, 1)[0].to_le_bytes();
+            word[..chunk.len()].copy_from_slice(chunk);
+            let record = rt.mw(addr, u32::from_le_bytes(word));
+            event.out_memory_records.push(record);
+        }
+
+        rt.record_mut().hex_encode_events.push(event);
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for HexEncodeChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for HexEncodeChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &HexEncodeCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &HexEncodeCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued.clone(), next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        let header_data_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_len = local.header_memory[1].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.data_ptr, header_data_ptr);
+        first.assert_eq(local.len, header_len);
+        first.assert_zero(local.index);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.out_ptr, local.out_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.data_ptr, local.data_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+
+        // The bytes of data are a nonempty prefix of the word, which is the whole word except on
+        // the last row, where it ends the data.
+        let num_bytes = local
+            .is_data
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &is_data| acc + is_data);
+        builder.assert_eq(local.is_data[0], local.is_real);
+        for k in 0..WORD_SIZE {
+            builder.assert_bool(local.is_data[k]);
+            if k + 1 < WORD_SIZE {
+                builder
+                    .when(local.is_data[k + 1])
+                    .assert_one(local.is_data[k]);
+            }
+        }
+        builder
+            .when(is_continued)
+            .assert_eq(num_bytes.clone(), AB::F::from_canonical_usize(HEX_ROW_BYTES));
+        builder.when(local.is_last).assert_eq(
+            local.index * AB::F::from_canonical_usize(HEX_ROW_BYTES) + num_bytes,
+            local.len,
+        );
+
+        // Look up the digits of each byte of data.
+        let data = *local.data_memory.value();
+        for k in 0..WORD_SIZE {
+            builder.send_byte_pair(
+                ByteOpcode::HexByte.as_field::<AB::F>(),
+                local.hi_digits[k],
+                local.lo_digits[k],
+                data[k],
+                AB::Expr::zero(),
+                local.shard,
+                local.channel,
+                local.is_data[k],
+            );
+        }
+
+        // Assert that each output word holds the digits of two bytes, keeping its previous bytes
+        // in place of the digits of bytes past the data.
+        for w in 0..OUT_WORDS {
+            let out = local.out_memory[w].value();
+            let prev = local.out_memory[w].prev_value();
+            for j in 0..WORD_SIZE {
+                let k = 2 * w + j / 2;
+                let digit = if j % 2 == 0 {
+                    local.hi_digits[k]
+                } else {
+                    local.lo_digits[k]
+                };
+                let expected = digit * local.is_data[k]
+                    + prev[j] * (AB::Expr::one() - local.is_data[k]);
+                builder
+                    .when(local.is_data[2 * w])
+                    .assert_eq(out[j], expected);
+            }
+        }
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the word of data, and write the output words a cycle later.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.data_ptr + local.index * AB::F::from_canonical_usize(HEX_ROW_BYTES),
+            &local.data_memory,
+            local.is_real,
+        );
+        for w in 0..OUT_WORDS {
+            let offset = local.index * AB::F::from_canonical_usize(OUT_WORDS * WORD_SIZE)
+                + AB::F::from_canonical_usize(w * WORD_SIZE);
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk + AB::F::one(),
+                local.out_ptr + offset,
+                &local.out_memory[w],
+                local.is_data[2 * w],
+            );
+        }
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::HEX_ENCODE.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,186 @@
+mod base64_encode;
+mod hex_encode;
+
+pub use base64_encode::*;
+pub use hex_encode::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord, SyscallContext};
+
+/// The number of words of the header, which contains the data pointer followed by its length in
+/// bytes.
+pub const ENCODE_HEADER_WORDS: usize = 2;
+
+/// An event of an encoding precompile, which writes the encoding of `len` bytes of data to the
+/// output buffer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodeEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub header_ptr: u32,
+    pub data_ptr: u32,
+    /// The number of bytes of data.
+    pub len: u32,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    /// The words of the data, the last of which may hold bytes past its end.
+    pub data_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+impl EncodeEvent {
+    /// The number of rows of the event in a chip encoding `row_bytes` bytes of data per row.
+    pub fn num_rows(&self, row_bytes: usize) -> usize {
+        (self.len as usize).div_ceil(row_bytes)
+    }
+}
+
+/// Reads the header and the data of an encoding syscall, reading `row_bytes` bytes of data per
+/// row, and returns the event without its output records together with the bytes of the data.
+///
+/// The words of the last row are read whole, so the last row may read past the end of the data.
+pub(crate) fn read_encode_input(
+    rt: &mut SyscallContext,
+    out_ptr: u32,
+    header_ptr: u32,
+    row_bytes: usize,
+) -> (EncodeEvent, Vec<u8>) {
+    let clk = rt.clk;
+    rt.require_aligned(out_ptr, 4).unwrap();
+    rt.require_aligned(header_ptr, 4).unwrap();
+
+    let (header_memory_records, header) = rt.mr_slice(header_ptr, ENCODE_HEADER_WORDS);
+    let data_ptr = header[0];
+    let len = header[1];
+    rt.require_aligned(data_ptr, 4).unwrap();
+    if len == 0 {
+        panic!("encoding length must be nonzero");
+    }
+
+    let num_rows = (len as usize).div_ceil(row_bytes);
+    let (data_memory_records, data) = rt.mr_slice(data_ptr, num_rows * row_bytes / 4);
+    let mut bytes = data
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect::<Vec<_>>();
+    bytes.truncate(len as usize);
+
+    let event = EncodeEvent {
+        lookup_id: rt.syscall_lookup_id,
+        shard: rt.current_shard(),
+        channel: rt.current_channel(),
+        clk,
+        out_ptr,
+        header_ptr,
+        data_ptr,
+        len,
+        header_memory_records,
+        data_memory_records,
+        out_memory_records: Vec::new(),
+    };
+    (event, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine, BASE64_STANDARD};
+
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const DATA_PTR: u32 = 0x1000;
+    const HEADER_PTR: u32 = 0x2000;
+    const OUT_PTR: u32 = 0x3000;
+
+    /// A word written to the output buffer before encoding, to check the bytes past the encoding.
+    const CANARY: u32 = 0xa5a5_a5a5;
+
+    /// Stores `data` at `DATA_PTR`, with ones past its end in its last word, and fills the output
+    /// buffer with `CANARY`, then encodes the data to `OUT_PTR` with the syscall `code`.
+    fn encode_program(code: SyscallCode, data: &[u8]) -> Program {
+        let data_words = data.chunks(4).map(|chunk| {
+            let mut word = [0xff; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        });
+        let out_words = data.len() / 2 + 2;
+        let mut instructions = store_words(DATA_PTR, data_words);
+        instructions.extend(store_words(OUT_PTR, (0..out_words).map(|_| CANARY)));
+        instructions.extend(store_words(HEADER_PTR, [DATA_PTR, data.len() as u32]));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Encodes `data` with the syscall `code` and checks the output against `expected`, and that
+    /// the rest of the output buffer is left untouched.
+    fn check_encode(code: SyscallCode, data: &[u8], expected: &str) {
+        let mut runtime = Runtime::new(encode_program(code, data), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let out_words = data.len() / 2 + 2;
+        let out = (0..out_words as u32)
+            .flat_map(|i| runtime.word(OUT_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(&out[..expected.len()], expected.as_bytes());
+        assert!(out[expected.len()..]
+            .iter()
+            .all(|&byte| byte == CANARY as u8));
+    }
+
+    /// Byte arrays covering every length modulo the row sizes, with all byte values.
+    fn test_vectors() -> Vec<Vec<u8>> {
+        let mut vectors = vec![b"f".to_vec(), b"fo".to_vec(), b"foobar".to_vec()];
+        vectors.extend((1..=25).map(|len| (0..len).map(|i| (i * 73 + 11) as u8).collect()));
+        vectors.push((0..=255).collect());
+        vectors
+    }
+
+    #[test]
+    fn test_hex_encode() {
+        utils::setup_logger();
+        for data in test_vectors() {
+            check_encode(SyscallCode::HEX_ENCODE, &data, &hex::encode(&data));
+        }
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        utils::setup_logger();
+        for data in test_vectors() {
+            let expected = BASE64_STANDARD.encode(&data);
+            check_encode(SyscallCode::BASE64_ENCODE, &data, &expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding length must be nonzero")]
+    fn test_encode_empty() {
+        check_encode(SyscallCode::HEX_ENCODE, &[], "");
+    }
+
+    #[test]
+    fn test_hex_encode_prove() {
+        utils::setup_logger();
+        for len in [1, 2, 7, 8] {
+            let data = (0..len).map(|i| (i * 73 + 11) as u8).collect::<Vec<_>>();
+            run_test(encode_program(SyscallCode::HEX_ENCODE, &data)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_base64_encode_prove() {
+        utils::setup_logger();
+        for len in [1, 2, 3, 13, 24] {
+            let data = (0..len).map(|i| (i * 73 + 11) as u8).collect::<Vec<_>>();
+            run_test(encode_program(SyscallCode::BASE64_ENCODE, &data)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::NumWords;
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le,
+    words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::Zero;
+use num::{BigUint, One};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the FieldAffineCols.
+const NUM_COLS: usize = size_of::<FieldAffineCols<u8>>();
+
+/// The number of words of the header, which contains the pointers to a, b and the modulus.
+pub const FIELD_AFFINE_HEADER_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldAffineEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub header_ptr: u32,
+    pub a_ptr: u32,
+    pub b_ptr: u32,
+    pub modulus_ptr: u32,
+    pub x: Vec<u32>,
+    pub a: Vec<u32>,
+    pub b: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub a_memory_records: Vec<MemoryReadRecord>,
+    pub b_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A precompile computing the affine combination `x = (a * x + b) mod modulus` in place.
+///
+/// The product is reduced first, and `b` is then added to the reduced product, which gives the same
+/// result as reducing `a * x + b`, at the cost of a single syscall instead of a multiplication
+/// followed by an addition. A zero modulus stands for `2^256`. The header, a, b and the modulus are
+/// each read a cycle apart and x is written last, so any of the operands may share their memory.
+#[derive(Default)]
+pub struct FieldAffineChip;
+
+impl FieldAffineChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// A set of columns for the FieldAffine operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldAffineCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x, which is overwritten with the result.
+    pub x_ptr: T,
+
+    /// The pointer to the header, which contains the pointers to a, b and the modulus.
+    pub header_ptr: T,
+
+    /// The pointers read from the header.
+    pub a_ptr: T,
+    pub b_ptr: T,
+    pub modulus_ptr: T,
+
+    // Memory columns.
+    // x_memory is written to with the result, which is why it is of type MemoryWriteCols.
+    pub header_memory: [MemoryReadCols<T>; FIELD_AFFINE_HEADER_WORDS],
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub a_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+    pub b_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    // Columns for checking if modulus is zero. If it's zero, then use 2^256 as the effective modulus.
+    pub modulus_is_zero: IsZeroOperation<T>,
+
+    /// The reduced product `(a * x) % modulus`.
+    pub product: FieldOpCols<T, U256Field>,
+
+    /// The result `(product + b) % modulus`.
+    pub sum: FieldOpCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for FieldAffineChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "FieldAffine".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .field_affine_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut FieldAffineCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Decode the uint256 values.
+                let x = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.x));
+                let a = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.a));
+                let b = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.b));
+                let modulus = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.modulus));
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.a_ptr = F::from_canonical_u32(event.a_ptr);
+                cols.b_ptr = F::from_canonical_u32(event.b_ptr);
+                cols.modulus_ptr = F::from_canonical_u32(event.modulus_ptr);
+
+                // Populate memory columns.
+                for i in 0..FIELD_AFFINE_HEADER_WORDS {
+                    cols.header_memory[i].populate(
+                        event.channel,
+                        event.header_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.a_memory[i].populate(
+                        event.channel,
+                        event.a_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.b_memory[i].populate(
+                        event.channel,
+                        event.b_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let modulus_bytes = words_to_bytes_le_vec(&event.modulus);
+                let modulus_byte_sum = modulus_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.modulus_is_zero, modulus_byte_sum);
+
+                // Populate the product and the sum.
+                let effective_modulus = if modulus.is_zero() {
+                    BigUint::one() << 256
+                } else {
+                    modulus
+                };
+                let product = cols.product.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &a,
+                    &x,
+                    &effective_modulus,
+                    FieldOperation::Mul,
+                );
+                cols.sum.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &product,
+                    &b,
+                    &effective_modulus,
+                    FieldOperation::Add,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut FieldAffineCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            cols.product
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            cols.sum
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Add);
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut FieldAffineCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.field_affine_events.is_empty()
+    }
+}
+
+impl Syscall for FieldAffineChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        let header_ptr = arg2;
+        if x_ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let start_clk = rt.clk;
+
+        // First read the words for x. We can read a slice_unsafe here because we write the
+        // computed result to it later.
+        let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
+
+        // Read the header, which contains the pointers to a, b and the modulus.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, FIELD_AFFINE_HEADER_WORDS);
+        let (a_ptr, b_ptr, modulus_ptr) = (header[0], header[1], header[2]);
+        if a_ptr % 4 != 0 || b_ptr % 4 != 0 || modulus_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read a, b and the modulus a cycle apart, since they could be the same.
+        let (a_memory_records, a) = rt.mr_slice(a_ptr, WORDS_FIELD_ELEMENT);
+        rt.clk += 1;
+        let (b_memory_records, b) = rt.mr_slice(b_ptr, WORDS_FIELD_ELEMENT);
+        rt.clk += 1;
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        // Get the BigUint values for x, a, b, and the modulus.
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let uint256_a = BigUint::from_bytes_le(&words_to_bytes_le_vec(&a));
+        let uint256_b = BigUint::from_bytes_le(&words_to_bytes_le_vec(&b));
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+
+        // Compute the affine combination and take the result modulo the modulus.
+        let result: BigUint = if uint256_modulus.is_zero() {
+            (uint256_a * uint256_x + uint256_b) % (BigUint::one() << 256)
+        } else {
+            (uint256_a * uint256_x + uint256_b) % uint256_modulus
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8); // Pad the result to 32 bytes.
+
+        // Convert the result to little endian u32 words.
+        let result = bytes_to_words_le::<8>(&result_bytes);
+
+        // Write the result to x after all the reads, since it could be any of the other
+        // operands, and keep track of the memory records.
+        rt.clk += 1;
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().field_affine_events.push(FieldAffineEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk: start_clk,
+            x_ptr,
+            header_ptr,
+            a_ptr,
+            b_ptr,
+            modulus_ptr,
+            x,
+            a,
+            b,
+            modulus,
+            header_memory_records,
+            x_memory_records,
+            a_memory_records,
+            b_memory_records,
+            modulus_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for FieldAffineChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for FieldAffineChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FieldAffineCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &FieldAffineCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The pointers to a, b and the modulus are the words of the header.
+        let header_a_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_b_ptr = local.header_memory[1].value().reduce::<AB>();
+        let header_modulus_ptr = local.header_memory[2].value().reduce::<AB>();
+        builder.when(local.is_real).assert_eq(local.a_ptr, header_a_ptr);
+        builder.when(local.is_real).assert_eq(local.b_ptr, header_b_ptr);
+        builder
+            .when(local.is_real)
+            .assert_eq(local.modulus_ptr, header_modulus_ptr);
+
+        // We are computing (a * x + b) % modulus. The value of x is stored in the "prev_value" of
+        // the x_memory, since we write to it later.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+        let a_limbs = limbs_from_access(&local.a_memory);
+        let b_limbs = limbs_from_access(&local.b_memory);
+        let modulus_limbs = limbs_from_access(&local.modulus_memory);
+
+        // If the modulus is zero, then we don't perform the modulus operation.
+        // Evaluate the modulus_is_zero operation by summing each byte of the modulus. The sum will
+        // not overflow because we are summing 32 bytes.
+        let modulus_byte_sum = modulus_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            modulus_byte_sum,
+            local.modulus_is_zero,
+            local.is_real.into(),
+        );
+
+        // If the modulus is zero, we'll actually use 2^256 as the modulus, so nothing happens.
+        // Otherwise, we use the modulus passed in.
+        let modulus_is_zero = local.modulus_is_zero.result;
+        let mut coeff_2_256 = Vec::new();
+        coeff_2_256.resize(32, AB::Expr::zero());
+        coeff_2_256.push(AB::Expr::one());
+        let modulus_polynomial: Polynomial<AB::Expr> = modulus_limbs.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus_polynomial
+            * (AB::Expr::one() - modulus_is_zero.into())
+            + Polynomial::from_coefficients(&coeff_2_256) * modulus_is_zero.into();
+
+        // Evaluate the reduced product, and then its sum with b.
+        local.product.eval_with_modulus(
+            builder,
+            &a_limbs,
+            &x_limbs,
+            &p_modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.sum.eval_with_modulus(
+            builder,
+            &local.product.result,
+            &b_limbs,
+            &p_modulus,
+            FieldOperation::Add,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.sum.result, value_as_limbs(&local.x_memory));
+
+        // Read the header and a, then b and the modulus a cycle apart each.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.a_ptr,
+            &local.a_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.b_ptr,
+            &local.b_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(2),
+            local.modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+
+        // Read and write x after all the reads.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(3),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::FIELD_AFFINE.syscall_id()),
+            local.x_ptr,
+            local.header_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
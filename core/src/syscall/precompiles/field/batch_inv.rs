@@ -0,0 +1,543 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le,
+    words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the FieldBatchInvCols.
+const NUM_COLS: usize = size_of::<FieldBatchInvCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of words of the header, which contains the modulus followed by the length.
+const HEADER_WORDS: usize = WORDS_FIELD_ELEMENT + 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldBatchInvEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub ptr: u32,
+    pub header_ptr: u32,
+    pub len: u32,
+    pub modulus: Vec<u32>,
+    /// The elements, as `len` consecutive uint256 values.
+    pub x: Vec<u32>,
+    /// The prefix products `x[0] * ... * x[i]` of the elements.
+    pub prefix_products: Vec<u32>,
+    /// The inverse of the product of all the elements.
+    pub inverse: Vec<u32>,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile inverting an array of uint256 field elements in place with Montgomery's trick.
+///
+/// Only the product of all the elements is inverted. Writing `p_i = x_0 * ... * x_i` and
+/// `r_i = p_i^{-1}`, the inverses are recovered with `x_i^{-1} = r_i * p_{i-1}` and
+/// `r_{i-1} = r_i * x_i`, walking back from the single inverse `r_{n-1}`.
+///
+/// The chip has one row per element. Each row constrains the prefix product `p_i`, the step
+/// `r_{i-1} = r_i * x_i` and the inverse `x_i^{-1}`, and the chain of `r_i` is anchored by
+/// `r_{-1} = 1` on the first row of each call.
+#[derive(Default)]
+pub struct FieldBatchInvChip;
+
+impl FieldBatchInvChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one element of the FieldBatchInv operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldBatchInvCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the elements.
+    pub ptr: T,
+
+    /// The pointer to the header, which contains the modulus followed by the number of elements.
+    pub header_ptr: T,
+
+    /// The number of elements.
+    pub len: T,
+
+    /// The index of the element of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; HEADER_WORDS],
+
+    /// The modulus, carried over to every row of the call.
+    pub modulus: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    /// The element, which is written to with its inverse.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// The prefix product of the elements before this one, or one on the first row.
+    pub prefix_in: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    /// The prefix product `p_i = p_{i-1} * x_i`.
+    pub prefix: FieldOpCols<T, U256Field>,
+
+    /// The inverse `r_i` of the prefix product.
+    pub inverse: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    /// The inverse `r_{i-1} = r_i * x_i` of the previous prefix product.
+    pub prev_inverse: FieldOpCols<T, U256Field>,
+
+    /// The inverse of the element, `r_i * p_{i-1}`.
+    pub result: FieldOpCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for FieldBatchInvChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "FieldBatchInv".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.field_batch_inv_events.iter() {
+            let modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.modulus));
+            let decode = |words: &[u32], i: usize| {
+                let words = &words[i * WORDS_FIELD_ELEMENT..(i + 1) * WORDS_FIELD_ELEMENT];
+                BigUint::from_bytes_le(&words_to_bytes_le::<32>(words))
+            };
+
+            // Walk back from the single inverse to the inverses of all the prefix products.
+            let len = event.len as usize;
+            let mut inverses = vec![BigUint::zero(); len];
+            inverses[len - 1] = decode(&event.inverse, 0);
+            for i in (1..len).rev() {
+                inverses[i - 1] = (&inverses[i] * decode(&event.x, i)) % &modulus;
+            }
+
+            for i in 0..len {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut FieldBatchInvCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.ptr = F::from_canonical_u32(event.ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == len - 1);
+
+                // Populate memory columns. The header is only read on the first row.
+                if i == 0 {
+                    for j in 0..HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                cols.modulus = U256Field::to_limbs_field::<F, _>(&modulus);
+                for j in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[j].populate(
+                        event.channel,
+                        event.x_memory_records[i * WORDS_FIELD_ELEMENT + j],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the prefix product.
+                let x = decode(&event.x, i);
+                let prefix_in = if i == 0 {
+                    BigUint::one()
+                } else {
+                    decode(&event.prefix_products, i - 1)
+                };
+                cols.prefix_in = U256Field::to_limbs_field::<F, _>(&prefix_in);
+                cols.prefix.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &prefix_in,
+                    &x,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+
+                // Populate the inverse of the prefix product and the step back to the previous one.
+                cols.inverse = U256Field::to_limbs_field::<F, _>(&inverses[i]);
+                new_byte_lookup_events.add_u8_range_checks_field(
+                    event.shard,
+                    event.channel,
+                    &cols.inverse.0,
+                );
+                cols.prev_inverse.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &inverses[i],
+                    &x,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+
+                // Populate the inverse of the element.
+                cols.result.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &inverses[i],
+                    &prefix_in,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut FieldBatchInvCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            cols.prefix
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            cols.prev_inverse
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            cols.result
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut FieldBatchInvCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.field_batch_inv_events.is_empty()
+    }
+}
+
+impl Syscall for FieldBatchInvChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let ptr = arg1;
+        let header_ptr = arg2;
+        if ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the header, which contains the modulus followed by the number of elements.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, HEADER_WORDS);
+        let modulus = header[..WORDS_FIELD_ELEMENT].to_vec();
+        let len = header[WORDS_FIELD_ELEMENT];
+        if len == 0 {
+            panic!("field_batch_inv length must be nonzero");
+        }
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+        if uint256_modulus <= BigUint::one() {
+            panic!("field_batch_inv modulus must be greater than one");
+        }
+
+        // Read the elements with slice_unsafe since they are overwritten with their inverses.
+        let x = rt.slice_unsafe(ptr, len as usize * WORDS_FIELD_ELEMENT);
+        let elements = x
+            .chunks_exact(WORDS_FIELD_ELEMENT)
+            .map(|words| BigUint::from_bytes_le(&words_to_bytes_le_vec(words)))
+            .collect::<Vec<_>>();
+        if elements.iter().any(|x| x >= &uint256_modulus) {
+            panic!("field_batch_inv input is not reduced modulo the modulus");
+        }
+
+        // Compute the prefix products, and invert the product of all the elements.
+        let mut prefix_products = Vec::with_capacity(elements.len());
+        let mut product = BigUint::one();
+        for x in elements.iter() {
+            product = (product * x) % &uint256_modulus;
+            prefix_products.push(product.clone());
+        }
+        let mut inverse = product
+            .modinv(&uint256_modulus)
+            .expect("field_batch_inv input is not invertible");
+
+        // Walk back through the elements to recover each inverse.
+        let mut result = vec![BigUint::zero(); elements.len()];
+        let single_inverse = inverse.clone();
+        for i in (0..elements.len()).rev() {
+            result[i] = if i == 0 {
+                inverse.clone()
+            } else {
+                (&inverse * &prefix_products[i - 1]) % &uint256_modulus
+            };
+            inverse = (inverse * &elements[i]) % &uint256_modulus;
+        }
+
+        let to_words = |value: &BigUint| {
+            let mut bytes = value.to_bytes_le();
+            bytes.resize(32, 0u8);
+            bytes_to_words_le::<8>(&bytes)
+        };
+        let result = result.iter().flat_map(to_words).collect::<Vec<_>>();
+        let prefix_products = prefix_products.iter().flat_map(to_words).collect();
+        let inverse = to_words(&single_inverse).to_vec();
+
+        // Write the inverses over the elements and keep track of the memory records.
+        let x_memory_records = rt.mw_slice(ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .field_batch_inv_events
+            .push(FieldBatchInvEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                ptr,
+                header_ptr,
+                len,
+                modulus,
+                x,
+                prefix_products,
+                inverse,
+                header_memory_records,
+                x_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for FieldBatchInvChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for FieldBatchInvChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FieldBatchInvCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &FieldBatchInvCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued, next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        let header_modulus: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.header_memory[..WORDS_FIELD_ELEMENT]);
+        let header_len = local.header_memory[WORDS_FIELD_ELEMENT].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_all_eq(local.modulus, header_modulus);
+        first.assert_eq(local.len, header_len);
+        first.assert_zero(local.index);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation.clone());
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.ptr, local.ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+        continuation.assert_all_eq(next.modulus, local.modulus);
+        builder
+            .when(local.is_last)
+            .assert_eq(local.index + AB::Expr::one(), local.len);
+
+        // The value of x is stored in the "prev_value" of the x_memory, since we write to it later.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+
+        // Evaluate the prefix product p_i = p_{i-1} * x_i, with p_{-1} = 1.
+        let one_limbs = U256Field::to_limbs_field::<AB::Expr, AB::F>(&BigUint::one());
+        builder
+            .when(local.is_first)
+            .assert_all_eq(local.prefix_in, one_limbs.clone());
+        local.prefix.eval_with_modulus(
+            builder,
+            &local.prefix_in,
+            &x_limbs,
+            &local.modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when_transition()
+            .when(next_is_continuation)
+            .assert_all_eq(next.prefix_in, local.prefix.result);
+
+        // Evaluate r_{i-1} = r_i * x_i. Chained from r_{-1} = 1, this makes each r_i the inverse
+        // of the prefix product p_i, and r_{n-1} the single inverse of the product of all elements.
+        builder.slice_range_check_u8(&local.inverse.0, local.shard, local.channel, local.is_real);
+        local.prev_inverse.eval_with_modulus(
+            builder,
+            &local.inverse,
+            &x_limbs,
+            &local.modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_first)
+            .assert_all_eq(local.prev_inverse.result, one_limbs);
+        builder
+            .when_transition()
+            .when(next_is_continuation)
+            .assert_all_eq(next.prev_inverse.result, local.inverse);
+
+        // Evaluate x_i^{-1} = r_i * p_{i-1}, and assert that it is written to x_memory.
+        local.result.eval_with_modulus(
+            builder,
+            &local.inverse,
+            &local.prefix_in,
+            &local.modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.result.result, value_as_limbs(&local.x_memory));
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read and write the element of this row.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.ptr
+                + local.index
+                    * AB::F::from_canonical_usize(WORDS_FIELD_ELEMENT * WORD_SIZE),
+            &local.x_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::FIELD_BATCH_INV.syscall_id()),
+            local.ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_is_zero::IsZeroCols;
+use crate::operations::field::params::{Limbs, NumLimbs, NumWords};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{limbs_from_access, pad_rows, words_to_bytes_le_vec};
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the FieldIsZeroCols.
+const NUM_COLS: usize = size_of::<FieldIsZeroCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldIsZeroEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub result_ptr: u32,
+    pub x: Vec<u32>,
+    pub is_zero: bool,
+    pub x_memory_records: Vec<MemoryReadRecord>,
+    pub result_memory_record: MemoryWriteRecord,
+}
+
+/// A precompile checking whether a field element `x` of up to 256 bits, such as an element of the
+/// bn254 base field, is zero.
+///
+/// The first argument points to `x`, and the second to the word the result is written to, since
+/// the return register of a precompile is left unchanged. The result is one if `x` is zero, and
+/// zero otherwise. `x` is expected to be reduced, as the check is on its representation.
+#[derive(Default)]
+pub struct FieldIsZeroChip;
+
+impl FieldIsZeroChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the FieldIsZero operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldIsZeroCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x.
+    pub x_ptr: T,
+
+    /// The pointer to the result.
+    pub result_ptr: T,
+
+    /// The value to check.
+    pub x_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The result.
+    pub result_memory: MemoryWriteCols<T>,
+
+    /// Whether x is zero.
+    pub is_zero: IsZeroCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for FieldIsZeroChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "FieldIsZero".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .field_is_zero_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut FieldIsZeroCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.result_ptr = F::from_canonical_u32(event.result_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.result_memory.populate(
+                    event.channel,
+                    event.result_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+
+                let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.x));
+                cols.is_zero.populate(&x);
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut FieldIsZeroCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.field_is_zero_events.is_empty()
+    }
+}
+
+impl Syscall for FieldIsZeroChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        rt.require_aligned(x_ptr, self.alignment()).unwrap();
+        let result_ptr = arg2;
+        rt.require_aligned(result_ptr, self.alignment()).unwrap();
+        rt.require_disjoint(
+            x_ptr,
+            (WORDS_FIELD_ELEMENT * WORD_SIZE) as u32,
+            result_ptr,
+            WORD_SIZE as u32,
+        )
+        .unwrap();
+
+        let (x_memory_records, x) = rt.mr_slice(x_ptr, WORDS_FIELD_ELEMENT);
+        let is_zero = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x)).is_zero();
+        let result_memory_record = rt.mw(result_ptr, is_zero as u32);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().field_is_zero_events.push(FieldIsZeroEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            result_ptr,
+            x,
+            is_zero,
+            x_memory_records,
+            result_memory_record,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for FieldIsZeroChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for FieldIsZeroChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FieldIsZeroCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &FieldIsZeroCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Check whether x is zero, and assert that the result written is that flag.
+        let x: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.x_memory);
+        local.is_zero.eval(builder, &x, local.is_real);
+        let result = local.result_memory.value();
+        builder
+            .when(local.is_real)
+            .assert_eq(result[0], local.is_zero.is_zero);
+        for i in 1..WORD_SIZE {
+            builder.when(local.is_real).assert_zero(result[i]);
+        }
+
+        // Read x and write the result.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.result_ptr,
+            &local.result_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::FIELD_IS_ZERO.syscall_id()),
+            local.x_ptr,
+            local.result_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
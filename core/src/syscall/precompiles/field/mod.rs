@@ -0,0 +1,650 @@
+mod affine;
+mod batch_inv;
+mod is_zero;
+mod mac;
+mod montgomery;
+mod mul_small;
+mod ntt;
+
+pub use affine::*;
+pub use batch_inv::*;
+pub use is_zero::*;
+pub use mac::*;
+pub use montgomery::*;
+pub use mul_small::*;
+pub use ntt::*;
+
+#[cfg(test)]
+mod tests {
+    use num::{BigUint, One};
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::runtime::{ExecutionError, Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::ec::edwards::ed25519::Ed25519BaseField;
+    use crate::utils::ec::weierstrass::bn254::{Bn254BaseField, Bn254ScalarField};
+    use crate::utils::ec::weierstrass::secp256k1::Secp256k1BaseField;
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    use super::montgomery_reduce;
+
+    const X_PTR: u32 = 0x1000;
+
+    fn to_words(value: &BigUint) -> Vec<u32> {
+        let mut bytes = value.to_bytes_le();
+        bytes.resize(32, 0);
+        bytes_to_words_le_vec(&bytes)
+    }
+
+    /// Stores x at `X_PTR`, the modulus at `MODULUS_PTR` and the header pointing to it at
+    /// `HEADER_PTR`, and multiplies x by `k` in place.
+    pub fn field_mul_small_program(x: &BigUint, k: u32, modulus: &BigUint) -> Program {
+        let mut instructions = vec![];
+        for (ptr, words) in [
+            (X_PTR, to_words(x)),
+            (MODULUS_PTR, to_words(modulus)),
+            (HEADER_PTR, vec![MODULUS_PTR, k]),
+        ] {
+            instructions.extend(store_words(ptr, words));
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::FIELD_MUL_SMALL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    const PTR: u32 = 0x2000;
+    const HEADER_PTR: u32 = 0x3000;
+
+    /// Stores the elements at `PTR` and the modulus and length at `HEADER_PTR`, and batch inverts
+    /// the elements in place.
+    pub fn field_batch_inv_program(elements: &[BigUint], modulus: &BigUint) -> Program {
+        let element_words = elements.iter().flat_map(to_words).collect::<Vec<_>>();
+        let header_words = [to_words(modulus), vec![elements.len() as u32]].concat();
+        let mut instructions = vec![];
+        for (ptr, words) in [(PTR, element_words), (HEADER_PTR, header_words)] {
+            instructions.extend(store_words(ptr, words));
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::FIELD_BATCH_INV as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    const T_PTR: u32 = 0x4000;
+    const MODULUS_PTR: u32 = 0x5000;
+    const OUT_PTR: u32 = 0x6000;
+
+    /// Stores `t` at `T_PTR`, the modulus at `MODULUS_PTR` and the header at `HEADER_PTR`, and
+    /// writes the Montgomery reduction of `t` to `out_ptr`.
+    pub fn montgomery_reduce_program(
+        t: &BigUint,
+        modulus: &BigUint,
+        n_prime: u32,
+        out_ptr: u32,
+    ) -> Program {
+        let mut t_bytes = t.to_bytes_le();
+        t_bytes.resize(64, 0);
+        let regions = [
+            (T_PTR, bytes_to_words_le_vec(&t_bytes)),
+            (MODULUS_PTR, to_words(modulus)),
+            (HEADER_PTR, vec![T_PTR, MODULUS_PTR, n_prime]),
+        ];
+        let mut instructions = vec![];
+        for (ptr, words) in regions {
+            instructions.extend(store_words(ptr, words));
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::MONTGOMERY_REDUCE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, out_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Returns `-modulus^-1 mod 2^32`.
+    fn montgomery_n_prime(modulus: &BigUint) -> u32 {
+        let word = BigUint::one() << 32;
+        let inverse = (modulus % &word).modinv(&word).unwrap();
+        let n_prime = (&word - inverse) % &word;
+        n_prime.iter_u32_digits().next().unwrap_or(0)
+    }
+
+    /// Computes `t * 2^-256 mod modulus` directly with the inverse of `2^256`.
+    fn reference_montgomery_reduce(t: &BigUint, modulus: &BigUint) -> BigUint {
+        let r_inverse = (BigUint::one() << 256).modinv(modulus).unwrap();
+        (t * r_inverse) % modulus
+    }
+
+    /// The moduli of the Montgomery reduction tests, from 256-bit primes down to a 17-bit one.
+    fn montgomery_moduli() -> Vec<BigUint> {
+        vec![
+            Secp256k1BaseField::modulus(),
+            Bn254BaseField::modulus(),
+            Ed25519BaseField::modulus(),
+            (BigUint::one() << 127) - 1u32,
+            BigUint::from(65537u32),
+        ]
+    }
+
+    /// Values to reduce modulo `modulus`, covering the bounds of `t < modulus * 2^256` and the
+    /// conversion of an element out of the Montgomery form.
+    fn montgomery_inputs(modulus: &BigUint) -> Vec<BigUint> {
+        let r = BigUint::one() << 256;
+        let x = modulus - BigUint::from(12345u32) % modulus;
+        vec![
+            BigUint::from(0u32),
+            BigUint::one(),
+            (modulus - 1u32) * (modulus - 1u32),
+            modulus * &r - 1u32,
+            (&x * &r) % modulus,
+            (&x * &x) % modulus + modulus * (&r - 1u32),
+        ]
+    }
+
+    const NTT_PTR: u32 = 0x7000;
+    const TWIDDLE_PTR: u32 = 0x7100;
+
+    /// The butterflies of a 4-point decimation in frequency NTT, as the indices of `a` and `b` and
+    /// the power of the twiddle factor. The transform is output in bit-reversed order.
+    const NTT_BUTTERFLIES: [(u32, u32, u32); 4] = [(0, 2, 0), (1, 3, 1), (0, 1, 0), (2, 3, 0)];
+
+    /// Stores `x` at `NTT_PTR`, the powers of `w` at `TWIDDLE_PTR` and the modulus at
+    /// `MODULUS_PTR`, and transforms `x` in place with butterflies.
+    pub fn ntt_program(x: &[BigUint; 4], w: &BigUint, modulus: &BigUint) -> Program {
+        let headers = NTT_BUTTERFLIES
+            .iter()
+            .flat_map(|&(_, b, k)| [NTT_PTR + b * 32, TWIDDLE_PTR + k * 32, MODULUS_PTR])
+            .collect::<Vec<_>>();
+        let regions = [
+            (NTT_PTR, x.iter().flat_map(to_words).collect::<Vec<_>>()),
+            (TWIDDLE_PTR, [to_words(&BigUint::one()), to_words(w)].concat()),
+            (MODULUS_PTR, to_words(modulus)),
+            (HEADER_PTR, headers),
+        ];
+        let mut instructions = vec![];
+        for (ptr, words) in regions {
+            instructions.extend(store_words(ptr, words));
+        }
+        for (i, &(a, _, _)) in NTT_BUTTERFLIES.iter().enumerate() {
+            let header_ptr = HEADER_PTR + i as u32 * 12;
+            instructions.extend(vec![
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::NTT_BUTTERFLY as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, NTT_PTR + a * 32, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, header_ptr, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Returns a primitive 4th root of unity modulo the prime `modulus = 1 mod 4`.
+    fn primitive_fourth_root(modulus: &BigUint) -> BigUint {
+        let exponent = (modulus - 1u32) / 4u32;
+        let minus_one = modulus - 1u32;
+        (2u32..)
+            .map(|g| BigUint::from(g).modpow(&exponent, modulus))
+            .find(|w| (w * w) % modulus == minus_one)
+            .unwrap()
+    }
+
+    /// Computes `X_k = sum_j x_j * w^(jk)` directly.
+    fn reference_ntt(x: &[BigUint; 4], w: &BigUint, modulus: &BigUint) -> Vec<BigUint> {
+        (0..4u32)
+            .map(|k| {
+                x.iter().enumerate().fold(BigUint::from(0u32), |acc, (j, x_j)| {
+                    let power = w.modpow(&BigUint::from(j as u32 * k), modulus);
+                    (acc + x_j * power) % modulus
+                })
+            })
+            .collect()
+    }
+
+    /// The moduli of the NTT tests, which are primes equal to 1 modulo 4.
+    fn ntt_moduli() -> Vec<BigUint> {
+        vec![
+            BigUint::from(17u32),
+            BigUint::from(3329u32),
+            Bn254ScalarField::modulus(),
+        ]
+    }
+
+    fn ntt_inputs(modulus: &BigUint) -> [BigUint; 4] {
+        core::array::from_fn(|j| (modulus - 1u32) - BigUint::from(7919u32 * j as u32) % modulus)
+    }
+
+    fn read_words(runtime: &Runtime, ptr: u32) -> BigUint {
+        let words = (0..8).map(|i| runtime.word(ptr + i * 4)).collect::<Vec<_>>();
+        BigUint::from_bytes_le(&words_to_bytes_le_vec(&words))
+    }
+
+    fn batch_inv_elements(modulus: &BigUint) -> Vec<BigUint> {
+        (1..=8u32)
+            .map(|i| modulus - BigUint::from(i * 7919))
+            .collect()
+    }
+
+    #[test]
+    fn test_field_mul_small_execute() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let x = &modulus - BigUint::from(12345u32);
+        let program = field_mul_small_program(&x, 3, &modulus);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let result = (0..8)
+            .map(|i| runtime.word(X_PTR + i * 4))
+            .collect::<Vec<_>>();
+        let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&result));
+        assert_eq!(result, (x * 3u32) % modulus);
+    }
+
+    #[test]
+    fn test_field_mul_small_prove() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let x = &modulus - BigUint::from(12345u32);
+        run_test(field_mul_small_program(&x, 3, &modulus)).unwrap();
+        run_test(field_mul_small_program(&x, 0xffff, &BigUint::from(0u32))).unwrap();
+    }
+
+    #[test]
+    fn test_field_mul_small_prove_max_quotient() {
+        utils::setup_logger();
+        // With x = modulus - 1 and k = 2^16 - 1, the quotient k - 1 uses both of its limbs.
+        let modulus = BigUint::from(65537u32);
+        let x = &modulus - BigUint::from(1u32);
+        run_test(field_mul_small_program(&x, 0xffff, &modulus)).unwrap();
+    }
+
+    #[test]
+    fn test_field_mul_small_unreduced() {
+        let modulus = Secp256k1BaseField::modulus();
+        let program = field_mul_small_program(&modulus, 3, &modulus);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::UnreducedSyscallOperand(X_PTR, MODULUS_PTR))
+        ));
+    }
+
+    #[test]
+    fn test_field_batch_inv_execute() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let elements = batch_inv_elements(&modulus);
+        let program = field_batch_inv_program(&elements, &modulus);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        for (i, x) in elements.iter().enumerate() {
+            let result = (0..8)
+                .map(|j| runtime.word(PTR + (i as u32 * 8 + j) * 4))
+                .collect::<Vec<_>>();
+            let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&result));
+            assert_eq!(result, x.modinv(&modulus).unwrap());
+        }
+        assert_eq!(runtime.record.field_batch_inv_events.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "field_batch_inv input is not invertible")]
+    fn test_field_batch_inv_not_invertible() {
+        utils::setup_logger();
+        let modulus = BigUint::from(15u32);
+        let elements = [BigUint::from(2u32), BigUint::from(5u32)];
+        let program = field_batch_inv_program(&elements, &modulus);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_field_batch_inv_prove() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let elements = batch_inv_elements(&modulus);
+        run_test(field_batch_inv_program(&elements, &modulus)).unwrap();
+        run_test(field_batch_inv_program(&elements[..1], &modulus)).unwrap();
+    }
+
+    #[test]
+    fn test_montgomery_reduce_execute() {
+        utils::setup_logger();
+        for modulus in montgomery_moduli() {
+            let n_prime = montgomery_n_prime(&modulus);
+            for t in montgomery_inputs(&modulus) {
+                let expected = reference_montgomery_reduce(&t, &modulus);
+                assert_eq!(montgomery_reduce(&t, &modulus, n_prime), expected);
+
+                let program = montgomery_reduce_program(&t, &modulus, n_prime, OUT_PTR);
+                let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+                runtime.run().unwrap();
+                assert_eq!(read_words(&runtime, OUT_PTR), expected);
+                assert_eq!(runtime.record.field_montgomery_reduce_events.len(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_reduce_in_place() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let t = &modulus * BigUint::from(0xdead_beef_u32) + 42u32;
+        let n_prime = montgomery_n_prime(&modulus);
+        let program = montgomery_reduce_program(&t, &modulus, n_prime, T_PTR);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(
+            read_words(&runtime, T_PTR),
+            reference_montgomery_reduce(&t, &modulus)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "montgomery_reduce n_prime is not -modulus^-1 mod 2^32")]
+    fn test_montgomery_reduce_invalid_n_prime() {
+        let modulus = Secp256k1BaseField::modulus();
+        let n_prime = montgomery_n_prime(&modulus) ^ 1;
+        let program = montgomery_reduce_program(&modulus, &modulus, n_prime, OUT_PTR);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "montgomery_reduce input must be below modulus * 2^256")]
+    fn test_montgomery_reduce_input_too_large() {
+        let modulus = Secp256k1BaseField::modulus();
+        let t = &modulus << 256;
+        let n_prime = montgomery_n_prime(&modulus);
+        let program = montgomery_reduce_program(&t, &modulus, n_prime, OUT_PTR);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_montgomery_reduce_prove() {
+        utils::setup_logger();
+        for modulus in [Secp256k1BaseField::modulus(), BigUint::from(65537u32)] {
+            let n_prime = montgomery_n_prime(&modulus);
+            let inputs = montgomery_inputs(&modulus);
+            for t in &inputs[3..5] {
+                run_test(montgomery_reduce_program(t, &modulus, n_prime, OUT_PTR)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_ntt_butterfly_execute() {
+        utils::setup_logger();
+        for modulus in ntt_moduli() {
+            let w = primitive_fourth_root(&modulus);
+            let x = ntt_inputs(&modulus);
+            let mut runtime = Runtime::new(ntt_program(&x, &w, &modulus), SP1CoreOpts::default());
+            runtime.run().unwrap();
+
+            // The butterflies leave the transform in bit-reversed order.
+            let expected = reference_ntt(&x, &w, &modulus);
+            for (slot, k) in [0, 2, 1, 3].into_iter().enumerate() {
+                let result = read_words(&runtime, NTT_PTR + slot as u32 * 32);
+                assert_eq!(result, expected[k]);
+            }
+            assert_eq!(runtime.record.ntt_butterfly_events.len(), 4);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ntt_butterfly inputs must be below the modulus")]
+    fn test_ntt_butterfly_unreduced_input() {
+        let modulus = BigUint::from(3329u32);
+        let w = primitive_fourth_root(&modulus);
+        let mut x = ntt_inputs(&modulus);
+        x[2] = modulus.clone();
+        let mut runtime = Runtime::new(ntt_program(&x, &w, &modulus), SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_ntt_butterfly_prove() {
+        utils::setup_logger();
+        for modulus in &ntt_moduli()[1..] {
+            let w = primitive_fourth_root(modulus);
+            run_test(ntt_program(&ntt_inputs(modulus), &w, modulus)).unwrap();
+        }
+    }
+
+    const IS_ZERO_RESULT_PTR: u32 = 0x4000;
+
+    /// Stores each x at `X_PTR` with a stride of 32 bytes and checks whether it is zero, writing
+    /// the results at `IS_ZERO_RESULT_PTR`.
+    fn field_is_zero_program(xs: &[BigUint]) -> Program {
+        let mut instructions = vec![];
+        for (i, x) in xs.iter().enumerate() {
+            let x_ptr = X_PTR + i as u32 * 32;
+            instructions.extend(store_words(x_ptr, to_words(x)));
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::FIELD_IS_ZERO as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, x_ptr, false, true),
+                Instruction::new(
+                    Opcode::ADD,
+                    11,
+                    0,
+                    IS_ZERO_RESULT_PTR + i as u32 * 4,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    fn field_is_zero_inputs() -> Vec<BigUint> {
+        let modulus = Bn254BaseField::modulus();
+        vec![
+            BigUint::from(0u32),
+            BigUint::one(),
+            BigUint::one() << 224,
+            &modulus - BigUint::one(),
+            &modulus >> 1,
+        ]
+    }
+
+    #[test]
+    fn test_field_is_zero() {
+        utils::setup_logger();
+        let xs = field_is_zero_inputs();
+        let mut runtime = Runtime::new(field_is_zero_program(&xs), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        for i in 0..xs.len() {
+            let result = runtime.word(IS_ZERO_RESULT_PTR + i as u32 * 4);
+            assert_eq!(result, (i == 0) as u32);
+        }
+        assert_eq!(runtime.record.field_is_zero_events.len(), xs.len());
+    }
+
+    #[test]
+    fn test_field_is_zero_prove() {
+        utils::setup_logger();
+        run_test(field_is_zero_program(&field_is_zero_inputs())).unwrap();
+    }
+
+    const MAC_ACC_PTR: u32 = 0x8000;
+    const MAC_A_PTR: u32 = 0x8100;
+    const MAC_B_PTR: u32 = 0x8200;
+
+    /// Stores the vectors `a` and `b` at `MAC_A_PTR` and `MAC_B_PTR`, the modulus at `MODULUS_PTR`
+    /// and a header for each pair of elements at `HEADER_PTR`, and accumulates their dot product
+    /// at `MAC_ACC_PTR`.
+    fn field_mac_program(a: &[BigUint], b: &[BigUint], modulus: &BigUint) -> Program {
+        let headers = (0..a.len() as u32)
+            .flat_map(|i| [MAC_A_PTR + i * 32, MAC_B_PTR + i * 32, MODULUS_PTR])
+            .collect::<Vec<_>>();
+        let regions = [
+            (MAC_A_PTR, a.iter().flat_map(to_words).collect::<Vec<_>>()),
+            (MAC_B_PTR, b.iter().flat_map(to_words).collect::<Vec<_>>()),
+            (MODULUS_PTR, to_words(modulus)),
+            (HEADER_PTR, headers),
+        ];
+        let mut instructions = vec![];
+        for (ptr, words) in regions {
+            instructions.extend(store_words(ptr, words));
+        }
+        for i in 0..a.len() as u32 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::FIELD_MAC as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, MAC_ACC_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR + i * 12, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    fn field_mac_vectors(modulus: &BigUint) -> (Vec<BigUint>, Vec<BigUint>) {
+        let a = (1..=8u32)
+            .map(|i| modulus - BigUint::from(i * 7919))
+            .collect();
+        let b = (1..=8u32)
+            .map(|i| (modulus >> i) + BigUint::from(i))
+            .collect();
+        (a, b)
+    }
+
+    #[test]
+    fn test_field_mac_dot_product() {
+        utils::setup_logger();
+        for modulus in [Bn254ScalarField::modulus(), Secp256k1BaseField::modulus()] {
+            let (a, b) = field_mac_vectors(&modulus);
+            let program = field_mac_program(&a, &b, &modulus);
+            let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+            runtime.run().unwrap();
+
+            let expected = a
+                .iter()
+                .zip(b.iter())
+                .fold(BigUint::from(0u32), |acc, (a, b)| (acc + a * b) % &modulus);
+            assert_eq!(read_words(&runtime, MAC_ACC_PTR), expected);
+            assert_eq!(runtime.record.field_mac_events.len(), 8);
+        }
+    }
+
+    #[test]
+    fn test_field_mac_zero_modulus() {
+        utils::setup_logger();
+        let (a, b) = field_mac_vectors(&Secp256k1BaseField::modulus());
+        let program = field_mac_program(&a, &b, &BigUint::from(0u32));
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let r = BigUint::one() << 256;
+        let expected = a
+            .iter()
+            .zip(b.iter())
+            .fold(BigUint::from(0u32), |acc, (a, b)| (acc + a * b) % &r);
+        assert_eq!(read_words(&runtime, MAC_ACC_PTR), expected);
+    }
+
+    #[test]
+    fn test_field_mac_prove() {
+        utils::setup_logger();
+        let modulus = Bn254ScalarField::modulus();
+        let (a, b) = field_mac_vectors(&modulus);
+        run_test(field_mac_program(&a, &b, &modulus)).unwrap();
+        run_test(field_mac_program(&a[..1], &b[..1], &BigUint::from(0u32))).unwrap();
+    }
+
+    /// Stores x at `MAC_ACC_PTR`, a at `MAC_A_PTR`, b at `MAC_B_PTR`, the modulus at `MODULUS_PTR`
+    /// and their header at `HEADER_PTR`, and computes `a * x + b` in place of x.
+    fn field_affine_program(x: &BigUint, a: &BigUint, b: &BigUint, modulus: &BigUint) -> Program {
+        let regions = [
+            (MAC_ACC_PTR, to_words(x)),
+            (MAC_A_PTR, to_words(a)),
+            (MAC_B_PTR, to_words(b)),
+            (MODULUS_PTR, to_words(modulus)),
+            (HEADER_PTR, vec![MAC_A_PTR, MAC_B_PTR, MODULUS_PTR]),
+        ];
+        let mut instructions = vec![];
+        for (ptr, words) in regions {
+            instructions.extend(store_words(ptr, words));
+        }
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::FIELD_AFFINE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, MAC_ACC_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_field_affine() {
+        utils::setup_logger();
+        for modulus in [Bn254ScalarField::modulus(), Secp256k1BaseField::modulus()] {
+            let (a, b) = (BigUint::from(3u32), BigUint::from(5u32));
+            for x in [BigUint::from(0u32), &modulus - 1u32, &modulus >> 1] {
+                let program = field_affine_program(&x, &a, &b, &modulus);
+                let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+                runtime.run().unwrap();
+
+                let expected = (&a * &x + &b) % &modulus;
+                assert_eq!(read_words(&runtime, MAC_ACC_PTR), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_affine_zero_modulus() {
+        utils::setup_logger();
+        let r = BigUint::one() << 256;
+        let (x, a, b) = (&r - 1u32, &r - 2u32, &r - 3u32);
+        let program = field_affine_program(&x, &a, &b, &BigUint::from(0u32));
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(read_words(&runtime, MAC_ACC_PTR), (&a * &x + &b) % &r);
+    }
+
+    #[test]
+    fn test_field_affine_prove() {
+        utils::setup_logger();
+        let modulus = Secp256k1BaseField::modulus();
+        let (a, b) = field_mac_vectors(&modulus);
+        run_test(field_affine_program(&a[0], &a[1], &b[0], &modulus)).unwrap();
+        run_test(field_affine_program(&a[2], &b[1], &b[2], &BigUint::from(0u32))).unwrap();
+    }
+}
@@ -0,0 +1,571 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, pad_rows, words_to_bytes_le, words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the FieldMontgomeryReduceCols.
+const NUM_COLS: usize = size_of::<FieldMontgomeryReduceCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The number of words of the input, a 512-bit value.
+const T_WORDS: usize = 2 * WORDS_FIELD_ELEMENT;
+
+/// The number of words of the header, which contains the pointer to the input, the pointer to the
+/// modulus and `n_prime`.
+const HEADER_WORDS: usize = 3;
+
+/// The number of bits `2^256` is multiplied in by each of the two shifting operations.
+const HALF_SHIFT_BITS: usize = 128;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldMontgomeryReduceEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub header_ptr: u32,
+    pub t_ptr: u32,
+    pub modulus_ptr: u32,
+    pub n_prime: u32,
+    pub t: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub result: Vec<u32>,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub t_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile computing the Montgomery reduction `t * 2^-256 mod modulus` of a 512-bit `t`,
+/// for an odd uint256 modulus chosen at runtime.
+///
+/// The first argument points to the output, and the second to a header made of the pointer to
+/// `t`, the pointer to the modulus, and `n_prime = -modulus^-1 mod 2^32`. The reduction is
+/// executed with word-by-word REDC, including the final conditional subtraction, and requires
+/// `t < modulus * 2^256`. The output is written a cycle after the inputs are read, so it may
+/// overlap them.
+///
+/// Writing `t = t_hi * 2^256 + t_lo` and `R = 2^256`, the chip constrains the output `out` with
+/// four field operations modulo the modulus: `r = t_lo`, `d = out - t_hi`, `s = d * 2^128` and
+/// `s * 2^128 = r`, so that `out * R = t`. The output is checked to be below the modulus, which
+/// makes it unique. `n_prime` only drives the execution, and is not constrained.
+#[derive(Default)]
+pub struct FieldMontgomeryReduceChip;
+
+impl FieldMontgomeryReduceChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the FieldMontgomeryReduce operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldMontgomeryReduceCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output.
+    pub out_ptr: T,
+
+    /// The pointer to the header.
+    pub header_ptr: T,
+
+    /// The header, which contains the pointer to `t`, the pointer to the modulus and `n_prime`.
+    pub header_memory: [MemoryReadCols<T>; HEADER_WORDS],
+
+    /// The value to reduce, with its low half first.
+    pub t_memory: [MemoryReadCols<T>; T_WORDS],
+
+    /// The modulus.
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The output, written a cycle after the inputs are read.
+    pub out_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// The reduction `r` of the low half of `t`.
+    pub t_lo_reduced: FieldOpCols<T, U256Field>,
+
+    /// The difference `d = out - t_hi`.
+    pub difference: FieldOpCols<T, U256Field>,
+
+    /// The product `s = d * 2^128`.
+    pub shifted: FieldOpCols<T, U256Field>,
+
+    /// The product `s * 2^128`, which must equal `r`.
+    pub montgomery: FieldOpCols<T, U256Field>,
+
+    /// Boolean flags marking the most significant byte in which the output differs from the
+    /// modulus.
+    pub byte_flags: [T; NUM_LIMBS],
+
+    /// The flagged byte of the output.
+    pub comparison_lhs: T,
+
+    /// The flagged byte of the modulus.
+    pub comparison_rhs: T,
+
+    pub is_real: T,
+}
+
+/// Computes the Montgomery reduction `t * 2^-256 mod modulus` with word-by-word REDC.
+///
+/// Each of the eight steps adds the multiple of the modulus that clears the lowest remaining word
+/// of `t`, which is then shifted out. The result is below `2 * modulus`, and the final conditional
+/// subtraction brings it below the modulus.
+pub fn montgomery_reduce(t: &BigUint, modulus: &BigUint, n_prime: u32) -> BigUint {
+    let mut value = t.clone();
+    for _ in 0..WORDS_FIELD_ELEMENT {
+        let low = value.iter_u32_digits().next().unwrap_or(0);
+        let m = low.wrapping_mul(n_prime);
+        value = (value + modulus * m) >> 32;
+    }
+    if &value >= modulus {
+        value -= modulus;
+    }
+    value
+}
+
+fn decode(words: &[u32]) -> BigUint {
+    BigUint::from_bytes_le(&words_to_bytes_le_vec(words))
+}
+
+impl<F: PrimeField32> MachineAir<F> for FieldMontgomeryReduceChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "FieldMontgomeryReduce".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .field_montgomery_reduce_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut FieldMontgomeryReduceCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+
+                // Populate memory columns.
+                for i in 0..HEADER_WORDS {
+                    cols.header_memory[i].populate(
+                        event.channel,
+                        event.header_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..T_WORDS {
+                    cols.t_memory[i].populate(
+                        event.channel,
+                        event.t_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.out_memory[i].populate(
+                        event.channel,
+                        event.out_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the field operations tying the output to t.
+                let t_lo = decode(&event.t[..WORDS_FIELD_ELEMENT]);
+                let t_hi = decode(&event.t[WORDS_FIELD_ELEMENT..]);
+                let modulus = decode(&event.modulus);
+                let result = decode(&event.result);
+                let half_shift = BigUint::one() << HALF_SHIFT_BITS;
+                cols.t_lo_reduced.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &t_lo,
+                    &BigUint::zero(),
+                    &modulus,
+                    FieldOperation::Add,
+                );
+                let difference = cols.difference.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &result,
+                    &t_hi,
+                    &modulus,
+                    FieldOperation::Sub,
+                );
+                let shifted = cols.shifted.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &difference,
+                    &half_shift,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+                cols.montgomery.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &shifted,
+                    &half_shift,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+
+                // Populate the comparison of the output against the modulus.
+                let result_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.result);
+                let modulus_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.modulus);
+                for i in (0..NUM_LIMBS).rev() {
+                    if result_bytes[i] != modulus_bytes[i] {
+                        cols.byte_flags[i] = F::one();
+                        cols.comparison_lhs = F::from_canonical_u8(result_bytes[i]);
+                        cols.comparison_rhs = F::from_canonical_u8(modulus_bytes[i]);
+                        new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                            opcode: ByteOpcode::LTU,
+                            shard: event.shard,
+                            channel: event.channel,
+                            a1: 1,
+                            a2: 0,
+                            b: result_bytes[i] as u32,
+                            c: modulus_bytes[i] as u32,
+                        });
+                        break;
+                    }
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut FieldMontgomeryReduceCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            for op in [
+                &mut cols.t_lo_reduced,
+                &mut cols.difference,
+                &mut cols.shifted,
+                &mut cols.montgomery,
+            ] {
+                op.populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            }
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut FieldMontgomeryReduceCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.field_montgomery_reduce_events.is_empty()
+    }
+}
+
+impl Syscall for FieldMontgomeryReduceChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let out_ptr = arg1;
+        let header_ptr = arg2;
+        if out_ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+        let clk = rt.clk;
+
+        // Read the header, then the input and the modulus it points to.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, HEADER_WORDS);
+        let t_ptr = header[0];
+        let modulus_ptr = header[1];
+        let n_prime = header[2];
+        if t_ptr % 4 != 0 || modulus_ptr % 4 != 0 {
+            panic!();
+        }
+        let (t_memory_records, t) = rt.mr_slice(t_ptr, T_WORDS);
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        if modulus[0].wrapping_mul(n_prime) != u32::MAX {
+            panic!("montgomery_reduce n_prime is not -modulus^-1 mod 2^32");
+        }
+        let uint256_modulus = decode(&modulus);
+        if decode(&t[WORDS_FIELD_ELEMENT..]) >= uint256_modulus {
+            panic!("montgomery_reduce input must be below modulus * 2^256");
+        }
+
+        let result = montgomery_reduce(&decode(&t), &uint256_modulus, n_prime);
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(NUM_LIMBS, 0u8);
+        let result = bytes_to_words_le::<8>(&result_bytes).to_vec();
+
+        // Write the output a cycle after the inputs are read, so that it may overlap them.
+        rt.clk += 1;
+        let out_memory_records = rt.mw_slice(out_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .field_montgomery_reduce_events
+            .push(FieldMontgomeryReduceEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                out_ptr,
+                header_ptr,
+                t_ptr,
+                modulus_ptr,
+                n_prime,
+                t,
+                modulus,
+                result,
+                header_memory_records,
+                t_memory_records,
+                modulus_memory_records,
+                out_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for FieldMontgomeryReduceChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for FieldMontgomeryReduceChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FieldMontgomeryReduceCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &FieldMontgomeryReduceCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let t_lo: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.t_memory[..WORDS_FIELD_ELEMENT]);
+        let t_hi: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.t_memory[WORDS_FIELD_ELEMENT..]);
+        let modulus: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.modulus_memory);
+        let out: Limbs<_, <U256Field as NumLimbs>::Limbs> = limbs_from_access(&local.out_memory);
+        let zero = Polynomial::from_coefficients(&[AB::Expr::zero()]);
+        let half_shift = U256Field::to_limbs_field::<AB::Expr, AB::F>(
+            &(BigUint::one() << HALF_SHIFT_BITS),
+        );
+
+        // Evaluate r = t_lo.
+        local.t_lo_reduced.eval_with_modulus(
+            builder,
+            &t_lo,
+            &zero,
+            &modulus,
+            FieldOperation::Add,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Evaluate d = out - t_hi.
+        local.difference.eval_with_modulus(
+            builder,
+            &out,
+            &t_hi,
+            &modulus,
+            FieldOperation::Sub,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Evaluate s = d * 2^128 and s * 2^128, and assert that the latter is r. Chained together,
+        // `(out - t_hi) * 2^256 = t_lo`, that is `out * 2^256 = t` modulo the modulus.
+        local.shifted.eval_with_modulus(
+            builder,
+            &local.difference.result,
+            &half_shift,
+            &modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.montgomery.eval_with_modulus(
+            builder,
+            &local.shifted.result,
+            &half_shift,
+            &modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.montgomery.result, local.t_lo_reduced.result);
+
+        // Check that the output is below the modulus. The bytes above the flagged byte are equal,
+        // and the flagged byte of the output is below that of the modulus. Exactly one byte is
+        // flagged on real rows.
+        let mut sum_flags = AB::Expr::zero();
+        let mut out_comparison_byte = AB::Expr::zero();
+        let mut modulus_comparison_byte = AB::Expr::zero();
+        for i in (0..NUM_LIMBS).rev() {
+            let flag = local.byte_flags[i];
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            out_comparison_byte += out[i] * flag;
+            modulus_comparison_byte += modulus[i] * flag;
+
+            builder
+                .when(local.is_real)
+                .when_not(sum_flags.clone())
+                .assert_eq(out[i], modulus[i]);
+        }
+        builder.assert_eq(sum_flags, local.is_real);
+        builder.assert_eq(local.comparison_lhs, out_comparison_byte);
+        builder.assert_eq(local.comparison_rhs, modulus_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.comparison_lhs,
+            local.comparison_rhs,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Read the header, the input and the modulus, and write the output a cycle later.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_real,
+        );
+        let t_ptr = local.header_memory[0].value().reduce::<AB>();
+        let modulus_ptr = local.header_memory[1].value().reduce::<AB>();
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            t_ptr,
+            &local.t_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.out_ptr,
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::MONTGOMERY_REDUCE.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
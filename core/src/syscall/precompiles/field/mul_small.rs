@@ -0,0 +1,645 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{value_as_limbs, MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::field::util::{
+    compute_root_quotient_and_shift, split_u16_limbs_to_u8_limbs,
+};
+use crate::operations::field::util_air::eval_field_operation;
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionError, ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le,
+    words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::Zero;
+use num::{BigUint, One};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the FieldMulSmallCols.
+const NUM_COLS: usize = size_of::<FieldMulSmallCols<u8>>();
+
+/// The exclusive upper bound on the constant `k` of a `FIELD_MUL_SMALL` call.
+pub const FIELD_MUL_SMALL_MAX_K: u32 = 1 << 16;
+
+/// The number of witness limbs of `k * x - quotient * modulus - result`. The quotient has two limbs
+/// and the modulus up to 33 (for 2^256), so the vanishing polynomial has 34 coefficients.
+const NUM_WITNESS_LIMBS: usize = 33;
+
+/// The number of bytes of the effective modulus, which is 2^256 for a zero modulus.
+const NUM_MODULUS_BYTES: usize = WORDS_FIELD_ELEMENT * WORD_SIZE + 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldMulSmallEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub io_ptr: u32,
+    pub modulus_ptr: u32,
+    pub x: Vec<u32>,
+    pub k: u32,
+    pub modulus: Vec<u32>,
+    pub modulus_ptr_memory_record: MemoryReadRecord,
+    pub k_memory_record: MemoryReadRecord,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A precompile computing `k * x mod modulus` for a small constant `k < 2^16`.
+///
+/// The first argument points to `x`, which is overwritten with the result, and the second to a pair
+/// of words: the pointer to the modulus, followed by `k`. A zero modulus is interpreted as `2^256`.
+/// The call fails if `x` is not reduced modulo the modulus.
+///
+/// The product is constrained as a single relation `k * x = quotient * modulus + result` rather
+/// than as an add-chain. An add-chain needs one modular addition per bit of `k`, each with its own
+/// result and carry columns, so it outgrows the full multiplier for `k` beyond a few bits. Unlike
+/// `UINT256_MUL`, the relation does not use the generic [`FieldOpCols`] layout either. Since `k`
+/// only spans two byte limbs and `x` is reduced, the quotient is less than `k` and fits in two byte
+/// limbs instead of 32, and the witness of `k * x - quotient * modulus - result` has 33 limbs
+/// instead of 63. The result is constrained to be below the modulus by flagging the most
+/// significant byte in which they differ.
+///
+/// [`FieldOpCols`]: crate::operations::field::field_op::FieldOpCols
+#[derive(Default)]
+pub struct FieldMulSmallChip;
+
+impl FieldMulSmallChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// A set of columns for the FieldMulSmall operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FieldMulSmallCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x.
+    pub x_ptr: T,
+
+    /// The pointer to the pointer to the modulus, followed by `k`.
+    pub io_ptr: T,
+
+    /// The pointer to the modulus.
+    pub modulus_ptr_memory: MemoryReadCols<T>,
+
+    /// The constant `k`, whose two high bytes are zero.
+    pub k_memory: MemoryReadCols<T>,
+
+    // Memory columns.
+    // x_memory is written to with the result, which is why it is of type MemoryWriteCols.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    // Columns for checking if modulus is zero. If it's zero, then use 2^256 as the effective modulus.
+    pub modulus_is_zero: IsZeroOperation<T>,
+
+    /// The result `(k * x) % modulus`, which is written to x.
+    pub result: Limbs<T, <U256Field as NumLimbs>::Limbs>,
+
+    /// The little-endian bytes of the quotient `(k * x) / modulus`, which is less than `k`.
+    pub quotient: [T; 2],
+
+    /// The shifted witness of `k * x - quotient * modulus - result`, split into bytes.
+    pub witness_low: [T; NUM_WITNESS_LIMBS],
+    pub witness_high: [T; NUM_WITNESS_LIMBS],
+
+    /// Boolean flags marking the most significant byte in which the result differs from the
+    /// effective modulus. The last byte is the 2^256 byte, which is one for a zero modulus.
+    pub byte_flags: [T; NUM_MODULUS_BYTES],
+
+    /// The flagged byte of the result.
+    pub result_comparison_byte: T,
+
+    /// The flagged byte of the effective modulus, which is larger than that of the result.
+    pub modulus_comparison_byte: T,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for FieldMulSmallChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "FieldMulSmall".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .field_mul_small_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut FieldMulSmallCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Decode the uint256 values.
+                let x = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.x));
+                let modulus = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.modulus));
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.io_ptr = F::from_canonical_u32(event.io_ptr);
+
+                // Populate memory columns, and range check the low bytes of k.
+                cols.modulus_ptr_memory.populate(
+                    event.channel,
+                    event.modulus_ptr_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+                cols.k_memory.populate(
+                    event.channel,
+                    event.k_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+                let k_bytes = [event.k as u8, (event.k >> 8) as u8];
+                new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, &k_bytes);
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let modulus_bytes = words_to_bytes_le_vec(&event.modulus);
+                let modulus_byte_sum = modulus_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.modulus_is_zero, modulus_byte_sum);
+
+                // Populate the output column.
+                let effective_modulus = if modulus.is_zero() {
+                    BigUint::one() << 256
+                } else {
+                    modulus
+                };
+                let result = cols.populate_output(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &x,
+                    event.k,
+                    &effective_modulus,
+                );
+                cols.populate_comparison(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &result,
+                    &effective_modulus,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut FieldMulSmallCols<F> = row.as_mut_slice().borrow_mut();
+
+            // A zero x and k with a modulus of one satisfy the relation with a zero witness.
+            cols.populate_output(&mut vec![], 0, 0, &BigUint::zero(), 0, &BigUint::one());
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut FieldMulSmallCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.field_mul_small_events.is_empty()
+    }
+}
+
+impl<F: PrimeField32> FieldMulSmallCols<F> {
+    /// Populates the result, quotient and witness columns of `(k * x) % modulus` and returns the
+    /// result. The caller must ensure `x < modulus` and `k < 2^16`.
+    fn populate_output(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        x: &BigUint,
+        k: u32,
+        modulus: &BigUint,
+    ) -> BigUint {
+        let product = x * k;
+        let result = &product % modulus;
+        let quotient = (&product - &result) / modulus;
+        debug_assert!(quotient < BigUint::from(FIELD_MUL_SMALL_MAX_K));
+        let quotient = quotient.iter_u32_digits().next().unwrap_or(0);
+
+        let k_bytes = [k as u8, (k >> 8) as u8];
+        let quotient_bytes = [quotient as u8, (quotient >> 8) as u8];
+
+        // The modulus can be 2^256, so it is not converted with to_limbs_field.
+        let p_modulus_limbs = modulus
+            .to_bytes_le()
+            .iter()
+            .map(|x| F::from_canonical_u8(*x))
+            .collect::<Vec<F>>();
+        let p_modulus: Polynomial<F> = p_modulus_limbs.iter().into();
+        let p_x: Polynomial<F> = U256Field::to_limbs_field::<F, _>(x).into();
+        let p_k: Polynomial<F> = Polynomial::from_coefficients(&k_bytes.map(F::from_canonical_u8));
+        let p_quotient: Polynomial<F> =
+            Polynomial::from_coefficients(&quotient_bytes.map(F::from_canonical_u8));
+        let p_result: Polynomial<F> = U256Field::to_limbs_field::<F, _>(&result).into();
+
+        // Compute the vanishing polynomial.
+        let p_vanishing: Polynomial<F> = &p_x * &p_k - &p_result - &p_quotient * &p_modulus;
+
+        let p_witness = compute_root_quotient_and_shift(
+            &p_vanishing,
+            U256Field::WITNESS_OFFSET,
+            U256Field::NB_BITS_PER_LIMB as u32,
+            NUM_WITNESS_LIMBS,
+        );
+        let (witness_low, witness_high) = split_u16_limbs_to_u8_limbs(&p_witness);
+
+        self.result = p_result.into();
+        self.quotient = quotient_bytes.map(F::from_canonical_u8);
+        self.witness_low = witness_low.try_into().unwrap();
+        self.witness_high = witness_high.try_into().unwrap();
+
+        // Range check the result, quotient and witness bytes.
+        record.add_u8_range_checks_field(shard, channel, &self.result.0);
+        record.add_u8_range_checks(shard, channel, &quotient_bytes);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_low);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_high);
+
+        result
+    }
+
+    /// Populates the flags comparing the result against the effective modulus, which it is below.
+    fn populate_comparison(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        result: &BigUint,
+        modulus: &BigUint,
+    ) {
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(NUM_MODULUS_BYTES, 0u8);
+        let mut modulus_bytes = modulus.to_bytes_le();
+        modulus_bytes.resize(NUM_MODULUS_BYTES, 0u8);
+
+        let i = (0..NUM_MODULUS_BYTES)
+            .rev()
+            .find(|&i| result_bytes[i] != modulus_bytes[i])
+            .expect("result is equal to the modulus");
+        debug_assert!(result_bytes[i] < modulus_bytes[i]);
+        self.byte_flags[i] = F::one();
+        self.result_comparison_byte = F::from_canonical_u8(result_bytes[i]);
+        self.modulus_comparison_byte = F::from_canonical_u8(modulus_bytes[i]);
+        record.add_byte_lookup_event(ByteLookupEvent {
+            opcode: ByteOpcode::LTU,
+            shard,
+            channel,
+            a1: 1,
+            a2: 0,
+            b: result_bytes[i] as u32,
+            c: modulus_bytes[i] as u32,
+        });
+    }
+}
+
+impl Syscall for FieldMulSmallChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        let io_ptr = arg2;
+        let num_bytes = (WORDS_FIELD_ELEMENT * WORD_SIZE) as u32;
+        let checked = rt
+            .require_aligned(x_ptr, self.alignment())
+            .and_then(|_| rt.require_aligned(io_ptr, self.alignment()))
+            .and_then(|_| rt.require_disjoint(x_ptr, num_bytes, io_ptr, 2 * WORD_SIZE as u32));
+        if let Err(err) = checked {
+            rt.set_error(err);
+            return None;
+        }
+
+        // Read the pointer to the modulus and the constant k.
+        let (modulus_ptr_memory_record, modulus_ptr) = rt.mr(io_ptr);
+        let (k_memory_record, k) = rt.mr(io_ptr + WORD_SIZE as u32);
+        if k >= FIELD_MUL_SMALL_MAX_K {
+            panic!("field_mul_small constant {} is not less than 2^16", k);
+        }
+        let checked = rt
+            .require_aligned(modulus_ptr, self.alignment())
+            .and_then(|_| rt.require_disjoint(x_ptr, num_bytes, modulus_ptr, num_bytes));
+        if let Err(err) = checked {
+            rt.set_error(err);
+            return None;
+        }
+
+        // Read the words for the x value. We can read a slice_unsafe here because we write the
+        // computed result to x later.
+        let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        // Get the BigUint values for x and the modulus.
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+
+        // The chip bounds the quotient by k, which requires x to be reduced.
+        let effective_modulus = if uint256_modulus.is_zero() {
+            BigUint::one() << 256
+        } else {
+            uint256_modulus
+        };
+        if uint256_x >= effective_modulus {
+            rt.set_error(ExecutionError::UnreducedSyscallOperand(x_ptr, modulus_ptr));
+            return None;
+        }
+
+        // Perform the multiplication and take the result modulo the modulus.
+        let result: BigUint = (uint256_x * k) % effective_modulus;
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8); // Pad the result to 32 bytes.
+
+        // Convert the result to little endian u32 words.
+        let result = bytes_to_words_le::<8>(&result_bytes);
+
+        // Write the result to x and keep track of the memory records.
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .field_mul_small_events
+            .push(FieldMulSmallEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                x_ptr,
+                io_ptr,
+                modulus_ptr,
+                x,
+                k,
+                modulus,
+                modulus_ptr_memory_record,
+                k_memory_record,
+                x_memory_records,
+                modulus_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for FieldMulSmallChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for FieldMulSmallChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FieldMulSmallCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &FieldMulSmallCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // We are computing (k * x) % modulus. The value of x is stored in the "prev_value" of
+        // the x_memory, since we write to it later.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+        let modulus_limbs = limbs_from_access(&local.modulus_memory);
+
+        // The constant k is a two limb polynomial, with each limb range checked to be a byte and
+        // the high bytes of its word zero.
+        let k = local.k_memory.value();
+        builder.slice_range_check_u8(&k.0[0..2], local.shard, local.channel, local.is_real);
+        builder.when(local.is_real).assert_zero(k[2]);
+        builder.when(local.is_real).assert_zero(k[3]);
+        let k_polynomial: Polynomial<AB::Expr> =
+            Polynomial::from_coefficients(&[k[0].into(), k[1].into()]);
+
+        // If the modulus is zero, then we don't perform the modulus operation.
+        // Evaluate the modulus_is_zero operation by summing each byte of the modulus. The sum will
+        // not overflow because we are summing 32 bytes.
+        let modulus_byte_sum = modulus_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            modulus_byte_sum,
+            local.modulus_is_zero,
+            local.is_real.into(),
+        );
+
+        // If the modulus is zero, we'll actually use 2^256 as the modulus, so nothing happens.
+        // Otherwise, we use the modulus passed in.
+        let modulus_is_zero = local.modulus_is_zero.result;
+        let mut coeff_2_256 = Vec::new();
+        coeff_2_256.resize(32, AB::Expr::zero());
+        coeff_2_256.push(AB::Expr::one());
+        let modulus_polynomial: Polynomial<AB::Expr> = modulus_limbs.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus_polynomial
+            * (AB::Expr::one() - modulus_is_zero.into())
+            + Polynomial::from_coefficients(&coeff_2_256) * modulus_is_zero.into();
+
+        // Evaluate k * x - quotient * modulus - result = (X - 256) * witness.
+        let p_x: Polynomial<AB::Expr> = x_limbs.into();
+        let p_quotient: Polynomial<AB::Expr> =
+            Polynomial::from_coefficients(&[local.quotient[0].into(), local.quotient[1].into()]);
+        let p_result: Polynomial<AB::Expr> = local.result.into();
+        let p_vanishing = &p_x * &k_polynomial - &p_result - &p_quotient * &p_modulus;
+        let p_witness_low: Polynomial<AB::Expr> = local.witness_low.iter().into();
+        let p_witness_high: Polynomial<AB::Expr> = local.witness_high.iter().into();
+        eval_field_operation::<AB, U256Field>(
+            builder,
+            &p_vanishing,
+            &p_witness_low,
+            &p_witness_high,
+        );
+
+        // Range check the result, quotient and witness bytes.
+        let (shard, channel, is_real) = (local.shard, local.channel, local.is_real);
+        builder.slice_range_check_u8(&local.result.0, shard, channel, is_real);
+        builder.slice_range_check_u8(&local.quotient, shard, channel, is_real);
+        builder.slice_range_check_u8(&local.witness_low, shard, channel, is_real);
+        builder.slice_range_check_u8(&local.witness_high, shard, channel, is_real);
+
+        // Constrain the result to be below the effective modulus. The bytes above the flagged byte
+        // are equal, and the flagged byte of the result is the smaller one. The last byte is the
+        // 2^256 byte, which is zero for the result and one for a zero modulus.
+        let result_bytes = local
+            .result
+            .0
+            .iter()
+            .map(|&byte| byte.into())
+            .chain([AB::Expr::zero()])
+            .collect::<Vec<AB::Expr>>();
+        let modulus_bytes = modulus_limbs
+            .0
+            .iter()
+            .map(|&byte| byte.into())
+            .chain([modulus_is_zero.into()])
+            .collect::<Vec<AB::Expr>>();
+        let mut sum_flags = AB::Expr::zero();
+        let mut result_comparison_byte = AB::Expr::zero();
+        let mut modulus_comparison_byte = AB::Expr::zero();
+        for i in (0..NUM_MODULUS_BYTES).rev() {
+            let flag = local.byte_flags[i];
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            result_comparison_byte += result_bytes[i].clone() * flag;
+            modulus_comparison_byte += modulus_bytes[i].clone() * flag;
+
+            builder
+                .when(local.is_real)
+                .when_not(sum_flags.clone())
+                .assert_eq(result_bytes[i].clone(), modulus_bytes[i].clone());
+        }
+        builder.when(local.is_real).assert_one(sum_flags);
+        builder
+            .when(local.is_real)
+            .assert_eq(local.result_comparison_byte, result_comparison_byte);
+        builder
+            .when(local.is_real)
+            .assert_eq(local.modulus_comparison_byte, modulus_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.result_comparison_byte,
+            local.modulus_comparison_byte,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.result, value_as_limbs(&local.x_memory));
+
+        // Read the pointer to the modulus and k, read and write x, and read the modulus.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.io_ptr,
+            &local.modulus_ptr_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.io_ptr + AB::F::from_canonical_usize(WORD_SIZE),
+            &local.k_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        let modulus_ptr = local.modulus_ptr_memory.value().reduce::<AB>();
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::FIELD_MUL_SMALL.syscall_id()),
+            local.x_ptr,
+            local.io_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
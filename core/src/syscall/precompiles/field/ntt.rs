@@ -0,0 +1,478 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::NumWords;
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the NttButterflyCols.
+const NUM_COLS: usize = size_of::<NttButterflyCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_BYTES: u32 = WORDS_FIELD_ELEMENT as u32 * 4;
+
+/// The number of words of the header, which contains the pointers to `b`, the twiddle factor and
+/// the modulus.
+const HEADER_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NttButterflyEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub a_ptr: u32,
+    pub header_ptr: u32,
+    pub b_ptr: u32,
+    pub twiddle_ptr: u32,
+    pub modulus_ptr: u32,
+    pub a: Vec<u32>,
+    pub b: Vec<u32>,
+    pub twiddle: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub twiddle_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+    pub a_memory_records: Vec<MemoryWriteRecord>,
+    pub b_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile performing one butterfly `(a, b) -> (a + b, (a - b) * w)` of a number theoretic
+/// transform, modulo a uint256 modulus chosen at runtime.
+///
+/// The first argument points to `a`, and the second to a header made of the pointers to `b`, the
+/// twiddle factor `w` and the modulus. Both outputs are written in place a cycle after the inputs
+/// are read, so the twiddle factor and the modulus may overlap `a` and `b`, which must not overlap
+/// each other. `a` and `b` must be below the modulus.
+///
+/// The chip constrains the outputs with three field operations modulo the modulus: `a + b`,
+/// `d = a - b` and `d * w`.
+#[derive(Default)]
+pub struct NttButterflyChip;
+
+impl NttButterflyChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the NttButterfly operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct NttButterflyCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to `a`.
+    pub a_ptr: T,
+
+    /// The pointer to the header.
+    pub header_ptr: T,
+
+    /// The header, which contains the pointers to `b`, the twiddle factor and the modulus.
+    pub header_memory: [MemoryReadCols<T>; HEADER_WORDS],
+
+    /// The twiddle factor.
+    pub twiddle_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The modulus.
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// `a`, overwritten with `a + b`. The input is the previous value of the access.
+    pub a_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// `b`, overwritten with `(a - b) * w`. The input is the previous value of the access.
+    pub b_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// The sum `a + b`.
+    pub sum: FieldOpCols<T, U256Field>,
+
+    /// The difference `d = a - b`.
+    pub difference: FieldOpCols<T, U256Field>,
+
+    /// The product `d * w`.
+    pub product: FieldOpCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+fn decode(words: &[u32]) -> BigUint {
+    BigUint::from_bytes_le(&words_to_bytes_le_vec(words))
+}
+
+fn encode(value: &BigUint) -> Vec<u32> {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(NUM_BYTES as usize, 0u8);
+    bytes_to_words_le::<8>(&bytes).to_vec()
+}
+
+impl<F: PrimeField32> MachineAir<F> for NttButterflyChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "NttButterfly".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .ntt_butterfly_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut NttButterflyCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.a_ptr = F::from_canonical_u32(event.a_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+
+                // Populate memory columns.
+                for i in 0..HEADER_WORDS {
+                    cols.header_memory[i].populate(
+                        event.channel,
+                        event.header_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.twiddle_memory[i].populate(
+                        event.channel,
+                        event.twiddle_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.a_memory[i].populate(
+                        event.channel,
+                        event.a_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.b_memory[i].populate(
+                        event.channel,
+                        event.b_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the field operations of the butterfly.
+                let a = decode(&event.a);
+                let b = decode(&event.b);
+                let twiddle = decode(&event.twiddle);
+                let modulus = decode(&event.modulus);
+                cols.sum.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &a,
+                    &b,
+                    &modulus,
+                    FieldOperation::Add,
+                );
+                let difference = cols.difference.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &a,
+                    &b,
+                    &modulus,
+                    FieldOperation::Sub,
+                );
+                cols.product.populate_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &difference,
+                    &twiddle,
+                    &modulus,
+                    FieldOperation::Mul,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut NttButterflyCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            for op in [&mut cols.sum, &mut cols.difference, &mut cols.product] {
+                op.populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Mul);
+            }
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut NttButterflyCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.ntt_butterfly_events.is_empty()
+    }
+}
+
+impl Syscall for NttButterflyChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let a_ptr = arg1;
+        rt.require_aligned(a_ptr, self.alignment()).unwrap();
+        let header_ptr = arg2;
+        rt.require_aligned(header_ptr, self.alignment()).unwrap();
+        let clk = rt.clk;
+
+        // Read the header, then the twiddle factor and the modulus it points to.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, HEADER_WORDS);
+        let b_ptr = header[0];
+        let twiddle_ptr = header[1];
+        let modulus_ptr = header[2];
+        for ptr in [b_ptr, twiddle_ptr, modulus_ptr] {
+            rt.require_aligned(ptr, self.alignment()).unwrap();
+        }
+        rt.require_disjoint(a_ptr, NUM_BYTES, b_ptr, NUM_BYTES).unwrap();
+        let (twiddle_memory_records, twiddle) = rt.mr_slice(twiddle_ptr, WORDS_FIELD_ELEMENT);
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        // Read a and b without records, since they are overwritten with the outputs later.
+        let a = rt.slice_unsafe(a_ptr, WORDS_FIELD_ELEMENT);
+        let b = rt.slice_unsafe(b_ptr, WORDS_FIELD_ELEMENT);
+
+        let uint256_a = decode(&a);
+        let uint256_b = decode(&b);
+        let uint256_modulus = decode(&modulus);
+        if uint256_a >= uint256_modulus || uint256_b >= uint256_modulus {
+            panic!("ntt_butterfly inputs must be below the modulus");
+        }
+        let sum = (&uint256_a + &uint256_b) % &uint256_modulus;
+        let difference = (&uint256_modulus + &uint256_a - &uint256_b) % &uint256_modulus;
+        let product = (difference * decode(&twiddle)) % &uint256_modulus;
+
+        // Write the outputs a cycle after the inputs are read, so that they may overlap the
+        // twiddle factor and the modulus.
+        rt.clk += 1;
+        let a_memory_records = rt.mw_slice(a_ptr, &encode(&sum));
+        let b_memory_records = rt.mw_slice(b_ptr, &encode(&product));
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().ntt_butterfly_events.push(NttButterflyEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            a_ptr,
+            header_ptr,
+            b_ptr,
+            twiddle_ptr,
+            modulus_ptr,
+            a,
+            b,
+            twiddle,
+            modulus,
+            header_memory_records,
+            twiddle_memory_records,
+            modulus_memory_records,
+            a_memory_records,
+            b_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for NttButterflyChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for NttButterflyChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &NttButterflyCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &NttButterflyCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The inputs are stored in the "prev_value" of a_memory and b_memory, since they are
+        // overwritten with the outputs.
+        let a: Limbs<_, <U256Field as NumLimbs>::Limbs> = limbs_from_prev_access(&local.a_memory);
+        let b: Limbs<_, <U256Field as NumLimbs>::Limbs> = limbs_from_prev_access(&local.b_memory);
+        let twiddle: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.twiddle_memory);
+        let modulus: Limbs<_, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.modulus_memory);
+
+        // Evaluate a + b, and assert that it is written to a.
+        local.sum.eval_with_modulus(
+            builder,
+            &a,
+            &b,
+            &modulus,
+            FieldOperation::Add,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.sum.result, value_as_limbs(&local.a_memory));
+
+        // Evaluate (a - b) * w, and assert that it is written to b.
+        local.difference.eval_with_modulus(
+            builder,
+            &a,
+            &b,
+            &modulus,
+            FieldOperation::Sub,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.product.eval_with_modulus(
+            builder,
+            &local.difference.result,
+            &twiddle,
+            &modulus,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.product.result, value_as_limbs(&local.b_memory));
+
+        // Read the header, the twiddle factor and the modulus, and write the outputs a cycle
+        // later. If a and b overlap, the two writes to the same address at the same clock cycle
+        // fail the memory checks.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_real,
+        );
+        let b_ptr = local.header_memory[0].value().reduce::<AB>();
+        let twiddle_ptr = local.header_memory[1].value().reduce::<AB>();
+        let modulus_ptr = local.header_memory[2].value().reduce::<AB>();
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            twiddle_ptr,
+            &local.twiddle_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.a_ptr,
+            &local.a_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            b_ptr,
+            &local.b_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::NTT_BUTTERFLY.syscall_id()),
+            local.a_ptr,
+            local.header_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
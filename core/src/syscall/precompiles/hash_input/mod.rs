@@ -0,0 +1,189 @@
+mod prepare;
+
+pub use prepare::*;
+
+#[cfg(test)]
+mod tests {
+    use tiny_keccak::keccakf;
+
+    use super::{hash_input_pad, HASH_INPUT_LENGTH_PREFIX, HASH_INPUT_PAD, HASH_INPUT_REVERSE};
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const INPUT_PTR: u32 = 0x1000;
+    const HEADER_PTR: u32 = 0x2000;
+    const OUT_PTR: u32 = 0x3000;
+
+    /// The number of output words filled with `CANARY` before preparing the input.
+    const OUT_WORDS: u32 = 64;
+
+    /// A word written to the output buffer before preparing the input, to check the bytes past the
+    /// output.
+    const CANARY: u32 = 0xa5a5_a5a5;
+
+    /// The length of a keccak state in bytes.
+    const KECCAK_STATE_BYTES: u32 = 200;
+
+    /// Stores `input` at `INPUT_PTR`, with ones past its end in its last word, and fills the output
+    /// buffer with `CANARY`, then prepares the input to `OUT_PTR` with `mode`.
+    fn prepare_instructions(input: &[u8], mode: u32) -> Vec<Instruction> {
+        let input_words = input.chunks(4).map(|chunk| {
+            let mut word = [0xff; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        });
+        let mut instructions = store_words(INPUT_PTR, input_words);
+        instructions.extend(store_words(OUT_PTR, (0..OUT_WORDS).map(|_| CANARY)));
+        instructions.extend(store_words(
+            HEADER_PTR,
+            [INPUT_PTR, input.len() as u32, mode],
+        ));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::PREPARE_HASH_INPUT as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        instructions
+    }
+
+    fn prepare_program(input: &[u8], mode: u32) -> Program {
+        Program::new(prepare_instructions(input, mode), 0, 0)
+    }
+
+    /// Prepares `input` by hand as the precompile would with `mode`.
+    fn prepare_reference(input: &[u8], mode: u32) -> Vec<u8> {
+        let mut out = vec![];
+        if mode & HASH_INPUT_LENGTH_PREFIX != 0 {
+            out.extend_from_slice(&(input.len() as u32).to_le_bytes());
+        }
+        if mode & HASH_INPUT_REVERSE != 0 {
+            out.extend(input.iter().rev());
+        } else {
+            out.extend_from_slice(input);
+        }
+        while out.len() % 4 != 0 {
+            out.push(0);
+        }
+        if mode & HASH_INPUT_PAD != 0 {
+            let block_len = (mode >> 8) as usize;
+            while out.len() % block_len != 0 {
+                out.push(0);
+            }
+        }
+        out
+    }
+
+    /// Prepares `input` with `mode` and checks the output against the reference, and that the rest
+    /// of the output buffer is left untouched.
+    fn check_prepare(input: &[u8], mode: u32) {
+        let program = prepare_program(input, mode);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let out = (0..OUT_WORDS)
+            .flat_map(|i| runtime.word(OUT_PTR + i * 4).to_le_bytes())
+            .collect::<Vec<_>>();
+        let expected = prepare_reference(input, mode);
+        assert_eq!(&out[..expected.len()], &expected[..], "mode {:#x}", mode);
+        assert!(out[expected.len()..]
+            .iter()
+            .all(|&byte| byte == CANARY as u8));
+    }
+
+    fn test_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 73 + 11) as u8).collect()
+    }
+
+    /// The modes combining every flag, with blocks of one, three and four words.
+    fn test_modes() -> Vec<u32> {
+        let mut modes = vec![];
+        for flags in 0..4 {
+            modes.push(flags);
+            for block_len in [4, 12, 16] {
+                modes.push(flags | hash_input_pad(block_len));
+            }
+        }
+        modes
+    }
+
+    #[test]
+    fn test_prepare_hash_input_execute() {
+        utils::setup_logger();
+        for mode in test_modes() {
+            for len in 1..=21 {
+                check_prepare(&test_input(len), mode);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "hash input length must be nonzero")]
+    fn test_prepare_hash_input_empty() {
+        check_prepare(&[], HASH_INPUT_REVERSE);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid hash input mode")]
+    fn test_prepare_hash_input_unaligned_block() {
+        check_prepare(&test_input(5), hash_input_pad(6));
+    }
+
+    #[test]
+    fn test_prepare_hash_input_prove() {
+        utils::setup_logger();
+        for mode in test_modes() {
+            for len in [1, 4, 7] {
+                run_test(prepare_program(&test_input(len), mode)).unwrap();
+            }
+        }
+    }
+
+    /// Prepares a reversed, length-prefixed input padded to a keccak state, and permutes it with
+    /// the keccak precompile, which matches the permutation of the input prepared by hand.
+    #[test]
+    fn test_prepare_hash_input_keccak() {
+        utils::setup_logger();
+        let input = test_input(37);
+        let mode =
+            HASH_INPUT_REVERSE | HASH_INPUT_LENGTH_PREFIX | hash_input_pad(KECCAK_STATE_BYTES);
+        let mut instructions = prepare_instructions(&input, mode);
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::KECCAK_PERMUTE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let reference = prepare_reference(&input, mode);
+        assert_eq!(reference.len(), KECCAK_STATE_BYTES as usize);
+        let mut expected = [0u64; 25];
+        for (lane, bytes) in expected.iter_mut().zip(reference.chunks(8)) {
+            *lane = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccakf(&mut expected);
+        for (i, lane) in expected.into_iter().enumerate() {
+            let addr = OUT_PTR + i as u32 * 8;
+            assert_eq!(runtime.word(addr), lane as u32);
+            assert_eq!(runtime.word(addr + 4), (lane >> 32) as u32);
+        }
+
+        run_test(program).unwrap();
+    }
+}
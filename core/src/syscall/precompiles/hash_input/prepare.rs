@@ -0,0 +1,634 @@
+use crate::air::{MachineAir, SP1AirBuilder, WordAirBuilder, WORD_SIZE};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the PrepareHashInputCols.
+const NUM_COLS: usize = size_of::<PrepareHashInputCols<u8>>();
+
+/// The number of words of the header, which contains the input pointer, its length in bytes and
+/// the mode.
+const HEADER_WORDS: usize = 3;
+
+/// The mode flag reversing the bytes of the input.
+pub const HASH_INPUT_REVERSE: u32 = 1;
+
+/// The mode flag prepending the length of the input in bytes, as a little-endian word.
+pub const HASH_INPUT_LENGTH_PREFIX: u32 = 2;
+
+/// The mode flag padding the output with zeros to a multiple of the block length, which is given
+/// in bytes by the bits of the mode above the first byte.
+pub const HASH_INPUT_PAD: u32 = 4;
+
+/// Returns the mode padding the output to blocks of `block_len` bytes, a nonzero multiple of four.
+pub const fn hash_input_pad(block_len: u32) -> u32 {
+    HASH_INPUT_PAD | (block_len << 8)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrepareHashInputEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub header_ptr: u32,
+    pub input_ptr: u32,
+    /// The number of bytes of input.
+    pub len: u32,
+    pub mode: u32,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    /// The words of the input in the order they are read by the rows, which is reversed if the
+    /// input is reversed. The last word of the input may hold bytes past its end.
+    pub input_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile preparing the input of a hash function, by reversing its bytes, prepending its
+/// length and padding it with zeros to a multiple of the block length, as selected by the mode.
+///
+/// Each row writes one output word, which is either the length prefix, a word of input or a word
+/// of padding. The output is the optional prefix, followed by the input padded with zeros to a
+/// whole word, followed by the optional padding. A row of reversed input takes the bytes of a word
+/// of input from the end of the word read by the row, and the rest from the word read by the next
+/// row. The input is read at the clock of the syscall and the output written a cycle later.
+#[derive(Default)]
+pub struct PrepareHashInputChip;
+
+impl PrepareHashInputChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one output word of the PrepareHashInput operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct PrepareHashInputCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output.
+    pub out_ptr: T,
+
+    /// The pointer to the header.
+    pub header_ptr: T,
+
+    /// The pointer to the input.
+    pub input_ptr: T,
+
+    /// The number of bytes of input.
+    pub len: T,
+
+    /// The flags of the mode.
+    pub is_reverse: T,
+    pub is_prefix: T,
+    pub is_pad: T,
+
+    /// The number of words of a block.
+    pub block_words: T,
+
+    /// The index of the last word of the input, and a one-hot encoding of the index of its last
+    /// byte within it.
+    pub last_word: T,
+    pub last_byte: [T; WORD_SIZE],
+
+    /// The index of the output word of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// Whether the output word is the length prefix, a word of input or a word of padding.
+    pub is_prefix_row: T,
+    pub is_input_row: T,
+    pub is_pad_row: T,
+
+    /// Whether the output word is the last word of input.
+    pub is_last_input: T,
+
+    /// Whether the row reverses a word of input, and whether it takes bytes from the next row.
+    pub is_reverse_row: T,
+    pub reverse_next: T,
+
+    /// The index of the word of input read by the row.
+    pub input_word: T,
+
+    /// The index of the output word within its block, and whether it is the last word of the block.
+    pub block_index: T,
+    pub is_block_end: T,
+
+    /// Whether each byte of the output word is part of the input.
+    pub is_valid: [T; WORD_SIZE],
+
+    /// The bytes of input of the output word, which are zero past the input.
+    pub bytes: [T; WORD_SIZE],
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; HEADER_WORDS],
+
+    /// The word of input read by the row.
+    pub input_memory: MemoryReadCols<T>,
+
+    /// The output word.
+    pub out_memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for PrepareHashInputChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "PrepareHashInput".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.prepare_hash_input_events.iter() {
+            let len = event.len as usize;
+            let is_reverse = event.mode & HASH_INPUT_REVERSE != 0;
+            let is_prefix = event.mode & HASH_INPUT_LENGTH_PREFIX != 0;
+            let is_pad = event.mode & HASH_INPUT_PAD != 0;
+            let block_words = (event.mode >> 8) as usize / WORD_SIZE;
+            let last_word = (len - 1) / WORD_SIZE;
+            let last_byte = (len - 1) % WORD_SIZE;
+            let num_prefix = is_prefix as usize;
+
+            // The bytes of the input in output order.
+            let mut words = event
+                .input_memory_records
+                .iter()
+                .map(|record| record.value)
+                .collect::<Vec<_>>();
+            if is_reverse {
+                words.reverse();
+            }
+            let mut data = words
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .take(len)
+                .collect::<Vec<_>>();
+            if is_reverse {
+                data.reverse();
+            }
+
+            let num_rows = event.out_memory_records.len();
+            for i in 0..num_rows {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut PrepareHashInputCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.is_reverse = F::from_bool(is_reverse);
+                cols.is_prefix = F::from_bool(is_prefix);
+                cols.is_pad = F::from_bool(is_pad);
+                cols.block_words = F::from_canonical_usize(block_words);
+                cols.last_word = F::from_canonical_usize(last_word);
+                cols.last_byte[last_byte] = F::one();
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == num_rows - 1);
+
+                // Assign the kind of the row, and its position in its block.
+                let is_prefix_row = is_prefix && i == 0;
+                let is_input_row = !is_prefix_row && i <= last_word + num_prefix;
+                let is_last_input = is_input_row && i == last_word + num_prefix;
+                cols.is_prefix_row = F::from_bool(is_prefix_row);
+                cols.is_input_row = F::from_bool(is_input_row);
+                cols.is_pad_row = F::from_bool(!is_prefix_row && !is_input_row);
+                cols.is_last_input = F::from_bool(is_last_input);
+                cols.is_reverse_row = F::from_bool(is_reverse && is_input_row);
+                cols.reverse_next = F::from_bool(is_reverse && is_input_row && !is_last_input);
+                if is_pad {
+                    cols.block_index = F::from_canonical_usize(i % block_words);
+                    cols.is_block_end = F::from_bool(i % block_words == block_words - 1);
+                } else {
+                    cols.block_index = F::from_canonical_usize(i);
+                }
+                for k in 0..WORD_SIZE {
+                    cols.is_valid[k] = F::from_bool(!is_last_input || k <= last_byte);
+                }
+
+                // Populate memory columns. The header is only read on the first row.
+                if i == 0 {
+                    for j in 0..HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                if is_input_row {
+                    let m = i - num_prefix;
+                    let record = event.input_memory_records[m];
+                    cols.input_memory.populate(event.channel, record, &mut new_byte_lookup_events);
+                    cols.input_word =
+                        F::from_canonical_usize(if is_reverse { last_word - m } else { m });
+                    let bytes = record.value.to_le_bytes();
+                    for k in 0..WORD_SIZE {
+                        cols.bytes[k] = F::from_canonical_u8(if is_reverse {
+                            data.get(WORD_SIZE * m + k).copied().unwrap_or(0)
+                        } else {
+                            bytes[k]
+                        });
+                    }
+                }
+                cols.out_memory.populate(
+                    event.channel,
+                    event.out_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut PrepareHashInputCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.prepare_hash_input_events.is_empty()
+    }
+}
+
+impl Syscall for PrepareHashInputChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let clk = rt.clk;
+        let out_ptr = arg1;
+        let header_ptr = arg2;
+        rt.require_aligned(out_ptr, 4).unwrap();
+        rt.require_aligned(header_ptr, 4).unwrap();
+
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, HEADER_WORDS);
+        let input_ptr = header[0];
+        let len = header[1];
+        let mode = header[2];
+        rt.require_aligned(input_ptr, 4).unwrap();
+        if len == 0 {
+            panic!("hash input length must be nonzero");
+        }
+        let block_len = (mode >> 8) as usize;
+        let is_pad = mode & HASH_INPUT_PAD != 0;
+        let valid_block_len = if is_pad {
+            block_len != 0 && block_len % WORD_SIZE == 0
+        } else {
+            block_len == 0
+        };
+        let flags = HASH_INPUT_REVERSE | HASH_INPUT_LENGTH_PREFIX | HASH_INPUT_PAD;
+        if mode & 0xff & !flags != 0 || !valid_block_len {
+            panic!("invalid hash input mode {:#x}", mode);
+        }
+
+        // Read the input, in reverse order if it is reversed.
+        let (mut input_memory_records, input) =
+            rt.mr_slice(input_ptr, (len as usize).div_ceil(WORD_SIZE));
+        let mut data = input
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(len as usize)
+            .collect::<Vec<_>>();
+        if mode & HASH_INPUT_REVERSE != 0 {
+            data.reverse();
+            input_memory_records.reverse();
+        }
+
+        // Write the output a cycle after the input is read, so that it may overlap the input.
+        let mut out = Vec::new();
+        if mode & HASH_INPUT_LENGTH_PREFIX != 0 {
+            out.extend_from_slice(&len.to_le_bytes());
+        }
+        out.extend_from_slice(&data);
+        out.resize(out.len().next_multiple_of(WORD_SIZE), 0);
+        if is_pad {
+            out.resize(out.len().next_multiple_of(block_len), 0);
+        }
+        let words = out
+            .chunks(WORD_SIZE)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        rt.clk += 1;
+        let out_memory_records = rt.mw_slice(out_ptr, &words);
+
+        let event = PrepareHashInputEvent {
+            lookup_id: rt.syscall_lookup_id,
+            shard: rt.current_shard(),
+            channel: rt.current_channel(),
+            clk,
+            out_ptr,
+            header_ptr,
+            input_ptr,
+            len,
+            mode,
+            header_memory_records,
+            input_memory_records,
+            out_memory_records,
+        };
+        rt.record_mut().prepare_hash_input_events.push(event);
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for PrepareHashInputChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for PrepareHashInputChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &PrepareHashInputCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &PrepareHashInputCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(local.is_real - local.is_last, next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        // The first byte of the mode holds its flags, and the others the block length in bytes.
+        let mode = local.header_memory[2].value();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.input_ptr, local.header_memory[0].value().reduce::<AB>());
+        first.assert_eq(local.len, local.header_memory[1].value().reduce::<AB>());
+        first.assert_eq(
+            mode[0],
+            local.is_reverse
+                + local.is_prefix * AB::F::two()
+                + local.is_pad * AB::F::from_canonical_u32(4),
+        );
+        first.assert_eq(
+            mode[1] + mode[2] * AB::F::from_canonical_u32(1 << 8)
+                + mode[3] * AB::F::from_canonical_u32(1 << 16),
+            local.block_words * AB::F::from_canonical_usize(WORD_SIZE),
+        );
+        first.assert_zero(local.index);
+        first.assert_zero(local.block_index);
+        first.assert_eq(local.is_prefix_row, local.is_prefix);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation.clone());
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.out_ptr, local.out_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.input_ptr, local.input_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.is_reverse, local.is_reverse);
+        continuation.assert_eq(next.is_prefix, local.is_prefix);
+        continuation.assert_eq(next.is_pad, local.is_pad);
+        continuation.assert_eq(next.block_words, local.block_words);
+        continuation.assert_eq(next.last_word, local.last_word);
+        for (next_last_byte, last_byte) in next.last_byte.iter().zip(local.last_byte.iter()) {
+            continuation.assert_eq(*next_last_byte, *last_byte);
+        }
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+
+        // Assert that the last word and byte of the input are those of its length.
+        builder.assert_bool(local.is_reverse);
+        builder.assert_bool(local.is_prefix);
+        builder.assert_bool(local.is_pad);
+        let mut num_last_bytes = AB::Expr::zero();
+        let mut last_byte = AB::Expr::zero();
+        for k in 0..WORD_SIZE {
+            builder.assert_bool(local.last_byte[k]);
+            num_last_bytes = num_last_bytes + local.last_byte[k];
+            last_byte = last_byte + local.last_byte[k] * AB::F::from_canonical_usize(k);
+        }
+        builder.assert_eq(num_last_bytes, local.is_real);
+        builder.when(local.is_real).assert_eq(
+            local.len - AB::Expr::one(),
+            local.last_word * AB::F::from_canonical_usize(WORD_SIZE) + last_byte,
+        );
+
+        // Constrain the kinds of the rows. The prefix is the first row if any, followed by the
+        // words of input, the last of which is the last word of the input, followed by padding.
+        builder.assert_bool(local.is_prefix_row);
+        builder.assert_bool(local.is_input_row);
+        builder.assert_bool(local.is_pad_row);
+        builder.assert_bool(local.is_last_input);
+        builder.assert_eq(
+            local.is_prefix_row + local.is_input_row + local.is_pad_row,
+            local.is_real,
+        );
+        builder.when(local.is_prefix_row).assert_one(local.is_first);
+        builder.when(local.is_last_input).assert_one(local.is_input_row);
+        builder.when(local.is_pad_row).assert_one(local.is_pad);
+        builder
+            .when_transition()
+            .when(next_is_continuation.clone())
+            .assert_eq(
+                next.is_input_row,
+                local.is_prefix_row + local.is_input_row - local.is_last_input,
+            );
+        builder.when(local.is_last).assert_zero(local.is_prefix_row);
+        builder
+            .when(local.is_last)
+            .assert_eq(local.is_input_row, local.is_last_input);
+        let input_index = local.index - local.is_prefix;
+        builder
+            .when(local.is_last_input)
+            .assert_eq(input_index.clone(), local.last_word);
+
+        // Constrain the blocks. The block index wraps around at the end of each block, and the
+        // output ends at the end of a block when padding. A padding word never starts a block, so
+        // the output has no block of padding only.
+        builder.assert_bool(local.is_block_end);
+        builder.when(local.is_block_end).assert_eq(
+            local.block_index,
+            local.block_words - AB::Expr::one(),
+        );
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(
+            next.block_index,
+            (local.block_index + AB::Expr::one()) * (AB::Expr::one() - local.is_block_end),
+        );
+        continuation.assert_zero(local.is_block_end * next.is_pad_row);
+        builder
+            .when(local.is_last)
+            .when(local.is_pad)
+            .assert_one(local.is_block_end);
+
+        // Read the word of input from the start of the input, or from its end if reversed.
+        builder.assert_eq(local.is_reverse_row, local.is_reverse * local.is_input_row);
+        builder.assert_eq(
+            local.reverse_next,
+            local.is_reverse * (local.is_input_row - local.is_last_input),
+        );
+        builder.when(local.is_input_row).assert_eq(
+            local.input_word,
+            input_index.clone()
+                + local.is_reverse * (local.last_word - input_index * AB::F::two()),
+        );
+
+        // Assert that the bytes are those of the word of input, or those of the reversed input. A
+        // reversed word ending at byte `r` of the word read takes its bytes up to `r` from the word
+        // read, in reverse order, and the rest from the end of the word read by the next row.
+        let input = local.input_memory.value();
+        let next_input = next.input_memory.value();
+        for k in 0..WORD_SIZE {
+            builder
+                .when(local.is_input_row - local.is_reverse_row)
+                .assert_eq(local.bytes[k], input[k]);
+            let mut from_word = AB::Expr::zero();
+            let mut from_next = AB::Expr::zero();
+            for r in 0..WORD_SIZE {
+                if r >= k {
+                    from_word = from_word + local.last_byte[r] * (local.bytes[k] - input[r - k]);
+                } else {
+                    from_next = from_next
+                        + local.last_byte[r] * (local.bytes[k] - next_input[WORD_SIZE + r - k]);
+                }
+            }
+            builder.when(local.is_reverse_row).assert_zero(from_word);
+            builder
+                .when_transition()
+                .when(local.reverse_next)
+                .assert_zero(from_next);
+        }
+
+        // Assert that the output word is the length, the bytes of input, or zero.
+        let out = local.out_memory.value();
+        builder
+            .when(local.is_prefix_row)
+            .assert_word_eq(*out, *local.header_memory[1].value());
+        let mut num_invalid = AB::Expr::zero();
+        for k in 0..WORD_SIZE {
+            builder.assert_eq(
+                local.is_valid[k],
+                AB::Expr::one() - local.is_last_input * num_invalid.clone(),
+            );
+            num_invalid = num_invalid + local.last_byte[k];
+            builder
+                .when(local.is_input_row)
+                .assert_eq(out[k], local.is_valid[k] * local.bytes[k]);
+        }
+        builder.when(local.is_pad_row).assert_word_zero(*out);
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the word of input, and write the output word a cycle later.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.input_ptr + local.input_word * AB::F::from_canonical_usize(WORD_SIZE),
+            &local.input_memory,
+            local.is_input_row,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.out_ptr + local.index * AB::F::from_canonical_usize(WORD_SIZE),
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::PREPARE_HASH_INPUT.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -40,6 +40,8 @@ impl KeccakPermuteChip {
 
 #[cfg(test)]
 pub mod permute_tests {
+    use tiny_keccak::keccakf;
+
     use crate::runtime::SyscallCode;
     use crate::utils::{run_test, SP1CoreOpts};
     use crate::{
@@ -80,6 +82,37 @@ pub mod permute_tests {
         runtime.run().unwrap();
     }
 
+    #[test]
+    fn test_keccak_f_zero_state() {
+        utils::setup_logger();
+        let state_ptr = 100;
+        let instructions = vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::KECCAK_PERMUTE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, state_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let mut expected = [0u64; 25];
+        keccakf(&mut expected);
+        for (i, lane) in expected.into_iter().enumerate() {
+            let addr = state_ptr + i as u32 * 8;
+            assert_eq!(runtime.word(addr), lane as u32);
+            assert_eq!(runtime.word(addr + 4), (lane >> 32) as u32);
+        }
+
+        run_test(program).unwrap();
+    }
+
     #[test]
     fn test_keccak_permute_prove_babybear() {
         utils::setup_logger();
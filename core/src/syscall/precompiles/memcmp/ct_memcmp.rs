@@ -0,0 +1,538 @@
+use crate::air::{MachineAir, SP1AirBuilder, Word, WordAirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the CtMemcmpCols.
+const NUM_COLS: usize = size_of::<CtMemcmpCols<u8>>();
+
+/// The number of words of the header read by the precompile, which contains the pointer to the
+/// second buffer and the number of bytes. The result is written to the word which follows them.
+pub const CT_MEMCMP_HEADER_WORDS: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CtMemcmpEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub a_ptr: u32,
+    pub header_ptr: u32,
+    pub b_ptr: u32,
+    /// The number of bytes compared.
+    pub len: u32,
+    pub is_equal: bool,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub a_memory_records: Vec<MemoryReadRecord>,
+    pub b_memory_records: Vec<MemoryReadRecord>,
+    pub result_memory_record: MemoryWriteRecord,
+}
+
+impl CtMemcmpEvent {
+    /// The number of words of each buffer, which is the number of rows of the event.
+    pub const fn num_words(&self) -> usize {
+        ct_memcmp_words(self.len)
+    }
+}
+
+/// Returns the number of words spanned by a buffer of `len` bytes.
+pub const fn ct_memcmp_words(len: u32) -> usize {
+    (len as usize).div_ceil(WORD_SIZE)
+}
+
+/// Returns whether the first `len` bytes of the words `a` and `b` are equal, by OR-ing the XOR of
+/// every pair of bytes.
+pub fn ct_memcmp(a: &[u32], b: &[u32], len: u32) -> bool {
+    let mut diff = 0u8;
+    let bytes = |words: &[u32]| {
+        words
+            .iter()
+            .flat_map(|w| w.to_le_bytes())
+            .collect::<Vec<_>>()
+    };
+    for (x, y) in bytes(a).into_iter().zip(bytes(b)).take(len as usize) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// A precompile comparing two buffers of bytes for equality, with an access pattern and a number
+/// of cycles which only depend on their length.
+///
+/// Each row compares one word of the buffers. The XOR of each pair of bytes within the length is
+/// OR-ed into a running difference, which is carried over the rows of the call, and the buffers
+/// are equal if the difference of the last row is zero. The first buffer is read at the clock of
+/// the syscall, and the second buffer a cycle later, so that the buffers may overlap. The result,
+/// one if the buffers are equal and zero otherwise, is written a cycle later to the word after the
+/// header, since the return register of a precompile is left unchanged.
+#[derive(Default)]
+pub struct CtMemcmpChip;
+
+impl CtMemcmpChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one word of the CtMemcmp operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct CtMemcmpCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the first buffer.
+    pub a_ptr: T,
+
+    /// The pointer to the header, which contains the pointer to the second buffer and the number
+    /// of bytes, followed by the result.
+    pub header_ptr: T,
+
+    /// The pointer to the second buffer.
+    pub b_ptr: T,
+
+    /// The number of bytes.
+    pub len: T,
+
+    /// The index of the word of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// Whether each byte of the word is within the length.
+    pub byte_mask: [T; WORD_SIZE],
+
+    /// The XOR of each pair of bytes.
+    pub xor: [T; WORD_SIZE],
+
+    /// The XOR of each pair of bytes within the length, and zero otherwise.
+    pub masked_xor: [T; WORD_SIZE],
+
+    /// The difference of the previous rows of the call.
+    pub diff_in: T,
+
+    /// The difference up to and including each byte of the word.
+    pub diff: [T; WORD_SIZE],
+
+    /// Whether the difference of the call is zero, on its last row.
+    pub is_equal: IsZeroOperation<T>,
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; CT_MEMCMP_HEADER_WORDS],
+
+    /// The word of the first buffer.
+    pub a_memory: MemoryReadCols<T>,
+
+    /// The word of the second buffer.
+    pub b_memory: MemoryReadCols<T>,
+
+    /// The result, which is only written on the last row of the call.
+    pub result_memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for CtMemcmpChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "CtMemcmp".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.ct_memcmp_events.iter() {
+            let num_words = event.num_words();
+            let mut diff = 0u8;
+            for i in 0..num_words {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut CtMemcmpCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.a_ptr = F::from_canonical_u32(event.a_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.b_ptr = F::from_canonical_u32(event.b_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == num_words - 1);
+
+                // OR the XOR of each pair of bytes within the length into the difference.
+                cols.diff_in = F::from_canonical_u8(diff);
+                let a = event.a_memory_records[i].value.to_le_bytes();
+                let b = event.b_memory_records[i].value.to_le_bytes();
+                for j in 0..WORD_SIZE {
+                    let in_len = i * WORD_SIZE + j < event.len as usize;
+                    let xor = a[j] ^ b[j];
+                    let masked_xor = if in_len { xor } else { 0 };
+                    let prev_diff = diff;
+                    diff |= masked_xor;
+                    cols.byte_mask[j] = F::from_bool(in_len);
+                    cols.xor[j] = F::from_canonical_u8(xor);
+                    cols.masked_xor[j] = F::from_canonical_u8(masked_xor);
+                    cols.diff[j] = F::from_canonical_u8(diff);
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                        event.shard,
+                        event.channel,
+                        ByteOpcode::XOR,
+                        xor as u32,
+                        0,
+                        a[j] as u32,
+                        b[j] as u32,
+                    ));
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                        event.shard,
+                        event.channel,
+                        ByteOpcode::OR,
+                        diff as u32,
+                        0,
+                        prev_diff as u32,
+                        masked_xor as u32,
+                    ));
+                }
+
+                // Populate memory columns. The header is read on the first row, and the result is
+                // written on the last row.
+                if i == 0 {
+                    for j in 0..CT_MEMCMP_HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                if i == num_words - 1 {
+                    let is_equal = cols.is_equal.populate(diff as u32);
+                    debug_assert_eq!(is_equal == 1, event.is_equal);
+                    cols.result_memory.populate(
+                        event.channel,
+                        event.result_memory_record,
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.a_memory.populate(
+                    event.channel,
+                    event.a_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.b_memory.populate(
+                    event.channel,
+                    event.b_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut CtMemcmpCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.ct_memcmp_events.is_empty()
+    }
+}
+
+impl Syscall for CtMemcmpChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let a_ptr = arg1;
+        rt.require_aligned(a_ptr, self.alignment()).unwrap();
+        let header_ptr = arg2;
+        rt.require_aligned(header_ptr, self.alignment()).unwrap();
+
+        // Read the header, which contains the pointer to the second buffer and the number of bytes.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, CT_MEMCMP_HEADER_WORDS);
+        let b_ptr = header[0];
+        rt.require_aligned(b_ptr, self.alignment()).unwrap();
+        let len = header[1];
+        if len == 0 {
+            panic!("ct memcmp length must be nonzero");
+        }
+
+        // The header and the result are accessed at the same clocks as the buffers, so they must
+        // not overlap them.
+        let num_words = ct_memcmp_words(len);
+        let buf_len = (num_words * WORD_SIZE) as u32;
+        let header_len = ((CT_MEMCMP_HEADER_WORDS + 1) * WORD_SIZE) as u32;
+        rt.require_disjoint(header_ptr, header_len, a_ptr, buf_len)
+            .unwrap();
+        rt.require_disjoint(header_ptr, header_len, b_ptr, buf_len)
+            .unwrap();
+
+        // Read every word of both buffers, whether or not they differ, the second a cycle later so
+        // that they may overlap, and write the result with it.
+        let (a_memory_records, a) = rt.mr_slice(a_ptr, num_words);
+        rt.clk += 1;
+        let (b_memory_records, b) = rt.mr_slice(b_ptr, num_words);
+        let is_equal = ct_memcmp(&a, &b, len);
+        let result_ptr = header_ptr + (CT_MEMCMP_HEADER_WORDS * WORD_SIZE) as u32;
+        let result_memory_record = rt.mw(result_ptr, is_equal as u32);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().ct_memcmp_events.push(CtMemcmpEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk: start_clk,
+            a_ptr,
+            header_ptr,
+            b_ptr,
+            len,
+            is_equal,
+            header_memory_records,
+            a_memory_records,
+            b_memory_records,
+            result_memory_record,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for CtMemcmpChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for CtMemcmpChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &CtMemcmpCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &CtMemcmpCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued.clone(), next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows,
+        // along with the difference.
+        let header_b_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_len = local.header_memory[1].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.b_ptr, header_b_ptr);
+        first.assert_eq(local.len, header_len);
+        first.assert_zero(local.index);
+        first.assert_zero(local.diff_in);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.a_ptr, local.a_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.b_ptr, local.b_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+        continuation.assert_eq(next.diff_in, local.diff[WORD_SIZE - 1]);
+
+        // The bytes within the length are a nonempty prefix of the word, the whole word on every
+        // row but the last, and the length ends on the last row.
+        for j in 0..WORD_SIZE {
+            builder.assert_bool(local.byte_mask[j]);
+            builder
+                .when(is_continued.clone())
+                .assert_one(local.byte_mask[j]);
+            if j > 0 {
+                builder
+                    .when(local.byte_mask[j])
+                    .assert_one(local.byte_mask[j - 1]);
+            }
+        }
+        builder.when(local.is_real).assert_one(local.byte_mask[0]);
+        let num_bytes = local
+            .byte_mask
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &mask| acc + mask);
+        builder.when(local.is_last).assert_eq(
+            local.index * AB::F::from_canonical_usize(WORD_SIZE) + num_bytes,
+            local.len,
+        );
+
+        // OR the XOR of each pair of bytes within the length into the difference.
+        let a = local.a_memory.value();
+        let b = local.b_memory.value();
+        for j in 0..WORD_SIZE {
+            let prev_diff = if j == 0 {
+                local.diff_in
+            } else {
+                local.diff[j - 1]
+            };
+            builder.send_byte(
+                ByteOpcode::XOR.as_field::<AB::F>(),
+                local.xor[j],
+                a[j],
+                b[j],
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            builder.assert_eq(local.masked_xor[j], local.byte_mask[j] * local.xor[j]);
+            builder.send_byte(
+                ByteOpcode::OR.as_field::<AB::F>(),
+                local.diff[j],
+                prev_diff,
+                local.masked_xor[j],
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        // The buffers are equal if the difference of the call is zero, which is the result.
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            local.diff[WORD_SIZE - 1].into(),
+            local.is_equal,
+            local.is_last.into(),
+        );
+        let result = Word([
+            local.is_equal.result.into(),
+            AB::Expr::zero(),
+            AB::Expr::zero(),
+            AB::Expr::zero(),
+        ]);
+        builder
+            .when(local.is_last)
+            .assert_word_eq(*local.result_memory.value(), result);
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the word of the first buffer, and the word of the second buffer a cycle later, along
+        // with the result on the last row.
+        let offset = local.index * AB::F::from_canonical_usize(WORD_SIZE);
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.a_ptr + offset.clone(),
+            &local.a_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.b_ptr + offset,
+            &local.b_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.header_ptr + AB::F::from_canonical_usize(CT_MEMCMP_HEADER_WORDS * WORD_SIZE),
+            &local.result_memory,
+            local.is_last,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::CT_MEMCMP.syscall_id()),
+            local.a_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,94 @@
+mod ct_memcmp;
+
+pub use ct_memcmp::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    use super::ct_memcmp;
+
+    const A_PTR: u32 = 0x1000;
+    const B_PTR: u32 = 0x1100;
+    const HEADER_PTR: u32 = 0x2000;
+    const BUF_WORDS: u32 = 8;
+
+    /// Returns a buffer of distinct bytes.
+    fn buffer() -> Vec<u32> {
+        (0..BUF_WORDS)
+            .map(|i| u32::from_le_bytes(core::array::from_fn(|j| (i * 4) as u8 + j as u8)))
+            .collect()
+    }
+
+    /// Stores `a` at `A_PTR`, `b` at `B_PTR` and the header at `HEADER_PTR`, and compares the
+    /// first `len` bytes of the buffers.
+    fn ct_memcmp_program(a: &[u32], b: &[u32], len: u32) -> Program {
+        let mut instructions = store_words(A_PTR, a.iter().copied());
+        instructions.extend(store_words(B_PTR, b.iter().copied()));
+        instructions.extend(store_words(HEADER_PTR, [B_PTR, len]));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CT_MEMCMP as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, A_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Checks that the precompile writes whether the buffers are equal after the header.
+    fn check_ct_memcmp(a: &[u32], b: &[u32], len: u32, expected: bool) {
+        assert_eq!(ct_memcmp(a, b, len), expected);
+        let program = ct_memcmp_program(a, b, len);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.word(HEADER_PTR + 8), expected as u32);
+        assert_eq!(runtime.record.ct_memcmp_events.len(), 1);
+    }
+
+    /// Returns the buffer with the byte at `index` flipped.
+    fn flip_byte(buf: &[u32], index: usize) -> Vec<u32> {
+        let mut buf = buf.to_vec();
+        buf[index / 4] ^= 0x80 << (8 * (index % 4));
+        buf
+    }
+
+    #[test]
+    fn test_ct_memcmp_execute() {
+        utils::setup_logger();
+        let a = buffer();
+        check_ct_memcmp(&a, &a, 32, true);
+        check_ct_memcmp(&a, &flip_byte(&a, 0), 32, false);
+        check_ct_memcmp(&a, &flip_byte(&a, 17), 32, false);
+        check_ct_memcmp(&a, &flip_byte(&a, 31), 32, false);
+        // The bytes of the last word beyond the length are not compared.
+        check_ct_memcmp(&a, &flip_byte(&a, 31), 30, true);
+        check_ct_memcmp(&a, &flip_byte(&a, 29), 30, false);
+        check_ct_memcmp(&a, &flip_byte(&a, 1), 1, true);
+        check_ct_memcmp(&a, &flip_byte(&a, 0), 1, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "ct memcmp length must be nonzero")]
+    fn test_ct_memcmp_empty() {
+        utils::setup_logger();
+        let a = buffer();
+        check_ct_memcmp(&a, &a, 0, true);
+    }
+
+    #[test]
+    fn test_ct_memcmp_prove() {
+        utils::setup_logger();
+        let a = buffer();
+        run_test(ct_memcmp_program(&a, &a, 32)).unwrap();
+        run_test(ct_memcmp_program(&a, &flip_byte(&a, 17), 30)).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+use crate::air::{MachineAir, SP1AirBuilder, WordAirBuilder, WORD_SIZE};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the MemcpyCols.
+const NUM_COLS: usize = size_of::<MemcpyCols<u8>>();
+
+/// The number of words of the header, which contains the source pointer followed by the length.
+const HEADER_WORDS: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemcpyEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub dst_ptr: u32,
+    pub header_ptr: u32,
+    pub src_ptr: u32,
+    /// The number of words copied.
+    pub len: u32,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub src_memory_records: Vec<MemoryReadRecord>,
+    pub dst_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile copying words from a source to a destination buffer, which may overlap, like
+/// `memmove`.
+///
+/// Each row copies one word. All the source words are read at the clock of the syscall, and all
+/// the destination words are written a cycle later, so a word in the overlap is read before it is
+/// written whichever way the buffers overlap, and the copy holds the original source values.
+#[derive(Default)]
+pub struct MemcpyChip;
+
+impl MemcpyChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one word of the Memcpy operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct MemcpyCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the destination.
+    pub dst_ptr: T,
+
+    /// The pointer to the header, which contains the source pointer followed by the number of
+    /// words.
+    pub header_ptr: T,
+
+    /// The pointer to the source.
+    pub src_ptr: T,
+
+    /// The number of words.
+    pub len: T,
+
+    /// The index of the word of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; HEADER_WORDS],
+
+    /// The source word.
+    pub src_memory: MemoryReadCols<T>,
+
+    /// The destination word, which is written to with the source word.
+    pub dst_memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for MemcpyChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Memcpy".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.memcpy_events.iter() {
+            let len = event.len as usize;
+            for i in 0..len {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut MemcpyCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.dst_ptr = F::from_canonical_u32(event.dst_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.src_ptr = F::from_canonical_u32(event.src_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == len - 1);
+
+                // Populate memory columns. The header is only read on the first row.
+                if i == 0 {
+                    for j in 0..HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                cols.src_memory.populate(
+                    event.channel,
+                    event.src_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.dst_memory.populate(
+                    event.channel,
+                    event.dst_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut MemcpyCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.memcpy_events.is_empty()
+    }
+}
+
+impl Syscall for MemcpyChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let dst_ptr = arg1;
+        let header_ptr = arg2;
+        if dst_ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the header, which contains the source pointer followed by the number of words.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, HEADER_WORDS);
+        let src_ptr = header[0];
+        let len = header[1];
+        if src_ptr % 4 != 0 {
+            panic!();
+        }
+        if len == 0 {
+            panic!("memcpy length must be nonzero");
+        }
+
+        // Read the whole source before writing the destination a cycle later, so that overlapping
+        // words are copied with their original values.
+        let (src_memory_records, src) = rt.mr_slice(src_ptr, len as usize);
+        rt.clk += 1;
+        let dst_memory_records = rt.mw_slice(dst_ptr, &src);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().memcpy_events.push(MemcpyEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk: start_clk,
+            dst_ptr,
+            header_ptr,
+            src_ptr,
+            len,
+            header_memory_records,
+            src_memory_records,
+            dst_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for MemcpyChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for MemcpyChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &MemcpyCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &MemcpyCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued, next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        let header_src_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_len = local.header_memory[1].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.src_ptr, header_src_ptr);
+        first.assert_eq(local.len, header_len);
+        first.assert_zero(local.index);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.dst_ptr, local.dst_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.src_ptr, local.src_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+        builder
+            .when(local.is_last)
+            .assert_eq(local.index + AB::Expr::one(), local.len);
+
+        // Assert that the destination word is written with the source word.
+        builder
+            .when(local.is_real)
+            .assert_word_eq(*local.dst_memory.value(), *local.src_memory.value());
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the source word, and write the destination word a cycle later.
+        let offset = local.index * AB::F::from_canonical_usize(WORD_SIZE);
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.src_ptr + offset.clone(),
+            &local.src_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.dst_ptr + offset,
+            &local.dst_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::MEMCPY.syscall_id()),
+            local.dst_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
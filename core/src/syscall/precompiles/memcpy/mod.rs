@@ -0,0 +1,73 @@
+mod copy;
+
+pub use copy::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const BUF_PTR: u32 = 0x1000;
+    const HEADER_PTR: u32 = 0x2000;
+    const BUF_WORDS: u32 = 16;
+
+    /// Fills the buffer at `BUF_PTR` with distinct words, and copies `len` words from word `src` of
+    /// the buffer to word `dst`.
+    fn memcpy_program(dst: u32, src: u32, len: u32) -> Program {
+        let mut instructions = store_words(BUF_PTR, (0..BUF_WORDS).map(|i| 0x1111_1111 * (i + 1)));
+        instructions.extend(store_words(HEADER_PTR, [BUF_PTR + src * 4, len]));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::MEMCPY as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, BUF_PTR + dst * 4, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Checks that the copy leaves the buffer as `memmove` would.
+    fn check_memcpy(dst: u32, src: u32, len: u32) {
+        let mut runtime = Runtime::new(memcpy_program(dst, src, len), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let buf = (0..BUF_WORDS)
+            .map(|i| runtime.word(BUF_PTR + i * 4))
+            .collect::<Vec<_>>();
+
+        let mut expected = (0..BUF_WORDS)
+            .map(|i| 0x1111_1111 * (i + 1))
+            .collect::<Vec<_>>();
+        expected.copy_within(src as usize..(src + len) as usize, dst as usize);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_memcpy_execute() {
+        utils::setup_logger();
+        // Disjoint buffers.
+        check_memcpy(8, 0, 8);
+        check_memcpy(0, 8, 8);
+        // Overlapping buffers, copied forward and backward.
+        check_memcpy(0, 3, 10);
+        check_memcpy(3, 0, 10);
+        check_memcpy(1, 0, 15);
+        check_memcpy(0, 1, 15);
+        // The same buffer, and a single word.
+        check_memcpy(2, 2, 5);
+        check_memcpy(15, 0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "memcpy length must be nonzero")]
+    fn test_memcpy_empty() {
+        utils::setup_logger();
+        check_memcpy(0, 1, 0);
+    }
+
+    #[test]
+    fn test_memcpy_prove() {
+        utils::setup_logger();
+        run_test(memcpy_program(0, 3, 10)).unwrap();
+        run_test(memcpy_program(3, 0, 10)).unwrap();
+    }
+}
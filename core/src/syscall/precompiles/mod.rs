@@ -1,6 +1,19 @@
+pub mod aes;
+pub mod babybear;
+pub mod bn254;
+pub mod chacha20;
+pub mod crt;
 pub mod edwards;
+pub mod encode;
+pub mod field;
+pub mod hash_input;
 pub mod keccak256;
+pub mod memcmp;
+pub mod memcpy;
+pub mod poseidon2;
+pub mod secp256k1;
 pub mod sha256;
+pub mod table;
 pub mod uint256;
 pub mod weierstrass;
 use crate::operations::field::params::{NumLimbs, NumWords};
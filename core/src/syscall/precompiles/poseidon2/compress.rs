@@ -0,0 +1,356 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::ByteLookupEvent;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::BabyBearWordRangeChecker;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{inner_perm, pad_rows};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use p3_symmetric::Permutation;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{Poseidon2RoundsCols, DIGEST_SIZE, WIDTH};
+
+/// The number of columns in the Poseidon2CompressCols.
+const NUM_COLS: usize = size_of::<Poseidon2CompressCols<u8>>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poseidon2Event {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub input_ptr: u32,
+    pub out_ptr: u32,
+    pub input: Vec<u32>,
+    pub output: Vec<u32>,
+    pub input_memory_records: Vec<MemoryReadRecord>,
+    pub output_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Compresses two digests into one, as done by the Merkle trees of the recursion layer.
+///
+/// The two halves are concatenated into a width-16 state, permuted, and the first 8 elements of
+/// the result are kept. The inputs are reduced modulo the BabyBear prime, and the output is
+/// canonical.
+pub fn poseidon2_compress(
+    left: [u32; DIGEST_SIZE],
+    right: [u32; DIGEST_SIZE],
+) -> [u32; DIGEST_SIZE] {
+    let state: [BabyBear; WIDTH] = core::array::from_fn(|i| {
+        BabyBear::from_wrapped_u32(if i < DIGEST_SIZE {
+            left[i]
+        } else {
+            right[i - DIGEST_SIZE]
+        })
+    });
+    let state = inner_perm().permute(state);
+    core::array::from_fn(|i| state[i].as_canonical_u32())
+}
+
+/// A precompile for the Poseidon2 2-to-1 compression function over BabyBear.
+///
+/// The whole width-16 permutation is computed in a single row, see [`Poseidon2RoundsCols`].
+#[derive(Default)]
+pub struct Poseidon2CompressChip;
+
+impl Poseidon2CompressChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Poseidon2Compress operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Poseidon2CompressCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the input, which contains the left digest followed by the right digest.
+    pub input_ptr: T,
+
+    /// The pointer to the output digest.
+    pub out_ptr: T,
+
+    pub input_memory: [MemoryReadCols<T>; WIDTH],
+    pub output_memory: [MemoryWriteCols<T>; DIGEST_SIZE],
+
+    /// Checks that the output words are canonical BabyBear elements.
+    pub output_range_checkers: [BabyBearWordRangeChecker<T>; DIGEST_SIZE],
+
+    /// The rounds of the permutation.
+    pub rounds: Poseidon2RoundsCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Poseidon2CompressChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Poseidon2Compress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let num_events = input.poseidon2_compress_events.len();
+        let chunk_size = std::cmp::max(num_events / num_cpus::get(), 1);
+        self.generate_trace_in_chunks(input, output, chunk_size)
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.poseidon2_compress_events.is_empty()
+    }
+}
+
+impl Poseidon2CompressChip {
+    /// Generates the trace of the events of `input`, populating the rows of each chunk of
+    /// `chunk_size` events in parallel.
+    ///
+    /// Every event fills a single row on its own, and the chunks are concatenated in order, so the
+    /// trace and the byte lookups added to `output` do not depend on `chunk_size`.
+    pub fn generate_trace_in_chunks<F: PrimeField32>(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+        chunk_size: usize,
+    ) -> RowMajorMatrix<F> {
+        // Generate the trace rows & corresponding records for each chunk of events in parallel.
+        let rows_and_records = input
+            .poseidon2_compress_events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut record = ExecutionRecord::default();
+                let mut new_byte_lookup_events = Vec::new();
+
+                let rows = events
+                    .iter()
+                    .map(|event| populate_row(event, &mut new_byte_lookup_events))
+                    .collect::<Vec<_>>();
+                record.add_byte_lookup_events(new_byte_lookup_events);
+                (rows, record)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = Vec::new();
+        for mut row_and_record in rows_and_records {
+            rows.extend(row_and_record.0);
+            output.append(&mut row_and_record.1);
+        }
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Poseidon2CompressCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+}
+
+/// Populates the row of a compression event, except for its nonce.
+fn populate_row<F: PrimeField32>(
+    event: &Poseidon2Event,
+    new_byte_lookup_events: &mut Vec<ByteLookupEvent>,
+) -> [F; NUM_COLS] {
+    let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+    let cols: &mut Poseidon2CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+    // Assign basic values to the columns.
+    cols.is_real = F::one();
+    cols.shard = F::from_canonical_u32(event.shard);
+    cols.channel = F::from_canonical_u32(event.channel);
+    cols.clk = F::from_canonical_u32(event.clk);
+    cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+    cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+
+    // Populate memory columns.
+    for i in 0..WIDTH {
+        cols.input_memory[i].populate(
+            event.channel,
+            event.input_memory_records[i],
+            new_byte_lookup_events,
+        );
+    }
+    for i in 0..DIGEST_SIZE {
+        cols.output_memory[i].populate(
+            event.channel,
+            event.output_memory_records[i],
+            new_byte_lookup_events,
+        );
+        cols.output_range_checkers[i].populate(event.output[i]);
+        new_byte_lookup_events.add_u8_range_checks(
+            event.shard,
+            event.channel,
+            &event.output[i].to_le_bytes(),
+        );
+    }
+
+    // Populate the permutation.
+    let input = core::array::from_fn(|i| F::from_wrapped_u32(event.input[i]));
+    let state = cols.rounds.populate(input);
+    for i in 0..DIGEST_SIZE {
+        debug_assert_eq!(state[i], F::from_canonical_u32(event.output[i]));
+    }
+
+    row
+}
+
+impl Syscall for Poseidon2CompressChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let input_ptr = arg1;
+        let out_ptr = arg2;
+        if input_ptr % 4 != 0 || out_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the left and right digests, which are stored contiguously.
+        let (input_memory_records, input) = rt.mr_slice(input_ptr, WIDTH);
+
+        let left = core::array::from_fn(|i| input[i]);
+        let right = core::array::from_fn(|i| input[DIGEST_SIZE + i]);
+        let output = poseidon2_compress(left, right).to_vec();
+
+        // Write the compressed digest to the output.
+        let output_memory_records = rt.mw_slice(out_ptr, &output);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().poseidon2_compress_events.push(Poseidon2Event {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            input_ptr,
+            out_ptr,
+            input,
+            output,
+            input_memory_records,
+            output_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Poseidon2CompressChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Poseidon2CompressChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Poseidon2CompressCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Poseidon2CompressCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the permutation of the input, and its first half against the output.
+        let input = core::array::from_fn(|i| local.input_memory[i].value().reduce::<AB>());
+        let state =
+            Poseidon2RoundsCols::<AB::F>::eval(builder, input, &local.rounds, local.is_real);
+        for i in 0..DIGEST_SIZE {
+            builder.assert_eq(
+                local.output_memory[i].value().reduce::<AB>(),
+                state[i].clone(),
+            );
+        }
+
+        // The output words must be canonical, so that the digest is unique.
+        for i in 0..DIGEST_SIZE {
+            let value = *local.output_memory[i].value();
+            builder.slice_range_check_u8(&value.0, local.shard, local.channel, local.is_real);
+            BabyBearWordRangeChecker::<AB::F>::range_check(
+                builder,
+                value,
+                local.output_range_checkers[i],
+                local.is_real.into(),
+            );
+        }
+
+        // Read the input.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.input_ptr,
+            &local.input_memory,
+            local.is_real,
+        );
+
+        // Write the output.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.out_ptr,
+            &local.output_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::POSEIDON2_COMPRESS.syscall_id()),
+            local.input_ptr,
+            local.out_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
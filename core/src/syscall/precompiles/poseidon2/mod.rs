@@ -0,0 +1,268 @@
+#![allow(clippy::needless_range_loop)]
+
+mod compress;
+mod permute;
+mod rounds;
+
+pub use compress::*;
+pub use permute::*;
+pub use rounds::*;
+
+use p3_baby_bear::{MONTY_INVERSE, POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY};
+use p3_field::{AbstractField, PrimeField32};
+use p3_poseidon2::matmul_internal;
+
+/// The width of the permutation.
+pub const WIDTH: usize = 16;
+
+/// The number of elements of a digest, and of each half of a compression input.
+pub const DIGEST_SIZE: usize = 8;
+
+pub const NUM_EXTERNAL_ROUNDS: usize = 8;
+pub const NUM_INTERNAL_ROUNDS: usize = 13;
+
+/// Multiplies four state elements by the 4x4 MDS matrix of the external rounds.
+pub fn apply_m_4<AF>(x: &mut [AF])
+where
+    AF: AbstractField,
+{
+    let t01 = x[0].clone() + x[1].clone();
+    let t23 = x[2].clone() + x[3].clone();
+    let t0123 = t01.clone() + t23.clone();
+    let t01123 = t0123.clone() + x[1].clone();
+    let t01233 = t0123.clone() + x[3].clone();
+    // The order here is important. Need to overwrite x[0] and x[2] after x[1] and x[3].
+    x[3] = t01233.clone() + x[0].double(); // 3*x[0] + x[1] + x[2] + 2*x[3]
+    x[1] = t01123.clone() + x[2].double(); // x[0] + 2*x[1] + 3*x[2] + x[3]
+    x[0] = t01123 + t01; // 2*x[0] + 3*x[1] + x[2] + x[3]
+    x[2] = t01233 + t23; // x[0] + x[1] + 2*x[2] + 3*x[3]
+}
+
+/// The linear layer of the external rounds, matching `Poseidon2ExternalMatrixGeneral`.
+pub fn external_linear_layer<AF: AbstractField>(state: &mut [AF; WIDTH]) {
+    for j in (0..WIDTH).step_by(4) {
+        apply_m_4(&mut state[j..j + 4]);
+    }
+    let sums: [AF; 4] = core::array::from_fn(|k| {
+        (0..WIDTH)
+            .step_by(4)
+            .map(|j| state[j + k].clone())
+            .sum::<AF>()
+    });
+
+    for j in 0..WIDTH {
+        state[j] += sums[j % 4].clone();
+    }
+}
+
+/// The linear layer of the internal rounds, matching `DiffusionMatrixBabyBear`.
+pub fn internal_linear_layer<F: AbstractField>(state: &mut [F; WIDTH]) {
+    let matmul_constants: [<F as AbstractField>::F; WIDTH] =
+        POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY
+            .iter()
+            .map(|x| <F as AbstractField>::F::from_wrapped_u32(x.as_canonical_u32()))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+    matmul_internal(state, matmul_constants);
+    let monty_inverse = F::from_wrapped_u32(MONTY_INVERSE.as_canonical_u32());
+    state.iter_mut().for_each(|i| *i *= monty_inverse.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField32};
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_symmetric::{Permutation, PseudoCompressionFunction};
+
+    use crate::air::MachineAir;
+    use crate::runtime::{ExecutionRecord, Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, inner_perm, run_test, InnerCompress, SP1CoreOpts};
+
+    use super::{poseidon2_compress, poseidon2_permute, Poseidon2CompressChip, DIGEST_SIZE, WIDTH};
+
+    const INPUT_PTR: u32 = 0x1000;
+    const NODE_PTR: u32 = 0x1800;
+    const OUT_PTR: u32 = 0x2000;
+
+    /// Returns the instructions compressing the two digests at `input_ptr` into `out_ptr`.
+    fn compress_instructions(input_ptr: u32, out_ptr: u32) -> Vec<Instruction> {
+        vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::POSEIDON2_COMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, input_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, out_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]
+    }
+
+    /// Stores `left` followed by `right` at `INPUT_PTR` and compresses them into `OUT_PTR`.
+    fn poseidon2_compress_program(left: [u32; 8], right: [u32; 8]) -> Program {
+        let mut instructions = store_words(INPUT_PTR, left.into_iter().chain(right));
+        instructions.extend(compress_instructions(INPUT_PTR, OUT_PTR));
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores the four `leaves` at `INPUT_PTR`, compresses each pair of them into the two nodes at
+    /// `NODE_PTR`, and compresses the nodes into the root at `OUT_PTR`.
+    fn merkle_root_program(leaves: [[u32; 8]; 4]) -> Program {
+        let mut instructions = store_words(INPUT_PTR, leaves.into_iter().flatten());
+        instructions.extend(compress_instructions(INPUT_PTR, NODE_PTR));
+        instructions.extend(compress_instructions(INPUT_PTR + 64, NODE_PTR + 32));
+        instructions.extend(compress_instructions(NODE_PTR, OUT_PTR));
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores two digests at `INPUT_PTR` and compresses them `num_calls` times, writing each result
+    /// over the left digest.
+    fn poseidon2_compress_chain_program(num_calls: usize) -> Program {
+        let mut instructions = store_words(INPUT_PTR, (0..16).map(|i| i * 0x0765_4321));
+        for _ in 0..num_calls {
+            instructions.extend(compress_instructions(INPUT_PTR, INPUT_PTR));
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Compresses two digests with the compression function of the recursion Merkle trees.
+    fn reference_compress(left: [u32; 8], right: [u32; 8]) -> [u32; 8] {
+        let compress = InnerCompress::new(inner_perm());
+        let left = left.map(BabyBear::from_wrapped_u32);
+        let right = right.map(BabyBear::from_wrapped_u32);
+        compress
+            .compress([left, right])
+            .map(|x| x.as_canonical_u32())
+    }
+
+    #[test]
+    fn test_poseidon2_compress_execute() {
+        utils::setup_logger();
+        let left = core::array::from_fn(|i| i as u32);
+        let right = core::array::from_fn(|i| 0x7000_0000 + i as u32);
+        let program = poseidon2_compress_program(left, right);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let result: [u32; DIGEST_SIZE] =
+            core::array::from_fn(|i| runtime.word(OUT_PTR + i as u32 * 4));
+        assert_eq!(result, reference_compress(left, right));
+        assert_eq!(poseidon2_compress(left, right), result);
+        assert_eq!(runtime.record.poseidon2_compress_events.len(), 1);
+    }
+
+    #[test]
+    fn test_poseidon2_compress_merkle_nodes() {
+        utils::setup_logger();
+        let leaves: [[u32; 8]; 4] =
+            core::array::from_fn(|i| core::array::from_fn(|j| (i * 8 + j) as u32 * 0x0123_4567));
+        let mut runtime = Runtime::new(merkle_root_program(leaves), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let nodes = [
+            reference_compress(leaves[0], leaves[1]),
+            reference_compress(leaves[2], leaves[3]),
+        ];
+        for (i, node) in nodes.iter().enumerate() {
+            let result: [u32; DIGEST_SIZE] =
+                core::array::from_fn(|j| runtime.word(NODE_PTR + (i * 8 + j) as u32 * 4));
+            assert_eq!(&result, node);
+        }
+        let root: [u32; DIGEST_SIZE] =
+            core::array::from_fn(|i| runtime.word(OUT_PTR + i as u32 * 4));
+        assert_eq!(root, reference_compress(nodes[0], nodes[1]));
+        assert_eq!(runtime.record.poseidon2_compress_events.len(), 3);
+
+        run_test(merkle_root_program(leaves)).unwrap();
+    }
+
+    #[test]
+    fn test_poseidon2_compress_prove() {
+        utils::setup_logger();
+        let left = core::array::from_fn(|i| i as u32);
+        let right = core::array::from_fn(|i| 0x7800_0000 + i as u32);
+        run_test(poseidon2_compress_program(left, right)).unwrap();
+    }
+
+    #[test]
+    fn test_poseidon2_compress_trace_chunks() {
+        utils::setup_logger();
+        const NUM_CALLS: usize = 37;
+        let mut runtime = Runtime::new(
+            poseidon2_compress_chain_program(NUM_CALLS),
+            SP1CoreOpts::default(),
+        );
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.poseidon2_compress_events.len(), NUM_CALLS);
+
+        // A single chunk populates the rows serially.
+        let chip = Poseidon2CompressChip::new();
+        let mut serial_output = ExecutionRecord::default();
+        let serial_trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace_in_chunks(&runtime.record, &mut serial_output, NUM_CALLS);
+
+        for chunk_size in [1, 4, 10, NUM_CALLS + 1] {
+            let mut output = ExecutionRecord::default();
+            let trace: RowMajorMatrix<BabyBear> =
+                chip.generate_trace_in_chunks(&runtime.record, &mut output, chunk_size);
+            assert_eq!(trace.values, serial_trace.values);
+            assert_eq!(output.byte_lookups, serial_output.byte_lookups);
+        }
+
+        let mut output = ExecutionRecord::default();
+        let trace: RowMajorMatrix<BabyBear> = chip.generate_trace(&runtime.record, &mut output);
+        assert_eq!(trace.values, serial_trace.values);
+        assert_eq!(output.byte_lookups, serial_output.byte_lookups);
+    }
+
+    /// Stores `state` at `INPUT_PTR` and permutes it in place `num_calls` times.
+    fn poseidon2_permute_program(state: [u32; WIDTH], num_calls: usize) -> Program {
+        let mut instructions = store_words(INPUT_PTR, state);
+        for _ in 0..num_calls {
+            instructions.extend(vec![
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::POSEIDON2_PERMUTE as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, INPUT_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_poseidon2_permute_execute() {
+        utils::setup_logger();
+        // The last words are not canonical, and are reduced by the permutation.
+        let state = core::array::from_fn(|i| 0x7000_0000 + i as u32 * 0x0100_0000);
+        let mut runtime = Runtime::new(poseidon2_permute_program(state, 2), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let perm = inner_perm();
+        let expected = perm.permute(perm.permute(state.map(BabyBear::from_wrapped_u32)));
+        let result: [u32; WIDTH] =
+            core::array::from_fn(|i| runtime.word(INPUT_PTR + i as u32 * 4));
+        assert_eq!(result, expected.map(|x| x.as_canonical_u32()));
+        assert_eq!(poseidon2_permute(poseidon2_permute(state)), result);
+        assert_eq!(runtime.record.poseidon2_permute_events.len(), 2);
+    }
+
+    #[test]
+    fn test_poseidon2_permute_prove() {
+        utils::setup_logger();
+        let state = core::array::from_fn(|i| 0x7000_0000 + i as u32 * 0x0100_0000);
+        run_test(poseidon2_permute_program(state, 3)).unwrap();
+    }
+}
@@ -0,0 +1,297 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::ByteLookupEvent;
+use crate::memory::{MemoryCols, MemoryWriteCols};
+use crate::operations::BabyBearWordRangeChecker;
+use crate::runtime::MemoryWriteRecord;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::stark::MachineRecord;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{inner_perm, pad_rows};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::{ParallelIterator, ParallelSlice};
+use p3_symmetric::Permutation;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+use super::{Poseidon2RoundsCols, WIDTH};
+
+/// The number of columns in the Poseidon2PermuteCols.
+const NUM_COLS: usize = size_of::<Poseidon2PermuteCols<u8>>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poseidon2PermuteEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub state_ptr: u32,
+    pub input: Vec<u32>,
+    pub output: Vec<u32>,
+    pub state_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Applies the width-16 Poseidon2 permutation of the recursion layer to `state`.
+///
+/// The inputs are reduced modulo the BabyBear prime, and the output is canonical.
+pub fn poseidon2_permute(state: [u32; WIDTH]) -> [u32; WIDTH] {
+    let state = inner_perm().permute(state.map(BabyBear::from_wrapped_u32));
+    state.map(|x| x.as_canonical_u32())
+}
+
+/// A precompile applying the Poseidon2 permutation over BabyBear to a state in place.
+///
+/// This is the building block of sponges and duplex constructions, which keep the state in memory
+/// across calls. The whole permutation is computed in a single row, see [`Poseidon2RoundsCols`].
+#[derive(Default)]
+pub struct Poseidon2PermuteChip;
+
+impl Poseidon2PermuteChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Poseidon2Permute operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Poseidon2PermuteCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the state, which is overwritten with its permutation.
+    pub state_ptr: T,
+
+    pub state_memory: [MemoryWriteCols<T>; WIDTH],
+
+    /// Checks that the output words are canonical BabyBear elements.
+    pub output_range_checkers: [BabyBearWordRangeChecker<T>; WIDTH],
+
+    /// The rounds of the permutation.
+    pub rounds: Poseidon2RoundsCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Poseidon2PermuteChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Poseidon2Permute".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let num_events = input.poseidon2_permute_events.len();
+        let chunk_size = std::cmp::max(num_events / num_cpus::get(), 1);
+
+        // Generate the trace rows & corresponding records for each chunk of events in parallel.
+        let rows_and_records = input
+            .poseidon2_permute_events
+            .par_chunks(chunk_size)
+            .map(|events| {
+                let mut record = ExecutionRecord::default();
+                let mut new_byte_lookup_events = Vec::new();
+
+                let rows = events
+                    .iter()
+                    .map(|event| populate_row(event, &mut new_byte_lookup_events))
+                    .collect::<Vec<_>>();
+                record.add_byte_lookup_events(new_byte_lookup_events);
+                (rows, record)
+            })
+            .collect::<Vec<_>>();
+
+        let mut rows = Vec::new();
+        for mut row_and_record in rows_and_records {
+            rows.extend(row_and_record.0);
+            output.append(&mut row_and_record.1);
+        }
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Poseidon2PermuteCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.poseidon2_permute_events.is_empty()
+    }
+}
+
+/// Populates the row of a permutation event, except for its nonce.
+fn populate_row<F: PrimeField32>(
+    event: &Poseidon2PermuteEvent,
+    new_byte_lookup_events: &mut Vec<ByteLookupEvent>,
+) -> [F; NUM_COLS] {
+    let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+    let cols: &mut Poseidon2PermuteCols<F> = row.as_mut_slice().borrow_mut();
+
+    // Assign basic values to the columns.
+    cols.is_real = F::one();
+    cols.shard = F::from_canonical_u32(event.shard);
+    cols.channel = F::from_canonical_u32(event.channel);
+    cols.clk = F::from_canonical_u32(event.clk);
+    cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+
+    // Populate memory columns.
+    for i in 0..WIDTH {
+        cols.state_memory[i].populate(
+            event.channel,
+            event.state_memory_records[i],
+            new_byte_lookup_events,
+        );
+        cols.output_range_checkers[i].populate(event.output[i]);
+        new_byte_lookup_events.add_u8_range_checks(
+            event.shard,
+            event.channel,
+            &event.output[i].to_le_bytes(),
+        );
+    }
+
+    // Populate the permutation.
+    let input = core::array::from_fn(|i| F::from_wrapped_u32(event.input[i]));
+    let state = cols.rounds.populate(input);
+    for i in 0..WIDTH {
+        debug_assert_eq!(state[i], F::from_canonical_u32(event.output[i]));
+    }
+
+    row
+}
+
+impl Syscall for Poseidon2PermuteChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, _: u32) -> Option<u32> {
+        let state_ptr = arg1;
+        if state_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the state with slice_unsafe, since the permutation is written over it.
+        let input = rt.slice_unsafe(state_ptr, WIDTH);
+        let output = poseidon2_permute(core::array::from_fn(|i| input[i])).to_vec();
+        let state_memory_records = rt.mw_slice(state_ptr, &output);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .poseidon2_permute_events
+            .push(Poseidon2PermuteEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                state_ptr,
+                input,
+                output,
+                state_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Poseidon2PermuteChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Poseidon2PermuteChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Poseidon2PermuteCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Poseidon2PermuteCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The input is the previous value of the state, and the output its new value.
+        let input = core::array::from_fn(|i| local.state_memory[i].prev_value().reduce::<AB>());
+        let state =
+            Poseidon2RoundsCols::<AB::F>::eval(builder, input, &local.rounds, local.is_real);
+        for i in 0..WIDTH {
+            builder.assert_eq(local.state_memory[i].value().reduce::<AB>(), state[i].clone());
+        }
+
+        // The output words must be canonical, so that the state has a unique encoding.
+        for i in 0..WIDTH {
+            let value = *local.state_memory[i].value();
+            builder.slice_range_check_u8(&value.0, local.shard, local.channel, local.is_real);
+            BabyBearWordRangeChecker::<AB::F>::range_check(
+                builder,
+                value,
+                local.output_range_checkers[i],
+                local.is_real.into(),
+            );
+        }
+
+        // Read and write the state.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.state_ptr,
+            &local.state_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::POSEIDON2_PERMUTE.syscall_id()),
+            local.state_ptr,
+            AB::Expr::zero(),
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
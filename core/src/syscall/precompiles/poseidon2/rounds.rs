@@ -0,0 +1,187 @@
+use p3_field::{AbstractField, PrimeField32};
+use sp1_derive::AlignedBorrow;
+use sp1_primitives::RC_16_30_U32;
+
+use crate::air::SP1AirBuilder;
+
+use super::{
+    external_linear_layer, internal_linear_layer, NUM_EXTERNAL_ROUNDS, NUM_INTERNAL_ROUNDS, WIDTH,
+};
+
+/// A set of columns computing the width-16 Poseidon2 permutation in a single row.
+///
+/// There are columns for the state at the start of every external round, for the cubes computed by
+/// the sboxes, and for the 0th element of the state across the internal rounds.
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Poseidon2RoundsCols<T> {
+    /// The state at the start of each external round.
+    pub external_rounds_state: [[T; WIDTH]; NUM_EXTERNAL_ROUNDS],
+
+    /// The cube of each state element after the round constants of each external round.
+    pub external_rounds_sbox: [[T; WIDTH]; NUM_EXTERNAL_ROUNDS],
+
+    /// The state at the start of the internal rounds.
+    pub internal_rounds_state: [T; WIDTH],
+
+    /// The 0th element of the state after each internal round but the last.
+    pub internal_rounds_s0: [T; NUM_INTERNAL_ROUNDS - 1],
+
+    /// The cube of the 0th state element after the round constant of each internal round.
+    pub internal_rounds_sbox: [T; NUM_INTERNAL_ROUNDS],
+}
+
+/// Returns the index of the round constants of the given external round.
+const fn external_round_index(r: usize) -> usize {
+    if r < NUM_EXTERNAL_ROUNDS / 2 {
+        r
+    } else {
+        r + NUM_INTERNAL_ROUNDS
+    }
+}
+
+impl<F: PrimeField32> Poseidon2RoundsCols<F> {
+    /// Populates the rounds of the permutation of `input`, and returns its output.
+    pub fn populate(&mut self, input: [F; WIDTH]) -> [F; WIDTH] {
+        let mut state = input;
+        external_linear_layer(&mut state);
+        for r in 0..NUM_EXTERNAL_ROUNDS {
+            if r == NUM_EXTERNAL_ROUNDS / 2 {
+                state = self.populate_internal_rounds(state);
+            }
+            state = self.populate_external_round(state, r);
+        }
+        state
+    }
+
+    /// Populates the columns of an external round, and returns the state after it.
+    fn populate_external_round(&mut self, state: [F; WIDTH], r: usize) -> [F; WIDTH] {
+        self.external_rounds_state[r] = state;
+
+        let round = external_round_index(r);
+        let mut next_state = [F::zero(); WIDTH];
+        for i in 0..WIDTH {
+            let add_rc = state[i] + F::from_wrapped_u32(RC_16_30_U32[round][i]);
+            let sbox_deg_3 = add_rc * add_rc * add_rc;
+            self.external_rounds_sbox[r][i] = sbox_deg_3;
+            next_state[i] = sbox_deg_3 * sbox_deg_3 * add_rc;
+        }
+        external_linear_layer(&mut next_state);
+        next_state
+    }
+
+    /// Populates the columns of the internal rounds, and returns the state after them.
+    fn populate_internal_rounds(&mut self, mut state: [F; WIDTH]) -> [F; WIDTH] {
+        self.internal_rounds_state = state;
+
+        for r in 0..NUM_INTERNAL_ROUNDS {
+            let round = r + NUM_EXTERNAL_ROUNDS / 2;
+            let add_rc = state[0] + F::from_wrapped_u32(RC_16_30_U32[round][0]);
+            let sbox_deg_3 = add_rc * add_rc * add_rc;
+            self.internal_rounds_sbox[r] = sbox_deg_3;
+            state[0] = sbox_deg_3 * sbox_deg_3 * add_rc;
+            internal_linear_layer(&mut state);
+
+            if r < NUM_INTERNAL_ROUNDS - 1 {
+                self.internal_rounds_s0[r] = state[0];
+            }
+        }
+        state
+    }
+}
+
+impl<F: AbstractField> Poseidon2RoundsCols<F> {
+    /// Constrains the rounds of the permutation of `input`, and returns its output.
+    ///
+    /// The output is of degree three in the columns. The round constants are multiplied by
+    /// `is_real` so that padding rows of zeros are valid.
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        input: [AB::Expr; WIDTH],
+        cols: &Poseidon2RoundsCols<AB::Var>,
+        is_real: AB::Var,
+    ) -> [AB::Expr; WIDTH] {
+        // The initial state is the input after the first external linear layer.
+        let mut state = input;
+        external_linear_layer(&mut state);
+        for i in 0..WIDTH {
+            builder.assert_eq(cols.external_rounds_state[0][i], state[i].clone());
+        }
+
+        // Constrain each external round against the state at the start of the next round, and the
+        // internal rounds against the state after them. The last external round gives the output.
+        let mut output = None;
+        for r in 0..NUM_EXTERNAL_ROUNDS {
+            let state = eval_external_round(builder, cols, is_real, r);
+            if r == NUM_EXTERNAL_ROUNDS / 2 - 1 {
+                for i in 0..WIDTH {
+                    builder.assert_eq(cols.internal_rounds_state[i], state[i].clone());
+                }
+            } else if r == NUM_EXTERNAL_ROUNDS - 1 {
+                output = Some(state);
+            } else {
+                for i in 0..WIDTH {
+                    builder.assert_eq(cols.external_rounds_state[r + 1][i], state[i].clone());
+                }
+            }
+        }
+        let state = eval_internal_rounds(builder, cols, is_real);
+        for i in 0..WIDTH {
+            builder.assert_eq(
+                cols.external_rounds_state[NUM_EXTERNAL_ROUNDS / 2][i],
+                state[i].clone(),
+            );
+        }
+        output.unwrap()
+    }
+}
+
+/// Constrains the sboxes of an external round, and returns the state after it.
+fn eval_external_round<AB: SP1AirBuilder>(
+    builder: &mut AB,
+    cols: &Poseidon2RoundsCols<AB::Var>,
+    is_real: AB::Var,
+    r: usize,
+) -> [AB::Expr; WIDTH] {
+    let round = external_round_index(r);
+    let mut state: [AB::Expr; WIDTH] = core::array::from_fn(|_| AB::Expr::zero());
+    for i in 0..WIDTH {
+        let add_rc: AB::Expr = cols.external_rounds_state[r][i].into()
+            + is_real * AB::F::from_wrapped_u32(RC_16_30_U32[round][i]);
+        let sbox_deg_3 = cols.external_rounds_sbox[r][i];
+        builder.assert_eq(sbox_deg_3, add_rc.clone() * add_rc.clone() * add_rc.clone());
+        state[i] = sbox_deg_3 * sbox_deg_3 * add_rc;
+    }
+    external_linear_layer(&mut state);
+    state
+}
+
+/// Constrains the internal rounds, and returns the state after them.
+fn eval_internal_rounds<AB: SP1AirBuilder>(
+    builder: &mut AB,
+    cols: &Poseidon2RoundsCols<AB::Var>,
+    is_real: AB::Var,
+) -> [AB::Expr; WIDTH] {
+    let mut state: [AB::Expr; WIDTH] =
+        core::array::from_fn(|i| cols.internal_rounds_state[i].into());
+    for r in 0..NUM_INTERNAL_ROUNDS {
+        let round = r + NUM_EXTERNAL_ROUNDS / 2;
+        let s0: AB::Expr = if r == 0 {
+            state[0].clone()
+        } else {
+            cols.internal_rounds_s0[r - 1].into()
+        };
+        let add_rc = s0 + is_real * AB::F::from_wrapped_u32(RC_16_30_U32[round][0]);
+        let sbox_deg_3 = cols.internal_rounds_sbox[r];
+        builder.assert_eq(sbox_deg_3, add_rc.clone() * add_rc.clone() * add_rc.clone());
+
+        // Since only the 0th element goes through the sbox, the rest of the state stays of degree
+        // one in the columns, and only the 0th element needs a column after each round.
+        state[0] = sbox_deg_3 * sbox_deg_3 * add_rc;
+        internal_linear_layer(&mut state);
+        if r < NUM_INTERNAL_ROUNDS - 1 {
+            builder.assert_eq(cols.internal_rounds_s0[r], state[0].clone());
+        }
+    }
+    state
+}
@@ -0,0 +1,330 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{bytes_to_words_le_vec, pad_rows, words_to_bytes_le_vec};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the Secp256k1CompressCols.
+const NUM_COLS: usize = size_of::<Secp256k1CompressCols<u8>>();
+
+/// The number of words of a coordinate of a secp256k1 point.
+const NUM_COORDINATE_WORDS: usize = 8;
+
+/// The number of words of the compressed point, whose last three bytes are zero.
+pub const NUM_COMPRESSED_WORDS: usize = 9;
+
+/// The number of bytes of the compressed point: the prefix byte followed by x in big-endian.
+pub const COMPRESSED_POINT_SIZE: usize = 33;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1CompressEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub point_ptr: u32,
+    pub out_ptr: u32,
+    pub point: Vec<u32>,
+    pub point_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Returns the SEC1 compressed form of the secp256k1 point whose coordinates are the little-endian
+/// words `x` followed by `y`: the prefix `0x02` or `0x03` for an even or odd `y`, followed by the
+/// big-endian bytes of `x`.
+pub fn secp256k1_compress(point: &[u32]) -> [u8; COMPRESSED_POINT_SIZE] {
+    let bytes = words_to_bytes_le_vec(point);
+    let mut compressed = [0u8; COMPRESSED_POINT_SIZE];
+    compressed[0] = 2 + (bytes[4 * NUM_COORDINATE_WORDS] & 1);
+    for (i, byte) in bytes[..4 * NUM_COORDINATE_WORDS].iter().rev().enumerate() {
+        compressed[1 + i] = *byte;
+    }
+    compressed
+}
+
+/// A precompile compressing a secp256k1 point to its SEC1 form, the inverse of the decompression
+/// precompile.
+///
+/// The compressed point is written as nine words, with three trailing zero bytes. The bytes of `x`
+/// are copied in reverse, and the parity of `y` is constrained by a byte lookup of the least
+/// significant byte of `y` with the mask `1`. The point is not checked to be on the curve.
+#[derive(Default)]
+pub struct Secp256k1CompressChip;
+
+impl Secp256k1CompressChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Secp256k1Compress operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Secp256k1CompressCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the point, as the words of x followed by the words of y.
+    pub point_ptr: T,
+
+    /// The pointer to the compressed point.
+    pub out_ptr: T,
+
+    /// The memory columns of the point.
+    pub point_memory: [MemoryReadCols<T>; 2 * NUM_COORDINATE_WORDS],
+
+    /// The memory columns of the compressed point.
+    pub out_memory: [MemoryWriteCols<T>; NUM_COMPRESSED_WORDS],
+
+    /// Whether y is odd.
+    pub y_is_odd: T,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Secp256k1CompressChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Secp256k1Compress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .secp256k1_compress_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Secp256k1CompressCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.point_ptr = F::from_canonical_u32(event.point_ptr);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+
+                // Populate memory columns.
+                for i in 0..2 * NUM_COORDINATE_WORDS {
+                    cols.point_memory[i].populate(
+                        event.channel,
+                        event.point_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..NUM_COMPRESSED_WORDS {
+                    cols.out_memory[i].populate(
+                        event.channel,
+                        event.out_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the parity of y.
+                let y_low_byte = event.point[NUM_COORDINATE_WORDS] & 0xff;
+                cols.y_is_odd = F::from_canonical_u32(y_low_byte & 1);
+                new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                    opcode: ByteOpcode::AND,
+                    shard: event.shard,
+                    channel: event.channel,
+                    a1: y_low_byte & 1,
+                    a2: 0,
+                    b: y_low_byte,
+                    c: 1,
+                });
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Secp256k1CompressCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.secp256k1_compress_events.is_empty()
+    }
+}
+
+impl Syscall for Secp256k1CompressChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let point_ptr = arg1;
+        let out_ptr = arg2;
+        if point_ptr % 4 != 0 || out_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let (point_memory_records, point) = rt.mr_slice(point_ptr, 2 * NUM_COORDINATE_WORDS);
+
+        let mut out = [0u8; 4 * NUM_COMPRESSED_WORDS];
+        out[..COMPRESSED_POINT_SIZE].copy_from_slice(&secp256k1_compress(&point));
+        let out = bytes_to_words_le_vec(&out);
+
+        // Write the compressed point a cycle after the point is read, since they may overlap.
+        rt.clk += 1;
+        let out_memory_records = rt.mw_slice(out_ptr, &out);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .secp256k1_compress_events
+            .push(Secp256k1CompressEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk: start_clk,
+                point_ptr,
+                out_ptr,
+                point,
+                point_memory_records,
+                out_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Secp256k1CompressChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Secp256k1CompressChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Secp256k1CompressCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Secp256k1CompressCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let point_bytes = local
+            .point_memory
+            .iter()
+            .flat_map(|memory| memory.value().0)
+            .collect::<Vec<_>>();
+        let out_bytes = local
+            .out_memory
+            .iter()
+            .flat_map(|memory| memory.value().0)
+            .collect::<Vec<_>>();
+
+        // Constrain the parity of y from its least significant byte.
+        builder.assert_bool(local.y_is_odd);
+        builder.send_byte(
+            ByteOpcode::AND.as_field::<AB::F>(),
+            local.y_is_odd,
+            point_bytes[4 * NUM_COORDINATE_WORDS],
+            AB::F::one(),
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the compressed point is the prefix followed by the bytes of x in reverse,
+        // padded with zeros.
+        builder
+            .when(local.is_real)
+            .assert_eq(out_bytes[0], local.y_is_odd + AB::F::from_canonical_u8(2));
+        for i in 0..4 * NUM_COORDINATE_WORDS {
+            builder
+                .when(local.is_real)
+                .assert_eq(out_bytes[1 + i], point_bytes[4 * NUM_COORDINATE_WORDS - 1 - i]);
+        }
+        for byte in &out_bytes[COMPRESSED_POINT_SIZE..] {
+            builder.when(local.is_real).assert_zero(*byte);
+        }
+
+        // Read the point, and write the compressed point a cycle later.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.point_ptr,
+            &local.point_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.out_ptr,
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::SECP256K1_COMPRESS.syscall_id()),
+            local.point_ptr,
+            local.out_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
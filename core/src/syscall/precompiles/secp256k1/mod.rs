@@ -0,0 +1,202 @@
+mod compress;
+mod normalize_s;
+
+pub use compress::*;
+pub use normalize_s::*;
+
+#[cfg(test)]
+mod tests {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use num::{BigUint, One};
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::ec::weierstrass::secp256k1::Secp256k1ScalarField;
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    use super::{secp256k1_high_s_bound, COMPRESSED_POINT_SIZE, NUM_COMPRESSED_WORDS};
+
+    const S_PTR: u32 = 0x1000;
+    const POINT_PTR: u32 = 0x2000;
+    const OUT_PTR: u32 = 0x3000;
+
+    /// Returns the uncompressed SEC1 encoding of `[k] G`.
+    fn secp256k1_test_point(k: u64) -> [u8; 65] {
+        let point = (k256::ProjectivePoint::GENERATOR * k256::Scalar::from(k)).to_affine();
+        point.to_encoded_point(false).as_bytes().try_into().unwrap()
+    }
+
+    /// Returns the little-endian words of the big-endian coordinate.
+    fn coordinate_words(coordinate: &[u8]) -> Vec<u32> {
+        let mut bytes = coordinate.to_vec();
+        bytes.reverse();
+        bytes_to_words_le_vec(&bytes)
+    }
+
+    /// Stores the point encoded in `uncompressed` at `POINT_PTR` and compresses it to `OUT_PTR`.
+    pub fn secp256k1_compress_program(uncompressed: &[u8; 65]) -> Program {
+        let mut instructions = vec![];
+        let mut point = coordinate_words(&uncompressed[1..33]);
+        point.extend(coordinate_words(&uncompressed[33..]));
+        instructions.extend(store_words(POINT_PTR, point));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SECP256K1_COMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, POINT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Decompresses the point compressed in `compressed` at `POINT_PTR`, the way
+    /// `syscall_secp256k1_decompress` does.
+    fn secp256k1_decompress_program(compressed: &[u8; COMPRESSED_POINT_SIZE]) -> Program {
+        let is_odd = match compressed[0] {
+            2 => 0,
+            3 => 1,
+            prefix => panic!("invalid prefix {}", prefix),
+        };
+        let mut instructions = store_words(POINT_PTR + 32, coordinate_words(&compressed[1..]));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SECP256K1_DECOMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, POINT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, is_odd, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    fn execute_compress(uncompressed: &[u8; 65]) -> Vec<u8> {
+        let program = secp256k1_compress_program(uncompressed);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let out = (0..NUM_COMPRESSED_WORDS as u32)
+            .map(|i| runtime.word(OUT_PTR + i * 4))
+            .collect::<Vec<_>>();
+        words_to_bytes_le_vec(&out)
+    }
+
+    #[test]
+    fn test_secp256k1_compress_round_trip() {
+        utils::setup_logger();
+        for k in [1, 2, 3, 0xdeadbeef, u64::MAX] {
+            let uncompressed = secp256k1_test_point(k);
+            let out = execute_compress(&uncompressed);
+            let compressed: [u8; COMPRESSED_POINT_SIZE] =
+                out[..COMPRESSED_POINT_SIZE].try_into().unwrap();
+            assert_eq!(out[COMPRESSED_POINT_SIZE..], [0; 3]);
+            let expected = k256::PublicKey::from_sec1_bytes(&uncompressed)
+                .unwrap()
+                .to_sec1_bytes();
+            assert_eq!(compressed[..], expected[..]);
+
+            // Decompressing recovers y.
+            let program = secp256k1_decompress_program(&compressed);
+            let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let y = (0..8)
+                .map(|i| runtime.word(POINT_PTR + i * 4))
+                .collect::<Vec<_>>();
+            assert_eq!(y, coordinate_words(&uncompressed[33..]));
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_compress_prove() {
+        utils::setup_logger();
+        for k in [1, 0xdeadbeef] {
+            run_test(secp256k1_compress_program(&secp256k1_test_point(k))).unwrap();
+        }
+    }
+
+    /// Stores s at `S_PTR` and normalizes it in place.
+    pub fn secp256k1_normalize_s_program(s: &BigUint) -> Program {
+        let mut bytes = s.to_bytes_le();
+        bytes.resize(32, 0);
+        let mut instructions = store_words(S_PTR, bytes_to_words_le_vec(&bytes));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SECP256K1_NORMALIZE_S as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, S_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    fn execute_normalize_s(s: &BigUint) -> BigUint {
+        let mut runtime = Runtime::new(secp256k1_normalize_s_program(s), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..8)
+            .map(|i| runtime.word(S_PTR + i * 4))
+            .collect::<Vec<_>>();
+        BigUint::from_bytes_le(&words_to_bytes_le_vec(&result))
+    }
+
+    #[test]
+    fn test_secp256k1_normalize_s_execute() {
+        utils::setup_logger();
+        let n = Secp256k1ScalarField::modulus();
+        let bound = secp256k1_high_s_bound();
+
+        // High values are replaced with their negation.
+        for s in [bound.clone(), &bound + BigUint::one(), &n - BigUint::one()] {
+            let normalized = execute_normalize_s(&s);
+            assert_eq!(normalized, &n - &s);
+            assert!(normalized < bound);
+        }
+
+        // Low values are unchanged.
+        for s in [
+            BigUint::from(0u32),
+            BigUint::one(),
+            BigUint::from(0xdeadbeefu32) << 128,
+            &bound - BigUint::one(),
+        ] {
+            assert_eq!(execute_normalize_s(&s), s);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_secp256k1_normalize_s_unreduced() {
+        utils::setup_logger();
+        execute_normalize_s(&Secp256k1ScalarField::modulus());
+    }
+
+    #[test]
+    fn test_secp256k1_normalize_s_prove() {
+        utils::setup_logger();
+        let n = Secp256k1ScalarField::modulus();
+        let bound = secp256k1_high_s_bound();
+        for s in [
+            &n - BigUint::from(12345u32),
+            bound.clone(),
+            &bound - BigUint::one(),
+            BigUint::from(0xdeadbeefu32),
+        ] {
+            run_test(secp256k1_normalize_s_program(&s)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{value_as_limbs, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::field::range::FieldRangeCols;
+use crate::runtime::{ExecutionRecord, MemoryWriteRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::weierstrass::secp256k1::Secp256k1ScalarField;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_prev_access, pad_rows, words_to_bytes_le, words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::{BigUint, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Secp256k1NormalizeSCols.
+const NUM_COLS: usize = size_of::<Secp256k1NormalizeSCols<u8>>();
+
+type WordsFieldElement = <Secp256k1ScalarField as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+type NumScalarLimbs = <Secp256k1ScalarField as NumLimbs>::Limbs;
+const NUM_LIMBS: usize = NumScalarLimbs::USIZE;
+
+/// Returns `(n + 1) / 2`, the smallest value of `s` that is not low, where `n` is the order of
+/// the secp256k1 curve.
+pub fn secp256k1_high_s_bound() -> BigUint {
+    (Secp256k1ScalarField::modulus() + BigUint::one()) >> 1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1NormalizeSEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub s_ptr: u32,
+    pub s: Vec<u32>,
+    pub s_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile normalizing the `s` value of a secp256k1 signature to the lower half of the curve
+/// order, replacing it with `n - s` if `s > n / 2`.
+///
+/// The value `s` is range checked to be below `n`. Whether it is high is constrained by comparing
+/// it byte by byte against the bound `(n + 1) / 2`, and the conditional subtraction selects between
+/// `s` and the field negation `n - s` with the result of the comparison.
+#[derive(Default)]
+pub struct Secp256k1NormalizeSChip;
+
+impl Secp256k1NormalizeSChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Secp256k1NormalizeS operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Secp256k1NormalizeSCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to s, which is overwritten with the normalized value.
+    pub s_ptr: T,
+
+    /// The memory columns of s, which is written to with the result.
+    pub s_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    /// Columns for checking that s is below the curve order.
+    pub s_range: FieldRangeCols<T, Secp256k1ScalarField>,
+
+    /// Whether s is at least the bound `(n + 1) / 2`.
+    pub is_high: T,
+
+    /// Boolean flags marking the most significant byte in which s differs from the bound. They are
+    /// all zero when s is equal to the bound.
+    pub byte_flags: Limbs<T, NumScalarLimbs>,
+
+    /// The smaller of the two differing bytes.
+    pub comparison_lhs: T,
+
+    /// The larger of the two differing bytes.
+    pub comparison_rhs: T,
+
+    /// The negation `n - s`.
+    pub negation: FieldOpCols<T, Secp256k1ScalarField>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Secp256k1NormalizeSChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Secp256k1NormalizeS".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let bound = Secp256k1ScalarField::to_limbs(&secp256k1_high_s_bound());
+
+        let mut rows = input
+            .secp256k1_normalize_s_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Secp256k1NormalizeSCols<F> = row.as_mut_slice().borrow_mut();
+
+                let s = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.s));
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.s_ptr = F::from_canonical_u32(event.s_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.s_memory[i].populate(
+                        event.channel,
+                        event.s_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.s_range.populate(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &s,
+                );
+
+                // Populate the comparison of s against the bound.
+                let s_bytes = Secp256k1ScalarField::to_limbs(&s);
+                let mut is_high = true;
+                for i in (0..NUM_LIMBS).rev() {
+                    if s_bytes[i] != bound[i] {
+                        is_high = s_bytes[i] > bound[i];
+                        let (lhs, rhs) = if is_high {
+                            (bound[i], s_bytes[i])
+                        } else {
+                            (s_bytes[i], bound[i])
+                        };
+                        cols.byte_flags[i] = F::one();
+                        cols.comparison_lhs = F::from_canonical_u8(lhs);
+                        cols.comparison_rhs = F::from_canonical_u8(rhs);
+                        new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                            opcode: ByteOpcode::LTU,
+                            shard: event.shard,
+                            channel: event.channel,
+                            a1: 1,
+                            a2: 0,
+                            b: lhs as u32,
+                            c: rhs as u32,
+                        });
+                        break;
+                    }
+                }
+                cols.is_high = F::from_bool(is_high);
+
+                // Populate the negation.
+                cols.negation.populate(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &BigUint::zero(),
+                    &s,
+                    FieldOperation::Sub,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut Secp256k1NormalizeSCols<F> = row.as_mut_slice().borrow_mut();
+
+            let zero = BigUint::zero();
+            cols.s_range.populate(&mut vec![], 0, 0, &zero);
+            cols.negation
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Sub);
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Secp256k1NormalizeSCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.secp256k1_normalize_s_events.is_empty()
+    }
+}
+
+impl Syscall for Secp256k1NormalizeSChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, _: u32) -> Option<u32> {
+        let s_ptr = arg1;
+        if s_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read s with slice_unsafe since it is overwritten with the result.
+        let s = rt.slice_unsafe(s_ptr, WORDS_FIELD_ELEMENT);
+        let uint256_s = BigUint::from_bytes_le(&words_to_bytes_le_vec(&s));
+        let modulus = Secp256k1ScalarField::modulus();
+        if uint256_s >= modulus {
+            panic!("secp256k1_normalize_s input is not reduced modulo the curve order");
+        }
+
+        let result = if uint256_s >= secp256k1_high_s_bound() {
+            modulus - uint256_s
+        } else {
+            uint256_s
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8);
+        let result = bytes_to_words_le::<8>(&result_bytes);
+
+        // Write the result to s and keep track of the memory records.
+        let s_memory_records = rt.mw_slice(s_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .secp256k1_normalize_s_events
+            .push(Secp256k1NormalizeSEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                s_ptr,
+                s,
+                s_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Secp256k1NormalizeSChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Secp256k1NormalizeSChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, NumScalarLimbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Secp256k1NormalizeSCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Secp256k1NormalizeSCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The value of s is stored in the "prev_value" of the s_memory, since we write to it later.
+        let s_limbs: Limbs<AB::Var, NumScalarLimbs> = limbs_from_prev_access(&local.s_memory);
+
+        // Check that s is below the curve order.
+        local.s_range.eval(
+            builder,
+            &s_limbs,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Compare s against the bound. The bytes above the flagged byte are equal, and the flagged
+        // byte of s is above the bound if s is high and below it otherwise. With no flag set s is
+        // equal to the bound, so it is high.
+        let bound = Secp256k1ScalarField::to_limbs(&secp256k1_high_s_bound());
+        builder.assert_bool(local.is_high);
+        let mut sum_flags = AB::Expr::zero();
+        let mut is_inequality_visited = AB::Expr::zero();
+        let mut s_comparison_byte = AB::Expr::zero();
+        let mut bound_comparison_byte = AB::Expr::zero();
+        for i in (0..NUM_LIMBS).rev() {
+            let flag = local.byte_flags[i];
+            let bound_byte = AB::F::from_canonical_u8(bound[i]);
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            is_inequality_visited += flag.into();
+            s_comparison_byte += s_limbs[i] * flag;
+            bound_comparison_byte += flag * bound_byte;
+
+            builder
+                .when(local.is_real)
+                .when_not(is_inequality_visited.clone())
+                .assert_eq(s_limbs[i], bound_byte);
+        }
+        builder.assert_bool(sum_flags.clone());
+        builder
+            .when(local.is_real)
+            .when_not(sum_flags.clone())
+            .assert_one(local.is_high);
+        builder
+            .when(local.is_high)
+            .assert_eq(local.comparison_lhs, bound_comparison_byte.clone());
+        builder
+            .when(local.is_high)
+            .assert_eq(local.comparison_rhs, s_comparison_byte.clone());
+        builder
+            .when_not(local.is_high)
+            .assert_eq(local.comparison_lhs, s_comparison_byte);
+        builder
+            .when_not(local.is_high)
+            .assert_eq(local.comparison_rhs, bound_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.comparison_lhs,
+            local.comparison_rhs,
+            local.shard,
+            local.channel,
+            sum_flags,
+        );
+
+        // Evaluate the negation n - s. Since s is nonzero when it is high, the result is the only
+        // value below 2^256 congruent to -s.
+        let zero: Polynomial<AB::Expr> = Polynomial::from_coefficients(&[AB::Expr::zero()]);
+        local.negation.eval(
+            builder,
+            &zero,
+            &s_limbs,
+            FieldOperation::Sub,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that s is replaced with its negation if it is high, and is unchanged otherwise.
+        let result_limbs = value_as_limbs(&local.s_memory);
+        for i in 0..NUM_LIMBS {
+            let result = s_limbs[i] + local.is_high * (local.negation.result[i] - s_limbs[i]);
+            builder
+                .when(local.is_real)
+                .assert_eq(result_limbs[i], result);
+        }
+
+        // Read and write s.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.s_ptr,
+            &local.s_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::SECP256K1_NORMALIZE_S.syscall_id()),
+            local.s_ptr,
+            AB::Expr::zero(),
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
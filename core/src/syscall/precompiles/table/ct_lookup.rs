@@ -0,0 +1,389 @@
+use crate::air::{MachineAir, SP1AirBuilder, Word, WordAirBuilder, WORD_SIZE};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the CtLookupCols.
+const NUM_COLS: usize = size_of::<CtLookupCols<u8>>();
+
+/// The number of words of the header, which contains the table pointer, the number of entries and
+/// the index.
+pub const CT_LOOKUP_HEADER_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CtLookupEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub out_ptr: u32,
+    pub header_ptr: u32,
+    pub table_ptr: u32,
+    /// The number of entries of the table.
+    pub len: u32,
+    pub index: u32,
+    pub header_memory_records: Vec<MemoryReadRecord>,
+    pub table_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_record: MemoryWriteRecord,
+}
+
+/// A precompile reading the entry at an index of a table of words in memory, with an access
+/// pattern which does not depend on the index.
+///
+/// Each row reads one entry of the table, so a call reads every entry and its events only depend
+/// on the number of entries. The row of the index is selected, and its entry is written to the
+/// output a cycle after the table is read. An index which is not below the number of entries
+/// panics, since no row could be selected.
+#[derive(Default)]
+pub struct CtLookupChip;
+
+impl CtLookupChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one entry of the CtLookup operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct CtLookupCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the output.
+    pub out_ptr: T,
+
+    /// The pointer to the header, which contains the table pointer, the number of entries and the
+    /// index.
+    pub header_ptr: T,
+
+    /// The pointer to the table.
+    pub table_ptr: T,
+
+    /// The number of entries.
+    pub len: T,
+
+    /// The index which is looked up.
+    pub lookup_index: T,
+
+    /// The index of the entry of this row.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// Whether the entry of this row is the one looked up.
+    pub is_selected: T,
+
+    /// The number of selected rows of the call up to and including this row.
+    pub num_selected: T,
+
+    /// The entry looked up, which is carried over all the rows of the call.
+    pub value: Word<T>,
+
+    /// The header, which is only read on the first row of the call.
+    pub header_memory: [MemoryReadCols<T>; CT_LOOKUP_HEADER_WORDS],
+
+    /// The entry of this row.
+    pub table_memory: MemoryReadCols<T>,
+
+    /// The output, which is only written on the first row of the call.
+    pub out_memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for CtLookupChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "CtLookup".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.ct_lookup_events.iter() {
+            let len = event.len as usize;
+            let value = event.table_memory_records[event.index as usize].value;
+            for i in 0..len {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut CtLookupCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.header_ptr = F::from_canonical_u32(event.header_ptr);
+                cols.table_ptr = F::from_canonical_u32(event.table_ptr);
+                cols.len = F::from_canonical_u32(event.len);
+                cols.lookup_index = F::from_canonical_u32(event.index);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == len - 1);
+                cols.is_selected = F::from_bool(i == event.index as usize);
+                cols.num_selected = F::from_bool(i >= event.index as usize);
+                cols.value = Word::from(value);
+
+                // Populate memory columns. The header is read and the output is written on the
+                // first row.
+                if i == 0 {
+                    for j in 0..CT_LOOKUP_HEADER_WORDS {
+                        cols.header_memory[j].populate(
+                            event.channel,
+                            event.header_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                    cols.out_memory.populate(
+                        event.channel,
+                        event.out_memory_record,
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.table_memory.populate(
+                    event.channel,
+                    event.table_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut CtLookupCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.ct_lookup_events.is_empty()
+    }
+}
+
+impl Syscall for CtLookupChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let out_ptr = arg1;
+        let header_ptr = arg2;
+        if out_ptr % 4 != 0 || header_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the header, which contains the table pointer, the number of entries and the index.
+        let (header_memory_records, header) = rt.mr_slice(header_ptr, CT_LOOKUP_HEADER_WORDS);
+        let table_ptr = header[0];
+        let len = header[1];
+        let index = header[2];
+        if table_ptr % 4 != 0 {
+            panic!();
+        }
+        if index >= len {
+            panic!("ct lookup index {} is out of range for {} entries", index, len);
+        }
+
+        // Read every entry, whatever the index, and write the looked up one a cycle later.
+        let (table_memory_records, table) = rt.mr_slice(table_ptr, len as usize);
+        rt.clk += 1;
+        let out_memory_record = rt.mw(out_ptr, table[index as usize]);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().ct_lookup_events.push(CtLookupEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk: start_clk,
+            out_ptr,
+            header_ptr,
+            table_ptr,
+            len,
+            index,
+            header_memory_records,
+            table_memory_records,
+            out_memory_record,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for CtLookupChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for CtLookupChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &CtLookupCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &CtLookupCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued, next_is_continuation.clone());
+
+        // The header is read on the first row, and its values are carried over to the next rows.
+        let header_table_ptr = local.header_memory[0].value().reduce::<AB>();
+        let header_len = local.header_memory[1].value().reduce::<AB>();
+        let header_index = local.header_memory[2].value().reduce::<AB>();
+        let mut first = builder.when(local.is_first);
+        first.assert_eq(local.table_ptr, header_table_ptr);
+        first.assert_eq(local.len, header_len);
+        first.assert_eq(local.lookup_index, header_index);
+        first.assert_zero(local.index);
+        first.assert_eq(local.num_selected, local.is_selected);
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation);
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.out_ptr, local.out_ptr);
+        continuation.assert_eq(next.header_ptr, local.header_ptr);
+        continuation.assert_eq(next.table_ptr, local.table_ptr);
+        continuation.assert_eq(next.len, local.len);
+        continuation.assert_eq(next.lookup_index, local.lookup_index);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+        continuation.assert_eq(next.num_selected, local.num_selected + next.is_selected);
+        continuation.assert_word_eq(next.value, local.value);
+        builder
+            .when(local.is_last)
+            .assert_eq(local.index + AB::Expr::one(), local.len);
+
+        // Exactly one row of the call is selected, the one whose index is the looked up index, and
+        // its entry is the looked up value. Since the indices of the rows are distinct, the index
+        // is below the number of entries.
+        builder.assert_bool(local.is_selected);
+        builder.when(local.is_selected).assert_one(local.is_real);
+        builder
+            .when(local.is_selected)
+            .assert_eq(local.index, local.lookup_index);
+        builder
+            .when(local.is_selected)
+            .assert_word_eq(*local.table_memory.value(), local.value);
+        builder.when(local.is_last).assert_one(local.num_selected);
+
+        // Assert that the output is written with the looked up value.
+        builder
+            .when(local.is_first)
+            .assert_word_eq(*local.out_memory.value(), local.value);
+
+        // Read the header on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.header_ptr,
+            &local.header_memory,
+            local.is_first,
+        );
+
+        // Read the entry of this row, and write the output a cycle later on the first row.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.table_ptr + local.index * AB::F::from_canonical_usize(WORD_SIZE),
+            &local.table_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            local.out_ptr,
+            &local.out_memory,
+            local.is_first,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::CT_LOOKUP.syscall_id()),
+            local.out_ptr,
+            local.header_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::memory::{MemoryCols, MemoryWriteCols};
+use crate::runtime::MemoryWriteRecord;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+
+/// The number of columns in the TableLookupCols.
+const NUM_COLS: usize = size_of::<TableLookupCols<u8>>();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableLookupEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub table_id: u32,
+    pub ptr: u32,
+    pub key: u32,
+    pub value: u32,
+    pub memory_record: MemoryWriteRecord,
+}
+
+/// A precompile for looking up a key in one of the lookup tables of the program.
+///
+/// The key is read from memory and overwritten with its value, which is sent to the
+/// [`LookupTableChip`](super::LookupTableChip) that holds the committed entries of the table.
+#[derive(Default)]
+pub struct TableLookupChip;
+
+impl TableLookupChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the TableLookup operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct TableLookupCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The id of the table, passed by value in the first argument.
+    pub table_id: T,
+
+    /// The pointer to the key, which is overwritten with the value.
+    pub ptr: T,
+
+    pub memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for TableLookupChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "TableLookup".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .table_lookup_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut TableLookupCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.table_id = F::from_canonical_u32(event.table_id);
+                cols.ptr = F::from_canonical_u32(event.ptr);
+
+                // Populate memory columns.
+                cols.memory.populate(
+                    event.channel,
+                    event.memory_record,
+                    &mut new_byte_lookup_events,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut TableLookupCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.table_lookup_events.is_empty()
+    }
+}
+
+impl Syscall for TableLookupChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let table_id = arg1;
+        let ptr = arg2;
+        rt.require_aligned(ptr, self.alignment()).unwrap();
+
+        let key = rt.word_unsafe(ptr);
+        let value = *rt
+            .rt
+            .program
+            .lookup_tables
+            .get(&table_id)
+            .unwrap_or_else(|| panic!("the program has no lookup table {}", table_id))
+            .get(&key)
+            .unwrap_or_else(|| panic!("key {} is not in lookup table {}", key, table_id));
+        let memory_record = rt.mw(ptr, value);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().table_lookup_events.push(TableLookupEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            table_id,
+            ptr,
+            key,
+            value,
+            memory_record,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for TableLookupChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for TableLookupChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &TableLookupCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &TableLookupCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The key is the previous value of the word, and the value is written over it. The table
+        // only holds entries whose key and value are made of bytes.
+        builder.send_table_lookup(
+            local.table_id,
+            *local.memory.prev_value(),
+            *local.memory.value(),
+            local.is_real,
+        );
+
+        // Read the key and write the value.
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.ptr,
+            &local.memory,
+            local.is_real,
+        );
+
+        // Receive the arguments. The first argument is the table id itself.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::TABLE_LOOKUP.syscall_id()),
+            local.table_id,
+            local.ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
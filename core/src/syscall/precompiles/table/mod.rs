@@ -0,0 +1,125 @@
+mod ct_lookup;
+mod lookup;
+mod preprocessed;
+
+pub use ct_lookup::*;
+pub use lookup::*;
+pub use preprocessed::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::stark::MachineRecord;
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const TABLE_ID: u32 = 7;
+    const PTR: u32 = 0x1000;
+
+    /// The 4-bit S-box of PRESENT, as a small custom table.
+    const SBOX: [u32; 16] = [
+        0xc, 0x5, 0x6, 0xb, 0x9, 0x0, 0xa, 0xd, 0x3, 0xe, 0xf, 0x8, 0x4, 0x7, 0x1, 0x2,
+    ];
+
+    /// Looks up each key in the S-box table, storing its value at `PTR + 4 * i`.
+    fn table_lookup_program(keys: &[u32]) -> Program {
+        let mut instructions = vec![];
+        for (i, &key) in keys.iter().enumerate() {
+            let ptr = PTR + i as u32 * 4;
+            instructions.extend(store_words(ptr, [key]));
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::TABLE_LOOKUP as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, TABLE_ID, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, ptr, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+            .with_lookup_table(TABLE_ID, (0..16).map(|key| (key, SBOX[key as usize])))
+    }
+
+    #[test]
+    fn test_table_lookup_execute() {
+        utils::setup_logger();
+        let keys = [3, 0, 15, 3, 9];
+        let mut runtime = Runtime::new(table_lookup_program(&keys), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        for (i, &key) in keys.iter().enumerate() {
+            assert_eq!(runtime.word(PTR + i as u32 * 4), SBOX[key as usize]);
+        }
+        assert_eq!(runtime.record.table_lookup_events.len(), keys.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "key 16 is not in lookup table 7")]
+    fn test_table_lookup_missing_key() {
+        let mut runtime = Runtime::new(table_lookup_program(&[16]), SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_table_lookup_prove() {
+        utils::setup_logger();
+        run_test(table_lookup_program(&[3, 0, 15, 3, 9])).unwrap();
+    }
+
+    const TABLE_PTR: u32 = 0x2000;
+    const HEADER_PTR: u32 = 0x3000;
+    const OUT_PTR: u32 = 0x3100;
+
+    /// Stores the S-box in memory, and reads its entry at `index` to `OUT_PTR` with `CT_LOOKUP`.
+    fn ct_lookup_program(index: u32) -> Program {
+        let mut instructions = store_words(TABLE_PTR, SBOX);
+        instructions.extend(store_words(
+            HEADER_PTR,
+            [TABLE_PTR, SBOX.len() as u32, index],
+        ));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::CT_LOOKUP as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, HEADER_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_ct_lookup_execute() {
+        utils::setup_logger();
+        for index in [0, 5, 15] {
+            let mut runtime = Runtime::new(ct_lookup_program(index), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            assert_eq!(runtime.word(OUT_PTR), SBOX[index as usize]);
+        }
+    }
+
+    #[test]
+    fn test_ct_lookup_event_counts() {
+        // Every entry is read whatever the index, so the events do not depend on it.
+        let records = [0, 7, 15].map(|index| {
+            let mut runtime = Runtime::new(ct_lookup_program(index), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            runtime.record
+        });
+        for record in records.iter() {
+            assert_eq!(record.stats(), records[0].stats());
+            assert_eq!(record.event_counts(), records[0].event_counts());
+            let event = &record.ct_lookup_events[0];
+            assert_eq!(event.table_memory_records.len(), SBOX.len());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ct lookup index 16 is out of range for 16 entries")]
+    fn test_ct_lookup_out_of_range() {
+        let mut runtime = Runtime::new(ct_lookup_program(16), SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_ct_lookup_prove() {
+        utils::setup_logger();
+        run_test(ct_lookup_program(9)).unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use std::collections::HashMap;
+
+use p3_air::{Air, BaseAir, PairBuilder};
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use sp1_derive::AlignedBorrow;
+
+use crate::air::{MachineAir, SP1AirBuilder, Word};
+use crate::runtime::{ExecutionRecord, Program};
+use crate::utils::pad_to_power_of_two;
+
+pub const NUM_LOOKUP_TABLE_PREPROCESSED_COLS: usize = size_of::<LookupTablePreprocessedCols<u8>>();
+pub const NUM_LOOKUP_TABLE_MULT_COLS: usize = size_of::<LookupTableMultCols<u8>>();
+
+/// The column layout of the entries of the tables.
+#[derive(AlignedBorrow, Clone, Copy, Default)]
+#[repr(C)]
+pub struct LookupTablePreprocessedCols<T> {
+    pub table_id: T,
+    pub key: Word<T>,
+    pub value: Word<T>,
+
+    /// Whether the row is an entry of a table, as opposed to padding.
+    pub is_entry: T,
+}
+
+/// Multiplicity columns.
+#[derive(AlignedBorrow, Clone, Copy, Default)]
+#[repr(C)]
+pub struct LookupTableMultCols<T> {
+    /// The number of times the entry is looked up in the shard.
+    pub multiplicity: T,
+}
+
+/// Chip that holds the entries of the lookup tables of the program in a preprocessed trace, and
+/// receives the lookups sent by the [`TableLookupChip`](super::TableLookupChip).
+///
+/// Since the entries are committed to in the verifying key, a lookup can only return the value of
+/// its key in the table.
+#[derive(Default)]
+pub struct LookupTableChip;
+
+impl LookupTableChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+
+    /// The `(table_id, key, value)` entries of the tables, in the order of the rows.
+    fn entries(program: &Program) -> impl Iterator<Item = (u32, u32, u32)> + '_ {
+        program.lookup_tables.iter().flat_map(|(&table_id, table)| {
+            table
+                .iter()
+                .map(move |(&key, &value)| (table_id, key, value))
+        })
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for LookupTableChip {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "LookupTable".to_string()
+    }
+
+    fn preprocessed_width(&self) -> usize {
+        NUM_LOOKUP_TABLE_PREPROCESSED_COLS
+    }
+
+    fn generate_preprocessed_trace(&self, program: &Self::Program) -> Option<RowMajorMatrix<F>> {
+        let rows = Self::entries(program)
+            .map(|(table_id, key, value)| {
+                let mut row = [F::zero(); NUM_LOOKUP_TABLE_PREPROCESSED_COLS];
+                let cols: &mut LookupTablePreprocessedCols<F> = row.as_mut_slice().borrow_mut();
+                cols.table_id = F::from_canonical_u32(table_id);
+                cols.key = Word::from(key);
+                cols.value = Word::from(value);
+                cols.is_entry = F::one();
+                row
+            })
+            .collect::<Vec<_>>();
+
+        // Convert the trace to a row major matrix.
+        let mut trace = RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_LOOKUP_TABLE_PREPROCESSED_COLS,
+        );
+
+        // Pad the trace to a power of two.
+        pad_to_power_of_two::<NUM_LOOKUP_TABLE_PREPROCESSED_COLS, F>(&mut trace.values);
+
+        Some(trace)
+    }
+
+    fn generate_dependencies(&self, _input: &ExecutionRecord, _output: &mut ExecutionRecord) {
+        // Do nothing since this chip has no dependencies.
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        _output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let rows_by_entry = Self::entries(&input.program)
+            .enumerate()
+            .map(|(row, (table_id, key, _))| ((table_id, key), row))
+            .collect::<HashMap<_, _>>();
+
+        let mut values = vec![F::zero(); rows_by_entry.len() * NUM_LOOKUP_TABLE_MULT_COLS];
+        pad_to_power_of_two::<NUM_LOOKUP_TABLE_MULT_COLS, F>(&mut values);
+        let mut trace = RowMajorMatrix::new(values, NUM_LOOKUP_TABLE_MULT_COLS);
+
+        for event in input.table_lookup_events.iter() {
+            let row = rows_by_entry[&(event.table_id, event.key)];
+            let cols: &mut LookupTableMultCols<F> = trace.row_mut(row).borrow_mut();
+            cols.multiplicity += F::one();
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.table_lookup_events.is_empty()
+    }
+}
+
+impl<F> BaseAir<F> for LookupTableChip {
+    fn width(&self) -> usize {
+        NUM_LOOKUP_TABLE_MULT_COLS
+    }
+}
+
+impl<AB> Air<AB> for LookupTableChip
+where
+    AB: SP1AirBuilder + PairBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+
+        let prep_local = preprocessed.row_slice(0);
+        let prep_local: &LookupTablePreprocessedCols<AB::Var> = (*prep_local).borrow();
+
+        let mult_local = main.row_slice(0);
+        let mult_local: &LookupTableMultCols<AB::Var> = (*mult_local).borrow();
+
+        // Padding rows are not entries of any table, so nothing can be looked up in them.
+        builder
+            .when_not(prep_local.is_entry)
+            .assert_zero(mult_local.multiplicity);
+
+        builder.receive_table_lookup(
+            prep_local.table_id,
+            prep_local.key,
+            prep_local.value,
+            mult_local.multiplicity,
+        );
+    }
+}
@@ -1,13 +1,17 @@
-use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::air::{
+    BaseAirBuilder, MachineAir, PackedTraceAir, Polynomial, SP1AirBuilder, WORD_SIZE,
+};
 use crate::bytes::event::ByteRecord;
 use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
 use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
 use crate::operations::field::params::NumWords;
 use crate::operations::field::params::{Limbs, NumLimbs};
-use crate::operations::IsZeroOperation;
+use crate::operations::{DisjointRangesOperation, IsZeroOperation};
 use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
 use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
-use crate::stark::MachineRecord;
+#[cfg(feature = "debug")]
+use crate::stark::debug_trace_constraints;
+use crate::stark::{log_sampled_rows, trace_sample_interval, MachineRecord};
 use crate::syscall::precompiles::SyscallContext;
 use crate::utils::ec::uint256::U256Field;
 use crate::utils::{
@@ -32,6 +36,11 @@ use typenum::Unsigned;
 /// The number of columns in the Uint256MulCols.
 const NUM_COLS: usize = size_of::<Uint256MulCols<u8>>();
 
+/// The number of bytes of x, and of y followed by the modulus. The two regions must not overlap
+/// unless the pointers are equal, which squares x.
+const X_BYTES: u32 = 32;
+const Y_BYTES: u32 = 64;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Uint256MulEvent {
     pub lookup_id: usize,
@@ -88,6 +97,9 @@ pub struct Uint256MulCols<T> {
     pub y_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
     pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
 
+    // Columns for checking that x does not overlap y and the modulus, unless x_ptr == y_ptr.
+    pub ptrs_disjoint: DisjointRangesOperation<T>,
+
     // Columns for checking if modulus is zero. If it's zero, then use 2^256 as the effective modulus.
     pub modulus_is_zero: IsZeroOperation<T>,
 
@@ -97,6 +109,8 @@ pub struct Uint256MulCols<T> {
     pub is_real: T,
 }
 
+impl<F: PrimeField32> PackedTraceAir<F> for Uint256MulChip {}
+
 impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
     type Record = ExecutionRecord;
     type Program = Program;
@@ -137,6 +151,21 @@ impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
                         cols.clk = F::from_canonical_u32(event.clk);
                         cols.x_ptr = F::from_canonical_u32(event.x_ptr);
                         cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+                        let disjoint = cols.ptrs_disjoint.populate(
+                            &mut new_byte_lookup_events,
+                            event.shard,
+                            event.channel,
+                            event.x_ptr,
+                            X_BYTES,
+                            event.y_ptr,
+                            Y_BYTES,
+                            true,
+                        );
+                        assert!(
+                            disjoint,
+                            "uint256_mul operands at {:#x} and {:#x} overlap or are too far apart",
+                            event.x_ptr, event.y_ptr
+                        );
 
                         // Populate memory columns.
                         for i in 0..WORDS_FIELD_ELEMENT {
@@ -216,6 +245,20 @@ impl<F: PrimeField32> MachineAir<F> for Uint256MulChip {
             cols.nonce = F::from_canonical_usize(i);
         }
 
+        // Check the populated rows against the constraints when debugging.
+        #[cfg(feature = "debug")]
+        debug_trace_constraints(self, &trace);
+
+        // Log a sample of the real rows if requested.
+        if let Some(interval) = trace_sample_interval() {
+            log_sampled_rows::<F, Uint256MulCols<F>>(
+                &MachineAir::<F>::name(self),
+                &trace,
+                input.uint256_mul_events.len(),
+                interval,
+            );
+        }
+
         trace
     }
 
@@ -229,16 +272,31 @@ impl Syscall for Uint256MulChip {
         0
     }
 
+    fn alignment(&self) -> u32 {
+        4
+    }
+
     fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
         let x_ptr = arg1;
-        if x_ptr % 4 != 0 {
-            panic!();
-        }
         let y_ptr = arg2;
-        if y_ptr % 4 != 0 {
-            panic!();
+        let checked = rt
+            .require_aligned(x_ptr, self.alignment())
+            .and_then(|_| rt.require_aligned(y_ptr, self.alignment()))
+            .and_then(|_| {
+                // The operands may alias exactly, in which case x is squared.
+                if x_ptr == y_ptr {
+                    Ok(())
+                } else {
+                    rt.require_disjoint(x_ptr, X_BYTES, y_ptr, Y_BYTES)
+                }
+            });
+        if let Err(err) = checked {
+            rt.set_error(err);
+            return None;
         }
 
+        let start_clk = rt.clk;
+
         // First read the words for the x value. We can read a slice_unsafe here because we write
         // the computed result to x later.
         let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
@@ -269,18 +327,19 @@ impl Syscall for Uint256MulChip {
         // Convert the result to little endian u32 words.
         let result = bytes_to_words_le::<8>(&result_bytes);
 
-        // Write the result to x and keep track of the memory records.
+        // Write the result to x a cycle later, since x and y could be the same, and keep track of
+        // the memory records.
+        rt.clk += 1;
         let x_memory_records = rt.mw_slice(x_ptr, &result);
 
         let lookup_id = rt.syscall_lookup_id;
         let shard = rt.current_shard();
         let channel = rt.current_channel();
-        let clk = rt.clk;
         rt.record_mut().uint256_mul_events.push(Uint256MulEvent {
             lookup_id,
             shard,
             channel,
-            clk,
+            clk: start_clk,
             x_ptr,
             x,
             y_ptr,
@@ -367,11 +426,25 @@ where
             .when(local.is_real)
             .assert_all_eq(local.output.result, value_as_limbs(&local.x_memory));
 
-        // Read and write x.
+        // Assert that x does not overlap y and the modulus, unless they are the same operand.
+        DisjointRangesOperation::<AB::F>::eval(
+            builder,
+            local.x_ptr.into(),
+            X_BYTES,
+            local.y_ptr.into(),
+            Y_BYTES,
+            true,
+            local.ptrs_disjoint,
+            local.shard,
+            local.channel,
+            local.is_real.into(),
+        );
+
+        // Read and write x, a cycle after y is read since x and y could be the same.
         builder.eval_memory_access_slice(
             local.shard,
             local.channel,
-            local.clk.into(),
+            local.clk + AB::F::from_canonical_u32(1),
             local.x_ptr,
             &local.x_memory,
             local.is_real,
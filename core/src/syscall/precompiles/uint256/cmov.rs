@@ -0,0 +1,297 @@
+use crate::air::{MachineAir, SP1AirBuilder, Word, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::pad_rows;
+use generic_array::GenericArray;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256CmovCols.
+const NUM_COLS: usize = size_of::<Uint256CmovCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256CmovEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub dst_ptr: u32,
+    pub cond: u32,
+    pub dst: Vec<u32>,
+    pub src: Vec<u32>,
+    pub dst_memory_records: Vec<MemoryWriteRecord>,
+    pub src_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A precompile for a constant-time conditional move of a uint256.
+///
+/// The source is copied over the destination if `cond` is nonzero. Every call reads the source and
+/// writes the destination, and emits the same events, whatever the value of `cond`, so the
+/// condition is not revealed by the shape of the execution trace.
+#[derive(Default)]
+pub struct Uint256CmovChip;
+
+impl Uint256CmovChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Uint256Cmov operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256CmovCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the input, which contains the destination followed by the source.
+    pub dst_ptr: T,
+
+    /// The condition, passed by value in the second argument.
+    pub cond: Word<T>,
+
+    /// Whether the condition is zero, in which case the destination is left unchanged.
+    pub cond_is_zero: IsZeroOperation<T>,
+
+    // Memory columns.
+    // dst_memory is written to with the result, which is why it is of type MemoryWriteCols.
+    pub dst_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub src_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256CmovChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256Cmov".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .uint256_cmov_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256CmovCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.dst_ptr = F::from_canonical_u32(event.dst_ptr);
+
+                // Populate and range check the bytes of the condition.
+                let cond_bytes = event.cond.to_le_bytes();
+                cols.cond = Word::from(event.cond);
+                new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, &cond_bytes);
+                let cond_byte_sum = cond_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.cond_is_zero, cond_byte_sum);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.dst_memory[i].populate(
+                        event.channel,
+                        event.dst_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.src_memory[i].populate(
+                        event.channel,
+                        event.src_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256CmovCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_cmov_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256CmovChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let dst_ptr = arg1;
+        if dst_ptr % 4 != 0 {
+            panic!();
+        }
+        let cond = arg2;
+
+        // First read the words for the destination. We can read a slice_unsafe here because we
+        // write the selected value to the destination later.
+        let dst = rt.slice_unsafe(dst_ptr, WORDS_FIELD_ELEMENT);
+
+        // The source is stored after the destination. It is read whatever the condition is.
+        let src_ptr = dst_ptr + WORDS_FIELD_ELEMENT as u32 * WORD_SIZE as u32;
+        let (src_memory_records, src) = rt.mr_slice(src_ptr, WORDS_FIELD_ELEMENT);
+
+        // The destination is always written, with its own value if the condition is zero.
+        let result = if cond != 0 { &src } else { &dst };
+        let dst_memory_records = rt.mw_slice(dst_ptr, result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().uint256_cmov_events.push(Uint256CmovEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            dst_ptr,
+            cond,
+            dst,
+            src,
+            dst_memory_records,
+            src_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256CmovChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256CmovChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256CmovCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256CmovCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // The condition is zero exactly when the sum of its range checked bytes is zero.
+        builder.slice_range_check_u8(&local.cond.0, local.shard, local.channel, local.is_real);
+        let cond_byte_sum = local
+            .cond
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &byte| acc + byte);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            cond_byte_sum,
+            local.cond_is_zero,
+            local.is_real.into(),
+        );
+        let is_move = AB::Expr::one() - local.cond_is_zero.result;
+
+        // Each byte of the destination becomes the source byte if the condition is nonzero, and
+        // keeps its previous value otherwise.
+        for (dst, src) in local.dst_memory.iter().zip(local.src_memory.iter()) {
+            for i in 0..WORD_SIZE {
+                let prev = dst.prev_value()[i];
+                builder.when(local.is_real).assert_eq(
+                    dst.value()[i],
+                    prev + is_move.clone() * (src.value()[i] - prev),
+                );
+            }
+        }
+
+        // Read and write the destination.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.dst_ptr,
+            &local.dst_memory,
+            local.is_real,
+        );
+
+        // Read the source, which is stored right after the destination.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.dst_ptr + AB::F::from_canonical_usize(WORDS_FIELD_ELEMENT * WORD_SIZE),
+            &local.src_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments. The second argument is the condition itself.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_CMOV.syscall_id()),
+            local.dst_ptr,
+            local.cond.reduce::<AB>(),
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{pad_rows, words_to_bytes_le};
+use generic_array::GenericArray;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256ToDecimalCols.
+const NUM_COLS: usize = size_of::<Uint256ToDecimalCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The number of decimal digits written by the precompile, which is enough for any uint256.
+pub const NUM_DECIMAL_DIGITS: usize = 80;
+
+/// The number of digits computed on each row, one for each byte of the output word of the row.
+const DIGITS_PER_ROW: usize = WORD_SIZE;
+
+/// The number of rows of each call.
+pub const UINT256_TO_DECIMAL_ROWS: usize = NUM_DECIMAL_DIGITS / DIGITS_PER_ROW;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256ToDecimalEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub out_ptr: u32,
+    pub x: Vec<u32>,
+    /// The ASCII digits, most significant first and padded with leading zeros.
+    pub out: Vec<u32>,
+    pub x_memory_records: Vec<MemoryReadRecord>,
+    pub out_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile writing the ASCII decimal digits of a uint256.
+///
+/// The digits are computed by repeated division by ten. Each division `a = 10 * q + r` takes the
+/// quotient `q` and the digit `r` as advice, and is verified limb by limb with byte carries, along
+/// with `r < 10`. The quotient of a division is the dividend of the next one.
+///
+/// The output is a fixed buffer of `NUM_DECIMAL_DIGITS` digits, most significant first and padded
+/// with leading zeros. The chip has `UINT256_TO_DECIMAL_ROWS` rows per call, and each row computes
+/// the four digits of one output word, starting from the least significant word. The last quotient
+/// of the call must be zero, so that the digits are the whole value.
+#[derive(Default)]
+pub struct Uint256ToDecimalChip;
+
+impl Uint256ToDecimalChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for one division by ten.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct DivTenCols<T> {
+    /// The bytes of the quotient.
+    pub quotient: [T; NUM_LIMBS],
+
+    /// The carries of `10 * quotient + remainder`, limb by limb. The carry out of the most
+    /// significant limb is zero.
+    pub carry: [T; NUM_LIMBS - 1],
+
+    /// The remainder, which is the decimal digit.
+    pub remainder: T,
+}
+
+/// A set of columns for one output word of the Uint256ToDecimal operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256ToDecimalCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the value.
+    pub x_ptr: T,
+
+    /// The pointer to the output digits.
+    pub out_ptr: T,
+
+    /// The index of the row within the call.
+    pub index: T,
+
+    /// Whether this row is the first row of the call.
+    pub is_first: T,
+
+    /// Whether this row is the last row of the call.
+    pub is_last: T,
+
+    /// The value, which is only read on the first row of the call.
+    pub x_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The bytes of the dividend of the first division of the row.
+    pub x: [T; NUM_LIMBS],
+
+    /// The divisions of the row, from the least significant digit.
+    pub steps: [DivTenCols<T>; DIGITS_PER_ROW],
+
+    /// The output word of the row.
+    pub out_memory: MemoryWriteCols<T>,
+
+    pub is_real: T,
+}
+
+/// Divides the little-endian bytes `a` by ten, and returns the quotient, the carries of
+/// `10 * quotient + remainder` and the remainder.
+fn div_ten(a: &[u8; NUM_LIMBS]) -> ([u8; NUM_LIMBS], [u8; NUM_LIMBS - 1], u8) {
+    let mut quotient = [0u8; NUM_LIMBS];
+    let mut remainder = 0u32;
+    for i in (0..NUM_LIMBS).rev() {
+        let current = remainder * 256 + a[i] as u32;
+        quotient[i] = (current / 10) as u8;
+        remainder = current % 10;
+    }
+
+    let mut carry = [0u8; NUM_LIMBS - 1];
+    let mut carry_in = remainder;
+    for i in 0..NUM_LIMBS - 1 {
+        let sum = 10 * quotient[i] as u32 + carry_in;
+        carry[i] = ((sum - a[i] as u32) / 256) as u8;
+        carry_in = carry[i] as u32;
+    }
+    debug_assert_eq!(10 * quotient[NUM_LIMBS - 1] as u32 + carry_in, a[NUM_LIMBS - 1] as u32);
+
+    (quotient, carry, remainder as u8)
+}
+
+/// Returns the ASCII decimal digits of the uint256 `x`, most significant first and padded with
+/// leading zeros to `NUM_DECIMAL_DIGITS` digits.
+pub fn uint256_to_decimal(x: &[u32]) -> [u8; NUM_DECIMAL_DIGITS] {
+    let mut digits = [b'0'; NUM_DECIMAL_DIGITS];
+    let mut value = words_to_bytes_le::<NUM_LIMBS>(x);
+    for digit in digits.iter_mut().rev() {
+        let (quotient, _, remainder) = div_ten(&value);
+        *digit += remainder;
+        value = quotient;
+    }
+    digits
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256ToDecimalChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256ToDecimal".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = Vec::new();
+
+        for event in input.uint256_to_decimal_events.iter() {
+            let mut value = words_to_bytes_le::<NUM_LIMBS>(&event.x);
+
+            for i in 0..UINT256_TO_DECIMAL_ROWS {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256ToDecimalCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+                cols.index = F::from_canonical_usize(i);
+                cols.is_first = F::from_bool(i == 0);
+                cols.is_last = F::from_bool(i == UINT256_TO_DECIMAL_ROWS - 1);
+
+                // Populate memory columns. The value is only read on the first row, and the rows
+                // write the output words from the least significant one.
+                if i == 0 {
+                    for j in 0..WORDS_FIELD_ELEMENT {
+                        cols.x_memory[j].populate(
+                            event.channel,
+                            event.x_memory_records[j],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+                cols.out_memory.populate(
+                    event.channel,
+                    event.out_memory_records[UINT256_TO_DECIMAL_ROWS - 1 - i],
+                    &mut new_byte_lookup_events,
+                );
+
+                // Populate the divisions of the row.
+                cols.x = value.map(F::from_canonical_u8);
+                for step in cols.steps.iter_mut() {
+                    let (quotient, carry, remainder) = div_ten(&value);
+                    step.quotient = quotient.map(F::from_canonical_u8);
+                    step.carry = carry.map(F::from_canonical_u8);
+                    step.remainder = F::from_canonical_u8(remainder);
+                    new_byte_lookup_events.add_u8_range_checks(
+                        event.shard,
+                        event.channel,
+                        &quotient,
+                    );
+                    new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, &carry);
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                        opcode: ByteOpcode::LTU,
+                        shard: event.shard,
+                        channel: event.channel,
+                        a1: 1,
+                        a2: 0,
+                        b: remainder as u32,
+                        c: 10,
+                    });
+                    value = quotient;
+                }
+
+                rows.push(row);
+            }
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256ToDecimalCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_to_decimal_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256ToDecimalChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        let out_ptr = arg2;
+        if x_ptr % 4 != 0 || out_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the value and write its digits.
+        let (x_memory_records, x) = rt.mr_slice(x_ptr, WORDS_FIELD_ELEMENT);
+        let digits = uint256_to_decimal(&x);
+        let out = digits
+            .chunks_exact(WORD_SIZE)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let out_memory_records = rt.mw_slice(out_ptr, &out);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .uint256_to_decimal_events
+            .push(Uint256ToDecimalEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                x_ptr,
+                out_ptr,
+                x,
+                out,
+                x_memory_records,
+                out_memory_records,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256ToDecimalChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256ToDecimalChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256ToDecimalCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256ToDecimalCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Constrain the row flags. Every call starts with a first row and ends with a last row, and
+        // the rows in between are continuations of the same call.
+        builder.assert_bool(local.is_first);
+        builder.assert_bool(local.is_last);
+        builder.when(local.is_first).assert_one(local.is_real);
+        builder.when(local.is_last).assert_one(local.is_real);
+        builder
+            .when_first_row()
+            .assert_eq(local.is_first, local.is_real);
+        builder
+            .when_last_row()
+            .assert_eq(local.is_last, local.is_real);
+        let is_continued = local.is_real - local.is_last;
+        let next_is_continuation = next.is_real - next.is_first;
+        builder
+            .when_transition()
+            .assert_eq(is_continued, next_is_continuation.clone());
+
+        // The index counts the rows of the call, so that every call has exactly
+        // `UINT256_TO_DECIMAL_ROWS` rows.
+        let last_index = AB::Expr::from_canonical_usize(UINT256_TO_DECIMAL_ROWS - 1);
+        builder.when(local.is_first).assert_zero(local.index);
+        builder
+            .when(local.is_last)
+            .assert_eq(local.index, last_index.clone());
+        let mut transition = builder.when_transition();
+        let mut continuation = transition.when(next_is_continuation.clone());
+        continuation.assert_eq(next.shard, local.shard);
+        continuation.assert_eq(next.channel, local.channel);
+        continuation.assert_eq(next.clk, local.clk);
+        continuation.assert_eq(next.x_ptr, local.x_ptr);
+        continuation.assert_eq(next.out_ptr, local.out_ptr);
+        continuation.assert_eq(next.index, local.index + AB::Expr::one());
+
+        // The first dividend is the value read from memory, and the dividend of each next row is
+        // the last quotient of the row before.
+        let x_bytes = local
+            .x_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        builder.when(local.is_first).assert_all_eq(local.x, x_bytes);
+        let last_quotient = &local.steps[DIGITS_PER_ROW - 1].quotient;
+        builder
+            .when_transition()
+            .when(next_is_continuation)
+            .assert_all_eq(next.x, *last_quotient);
+
+        // The value is fully converted when the last quotient of the call is zero.
+        for &byte in last_quotient.iter() {
+            builder.when(local.is_last).assert_zero(byte);
+        }
+
+        // Evaluate the divisions `a = 10 * q + r`. The quotient and the carries are bytes, so the
+        // limb equations can't wrap around the field, and the carry out of the top limb is zero.
+        let mut dividend = local.x;
+        for step in local.steps.iter() {
+            let ten = AB::F::from_canonical_u32(10);
+            let base = AB::F::from_canonical_u32(256);
+            for i in 0..NUM_LIMBS {
+                let carry_in: AB::Expr = if i == 0 {
+                    step.remainder.into()
+                } else {
+                    step.carry[i - 1].into()
+                };
+                let carry_out: AB::Expr = if i == NUM_LIMBS - 1 {
+                    AB::Expr::zero()
+                } else {
+                    step.carry[i] * base
+                };
+                builder.assert_eq(step.quotient[i] * ten + carry_in, dividend[i] + carry_out);
+            }
+            builder.slice_range_check_u8(&step.quotient, local.shard, local.channel, local.is_real);
+            builder.slice_range_check_u8(&step.carry, local.shard, local.channel, local.is_real);
+            builder.send_byte(
+                ByteOpcode::LTU.as_field::<AB::F>(),
+                AB::F::one(),
+                step.remainder,
+                AB::F::from_canonical_u32(10),
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            dividend = step.quotient;
+        }
+
+        // The output word holds the ASCII digits of the row, the least significant one last.
+        let zero = AB::F::from_canonical_u8(b'0');
+        let out_bytes = local.out_memory.value().0;
+        for (byte, step) in out_bytes.into_iter().zip(local.steps.iter().rev()) {
+            builder
+                .when(local.is_real)
+                .assert_eq(byte, step.remainder + zero);
+        }
+
+        // Read the value on the first row of the call.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_first,
+        );
+
+        // Write the output word of this row.
+        let out_index = last_index - local.index;
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.out_ptr + out_index * AB::F::from_canonical_usize(WORD_SIZE),
+            &local.out_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments on the first row of the call.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_TO_DECIMAL.syscall_id()),
+            local.x_ptr,
+            local.out_ptr,
+            local.is_first,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
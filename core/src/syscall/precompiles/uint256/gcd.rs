@@ -0,0 +1,415 @@
+use crate::air::{MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{Limbs, NumLimbs, NumWords};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, pad_rows, words_to_bytes_le, words_to_bytes_le_vec,
+};
+use num::{BigUint, Integer, One, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256GcdCols.
+const NUM_COLS: usize = size_of::<Uint256GcdCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of words of the input, x followed by y.
+const INPUT_WORDS: usize = 2 * WORDS_FIELD_ELEMENT;
+
+/// The number of words of the output, the gcd followed by the Bezout coefficients a and b.
+const OUTPUT_WORDS: usize = 3 * WORDS_FIELD_ELEMENT;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256GcdEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub input_ptr: u32,
+    pub out_ptr: u32,
+    pub input: Vec<u32>,
+    pub output: Vec<u32>,
+    pub input_memory_records: Vec<MemoryReadRecord>,
+    pub output_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Returns the gcd of `x` and `y`, and Bezout coefficients `a` and `b` such that
+/// `a * x = b * y + gcd`.
+///
+/// Such coefficients exist unless `x` is zero and `y` is not, in which case this returns `None`.
+pub fn uint256_gcd(x: &BigUint, y: &BigUint) -> Option<(BigUint, BigUint, BigUint)> {
+    let gcd = x.gcd(y);
+    if y.is_zero() {
+        // gcd(x, 0) = x = 1 * x.
+        return Some((gcd, BigUint::one(), BigUint::zero()));
+    }
+    if x.is_zero() {
+        return None;
+    }
+    let (x_reduced, y_reduced) = (x / &gcd, y / &gcd);
+    let a = if y_reduced.is_one() {
+        // y divides x, so x = (x / y - 1) * y + y.
+        BigUint::one()
+    } else {
+        x_reduced.modinv(&y_reduced).unwrap()
+    };
+    let b = (&a * x - &gcd) / y;
+    Some((gcd, a, b))
+}
+
+/// A precompile for the gcd of two uint256 values, along with Bezout coefficients.
+///
+/// The first argument points to `x` followed by `y`, and the second to the output, which receives
+/// the gcd followed by coefficients `a` and `b` such that `a * x = b * y + gcd`. `x` must not be
+/// zero unless `y` is, as the gcd of zero and `y` is `y`.
+///
+/// The gcd is computed on the host, and the circuit only verifies it: the gcd divides both `x`
+/// and `y`, with a zero remainder, and is an integer combination of them, so any common divisor
+/// divides it as well.
+#[derive(Default)]
+pub struct Uint256GcdChip;
+
+impl Uint256GcdChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Uint256Gcd operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256GcdCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the input, which contains x followed by y.
+    pub input_ptr: T,
+
+    /// The pointer to the output, which receives the gcd followed by a and b.
+    pub out_ptr: T,
+
+    pub input_memory: [MemoryReadCols<T>; INPUT_WORDS],
+    pub output_memory: [MemoryWriteCols<T>; OUTPUT_WORDS],
+
+    /// Checks that `a * x = b * y + gcd`, with b as the quotient.
+    pub bezout: FieldOpCols<T, U256Field>,
+
+    /// Checks that x is a multiple of the gcd, with a zero remainder.
+    pub x_remainder: FieldOpCols<T, U256Field>,
+
+    /// Checks that y is a multiple of the gcd, with a zero remainder.
+    pub y_remainder: FieldOpCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256GcdChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256Gcd".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .uint256_gcd_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256GcdCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+                cols.out_ptr = F::from_canonical_u32(event.out_ptr);
+
+                // Populate memory columns.
+                for i in 0..INPUT_WORDS {
+                    cols.input_memory[i].populate(
+                        event.channel,
+                        event.input_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..OUTPUT_WORDS {
+                    cols.output_memory[i].populate(
+                        event.channel,
+                        event.output_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the checks of the gcd. The coefficient b is the quotient of the first.
+                let a_words = &event.output[WORDS_FIELD_ELEMENT..2 * WORDS_FIELD_ELEMENT];
+                let [x, y, gcd, a] = [
+                    &event.input[..WORDS_FIELD_ELEMENT],
+                    &event.input[WORDS_FIELD_ELEMENT..],
+                    &event.output[..WORDS_FIELD_ELEMENT],
+                    a_words,
+                ]
+                .map(|words| BigUint::from_bytes_le(&words_to_bytes_le_vec(words)));
+                cols.bezout.populate_mul_with_result(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &a,
+                    &x,
+                    &y,
+                    &gcd,
+                );
+                let remainders = [(&mut cols.x_remainder, &x), (&mut cols.y_remainder, &y)];
+                for (remainder, value) in remainders {
+                    remainder.populate_mul_with_result(
+                        &mut new_byte_lookup_events,
+                        event.shard,
+                        event.channel,
+                        value,
+                        &BigUint::one(),
+                        &gcd,
+                        &BigUint::zero(),
+                    );
+                }
+
+                // The coefficient a is a factor of the product, so its bytes are range checked.
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &words_to_bytes_le::<32>(a_words),
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+            let cols: &mut Uint256GcdCols<F> = row.as_mut_slice().borrow_mut();
+
+            // The checks hold for zero operands, and a zero gcd and coefficients.
+            let zero = BigUint::zero();
+            for op in [&mut cols.bezout, &mut cols.x_remainder, &mut cols.y_remainder] {
+                op.populate_mul_with_result(&mut vec![], 0, 0, &zero, &zero, &zero, &zero);
+            }
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256GcdCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_gcd_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256GcdChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let input_ptr = arg1;
+        rt.require_aligned(input_ptr, self.alignment()).unwrap();
+        let out_ptr = arg2;
+        rt.require_aligned(out_ptr, self.alignment()).unwrap();
+        rt.require_disjoint(
+            input_ptr,
+            (INPUT_WORDS * WORD_SIZE) as u32,
+            out_ptr,
+            (OUTPUT_WORDS * WORD_SIZE) as u32,
+        )
+        .unwrap();
+
+        // Read x and y, which are stored contiguously.
+        let (input_memory_records, input) = rt.mr_slice(input_ptr, INPUT_WORDS);
+        let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&input[..WORDS_FIELD_ELEMENT]));
+        let y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&input[WORDS_FIELD_ELEMENT..]));
+        let (gcd, a, b) = uint256_gcd(&x, &y).expect("uint256_gcd x is zero but y is not");
+
+        // Write the gcd and the coefficients to the output.
+        let output = [gcd, a, b]
+            .iter()
+            .flat_map(|value| {
+                let mut bytes = value.to_bytes_le();
+                bytes.resize(32, 0u8);
+                bytes_to_words_le::<8>(&bytes)
+            })
+            .collect::<Vec<_>>();
+        let output_memory_records = rt.mw_slice(out_ptr, &output);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().uint256_gcd_events.push(Uint256GcdEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            input_ptr,
+            out_ptr,
+            input,
+            output,
+            input_memory_records,
+            output_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256GcdChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256GcdChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256GcdCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256GcdCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let x: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.input_memory[..WORDS_FIELD_ELEMENT]);
+        let y: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.input_memory[WORDS_FIELD_ELEMENT..]);
+        let gcd: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.output_memory[..WORDS_FIELD_ELEMENT]);
+        let a: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.output_memory[WORDS_FIELD_ELEMENT..2 * WORDS_FIELD_ELEMENT]);
+        let b: Limbs<AB::Var, <U256Field as NumLimbs>::Limbs> =
+            limbs_from_access(&local.output_memory[2 * WORDS_FIELD_ELEMENT..]);
+
+        // Verify that `a * x = b * y + gcd`, so that the gcd is an integer combination of x and y.
+        // If y is zero, this asserts that the gcd is `a * x`.
+        local.bezout.eval_with_modulus(
+            builder,
+            &a,
+            &x,
+            &y,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.bezout.result, gcd);
+        builder
+            .when(local.is_real)
+            .assert_all_eq(*local.bezout.quotient(), b);
+        builder.slice_range_check_u8(&a.0, local.shard, local.channel, local.is_real);
+
+        // Verify that the gcd divides x and y, with a zero remainder. If the gcd is zero, this
+        // asserts that x and y are zero.
+        let one = Polynomial::from_coefficients(&[AB::Expr::one()]);
+        for (remainder, value) in [(&local.x_remainder, &x), (&local.y_remainder, &y)] {
+            remainder.eval_with_modulus(
+                builder,
+                value,
+                &one,
+                &gcd,
+                FieldOperation::Mul,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            for &limb in remainder.result.0.iter() {
+                builder.when(local.is_real).assert_zero(limb);
+            }
+        }
+
+        // Read the input and write the output.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.input_ptr,
+            &local.input_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.out_ptr,
+            &local.output_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_GCD.syscall_id()),
+            local.input_ptr,
+            local.out_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
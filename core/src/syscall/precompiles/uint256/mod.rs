@@ -1,22 +1,245 @@
 mod air;
+mod cmov;
+mod decimal;
+mod gcd;
+mod mod_small;
+mod naf;
+mod scalar_in_range;
+mod shift;
+mod sqr;
+mod wnaf;
 
 pub use air::*;
+pub use cmov::*;
+pub use decimal::*;
+pub use gcd::*;
+pub use mod_small::*;
+pub use naf::*;
+pub use scalar_in_range::*;
+pub use shift::*;
+pub use sqr::*;
+pub use wnaf::*;
 
 #[cfg(test)]
 mod tests {
 
+    use std::num::NonZeroUsize;
+
+    use num::{BigInt, BigUint};
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::Matrix;
+
+    use crate::air::{MachineAir, PackedTraceAir, Polynomial, TraceLayout, SP1_PROOF_NUM_PV_ELTS};
     use crate::operations::field::params::FieldParameters;
+    use crate::operations::MAX_REGION_GAP;
+    use crate::runtime::{
+        ExecutionError, ExecutionRecord, Instruction, MemoryAccessCounts, Opcode,
+        RecordValidationError, Runtime, ShardingConfig, SyscallCode,
+    };
+    use crate::stark::{
+        log_sampled_rows, LocalProver, MachineRecord, Prover, RiscvAir, StarkGenericConfig,
+    };
+    use crate::utils::tests::store_words;
+    use crate::utils::{
+        assert_event_count, execute_only, prove_simple, trace_row_report, BabyBearPoseidon2,
+        ChipRowReport, SP1CoreOpts, SP1CoreProverError,
+    };
     use crate::{
         io::SP1Stdin,
         runtime::Program,
         utils::{
             self,
             ec::{uint256::U256Field, utils::biguint_from_limbs},
-            run_test_io,
+            run_test, run_test_core, run_test_io,
             tests::UINT256_MUL_ELF,
+            words_to_bytes_le,
         },
     };
 
+    use super::{
+        scalar_to_naf, uint256_gcd, uint256_shift, uint256_wnaf, ShiftDirection, Uint256CmovChip,
+        Uint256MulChip, Uint256MulCols, NAF_WIDTH, NUM_DECIMAL_DIGITS, NUM_NAF_DIGITS,
+        NUM_NAF_WORDS, NUM_UINT256_SQR_COLS, NUM_WNAF_DIGITS, NUM_WNAF_WORDS,
+    };
+
+    const DST_PTR: u32 = 0x1000;
+    const OUT_PTR: u32 = 0x2000;
+    const MODULUS_PTR: u32 = 0x3000;
+
+    /// Stores `x` at `DST_PTR` and `y` at `OUT_PTR`, followed by a zero modulus, and multiplies `x`
+    /// by `y` in place `num_calls` times.
+    pub fn uint256_mul_program(num_calls: usize) -> Program {
+        uint256_mul_program_at(DST_PTR, OUT_PTR, num_calls)
+    }
+
+    /// Stores `x` at `x_ptr` and then `y` at `y_ptr`, followed by a zero modulus if the operands do
+    /// not overlap it, and multiplies `x` by `y` in place `num_calls` times.
+    pub fn uint256_mul_program_at(x_ptr: u32, y_ptr: u32, num_calls: usize) -> Program {
+        let x = core::array::from_fn::<u32, 8, _>(|i| i as u32 + 1);
+        let y = core::array::from_fn::<u32, 8, _>(|i| 0x1234_5678 ^ i as u32);
+        let mut instructions = vec![];
+        for (ptr, words) in [(x_ptr, x), (y_ptr, y)] {
+            instructions.extend(store_words(ptr, words));
+        }
+        for _ in 0..num_calls {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::UINT256_MUL as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, x_ptr, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, y_ptr, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `dst` followed by `src` at `DST_PTR` and conditionally moves `src` into `dst`.
+    pub fn uint256_cmov_program(dst: [u32; 8], src: [u32; 8], cond: u32) -> Program {
+        let mut instructions = store_words(DST_PTR, dst.into_iter().chain(src));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::UINT256_CMOV as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, cond, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` at `DST_PTR` and writes its decimal digits to `OUT_PTR`.
+    pub fn uint256_to_decimal_program(x: [u32; 8]) -> Program {
+        let mut instructions = store_words(DST_PTR, x);
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::UINT256_TO_DECIMAL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` at `DST_PTR` and `m` at `OUT_PTR`, and writes `x mod m` to `OUT_PTR + 4`.
+    pub fn uint256_mod_small_program(x: [u32; 8], m: u32) -> Program {
+        let mut instructions = store_words(DST_PTR, x);
+        instructions.extend(store_words(OUT_PTR, [m]));
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::UINT256_MOD_SMALL as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `scalar` at `DST_PTR` and writes its signed digits to `OUT_PTR`.
+    pub fn uint256_wnaf_program(scalar: [u32; 8]) -> Program {
+        let mut instructions = store_words(DST_PTR, scalar);
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::UINT256_WNAF as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `scalar` at `DST_PTR` and writes its NAF digits to `OUT_PTR`.
+    pub fn scalar_to_naf_program(scalar: [u32; 8]) -> Program {
+        let mut instructions = store_words(DST_PTR, scalar);
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::SCALAR_TO_NAF as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` at `DST_PTR`, `modulus` at `MODULUS_PTR` and a pointer to it at `OUT_PTR`, and
+    /// writes whether `0 < x < modulus` to `OUT_PTR + 4`.
+    pub fn scalar_in_range_program(x: [u32; 8], modulus: [u32; 8]) -> Program {
+        let mut instructions = store_words(DST_PTR, x);
+        instructions.extend(store_words(MODULUS_PTR, modulus));
+        instructions.extend(store_words(OUT_PTR, [MODULUS_PTR]));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::SCALAR_IN_RANGE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` at `DST_PTR` and shifts it in place by `shift` bits with the shift precompile
+    /// `code`.
+    pub fn uint256_shift_program(x: [u32; 8], shift: u32, code: SyscallCode) -> Program {
+        let mut instructions = store_words(DST_PTR, x);
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, shift, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` followed by `y` at `DST_PTR`, and writes their gcd and Bezout coefficients to
+    /// `OUT_PTR`.
+    pub fn uint256_gcd_program(x: [u32; 8], y: [u32; 8]) -> Program {
+        let mut instructions = store_words(DST_PTR, x.into_iter().chain(y));
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::UINT256_GCD as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, OUT_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Stores `x` at `DST_PTR` and `modulus` at `modulus_ptr`, and squares `x` in place modulo
+    /// `modulus` `num_calls` times.
+    pub fn uint256_sqr_program(
+        x: [u32; 8],
+        modulus: [u32; 8],
+        modulus_ptr: u32,
+        num_calls: usize,
+    ) -> Program {
+        let mut instructions = vec![];
+        for (ptr, words) in [(DST_PTR, x), (modulus_ptr, modulus)] {
+            instructions.extend(store_words(ptr, words));
+        }
+        for _ in 0..num_calls {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::UINT256_SQR as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, modulus_ptr, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
     #[test]
     fn test_uint256_mul() {
         utils::setup_logger();
@@ -24,8 +247,918 @@ mod tests {
         run_test_io(program, SP1Stdin::new()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "debug")]
+    #[should_panic(expected = "of chip Uint256MulMod does not satisfy its constraints")]
+    fn test_uint256_mul_mispopulated_output() {
+        utils::setup_logger();
+        let mut runtime = Runtime::new(Program::from(UINT256_MUL_ELF), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Populate the output from a different operand than the one read from memory.
+        let event = &mut runtime.record.uint256_mul_events[0];
+        event.y[0] ^= 1;
+        let _: RowMajorMatrix<BabyBear> =
+            Uint256MulChip::new().generate_trace(&runtime.record, &mut ExecutionRecord::default());
+    }
+
+    #[test]
+    fn test_uint256_mul_aliased_operands() {
+        utils::setup_logger();
+        let mut runtime =
+            Runtime::new(uint256_mul_program_at(DST_PTR, DST_PTR, 1), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // With `x_ptr == y_ptr`, the operand is squared.
+        let y = core::array::from_fn::<u32, 8, _>(|i| 0x1234_5678 ^ i as u32);
+        let y = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&y));
+        let result: [u32; 8] = core::array::from_fn(|i| runtime.word(DST_PTR + i as u32 * 4));
+        let result = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&result));
+        assert_eq!(result, (&y * &y) % (BigUint::from(1u32) << 256));
+
+        run_test(uint256_mul_program_at(DST_PTR, DST_PTR, 2)).unwrap();
+    }
+
+    #[test]
+    fn test_uint256_mul_overlapping_operands() {
+        // The modulus after y overlaps x.
+        let program = uint256_mul_program_at(DST_PTR + 48, DST_PTR, 1);
+        let result = Runtime::new(program, SP1CoreOpts::default()).run();
+        assert!(matches!(
+            result,
+            Err(ExecutionError::AliasingSyscallPointers(x_ptr, DST_PTR)) if x_ptr == DST_PTR + 48
+        ));
+    }
+
+    #[test]
+    fn test_uint256_mul_distant_operands() {
+        // Disjoint operands at least `MAX_REGION_GAP` bytes apart cannot be proven, so they are
+        // rejected as well, whichever one is lower.
+        let far_ptr = DST_PTR + 64 + MAX_REGION_GAP;
+        for (x_ptr, y_ptr) in [(DST_PTR, far_ptr), (far_ptr, DST_PTR)] {
+            let program = uint256_mul_program_at(x_ptr, y_ptr, 1);
+            let result = Runtime::new(program, SP1CoreOpts::default()).run();
+            assert!(matches!(
+                result,
+                Err(ExecutionError::DistantSyscallPointers(x, y)) if (x, y) == (x_ptr, y_ptr)
+            ));
+        }
+
+        // Just below the bound, they are proven.
+        let near_ptr = DST_PTR + 32 + MAX_REGION_GAP - 4;
+        run_test(uint256_mul_program_at(DST_PTR, near_ptr, 1)).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "uint256_mul operands at 0x1000 and 0x1010 overlap")]
+    fn test_uint256_mul_overlapping_operands_trace() {
+        utils::setup_logger();
+        let mut runtime = Runtime::new(uint256_mul_program(1), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Populate the trace of operands whose buffers overlap, which the runtime rejects.
+        let event = &mut runtime.record.uint256_mul_events[0];
+        event.y_ptr = event.x_ptr + 16;
+        let _: RowMajorMatrix<BabyBear> =
+            Uint256MulChip::new().generate_trace(&runtime.record, &mut ExecutionRecord::default());
+    }
+
+    #[test]
+    fn test_uint256_mul_validate() {
+        let mut runtime = Runtime::new(uint256_mul_program(3), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.validate(), Ok(()));
+
+        // A memory record array of the wrong length is rejected, naming the event and the field.
+        let mut record = runtime.record.clone();
+        record.uint256_mul_events[1].y_memory_records.pop();
+        let err = record.validate().unwrap_err();
+        assert_eq!(
+            err,
+            RecordValidationError::LengthMismatch {
+                events: "uint256_mul_events",
+                index: 1,
+                field: "y_memory_records",
+                len: 7,
+                expected: 8,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "uint256_mul_events[1].y_memory_records has 7 entries, but 8 are expected"
+        );
+
+        // So are events out of order, and unaligned pointers.
+        let mut record = runtime.record.clone();
+        record.uint256_mul_events.swap(0, 2);
+        assert!(matches!(
+            record.validate(),
+            Err(RecordValidationError::ClkOutOfOrder { index: 1, .. })
+        ));
+        let mut record = runtime.record.clone();
+        record.uint256_mul_events[2].x_ptr += 2;
+        assert!(matches!(
+            record.validate(),
+            Err(RecordValidationError::UnalignedPointer {
+                index: 2,
+                field: "x_ptr",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_uint256_mul_prove_malformed_event() {
+        let mut runtime = Runtime::new(uint256_mul_program(1), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        runtime.record.uint256_mul_events[0].x_memory_records.truncate(4);
+        let err = prove_simple(BabyBearPoseidon2::new(), runtime).unwrap_err();
+        assert!(matches!(
+            err,
+            SP1CoreProverError::InvalidRecord(RecordValidationError::LengthMismatch {
+                events: "uint256_mul_events",
+                index: 0,
+                field: "x_memory_records",
+                ..
+            })
+        ));
+        assert!(err
+            .to_string()
+            .starts_with("invalid execution record: uint256_mul_events[0].x_memory_records"));
+    }
+
+    #[test]
+    fn test_uint256_mul_execute_only() {
+        let result = execute_only(Program::from(UINT256_MUL_ELF), &SP1Stdin::new()).unwrap();
+        assert_eq!(result.exit_code, 0);
+        assert!(!result.record.uint256_mul_events.is_empty());
+
+        let result = execute_only(uint256_mul_program(3), &SP1Stdin::new()).unwrap();
+        assert_eq!(result.record.uint256_mul_events.len(), 3);
+    }
+
+    #[test]
+    fn test_uint256_mul_total_cycles() {
+        utils::setup_logger();
+        let program = Program::from(UINT256_MUL_ELF);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let cycles = runtime.state.global_clk;
+        let proof = run_test_core(runtime).unwrap();
+        assert_eq!(proof.total_cycles(), cycles);
+
+        // A proof claiming another number of cycles does not verify.
+        let mut forged = proof;
+        let num_cycles = &mut forged.shard_proofs[0].public_values[SP1_PROOF_NUM_PV_ELTS - 1];
+        *num_cycles += BabyBear::one();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        assert!(machine.verify(&vk, &forged, &mut challenger).is_err());
+    }
+
+    #[test]
+    fn test_uint256_mul_memory_access_counts() {
+        utils::setup_logger();
+        let run = |num_calls| {
+            let mut runtime = Runtime::new(uint256_mul_program(num_calls), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            assert_eq!(runtime.record.memory_access_counts.len(), 1);
+            assert_eq!(
+                runtime.record.memory_access_counts,
+                runtime.report.memory_access_counts
+            );
+            runtime
+        };
+        let counts = |runtime: &Runtime| {
+            *runtime.record.memory_access_counts.values().next().unwrap()
+        };
+        let base = counts(&run(0));
+
+        // Each multiply reads the 8 words of y and the 8 of the modulus and writes the 8 of x.
+        // Around it, three ADDs each read x0 and write a register, and the ECALL reads x10 and x11
+        // and writes x5.
+        let per_call = MemoryAccessCounts {
+            reads: 3 + 2 + 16,
+            writes: 3 + 1 + 8,
+        };
+        for num_calls in [1, 5] {
+            let runtime = run(num_calls);
+            let counts = counts(&runtime);
+            assert_eq!(counts.reads - base.reads, num_calls as u64 * per_call.reads);
+            assert_eq!(counts.writes - base.writes, num_calls as u64 * per_call.writes);
+
+            // The counts move to the shard they were recorded in.
+            let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+            let expected = runtime.record.memory_access_counts.clone();
+            let shards = machine.shard(runtime.record, &ShardingConfig::default());
+            assert_eq!(shards[0].memory_access_counts, expected);
+        }
+    }
+
+    #[test]
+    fn test_uint256_mul_trace_row_report() {
+        utils::setup_logger();
+        const NUM_CALLS: usize = 20;
+        let mut runtime = Runtime::new(uint256_mul_program(NUM_CALLS), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, vk) = machine.setup(runtime.program.as_ref());
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].uint256_mul_events.len(), NUM_CALLS);
+        let trace: RowMajorMatrix<BabyBear> =
+            Uint256MulChip::new().generate_trace(&shards[0], &mut ExecutionRecord::default());
+        assert_eq!(trace.height(), NUM_CALLS.next_power_of_two());
+
+        let estimate = shards[0].estimate_prove_cost();
+        let mut challenger = machine.config().challenger();
+        let opts = SP1CoreOpts::default();
+        let proof = LocalProver::prove_shards(&machine, &pk, shards, &mut challenger, opts);
+        machine
+            .verify(&vk, &proof, &mut machine.config().challenger())
+            .unwrap();
+
+        let report = trace_row_report(&estimate, &proof.shard_proofs[0]);
+        let expected = ChipRowReport {
+            estimated_rows: NUM_CALLS.next_power_of_two(),
+            real_rows: NUM_CALLS,
+            padded_rows: NUM_CALLS.next_power_of_two(),
+        };
+        assert_eq!(report["Uint256MulMod"], expected);
+        assert_eq!(report["Uint256MulMod"].padding_rows(), 12);
+        assert!(report
+            .values()
+            .all(|chip| chip.estimated_rows == chip.padded_rows));
+    }
+
+    #[test]
+    fn test_uint256_mul_precompile_shard_cap() {
+        utils::setup_logger();
+        const NUM_CALLS: usize = 20;
+        const CAP: usize = 8;
+
+        // Without a cap, the burst of multiplications fills a single table.
+        let mut runtime = Runtime::new(uint256_mul_program(NUM_CALLS), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].uint256_mul_events.len(), NUM_CALLS);
+
+        // With it, the runtime closes a shard before each multiplication past the cap. Every shard
+        // but the last makes exactly `CAP` calls, so each gets the multiplications it called.
+        let mut opts = SP1CoreOpts::default();
+        opts.precompile_shard_cap = NonZeroUsize::new(CAP);
+        let mut runtime = Runtime::new(uint256_mul_program(NUM_CALLS), opts);
+        runtime.run().unwrap();
+        assert_eq!(runtime.state.current_shard, 3);
+        let (pk, vk) = machine.setup(runtime.program.as_ref());
+        let shards = machine.shard(runtime.record, &ShardingConfig::new(&opts));
+        let counts = shards
+            .iter()
+            .map(|shard| shard.uint256_mul_events.len())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![CAP, CAP, NUM_CALLS - 2 * CAP]);
+        for shard in shards.iter() {
+            assert!(shard
+                .uint256_mul_events
+                .iter()
+                .all(|event| event.shard == shard.index()));
+        }
+
+        // The first call of each new shard starts it, so its memory accesses stay in the shard.
+        assert_eq!(shards[1].cpu_events[0].instruction.opcode, Opcode::ECALL);
+        assert_eq!(shards[1].uint256_mul_events[0].clk, 0);
+
+        let mut challenger = machine.config().challenger();
+        let proof = LocalProver::prove_shards(&machine, &pk, shards, &mut challenger, opts);
+        machine
+            .verify(&vk, &proof, &mut machine.config().challenger())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_uint256_mul_event_count() {
+        utils::setup_logger();
+        for num_calls in [0, 1, 3, 10] {
+            let mut runtime = Runtime::new(uint256_mul_program(num_calls), SP1CoreOpts::default());
+            runtime.run().unwrap();
+
+            // One event per syscall, and no events of other precompiles.
+            assert_event_count(&runtime.record, "Uint256MulMod", num_calls);
+            assert_event_count(&runtime.record, "FieldMulSmall", 0);
+            let counts = runtime.record.event_counts();
+            assert_eq!(counts["CPU"], runtime.record.cpu_events.len());
+            assert!(!counts.contains_key("KeccakPermute"));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no chip named Uint256Mul")]
+    fn test_uint256_mul_event_count_unknown_chip() {
+        let mut runtime = Runtime::new(uint256_mul_program(1), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_event_count(&runtime.record, "Uint256Mul", 1);
+    }
+
+    #[test]
+    fn test_uint256_mul_log_sampled_rows() {
+        utils::setup_logger();
+        const NUM_CALLS: usize = 5000;
+        let mut runtime = Runtime::new(uint256_mul_program(NUM_CALLS), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.record.uint256_mul_events.len(), NUM_CALLS);
+
+        let trace: RowMajorMatrix<BabyBear> =
+            Uint256MulChip::new().generate_trace(&runtime.record, &mut ExecutionRecord::default());
+        assert_eq!(trace.height(), NUM_CALLS.next_power_of_two());
+
+        let log = |interval| {
+            log_sampled_rows::<BabyBear, Uint256MulCols<BabyBear>>(
+                "Uint256MulMod",
+                &trace,
+                NUM_CALLS,
+                interval,
+            )
+        };
+        assert_eq!(log(1000), 5);
+        // Only real rows are sampled, so row 6000 of the padded trace is not logged.
+        assert_eq!(log(3000), 2);
+    }
+
+    #[test]
+    fn test_uint256_mul_packed_trace() {
+        utils::setup_logger();
+        let mut runtime = Runtime::new(uint256_mul_program(20), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let chip = Uint256MulChip::new();
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&runtime.record, &mut ExecutionRecord::default());
+
+        for layout in [
+            TraceLayout::RowMajor,
+            TraceLayout::ColumnMajor,
+            TraceLayout::Interleaved { block_height: 8 },
+        ] {
+            let packed = PackedTraceAir::<BabyBear>::generate_packed_trace(
+                &chip,
+                &runtime.record,
+                &mut ExecutionRecord::default(),
+                layout,
+            );
+            assert_eq!(packed.width, trace.width());
+            assert_eq!(packed.height, trace.height());
+            let unpacked = packed.unpack::<BabyBear>();
+            assert_eq!(unpacked.values, trace.values);
+        }
+    }
+
+    #[test]
+    fn test_uint256_mul_column_names() {
+        assert_eq!(
+            Uint256MulCols::<u8>::column_names(),
+            [
+                "shard",
+                "channel",
+                "clk",
+                "nonce",
+                "x_ptr",
+                "y_ptr",
+                "x_memory",
+                "y_memory",
+                "modulus_memory",
+                "ptrs_disjoint",
+                "modulus_is_zero",
+                "output",
+                "is_real",
+            ]
+        );
+
+        // The scalar columns come first, and `is_real` is the last column.
+        let offsets = Uint256MulCols::<u8>::column_offsets();
+        assert_eq!(offsets.len(), Uint256MulCols::<u8>::column_names().len());
+        assert_eq!(offsets[..7], [0, 1, 2, 3, 4, 5, 6]);
+        assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            offsets.last(),
+            Some(&(std::mem::size_of::<Uint256MulCols<u8>>() - 1))
+        );
+    }
+
+    #[test]
+    fn test_uint256_cmov() {
+        utils::setup_logger();
+        let dst = core::array::from_fn(|i| i as u32);
+        let src = core::array::from_fn(|i| 0xdead_0000 + i as u32);
+
+        let runtimes = [0, 1, 0x8000_0000].map(|cond| {
+            let program = uint256_cmov_program(dst, src, cond);
+            let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+            runtime.run().unwrap();
+            runtime
+        });
+        for (runtime, expected) in runtimes.iter().zip([dst, src, src]) {
+            let result: [u32; 8] = core::array::from_fn(|i| runtime.word(DST_PTR + i as u32 * 4));
+            assert_eq!(result, expected);
+            assert_eq!(runtime.word(DST_PTR + 32), src[0]);
+        }
+
+        // The events and the trace shape do not depend on the condition.
+        let [not_moved, moved, _] = &runtimes;
+        assert_eq!(not_moved.record.stats(), moved.record.stats());
+        assert_eq!(not_moved.state.global_clk, moved.state.global_clk);
+        let chip = Uint256CmovChip::new();
+        let not_moved_trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&not_moved.record, &mut ExecutionRecord::default());
+        let moved_trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&moved.record, &mut ExecutionRecord::default());
+        assert_eq!(not_moved_trace.width(), moved_trace.width());
+        assert_eq!(not_moved_trace.height(), moved_trace.height());
+    }
+
+    #[test]
+    fn test_uint256_cmov_prove() {
+        utils::setup_logger();
+        let dst = core::array::from_fn(|i| i as u32);
+        let src = core::array::from_fn(|i| u32::MAX - i as u32);
+        for cond in [0, 1, 0x8000_0000] {
+            run_test(uint256_cmov_program(dst, src, cond)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_uint256_to_decimal() {
+        utils::setup_logger();
+        let cases: [([u32; 8], &str); 3] = [
+            ([0; 8], "0"),
+            (
+                [0xeb1f_0ad2, 0xab54_a98c, 0, 0, 0, 0, 0, 0],
+                "12345678901234567890",
+            ),
+            (
+                [u32::MAX; 8],
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+            ),
+        ];
+        for (x, expected) in cases {
+            let program = uint256_to_decimal_program(x);
+            let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let digits = (0..NUM_DECIMAL_DIGITS as u32 / 4)
+                .flat_map(|i| runtime.word(OUT_PTR + i * 4).to_le_bytes())
+                .collect::<Vec<_>>();
+            let expected = format!("{:0>width$}", expected, width = NUM_DECIMAL_DIGITS);
+            assert_eq!(String::from_utf8(digits).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_uint256_to_decimal_prove() {
+        utils::setup_logger();
+        run_test(uint256_to_decimal_program([0xeb1f_0ad2, 0xab54_a98c, 0, 0, 0, 0, 0, 0])).unwrap();
+        run_test(uint256_to_decimal_program([u32::MAX; 8])).unwrap();
+    }
+
+    #[test]
+    fn test_uint256_mod_small() {
+        utils::setup_logger();
+        let xs: [[u32; 8]; 3] = [
+            [0; 8],
+            [0xeb1f_0ad2, 0xab54_a98c, 0, 0, 0, 0, 0, 0],
+            [u32::MAX; 8],
+        ];
+        for x in xs {
+            for m in [1, 2, 1_000_000_007, u32::MAX] {
+                let mut runtime =
+                    Runtime::new(uint256_mod_small_program(x, m), SP1CoreOpts::default());
+                runtime.run().unwrap();
+                let expected = biguint_from_limbs(&words_to_bytes_le::<32>(&x)) % m;
+                assert_eq!(BigUint::from(runtime.word(OUT_PTR + 4)), expected);
+                assert_eq!(runtime.word(OUT_PTR), m);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "uint256_mod_small modulus is zero")]
+    fn test_uint256_mod_small_zero_modulus() {
+        let program = uint256_mod_small_program([1; 8], 0);
+        Runtime::new(program, SP1CoreOpts::default()).run().unwrap();
+    }
+
+    #[test]
+    fn test_uint256_mod_small_prove() {
+        utils::setup_logger();
+        run_test(uint256_mod_small_program([u32::MAX; 8], 1_000_000_007)).unwrap();
+        run_test(uint256_mod_small_program([0xeb1f_0ad2, 0xab54_a98c, 0, 0, 0, 0, 0, 0], 10))
+            .unwrap();
+    }
+
+    const WNAF_SCALARS: [[u32; 8]; 4] = [
+        [0; 8],
+        [0x8888_8888; 8],
+        [u32::MAX; 8],
+        [
+            0xd036_4141, 0xbfd2_5e8c, 0xaf48_a03b, 0xbaae_dce6, 0xffff_fffe, 0xffff_ffff,
+            0xffff_ffff, 0xffff_ffff,
+        ],
+    ];
+
+    #[test]
+    fn test_uint256_wnaf_reconstructs_scalar() {
+        for scalar in WNAF_SCALARS {
+            let digits = uint256_wnaf(&scalar);
+            assert!(digits[..NUM_WNAF_DIGITS - 1]
+                .iter()
+                .all(|digit| (-8..8).contains(digit)));
+            assert!((0..=1).contains(&digits[NUM_WNAF_DIGITS - 1]));
+
+            let sum = digits
+                .iter()
+                .rev()
+                .fold(BigInt::from(0), |sum, &digit| (sum << 4) + digit);
+            let expected = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&scalar));
+            assert_eq!(sum, BigInt::from(expected));
+        }
+    }
+
+    #[test]
+    fn test_uint256_wnaf() {
+        utils::setup_logger();
+        for scalar in WNAF_SCALARS {
+            let mut runtime = Runtime::new(uint256_wnaf_program(scalar), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let bytes = (0..NUM_WNAF_WORDS as u32)
+                .flat_map(|i| runtime.word(OUT_PTR + i * 4).to_le_bytes())
+                .collect::<Vec<_>>();
+            let digits = uint256_wnaf(&scalar);
+            assert!(bytes.iter().zip(digits).all(|(&byte, digit)| byte as i8 == digit));
+            assert!(bytes[NUM_WNAF_DIGITS..].iter().all(|&byte| byte == 0));
+        }
+    }
+
+    #[test]
+    fn test_uint256_wnaf_prove() {
+        utils::setup_logger();
+        for scalar in WNAF_SCALARS {
+            run_test(uint256_wnaf_program(scalar)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scalar_to_naf_reconstructs_scalar() {
+        for scalar in WNAF_SCALARS {
+            let digits = scalar_to_naf(&scalar);
+            for (i, &digit) in digits.iter().enumerate() {
+                if digit == 0 {
+                    continue;
+                }
+                assert!(digit % 2 != 0 && (-7..=7).contains(&digit));
+                let end = (i + NAF_WIDTH).min(NUM_NAF_DIGITS);
+                assert!(digits[i + 1..end].iter().all(|&digit| digit == 0));
+            }
+
+            let sum = digits
+                .iter()
+                .rev()
+                .fold(BigInt::from(0), |sum, &digit| (sum << 1) + digit);
+            let expected = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&scalar));
+            assert_eq!(sum, BigInt::from(expected));
+        }
+
+        // All ones is `2^256 - 1`, whose NAF has two nonzero digits.
+        let digits = scalar_to_naf(&[u32::MAX; 8]);
+        assert_eq!(digits.iter().filter(|&&digit| digit != 0).count(), 2);
+        assert_eq!((digits[0], digits[NUM_NAF_DIGITS - 1]), (-1, 1));
+    }
+
+    #[test]
+    fn test_scalar_to_naf() {
+        utils::setup_logger();
+        for scalar in WNAF_SCALARS {
+            let mut runtime = Runtime::new(scalar_to_naf_program(scalar), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let bytes = (0..NUM_NAF_WORDS as u32)
+                .flat_map(|i| runtime.word(OUT_PTR + i * 4).to_le_bytes())
+                .collect::<Vec<_>>();
+            let digits = scalar_to_naf(&scalar);
+            assert!(bytes.iter().zip(digits).all(|(&byte, digit)| byte as i8 == digit));
+            assert!(bytes[NUM_NAF_DIGITS..].iter().all(|&byte| byte == 0));
+        }
+    }
+
+    #[test]
+    fn test_scalar_to_naf_prove() {
+        utils::setup_logger();
+        for scalar in WNAF_SCALARS {
+            run_test(scalar_to_naf_program(scalar)).unwrap();
+        }
+    }
+
+    /// The order of the secp256k1 group.
+    const SECP256K1_N: [u32; 8] = [
+        0xd036_4141, 0xbfd2_5e8c, 0xaf48_a03b, 0xbaae_dce6, 0xffff_fffe, 0xffff_ffff, 0xffff_ffff,
+        0xffff_ffff,
+    ];
+
+    #[test]
+    fn test_scalar_in_range() {
+        utils::setup_logger();
+        let mut n_minus_one = SECP256K1_N;
+        n_minus_one[0] -= 1;
+        let mut n_plus_one = SECP256K1_N;
+        n_plus_one[0] += 1;
+        let cases = [
+            ([0; 8], false),
+            ([1, 0, 0, 0, 0, 0, 0, 0], true),
+            ([0, 0, 0, 0, 0, 0, 0, 1], true),
+            (n_minus_one, true),
+            (SECP256K1_N, false),
+            (n_plus_one, false),
+            ([u32::MAX; 8], false),
+        ];
+        for (x, expected) in cases {
+            let program = scalar_in_range_program(x, SECP256K1_N);
+            let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+            runtime.run().unwrap();
+            assert_eq!(runtime.word(OUT_PTR + 4), expected as u32);
+            assert_eq!(runtime.word(OUT_PTR), MODULUS_PTR);
+        }
+    }
+
+    #[test]
+    fn test_scalar_in_range_prove() {
+        utils::setup_logger();
+        let mut n_minus_one = SECP256K1_N;
+        n_minus_one[0] -= 1;
+        for x in [[0; 8], [1, 0, 0, 0, 0, 0, 0, 0], n_minus_one, SECP256K1_N, [u32::MAX; 8]] {
+            run_test(scalar_in_range_program(x, SECP256K1_N)).unwrap();
+        }
+    }
+
+    const SHIFT_VALUES: [[u32; 8]; 3] = [
+        [u32::MAX; 8],
+        [
+            0x0123_4567, 0x89ab_cdef, 0xfedc_ba98, 0x7654_3210, 0xdead_beef, 0x0bad_f00d,
+            0x8000_0001, 0xc001_d00d,
+        ],
+        [1, 0, 0, 0, 0, 0, 0, 0x8000_0000],
+    ];
+
+    const SHIFT_AMOUNTS: [u32; 12] = [0, 1, 7, 8, 63, 64, 127, 128, 129, 255, 256, 300];
+
+    #[test]
+    fn test_uint256_shift_reference() {
+        let to_biguint = |words: [u32; 8]| biguint_from_limbs(&words_to_bytes_le::<32>(&words));
+        let mask = (BigUint::from(1u32) << 256) - 1u32;
+        for x in SHIFT_VALUES {
+            let value = to_biguint(x);
+            for shift in SHIFT_AMOUNTS {
+                let shl = uint256_shift(&x, shift, ShiftDirection::Left);
+                assert_eq!(to_biguint(shl), (&value << shift) & &mask);
+                let shr = uint256_shift(&x, shift, ShiftDirection::Right);
+                assert_eq!(to_biguint(shr), &value >> shift);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint256_shift() {
+        utils::setup_logger();
+        let cases = [
+            (SyscallCode::UINT256_SHL, ShiftDirection::Left),
+            (SyscallCode::UINT256_SHR, ShiftDirection::Right),
+        ];
+        for (code, direction) in cases {
+            for x in SHIFT_VALUES {
+                for shift in SHIFT_AMOUNTS {
+                    let program = uint256_shift_program(x, shift, code);
+                    let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+                    runtime.run().unwrap();
+                    let result: [u32; 8] =
+                        core::array::from_fn(|i| runtime.word(DST_PTR + i as u32 * 4));
+                    assert_eq!(result, uint256_shift(&x, shift, direction));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "uint256 shift amount 65536 is not less than 2^16")]
+    fn test_uint256_shift_too_large() {
+        let program = uint256_shift_program([1; 8], 1 << 16, SyscallCode::UINT256_SHL);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_uint256_shift_prove() {
+        utils::setup_logger();
+        for code in [SyscallCode::UINT256_SHL, SyscallCode::UINT256_SHR] {
+            for shift in [0, 1, 8, 129, 255, 256, 300] {
+                run_test(uint256_shift_program(SHIFT_VALUES[1], shift, code)).unwrap();
+            }
+        }
+    }
+
+    /// Pairs of operands of the gcd precompile, with a nonzero x unless y is zero.
+    const GCD_OPERANDS: [([u32; 8], [u32; 8]); 6] = [
+        ([0; 8], [0; 8]),
+        ([7, 0, 0, 0, 0, 0, 0, 0], [0; 8]),
+        ([0, 0, 0, 0, 6, 0, 0, 0], [0, 0, 15, 0, 0, 0, 0, 0]),
+        ([0x6000_0000, 0, 0, 0, 0, 0, 0, 0], [0x2000_0000, 0, 0, 0, 0, 0, 0, 0]),
+        ([1, 0, 0, 0, 0, 0, 0, 0], [u32::MAX; 8]),
+        (
+            [u32::MAX - 1, u32::MAX, u32::MAX, u32::MAX, 0, 0, 0, 0],
+            [u32::MAX; 8],
+        ),
+    ];
+
+    #[test]
+    fn test_uint256_gcd() {
+        utils::setup_logger();
+        let to_biguint = |words: &[u32]| BigUint::from_slice(words);
+        for (x, y) in GCD_OPERANDS {
+            let mut runtime = Runtime::new(uint256_gcd_program(x, y), SP1CoreOpts::default());
+            runtime.run().unwrap();
+            let out = (0..24)
+                .map(|i| runtime.word(OUT_PTR + i * 4))
+                .collect::<Vec<_>>();
+            let (gcd, a, b) = (
+                to_biguint(&out[..8]),
+                to_biguint(&out[8..16]),
+                to_biguint(&out[16..]),
+            );
+            let (x, y) = (to_biguint(&x), to_biguint(&y));
+            assert_eq!(gcd, num::Integer::gcd(&x, &y));
+            assert_eq!(&a * &x, &b * &y + &gcd);
+            assert_eq!(uint256_gcd(&x, &y), Some((gcd, a, b)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "uint256_gcd x is zero but y is not")]
+    fn test_uint256_gcd_zero_x() {
+        let program = uint256_gcd_program([0; 8], [5, 0, 0, 0, 0, 0, 0, 0]);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_uint256_gcd_prove() {
+        utils::setup_logger();
+        for (x, y) in GCD_OPERANDS {
+            run_test(uint256_gcd_program(x, y)).unwrap();
+        }
+    }
+
     #[test]
     fn test_uint256_modulus() {
         assert_eq!(biguint_from_limbs(U256Field::MODULUS), U256Field::modulus());
     }
+
+    const SQR_OPERANDS: [[u32; 8]; 4] = [
+        [0; 8],
+        [1, 0, 0, 0, 0, 0, 0, 0],
+        [u32::MAX; 8],
+        [
+            0x0123_4567, 0x89ab_cdef, 0xfedc_ba98, 0x7654_3210, 0xdead_beef, 0x0bad_f00d,
+            0x8000_0001, 0xc001_d00d,
+        ],
+    ];
+
+    const SQR_MODULI: [[u32; 8]; 4] = [
+        [0; 8],
+        SECP256K1_N,
+        [7, 0, 0, 0, 0, 0, 0, 0],
+        [u32::MAX; 8],
+    ];
+
+    /// Returns the uint256 stored at `ptr`.
+    fn read_uint256(runtime: &Runtime, ptr: u32) -> BigUint {
+        let words: [u32; 8] = core::array::from_fn(|i| runtime.word(ptr + i as u32 * 4));
+        BigUint::from_bytes_le(&words_to_bytes_le::<32>(&words))
+    }
+
+    #[test]
+    fn test_uint256_sqr() {
+        utils::setup_logger();
+        let to_biguint = |words: [u32; 8]| biguint_from_limbs(&words_to_bytes_le::<32>(&words));
+        for x in SQR_OPERANDS {
+            for modulus in SQR_MODULI {
+                let program = uint256_sqr_program(x, modulus, MODULUS_PTR, 1);
+                let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+                let reduced = modulus == [0; 8] || to_biguint(x) < to_biguint(modulus);
+                if !reduced {
+                    // The quotient of the square by the modulus may not fit in 256 bits.
+                    assert!(matches!(
+                        runtime.run(),
+                        Err(ExecutionError::UnreducedSyscallOperand(
+                            DST_PTR,
+                            MODULUS_PTR
+                        ))
+                    ));
+                    continue;
+                }
+                runtime.run().unwrap();
+                let result = read_uint256(&runtime, DST_PTR);
+                assert_eq!(runtime.record.uint256_sqr_events.len(), 1);
+
+                // The result matches `uint256_mul` with x as both operands, and the modulus after.
+                let mul_program = uint256_sqr_program(x, modulus, DST_PTR + 32, 0);
+                let mut instructions = mul_program.instructions;
+                instructions.extend(vec![
+                    Instruction::new(
+                        Opcode::ADD,
+                        5,
+                        0,
+                        SyscallCode::UINT256_MUL as u32,
+                        false,
+                        true,
+                    ),
+                    Instruction::new(Opcode::ADD, 10, 0, DST_PTR, false, true),
+                    Instruction::new(Opcode::ADD, 11, 0, DST_PTR, false, true),
+                    Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+                ]);
+                let mut runtime =
+                    Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+                runtime.run().unwrap();
+                assert_eq!(read_uint256(&runtime, DST_PTR), result);
+
+                let modulus = to_biguint(modulus);
+                let modulus = if modulus == BigUint::from(0u32) {
+                    BigUint::from(1u32) << 256
+                } else {
+                    modulus
+                };
+                let x = to_biguint(x);
+                assert_eq!(result, (&x * &x) % modulus);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "AliasingSyscallPointers")]
+    fn test_uint256_sqr_overlapping_operands() {
+        let program = uint256_sqr_program(SQR_OPERANDS[3], SECP256K1_N, DST_PTR + 16, 1);
+        Runtime::new(program, SP1CoreOpts::default()).run().unwrap();
+    }
+
+    #[test]
+    fn test_uint256_sqr_prove() {
+        utils::setup_logger();
+        // The operands are reduced, so the quotient of the square by the modulus fits in 256 bits.
+        let cases = [
+            (SQR_OPERANDS[3], SQR_MODULI[0]),
+            (SQR_OPERANDS[3], SQR_MODULI[1]),
+            ([5, 0, 0, 0, 0, 0, 0, 0], SQR_MODULI[2]),
+            (SQR_OPERANDS[3], SQR_MODULI[3]),
+            ([u32::MAX; 8], SQR_MODULI[0]),
+        ];
+        for (x, modulus) in cases {
+            run_test(uint256_sqr_program(x, modulus, MODULUS_PTR, 2)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_uint256_sqr_unreduced_operand() {
+        // The quotient of (2^256 - 1)^2 by 7 has 510 bits, which the chip cannot prove, so the
+        // execution fails instead of recording the square.
+        let program = uint256_sqr_program([u32::MAX; 8], SQR_MODULI[2], MODULUS_PTR, 1);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::UnreducedSyscallOperand(
+                DST_PTR,
+                MODULUS_PTR
+            ))
+        ));
+        assert!(runtime.record.uint256_sqr_events.is_empty());
+
+        // A modulus equal to x is not above it either.
+        let program = uint256_sqr_program(SQR_MODULI[1], SQR_MODULI[1], MODULUS_PTR, 1);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        assert!(matches!(
+            runtime.run(),
+            Err(ExecutionError::UnreducedSyscallOperand(
+                DST_PTR,
+                MODULUS_PTR
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_uint256_sqr_polynomial() {
+        let x = SQR_OPERANDS[3];
+        let p: Polynomial<BabyBear> = words_to_bytes_le::<32>(&x)
+            .iter()
+            .map(|&b| BabyBear::from_canonical_u8(b))
+            .collect();
+        assert_eq!(p.square(), &p * &p);
+
+        // The chip has no columns for a second operand.
+        assert!(NUM_UINT256_SQR_COLS < std::mem::size_of::<Uint256MulCols<u8>>());
+    }
 }
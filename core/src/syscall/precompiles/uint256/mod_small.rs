@@ -0,0 +1,481 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{pad_rows, words_to_bytes_le};
+use generic_array::GenericArray;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256ModSmallCols.
+const NUM_COLS: usize = size_of::<Uint256ModSmallCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The number of limbs of `quotient * m`, where the quotient is a uint256 and `m` is a word.
+const NUM_PRODUCT_LIMBS: usize = NUM_LIMBS + WORD_SIZE - 1;
+
+/// The number of carries of `quotient * m + remainder`. The carry out of the top limb is zero.
+const NUM_CARRIES: usize = NUM_PRODUCT_LIMBS - 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256ModSmallEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub m_ptr: u32,
+    pub x: Vec<u32>,
+    pub m: u32,
+    pub remainder: u32,
+    pub x_memory_records: Vec<MemoryReadRecord>,
+    pub m_memory_record: MemoryReadRecord,
+    pub remainder_memory_record: MemoryWriteRecord,
+}
+
+/// A precompile reducing a uint256 `x` modulo a nonzero word `m`.
+///
+/// The first argument points to `x`, and the second to two words: `m`, which is read, followed by
+/// the remainder, which is written. The result goes through memory rather than the return value of
+/// the ecall, since the CPU table constrains the return register to be unchanged by precompiles.
+///
+/// The quotient and the remainder are computed with Barrett reduction. The chip takes them as
+/// advice and verifies `x = quotient * m + remainder` limb by limb, with carries split into two
+/// range checked bytes, along with `remainder < m`, which also rules out `m == 0`.
+#[derive(Default)]
+pub struct Uint256ModSmallChip;
+
+impl Uint256ModSmallChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Uint256ModSmall operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256ModSmallCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the value.
+    pub x_ptr: T,
+
+    /// The pointer to the modulus, which is followed by the remainder.
+    pub m_ptr: T,
+
+    /// The value to reduce.
+    pub x_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The modulus.
+    pub m_memory: MemoryReadCols<T>,
+
+    /// The remainder, written after the modulus.
+    pub remainder_memory: MemoryWriteCols<T>,
+
+    /// The bytes of the quotient.
+    pub quotient: [T; NUM_LIMBS],
+
+    /// The low bytes of the carries of `quotient * m + remainder`.
+    pub carry_lo: [T; NUM_CARRIES],
+
+    /// The high bytes of the carries of `quotient * m + remainder`.
+    pub carry_hi: [T; NUM_CARRIES],
+
+    /// Boolean flags marking the most significant byte in which the remainder differs from the
+    /// modulus.
+    pub byte_flags: [T; WORD_SIZE],
+
+    /// The flagged byte of the remainder.
+    pub comparison_lhs: T,
+
+    /// The flagged byte of the modulus.
+    pub comparison_rhs: T,
+
+    pub is_real: T,
+}
+
+/// Divides the uint256 `x` by the nonzero word `m` with Barrett reduction, and returns the quotient
+/// and the remainder.
+///
+/// The reciprocal `floor(2^64 / m)` is computed once, and each word of `x` is reduced from the
+/// most significant one by multiplying the running value with it, which underestimates the
+/// quotient by at most two.
+pub fn uint256_div_small(x: &[u32], m: u32) -> ([u32; WORDS_FIELD_ELEMENT], u32) {
+    assert_ne!(m, 0, "uint256_mod_small modulus is zero");
+    let m = m as u64;
+    let reciprocal = (1u128 << 64) / m as u128;
+
+    let mut quotient = [0u32; WORDS_FIELD_ELEMENT];
+    let mut remainder = 0u64;
+    for i in (0..WORDS_FIELD_ELEMENT).rev() {
+        let current = (remainder << 32) | x[i] as u64;
+        let mut q = ((current as u128 * reciprocal) >> 64) as u64;
+        let mut r = current - q * m;
+        while r >= m {
+            r -= m;
+            q += 1;
+        }
+        quotient[i] = q as u32;
+        remainder = r;
+    }
+    (quotient, remainder as u32)
+}
+
+/// Returns the carries of `quotient * m + remainder`, limb by limb, which must equal `x`.
+fn mod_small_carries(
+    x: &[u8; NUM_LIMBS],
+    m: &[u8; WORD_SIZE],
+    quotient: &[u8; NUM_LIMBS],
+    remainder: &[u8; WORD_SIZE],
+) -> [u16; NUM_CARRIES] {
+    let mut carries = [0u16; NUM_CARRIES];
+    let mut carry_in = 0u32;
+    for k in 0..NUM_PRODUCT_LIMBS {
+        let mut sum = carry_in + remainder.get(k).map_or(0, |&byte| byte as u32);
+        for (j, &m_byte) in m.iter().enumerate() {
+            if k >= j && k - j < NUM_LIMBS {
+                sum += quotient[k - j] as u32 * m_byte as u32;
+            }
+        }
+        let x_byte = x.get(k).map_or(0, |&byte| byte as u32);
+        debug_assert_eq!((sum - x_byte) % 256, 0);
+        carry_in = (sum - x_byte) / 256;
+        if k < NUM_CARRIES {
+            carries[k] = carry_in as u16;
+        }
+    }
+    debug_assert_eq!(carry_in, 0);
+    carries
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256ModSmallChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256ModSmall".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .uint256_mod_small_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256ModSmallCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.m_ptr = F::from_canonical_u32(event.m_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.m_memory.populate(
+                    event.channel,
+                    event.m_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+                cols.remainder_memory.populate(
+                    event.channel,
+                    event.remainder_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+
+                // Populate the quotient and the carries.
+                let (quotient, remainder) = uint256_div_small(&event.x, event.m);
+                debug_assert_eq!(remainder, event.remainder);
+                let x_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.x);
+                let m_bytes = event.m.to_le_bytes();
+                let quotient_bytes = words_to_bytes_le::<NUM_LIMBS>(&quotient);
+                let remainder_bytes = remainder.to_le_bytes();
+                let carries =
+                    mod_small_carries(&x_bytes, &m_bytes, &quotient_bytes, &remainder_bytes);
+                let carry_lo = carries.map(|carry| carry as u8);
+                let carry_hi = carries.map(|carry| (carry >> 8) as u8);
+                cols.quotient = quotient_bytes.map(F::from_canonical_u8);
+                cols.carry_lo = carry_lo.map(F::from_canonical_u8);
+                cols.carry_hi = carry_hi.map(F::from_canonical_u8);
+                let range_checked = [
+                    &quotient_bytes[..],
+                    &carry_lo[..],
+                    &carry_hi[..],
+                    &remainder_bytes[..],
+                ];
+                for bytes in range_checked {
+                    new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, bytes);
+                }
+
+                // Populate the comparison of the remainder against the modulus.
+                for i in (0..WORD_SIZE).rev() {
+                    if remainder_bytes[i] != m_bytes[i] {
+                        cols.byte_flags[i] = F::one();
+                        cols.comparison_lhs = F::from_canonical_u8(remainder_bytes[i]);
+                        cols.comparison_rhs = F::from_canonical_u8(m_bytes[i]);
+                        new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                            opcode: ByteOpcode::LTU,
+                            shard: event.shard,
+                            channel: event.channel,
+                            a1: 1,
+                            a2: 0,
+                            b: remainder_bytes[i] as u32,
+                            c: m_bytes[i] as u32,
+                        });
+                        break;
+                    }
+                }
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256ModSmallCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_mod_small_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256ModSmallChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        let m_ptr = arg2;
+        if x_ptr % 4 != 0 || m_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the value and the modulus, and write the remainder after the modulus.
+        let (x_memory_records, x) = rt.mr_slice(x_ptr, WORDS_FIELD_ELEMENT);
+        let (m_memory_record, m) = rt.mr(m_ptr);
+        let (_, remainder) = uint256_div_small(&x, m);
+        let remainder_memory_record = rt.mw(m_ptr + WORD_SIZE as u32, remainder);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .uint256_mod_small_events
+            .push(Uint256ModSmallEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                x_ptr,
+                m_ptr,
+                x,
+                m,
+                remainder,
+                x_memory_records,
+                m_memory_record,
+                remainder_memory_record,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256ModSmallChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256ModSmallChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256ModSmallCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256ModSmallCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let x = local
+            .x_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        let m = local.m_memory.value().0;
+        let remainder = local.remainder_memory.value().0;
+
+        // Evaluate `quotient * m + remainder = x` limb by limb. The quotient and the carries are
+        // made of bytes, so the limb equations can't wrap around the field, and the carry out of
+        // the top limb is zero. Padding rows are all zero, so they satisfy the equations.
+        let base = AB::F::from_canonical_u32(256);
+        for k in 0..NUM_PRODUCT_LIMBS {
+            let mut lhs: AB::Expr = if k < WORD_SIZE {
+                remainder[k].into()
+            } else {
+                AB::Expr::zero()
+            };
+            for (j, &m_byte) in m.iter().enumerate() {
+                if k >= j && k - j < NUM_LIMBS {
+                    lhs += local.quotient[k - j] * m_byte;
+                }
+            }
+            if k > 0 {
+                lhs += local.carry_lo[k - 1] + local.carry_hi[k - 1] * base;
+            }
+            let mut rhs: AB::Expr = if k < NUM_LIMBS {
+                x[k].into()
+            } else {
+                AB::Expr::zero()
+            };
+            if k < NUM_CARRIES {
+                rhs += (local.carry_lo[k] + local.carry_hi[k] * base) * base;
+            }
+            builder.assert_eq(lhs, rhs);
+        }
+        for bytes in [
+            &local.quotient[..],
+            &local.carry_lo[..],
+            &local.carry_hi[..],
+            &remainder[..],
+        ] {
+            builder.slice_range_check_u8(bytes, local.shard, local.channel, local.is_real);
+        }
+
+        // Check that the remainder is below the modulus. The bytes above the flagged byte are
+        // equal, and the flagged byte of the remainder is below that of the modulus. Exactly one
+        // byte is flagged on real rows, so the modulus is nonzero.
+        let mut sum_flags = AB::Expr::zero();
+        let mut remainder_comparison_byte = AB::Expr::zero();
+        let mut m_comparison_byte = AB::Expr::zero();
+        for i in (0..WORD_SIZE).rev() {
+            let flag = local.byte_flags[i];
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            remainder_comparison_byte += remainder[i] * flag;
+            m_comparison_byte += m[i] * flag;
+
+            builder
+                .when(local.is_real)
+                .when_not(sum_flags.clone())
+                .assert_eq(remainder[i], m[i]);
+        }
+        builder.assert_eq(sum_flags, local.is_real);
+        builder.assert_eq(local.comparison_lhs, remainder_comparison_byte);
+        builder.assert_eq(local.comparison_rhs, m_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.comparison_lhs,
+            local.comparison_rhs,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Read the value and the modulus, and write the remainder.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.m_ptr,
+            &local.m_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.m_ptr + AB::F::from_canonical_usize(WORD_SIZE),
+            &local.remainder_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_MOD_SMALL.syscall_id()),
+            local.x_ptr,
+            local.m_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,469 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{pad_rows, words_to_bytes_le};
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the ScalarToNafCols.
+const NUM_COLS: usize = size_of::<ScalarToNafCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The width `w` of the NAF: every nonzero digit is odd, lies in `(-2^{w - 1}, 2^{w - 1})`, and is
+/// followed by at least `w - 1` zero digits.
+pub const NAF_WIDTH: usize = 4;
+
+/// The number of digits of the NAF of a uint256, which may be one digit longer than the scalar.
+pub const NUM_NAF_DIGITS: usize = NUM_LIMBS * 8 + 1;
+
+/// The number of words the digits are written to, one byte per digit.
+pub const NUM_NAF_WORDS: usize = NUM_NAF_DIGITS.div_ceil(WORD_SIZE);
+
+/// The bound `2^{w - 1}` on the absolute value of the digits.
+const NAF_HALF: i8 = 1 << (NAF_WIDTH - 1);
+
+/// The bound on the absolute value of the carries of the reconstruction of the scalar.
+const CARRY_BOUND: u32 = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalarToNafEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub scalar_ptr: u32,
+    pub naf_ptr: u32,
+    pub scalar: Vec<u32>,
+    pub scalar_memory_records: Vec<MemoryReadRecord>,
+    pub naf_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile recoding a uint256 scalar into its width-4 non-adjacent form.
+///
+/// The scalar `k` is recoded into `NUM_NAF_DIGITS` digits `d_i` with `k = sum d_i * 2^i`. Every
+/// nonzero digit is odd, lies in `[-7, 7]` and is followed by at least three zero digits, so on
+/// average only one digit in five is nonzero, and a scalar multiplication only needs the odd
+/// multiples `P, 3P, 5P, 7P` of the base point and one addition per nonzero digit.
+///
+/// The first argument points to the scalar, and the second to `NUM_NAF_WORDS` words to which the
+/// digits are written as two's complement bytes, with the unused bytes zeroed. The digits are
+/// computed by the host, and the chip checks that every digit is zero or `2h + 1` with `h` in
+/// `[-4, 4)`, that no two nonzero digits are less than four apart, and that
+/// `sum_t d_{8j + t} * 2^t + 256 * c_{j + 1} = k_j + c_j` for every byte `k_j` of the scalar, with
+/// small carries `c_j`, `c_0 = 0` and the last digit equal to `c_32`. The NAF is the only
+/// representation with these properties, so the digits are determined by the scalar.
+#[derive(Default)]
+pub struct ScalarToNafChip;
+
+impl ScalarToNafChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the ScalarToNaf operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct ScalarToNafCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the scalar.
+    pub scalar_ptr: T,
+
+    /// The pointer to the digits.
+    pub naf_ptr: T,
+
+    /// The scalar.
+    pub scalar_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The digits, as two's complement bytes.
+    pub naf_memory: [MemoryWriteCols<T>; NUM_NAF_WORDS],
+
+    /// Whether each digit is nonzero.
+    pub is_nonzero: [T; NUM_NAF_DIGITS],
+
+    /// Whether each digit is negative.
+    pub is_negative: [T; NUM_NAF_DIGITS],
+
+    /// The value `h` of each nonzero digit `2h + 1`.
+    pub half: [T; NUM_NAF_DIGITS],
+
+    /// The carries `c_1, ..., c_32` into each byte of the scalar, the last of which is the last
+    /// digit.
+    pub carries: [T; NUM_LIMBS],
+
+    pub is_real: T,
+}
+
+/// Recodes the scalar into its width-4 NAF, with `scalar = sum digits[i] * 2^i`.
+pub fn scalar_to_naf(scalar: &[u32]) -> [i8; NUM_NAF_DIGITS] {
+    let mut k = BigUint::from_slice(scalar);
+    let mut digits = [0i8; NUM_NAF_DIGITS];
+    for digit in digits.iter_mut() {
+        if k.bit(0) {
+            let window = (k.to_u32_digits()[0] % (1 << NAF_WIDTH)) as i8;
+            *digit = if window >= NAF_HALF { window - 2 * NAF_HALF } else { window };
+            if *digit > 0 {
+                k -= digit.unsigned_abs() as u32;
+            } else {
+                k += digit.unsigned_abs() as u32;
+            }
+        }
+        k >>= 1;
+    }
+    debug_assert!(k.is_zero());
+    digits
+}
+
+/// Returns the carries `c_1, ..., c_32` of the reconstruction of the scalar from the digits.
+fn naf_carries(scalar: &[u8; NUM_LIMBS], digits: &[i8; NUM_NAF_DIGITS]) -> [i32; NUM_LIMBS] {
+    let mut carries = [0i32; NUM_LIMBS];
+    let mut carry = 0i32;
+    for (j, &byte) in scalar.iter().enumerate() {
+        let limb = (0..8)
+            .map(|t| (digits[8 * j + t] as i32) << t)
+            .sum::<i32>();
+        let sum = byte as i32 + carry - limb;
+        debug_assert_eq!(sum % 256, 0);
+        carry = sum / 256;
+        carries[j] = carry;
+    }
+    debug_assert_eq!(carry, digits[NUM_NAF_DIGITS - 1] as i32);
+    carries
+}
+
+/// Returns the field element of a small signed integer.
+fn signed_to_field<F: PrimeField32>(value: i32) -> F {
+    if value < 0 {
+        -F::from_canonical_u32(value.unsigned_abs())
+    } else {
+        F::from_canonical_u32(value as u32)
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for ScalarToNafChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "ScalarToNaf".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .scalar_to_naf_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut ScalarToNafCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.scalar_ptr = F::from_canonical_u32(event.scalar_ptr);
+                cols.naf_ptr = F::from_canonical_u32(event.naf_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.scalar_memory[i].populate(
+                        event.channel,
+                        event.scalar_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..NUM_NAF_WORDS {
+                    cols.naf_memory[i].populate(
+                        event.channel,
+                        event.naf_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the decompositions of the digits and the carries.
+                let digits = scalar_to_naf(&event.scalar);
+                let scalar_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.scalar);
+                let carries = naf_carries(&scalar_bytes, &digits);
+                for (j, &carry) in carries.iter().enumerate() {
+                    cols.carries[j] = signed_to_field(carry);
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                        opcode: ByteOpcode::LTU,
+                        shard: event.shard,
+                        channel: event.channel,
+                        a1: 1,
+                        a2: 0,
+                        b: (carry + CARRY_BOUND as i32) as u32,
+                        c: 2 * CARRY_BOUND,
+                    });
+                }
+                for (i, &digit) in digits.iter().enumerate() {
+                    let half = if digit == 0 { 0 } else { (digit as i32 - 1) / 2 };
+                    cols.is_nonzero[i] = F::from_bool(digit != 0);
+                    cols.is_negative[i] = F::from_bool(digit < 0);
+                    cols.half[i] = signed_to_field(half);
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                        opcode: ByteOpcode::LTU,
+                        shard: event.shard,
+                        channel: event.channel,
+                        a1: 1,
+                        a2: 0,
+                        b: (half + NAF_HALF as i32 / 2) as u32,
+                        c: NAF_HALF as u32,
+                    });
+                }
+                let digit_bytes = digits.iter().map(|&digit| digit as u8).collect::<Vec<_>>();
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &digit_bytes,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut ScalarToNafCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.scalar_to_naf_events.is_empty()
+    }
+}
+
+impl Syscall for ScalarToNafChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let scalar_ptr = arg1;
+        let naf_ptr = arg2;
+        if scalar_ptr % 4 != 0 || naf_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the scalar and write its digits.
+        let (scalar_memory_records, scalar) = rt.mr_slice(scalar_ptr, WORDS_FIELD_ELEMENT);
+        let mut digit_bytes = [0u8; NUM_NAF_WORDS * WORD_SIZE];
+        for (byte, digit) in digit_bytes.iter_mut().zip(scalar_to_naf(&scalar)) {
+            *byte = digit as u8;
+        }
+        let digit_words = digit_bytes
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let naf_memory_records = rt.mw_slice(naf_ptr, &digit_words);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().scalar_to_naf_events.push(ScalarToNafEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            scalar_ptr,
+            naf_ptr,
+            scalar,
+            scalar_memory_records,
+            naf_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for ScalarToNafChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for ScalarToNafChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &ScalarToNafCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &ScalarToNafCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let scalar = local
+            .scalar_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        let digit_bytes = local
+            .naf_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+
+        // Every digit is zero or `2h + 1` with `h` in `[-4, 4)`, and is written as a byte `b` with
+        // a sign `s`, so that `b = d + 256 * s`. Given that `b` is a byte, this determines `s`.
+        let base = AB::F::from_canonical_u32(256);
+        let two = AB::F::two();
+        let half_bound = AB::F::from_canonical_u8(NAF_HALF as u8 / 2);
+        let mut digits: Vec<AB::Expr> = vec![];
+        for i in 0..NUM_NAF_DIGITS {
+            builder.assert_bool(local.is_nonzero[i]);
+            builder.assert_bool(local.is_negative[i]);
+            let digit = local.is_nonzero[i] * (local.half[i] * two + AB::Expr::one());
+            builder.assert_eq(
+                digit_bytes[i],
+                digit.clone() + local.is_negative[i] * base,
+            );
+            builder.send_byte(
+                ByteOpcode::LTU.as_field::<AB::F>(),
+                AB::F::one(),
+                local.half[i] + half_bound,
+                half_bound + half_bound,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            digits.push(digit);
+        }
+        builder.slice_range_check_u8(
+            &digit_bytes[..NUM_NAF_DIGITS],
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        for &byte in digit_bytes[NUM_NAF_DIGITS..].iter() {
+            builder.assert_zero(byte);
+        }
+
+        // A nonzero digit is followed by at least `w - 1` zero digits.
+        for i in 0..NUM_NAF_DIGITS {
+            for j in i + 1..(i + NAF_WIDTH).min(NUM_NAF_DIGITS) {
+                builder.assert_zero(local.is_nonzero[i] * local.is_nonzero[j]);
+            }
+        }
+
+        // Reconstruct the scalar byte by byte. All the terms are small, so the equations hold over
+        // the integers. Padding rows are all zero, so they satisfy the equations.
+        let carry_bound = AB::F::from_canonical_u32(CARRY_BOUND);
+        for j in 0..NUM_LIMBS {
+            builder.send_byte(
+                ByteOpcode::LTU.as_field::<AB::F>(),
+                AB::F::one(),
+                local.carries[j] + carry_bound,
+                carry_bound + carry_bound,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            let carry_in: AB::Expr = if j > 0 {
+                local.carries[j - 1].into()
+            } else {
+                AB::Expr::zero()
+            };
+            let limb = (0..8).fold(AB::Expr::zero(), |limb, t| {
+                limb + digits[8 * j + t].clone() * AB::F::from_canonical_u32(1 << t)
+            });
+            builder.assert_eq(limb + local.carries[j] * base, scalar[j] + carry_in);
+        }
+
+        // The last digit is the final carry.
+        builder.assert_eq(
+            digits[NUM_NAF_DIGITS - 1].clone(),
+            local.carries[NUM_LIMBS - 1],
+        );
+
+        // Read the scalar and write the digits.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.scalar_ptr,
+            &local.scalar_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.naf_ptr,
+            &local.naf_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::SCALAR_TO_NAF.syscall_id()),
+            local.scalar_ptr,
+            local.naf_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,446 @@
+use crate::air::{BaseAirBuilder, MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{pad_rows, words_to_bytes_le, words_to_bytes_le_vec};
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the ScalarInRangeCols.
+const NUM_COLS: usize = size_of::<ScalarInRangeCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalarInRangeEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub io_ptr: u32,
+    pub modulus_ptr: u32,
+    pub x: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub in_range: bool,
+    pub x_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_ptr_memory_record: MemoryReadRecord,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+    pub result_memory_record: MemoryWriteRecord,
+}
+
+/// A precompile checking that a uint256 `x` is strictly between zero and a uint256 modulus, such
+/// as the range `0 < r, s < n` of the components of an ECDSA signature.
+///
+/// The first argument points to `x`, and the second to a pair of words: the pointer to the
+/// modulus, followed by the word the result is written to, since the return register of a
+/// precompile is left unchanged. The result is one if `0 < x < modulus`, and zero otherwise.
+///
+/// The comparison flags the most significant byte in which `x` differs from the modulus, and no
+/// byte if they are equal. `x` is nonzero if the sum of its bytes is.
+#[derive(Default)]
+pub struct ScalarInRangeChip;
+
+impl ScalarInRangeChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the ScalarInRange operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct ScalarInRangeCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x.
+    pub x_ptr: T,
+
+    /// The pointer to the pointer to the modulus, followed by the result.
+    pub io_ptr: T,
+
+    /// The value to check.
+    pub x_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The pointer to the modulus.
+    pub modulus_ptr_memory: MemoryReadCols<T>,
+
+    /// The modulus.
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The result, written after the pointer to the modulus.
+    pub result_memory: MemoryWriteCols<T>,
+
+    /// Whether the sum of the bytes of x is zero, that is whether x is zero.
+    pub x_is_zero: IsZeroOperation<T>,
+
+    /// Boolean flags marking the most significant byte in which x differs from the modulus.
+    pub byte_flags: [T; NUM_LIMBS],
+
+    /// The smaller of the flagged bytes.
+    pub comparison_lhs: T,
+
+    /// The larger of the flagged bytes.
+    pub comparison_rhs: T,
+
+    /// Whether x is below the modulus.
+    pub is_below: T,
+
+    /// Whether x is nonzero and below the modulus.
+    pub in_range: T,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for ScalarInRangeChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "ScalarInRange".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .scalar_in_range_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut ScalarInRangeCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.io_ptr = F::from_canonical_u32(event.io_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                cols.modulus_ptr_memory.populate(
+                    event.channel,
+                    event.modulus_ptr_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+                cols.result_memory.populate(
+                    event.channel,
+                    event.result_memory_record,
+                    &mut new_byte_lookup_events,
+                );
+
+                // Populate the zero check of x.
+                let x_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.x);
+                let x_byte_sum = x_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.x_is_zero, x_byte_sum);
+
+                // Populate the comparison of x against the modulus.
+                let modulus_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.modulus);
+                let mut is_below = false;
+                for i in (0..NUM_LIMBS).rev() {
+                    if x_bytes[i] != modulus_bytes[i] {
+                        is_below = x_bytes[i] < modulus_bytes[i];
+                        let (lhs, rhs) = if is_below {
+                            (x_bytes[i], modulus_bytes[i])
+                        } else {
+                            (modulus_bytes[i], x_bytes[i])
+                        };
+                        cols.byte_flags[i] = F::one();
+                        cols.comparison_lhs = F::from_canonical_u8(lhs);
+                        cols.comparison_rhs = F::from_canonical_u8(rhs);
+                        new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                            opcode: ByteOpcode::LTU,
+                            shard: event.shard,
+                            channel: event.channel,
+                            a1: 1,
+                            a2: 0,
+                            b: lhs as u32,
+                            c: rhs as u32,
+                        });
+                        break;
+                    }
+                }
+                cols.is_below = F::from_bool(is_below);
+                cols.in_range = F::from_bool(event.in_range);
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut ScalarInRangeCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.scalar_in_range_events.is_empty()
+    }
+}
+
+impl Syscall for ScalarInRangeChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        rt.require_aligned(x_ptr, self.alignment()).unwrap();
+        let io_ptr = arg2;
+        rt.require_aligned(io_ptr, self.alignment()).unwrap();
+        let num_bytes = NUM_LIMBS as u32;
+        let result_ptr = io_ptr + WORD_SIZE as u32;
+        rt.require_disjoint(x_ptr, num_bytes, result_ptr, WORD_SIZE as u32)
+            .unwrap();
+
+        // Read x, then the modulus through its pointer.
+        let (x_memory_records, x) = rt.mr_slice(x_ptr, WORDS_FIELD_ELEMENT);
+        let (modulus_ptr_memory_record, modulus_ptr) = rt.mr(io_ptr);
+        rt.require_aligned(modulus_ptr, self.alignment()).unwrap();
+        rt.require_disjoint(x_ptr, num_bytes, modulus_ptr, num_bytes)
+            .unwrap();
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+        let in_range = !uint256_x.is_zero() && uint256_x < uint256_modulus;
+        let result_memory_record = rt.mw(result_ptr, in_range as u32);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut()
+            .scalar_in_range_events
+            .push(ScalarInRangeEvent {
+                lookup_id,
+                shard,
+                channel,
+                clk,
+                x_ptr,
+                io_ptr,
+                modulus_ptr,
+                x,
+                modulus,
+                in_range,
+                x_memory_records,
+                modulus_ptr_memory_record,
+                modulus_memory_records,
+                result_memory_record,
+            });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for ScalarInRangeChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for ScalarInRangeChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &ScalarInRangeCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &ScalarInRangeCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let x = local
+            .x_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        let modulus = local
+            .modulus_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+
+        // Check whether x is zero by summing its bytes. The sum of 32 bytes does not overflow.
+        let x_byte_sum = x.iter().fold(AB::Expr::zero(), |acc, &byte| acc + byte);
+        IsZeroOperation::<AB::F>::eval(builder, x_byte_sum, local.x_is_zero, local.is_real.into());
+
+        // Compare x against the modulus. The bytes above the flagged byte are equal, and of the
+        // flagged bytes, that of x is the smaller one if x is below the modulus. With no flag set
+        // x is equal to the modulus, so it is not below it.
+        builder.assert_bool(local.is_below);
+        let mut sum_flags = AB::Expr::zero();
+        let mut x_comparison_byte = AB::Expr::zero();
+        let mut modulus_comparison_byte = AB::Expr::zero();
+        for i in (0..NUM_LIMBS).rev() {
+            let flag = local.byte_flags[i];
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            x_comparison_byte += x[i] * flag;
+            modulus_comparison_byte += modulus[i] * flag;
+
+            builder
+                .when(local.is_real)
+                .when_not(sum_flags.clone())
+                .assert_eq(x[i], modulus[i]);
+        }
+        builder.assert_bool(sum_flags.clone());
+        builder
+            .when_not(local.is_real)
+            .assert_zero(sum_flags.clone());
+        builder
+            .when(local.is_real)
+            .when_not(sum_flags.clone())
+            .assert_zero(local.is_below);
+        builder
+            .when(local.is_below)
+            .assert_eq(local.comparison_lhs, x_comparison_byte.clone());
+        builder
+            .when(local.is_below)
+            .assert_eq(local.comparison_rhs, modulus_comparison_byte.clone());
+        builder
+            .when_not(local.is_below)
+            .assert_eq(local.comparison_lhs, modulus_comparison_byte);
+        builder
+            .when_not(local.is_below)
+            .assert_eq(local.comparison_rhs, x_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.comparison_lhs,
+            local.comparison_rhs,
+            local.shard,
+            local.channel,
+            sum_flags,
+        );
+
+        // Assert that x is in range if and only if it is nonzero and below the modulus, and that
+        // the result written is that flag.
+        builder.when(local.is_real).assert_eq(
+            local.in_range,
+            local.is_below * (AB::Expr::one() - local.x_is_zero.result),
+        );
+        let result = local.result_memory.value();
+        builder
+            .when(local.is_real)
+            .assert_eq(result[0], local.in_range);
+        for i in 1..WORD_SIZE {
+            builder.when(local.is_real).assert_zero(result[i]);
+        }
+
+        // Read x and the modulus, and write the result.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.io_ptr,
+            &local.modulus_ptr_memory,
+            local.is_real,
+        );
+        let modulus_ptr = local.modulus_ptr_memory.value().reduce::<AB>();
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.io_ptr + AB::F::from_canonical_usize(WORD_SIZE),
+            &local.result_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::SCALAR_IN_RANGE.syscall_id()),
+            local.x_ptr,
+            local.io_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
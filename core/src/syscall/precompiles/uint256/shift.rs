@@ -0,0 +1,494 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::operations::IsZeroOperation;
+use crate::runtime::{ExecutionRecord, MemoryWriteRecord, Program, Syscall, SyscallCode};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{bytes_to_words_le, pad_rows, words_to_bytes_le};
+use generic_array::GenericArray;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256ShiftCols.
+const NUM_COLS: usize = size_of::<Uint256ShiftCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_BYTES: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The exclusive upper bound on the shift amount of a `UINT256_SHL` or `UINT256_SHR` call.
+pub const UINT256_SHIFT_MAX: u32 = 1 << 16;
+
+/// The direction of the shift of a [`Uint256ShiftChip`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShiftDirection {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256ShiftEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub shift: u32,
+    pub x: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// Shifts the uint256 `x`, given as little-endian words, by `shift` bits in `direction`. Shifts of
+/// 256 bits or more give zero.
+pub fn uint256_shift(x: &[u32], shift: u32, direction: ShiftDirection) -> [u32; 8] {
+    let bytes = words_to_bytes_le::<NUM_BYTES>(x);
+    let value = u128::from_le_bytes(bytes[..16].try_into().unwrap());
+    let high = u128::from_le_bytes(bytes[16..].try_into().unwrap());
+    let (low, high) = match direction {
+        _ if shift >= 256 => (0, 0),
+        _ if shift == 0 => (value, high),
+        ShiftDirection::Left if shift >= 128 => (0, value << (shift - 128)),
+        ShiftDirection::Left => (value << shift, high << shift | value >> (128 - shift)),
+        ShiftDirection::Right if shift >= 128 => (high >> (shift - 128), 0),
+        ShiftDirection::Right => (value >> shift | high << (128 - shift), high >> shift),
+    };
+    let mut result = [0u8; NUM_BYTES];
+    result[..16].copy_from_slice(&low.to_le_bytes());
+    result[16..].copy_from_slice(&high.to_le_bytes());
+    bytes_to_words_le::<8>(&result)
+}
+
+/// A precompile for logical shifts of a uint256 by a number of bits, in place.
+///
+/// The first argument points to `x`, and the second is the shift amount itself, which must be less
+/// than 2^16. Shifts of 256 bits or more give zero.
+///
+/// The shift is split into a number of whole bytes and of remaining bits. The bytes of `x` are
+/// first moved by the whole bytes, selected by a one-hot flag. Each moved byte is then multiplied
+/// by `2^bits` for left shifts, or `2^(8 - bits)` for right shifts, and the product split into a
+/// low and a high byte with byte lookups. A byte of the result is the sum of a part of one byte and
+/// the part of its neighbour which crossed over into it.
+pub struct Uint256ShiftChip {
+    direction: ShiftDirection,
+}
+
+impl Uint256ShiftChip {
+    pub const fn shl() -> Self {
+        Self {
+            direction: ShiftDirection::Left,
+        }
+    }
+
+    pub const fn shr() -> Self {
+        Self {
+            direction: ShiftDirection::Right,
+        }
+    }
+
+    fn syscall_code(&self) -> SyscallCode {
+        match self.direction {
+            ShiftDirection::Left => SyscallCode::UINT256_SHL,
+            ShiftDirection::Right => SyscallCode::UINT256_SHR,
+        }
+    }
+
+    fn events<'a>(&self, record: &'a ExecutionRecord) -> &'a [Uint256ShiftEvent] {
+        match self.direction {
+            ShiftDirection::Left => &record.uint256_shl_events,
+            ShiftDirection::Right => &record.uint256_shr_events,
+        }
+    }
+
+    /// The index of the byte of `x` moved to byte `i` by a shift of `num_bytes` whole bytes, if
+    /// any.
+    fn source_byte(&self, i: usize, num_bytes: usize) -> Option<usize> {
+        match self.direction {
+            ShiftDirection::Left => i.checked_sub(num_bytes),
+            ShiftDirection::Right => Some(i + num_bytes).filter(|&j| j < NUM_BYTES),
+        }
+    }
+}
+
+/// A set of columns for the Uint256Shift operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256ShiftCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x.
+    pub x_ptr: T,
+
+    /// The shift amount.
+    pub shift: T,
+
+    /// The bits of the low byte of the shift amount. The lowest three are the remaining bits, and
+    /// the others the number of whole bytes.
+    pub shift_bits: [T; 8],
+
+    /// The shift amount divided by 256.
+    pub shift_high: T,
+
+    /// Whether the shift amount is below 256.
+    pub shift_high_is_zero: IsZeroOperation<T>,
+
+    /// One-hot flags selecting the number of whole bytes, all zero for shifts of 256 bits or more.
+    pub byte_shift_flags: [T; NUM_BYTES],
+
+    /// The factor of the first two remaining bits in the bit multiplier.
+    pub bit_multiplier_low: T,
+
+    /// `2^bits` for left shifts, or `2^(8 - bits)` for right shifts.
+    pub bit_multiplier: T,
+
+    /// The bytes of x moved by the whole bytes.
+    pub shifted_bytes: [T; NUM_BYTES],
+
+    /// The low bytes of the shifted bytes times the bit multiplier.
+    pub low: [T; NUM_BYTES],
+
+    /// The high bytes of the shifted bytes times the bit multiplier.
+    pub high: [T; NUM_BYTES],
+
+    /// x, overwritten with the result.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256ShiftChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match self.direction {
+            ShiftDirection::Left => "Uint256Shl".to_string(),
+            ShiftDirection::Right => "Uint256Shr".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = self
+            .events(input)
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256ShiftCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.shift = F::from_canonical_u32(event.shift);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Decompose the shift amount.
+                let shift_low = event.shift & 0xff;
+                let shift_high = event.shift >> 8;
+                for j in 0..8 {
+                    cols.shift_bits[j] = F::from_canonical_u32((shift_low >> j) & 1);
+                }
+                cols.shift_high = F::from_canonical_u32(shift_high);
+                IsZeroOperation::populate(&mut cols.shift_high_is_zero, shift_high);
+
+                // Compute the bit multiplier from the remaining bits.
+                let bits = shift_low & 0x7;
+                let (multiplier_low, multiplier) = match self.direction {
+                    ShiftDirection::Left => (1 << (bits & 0x3), 1 << bits),
+                    ShiftDirection::Right => (2 << (3 - (bits & 0x3)), 1 << (8 - bits)),
+                };
+                cols.bit_multiplier_low = F::from_canonical_u32(multiplier_low);
+                cols.bit_multiplier = F::from_canonical_u32(multiplier);
+
+                // Move the bytes of x by the whole bytes, and split them times the multiplier.
+                let x_bytes = words_to_bytes_le::<NUM_BYTES>(&event.x);
+                let num_bytes = (shift_high == 0).then_some(shift_low as usize >> 3);
+                if let Some(num_bytes) = num_bytes {
+                    cols.byte_shift_flags[num_bytes] = F::one();
+                }
+                let mut low = [0u8; NUM_BYTES];
+                let mut high = [0u8; NUM_BYTES];
+                for i in 0..NUM_BYTES {
+                    let shifted = num_bytes
+                        .and_then(|num_bytes| self.source_byte(i, num_bytes))
+                        .map_or(0, |j| x_bytes[j]);
+                    let product = shifted as u32 * multiplier;
+                    low[i] = product as u8;
+                    high[i] = (product >> 8) as u8;
+                    cols.shifted_bytes[i] = F::from_canonical_u8(shifted);
+                    cols.low[i] = F::from_canonical_u8(low[i]);
+                    cols.high[i] = F::from_canonical_u8(high[i]);
+                }
+                let range_checked = [&low[..], &high[..], &[shift_high as u8]].concat();
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &range_checked,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256ShiftCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !self.events(shard).is_empty()
+    }
+}
+
+impl Syscall for Uint256ShiftChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        rt.require_aligned(x_ptr, self.alignment()).unwrap();
+        let shift = arg2;
+        if shift >= UINT256_SHIFT_MAX {
+            panic!("uint256 shift amount {} is not less than 2^16", shift);
+        }
+
+        // Read x, which is overwritten with the result.
+        let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
+        let result = uint256_shift(&x, shift, self.direction);
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        let event = Uint256ShiftEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            shift,
+            x,
+            x_memory_records,
+        };
+        match self.direction {
+            ShiftDirection::Left => rt.record_mut().uint256_shl_events.push(event),
+            ShiftDirection::Right => rt.record_mut().uint256_shr_events.push(event),
+        }
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256ShiftChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256ShiftChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256ShiftCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256ShiftCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // Decompose the shift amount into the bits of its low byte, and the byte above them. The
+        // amount is less than 2^16, so the decomposition is unique.
+        let mut shift_low = AB::Expr::zero();
+        for (j, &bit) in local.shift_bits.iter().enumerate() {
+            builder.assert_bool(bit);
+            shift_low += bit * AB::F::from_canonical_u32(1 << j);
+        }
+        builder.when(local.is_real).assert_eq(
+            local.shift,
+            shift_low + local.shift_high * AB::F::from_canonical_u32(1 << 8),
+        );
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            local.shift_high.into(),
+            local.shift_high_is_zero,
+            local.is_real.into(),
+        );
+        let is_small = local.shift_high_is_zero.result;
+
+        // Exactly one number of whole bytes is flagged for shifts below 256 bits, and none for
+        // larger shifts, which moves every byte out.
+        let mut sum_flags = AB::Expr::zero();
+        let mut flagged_bytes = AB::Expr::zero();
+        for (k, &flag) in local.byte_shift_flags.iter().enumerate() {
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            flagged_bytes += flag * AB::F::from_canonical_usize(k);
+        }
+        let num_bytes = (3..8).fold(AB::Expr::zero(), |acc, j| {
+            acc + local.shift_bits[j] * AB::F::from_canonical_u32(1 << (j - 3))
+        });
+        builder.when(local.is_real).assert_eq(sum_flags, is_small);
+        builder
+            .when(local.is_real)
+            .assert_eq(flagged_bytes, num_bytes * is_small);
+
+        // The bit multiplier is 2^bits for left shifts, and 2^(8 - bits) for right shifts.
+        let [b0, b1, b2] = [local.shift_bits[0], local.shift_bits[1], local.shift_bits[2]];
+        let one = AB::Expr::one();
+        let (multiplier_low, multiplier_high) = match self.direction {
+            ShiftDirection::Left => (
+                (one.clone() + b0) * (one.clone() + b1 * AB::F::from_canonical_u32(3)),
+                one.clone() + b2 * AB::F::from_canonical_u32(15),
+            ),
+            ShiftDirection::Right => (
+                AB::Expr::two()
+                    * (AB::Expr::two() - b0)
+                    * (AB::Expr::from_canonical_u32(4) - b1 * AB::F::from_canonical_u32(3)),
+                AB::Expr::from_canonical_u32(16) - b2 * AB::F::from_canonical_u32(15),
+            ),
+        };
+        builder
+            .when(local.is_real)
+            .assert_eq(local.bit_multiplier_low, multiplier_low);
+        builder.when(local.is_real).assert_eq(
+            local.bit_multiplier,
+            local.bit_multiplier_low * multiplier_high,
+        );
+
+        // Move the bytes of x by the flagged number of whole bytes.
+        let x = local
+            .x_memory
+            .iter()
+            .flat_map(|access| access.prev_value().0)
+            .collect::<Vec<_>>();
+        for i in 0..NUM_BYTES {
+            let shifted = (0..NUM_BYTES).fold(AB::Expr::zero(), |acc, k| {
+                match self.source_byte(i, k) {
+                    Some(j) => acc + local.byte_shift_flags[k] * x[j],
+                    None => acc,
+                }
+            });
+            builder
+                .when(local.is_real)
+                .assert_eq(local.shifted_bytes[i], shifted);
+        }
+
+        // Split each moved byte times the bit multiplier into a low and a high byte. The product
+        // is less than 2^16, so the split is unique.
+        for i in 0..NUM_BYTES {
+            builder.when(local.is_real).assert_eq(
+                local.shifted_bytes[i] * local.bit_multiplier,
+                local.low[i] + local.high[i] * AB::F::from_canonical_u32(1 << 8),
+            );
+        }
+        let range_checked = local
+            .low
+            .iter()
+            .chain(local.high.iter())
+            .chain(std::iter::once(&local.shift_high))
+            .copied()
+            .collect::<Vec<_>>();
+        builder.slice_range_check_u8(&range_checked, local.shard, local.channel, local.is_real);
+
+        // A byte of the result is the part of a moved byte which stays in it, plus the part of its
+        // neighbour which crosses over: the high byte of the one below for left shifts, and the
+        // low byte of the one above for right shifts.
+        let result = local
+            .x_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        for i in 0..NUM_BYTES {
+            let expected = match self.direction {
+                ShiftDirection::Left if i == 0 => local.low[i].into(),
+                ShiftDirection::Left => local.low[i] + local.high[i - 1],
+                ShiftDirection::Right if i == NUM_BYTES - 1 => local.high[i].into(),
+                ShiftDirection::Right => local.high[i] + local.low[i + 1],
+            };
+            builder.when(local.is_real).assert_eq(result[i], expected);
+        }
+
+        // Read and write x.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments. The second argument is the shift amount itself.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(self.syscall_code().syscall_id()),
+            local.x_ptr,
+            local.shift,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::{DisjointRangesOperation, IsZeroOperation};
+use crate::runtime::{ExecutionError, ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+#[cfg(feature = "debug")]
+use crate::stark::debug_trace_constraints;
+use crate::stark::{log_sampled_rows, trace_sample_interval};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{
+    bytes_to_words_le, limbs_from_access, limbs_from_prev_access, pad_rows, words_to_bytes_le,
+    words_to_bytes_le_vec,
+};
+use generic_array::GenericArray;
+use num::Zero;
+use num::{BigUint, One};
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256SqrCols.
+pub const NUM_UINT256_SQR_COLS: usize = size_of::<Uint256SqrCols<u8>>();
+
+/// The number of bytes of x and of the modulus, whose regions must not overlap.
+const OPERAND_BYTES: u32 = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256SqrEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub modulus_ptr: u32,
+    pub modulus: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A chip which squares a uint256 modulo another one in place, `x = x^2 mod modulus`, where a zero
+/// modulus stands for `2^256`.
+///
+/// The result is the same as `uint256_mul` with `x` as both operands, but the chip has no columns
+/// for a second operand. The reduction has the same witness columns as the multiplication, and
+/// only its constraint is cheaper to evaluate, since [`Polynomial::square`] computes each cross
+/// product of limbs once.
+///
+/// Unless the modulus is zero, `x` must be below it, so that the quotient of the square by the
+/// modulus fits in the 256 bits of its columns. The execution fails with
+/// [`ExecutionError::UnreducedSyscallOperand`] otherwise.
+#[derive(Default)]
+pub struct Uint256SqrChip;
+
+impl Uint256SqrChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// A set of columns for the Uint256Sqr operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256SqrCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to x, which is overwritten with the result.
+    pub x_ptr: T,
+
+    /// The pointer to the modulus.
+    pub modulus_ptr: T,
+
+    // Memory columns.
+    // x_memory is written to with the result, which is why it is of type MemoryWriteCols.
+    pub x_memory: GenericArray<MemoryWriteCols<T>, WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    // Columns for checking that x does not overlap the modulus.
+    pub ptrs_disjoint: DisjointRangesOperation<T>,
+
+    // Columns for checking if modulus is zero. If it's zero, then use 2^256 as the effective modulus.
+    pub modulus_is_zero: IsZeroOperation<T>,
+
+    // Output values. We compute (x * x) % modulus.
+    pub output: FieldOpCols<T, U256Field>,
+
+    pub is_real: T,
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256SqrChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256Sqr".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+        let mut rows = input
+            .uint256_sqr_events
+            .iter()
+            .map(|event| {
+                let mut row = [F::zero(); NUM_UINT256_SQR_COLS];
+                let cols: &mut Uint256SqrCols<F> = row.as_mut_slice().borrow_mut();
+
+                let x = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.x));
+                let modulus = BigUint::from_bytes_le(&words_to_bytes_le::<32>(&event.modulus));
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+                cols.modulus_ptr = F::from_canonical_u32(event.modulus_ptr);
+                let disjoint = cols.ptrs_disjoint.populate(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    event.x_ptr,
+                    OPERAND_BYTES,
+                    event.modulus_ptr,
+                    OPERAND_BYTES,
+                    false,
+                );
+                assert!(
+                    disjoint,
+                    "uint256_sqr operands at {:#x} and {:#x} overlap or are too far apart",
+                    event.x_ptr, event.modulus_ptr
+                );
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.x_memory[i].populate(
+                        event.channel,
+                        event.x_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                    cols.modulus_memory[i].populate(
+                        event.channel,
+                        event.modulus_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                let modulus_bytes = words_to_bytes_le_vec(&event.modulus);
+                let modulus_byte_sum = modulus_bytes.iter().map(|b| *b as u32).sum::<u32>();
+                IsZeroOperation::populate(&mut cols.modulus_is_zero, modulus_byte_sum);
+
+                // Populate the output column.
+                let effective_modulus = if modulus.is_zero() {
+                    BigUint::one() << 256
+                } else {
+                    modulus
+                };
+                cols.output.populate_square_with_modulus(
+                    &mut new_byte_lookup_events,
+                    event.shard,
+                    event.channel,
+                    &x,
+                    &effective_modulus,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row = [F::zero(); NUM_UINT256_SQR_COLS];
+            let cols: &mut Uint256SqrCols<F> = row.as_mut_slice().borrow_mut();
+
+            // The padded rows square zero modulo the modulus of the field parameters.
+            cols.output.populate_square_with_modulus(
+                &mut vec![],
+                0,
+                0,
+                &BigUint::zero(),
+                &U256Field::modulus(),
+            );
+
+            row
+        });
+
+        // Convert the trace to a row major matrix.
+        let mut trace = RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_UINT256_SQR_COLS,
+        );
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256SqrCols<F> =
+                trace.values[i * NUM_UINT256_SQR_COLS..(i + 1) * NUM_UINT256_SQR_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        // Check the populated rows against the constraints when debugging.
+        #[cfg(feature = "debug")]
+        debug_trace_constraints(self, &trace);
+
+        // Log a sample of the real rows if requested.
+        if let Some(interval) = trace_sample_interval() {
+            log_sampled_rows::<F, Uint256SqrCols<F>>(
+                &MachineAir::<F>::name(self),
+                &trace,
+                input.uint256_sqr_events.len(),
+                interval,
+            );
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_sqr_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256SqrChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn alignment(&self) -> u32 {
+        4
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let x_ptr = arg1;
+        let modulus_ptr = arg2;
+        let checked = rt
+            .require_aligned(x_ptr, self.alignment())
+            .and_then(|_| rt.require_aligned(modulus_ptr, self.alignment()))
+            .and_then(|_| rt.require_disjoint(x_ptr, OPERAND_BYTES, modulus_ptr, OPERAND_BYTES));
+        if let Err(err) = checked {
+            rt.set_error(err);
+            return None;
+        }
+
+        // First read the words for the x value. We can read a slice_unsafe here because we write
+        // the computed result to x later.
+        let x = rt.slice_unsafe(x_ptr, WORDS_FIELD_ELEMENT);
+
+        // Read the modulus, which does not overlap x, so x can be written in the same cycle.
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, WORDS_FIELD_ELEMENT);
+
+        let uint256_x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let uint256_modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+
+        // The quotient of the square by the modulus only fits in 256 bits for a reduced x.
+        if !uint256_modulus.is_zero() && uint256_x >= uint256_modulus {
+            rt.set_error(ExecutionError::UnreducedSyscallOperand(x_ptr, modulus_ptr));
+            return None;
+        }
+
+        // Square x and take the result modulo the modulus.
+        let result: BigUint = if uint256_modulus.is_zero() {
+            (&uint256_x * &uint256_x) % (BigUint::one() << 256)
+        } else {
+            (&uint256_x * &uint256_x) % uint256_modulus
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(32, 0u8); // Pad the result to 32 bytes.
+
+        // Convert the result to little endian u32 words, and write them to x.
+        let result = bytes_to_words_le::<8>(&result_bytes);
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().uint256_sqr_events.push(Uint256SqrEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            x_ptr,
+            x,
+            modulus_ptr,
+            modulus,
+            x_memory_records,
+            modulus_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256SqrChip {
+    fn width(&self) -> usize {
+        NUM_UINT256_SQR_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256SqrChip
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <U256Field as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256SqrCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256SqrCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        // We are computing (x * x) % modulus. The value of x is stored in the "prev_value" of
+        // the x_memory, since we write to it later.
+        let x_limbs = limbs_from_prev_access(&local.x_memory);
+        let modulus_limbs = limbs_from_access(&local.modulus_memory);
+
+        // If the modulus is zero, then we don't perform the modulus operation. The byte sum of the
+        // modulus does not overflow because we are summing 32 bytes.
+        let modulus_byte_sum = modulus_limbs
+            .0
+            .iter()
+            .fold(AB::Expr::zero(), |acc, &limb| acc + limb);
+        IsZeroOperation::<AB::F>::eval(
+            builder,
+            modulus_byte_sum,
+            local.modulus_is_zero,
+            local.is_real.into(),
+        );
+
+        // If the modulus is zero, we'll actually use 2^256 as the modulus, so nothing happens.
+        // Otherwise, we use the modulus passed in.
+        let modulus_is_zero = local.modulus_is_zero.result;
+        let mut coeff_2_256 = Vec::new();
+        coeff_2_256.resize(32, AB::Expr::zero());
+        coeff_2_256.push(AB::Expr::one());
+        let modulus_polynomial: Polynomial<AB::Expr> = modulus_limbs.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus_polynomial
+            * (AB::Expr::one() - modulus_is_zero.into())
+            + Polynomial::from_coefficients(&coeff_2_256) * modulus_is_zero.into();
+
+        // Evaluate the uint256 square.
+        local.output.eval_square_with_modulus(
+            builder,
+            &x_limbs,
+            &p_modulus,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.output.result, value_as_limbs(&local.x_memory));
+
+        // Assert that x does not overlap the modulus.
+        DisjointRangesOperation::<AB::F>::eval(
+            builder,
+            local.x_ptr.into(),
+            OPERAND_BYTES,
+            local.modulus_ptr.into(),
+            OPERAND_BYTES,
+            false,
+            local.ptrs_disjoint,
+            local.shard,
+            local.channel,
+            local.is_real.into(),
+        );
+
+        // Read the modulus and write x in the same cycle, since they do not overlap.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.modulus_ptr,
+            &local.modulus_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_SQR.syscall_id()),
+            local.x_ptr,
+            local.modulus_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::NumWords;
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::ec::uint256::U256Field;
+use crate::utils::{pad_rows, words_to_bytes_le};
+use generic_array::GenericArray;
+use p3_air::AirBuilder;
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use std::borrow::{Borrow, BorrowMut};
+use std::mem::size_of;
+use typenum::Unsigned;
+
+/// The number of columns in the Uint256WnafCols.
+const NUM_COLS: usize = size_of::<Uint256WnafCols<u8>>();
+
+type WordsFieldElement = <U256Field as NumWords>::WordsFieldElement;
+const WORDS_FIELD_ELEMENT: usize = WordsFieldElement::USIZE;
+
+/// The number of bytes of a uint256.
+const NUM_LIMBS: usize = WORDS_FIELD_ELEMENT * WORD_SIZE;
+
+/// The width in bits of the windows of the recoding.
+pub const WNAF_WINDOW: usize = 4;
+
+/// The number of signed digits of the recoding of a uint256: one for each window, plus the final
+/// carry.
+pub const NUM_WNAF_DIGITS: usize = NUM_LIMBS * 8 / WNAF_WINDOW + 1;
+
+/// The number of words the digits are written to, one byte per digit.
+pub const NUM_WNAF_WORDS: usize = NUM_WNAF_DIGITS.div_ceil(WORD_SIZE);
+
+/// The number of digits in each byte of the scalar.
+const DIGITS_PER_LIMB: usize = 8 / WNAF_WINDOW;
+
+/// The bound on the absolute value of the digits, which lie in `[-WNAF_HALF, WNAF_HALF)`.
+const WNAF_HALF: u8 = 1 << (WNAF_WINDOW - 1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uint256WnafEvent {
+    pub lookup_id: usize,
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub scalar_ptr: u32,
+    pub digits_ptr: u32,
+    pub scalar: Vec<u32>,
+    pub scalar_memory_records: Vec<MemoryReadRecord>,
+    pub digits_memory_records: Vec<MemoryWriteRecord>,
+}
+
+/// A precompile recoding a uint256 scalar into signed digits for windowed scalar multiplication.
+///
+/// The scalar `k` is recoded into `NUM_WNAF_DIGITS` digits `d_i` with `k = sum d_i * 16^i`. Every
+/// digit but the last lies in `[-8, 8)`, and the last one is the final carry, so a scalar
+/// multiplication only needs the multiples `P, ..., 8P` of the base point and one addition per
+/// window, against one addition per set bit with double-and-add.
+///
+/// The first argument points to the scalar, and the second to `NUM_WNAF_WORDS` words to which the
+/// digits are written as two's complement bytes, with the unused bytes zeroed. The digits are
+/// derived from the nibbles of the scalar with a carry of one whenever a window is at least 8, and
+/// the chip checks `d_{2j} + 16 * d_{2j + 1} + 256 * c_{j + 1} = k_j + c_j` for every byte `k_j`
+/// of the scalar, with boolean carries `c_j` and `c_0 = 0`, which reconstructs the scalar.
+#[derive(Default)]
+pub struct Uint256WnafChip;
+
+impl Uint256WnafChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// A set of columns for the Uint256Wnaf operation.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct Uint256WnafCols<T> {
+    /// The shard number of the syscall.
+    pub shard: T,
+
+    /// The byte lookup channel.
+    pub channel: T,
+
+    /// The clock cycle of the syscall.
+    pub clk: T,
+
+    /// The nonce of the operation.
+    pub nonce: T,
+
+    /// The pointer to the scalar.
+    pub scalar_ptr: T,
+
+    /// The pointer to the digits.
+    pub digits_ptr: T,
+
+    /// The scalar.
+    pub scalar_memory: GenericArray<MemoryReadCols<T>, WordsFieldElement>,
+
+    /// The digits, as two's complement bytes.
+    pub digits_memory: [MemoryWriteCols<T>; NUM_WNAF_WORDS],
+
+    /// Whether each digit but the last is negative.
+    pub is_negative: [T; NUM_WNAF_DIGITS - 1],
+
+    /// The carries `c_1, ..., c_32` into each byte of the scalar, the last of which is the last
+    /// digit.
+    pub carries: [T; NUM_LIMBS],
+
+    pub is_real: T,
+}
+
+/// Recodes the scalar into signed digits with `scalar = sum digits[i] * 16^i`.
+pub fn uint256_wnaf(scalar: &[u32]) -> [i8; NUM_WNAF_DIGITS] {
+    let scalar_bytes = words_to_bytes_le::<NUM_LIMBS>(scalar);
+    let mut digits = [0i8; NUM_WNAF_DIGITS];
+    let mut carry = 0u8;
+    for (i, digit) in digits[..NUM_WNAF_DIGITS - 1].iter_mut().enumerate() {
+        let window = (scalar_bytes[i / DIGITS_PER_LIMB] >> (i % DIGITS_PER_LIMB * WNAF_WINDOW))
+            & ((1 << WNAF_WINDOW) - 1);
+        let value = window + carry;
+        carry = (value >= WNAF_HALF) as u8;
+        *digit = value as i8 - ((carry as i8) << WNAF_WINDOW);
+    }
+    digits[NUM_WNAF_DIGITS - 1] = carry as i8;
+    digits
+}
+
+/// Returns the carries `c_1, ..., c_32` of the reconstruction of the scalar from the digits.
+fn wnaf_carries(scalar: &[u8; NUM_LIMBS], digits: &[i8; NUM_WNAF_DIGITS]) -> [u8; NUM_LIMBS] {
+    let mut carries = [0u8; NUM_LIMBS];
+    let mut carry = 0i32;
+    for (j, &byte) in scalar.iter().enumerate() {
+        let limb = digits[2 * j] as i32 + ((digits[2 * j + 1] as i32) << WNAF_WINDOW);
+        let sum = byte as i32 + carry - limb;
+        debug_assert_eq!(sum % 256, 0);
+        carry = sum / 256;
+        carries[j] = carry as u8;
+    }
+    debug_assert_eq!(carry, digits[NUM_WNAF_DIGITS - 1] as i32);
+    carries
+}
+
+impl<F: PrimeField32> MachineAir<F> for Uint256WnafChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Uint256Wnaf".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut new_byte_lookup_events = Vec::new();
+
+        let mut rows = input
+            .uint256_wnaf_events
+            .iter()
+            .map(|event| {
+                let mut row: [F; NUM_COLS] = [F::zero(); NUM_COLS];
+                let cols: &mut Uint256WnafCols<F> = row.as_mut_slice().borrow_mut();
+
+                // Assign basic values to the columns.
+                cols.is_real = F::one();
+                cols.shard = F::from_canonical_u32(event.shard);
+                cols.channel = F::from_canonical_u32(event.channel);
+                cols.clk = F::from_canonical_u32(event.clk);
+                cols.scalar_ptr = F::from_canonical_u32(event.scalar_ptr);
+                cols.digits_ptr = F::from_canonical_u32(event.digits_ptr);
+
+                // Populate memory columns.
+                for i in 0..WORDS_FIELD_ELEMENT {
+                    cols.scalar_memory[i].populate(
+                        event.channel,
+                        event.scalar_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+                for i in 0..NUM_WNAF_WORDS {
+                    cols.digits_memory[i].populate(
+                        event.channel,
+                        event.digits_memory_records[i],
+                        &mut new_byte_lookup_events,
+                    );
+                }
+
+                // Populate the signs of the digits and the carries.
+                let digits = uint256_wnaf(&event.scalar);
+                let scalar_bytes = words_to_bytes_le::<NUM_LIMBS>(&event.scalar);
+                cols.carries = wnaf_carries(&scalar_bytes, &digits).map(F::from_canonical_u8);
+                for (i, &digit) in digits[..NUM_WNAF_DIGITS - 1].iter().enumerate() {
+                    cols.is_negative[i] = F::from_bool(digit < 0);
+                    new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent {
+                        opcode: ByteOpcode::LTU,
+                        shard: event.shard,
+                        channel: event.channel,
+                        a1: 1,
+                        a2: 0,
+                        b: (digit + WNAF_HALF as i8) as u32,
+                        c: 2 * WNAF_HALF as u32,
+                    });
+                }
+                let digit_bytes = digits[..NUM_WNAF_DIGITS - 1]
+                    .iter()
+                    .map(|&digit| digit as u8)
+                    .collect::<Vec<_>>();
+                new_byte_lookup_events.add_u8_range_checks(
+                    event.shard,
+                    event.channel,
+                    &digit_bytes,
+                );
+
+                row
+            })
+            .collect::<Vec<_>>();
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_COLS]);
+
+        // Convert the trace to a row major matrix.
+        let mut trace =
+            RowMajorMatrix::new(rows.into_iter().flatten().collect::<Vec<_>>(), NUM_COLS);
+
+        // Write the nonces to the trace.
+        for i in 0..trace.height() {
+            let cols: &mut Uint256WnafCols<F> =
+                trace.values[i * NUM_COLS..(i + 1) * NUM_COLS].borrow_mut();
+            cols.nonce = F::from_canonical_usize(i);
+        }
+
+        trace
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.uint256_wnaf_events.is_empty()
+    }
+}
+
+impl Syscall for Uint256WnafChip {
+    fn num_extra_cycles(&self) -> u32 {
+        0
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let scalar_ptr = arg1;
+        let digits_ptr = arg2;
+        if scalar_ptr % 4 != 0 || digits_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // Read the scalar and write its digits.
+        let (scalar_memory_records, scalar) = rt.mr_slice(scalar_ptr, WORDS_FIELD_ELEMENT);
+        let mut digit_bytes = [0u8; NUM_WNAF_WORDS * WORD_SIZE];
+        for (byte, digit) in digit_bytes.iter_mut().zip(uint256_wnaf(&scalar)) {
+            *byte = digit as u8;
+        }
+        let digit_words = digit_bytes
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let digits_memory_records = rt.mw_slice(digits_ptr, &digit_words);
+
+        let lookup_id = rt.syscall_lookup_id;
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        let clk = rt.clk;
+        rt.record_mut().uint256_wnaf_events.push(Uint256WnafEvent {
+            lookup_id,
+            shard,
+            channel,
+            clk,
+            scalar_ptr,
+            digits_ptr,
+            scalar,
+            scalar_memory_records,
+            digits_memory_records,
+        });
+
+        None
+    }
+}
+
+impl<F> BaseAir<F> for Uint256WnafChip {
+    fn width(&self) -> usize {
+        NUM_COLS
+    }
+}
+
+impl<AB> Air<AB> for Uint256WnafChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Uint256WnafCols<AB::Var> = (*local).borrow();
+        let next = main.row_slice(1);
+        let next: &Uint256WnafCols<AB::Var> = (*next).borrow();
+
+        // Constrain the incrementing nonce.
+        builder.when_first_row().assert_zero(local.nonce);
+        builder
+            .when_transition()
+            .assert_eq(local.nonce + AB::Expr::one(), next.nonce);
+
+        let scalar = local
+            .scalar_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+        let digit_bytes = local
+            .digits_memory
+            .iter()
+            .flat_map(|access| access.value().0)
+            .collect::<Vec<_>>();
+
+        // Every digit but the last is a byte `b` with a sign `s`, so its value is `b - 256 * s`,
+        // which is checked to lie in `[-8, 8)`. Given that `b` is a byte, this determines `s`.
+        let base = AB::F::from_canonical_u32(256);
+        let half = AB::F::from_canonical_u8(WNAF_HALF);
+        let mut digits: Vec<AB::Expr> = vec![];
+        for i in 0..NUM_WNAF_DIGITS - 1 {
+            builder.assert_bool(local.is_negative[i]);
+            let digit = digit_bytes[i] - local.is_negative[i] * base;
+            builder.send_byte(
+                ByteOpcode::LTU.as_field::<AB::F>(),
+                AB::F::one(),
+                digit.clone() + half,
+                half + half,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            digits.push(digit);
+        }
+        builder.slice_range_check_u8(
+            &digit_bytes[..NUM_WNAF_DIGITS - 1],
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // The last digit is the final carry, and the bytes after it are zero.
+        builder.assert_eq(
+            digit_bytes[NUM_WNAF_DIGITS - 1],
+            local.carries[NUM_LIMBS - 1],
+        );
+        for &byte in digit_bytes[NUM_WNAF_DIGITS..].iter() {
+            builder.assert_zero(byte);
+        }
+
+        // Reconstruct the scalar byte by byte. All the terms are small, so the equations hold over
+        // the integers. Padding rows are all zero, so they satisfy the equations.
+        let window_base = AB::F::from_canonical_u32(1 << WNAF_WINDOW);
+        for j in 0..NUM_LIMBS {
+            builder.assert_bool(local.carries[j]);
+            let carry_in: AB::Expr = if j > 0 {
+                local.carries[j - 1].into()
+            } else {
+                AB::Expr::zero()
+            };
+            builder.assert_eq(
+                digits[2 * j].clone()
+                    + digits[2 * j + 1].clone() * window_base
+                    + local.carries[j] * base,
+                scalar[j] + carry_in,
+            );
+        }
+
+        // Read the scalar and write the digits.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.scalar_ptr,
+            &local.scalar_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.digits_ptr,
+            &local.digits_memory,
+            local.is_real,
+        );
+
+        // Receive the arguments.
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.nonce,
+            AB::F::from_canonical_u32(SyscallCode::UINT256_WNAF.syscall_id()),
+            local.scalar_ptr,
+            local.digits_ptr,
+            local.is_real,
+        );
+
+        // Assert that is_real is a boolean.
+        builder.assert_bool(local.is_real);
+    }
+}
@@ -522,17 +522,319 @@ where
 #[cfg(test)]
 mod tests {
 
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use num::BigUint;
+    use sp1_zkvm::precompiles::msm::{msm, msm_window_bits, MsmPoint};
+    use sp1_zkvm::precompiles::pedersen::{
+        pedersen_scalar, PEDERSEN_CHUNK_SIZE, PEDERSEN_GENERATORS,
+    };
+
     use crate::{
-        runtime::Program,
+        runtime::{Instruction, Opcode, Program, Runtime, SyscallCode},
         utils::{
+            ec::{
+                weierstrass::{bn254::Bn254, secp256k1::Secp256k1},
+                AffinePoint, EllipticCurve,
+            },
             run_test, setup_logger,
             tests::{
-                BLS12381_ADD_ELF, BLS12381_DOUBLE_ELF, BLS12381_MUL_ELF, BN254_ADD_ELF,
-                BN254_MUL_ELF, SECP256K1_ADD_ELF, SECP256K1_MUL_ELF,
+                store_words, BLS12381_ADD_ELF, BLS12381_DOUBLE_ELF, BLS12381_MUL_ELF,
+                BN254_ADD_ELF, BN254_MUL_ELF, SECP256K1_ADD_ELF, SECP256K1_MUL_ELF,
             },
+            SP1CoreOpts,
         },
     };
 
+    /// The buffers of the Pedersen hash program, for the hash, the current term, and the current
+    /// multiple of the generator of the term.
+    const HASH_PTR: u32 = 0x1000;
+    const TERM_PTR: u32 = 0x1100;
+    const TEMP_PTR: u32 = 0x1200;
+
+    /// Returns the scalars of the terms of the Pedersen hash of `msg`, the length first.
+    fn pedersen_scalars(msg: &[u8]) -> Vec<BigUint> {
+        std::iter::once(pedersen_scalar(&(msg.len() as u32 + 1).to_le_bytes()))
+            .chain(msg.chunks(PEDERSEN_CHUNK_SIZE).map(pedersen_scalar))
+            .map(|scalar| BigUint::from_slice(&scalar))
+            .collect()
+    }
+
+    fn ecall(instructions: &mut Vec<Instruction>, code: SyscallCode, arg1: u32, arg2: u32) {
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, arg1, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+    }
+
+    fn copy_point(instructions: &mut Vec<Instruction>, src: u32, dst: u32) {
+        for i in 0..16 {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 30, 0, src + i * 4, false, true),
+                Instruction::new(Opcode::LW, 29, 30, 0, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, dst + i * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+    }
+
+    /// Computes the Pedersen hash of `msg` into `HASH_PTR` with the secp256k1 add and double
+    /// precompiles, the same way as `syscall_pedersen_hash`.
+    fn pedersen_hash_program(msg: &[u8]) -> Program {
+        let mut instructions = vec![];
+        let mut has_hash = false;
+        for (scalar, generator) in pedersen_scalars(msg).iter().zip(PEDERSEN_GENERATORS) {
+            let num_bits = scalar.bits();
+            if num_bits == 0 {
+                continue;
+            }
+            instructions.extend(store_words(TEMP_PTR, generator));
+            let mut has_term = false;
+            for bit in 0..num_bits {
+                if scalar.bit(bit) {
+                    if has_term {
+                        ecall(&mut instructions, SyscallCode::SECP256K1_ADD, TERM_PTR, TEMP_PTR);
+                    } else {
+                        copy_point(&mut instructions, TEMP_PTR, TERM_PTR);
+                        has_term = true;
+                    }
+                }
+                if bit + 1 < num_bits {
+                    ecall(&mut instructions, SyscallCode::SECP256K1_DOUBLE, TEMP_PTR, 0);
+                }
+            }
+            if has_hash {
+                ecall(&mut instructions, SyscallCode::SECP256K1_ADD, HASH_PTR, TERM_PTR);
+            } else {
+                copy_point(&mut instructions, TERM_PTR, HASH_PTR);
+                has_hash = true;
+            }
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    /// Computes the Pedersen hash of `msg` with the reference curve arithmetic.
+    fn reference_pedersen_hash(msg: &[u8]) -> Vec<u32> {
+        pedersen_scalars(msg)
+            .iter()
+            .zip(PEDERSEN_GENERATORS)
+            .filter(|(scalar, _)| scalar.bits() > 0)
+            .map(|(scalar, generator)| {
+                AffinePoint::<Secp256k1>::from_words_le(&generator).sw_scalar_mul(scalar)
+            })
+            .reduce(|hash, term| hash.sw_add(&term))
+            .unwrap()
+            .to_words_le()
+    }
+
+    #[test]
+    fn test_secp256k1_pedersen_hash() {
+        setup_logger();
+        let mut msg = b"a short message for the pedersen hash".to_vec();
+        // A zero chunk, which is skipped.
+        msg.extend([0u8; PEDERSEN_CHUNK_SIZE]);
+        let program = pedersen_hash_program(&msg);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let hash = (0..16)
+            .map(|i| runtime.word(HASH_PTR + i * 4))
+            .collect::<Vec<_>>();
+        assert_eq!(hash, reference_pedersen_hash(&msg));
+        run_test(program).unwrap();
+    }
+
+    /// The instructions of a test program and the next free point buffer in its memory.
+    struct PointProgram {
+        instructions: Vec<Instruction>,
+        next_ptr: u32,
+    }
+
+    /// A bn254 point in the memory of a test program, which emits the precompile calls made on it
+    /// along with its reference value, so that the guest multi-scalar multiplication can be run
+    /// with the precompiles.
+    struct ProgramPoint {
+        ptr: u32,
+        value: AffinePoint<Bn254>,
+        program: Rc<RefCell<PointProgram>>,
+    }
+
+    impl ProgramPoint {
+        /// Stores `value` in a new buffer of the program.
+        fn new(program: &Rc<RefCell<PointProgram>>, value: AffinePoint<Bn254>) -> Self {
+            let mut state = program.borrow_mut();
+            let ptr = state.next_ptr;
+            state.next_ptr += 64;
+            state
+                .instructions
+                .extend(store_words(ptr, value.to_words_le()));
+            drop(state);
+            Self {
+                ptr,
+                value,
+                program: program.clone(),
+            }
+        }
+    }
+
+    impl Clone for ProgramPoint {
+        fn clone(&self) -> Self {
+            let mut state = self.program.borrow_mut();
+            let ptr = state.next_ptr;
+            state.next_ptr += 64;
+            copy_point(&mut state.instructions, self.ptr, ptr);
+            Self {
+                ptr,
+                value: self.value.clone(),
+                program: self.program.clone(),
+            }
+        }
+    }
+
+    impl MsmPoint for ProgramPoint {
+        fn add_assign(&mut self, other: &Self) {
+            let mut state = self.program.borrow_mut();
+            ecall(&mut state.instructions, SyscallCode::BN254_ADD, self.ptr, other.ptr);
+            drop(state);
+            self.value = self.value.sw_add(&other.value);
+        }
+
+        fn double(&mut self) {
+            let mut state = self.program.borrow_mut();
+            ecall(&mut state.instructions, SyscallCode::BN254_DOUBLE, self.ptr, 0);
+            drop(state);
+            self.value = self.value.sw_double();
+        }
+
+        fn is_equal(&self, other: &Self) -> bool {
+            self.value == other.value
+        }
+
+        fn is_opposite(&self, other: &Self) -> bool {
+            self.value.x == other.value.x && self.value.y != other.value.y
+        }
+    }
+
+    /// Computes `sum_i [scalars[i]] points[i]` with the multi-scalar multiplication of the guest
+    /// and the bn254 precompiles, and returns the program with the pointer to the sum, if it is not
+    /// the identity.
+    fn bn254_msm_program(
+        scalars: &[[u32; 8]],
+        points: &[AffinePoint<Bn254>],
+    ) -> (Program, Option<u32>) {
+        let program = Rc::new(RefCell::new(PointProgram {
+            instructions: vec![],
+            next_ptr: 0x1000,
+        }));
+        let points = points
+            .iter()
+            .map(|point| ProgramPoint::new(&program, point.clone()))
+            .collect::<Vec<_>>();
+        let sum = msm(scalars, &points).map(|sum| sum.ptr);
+        drop(points);
+        let instructions = Rc::try_unwrap(program).ok().unwrap().into_inner().instructions;
+        (Program::new(instructions, 0, 0), sum)
+    }
+
+    /// Computes `sum_i [scalars[i]] points[i]` with the reference curve arithmetic.
+    fn reference_bn254_msm(
+        scalars: &[[u32; 8]],
+        points: &[AffinePoint<Bn254>],
+    ) -> Option<AffinePoint<Bn254>> {
+        let mut sum: Option<AffinePoint<Bn254>> = None;
+        for (scalar, point) in scalars.iter().zip(points) {
+            let scalar = BigUint::from_slice(scalar);
+            if scalar.bits() == 0 {
+                continue;
+            }
+            let term = point.sw_scalar_mul(&scalar);
+            sum = match sum {
+                None => Some(term),
+                Some(sum) if sum == term => Some(sum.sw_double()),
+                Some(sum) if sum.x == term.x => None,
+                Some(sum) => Some(sum.sw_add(&term)),
+            };
+        }
+        sum
+    }
+
+    /// Runs the multi-scalar multiplication of the guest and checks it against the reference.
+    fn check_bn254_msm(scalars: &[[u32; 8]], points: &[AffinePoint<Bn254>]) -> Program {
+        let (program, sum_ptr) = bn254_msm_program(scalars, points);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let sum = sum_ptr.map(|ptr| {
+            (0..16)
+                .map(|i| runtime.word(ptr + i * 4))
+                .collect::<Vec<_>>()
+        });
+        let expected = reference_bn254_msm(scalars, points).map(|sum| sum.to_words_le());
+        assert_eq!(sum, expected);
+        program
+    }
+
+    /// A scalar below `2^252`, derived from `seed`.
+    fn bn254_test_scalar(seed: u32) -> [u32; 8] {
+        let mut scalar = core::array::from_fn(|i| {
+            (seed.wrapping_add(1).wrapping_mul(0x9e37_79b9)).wrapping_add(i as u32 * 0x7f4a_7c15)
+        });
+        scalar[7] >>= 4;
+        scalar
+    }
+
+    #[test]
+    fn test_bn254_msm_small() {
+        setup_logger();
+        let generator = Bn254::generator();
+        let point = generator.sw_double();
+        let other = generator.sw_scalar_mul(&BigUint::from(5u32));
+
+        // A single term.
+        let program = check_bn254_msm(&[bn254_test_scalar(0)], &[point.clone()]);
+        run_test(program).unwrap();
+
+        // Equal points in the same buckets, opposite points which cancel, and a zero scalar.
+        let scalars = [
+            bn254_test_scalar(1),
+            bn254_test_scalar(1),
+            bn254_test_scalar(2),
+            bn254_test_scalar(2),
+            [0; 8],
+        ];
+        let points = [
+            point.clone(),
+            point.clone(),
+            other.clone(),
+            Bn254::ec_neg(&other),
+            generator.clone(),
+        ];
+        let program = check_bn254_msm(&scalars, &points);
+        run_test(program).unwrap();
+
+        // Terms which sum to the identity.
+        let scalars = [bn254_test_scalar(3), bn254_test_scalar(3)];
+        let (_, sum_ptr) = bn254_msm_program(&scalars, &[point.clone(), Bn254::ec_neg(&point)]);
+        assert!(sum_ptr.is_none());
+    }
+
+    #[test]
+    fn test_bn254_msm_buckets() {
+        setup_logger();
+        const N: u32 = 40;
+        assert!(msm_window_bits(N as usize) > msm_window_bits(1));
+
+        let generator = Bn254::generator();
+        let points = (1..=N)
+            .map(|i| generator.sw_scalar_mul(&BigUint::from(i)))
+            .collect::<Vec<_>>();
+        let scalars = (0..N).map(bn254_test_scalar).collect::<Vec<_>>();
+        check_bn254_msm(&scalars, &points);
+    }
+
     #[test]
     fn test_secp256k1_add_simple() {
         setup_logger();
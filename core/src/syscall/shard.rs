@@ -0,0 +1,89 @@
+use crate::runtime::{Syscall, SyscallContext};
+
+/// SyscallShardBoundary closes the current shard once the calling instruction has executed, so the
+/// next instruction starts a new shard.
+///
+/// This lets programs place shard boundaries where their traces are balanced, e.g. between loop
+/// iterations, in addition to the boundaries from the shard size. Shards are not observable by the
+/// program, so the request is ignored in unconstrained mode, whose execution is discarded anyway.
+pub struct SyscallShardBoundary;
+
+impl SyscallShardBoundary {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallShardBoundary {
+    fn execute(&self, ctx: &mut SyscallContext, _: u32, _: u32) -> Option<u32> {
+        if !ctx.rt.unconstrained {
+            ctx.rt.shard_boundary_requested = true;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, ShardingConfig, SyscallCode};
+    use crate::stark::MachineRecord;
+    use crate::utils::{run_test, setup_logger, SP1CoreOpts};
+
+    const BATCH_SIZE: usize = 20;
+
+    /// Runs two batches of multiplications, requesting a shard boundary between them.
+    fn shard_boundary_program() -> Program {
+        let mut instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 7, false, true),
+        ];
+        for _ in 0..BATCH_SIZE {
+            instructions.push(Instruction::new(Opcode::MUL, 29, 29, 30, false, false));
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SHARD_BOUNDARY as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        for _ in 0..BATCH_SIZE {
+            instructions.push(Instruction::new(Opcode::MUL, 29, 29, 30, false, false));
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_shard_boundary_splits_shards() {
+        setup_logger();
+        let mut runtime = Runtime::new(shard_boundary_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.state.current_shard, 2);
+
+        // The first shard ends with the ecall, and the second holds the second batch.
+        let shards = runtime.record.shard(&ShardingConfig::default());
+        assert_eq!(shards.len(), 2);
+        assert_eq!(shards[0].cpu_events.len(), BATCH_SIZE + 4);
+        assert_eq!(shards[1].cpu_events.len(), BATCH_SIZE);
+        assert_eq!(shards[1].cpu_events[0].clk, 0);
+    }
+
+    #[test]
+    fn test_shard_boundary_without_request() {
+        let mut instructions = shard_boundary_program().instructions;
+        instructions.retain(|instruction| instruction.opcode != Opcode::ECALL);
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.state.current_shard, 1);
+    }
+
+    #[test]
+    fn test_shard_boundary_prove() {
+        setup_logger();
+        run_test(shard_boundary_program()).unwrap();
+    }
+}
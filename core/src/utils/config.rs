@@ -1,4 +1,4 @@
-use crate::stark::StarkGenericConfig;
+use crate::stark::{Poseidon2Params, StarkGenericConfig};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -19,6 +19,16 @@ use sp1_primitives::poseidon2_init;
 
 pub const DIGEST_SIZE: usize = 8;
 
+/// The parameters of the Poseidon2 permutation for inner recursion, which [`BabyBearPoseidon2`]
+/// uses as well.
+///
+/// [`BabyBearPoseidon2`]: crate::utils::BabyBearPoseidon2
+pub const INNER_POSEIDON2_PARAMS: Poseidon2Params = Poseidon2Params {
+    width: 16,
+    rounds_f: 8,
+    rounds_p: 13,
+};
+
 /// A configuration for inner recursion.
 pub type InnerVal = BabyBear;
 pub type InnerChallenge = BinomialExtensionField<InnerVal, 4>;
@@ -149,4 +159,8 @@ impl StarkGenericConfig for BabyBearPoseidon2Inner {
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
+
+    fn poseidon2_params(&self) -> Option<Poseidon2Params> {
+        Some(INNER_POSEIDON2_PARAMS)
+    }
 }
@@ -44,6 +44,75 @@ impl NumLimbs for Bn254BaseField {
     type Witness = U62;
 }
 
+/// An element `c0 + c1 i` of the quadratic extension of the Bn254 base field by `i^2 = -1`, with
+/// both coefficients reduced modulo the base field modulus.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bn254Fp2 {
+    pub c0: BigUint,
+    pub c1: BigUint,
+}
+
+impl Bn254Fp2 {
+    /// The number of words of an element, the 8 little-endian words of `c0` followed by `c1`.
+    pub const NUM_WORDS: usize = 16;
+
+    pub const fn new(c0: BigUint, c1: BigUint) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Reads an element from its words, without reducing the coefficients.
+    pub fn from_words_le(words: &[u32]) -> Self {
+        assert_eq!(words.len(), Self::NUM_WORDS, "an Fp2 element has 16 words");
+        Self::new(
+            BigUint::from_slice(&words[..Self::NUM_WORDS / 2]),
+            BigUint::from_slice(&words[Self::NUM_WORDS / 2..]),
+        )
+    }
+
+    /// Returns the words of the element.
+    pub fn to_words_le(&self) -> Vec<u32> {
+        [&self.c0, &self.c1]
+            .into_iter()
+            .flat_map(|c| {
+                let mut words = c.to_u32_digits();
+                words.resize(Self::NUM_WORDS / 2, 0);
+                words
+            })
+            .collect()
+    }
+
+    /// Returns `(a + b i)(c + d i) = (ac - bd) + (ad + bc) i`.
+    pub fn mul(&self, other: &Self) -> Self {
+        let p = Bn254BaseField::modulus();
+        let c0 = (&p + &self.c0 * &other.c0 % &p - &self.c1 * &other.c1 % &p) % &p;
+        let c1 = (&self.c0 * &other.c1 + &self.c1 * &other.c0) % &p;
+        Self::new(c0, c1)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Bn254 scalar field parameter
+pub struct Bn254ScalarField;
+
+impl FieldParameters for Bn254ScalarField {
+    const MODULUS: &'static [u8] = &[
+        1, 0, 0, 240, 147, 245, 225, 67, 145, 112, 185, 121, 72, 232, 51, 40, 93, 88, 129, 129,
+        182, 69, 80, 184, 41, 160, 49, 225, 114, 78, 100, 48,
+    ];
+
+    // A rough witness-offset estimate given the size of the limbs and the size of the field.
+    const WITNESS_OFFSET: usize = 1usize << 14;
+
+    fn modulus() -> BigUint {
+        Bn254Parameters::prime_group_order()
+    }
+}
+
+impl NumLimbs for Bn254ScalarField {
+    type Limbs = U32;
+    type Witness = U62;
+}
+
 impl EllipticCurveParameters for Bn254Parameters {
     type BaseField = Bn254BaseField;
 
@@ -97,5 +166,24 @@ mod tests {
             biguint_from_limbs(Bn254BaseField::MODULUS),
             Bn254BaseField::modulus()
         );
+        assert_eq!(
+            biguint_from_limbs(Bn254ScalarField::MODULUS),
+            Bn254ScalarField::modulus()
+        );
+    }
+
+    #[test]
+    fn test_bn254_fp2_mul() {
+        let p = Bn254BaseField::modulus();
+        let i = Bn254Fp2::new(BigUint::zero(), BigUint::from(1u32));
+        let minus_one = Bn254Fp2::new(&p - 1u32, BigUint::zero());
+        assert_eq!(i.mul(&i), minus_one);
+
+        // (3 + 5i)(7 + 11i) = (21 - 55) + (33 + 35)i.
+        let x = Bn254Fp2::new(BigUint::from(3u32), BigUint::from(5u32));
+        let y = Bn254Fp2::new(BigUint::from(7u32), BigUint::from(11u32));
+        let product = Bn254Fp2::new(&p - 34u32, BigUint::from(68u32));
+        assert_eq!(x.mul(&y), product);
+        assert_eq!(Bn254Fp2::from_words_le(&product.to_words_le()), product);
     }
 }
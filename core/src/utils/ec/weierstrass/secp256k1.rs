@@ -52,6 +52,30 @@ impl NumLimbs for Secp256k1BaseField {
     type Witness = U62;
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+/// Secp256k1 scalar field parameter
+pub struct Secp256k1ScalarField;
+
+impl FieldParameters for Secp256k1ScalarField {
+    const MODULUS: &'static [u8] = &[
+        0x41, 0x41, 0x36, 0xd0, 0x8c, 0x5e, 0xd2, 0xbf, 0x3b, 0xa0, 0x48, 0xaf, 0xe6, 0xdc, 0xae,
+        0xba, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff,
+    ];
+
+    /// A rough witness-offset estimate given the size of the limbs and the size of the field.
+    const WITNESS_OFFSET: usize = 1usize << 14;
+
+    fn modulus() -> BigUint {
+        Secp256k1Parameters::prime_group_order()
+    }
+}
+
+impl NumLimbs for Secp256k1ScalarField {
+    type Limbs = U32;
+    type Witness = U62;
+}
+
 impl EllipticCurveParameters for Secp256k1Parameters {
     type BaseField = Secp256k1BaseField;
     const CURVE_TYPE: CurveType = CurveType::Secp256k1;
@@ -128,6 +152,10 @@ mod tests {
             biguint_from_limbs(Secp256k1BaseField::MODULUS),
             Secp256k1BaseField::modulus()
         );
+        assert_eq!(
+            biguint_from_limbs(Secp256k1ScalarField::MODULUS),
+            Secp256k1ScalarField::modulus()
+        );
     }
 
     #[test]
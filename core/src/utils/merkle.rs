@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+use crate::syscall::precompiles::babybear::bytes_to_fields;
+use crate::syscall::precompiles::poseidon2::{poseidon2_compress, DIGEST_SIZE};
+
+/// A digest of the Poseidon2 Merkle tree built by `sp1_zkvm::io::commit_merkleized`.
+pub type MerkleDigest = [u32; DIGEST_SIZE];
+
+/// Hashes a value into a leaf of the tree.
+///
+/// The value is packed into BabyBear elements, three bytes each, and absorbed in zero-padded blocks
+/// of eight elements by compressing them with the running digest, which starts as the length of
+/// the value. At least one block is absorbed, so that no value hashes to the zero padding leaf.
+/// Make sure this matches `leaf_digest` in `zkvm/precompiles/src/merkle.rs`.
+pub fn merkle_leaf_digest(value: &[u8]) -> MerkleDigest {
+    let mut fields = bytes_to_fields(value);
+    fields.resize(fields.len().div_ceil(DIGEST_SIZE).max(1) * DIGEST_SIZE, 0);
+    let mut digest = [0; DIGEST_SIZE];
+    digest[0] = value.len() as u32;
+    for block in fields.chunks_exact(DIGEST_SIZE) {
+        digest = poseidon2_compress(digest, block.try_into().unwrap());
+    }
+    digest
+}
+
+/// Serializes a root into the bytes committed to the public values, as little-endian words.
+pub fn merkle_root_bytes(root: &MerkleDigest) -> [u8; DIGEST_SIZE * 4] {
+    let mut bytes = [0; DIGEST_SIZE * 4];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(root) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// The bytes committed to the public values by `sp1_zkvm::io::commit_hashed(value)`, against which
+/// a revealed preimage is checked.
+pub fn hashed_commitment(value: &[u8]) -> [u8; DIGEST_SIZE * 4] {
+    merkle_root_bytes(&merkle_leaf_digest(value))
+}
+
+/// The Merkle tree over the values committed by `sp1_zkvm::io::commit_merkleized`, from which the
+/// host produces membership proofs for single values.
+///
+/// The leaves are padded with zero digests to a power of two, and every inner node is the Poseidon2
+/// compression of its two children.
+#[derive(Debug, Clone)]
+pub struct MerkleizedValues {
+    /// The layers of the tree, from the leaves to the root.
+    layers: Vec<Vec<MerkleDigest>>,
+}
+
+/// A proof that a value is committed at a given index of a [MerkleizedValues] tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleMembershipProof {
+    pub index: usize,
+    /// The siblings of the path from the leaf to the root, starting at the leaf.
+    pub siblings: Vec<MerkleDigest>,
+}
+
+impl MerkleizedValues {
+    /// Builds the tree over the same values the program committed.
+    pub fn new(values: &[&[u8]]) -> Self {
+        assert!(!values.is_empty(), "cannot merkleize an empty list of values");
+        let mut leaves = values
+            .iter()
+            .map(|value| merkle_leaf_digest(value))
+            .collect::<Vec<_>>();
+        leaves.resize(values.len().next_power_of_two(), [0; DIGEST_SIZE]);
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| poseidon2_compress(pair[0], pair[1]))
+                .collect();
+            layers.push(layer);
+        }
+        Self { layers }
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> MerkleDigest {
+        self.layers.last().unwrap()[0]
+    }
+
+    /// The root of the tree, as committed to the public values.
+    pub fn root_bytes(&self) -> [u8; DIGEST_SIZE * 4] {
+        merkle_root_bytes(&self.root())
+    }
+
+    /// Produces a proof that the value at `index` is committed by the root.
+    pub fn prove(&self, index: usize) -> MerkleMembershipProof {
+        assert!(index < self.layers[0].len(), "index {} out of range", index);
+        let siblings = self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, layer)| layer[(index >> height) ^ 1])
+            .collect();
+        MerkleMembershipProof { index, siblings }
+    }
+}
+
+impl MerkleMembershipProof {
+    /// Checks that `value` is committed at the index of the proof by the committed root.
+    pub fn verify(&self, root: &[u8; DIGEST_SIZE * 4], value: &[u8]) -> bool {
+        let mut digest = merkle_leaf_digest(value);
+        for (height, sibling) in self.siblings.iter().enumerate() {
+            digest = if (self.index >> height) & 1 == 0 {
+                poseidon2_compress(digest, *sibling)
+            } else {
+                poseidon2_compress(*sibling, digest)
+            };
+        }
+        self.index >> self.siblings.len() == 0 && merkle_root_bytes(&digest) == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hashed_commitment, merkle_root_bytes, MerkleDigest, MerkleizedValues};
+    use crate::io::SP1PublicValues;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::syscall::precompiles::babybear::bytes_to_fields;
+    use crate::syscall::precompiles::poseidon2::DIGEST_SIZE;
+    use crate::utils::tests::store_words;
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const VALUES: [&[u8]; 4] = [b"alice", b"bob: 42", b"", b"a value which spans several blocks"];
+
+    #[test]
+    fn test_membership_proof() {
+        let tree = MerkleizedValues::new(&VALUES);
+        let root = tree.root_bytes();
+        for (index, value) in VALUES.iter().enumerate() {
+            let proof = tree.prove(index);
+            assert_eq!(proof.siblings.len(), 2);
+            assert!(proof.verify(&root, value));
+        }
+
+        // The proof only holds for its own value and index.
+        let mut proof = tree.prove(1);
+        assert!(!proof.verify(&root, b"bob: 43"));
+        assert!(!proof.verify(&root, VALUES[0]));
+        proof.index = 0;
+        assert!(!proof.verify(&root, VALUES[1]));
+        proof.index = 5;
+        assert!(!proof.verify(&root, VALUES[1]));
+
+        // Trees of non-power-of-two sizes are padded.
+        let tree = MerkleizedValues::new(&VALUES[..3]);
+        let proof = tree.prove(2);
+        assert!(proof.verify(&tree.root_bytes(), VALUES[2]));
+    }
+
+    /// Stores the leaf digests, then compresses each pair of nodes with the precompile, the way the
+    /// guest builds the tree, leaving the root at the address of the last node.
+    fn merkle_root_program(leaves: &[MerkleDigest; 4]) -> Program {
+        let node_ptr = |i: u32| 0x1000 + i * DIGEST_SIZE as u32 * 4;
+        let mut instructions = store_words(node_ptr(0), leaves.iter().flatten().copied());
+        // Nodes 0..4 are the leaves, nodes 4 and 5 their parents, and node 6 the root.
+        for (left, out) in [(0, 4), (2, 5), (4, 6)] {
+            let code = SyscallCode::POSEIDON2_COMPRESS as u32;
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, code, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, node_ptr(left), false, true),
+                Instruction::new(Opcode::ADD, 11, 0, node_ptr(out), false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_merkle_root_matches_precompile() {
+        utils::setup_logger();
+        let tree = MerkleizedValues::new(&VALUES);
+        let leaves = tree.layers[0].clone().try_into().unwrap();
+        let mut runtime = Runtime::new(merkle_root_program(&leaves), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        let root_ptr = 0x1000 + 6 * DIGEST_SIZE as u32 * 4;
+        let root: MerkleDigest = core::array::from_fn(|i| runtime.word(root_ptr + i as u32 * 4));
+        assert_eq!(merkle_root_bytes(&root), tree.root_bytes());
+    }
+
+    /// Hashes `secret` with the precompile the way `commit_hashed` does, and commits only the hash
+    /// to the public values. The secret fits in a single block.
+    fn commit_hashed_program(secret: &[u8]) -> Program {
+        let (state_ptr, out_ptr) = (0x1000, 0x2000);
+        let mut input = vec![0; 2 * DIGEST_SIZE];
+        input[0] = secret.len() as u32;
+        let fields = bytes_to_fields(secret);
+        assert!(fields.len() <= DIGEST_SIZE);
+        input[DIGEST_SIZE..DIGEST_SIZE + fields.len()].copy_from_slice(&fields);
+
+        let mut instructions = store_words(state_ptr, input);
+        let code = SyscallCode::POSEIDON2_COMPRESS as u32;
+        instructions.extend(vec![
+            Instruction::new(Opcode::ADD, 5, 0, code, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, state_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, out_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, out_ptr, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, DIGEST_SIZE as u32 * 4, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_commit_hashed() {
+        utils::setup_logger();
+        let secret = b"sealed bid: 1000";
+        let program = commit_hashed_program(secret);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Only the hash of the secret is committed.
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+        assert_eq!(public_values.as_slice(), hashed_commitment(secret));
+        assert!(!public_values
+            .as_slice()
+            .windows(secret.len())
+            .any(|window| window == secret));
+
+        // The commitment binds the secret.
+        assert_ne!(hashed_commitment(b"sealed bid: 1001"), hashed_commitment(secret));
+
+        run_test(program).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ mod buffer;
 mod config;
 pub mod ec;
 mod logger;
+mod merkle;
 mod options;
 #[cfg(any(test, feature = "programs"))]
 mod programs;
@@ -11,6 +12,7 @@ mod tracer;
 pub use buffer::*;
 pub use config::*;
 pub use logger::*;
+pub use merkle::*;
 pub use options::*;
 pub use prove::*;
 pub use tracer::*;
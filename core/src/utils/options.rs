@@ -1,4 +1,5 @@
 use std::env;
+use std::num::NonZeroUsize;
 
 const DEFAULT_SHARD_SIZE: usize = 1 << 22;
 const DEFAULT_SHARD_BATCH_SIZE: usize = 16;
@@ -9,6 +10,14 @@ pub struct SP1CoreOpts {
     pub shard_batch_size: usize,
     pub shard_chunking_multiplier: usize,
     pub reconstruct_commitments: bool,
+    pub max_shards: Option<usize>,
+    /// If set, shards are also closed once their estimated number of trace cells reaches this
+    /// budget, so that shards of wide chips get fewer rows than shards of narrow ones.
+    pub shard_cell_budget: Option<usize>,
+    /// If set, shards are also closed before a precompile is called more than this many times in
+    /// them, so that the tables of precompiles chunked across shards have similar heights. The cap
+    /// is non-zero, since a shard must fit at least one call; `PRECOMPILE_SHARD_CAP=0` is ignored.
+    pub precompile_shard_cap: Option<NonZeroUsize>,
 }
 
 impl Default for SP1CoreOpts {
@@ -24,6 +33,13 @@ impl Default for SP1CoreOpts {
             ),
             shard_chunking_multiplier: 1,
             reconstruct_commitments: true,
+            max_shards: None,
+            shard_cell_budget: env::var("SHARD_CELL_BUDGET")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok()),
+            precompile_shard_cap: env::var("PRECOMPILE_SHARD_CAP")
+                .ok()
+                .and_then(|s| s.parse::<NonZeroUsize>().ok()),
         }
     }
 }
@@ -1,4 +1,22 @@
 pub mod tests {
+    use crate::runtime::{Instruction, Opcode};
+
+    /// Returns instructions which store `words` to consecutive words of memory starting at `ptr`,
+    /// using registers x29 and x30.
+    pub fn store_words(ptr: u32, words: impl IntoIterator<Item = u32>) -> Vec<Instruction> {
+        words
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, word)| {
+                [
+                    Instruction::new(Opcode::ADD, 29, 0, word, false, true),
+                    Instruction::new(Opcode::ADD, 30, 0, ptr + i as u32 * 4, false, true),
+                    Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+                ]
+            })
+            .collect()
+    }
+
     /// Demos.
 
     pub const CHESS_ELF: &[u8] =
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
 use std::io::{Seek, Write};
@@ -5,10 +6,11 @@ use std::sync::Arc;
 use web_time::Instant;
 
 pub use baby_bear_blake3::BabyBearBlake3;
+use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::PrimeField32;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use size::Size;
 use thiserror::Error;
 
@@ -18,7 +20,10 @@ use crate::lookup::InteractionBuilder;
 use crate::runtime::{
     DefaultSubproofVerifier, ExecutionError, NoOpSubproofVerifier, SubproofVerifier,
 };
-use crate::runtime::{ExecutionRecord, ExecutionReport, ShardingConfig};
+use crate::runtime::{
+    ExecutionRecord, ExecutionReport, ExecutionState, ProveEstimate, RecordValidationError,
+    ShardingConfig,
+};
 use crate::stark::DebugConstraintBuilder;
 use crate::stark::MachineProof;
 use crate::stark::ProverConstraintFolder;
@@ -29,9 +34,9 @@ use crate::stark::{Com, PcsProverData, RiscvAir, ShardProof, StarkProvingKey, Un
 use crate::stark::{MachineRecord, StarkMachine};
 use crate::utils::SP1CoreOpts;
 use crate::{
-    runtime::{Program, Runtime},
+    runtime::{Instruction, Opcode, Program, Runtime, SyscallCode},
     stark::StarkGenericConfig,
-    stark::{LocalProver, OpeningProof, ShardMainData},
+    stark::{LocalProver, OpeningProof, Prover, ShardMainData},
 };
 
 const LOG_DEGREE_BOUND: usize = 31;
@@ -44,12 +49,106 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("too many shards: produced {produced}, limit {limit}")]
+    TooManyShards { produced: usize, limit: usize },
+    #[error("invalid execution record: {0}")]
+    InvalidRecord(RecordValidationError),
+}
+
+/// Returns an error if more shards were produced than allowed by `opts.max_shards`.
+fn check_num_shards(produced: usize, opts: &SP1CoreOpts) -> Result<(), SP1CoreProverError> {
+    match opts.max_shards {
+        Some(limit) if produced > limit => {
+            Err(SP1CoreProverError::TooManyShards { produced, limit })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The rows of a chip in a proven shard, comparing the estimate of the shard with its trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChipRowReport {
+    /// The padded number of rows estimated from the event counts of the shard.
+    pub estimated_rows: usize,
+
+    /// The number of rows filled by events.
+    pub real_rows: usize,
+
+    /// The height of the trace that was proven, including the padding rows.
+    pub padded_rows: usize,
+}
+
+impl ChipRowReport {
+    /// The number of padding rows of the trace.
+    pub const fn padding_rows(&self) -> usize {
+        self.padded_rows.saturating_sub(self.real_rows)
+    }
+}
+
+/// Compares the estimate of a shard with the trace heights of its proof, keyed by chip name.
+pub fn trace_row_report<SC: StarkGenericConfig>(
+    estimate: &ProveEstimate,
+    proof: &ShardProof<SC>,
+) -> BTreeMap<String, ChipRowReport> {
+    proof
+        .chip_ordering
+        .iter()
+        .map(|(name, &i)| {
+            let report = ChipRowReport {
+                estimated_rows: estimate.chip_rows.get(name).copied().unwrap_or(0),
+                real_rows: estimate.chip_real_rows.get(name).copied().unwrap_or(0),
+                padded_rows: 1 << proof.opened_values.chips[i].log_degree,
+            };
+            (name.clone(), report)
+        })
+        .collect()
+}
+
+/// Logs the rows of each chip of a proven shard, to spot estimate mismatches and padding waste.
+fn log_trace_row_report(shard: u32, report: &BTreeMap<String, ChipRowReport>) {
+    tracing::info!("trace row report (shard {}):", shard);
+    for (name, chip) in report.iter() {
+        if chip.estimated_rows != chip.padded_rows {
+            tracing::warn!(
+                "  {}: estimated {} rows, but the trace has {} rows",
+                name,
+                chip.estimated_rows,
+                chip.padded_rows
+            );
+        }
+        tracing::info!(
+            "  {}: estimated={}, real={}, padded={}, padding={:.1}%",
+            name,
+            chip.estimated_rows,
+            chip.real_rows,
+            chip.padded_rows,
+            100.0 * chip.padding_rows() as f64 / chip.padded_rows as f64,
+        );
+    }
 }
 
 pub fn prove_simple<SC: StarkGenericConfig>(
     config: SC,
     runtime: Runtime,
 ) -> Result<MachineProof<SC>, SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    prove_simple_with_opts(config, runtime, &SP1CoreOpts::default())
+}
+
+/// Proves the record of `runtime` like [prove_simple], failing before proving if it has more
+/// shards than allowed by `opts.max_shards`.
+fn prove_simple_with_opts<SC: StarkGenericConfig>(
+    config: SC,
+    runtime: Runtime,
+    opts: &SP1CoreOpts,
+) -> Result<MachineProof<SC>, SP1CoreProverError>
 where
     SC::Challenger: Clone,
     OpeningProof<SC>: Send + Sync,
@@ -62,16 +161,33 @@ where
     let machine = RiscvAir::machine(config);
     let (pk, _) = machine.setup(runtime.program.as_ref());
 
-    // Prove the program.
+    // Prove the program, keeping the estimates of the shards to compare them with their traces.
     let mut challenger = machine.config().challenger();
     let proving_start = Instant::now();
-    let proof = machine.prove::<LocalProver<_, _>>(
-        &pk,
-        runtime.record,
-        &mut challenger,
-        SP1CoreOpts::default(),
-    );
+    runtime
+        .record
+        .validate()
+        .map_err(SP1CoreProverError::InvalidRecord)?;
+    let shards = tracing::info_span!("shard_record")
+        .in_scope(|| machine.shard(runtime.record, &ShardingConfig::default()));
+    check_num_shards(shards.len(), opts)?;
+    let estimates = shards
+        .iter()
+        .map(|shard| (shard.index(), shard.estimate_prove_cost()))
+        .collect::<Vec<_>>();
+    let proof = tracing::info_span!("prove_shards").in_scope(|| {
+        LocalProver::prove_shards(
+            &machine,
+            &pk,
+            shards,
+            &mut challenger,
+            SP1CoreOpts::default(),
+        )
+    });
     let proving_duration = proving_start.elapsed().as_millis();
+    for ((index, estimate), shard_proof) in estimates.iter().zip(proof.shard_proofs.iter()) {
+        log_trace_row_report(*index, &trace_row_report(estimate, shard_proof));
+    }
     let nb_bytes = bincode::serialize(&proof).unwrap().len();
 
     // Print the summary.
@@ -123,6 +239,7 @@ where
     // Execute the program.
     let mut runtime = Runtime::new(program.clone(), opts);
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_hints(&stdin.hints);
     for proof in stdin.proofs.iter() {
         runtime.write_proof(proof.0.clone(), proof.1.clone());
     }
@@ -146,9 +263,10 @@ where
             machine.debug_constraints(&pk, runtime.record.clone(), &mut challenger);
         }
 
-        // Generate the proof and return the proof and public values.
+        // Generate the proof and return the proof and public values. The shards are counted once
+        // the record is sharded, the same way as the shards of each checkpoint below.
         let public_values = std::mem::take(&mut runtime.state.public_values_stream);
-        let proof = prove_simple(machine.config().clone(), runtime)?;
+        let proof = prove_simple_with_opts(machine.config().clone(), runtime, &opts)?;
         return Ok((proof, public_values));
     }
 
@@ -182,8 +300,9 @@ where
     };
 
     // For each checkpoint, generate events, shard them, commit shards, and observe in challenger.
-    let sharding_config = ShardingConfig::default();
+    let sharding_config = ShardingConfig::new(&opts);
     let mut shard_main_datas = Vec::new();
+    let mut num_shards = 0;
     let mut challenger = machine.config().challenger();
     vk.observe_into(&mut challenger);
     for (num, checkpoint_file) in checkpoints.iter_mut().enumerate() {
@@ -192,10 +311,18 @@ where
         record.public_values = public_values;
         reset_seek(&mut *checkpoint_file);
 
-        // Shard the record into shards.
+        // Shard the record into shards, once its events are checked. The events of the checkpoint
+        // are traced the same way again before proving, so they are only checked here.
+        record
+            .validate()
+            .map_err(SP1CoreProverError::InvalidRecord)?;
         let checkpoint_shards =
             tracing::info_span!("shard").in_scope(|| machine.shard(record, &sharding_config));
 
+        // Stop before committing to more shards than allowed.
+        num_shards += checkpoint_shards.len();
+        check_num_shards(num_shards, &opts)?;
+
         // Commit to each shard.
         let (commitments, commit_data) = tracing::info_span!("commit")
             .in_scope(|| LocalProver::commit_shards(&machine, &checkpoint_shards, opts));
@@ -232,13 +359,16 @@ where
                     .shard_chips_ordered(&chip_ordering)
                     .collect::<Vec<_>>()
                     .to_vec();
-                LocalProver::prove_shard(
+                let proof = LocalProver::prove_shard(
                     config,
                     &pk,
                     &ordered_chips,
                     shard_data,
                     &mut challenger.clone(),
-                )
+                );
+                let report = trace_row_report(&shard.estimate_prove_cost(), &proof);
+                log_trace_row_report(shard.index(), &report);
+                proof
             })
             .collect::<Vec<_>>();
         shard_proofs.append(&mut checkpoint_proofs);
@@ -275,6 +405,142 @@ where
     Ok((proof, public_values_stream))
 }
 
+/// The result of executing a program without proving it.
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    /// The public values committed by the program.
+    pub public_values: SP1PublicValues,
+
+    /// The exit code of the program, which is zero if it halted successfully.
+    pub exit_code: u32,
+
+    /// The number of cycles executed.
+    pub cycles: u64,
+
+    /// The events emitted by the execution, which are those a proof of it would prove.
+    pub record: ExecutionRecord,
+
+    /// The opcode and syscall counts of the execution.
+    pub report: ExecutionReport,
+}
+
+/// Executes a program with the given inputs, without generating any trace or proof.
+///
+/// This is meant for checks which only need the outputs of the program. The syscalls run the same
+/// `execute` as when proving, so the events of the record are those a proof would be generated
+/// from. A program halting with a non-zero exit code is reported in the result, not as an error.
+pub fn execute_only(program: Program, stdin: &SP1Stdin) -> Result<ExecutionResult, ExecutionError> {
+    let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+    runtime.write_vecs(&stdin.buffer);
+    runtime.write_hints(&stdin.hints);
+    for proof in stdin.proofs.iter() {
+        runtime.write_proof(proof.0.clone(), proof.1.clone());
+    }
+    let exit_code = match runtime.run() {
+        Ok(()) => 0,
+        Err(ExecutionError::HaltWithNonZeroExitCode(exit_code)) => exit_code,
+        Err(e) => return Err(e),
+    };
+    Ok(ExecutionResult {
+        public_values: SP1PublicValues::from(&runtime.state.public_values_stream),
+        exit_code,
+        cycles: runtime.state.global_clk,
+        record: runtime.record,
+        report: runtime.report,
+    })
+}
+
+/// A checkpoint of an execution, reached after a given number of cycles or when the program
+/// halted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionCheckpoint {
+    /// The state of the execution at the checkpoint, which a later run resumes from.
+    pub state: ExecutionState,
+
+    /// The digest of the memory at the checkpoint.
+    pub memory_digest: [u8; 32],
+
+    /// Whether the program halted before reaching the cycle limit.
+    pub done: bool,
+}
+
+/// Proves the execution of a program with the given inputs up to `clk_limit` cycles, returning the
+/// proof and the checkpoint it reaches.
+///
+/// The proof is of the execution up to the checkpoint, whose memory values close the memory
+/// argument. A later run resumes from the state of the checkpoint with [Runtime::recover].
+pub fn prove_until<SC: StarkGenericConfig + Send + Sync>(
+    program: Program,
+    stdin: &SP1Stdin,
+    config: SC,
+    opts: SP1CoreOpts,
+    clk_limit: u64,
+) -> Result<(MachineProof<SC>, ExecutionCheckpoint), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    let mut runtime = Runtime::new(program, opts);
+    runtime.write_vecs(&stdin.buffer);
+    runtime.write_hints(&stdin.hints);
+    for proof in stdin.proofs.iter() {
+        runtime.write_proof(proof.0.clone(), proof.1.clone());
+    }
+    let done = runtime
+        .run_until(clk_limit)
+        .map_err(SP1CoreProverError::ExecutionError)?;
+    let checkpoint = ExecutionCheckpoint {
+        state: runtime.state.clone(),
+        memory_digest: runtime.state.memory_digest(),
+        done,
+    };
+    let proof = prove_simple(config, runtime)?;
+    Ok((proof, checkpoint))
+}
+
+/// Proves a program which halts right away with exit code zero, returning the proof and the
+/// verifying key of the program.
+///
+/// This is a minimal valid proof for verifier tests, which don't need a real program. The program
+/// commits nothing, so its committed value digest is zero, and proving it is deterministic, so the
+/// same proof is returned for a given config.
+pub fn prove_empty<SC: StarkGenericConfig + Send + Sync>(
+    config: SC,
+) -> Result<(MachineProof<SC>, StarkVerifyingKey<SC>), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    let instructions = vec![
+        Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+        Instruction::new(Opcode::ADD, 10, 0, 0, false, true),
+        Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+    ];
+    let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+    runtime.run().map_err(SP1CoreProverError::ExecutionError)?;
+
+    let machine = RiscvAir::machine(config);
+    let (pk, vk) = machine.setup(runtime.program.as_ref());
+    let mut challenger = machine.config().challenger();
+    let shards = machine.shard(runtime.record, &ShardingConfig::default());
+    let proof = LocalProver::prove_shards(
+        &machine,
+        &pk,
+        shards,
+        &mut challenger,
+        SP1CoreOpts::default(),
+    );
+    Ok((proof, vk))
+}
+
 /// Runs a program and returns the public values stream.
 pub fn run_test_io(
     program: Program,
@@ -283,6 +549,7 @@ pub fn run_test_io(
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&inputs.buffer);
+        runtime.write_hints(&inputs.hints);
         runtime.run().unwrap();
         runtime
     });
@@ -305,6 +572,27 @@ pub fn run_test(
     run_test_core(runtime)
 }
 
+/// Asserts that `record` has `expected` events of the chip named `chip_name`, so that property
+/// tests can check the events a precompile emits for its inputs.
+#[track_caller]
+pub fn assert_event_count(record: &ExecutionRecord, chip_name: &str, expected: usize) {
+    assert!(
+        RiscvAir::<BabyBear>::get_all()
+            .iter()
+            .any(|chip| chip.name() == chip_name),
+        "there is no chip named {}",
+        chip_name
+    );
+    let event_counts = record.event_counts();
+    assert_eq!(
+        event_counts.get(chip_name).copied().unwrap_or(0),
+        expected,
+        "unexpected number of {} events, the record has {:?}",
+        chip_name,
+        event_counts
+    );
+}
+
 #[allow(unused_variables)]
 pub fn run_test_core(
     runtime: Runtime,
@@ -477,7 +765,8 @@ pub mod baby_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use sp1_primitives::RC_16_30;
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{Poseidon2Params, StarkGenericConfig};
+    use crate::utils::INNER_POSEIDON2_PARAMS;
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -642,6 +931,10 @@ pub mod baby_bear_poseidon2 {
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
+
+        fn poseidon2_params(&self) -> Option<Poseidon2Params> {
+            Some(INNER_POSEIDON2_PARAMS)
+        }
     }
 }
 
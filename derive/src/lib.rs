@@ -67,7 +67,52 @@ pub fn aligned_borrow_derive(input: TokenStream) -> TokenStream {
     // Get impl generics (`<T, N: NumLimbs, const M: usize>`), type generics (`<T, N>`), where clause (`where T: Clone`)
     let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
 
+    // Get the name of each field, and its offset in columns. With `T = u8` every column is a
+    // byte, so the offset in bytes is the offset in columns. The fields of a union all start at
+    // the beginning of the struct.
+    let (column_names, column_offsets): (Vec<_>, Vec<_>) = match &ast.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let member = match &field.ident {
+                    Some(ident) => syn::Member::Named(ident.clone()),
+                    None => syn::Member::Unnamed(i.into()),
+                };
+                let column_name = match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => i.to_string(),
+                };
+                let column_offset = quote! {
+                    core::mem::offset_of!(#name<u8 #(, #non_first_generics)*>, #member)
+                };
+                (column_name, column_offset)
+            })
+            .unzip(),
+        Data::Union(data) => data
+            .fields
+            .named
+            .iter()
+            .map(|field| (field.ident.as_ref().unwrap().to_string(), quote! { 0 }))
+            .unzip(),
+        Data::Enum(_) => panic!("Enums are not supported"),
+    };
+
     let methods = quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #type_generics #where_clause {
+            /// The names of the fields, in the order of their columns.
+            pub const fn column_names() -> &'static [&'static str] {
+                &[#(#column_names),*]
+            }
+
+            /// The index of the first column of each field, in the order of `column_names`.
+            pub fn column_offsets() -> Vec<usize> {
+                vec![#(#column_offsets),*]
+            }
+        }
+
         impl #impl_generics core::borrow::Borrow<#name #type_generics> for [#type_generic] #where_clause {
             fn borrow(&self) -> &#name #type_generics {
                 debug_assert_eq!(self.len(), std::mem::size_of::<#name<u8 #(, #non_first_generics)*>>());
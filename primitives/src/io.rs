@@ -0,0 +1,83 @@
+//! The layout of the public values, shared by the guest which commits them and the host which
+//! reads them: the commitment scheme of their digest, and the footer which `syscall_halt` appends
+//! to the public values of programs built with the `footer` feature of `sp1-zkvm`.
+
+use serde::{Deserialize, Serialize};
+
+/// The hash function of the digest of the public values, which the program selects at runtime with
+/// `set_commitment_scheme` before it commits any public value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Serialize, Deserialize)]
+#[repr(u32)]
+pub enum CommitmentScheme {
+    /// SHA-256, which the Solidity verifier hashes the public values with.
+    #[default]
+    Sha256 = 0,
+    /// Poseidon2 over BabyBear, the hash function of the recursion layer, as computed by
+    /// `poseidon2_hash_bytes`. The Solidity verifier does not support it.
+    Poseidon2 = 1,
+    /// Keccak256, the hash function of Ethereum.
+    Keccak256 = 2,
+}
+
+impl CommitmentScheme {
+    /// All the commitment schemes.
+    pub const ALL: [CommitmentScheme; 3] = [
+        CommitmentScheme::Sha256,
+        CommitmentScheme::Poseidon2,
+        CommitmentScheme::Keccak256,
+    ];
+
+    /// Returns the scheme with the given id, if any.
+    pub const fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(CommitmentScheme::Sha256),
+            1 => Some(CommitmentScheme::Poseidon2),
+            2 => Some(CommitmentScheme::Keccak256),
+            _ => None,
+        }
+    }
+}
+
+/// The magic ending the footer.
+pub const FOOTER_MAGIC: [u8; 8] = *b"SP1FOOTR";
+
+/// The version of the layout of the footer.
+pub const FOOTER_VERSION: u32 = 1;
+
+/// The kinds of the entries of the footer, in the order they are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FooterEntryKind {
+    /// The schema id set with `commit_with_schema`.
+    SchemaId = 1,
+    /// The nonce committed with `commit_nonce`.
+    Nonce = 2,
+    /// The digest of the inputs committed with `commit_input_digest`.
+    InputDigest = 3,
+    /// The tags of the invariants checked with `prove_assert`.
+    Invariants = 4,
+    /// The error code set with `set_error_code`.
+    ErrorCode = 5,
+    /// The marker of a panic with a code registered with `register_panic_code`, without payload.
+    Panicked = 6,
+}
+
+impl FooterEntryKind {
+    /// Returns the id of the kind in the footer.
+    pub const fn id(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Returns the kind with the given id, if any.
+    pub const fn from_id(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(FooterEntryKind::SchemaId),
+            2 => Some(FooterEntryKind::Nonce),
+            3 => Some(FooterEntryKind::InputDigest),
+            4 => Some(FooterEntryKind::Invariants),
+            5 => Some(FooterEntryKind::ErrorCode),
+            6 => Some(FooterEntryKind::Panicked),
+            _ => None,
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! sp1-primitives contains types and functions that are used in both sp1-core and sp1-zkvm.
 //! Because it is imported in the zkvm entrypoint, it should be kept minimal.
 
+pub mod io;
+
 use lazy_static::lazy_static;
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 
 lazy_static! {
@@ -1151,6 +1153,31 @@ lazy_static! {
     > = poseidon2_hasher();
 }
 
+/// Hashes `data` with Poseidon2, which is the digest of the public values under
+/// `CommitmentScheme::Poseidon2`.
+///
+/// The bytes are packed three by three into little-endian BabyBear elements, followed by the number
+/// of bytes so that inputs of different lengths do not collide, and the digest is the eight output
+/// elements as little-endian u32s.
+pub fn poseidon2_hash_bytes(data: &[u8]) -> [u8; 32] {
+    let mut input = data
+        .chunks(3)
+        .map(|chunk| {
+            let word = chunk
+                .iter()
+                .rev()
+                .fold(0u32, |word, byte| word << 8 | *byte as u32);
+            BabyBear::from_canonical_u32(word)
+        })
+        .collect::<Vec<_>>();
+    input.push(BabyBear::from_canonical_usize(data.len()));
+    let mut digest = [0u8; 32];
+    for (bytes, element) in digest.chunks_exact_mut(4).zip(poseidon2_hash(input)) {
+        bytes.copy_from_slice(&element.as_canonical_u32().to_le_bytes());
+    }
+    digest
+}
+
 /// Append a single deferred proof to a hash chain of deferred proofs.
 pub fn hash_deferred_proof(
     prev_digest: &[BabyBear; 8],
@@ -26,7 +26,7 @@ use p3_field::{AbstractField, PrimeField};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rayon::prelude::*;
 use sp1_core::air::{PublicValues, Word};
-pub use sp1_core::io::{SP1PublicValues, SP1Stdin};
+pub use sp1_core::io::{CommitmentScheme, SP1PublicValues, SP1Stdin};
 use sp1_core::runtime::{ExecutionError, ExecutionReport, Runtime};
 use sp1_core::stark::{Challenge, StarkProvingKey};
 use sp1_core::stark::{Challenger, MachineVerificationError};
@@ -144,6 +144,9 @@ pub struct SP1Prover {
 
     /// The options for the recursion prover.
     pub recursion_opts: SP1CoreOpts,
+
+    /// The number of proofs verified by each node of the reduction tree.
+    pub reduce_batch_size: usize,
 }
 
 impl SP1Prover {
@@ -203,6 +206,7 @@ impl SP1Prover {
             wrap_machine,
             core_opts: SP1CoreOpts::default(),
             recursion_opts: SP1CoreOpts::recursion(),
+            reduce_batch_size: 2,
         }
     }
 
@@ -226,18 +230,46 @@ impl SP1Prover {
         elf: &[u8],
         stdin: &SP1Stdin,
     ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        let runtime = Self::run_untraced(elf, stdin)?;
+        Ok((
+            SP1PublicValues::from(&runtime.state.public_values_stream),
+            runtime.report,
+        ))
+    }
+
+    /// Executes an SP1 program like [Self::execute], and also returns the commitment scheme the
+    /// program selected for the digest of its public values, or `None` if the digest it committed
+    /// to is not that of its public values under any scheme.
+    #[instrument(name = "execute", level = "info", skip_all)]
+    pub fn execute_with_commitment_scheme(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, Option<CommitmentScheme>, ExecutionReport), ExecutionError> {
+        let runtime = Self::run_untraced(elf, stdin)?;
+        let public_values = SP1PublicValues::from(&runtime.state.public_values_stream);
+        let digest: Vec<u8> = runtime
+            .record
+            .public_values
+            .committed_value_digest
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let scheme = public_values.commitment_scheme(&digest.try_into().unwrap());
+        Ok((public_values, scheme, runtime.report))
+    }
+
+    /// Runs an SP1 program with the specified inputs without tracing.
+    fn run_untraced(elf: &[u8], stdin: &SP1Stdin) -> Result<Runtime<'static>, ExecutionError> {
         let program = Program::from(elf);
         let opts = SP1CoreOpts::default();
         let mut runtime = Runtime::new(program, opts);
         runtime.write_vecs(&stdin.buffer);
+        runtime.write_hints(&stdin.hints);
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
         }
         runtime.run_untraced()?;
-        Ok((
-            SP1PublicValues::from(&runtime.state.public_values_stream),
-            runtime.report,
-        ))
+        Ok(runtime)
     }
 
     /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
@@ -390,7 +422,7 @@ impl SP1Prover {
         deferred_proofs: Vec<ShardProof<InnerSC>>,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
         // Set the batch size for the reduction tree.
-        let batch_size = 2;
+        let batch_size = self.reduce_batch_size;
 
         let shard_proofs = &proof.proof.0;
         // Get the leaf challenger.
@@ -410,6 +442,13 @@ impl SP1Prover {
             batch_size,
         );
 
+        // Record the number of proofs verified by each node of the first layer.
+        let mut layers = vec![core_inputs
+            .iter()
+            .map(|input| input.shard_proofs.len())
+            .chain(deferred_inputs.iter().map(|input| input.proofs.len()))
+            .collect::<Vec<_>>()];
+
         let mut reduce_proofs = Vec::new();
         let opts = self.recursion_opts;
         let shard_batch_size = opts.shard_batch_size;
@@ -448,6 +487,7 @@ impl SP1Prover {
             is_complete = reduce_proofs.len() <= batch_size;
 
             let compress_inputs = reduce_proofs.chunks(batch_size).collect::<Vec<_>>();
+            layers.push(compress_inputs.iter().map(|batch| batch.len()).collect());
             let batched_compress_inputs =
                 compress_inputs.chunks(shard_batch_size).collect::<Vec<_>>();
             reduce_proofs = batched_compress_inputs
@@ -487,6 +527,7 @@ impl SP1Prover {
 
         Ok(SP1ReduceProof {
             proof: reduce_proof.0,
+            tree_shape: TreeShape { layers },
         })
     }
 
@@ -524,6 +565,7 @@ impl SP1Prover {
         reduced_proof: SP1ReduceProof<InnerSC>,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
         // Make the compress proof.
+        let tree_shape = reduced_proof.tree_shape;
         let input = SP1RootMemoryLayout {
             machine: &self.compress_machine,
             proof: reduced_proof.proof,
@@ -556,6 +598,7 @@ impl SP1Prover {
 
         Ok(SP1ReduceProof {
             proof: compress_proof.shard_proofs.pop().unwrap(),
+            tree_shape,
         })
     }
 
@@ -565,6 +608,7 @@ impl SP1Prover {
         &self,
         compressed_proof: SP1ReduceProof<InnerSC>,
     ) -> Result<SP1ReduceProof<OuterSC>, SP1RecursionProverError> {
+        let tree_shape = compressed_proof.tree_shape;
         let input = SP1RootMemoryLayout {
             machine: &self.shrink_machine,
             proof: compressed_proof.proof,
@@ -612,6 +656,7 @@ impl SP1Prover {
 
         Ok(SP1ReduceProof {
             proof: wrap_proof.shard_proofs.pop().unwrap(),
+            tree_shape,
         })
     }
 
@@ -754,6 +799,52 @@ mod tests {
         Ok(())
     }
 
+    /// Tests that the reduction tree of a compressed proof follows the configured batch size.
+    #[test]
+    #[serial]
+    fn test_compress_tree_shape() -> Result<()> {
+        setup_logger();
+        let elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+
+        let mut prover = SP1Prover::new();
+        prover.core_opts.shard_size = 1 << 12;
+        prover.reduce_batch_size = 3;
+        let (pk, vk) = prover.setup(elf);
+
+        let core_proof = prover.prove_core(&pk, &SP1Stdin::new())?;
+        let num_shards = core_proof.proof.0.len();
+        let compressed_proof = prover.compress(&vk, core_proof, vec![])?;
+        prover.verify_compressed(&compressed_proof, &vk)?;
+
+        // Every node but the last one of each layer has exactly the configured number of children.
+        let shape = compressed_proof.tree_shape();
+        let batches = |num_children: usize| {
+            let mut layer = vec![3; num_children / 3];
+            if num_children % 3 != 0 {
+                layer.push(num_children % 3);
+            }
+            layer
+        };
+        let mut expected_layers = vec![batches(num_shards)];
+        loop {
+            let layer = batches(expected_layers.last().unwrap().len());
+            let is_root = layer.len() == 1;
+            expected_layers.push(layer);
+            if is_root {
+                break;
+            }
+        }
+        assert_eq!(shape.layers, expected_layers);
+        assert_eq!(shape.leaf_count(), num_shards);
+        assert!(shape.max_fan_out() <= 3);
+
+        // The shape is carried over when the proof is shrunk.
+        let shrink_proof = prover.shrink(compressed_proof)?;
+        assert_eq!(shrink_proof.tree_shape(), shape);
+
+        Ok(())
+    }
+
     /// Tests an end-to-end workflow of proving a program across the entire proof generation
     /// pipeline in addition to verifying deferred proofs.
     #[test]
@@ -80,10 +80,15 @@ where
 {
     fn hash_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
         let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
-        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len());
+        let poseidon2_params = self
+            .poseidon2_params
+            .iter()
+            .flat_map(|params| params.to_field_elements::<BabyBear>());
+        let num_inputs = DIGEST_SIZE + 1 + 3 + (4 * prep_domains.len());
         let mut inputs = Vec::with_capacity(num_inputs);
         inputs.extend(self.commit.as_ref());
         inputs.push(self.pc_start);
+        inputs.extend(poseidon2_params);
         for domain in prep_domains {
             inputs.push(BabyBear::from_canonical_usize(domain.log_n));
             let size = 1 << domain.log_n;
@@ -166,6 +171,46 @@ pub struct SP1PlonkProofData(pub PlonkBn254Proof);
 #[serde(bound(deserialize = "ShardProof<SC>: Deserialize<'de>"))]
 pub struct SP1ReduceProof<SC: StarkGenericConfig> {
     pub proof: ShardProof<SC>,
+    /// The shape of the tree of recursive proofs which was reduced into this proof.
+    #[serde(default)]
+    pub tree_shape: TreeShape,
+}
+
+impl<SC: StarkGenericConfig> SP1ReduceProof<SC> {
+    /// Returns the shape of the tree of recursive proofs which was reduced into this proof.
+    ///
+    /// The shape is only known to the prover, so it is empty for proofs which were not produced by
+    /// `SP1Prover::compress` or by wrapping such a proof.
+    pub fn tree_shape(&self) -> TreeShape {
+        self.tree_shape.clone()
+    }
+}
+
+/// The shape of a tree of recursive proofs.
+///
+/// The first layer contains the proofs verifying batches of shard proofs and deferred proofs, and
+/// each following layer reduces batches of the proofs of the previous layer, up to the root.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeShape {
+    /// The number of children of each node, layer by layer from the first layer to the root.
+    pub layers: Vec<Vec<usize>>,
+}
+
+impl TreeShape {
+    /// The number of layers of recursive proofs.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// The number of shard proofs and deferred proofs verified by the first layer.
+    pub fn leaf_count(&self) -> usize {
+        self.layers.first().map_or(0, |layer| layer.iter().sum())
+    }
+
+    /// The largest number of children of a node.
+    pub fn max_fan_out(&self) -> usize {
+        self.layers.iter().flatten().copied().max().unwrap_or(0)
+    }
 }
 
 impl SP1ReduceProof<BabyBearPoseidon2Outer> {
@@ -31,6 +31,7 @@ pub fn get_cycles(elf: &[u8], stdin: &SP1Stdin) -> u64 {
     let program = Program::from(elf);
     let mut runtime = Runtime::new(program, SP1CoreOpts::default());
     runtime.write_vecs(&stdin.buffer);
+    runtime.write_hints(&stdin.hints);
     runtime.dry_run();
     runtime.state.global_clk
 }
@@ -18,6 +18,7 @@ use thiserror::Error;
 
 use crate::{
     CoreSC, HashableKey, OuterSC, SP1CoreProofData, SP1Prover, SP1ReduceProof, SP1VerifyingKey,
+    TreeShape,
 };
 
 #[derive(Error, Debug)]
@@ -276,6 +277,9 @@ impl SP1Prover {
 }
 
 /// Verify the vk_hash and public_values_hash in the public inputs of the PlonkBn254Proof match the expected values.
+///
+/// The public values are hashed with the commitment scheme the program selected, which is the one
+/// under which they hash to the public values hash of the proof.
 pub fn verify_plonk_bn254_public_inputs(
     vk: &SP1VerifyingKey,
     public_values: &SP1PublicValues,
@@ -289,8 +293,10 @@ pub fn verify_plonk_bn254_public_inputs(
         return Err(PlonkVerificationError::InvalidVerificationKey.into());
     }
 
-    let public_values_hash = public_values.hash();
-    if public_values_hash != expected_public_values_hash {
+    if public_values
+        .commitment_scheme_of_hash(&expected_public_values_hash)
+        .is_none()
+    {
         return Err(PlonkVerificationError::InvalidPublicValues.into());
     }
 
@@ -315,6 +321,7 @@ impl SubproofVerifier for &SP1Prover {
         self.verify_compressed(
             &SP1ReduceProof {
                 proof: proof.clone(),
+                tree_shape: TreeShape::default(),
             },
             &SP1VerifyingKey { vk: vk.clone() },
         )?;
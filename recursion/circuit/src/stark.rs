@@ -255,6 +255,14 @@ pub fn build_wrap_circuit(
     challenger.observe_commitment(&mut builder, preprocessed_commit);
     let pc_start = builder.eval(wrap_vk.pc_start);
     challenger.observe(&mut builder, pc_start);
+    for value in wrap_vk
+        .poseidon2_params
+        .iter()
+        .flat_map(|params| params.to_field_elements::<BabyBear>())
+    {
+        let param = builder.eval(value);
+        challenger.observe(&mut builder, param);
+    }
 
     let mut witness = Witness::default();
     template_proof.write(&mut witness);
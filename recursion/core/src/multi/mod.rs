@@ -73,6 +73,7 @@ impl<F: PrimeField32, const DEGREE: usize> MachineAir<F> for MultiChip<DEGREE> {
         let poseidon2 = Poseidon2Chip {
             fixed_log2_rows: None,
             pad: false,
+            ..Default::default()
         };
         let fri_fold_trace = fri_fold_chip.generate_trace(input, output);
         let mut poseidon2_trace = poseidon2.generate_trace(input, output);
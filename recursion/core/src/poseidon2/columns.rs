@@ -60,3 +60,12 @@ pub struct MemAccessCols<T> {
     pub addr_second_half: T,
     pub mem_access: [MemoryReadWriteSingleCols<T>; WIDTH],
 }
+
+/// The columns writing the output of a computation round to memory, appended to each row when the
+/// chip traces every round.
+#[derive(AlignedBorrow, Clone, Copy)]
+#[repr(C)]
+pub struct RoundStateCols<T: Copy> {
+    pub do_memory: T,
+    pub mem_access: [MemoryReadWriteSingleCols<T>; WIDTH],
+}
@@ -2,21 +2,27 @@ use core::borrow::Borrow;
 use core::mem::size_of;
 use p3_air::AirBuilder;
 use p3_air::{Air, BaseAir};
-use p3_field::AbstractField;
+use p3_field::{AbstractField, PrimeField32};
 use p3_matrix::Matrix;
 use sp1_core::air::{BaseAirBuilder, ExtensionAirBuilder, SP1AirBuilder};
 use sp1_primitives::RC_16_30_U32;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 
 use crate::air::{RecursionInteractionAirBuilder, RecursionMemoryAirBuilder};
 use crate::memory::MemoryCols;
 use crate::poseidon2_wide::{apply_m_4, internal_linear_layer};
 use crate::runtime::Opcode;
 
-use super::columns::Poseidon2Cols;
+use super::columns::{Poseidon2Cols, RoundStateCols};
+use super::NUM_COMPUTATION_ROUNDS;
 
 /// The number of main trace columns for `AddChip`.
 pub const NUM_POSEIDON2_COLS: usize = size_of::<Poseidon2Cols<u8>>();
 
+/// The number of columns appended to each row when the chip traces every round.
+pub const NUM_ROUND_STATE_COLS: usize = size_of::<RoundStateCols<u8>>();
+
 /// The width of the permutation.
 pub const WIDTH: usize = 16;
 
@@ -25,11 +31,110 @@ pub const WIDTH: usize = 16;
 pub struct Poseidon2Chip {
     pub fixed_log2_rows: Option<usize>,
     pub pad: bool,
+    /// An optional backend computing the permutation during trace generation.
+    pub backend: Option<Arc<dyn Poseidon2Backend>>,
+    /// If set, every computation round also writes its output state to memory at
+    /// `round_states_ptr + WIDTH * (round - 1)`, where round 1 is the initial layer. This is a
+    /// debugging mode, which must match the runtime's `poseidon2_round_states_ptr`.
+    pub round_states_ptr: Option<usize>,
+    /// If set, trace generation records the intermediate states of every permutation. This is a
+    /// debugging mode, which does not change the trace.
+    pub state_recorder: Option<Arc<Poseidon2StateRecorder>>,
+}
+
+/// The intermediate states of the permutations whose trace a [Poseidon2Chip] generated, keyed by
+/// the index of their event in the record.
+///
+/// The states of a permutation are the input state of each of its `NUM_COMPUTATION_ROUNDS`
+/// computation rounds, followed by the output state, as canonical BabyBear values.
+#[derive(Debug, Default)]
+pub struct Poseidon2StateRecorder {
+    states: Mutex<BTreeMap<usize, Vec<[u32; WIDTH]>>>,
+}
+
+impl Poseidon2StateRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the states of the permutation of the event at `event_index`, if its trace was
+    /// generated.
+    pub fn round_states<F: PrimeField32>(&self, event_index: usize) -> Option<Vec<[F; WIDTH]>> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        let event_states = states.get(&event_index)?;
+        Some(
+            event_states
+                .iter()
+                .map(|state| state.map(F::from_canonical_u32))
+                .collect(),
+        )
+    }
+
+    /// Returns the number of permutations whose states were recorded.
+    pub fn len(&self) -> usize {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Returns whether no permutation was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Records the states of the permutation of the event at `event_index`, replacing those of a
+    /// previous trace generation.
+    pub(crate) fn record<F: PrimeField32>(&self, event_index: usize, states: &[[F; WIDTH]]) {
+        let states = states
+            .iter()
+            .map(|state| state.map(|x| x.as_canonical_u32()))
+            .collect();
+        let mut recorded = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        recorded.insert(event_index, states);
+    }
+}
+
+/// A backend computing the Poseidon2 permutation for witness generation, e.g. a vectorized or
+/// GPU implementation on the host.
+///
+/// The state is given as canonical BabyBear values. The backend only computes the final state of
+/// the permutation, so it does not change the constraints or the intermediate rounds of the trace.
+pub trait Poseidon2Backend: Send + Sync {
+    /// Returns the permutation of `state`.
+    fn permute(&self, state: [u32; WIDTH]) -> [u32; WIDTH];
+}
+
+impl Poseidon2Chip {
+    /// Returns the chip with the given backend computing the final state of each permutation.
+    ///
+    /// Trace generation still fills the intermediate rounds for the constraints, and in debug
+    /// builds checks that they reach the state computed by the backend.
+    pub fn with_permutation_backend(mut self, backend: Arc<dyn Poseidon2Backend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Returns the chip recording the intermediate states of every permutation into `recorder`
+    /// during trace generation, where they can be inspected without reading the trace.
+    pub fn with_state_recorder(mut self, recorder: Arc<Poseidon2StateRecorder>) -> Self {
+        self.state_recorder = Some(recorder);
+        self
+    }
+
+    /// Returns the chip writing the output of every computation round to memory at
+    /// `round_states_ptr`, so that the intermediate states of a permutation can be inspected.
+    ///
+    /// The default chip only accesses memory in the first and last rounds.
+    pub fn with_round_states(mut self, round_states_ptr: usize) -> Self {
+        self.round_states_ptr = Some(round_states_ptr);
+        self
+    }
 }
 
 impl<F> BaseAir<F> for Poseidon2Chip {
     fn width(&self) -> usize {
-        NUM_POSEIDON2_COLS
+        match self.round_states_ptr {
+            Some(_) => NUM_POSEIDON2_COLS + NUM_ROUND_STATE_COLS,
+            None => NUM_POSEIDON2_COLS,
+        }
     }
 }
 
@@ -387,6 +492,40 @@ impl Poseidon2Chip {
         );
     }
 
+    /// Writes the output of each computation round to memory at `round_states_ptr`.
+    fn eval_round_states<AB: BaseAirBuilder + ExtensionAirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Poseidon2Cols<AB::Var>,
+        round_state: &RoundStateCols<AB::Var>,
+        round_states_ptr: usize,
+    ) {
+        // The computation rounds follow the memory read round.
+        let computation_rounds = 1..NUM_COMPUTATION_ROUNDS + 1;
+        let is_computation = computation_rounds
+            .clone()
+            .map(|r| local.rounds[r].into())
+            .sum::<AB::Expr>();
+        builder.assert_eq(round_state.do_memory, local.is_real * is_computation);
+
+        let round_addr = computation_rounds
+            .map(|r| local.rounds[r] * AB::F::from_canonical_usize(WIDTH * (r - 1)))
+            .sum::<AB::Expr>()
+            + AB::F::from_canonical_usize(round_states_ptr);
+        let computation_cols = local.round_specific_cols.computation();
+        for i in 0..WIDTH {
+            builder.recursion_eval_memory_access_single(
+                local.clk + AB::Expr::one(),
+                round_addr.clone() + AB::F::from_canonical_usize(i),
+                &round_state.mem_access[i],
+                round_state.do_memory,
+            );
+            builder
+                .when(round_state.do_memory)
+                .assert_eq(*round_state.mem_access[i].value(), computation_cols.output[i]);
+        }
+    }
+
     pub const fn do_receive_table<T: Copy>(local: &Poseidon2Cols<T>) -> T {
         local.do_receive
     }
@@ -402,10 +541,10 @@ where
 {
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
-        let local = main.row_slice(0);
-        let local: &Poseidon2Cols<AB::Var> = (*local).borrow();
-        let next = main.row_slice(1);
-        let next: &Poseidon2Cols<AB::Var> = (*next).borrow();
+        let local_row = main.row_slice(0);
+        let local: &Poseidon2Cols<AB::Var> = local_row[..NUM_POSEIDON2_COLS].borrow();
+        let next_row = main.row_slice(1);
+        let next: &Poseidon2Cols<AB::Var> = next_row[..NUM_POSEIDON2_COLS].borrow();
 
         self.eval_poseidon2::<AB>(
             builder,
@@ -414,6 +553,11 @@ where
             Self::do_receive_table::<AB::Var>(local),
             Self::do_memory_access::<AB::Var>(local),
         );
+
+        if let Some(round_states_ptr) = self.round_states_ptr {
+            let round_state: &RoundStateCols<AB::Var> = local_row[NUM_POSEIDON2_COLS..].borrow();
+            self.eval_round_states(builder, local, round_state, round_states_ptr);
+        }
     }
 }
 
@@ -421,28 +565,35 @@ where
 mod tests {
     use itertools::Itertools;
     use std::borrow::Borrow;
+    use std::sync::Arc;
     use std::time::Instant;
     use zkhash::ark_ff::UniformRand;
 
     use p3_baby_bear::BabyBear;
     use p3_baby_bear::DiffusionMatrixBabyBear;
+    use p3_field::{AbstractField, PrimeField32};
     use p3_matrix::{dense::RowMajorMatrix, Matrix};
     use p3_poseidon2::Poseidon2;
     use p3_poseidon2::Poseidon2ExternalMatrixGeneral;
     use sp1_core::stark::StarkGenericConfig;
     use sp1_core::utils::inner_perm;
     use sp1_core::{
-        air::MachineAir,
+        air::{MachineAir, PackedTraceAir, TraceLayout},
         utils::{uni_stark_prove, uni_stark_verify, BabyBearPoseidon2},
     };
 
     use crate::{
-        poseidon2::{Poseidon2Chip, Poseidon2Event},
+        air::Block,
+        memory::MemoryRecord,
+        poseidon2::{
+            round_states, Poseidon2Chip, Poseidon2Event, Poseidon2StateRecorder,
+            NUM_COMPUTATION_ROUNDS,
+        },
         runtime::ExecutionRecord,
     };
     use p3_symmetric::Permutation;
 
-    use super::Poseidon2Cols;
+    use super::{Poseidon2Backend, Poseidon2Cols, RoundStateCols, NUM_POSEIDON2_COLS, WIDTH};
 
     const ROWS_PER_PERMUTATION: usize = 24;
 
@@ -451,6 +602,7 @@ mod tests {
         let chip = Poseidon2Chip {
             fixed_log2_rows: None,
             pad: true,
+            ..Default::default()
         };
 
         let rng = &mut rand::thread_rng();
@@ -490,6 +642,228 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generate_trace_reproducible() {
+        let rng = &mut rand::thread_rng();
+        let mut input_exec = ExecutionRecord::<BabyBear>::default();
+        for i in 0..64 {
+            let input: [BabyBear; 16] = core::array::from_fn(|_| BabyBear::rand(rng));
+            let mut event = Poseidon2Event::dummy_from_input(input, inner_perm().permute(input));
+            event.clk = BabyBear::from_canonical_usize(4 * i);
+            input_exec.poseidon2_events.push(event);
+        }
+
+        let chip = Poseidon2Chip {
+            fixed_log2_rows: None,
+            pad: true,
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+        for _ in 0..4 {
+            let other: RowMajorMatrix<BabyBear> =
+                chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+            assert_eq!(trace.values, other.values);
+        }
+
+        // The rows of each permutation follow the order of the events.
+        for (i, event) in input_exec.poseidon2_events.iter().enumerate() {
+            for r in 0..ROWS_PER_PERMUTATION {
+                let row = trace.row(ROWS_PER_PERMUTATION * i + r).collect_vec();
+                let cols: &Poseidon2Cols<BabyBear> = row.as_slice().borrow();
+                assert_eq!(cols.clk, event.clk);
+            }
+        }
+    }
+
+    #[test]
+    fn generate_packed_trace() {
+        let rng = &mut rand::thread_rng();
+        let mut input_exec = ExecutionRecord::<BabyBear>::default();
+        for _ in 0..8 {
+            let input: [BabyBear; 16] = core::array::from_fn(|_| BabyBear::rand(rng));
+            let output = inner_perm().permute(input);
+            input_exec
+                .poseidon2_events
+                .push(Poseidon2Event::dummy_from_input(input, output));
+        }
+
+        let chip = Poseidon2Chip {
+            fixed_log2_rows: None,
+            pad: true,
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        // The packed trace reads back as the trace of the CPU prover in every layout.
+        for layout in [
+            TraceLayout::ColumnMajor,
+            TraceLayout::Interleaved {
+                block_height: ROWS_PER_PERMUTATION,
+            },
+        ] {
+            let packed = chip.generate_packed_trace(
+                &input_exec,
+                &mut ExecutionRecord::<BabyBear>::default(),
+                layout,
+            );
+            assert_eq!(packed.values.len(), trace.values.len());
+            let unpacked: RowMajorMatrix<BabyBear> = packed.unpack();
+            assert_eq!(unpacked.width(), NUM_POSEIDON2_COLS);
+            assert_eq!(unpacked.values, trace.values);
+        }
+    }
+
+    /// A backend computing the permutation with the Plonky3 implementation.
+    struct InnerPermBackend;
+
+    impl Poseidon2Backend for InnerPermBackend {
+        fn permute(&self, state: [u32; 16]) -> [u32; 16] {
+            inner_perm()
+                .permute(state.map(BabyBear::from_canonical_u32))
+                .map(|x| x.as_canonical_u32())
+        }
+    }
+
+    #[test]
+    fn generate_trace_with_backend() {
+        let rng = &mut rand::thread_rng();
+        let mut input_exec = ExecutionRecord::<BabyBear>::default();
+        for _ in 0..16 {
+            let input: [BabyBear; 16] = core::array::from_fn(|_| BabyBear::rand(rng));
+            let output = inner_perm().permute(input);
+            input_exec
+                .poseidon2_events
+                .push(Poseidon2Event::dummy_from_input(input, output));
+        }
+
+        let chip = Poseidon2Chip {
+            fixed_log2_rows: None,
+            pad: true,
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        let chip = chip.with_permutation_backend(Arc::new(InnerPermBackend));
+        let backend_trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        assert_eq!(trace.values, backend_trace.values);
+    }
+
+    #[test]
+    fn generate_trace_with_state_recorder() {
+        let rng = &mut rand::thread_rng();
+        let mut input_exec = ExecutionRecord::<BabyBear>::default();
+        let mut outputs = vec![];
+        for _ in 0..4 {
+            let input: [BabyBear; 16] = core::array::from_fn(|_| BabyBear::rand(rng));
+            let output = inner_perm().permute(input);
+            outputs.push(output);
+            input_exec
+                .poseidon2_events
+                .push(Poseidon2Event::dummy_from_input(input, output));
+        }
+
+        let chip = Poseidon2Chip {
+            fixed_log2_rows: None,
+            pad: true,
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        let recorder = Arc::new(Poseidon2StateRecorder::new());
+        let chip = chip.with_state_recorder(recorder.clone());
+        let recorded_trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        // Recording the states does not change the trace.
+        assert_eq!(trace.values, recorded_trace.values);
+        assert_eq!(recorder.len(), input_exec.poseidon2_events.len());
+        for (i, event) in input_exec.poseidon2_events.iter().enumerate() {
+            let states = recorder.round_states::<BabyBear>(i).unwrap();
+            assert_eq!(states.len(), NUM_COMPUTATION_ROUNDS + 1);
+            assert_eq!(states[0], event.input);
+            assert_eq!(states[NUM_COMPUTATION_ROUNDS], outputs[i]);
+
+            // The input of each round after the initial layer is the output of the previous one.
+            let expected = round_states(event.input);
+            assert_eq!(states[1..], expected[..]);
+        }
+        assert!(recorder.round_states::<BabyBear>(4).is_none());
+    }
+
+    #[test]
+    fn generate_trace_with_round_states() {
+        const ROUND_STATES_PTR: usize = 1 << 20;
+
+        let rng = &mut rand::thread_rng();
+        let mut input_exec = ExecutionRecord::<BabyBear>::default();
+        for _ in 0..16 {
+            let input: [BabyBear; 16] = core::array::from_fn(|_| BabyBear::rand(rng));
+            let states = round_states(input);
+            let mut event = Poseidon2Event::dummy_from_input(input, inner_perm().permute(input));
+            event.round_state_records = states
+                .iter()
+                .enumerate()
+                .map(|(r, state)| {
+                    core::array::from_fn(|i| {
+                        MemoryRecord::new_write(
+                            BabyBear::from_canonical_usize(ROUND_STATES_PTR + WIDTH * r + i),
+                            Block::from(state[i]),
+                            BabyBear::two(),
+                            Block::from(BabyBear::zero()),
+                            BabyBear::zero(),
+                        )
+                    })
+                })
+                .collect();
+            input_exec.poseidon2_events.push(event);
+        }
+
+        let chip = Poseidon2Chip {
+            fixed_log2_rows: None,
+            pad: true,
+            ..Default::default()
+        };
+        let trace: RowMajorMatrix<BabyBear> =
+            chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        let traced_chip = chip.with_round_states(ROUND_STATES_PTR);
+        let traced_trace: RowMajorMatrix<BabyBear> =
+            traced_chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
+
+        // The traced mode only appends columns, so the final states match the default mode's.
+        assert_eq!(trace.height(), traced_trace.height());
+        for r in 0..trace.height() {
+            let traced_row = traced_trace.row(r).collect_vec();
+            assert_eq!(trace.row(r).collect_vec(), traced_row[..NUM_POSEIDON2_COLS]);
+        }
+        for (i, event) in input_exec.poseidon2_events.iter().enumerate() {
+            let last_round = ROWS_PER_PERMUTATION * (i + 1) - 2;
+            let row = traced_trace.row(last_round).collect_vec();
+            let cols: &Poseidon2Cols<BabyBear> = row[..NUM_POSEIDON2_COLS].borrow();
+            let round_state: &RoundStateCols<BabyBear> = row[NUM_POSEIDON2_COLS..].borrow();
+            let final_state = inner_perm().permute(event.input);
+            assert_eq!(cols.round_specific_cols.computation().output, final_state);
+            assert_eq!(
+                round_state.mem_access.map(|access| access.access.value),
+                final_state
+            );
+            assert_eq!(round_states(event.input)[NUM_COMPUTATION_ROUNDS - 1], final_state);
+        }
+
+        let config = BabyBearPoseidon2::compressed();
+        let mut challenger = config.challenger();
+        let proof = uni_stark_prove(&config, &traced_chip, &mut challenger, traced_trace);
+        let mut challenger = config.challenger();
+        uni_stark_verify(&config, &traced_chip, &mut challenger, &proof)
+            .expect("expected proof to be valid");
+    }
+
     fn prove_babybear(inputs: Vec<[BabyBear; 16]>, outputs: Vec<[BabyBear; 16]>) {
         let mut input_exec = ExecutionRecord::<BabyBear>::default();
         for (input, output) in inputs.into_iter().zip_eq(outputs) {
@@ -501,6 +875,7 @@ mod tests {
         let chip = Poseidon2Chip {
             fixed_log2_rows: None,
             pad: true,
+            ..Default::default()
         };
         let trace: RowMajorMatrix<BabyBear> =
             chip.generate_trace(&input_exec, &mut ExecutionRecord::<BabyBear>::default());
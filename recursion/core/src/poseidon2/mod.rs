@@ -6,10 +6,16 @@ pub mod external;
 mod trace;
 use crate::air::Block;
 use crate::memory::MemoryRecord;
+use crate::poseidon2_wide::{external_linear_layer, internal_linear_layer};
 use p3_field::PrimeField32;
+use sp1_primitives::RC_16_30_U32;
 
-pub use columns::Poseidon2Cols;
-pub use external::Poseidon2Chip;
+pub use columns::{Poseidon2Cols, RoundStateCols};
+pub use external::{Poseidon2Chip, Poseidon2StateRecorder};
+
+/// The number of rounds computing the permutation: the initial linear layer, followed by 8
+/// external and 13 internal rounds.
+pub const NUM_COMPUTATION_ROUNDS: usize = 22;
 
 #[derive(Debug, Clone)]
 pub struct Poseidon2Event<F> {
@@ -21,6 +27,8 @@ pub struct Poseidon2Event<F> {
     pub result_array: [F; WIDTH],
     pub input_records: [MemoryRecord<F>; WIDTH],
     pub result_records: [MemoryRecord<F>; WIDTH],
+    /// The writes of the output of each computation round, if the runtime traces every round.
+    pub round_state_records: Vec<[MemoryRecord<F>; WIDTH]>,
 }
 
 impl<F: PrimeField32> Poseidon2Event<F> {
@@ -42,6 +50,35 @@ impl<F: PrimeField32> Poseidon2Event<F> {
             result_array: [F::zero(); WIDTH],
             input_records,
             result_records: output_records,
+            round_state_records: vec![],
         }
     }
 }
+
+/// Returns the state after each computation round of the permutation of `input`. The last state
+/// is the output of the permutation.
+pub fn round_states<F: PrimeField32>(input: [F; WIDTH]) -> [[F; WIDTH]; NUM_COMPUTATION_ROUNDS] {
+    let rounds_f = 8;
+    let rounds_p = 13;
+    let sbox = |x: F| {
+        let x3 = x * x * x;
+        x3 * x3 * x
+    };
+
+    let mut state = input;
+    core::array::from_fn(|r| {
+        if r == 0 {
+            // The initial layer only applies the external linear layer.
+            external_linear_layer(&mut state);
+        } else if r <= rounds_f / 2 || r > rounds_f / 2 + rounds_p {
+            for j in 0..WIDTH {
+                state[j] = sbox(state[j] + F::from_wrapped_u32(RC_16_30_U32[r - 1][j]));
+            }
+            external_linear_layer(&mut state);
+        } else {
+            state[0] = sbox(state[0] + F::from_wrapped_u32(RC_16_30_U32[r - 1][0]));
+            internal_linear_layer(&mut state);
+        }
+        state
+    })
+}
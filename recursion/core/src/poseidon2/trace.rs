@@ -1,8 +1,12 @@
-use std::borrow::BorrowMut;
+use std::borrow::{Borrow, BorrowMut};
 
 use p3_field::PrimeField32;
 use p3_matrix::dense::RowMajorMatrix;
-use sp1_core::{air::MachineAir, utils::pad_rows_fixed};
+use p3_maybe_rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use sp1_core::{
+    air::{MachineAir, PackedTraceAir},
+    utils::pad_rows_fixed,
+};
 use sp1_primitives::RC_16_30_U32;
 use tracing::instrument;
 
@@ -12,10 +16,18 @@ use crate::{
 };
 
 use super::{
-    external::{NUM_POSEIDON2_COLS, WIDTH},
-    Poseidon2Chip, Poseidon2Cols,
+    external::{NUM_POSEIDON2_COLS, NUM_ROUND_STATE_COLS, WIDTH},
+    Poseidon2Chip, Poseidon2Cols, Poseidon2Event, RoundStateCols, NUM_COMPUTATION_ROUNDS,
 };
 
+// 1 round for memory input; 1 round for initialize; 8 rounds for external; 13 rounds for internal;
+// 1 round for memory output.
+const ROUNDS_F: usize = 8;
+const ROUNDS_P: usize = 13;
+const NUM_ROUNDS: usize = ROUNDS_F + ROUNDS_P + 3;
+
+impl<F: PrimeField32> PackedTraceAir<F> for Poseidon2Chip {}
+
 impl<F: PrimeField32> MachineAir<F> for Poseidon2Chip {
     type Record = ExecutionRecord<F>;
 
@@ -35,140 +47,22 @@ impl<F: PrimeField32> MachineAir<F> for Poseidon2Chip {
         input: &ExecutionRecord<F>,
         _: &mut ExecutionRecord<F>,
     ) -> RowMajorMatrix<F> {
-        let mut rows = Vec::new();
-
-        // 1 round for memory input; 1 round for initialize; 8 rounds for external; 13 rounds for internal; 1 round for memory output
-        let rounds_f = 8;
-        let rounds_p = 13;
-        let rounds = rounds_f + rounds_p + 3;
-        let rounds_p_beginning = 2 + rounds_f / 2;
-        let p_end = rounds_p_beginning + rounds_p;
-
-        for poseidon2_event in input.poseidon2_events.iter() {
-            let mut round_input = Default::default();
-            for r in 0..rounds {
-                let mut row = [F::zero(); NUM_POSEIDON2_COLS];
-                let cols: &mut Poseidon2Cols<F> = row.as_mut_slice().borrow_mut();
-                cols.is_real = F::one();
-
-                let is_receive = r == 0;
-                let is_memory_read = r == 0;
-                let is_initial_layer = r == 1;
-                let is_external_layer =
-                    (r >= 2 && r < rounds_p_beginning) || (r >= p_end && r < p_end + rounds_f / 2);
-                let is_internal_layer = r >= rounds_p_beginning && r < p_end;
-                let is_memory_write = r == rounds - 1;
-
-                let sum = (is_memory_read as u32)
-                    + (is_initial_layer as u32)
-                    + (is_external_layer as u32)
-                    + (is_internal_layer as u32)
-                    + (is_memory_write as u32);
-                assert!(
-                    sum == 0 || sum == 1,
-                    "{} {} {} {} {}",
-                    is_memory_read,
-                    is_initial_layer,
-                    is_external_layer,
-                    is_internal_layer,
-                    is_memory_write
-                );
-
-                cols.clk = poseidon2_event.clk;
-                cols.dst_input = poseidon2_event.dst;
-                cols.left_input = poseidon2_event.left;
-                cols.right_input = poseidon2_event.right;
-                cols.rounds[r] = F::one();
-
-                if is_receive {
-                    cols.do_receive = F::one();
-                }
-
-                if is_memory_read || is_memory_write {
-                    let memory_access_cols = cols.round_specific_cols.memory_access_mut();
-
-                    if is_memory_read {
-                        memory_access_cols.addr_first_half = poseidon2_event.left;
-                        memory_access_cols.addr_second_half = poseidon2_event.right;
-                        for i in 0..WIDTH {
-                            memory_access_cols.mem_access[i]
-                                .populate(&poseidon2_event.input_records[i]);
-                        }
-                    } else {
-                        memory_access_cols.addr_first_half = poseidon2_event.dst;
-                        memory_access_cols.addr_second_half =
-                            poseidon2_event.dst + F::from_canonical_usize(WIDTH / 2);
-                        for i in 0..WIDTH {
-                            memory_access_cols.mem_access[i]
-                                .populate(&poseidon2_event.result_records[i]);
-                        }
-                    }
-                    cols.do_memory = F::one();
-                } else {
-                    let computation_cols = cols.round_specific_cols.computation_mut();
-
-                    if is_initial_layer {
-                        round_input = poseidon2_event.input;
-                    }
-
-                    computation_cols.input = round_input;
-
-                    if is_initial_layer {
-                        // Don't apply the round constants.
-                        computation_cols
-                            .add_rc
-                            .copy_from_slice(&computation_cols.input);
-                    } else if is_external_layer {
-                        // Apply the round constants.
-                        for j in 0..WIDTH {
-                            computation_cols.add_rc[j] = computation_cols.input[j]
-                                + F::from_wrapped_u32(RC_16_30_U32[r - 2][j]);
-                        }
-                    } else {
-                        // Apply the round constants only on the first element.
-                        computation_cols
-                            .add_rc
-                            .copy_from_slice(&computation_cols.input);
-                        computation_cols.add_rc[0] =
-                            computation_cols.input[0] + F::from_wrapped_u32(RC_16_30_U32[r - 2][0]);
-                    };
-
-                    // Apply the sbox.
-                    for j in 0..WIDTH {
-                        let sbox_deg_3 = computation_cols.add_rc[j]
-                            * computation_cols.add_rc[j]
-                            * computation_cols.add_rc[j];
-                        computation_cols.sbox_deg_3[j] = sbox_deg_3;
-                        computation_cols.sbox_deg_7[j] =
-                            sbox_deg_3 * sbox_deg_3 * computation_cols.add_rc[j];
-                    }
-
-                    // What state to use for the linear layer.
-                    let mut state = if is_initial_layer {
-                        computation_cols.add_rc
-                    } else if is_external_layer {
-                        computation_cols.sbox_deg_7
-                    } else {
-                        let mut state = computation_cols.add_rc;
-                        state[0] = computation_cols.sbox_deg_7[0];
-                        state
-                    };
-
-                    // Apply either the external or internal linear layer.
-                    if is_initial_layer || is_external_layer {
-                        external_linear_layer(&mut state);
-                    } else if is_internal_layer {
-                        internal_linear_layer(&mut state)
-                    }
-
-                    // Copy the state to the output.
-                    computation_cols.output.copy_from_slice(&state);
-
-                    round_input = computation_cols.output;
-                }
-
-                rows.push(row);
+        // Generate the rows of each event in parallel. Collecting the indexed parallel iterator
+        // keeps the rows in the order of the events, so the trace, including the clks of the
+        // memory accesses, does not depend on how the events are scheduled across threads.
+        let event_rows = input
+            .poseidon2_events
+            .par_iter()
+            .map(|event| self.event_rows(event))
+            .collect::<Vec<_>>();
+        let mut rows = Vec::with_capacity(input.poseidon2_events.len() * NUM_ROUNDS);
+        let mut round_state_rows = Vec::new();
+        for (i, (event_rows, event_round_state_rows)) in event_rows.into_iter().enumerate() {
+            if let Some(recorder) = &self.state_recorder {
+                recorder.record(i, &Self::event_states(&event_rows));
             }
+            rows.extend(event_rows);
+            round_state_rows.extend(event_round_state_rows);
         }
 
         let num_real_rows = rows.len();
@@ -187,17 +81,223 @@ impl<F: PrimeField32> MachineAir<F> for Poseidon2Chip {
             let cols: &mut Poseidon2Cols<F> = row.as_mut_slice().borrow_mut();
             cols.rounds[round_num] = F::one();
 
-            round_num = (round_num + 1) % rounds;
+            round_num = (round_num + 1) % NUM_ROUNDS;
         }
 
-        // Convert the trace to a row major matrix.
-        RowMajorMatrix::new(
-            rows.into_iter().flatten().collect::<Vec<_>>(),
-            NUM_POSEIDON2_COLS,
-        )
+        // Convert the trace to a row major matrix, appending the round states if every round is
+        // traced.
+        if self.round_states_ptr.is_some() {
+            round_state_rows.resize(rows.len(), [F::zero(); NUM_ROUND_STATE_COLS]);
+            RowMajorMatrix::new(
+                rows.into_iter()
+                    .zip(round_state_rows)
+                    .flat_map(|(row, round_state_row)| row.into_iter().chain(round_state_row))
+                    .collect::<Vec<_>>(),
+                NUM_POSEIDON2_COLS + NUM_ROUND_STATE_COLS,
+            )
+        } else {
+            RowMajorMatrix::new(
+                rows.into_iter().flatten().collect::<Vec<_>>(),
+                NUM_POSEIDON2_COLS,
+            )
+        }
     }
 
     fn included(&self, record: &Self::Record) -> bool {
         !record.poseidon2_events.is_empty()
     }
 }
+
+impl Poseidon2Chip {
+    /// Returns the input state of each computation round of the rows of a permutation, followed
+    /// by the output state of the last one.
+    fn event_states<F: PrimeField32>(rows: &[[F; NUM_POSEIDON2_COLS]]) -> Vec<[F; WIDTH]> {
+        let computation_rows = &rows[1..NUM_COMPUTATION_ROUNDS + 1];
+        let mut states = computation_rows
+            .iter()
+            .map(|row| {
+                let cols: &Poseidon2Cols<F> = row.as_slice().borrow();
+                cols.round_specific_cols.computation().input
+            })
+            .collect::<Vec<_>>();
+        let cols: &Poseidon2Cols<F> = computation_rows[NUM_COMPUTATION_ROUNDS - 1]
+            .as_slice()
+            .borrow();
+        states.push(cols.round_specific_cols.computation().output);
+        states
+    }
+
+    /// Returns the rows of the permutation of `poseidon2_event`, one per round, and the rows of
+    /// the round states if every round is traced.
+    fn event_rows<F: PrimeField32>(
+        &self,
+        poseidon2_event: &Poseidon2Event<F>,
+    ) -> (Vec<[F; NUM_POSEIDON2_COLS]>, Vec<[F; NUM_ROUND_STATE_COLS]>) {
+        let rounds_p_beginning = 2 + ROUNDS_F / 2;
+        let p_end = rounds_p_beginning + ROUNDS_P;
+
+        let mut rows = Vec::with_capacity(NUM_ROUNDS);
+        let mut round_state_rows = Vec::new();
+
+        if self.round_states_ptr.is_some() {
+            assert_eq!(
+                poseidon2_event.round_state_records.len(),
+                NUM_COMPUTATION_ROUNDS,
+                "the runtime did not trace every round of the permutation"
+            );
+        }
+        let final_state = self.backend.as_ref().map(|backend| {
+            backend
+                .permute(poseidon2_event.input.map(|x| x.as_canonical_u32()))
+                .map(F::from_canonical_u32)
+        });
+        let mut round_input = Default::default();
+        for r in 0..NUM_ROUNDS {
+            let mut row = [F::zero(); NUM_POSEIDON2_COLS];
+            let cols: &mut Poseidon2Cols<F> = row.as_mut_slice().borrow_mut();
+            cols.is_real = F::one();
+
+            let is_receive = r == 0;
+            let is_memory_read = r == 0;
+            let is_initial_layer = r == 1;
+            let is_external_layer =
+                (r >= 2 && r < rounds_p_beginning) || (r >= p_end && r < p_end + ROUNDS_F / 2);
+            let is_internal_layer = r >= rounds_p_beginning && r < p_end;
+            let is_memory_write = r == NUM_ROUNDS - 1;
+
+            let sum = (is_memory_read as u32)
+                + (is_initial_layer as u32)
+                + (is_external_layer as u32)
+                + (is_internal_layer as u32)
+                + (is_memory_write as u32);
+            assert!(
+                sum == 0 || sum == 1,
+                "{} {} {} {} {}",
+                is_memory_read,
+                is_initial_layer,
+                is_external_layer,
+                is_internal_layer,
+                is_memory_write
+            );
+
+            cols.clk = poseidon2_event.clk;
+            cols.dst_input = poseidon2_event.dst;
+            cols.left_input = poseidon2_event.left;
+            cols.right_input = poseidon2_event.right;
+            cols.NUM_ROUNDS[r] = F::one();
+
+            if is_receive {
+                cols.do_receive = F::one();
+            }
+
+            if is_memory_read || is_memory_write {
+                let memory_access_cols = cols.round_specific_cols.memory_access_mut();
+
+                if is_memory_read {
+                    memory_access_cols.addr_first_half = poseidon2_event.left;
+                    memory_access_cols.addr_second_half = poseidon2_event.right;
+                    for i in 0..WIDTH {
+                        memory_access_cols.mem_access[i]
+                            .populate(&poseidon2_event.input_records[i]);
+                    }
+                } else {
+                    memory_access_cols.addr_first_half = poseidon2_event.dst;
+                    memory_access_cols.addr_second_half =
+                        poseidon2_event.dst + F::from_canonical_usize(WIDTH / 2);
+                    for i in 0..WIDTH {
+                        memory_access_cols.mem_access[i]
+                            .populate(&poseidon2_event.result_records[i]);
+                    }
+                }
+                cols.do_memory = F::one();
+            } else {
+                let computation_cols = cols.round_specific_cols.computation_mut();
+
+                if is_initial_layer {
+                    round_input = poseidon2_event.input;
+                }
+
+                computation_cols.input = round_input;
+
+                if is_initial_layer {
+                    // Don't apply the round constants.
+                    computation_cols
+                        .add_rc
+                        .copy_from_slice(&computation_cols.input);
+                } else if is_external_layer {
+                    // Apply the round constants.
+                    for j in 0..WIDTH {
+                        computation_cols.add_rc[j] = computation_cols.input[j]
+                            + F::from_wrapped_u32(RC_16_30_U32[r - 2][j]);
+                    }
+                } else {
+                    // Apply the round constants only on the first element.
+                    computation_cols
+                        .add_rc
+                        .copy_from_slice(&computation_cols.input);
+                    computation_cols.add_rc[0] =
+                        computation_cols.input[0] + F::from_wrapped_u32(RC_16_30_U32[r - 2][0]);
+                };
+
+                // Apply the sbox.
+                for j in 0..WIDTH {
+                    let sbox_deg_3 = computation_cols.add_rc[j]
+                        * computation_cols.add_rc[j]
+                        * computation_cols.add_rc[j];
+                    computation_cols.sbox_deg_3[j] = sbox_deg_3;
+                    computation_cols.sbox_deg_7[j] =
+                        sbox_deg_3 * sbox_deg_3 * computation_cols.add_rc[j];
+                }
+
+                // What state to use for the linear layer.
+                let mut state = if is_initial_layer {
+                    computation_cols.add_rc
+                } else if is_external_layer {
+                    computation_cols.sbox_deg_7
+                } else {
+                    let mut state = computation_cols.add_rc;
+                    state[0] = computation_cols.sbox_deg_7[0];
+                    state
+                };
+
+                // Apply either the external or internal linear layer.
+                if is_initial_layer || is_external_layer {
+                    external_linear_layer(&mut state);
+                } else if is_internal_layer {
+                    internal_linear_layer(&mut state)
+                }
+
+                // Copy the state to the output. The output of the last round is the final
+                // state, which is computed by the backend if there is one.
+                match final_state {
+                    Some(final_state) if r == NUM_ROUNDS - 2 => {
+                        debug_assert_eq!(state, final_state);
+                        computation_cols.output = final_state;
+                    }
+                    _ => computation_cols.output.copy_from_slice(&state),
+                }
+
+                round_input = computation_cols.output;
+            }
+
+            // Write the output of each computation round to memory if every round is traced.
+            if self.round_states_ptr.is_some() {
+                let mut round_state_row = [F::zero(); NUM_ROUND_STATE_COLS];
+                if !is_memory_read && !is_memory_write {
+                    let round_state: &mut RoundStateCols<F> =
+                        round_state_row.as_mut_slice().borrow_mut();
+                    round_state.do_memory = F::one();
+                    for i in 0..WIDTH {
+                        round_state.mem_access[i]
+                            .populate(&poseidon2_event.round_state_records[r - 1][i]);
+                    }
+                }
+                round_state_rows.push(round_state_row);
+            }
+
+            rows.push(row);
+        }
+
+        (rows, round_state_rows)
+    }
+}
@@ -25,7 +25,7 @@ use crate::cpu::CpuEvent;
 use crate::exp_reverse_bits::ExpReverseBitsLenEvent;
 use crate::fri_fold::FriFoldEvent;
 use crate::memory::MemoryRecord;
-use crate::poseidon2::Poseidon2Event;
+use crate::poseidon2::{round_states, Poseidon2Event};
 use crate::range_check::{RangeCheckEvent, RangeCheckOpcode};
 
 use p3_field::{ExtensionField, PrimeField32};
@@ -120,6 +120,11 @@ pub struct Runtime<F: PrimeField32, EF: ExtensionField<F>, Diffusion> {
 
     pub cycle_tracker: HashMap<String, CycleTrackerEntry>,
 
+    /// If set, each Poseidon2 permutation also writes the output of every computation round to
+    /// memory at this address, for debugging. The region must not overlap the operands, and the
+    /// Poseidon2 chip must be configured with the same address.
+    pub poseidon2_round_states_ptr: Option<usize>,
+
     // pub witness_stream: Vec<Witness<F, EF>>,
     perm: Option<
         Poseidon2<
@@ -179,6 +184,7 @@ where
             access: CpuRecord::default(),
             witness_stream: VecDeque::new(),
             cycle_tracker: HashMap::new(),
+            poseidon2_round_states_ptr: None,
             _marker: PhantomData,
         }
     }
@@ -209,6 +215,7 @@ where
             access: CpuRecord::default(),
             witness_stream: VecDeque::new(),
             cycle_tracker: HashMap::new(),
+            poseidon2_round_states_ptr: None,
             _marker: PhantomData,
         }
     }
@@ -675,6 +682,26 @@ where
                         ));
                     }
 
+                    // Write the output of every round of the permutation if they are traced.
+                    let mut round_state_records = vec![];
+                    if let Some(round_states_ptr) = self.poseidon2_round_states_ptr {
+                        for (r, state) in round_states(array).iter().enumerate() {
+                            let records = state
+                                .iter()
+                                .enumerate()
+                                .map(|(i, value)| {
+                                    let addr = round_states_ptr + PERMUTATION_WIDTH * r + i;
+                                    self.mw(
+                                        F::from_canonical_usize(addr),
+                                        Block::from(*value),
+                                        timestamp + F::one(),
+                                    )
+                                })
+                                .collect::<Vec<_>>();
+                            round_state_records.push(records.try_into().unwrap());
+                        }
+                    }
+
                     self.record.poseidon2_events.push(Poseidon2Event {
                         clk: timestamp,
                         dst,
@@ -684,6 +711,7 @@ where
                         result_array: result,
                         input_records,
                         result_records: result_records.try_into().unwrap(),
+                        round_state_records,
                     });
                     (a, b, c) = (a_val, b_val, c_val);
                 }
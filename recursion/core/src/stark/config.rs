@@ -15,7 +15,7 @@ use p3_symmetric::Hash;
 use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::Deserialize;
 use serde::Serialize;
-use sp1_core::stark::StarkGenericConfig;
+use sp1_core::stark::{Poseidon2Params, StarkGenericConfig};
 
 use super::poseidon2::bn254_poseidon2_rc3;
 use super::utils;
@@ -146,6 +146,14 @@ impl StarkGenericConfig for BabyBearPoseidon2Outer {
     fn challenger(&self) -> Self::Challenger {
         OuterChallenger::new(self.perm.clone()).unwrap()
     }
+
+    fn poseidon2_params(&self) -> Option<Poseidon2Params> {
+        Some(Poseidon2Params {
+            width: 3,
+            rounds_f: 8,
+            rounds_p: 56,
+        })
+    }
 }
 
 /// The FRI config for testing recursion.
@@ -9,6 +9,7 @@ use sp1_recursion_core::runtime::{DIGEST_SIZE, PERMUTATION_WIDTH};
 
 use crate::fri::types::DigestVariable;
 use crate::types::VerifyingKeyVariable;
+use crate::utils::poseidon2_params_felts;
 
 /// Reference: [p3_challenger::CanObserve].
 pub trait CanObserveVariable<C: Config, V> {
@@ -268,7 +269,10 @@ impl<C: Config> CanObserveVariable<C, DigestVariable<C>> for DuplexChallengerVar
 impl<C: Config> CanObserveVariable<C, VerifyingKeyVariable<C>> for DuplexChallengerVariable<C> {
     fn observe(&mut self, builder: &mut Builder<C>, value: VerifyingKeyVariable<C>) {
         self.observe_commitment(builder, value.commitment);
-        self.observe(builder, value.pc_start)
+        self.observe(builder, value.pc_start);
+        for param in poseidon2_params_felts(builder) {
+            self.observe(builder, param);
+        }
     }
 
     fn observe_slice(
@@ -236,8 +236,7 @@ where
                 // verifier key and the initial pc.
                 let mut first_initial_challenger = DuplexChallengerVariable::new(builder);
 
-                first_initial_challenger.observe(builder, vk.commitment.clone());
-                first_initial_challenger.observe(builder, vk.pc_start);
+                first_initial_challenger.observe(builder, vk.clone());
 
                 // Make sure the start reconstruct challenger is correct, since we will
                 // commit to it in public values.
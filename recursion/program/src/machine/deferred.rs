@@ -178,9 +178,8 @@ where
 
             // Prepare a challenger.
             let mut challenger = DuplexChallengerVariable::new(builder);
-            // Observe the vk and start pc.
-            challenger.observe(builder, compress_vk.commitment.clone());
-            challenger.observe(builder, compress_vk.pc_start);
+            // Observe the vk, its start pc and Poseidon2 parameters.
+            challenger.observe(builder, compress_vk.clone());
             // Observe the main commitment and public values.
             challenger.observe(builder, proof.commitment.main_commit.clone());
             for j in 0..machine.num_pv_elts() {
@@ -8,7 +8,7 @@ use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use sp1_core::air::MachineAir;
 use sp1_core::stark::{Dom, ShardProof, StarkGenericConfig, StarkMachine, StarkVerifyingKey};
-use sp1_core::utils::BabyBearPoseidon2;
+use sp1_core::utils::{BabyBearPoseidon2, INNER_POSEIDON2_PARAMS};
 use sp1_recursion_compiler::asm::AsmConfig;
 use sp1_recursion_compiler::ir::{Array, Builder, Config, Felt, MemVariable, Var};
 use sp1_recursion_core::air::ChallengerPublicValues;
@@ -166,14 +166,27 @@ pub fn get_challenger_public_values<C: Config>(
     }
 }
 
+/// The Poseidon2 parameters of the verified proofs, which are observed after the verifying key
+/// and hashed into its digest like in `StarkVerifyingKey::observe_into` and
+/// `HashableKey::hash_babybear`.
+pub fn poseidon2_params_felts<C: Config>(builder: &mut Builder<C>) -> [Felt<C::F>; 3] {
+    INNER_POSEIDON2_PARAMS
+        .to_field_elements::<C::F>()
+        .map(|value| builder.eval(value))
+}
+
 /// Hash the verifying key + prep domains into a single digest.
-/// poseidon2( commit[0..8] || pc_start || prep_domains[N].{log_n, .size, .shift, .g})
+/// poseidon2( commit[0..8] || pc_start || poseidon2_params[0..3] ||
+///     prep_domains[N].{log_n, .size, .shift, .g})
 pub fn hash_vkey<C: Config>(
     builder: &mut Builder<C>,
     vk: &VerifyingKeyVariable<C>,
 ) -> Array<C, Felt<C::F>> {
+    let poseidon2_params = poseidon2_params_felts(builder);
     let domain_slots: Var<_> = builder.eval(vk.prep_domains.len() * 4);
-    let vkey_slots: Var<_> = builder.constant(C::N::from_canonical_usize(DIGEST_SIZE + 1));
+    let vkey_slots: Var<_> = builder.constant(C::N::from_canonical_usize(
+        DIGEST_SIZE + 1 + poseidon2_params.len(),
+    ));
     let total_slots: Var<_> = builder.eval(vkey_slots + domain_slots);
     let mut inputs = builder.dyn_array(total_slots);
     builder.range(0, DIGEST_SIZE).for_each(|i, builder| {
@@ -181,6 +194,9 @@ pub fn hash_vkey<C: Config>(
         builder.set(&mut inputs, i, element);
     });
     builder.set(&mut inputs, DIGEST_SIZE, vk.pc_start);
+    for (i, value) in poseidon2_params.into_iter().enumerate() {
+        builder.set(&mut inputs, DIGEST_SIZE + 1 + i, value);
+    }
     let four: Var<_> = builder.constant(C::N::from_canonical_usize(4));
     let one: Var<_> = builder.constant(C::N::one());
     builder
@@ -10,8 +10,10 @@ pub mod proto {
     pub mod network;
 }
 pub mod artifacts;
+mod metadata;
 #[cfg(feature = "network")]
 pub mod network;
+mod serialize;
 #[cfg(feature = "network")]
 pub use crate::network::prover::NetworkProver;
 
@@ -22,21 +24,25 @@ pub mod utils {
 
 use cfg_if::cfg_if;
 pub use provers::SP1VerificationError;
-use std::{env, fmt::Debug, fs::File, path::Path};
+use std::{env, fmt::Debug, path::Path};
 
 use anyhow::{Ok, Result};
 
+pub use metadata::ProofMetadata;
 pub use provers::{LocalProver, MockProver, Prover};
+pub use serialize::{DeserializeError, ProofSection, PROOF_FORMAT_VERSION, PROOF_MAGIC};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sp1_core::{
+    io::FooterError,
     runtime::ExecutionReport,
     stark::{MachineVerificationError, ShardProof},
     SP1_CIRCUIT_VERSION,
 };
 pub use sp1_prover::{
-    CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
-    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+    CommitmentScheme, CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover,
+    SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey, TreeShape,
 };
 
 /// A client for interacting with SP1.
@@ -54,6 +60,12 @@ pub struct SP1ProofWithPublicValues<P> {
     pub stdin: SP1Stdin,
     pub public_values: SP1PublicValues,
     pub sp1_version: String,
+    /// The provenance of the proof, which is not part of the verified payload.
+    ///
+    /// It is skipped by serde, so that the bincode encoding of proofs, e.g. those returned by the
+    /// network, is unchanged. It is kept by [Self::to_bytes] and [Self::save].
+    #[serde(skip)]
+    pub metadata: Option<ProofMetadata>,
 }
 
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove].
@@ -345,6 +357,139 @@ impl ProverClient {
         self.prover.verify(proof, vkey)
     }
 
+    /// Verifies a proof like [Self::verify], and decodes the public values committed by the
+    /// program as a value of type `T`.
+    ///
+    /// The public values of the proof are checked against the digest committed in the proof, and
+    /// must be exactly the encoding of the decoded value, as with
+    /// [SP1PublicValues::decode]. For programs built with the `footer` feature of `sp1-zkvm`, the
+    /// footer written on halt is part of the public values, so use
+    /// [SP1PublicValues::decode_committed] instead.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10u32);
+    /// let proof = client.prove(&pk, stdin).unwrap();
+    /// let (n, a, b) = client
+    ///     .verify_with_public_values::<(u32, u32, u32)>(&proof, &vk)
+    ///     .unwrap();
+    /// ```
+    pub fn verify_with_public_values<T: Serialize + DeserializeOwned>(
+        &self,
+        proof: &SP1Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<T, SP1VerificationError> {
+        self.prover.verify(proof, vkey)?;
+        provers::verify_public_values_digest(proof)?;
+        proof
+            .public_values
+            .decode()
+            .map_err(SP1VerificationError::PublicValues)
+    }
+
+    /// Verifies a proof like [Self::verify], and checks that the program committed the expected
+    /// nonce with `sp1_zkvm::io::commit_nonce`.
+    ///
+    /// The program must be built with the `footer` feature of `sp1-zkvm`.
+    ///
+    /// The public values of the proof are checked against the digest committed in the proof, so
+    /// a proof generated for another nonce, e.g. a replayed one, is rejected.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let nonce = 7u64;
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&nonce);
+    /// let proof = client.prove(&pk, stdin).unwrap();
+    /// client.verify_with_nonce(&proof, &vk, nonce).unwrap();
+    /// ```
+    pub fn verify_with_nonce(
+        &self,
+        proof: &SP1Proof,
+        vkey: &SP1VerifyingKey,
+        nonce: u64,
+    ) -> Result<(), SP1VerificationError> {
+        self.prover.verify(proof, vkey)?;
+        provers::verify_public_values_digest(proof)?;
+        proof
+            .public_values
+            .footer()
+            .map_err(SP1VerificationError::Footer)?
+            .verify_nonce(nonce)
+            .map_err(SP1VerificationError::Nonce)
+    }
+
+    /// Verifies a proof like [Self::verify], and returns the number of cycles of the proven
+    /// execution.
+    ///
+    /// The number of cycles of each shard is committed in its public values and constrained to
+    /// its number of CPU rows, so it cannot be forged in a proof which verifies. Compressed and
+    /// plonk proofs do not carry the cycle counts of the shards they recursively verify, so the
+    /// number of cycles is only available for core proofs.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove(&pk, stdin).unwrap();
+    /// let cycles = client.verify_with_cycles(&proof, &vk).unwrap();
+    /// ```
+    pub fn verify_with_cycles(
+        &self,
+        proof: &SP1Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<u64, SP1VerificationError> {
+        self.prover.verify(proof, vkey)?;
+        Ok(proof.total_cycles())
+    }
+
+    /// Verifies a batch of proofs, each against its own verification key produced by
+    /// [Self::setup], for example proofs of several independent programs.
+    ///
+    /// This is a convenience over calling [Self::verify] on each proof: the proofs are verified one
+    /// after the other and no work is shared between them, so a batch costs as much as verifying
+    /// its proofs separately. Returns the index of the first proof which fails to verify, and its
+    /// error.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove(&pk, stdin.clone()).unwrap();
+    /// let other_proof = client.prove(&pk, stdin).unwrap();
+    /// client.verify_batch(&[(&vk, &proof), (&vk, &other_proof)]).unwrap();
+    /// ```
+    pub fn verify_batch(
+        &self,
+        items: &[(&SP1VerifyingKey, &SP1Proof)],
+    ) -> Result<(), (usize, SP1VerificationError)> {
+        // `Ok` is anyhow's in this module, so the proofs are verified with `try_for_each`.
+        items.iter().enumerate().try_for_each(|(i, (vkey, proof))| {
+            self.prover.verify(proof, vkey).map_err(|err| (i, err))
+        })
+    }
+
     /// Verifies that the given compressed proof is valid and matches the given verification key
     /// produced by [Self::setup].
     ///
@@ -419,16 +564,116 @@ impl Default for ProverClient {
 }
 
 impl<P: Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
-    /// Saves the proof to a path.
+    /// Saves the proof to a path, in the format of [Self::to_bytes].
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
+        std::fs::write(path, self.to_bytes()).map_err(Into::into)
     }
 
-    /// Loads a proof from a path.
+    /// Loads a proof saved with [Self::save] from a path.
+    ///
+    /// A corrupted file is reported as a [DeserializeError] with the section which failed. Files
+    /// which do not start with [PROOF_MAGIC] were saved by earlier versions as the bincode encoding
+    /// of the proof, without metadata, and are decoded as such.
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
+        let bytes = std::fs::read(path)?;
+        if !bytes.starts_with(&PROOF_MAGIC) {
+            return bincode::deserialize(&bytes).map_err(Into::into);
+        }
+        Self::from_bytes(&bytes).map_err(Into::into)
+    }
+
+    /// Returns a hash of the statement proven by the proof, i.e. the verifying key and the public
+    /// values.
+    ///
+    /// The hash does not depend on the proof itself, so proofs of the same program and public
+    /// values have the same statement hash even if they were generated with different randomness
+    /// or in different modes.
+    pub fn statement_hash(&self, vkey: &SP1VerifyingKey) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(vkey.hash_bytes());
+        hasher.update(self.public_values.as_slice());
+        hasher.finalize().into()
+    }
+
+    /// Returns the shape of the tree of recursive proofs which was reduced into the proof, from its
+    /// metadata.
+    ///
+    /// The shape is only known to the prover which generated the proof, so it is `None` for proofs
+    /// without metadata, e.g. those returned by the network.
+    pub fn tree_shape(&self) -> Option<TreeShape> {
+        self.metadata
+            .as_ref()
+            .map(|metadata| metadata.tree_shape.clone())
+    }
+
+    /// Returns the scheme the program committed to its public values with, as recorded in the
+    /// proof's metadata.
+    ///
+    /// Verification does not rely on it: the scheme is derived from the committed digest, which
+    /// only matches the public values under the scheme the program used.
+    pub fn commitment_scheme(&self) -> Option<CommitmentScheme> {
+        self.metadata
+            .as_ref()
+            .map(|metadata| metadata.commitment_scheme)
+    }
+
+    /// Returns the error code the program set with `sp1_zkvm::io::set_error_code`, if any.
+    ///
+    /// The error code is committed to the footer of the public values, so a program which reports
+    /// an error still has a valid proof. The program must be built with the `footer` feature of
+    /// `sp1-zkvm`.
+    pub fn error_code(&self) -> Result<Option<u32>, FooterError> {
+        self.public_values.footer().map(|footer| footer.error_code)
+    }
+
+    /// Returns whether the program panicked with a code registered with
+    /// `sp1_zkvm::io::register_panic_code`, in which case it still has a valid proof and
+    /// [Self::error_code] is the code of the panic.
+    pub fn panicked(&self) -> Result<bool, FooterError> {
+        self.public_values.footer().map(|footer| footer.panicked)
+    }
+
+    /// Returns the nonce the program committed with `sp1_zkvm::io::commit_nonce`, if any.
+    pub fn nonce(&self) -> Result<Option<u64>, FooterError> {
+        self.public_values.footer().map(|footer| footer.nonce)
+    }
+
+    /// Returns the digest of the inputs the program committed with
+    /// `sp1_zkvm::io::commit_input_digest`, if any.
+    ///
+    /// It equals [SP1Stdin::input_digest_of] the number of inputs the program read before
+    /// committing the digest.
+    pub fn input_digest(&self) -> Result<Option<[u8; 32]>, FooterError> {
+        self.public_values
+            .footer()
+            .map(|footer| footer.input_digest)
+    }
+}
+
+/// Returns whether two proofs were generated from the same inputs, by comparing the input digests
+/// committed to the footers of their public values.
+///
+/// The proofs are not verified, so this only shows that they ran on the same inputs if they were
+/// verified beforehand. They can be proofs of different programs, for example to show that two
+/// programs disagree on the same inputs. Proofs of programs which did not commit an input digest
+/// never share their inputs.
+pub fn proofs_share_input<P, Q>(
+    a: &SP1ProofWithPublicValues<P>,
+    b: &SP1ProofWithPublicValues<Q>,
+) -> bool {
+    match (a.input_digest(), b.input_digest()) {
+        (Ok(Some(a)), Ok(Some(b))) => a == b,
+        _ => false,
+    }
+}
+
+impl SP1Proof {
+    /// Returns the number of cycles of the execution, summed over the shards of the proof.
+    ///
+    /// The proof is not verified, so use [ProverClient::verify_with_cycles] for a number of cycles
+    /// which can be trusted.
+    pub fn total_cycles(&self) -> u64 {
+        self.proof.iter().map(ShardProof::num_cycles).sum()
     }
 }
 
@@ -446,7 +691,61 @@ impl SP1PlonkBn254Proof {
 #[cfg(test)]
 mod tests {
 
-    use crate::{utils, ProverClient, SP1Stdin};
+    use crate::{
+        proofs_share_input, utils, CommitmentScheme, CoreSC, HashableKey, ProverClient,
+        SP1CompressedProof, SP1ProofWithPublicValues, SP1PublicValues, SP1Stdin,
+        SP1VerificationError, TreeShape,
+    };
+    use p3_field::AbstractField;
+    use sp1_core::air::SP1_PROOF_NUM_PV_ELTS;
+    use sp1_core::io::{DecodeError, FooterError, PublicValuesFooter};
+    use sp1_core::stark::Val;
+
+    /// A proof whose public values are `output` followed by a footer with the input digest of
+    /// `stdin`, as committed by a program which reads all of `stdin` and then calls
+    /// `commit_input_digest`.
+    fn proof_with_input(output: u64, stdin: &SP1Stdin) -> SP1ProofWithPublicValues<()> {
+        let footer = PublicValuesFooter {
+            input_digest: Some(stdin.input_digest()),
+            ..Default::default()
+        };
+        let mut public_values = SP1PublicValues::new();
+        public_values.write(&output);
+        public_values.write_slice(&footer.encode());
+        SP1ProofWithPublicValues {
+            proof: (),
+            stdin: stdin.clone(),
+            public_values,
+            sp1_version: String::new(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_proofs_share_input() {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let mut other_stdin = SP1Stdin::new();
+        other_stdin.write(&11usize);
+
+        // Two programs with different outputs on the same input.
+        let a = proof_with_input(55, &stdin);
+        let b = proof_with_input(89, &stdin);
+        assert!(proofs_share_input(&a, &b));
+
+        let c = proof_with_input(55, &other_stdin);
+        assert!(!proofs_share_input(&a, &c));
+
+        // A proof without a footer or an input digest shares its input with no other proof.
+        let mut d = a.clone();
+        d.public_values = SP1PublicValues::new();
+        assert!(!proofs_share_input(&a, &d));
+        assert!(!proofs_share_input(&d, &d));
+        let mut e = a.clone();
+        e.public_values = SP1PublicValues::from(&PublicValuesFooter::default().encode());
+        assert_eq!(e.input_digest(), Ok(None));
+        assert!(!proofs_share_input(&e, &e));
+    }
 
     #[test]
     fn test_execute() {
@@ -470,6 +769,232 @@ mod tests {
         client.execute(elf, stdin).unwrap();
     }
 
+    #[test]
+    fn test_statement_hash() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // Two proofs of the same statement in the same mode have the same statement hash.
+        let proof = client.prove(&pk, stdin.clone()).unwrap();
+        let reproof = client.prove(&pk, stdin.clone()).unwrap();
+        client.verify(&reproof, &vk).unwrap();
+        assert_eq!(proof.statement_hash(&vk), reproof.statement_hash(&vk));
+
+        // So does a proof of the same statement in another mode.
+        let compressed = client.prove_compressed(&pk, stdin).unwrap();
+        assert_eq!(proof.statement_hash(&vk), compressed.statement_hash(&vk));
+        assert_ne!(
+            bincode::serialize(&proof.proof).unwrap(),
+            bincode::serialize(&compressed.proof).unwrap()
+        );
+
+        // A proof of the same program in the same mode with other inputs, and so other public
+        // values, has another statement hash.
+        let mut other_stdin = SP1Stdin::new();
+        other_stdin.write(&11usize);
+        let other = client.prove(&pk, other_stdin).unwrap();
+        assert_ne!(
+            proof.public_values.as_slice(),
+            other.public_values.as_slice()
+        );
+        assert_ne!(proof.statement_hash(&vk), other.statement_hash(&vk));
+
+        // So do the same public values under the verifying key of another program.
+        let other_elf = include_bytes!("../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let (_, other_vk) = client.setup(other_elf);
+        assert_ne!(vk.hash_bytes(), other_vk.hash_bytes());
+        assert_ne!(proof.statement_hash(&vk), proof.statement_hash(&other_vk));
+    }
+
+    #[test]
+    fn test_proof_metadata() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, stdin).unwrap();
+
+        let metadata = proof.metadata.as_ref().unwrap();
+        assert_eq!(metadata.program_hash, vk.hash_bytes());
+        assert!(metadata.matches_vkey(&vk));
+        assert_eq!(metadata.sp1_version, proof.sp1_version);
+        assert!(metadata.created_at > 0);
+
+        // The metadata does not match the verifying key of another program.
+        let other_elf = include_bytes!("../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let (_, other_vk) = client.setup(other_elf);
+        assert!(!metadata.matches_vkey(&other_vk));
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let other_elf = include_bytes!("../../tests/panic/elf/riscv32im-succinct-zkvm-elf");
+        let (_, other_vk) = client.setup(other_elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, stdin).unwrap();
+        client
+            .verify_batch(&[(&vk, &proof), (&vk, &proof)])
+            .unwrap();
+
+        // The first invalid proof is reported, even if a later one is invalid too.
+        let mut old_proof = proof.clone();
+        old_proof.sp1_version = "v0.0.0".to_string();
+        let result = client.verify_batch(&[(&vk, &proof), (&other_vk, &proof), (&vk, &old_proof)]);
+        assert!(matches!(
+            result,
+            Err((1, SP1VerificationError::MetadataMismatch))
+        ));
+        let result = client.verify_batch(&[(&vk, &proof), (&vk, &proof), (&vk, &old_proof)]);
+        assert!(matches!(
+            result,
+            Err((2, SP1VerificationError::VersionMismatch(_)))
+        ));
+
+        // So is a proof which fails the verification of the machine itself.
+        let mut forged = proof.clone();
+        let num_cycles = &mut forged.proof[0].public_values[SP1_PROOF_NUM_PV_ELTS - 1];
+        *num_cycles += Val::<CoreSC>::one();
+        let result = client.verify_batch(&[(&vk, &proof), (&vk, &forged), (&vk, &proof)]);
+        assert!(matches!(result, Err((1, SP1VerificationError::Core(_)))));
+    }
+
+    #[test]
+    fn test_verify_with_public_values() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        let proof = client.prove(&pk, stdin).unwrap();
+
+        // The program commits n followed by the (n-1)th and nth fibonacci numbers.
+        let values = client
+            .verify_with_public_values::<(u32, u32, u32)>(&proof, &vk)
+            .unwrap();
+        assert_eq!(values, (10, 55, 89));
+
+        // The public values must be exactly the encoding of the expected type.
+        let result = client.verify_with_public_values::<(u32, u32)>(&proof, &vk);
+        assert!(matches!(
+            result,
+            Err(SP1VerificationError::PublicValues(
+                DecodeError::EncodingMismatch
+            ))
+        ));
+
+        // Public values which were not committed by the program are rejected.
+        let mut tampered = proof.clone();
+        let mut bytes = proof.public_values.to_vec();
+        bytes[8] += 1;
+        tampered.public_values = SP1PublicValues::from(&bytes);
+        let result = client.verify_with_public_values::<(u32, u32, u32)>(&tampered, &vk);
+        assert!(matches!(
+            result,
+            Err(SP1VerificationError::PublicValuesMismatch)
+        ));
+
+        // The program does not select a commitment scheme, so it commits to the SHA-256 digest of
+        // its public values.
+        assert_eq!(proof.commitment_scheme(), Some(CommitmentScheme::Sha256));
+    }
+
+    #[test]
+    fn test_verify_with_nonce() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        let proof = client.prove(&pk, stdin).unwrap();
+
+        // The program is not built with the footer, so it commits no nonce.
+        assert_eq!(proof.nonce(), Err(FooterError::MissingFooter));
+        let result = client.verify_with_nonce(&proof, &vk, 7);
+        assert!(matches!(
+            result,
+            Err(SP1VerificationError::Footer(FooterError::MissingFooter))
+        ));
+
+        // A nonce which was not committed by the program cannot be added to its public values.
+        let mut tampered = proof.clone();
+        let footer = PublicValuesFooter {
+            nonce: Some(7),
+            ..Default::default()
+        };
+        tampered.public_values.write_slice(&footer.encode());
+        assert_eq!(tampered.nonce(), Ok(Some(7)));
+        let result = client.verify_with_nonce(&tampered, &vk, 7);
+        assert!(matches!(
+            result,
+            Err(SP1VerificationError::PublicValuesMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_verify_with_cycles() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        let (_, report) = client.execute(elf, stdin.clone()).unwrap();
+        let proof = client.prove(&pk, stdin).unwrap();
+        let cycles = client.verify_with_cycles(&proof, &vk).unwrap();
+        assert_eq!(cycles, report.total_instruction_count());
+        assert_eq!(cycles, proof.total_cycles());
+
+        // A proof claiming fewer cycles than it ran does not verify.
+        let mut forged = proof.clone();
+        let num_cycles = &mut forged.proof[0].public_values[SP1_PROOF_NUM_PV_ELTS - 1];
+        *num_cycles -= Val::<CoreSC>::one();
+        assert_eq!(forged.total_cycles(), cycles - 1);
+        assert!(client.verify_with_cycles(&forged, &vk).is_err());
+    }
+
+    #[test]
+    fn test_tree_shape() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // A core proof is not reduced.
+        let proof = client.prove(&pk, stdin.clone()).unwrap();
+        assert_eq!(proof.tree_shape(), Some(TreeShape::default()));
+
+        // The shape of a compressed proof reduces every shard, and is kept when it is serialized.
+        let compressed = client.prove_compressed(&pk, stdin).unwrap();
+        client.verify_compressed(&compressed, &vk).unwrap();
+        let shape = compressed.tree_shape().unwrap();
+        assert_eq!(shape.leaf_count(), proof.proof.len());
+        assert_eq!(shape.layers.last().map(Vec::len), Some(1));
+        let decoded = SP1CompressedProof::from_bytes(&compressed.to_bytes()).unwrap();
+        assert_eq!(decoded.tree_shape(), Some(shape));
+    }
+
     #[test]
     fn test_e2e_prove_plonk() {
         utils::setup_logger();
@@ -0,0 +1,61 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sp1_prover::{CommitmentScheme, HashableKey, SP1VerifyingKey, TreeShape};
+
+/// Provenance metadata stored alongside a proof, to track which program and version of SP1
+/// generated it.
+///
+/// The metadata is not part of the verified statement. The program hash is checked against the
+/// verifying key when the proof is verified, since the verifying key is what binds the proof to its
+/// program, but the other fields are only informative.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetadata {
+    /// The hash of the verifying key of the program, as returned by [HashableKey::hash_bytes].
+    pub program_hash: [u8; 32],
+    /// The version of SP1 which generated the proof.
+    pub sp1_version: String,
+    /// The time at which the proof was generated, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// The number of shards of the execution.
+    pub num_shards: usize,
+    /// The shape of the tree of recursive proofs which was reduced into the proof, which is empty
+    /// for core proofs and for proofs of the mock prover.
+    pub tree_shape: TreeShape,
+    /// The scheme the program committed to its public values with.
+    pub commitment_scheme: CommitmentScheme,
+}
+
+impl ProofMetadata {
+    /// Creates the metadata of a proof of the program of `vkey` generated now.
+    pub fn new(vkey: &SP1VerifyingKey, sp1_version: &str, num_shards: usize) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self {
+            program_hash: vkey.hash_bytes(),
+            sp1_version: sp1_version.to_string(),
+            created_at,
+            num_shards,
+            tree_shape: TreeShape::default(),
+            commitment_scheme: CommitmentScheme::default(),
+        }
+    }
+
+    /// Sets the shape of the tree of recursive proofs which was reduced into the proof.
+    pub fn with_tree_shape(mut self, tree_shape: TreeShape) -> Self {
+        self.tree_shape = tree_shape;
+        self
+    }
+
+    /// Sets the scheme the program committed to its public values with.
+    pub fn with_commitment_scheme(mut self, commitment_scheme: CommitmentScheme) -> Self {
+        self.commitment_scheme = commitment_scheme;
+        self
+    }
+
+    /// Returns whether the metadata belongs to a proof of the program of `vkey`.
+    pub fn matches_vkey(&self, vkey: &SP1VerifyingKey) -> bool {
+        self.program_hash == vkey.hash_bytes()
+    }
+}
@@ -2,11 +2,11 @@ use anyhow::Result;
 use sp1_prover::{SP1Prover, SP1Stdin};
 
 use crate::{
-    Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues,
-    SP1ProvingKey, SP1VerifyingKey,
+    ProofMetadata, Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof,
+    SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
 };
 
-use super::ProverType;
+use super::{commitment_scheme, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
 pub struct LocalProver {
@@ -36,32 +36,45 @@ impl Prover for LocalProver {
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
         let proof = self.prover.prove_core(pk, &stdin)?;
+        let scheme = commitment_scheme(&proof.proof.0, &proof.public_values);
+        let metadata = ProofMetadata::new(&pk.vk, self.version(), proof.proof.0.len())
+            .with_commitment_scheme(scheme.unwrap_or_default());
         Ok(SP1ProofWithPublicValues {
             proof: proof.proof.0,
             stdin: proof.stdin,
             public_values: proof.public_values,
             sp1_version: self.version().to_string(),
+            metadata: Some(metadata),
         })
     }
 
     fn prove_compressed(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1CompressedProof> {
         let proof = self.prover.prove_core(pk, &stdin)?;
+        let scheme = commitment_scheme(&proof.proof.0, &proof.public_values);
+        let metadata = ProofMetadata::new(&pk.vk, self.version(), proof.proof.0.len())
+            .with_commitment_scheme(scheme.unwrap_or_default());
         let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
         let public_values = proof.public_values.clone();
         let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
+        let metadata = metadata.with_tree_shape(reduce_proof.tree_shape());
         Ok(SP1CompressedProof {
             proof: reduce_proof.proof,
             stdin,
             public_values,
             sp1_version: self.version().to_string(),
+            metadata: Some(metadata),
         })
     }
 
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
         let proof = self.prover.prove_core(pk, &stdin)?;
+        let scheme = commitment_scheme(&proof.proof.0, &proof.public_values);
+        let metadata = ProofMetadata::new(&pk.vk, self.version(), proof.proof.0.len())
+            .with_commitment_scheme(scheme.unwrap_or_default());
         let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
         let public_values = proof.public_values.clone();
         let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
+        let metadata = metadata.with_tree_shape(reduce_proof.tree_shape());
         let compress_proof = self.prover.shrink(reduce_proof)?;
         let outer_proof = self.prover.wrap_bn254(compress_proof)?;
 
@@ -81,6 +94,7 @@ impl Prover for LocalProver {
             stdin,
             public_values,
             sp1_version: self.version().to_string(),
+            metadata: Some(metadata),
         })
     }
 }
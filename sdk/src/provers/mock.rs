@@ -1,7 +1,7 @@
 #![allow(unused_variables)]
 use crate::{
-    Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues,
-    SP1ProvingKey, SP1VerificationError, SP1VerifyingKey,
+    ProofMetadata, Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof,
+    SP1ProofWithPublicValues, SP1ProvingKey, SP1VerificationError, SP1VerifyingKey,
 };
 use anyhow::Result;
 use p3_field::PrimeField;
@@ -38,12 +38,18 @@ impl Prover for MockProver {
     }
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let (public_values, scheme, _) =
+            SP1Prover::execute_with_commitment_scheme(&pk.elf, &stdin)?;
         Ok(SP1ProofWithPublicValues {
             proof: vec![],
             stdin,
             public_values,
             sp1_version: self.version().to_string(),
+            // Mock proofs have no shards.
+            metadata: Some(
+                ProofMetadata::new(&pk.vk, self.version(), 0)
+                    .with_commitment_scheme(scheme.unwrap_or_default()),
+            ),
         })
     }
 
@@ -56,12 +62,14 @@ impl Prover for MockProver {
     }
 
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let (public_values, scheme, _) =
+            SP1Prover::execute_with_commitment_scheme(&pk.elf, &stdin)?;
+        let scheme = scheme.unwrap_or_default();
         Ok(SP1PlonkBn254Proof {
             proof: PlonkBn254Proof {
                 public_inputs: [
                     pk.vk.hash_bn254().as_canonical_biguint().to_string(),
-                    public_values.hash().to_string(),
+                    public_values.hash_with(scheme).to_string(),
                 ],
                 encoded_proof: "".to_string(),
                 raw_proof: "".to_string(),
@@ -70,6 +78,10 @@ impl Prover for MockProver {
             stdin,
             public_values,
             sp1_version: self.version().to_string(),
+            // Mock proofs have no shards.
+            metadata: Some(
+                ProofMetadata::new(&pk.vk, self.version(), 0).with_commitment_scheme(scheme),
+            ),
         })
     }
 
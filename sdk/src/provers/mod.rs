@@ -1,18 +1,21 @@
 mod local;
 mod mock;
 
-use crate::{SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof};
+use crate::{SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues};
 use anyhow::Result;
 pub use local::LocalProver;
 pub use mock::MockProver;
-use sp1_core::stark::MachineVerificationError;
+use p3_field::PrimeField32;
+use sp1_core::air::PublicValues;
+use sp1_core::io::{CommitmentScheme, DecodeError, FooterError, NonceError};
+use sp1_core::stark::{MachineVerificationError, ShardProof};
 use sp1_core::SP1_CIRCUIT_VERSION;
 use sp1_prover::CoreSC;
 use sp1_prover::InnerSC;
 use sp1_prover::SP1CoreProofData;
 use sp1_prover::SP1Prover;
 use sp1_prover::SP1ReduceProof;
-use sp1_prover::{SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+use sp1_prover::{SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey};
 use strum_macros::EnumString;
 use thiserror::Error;
 
@@ -28,12 +31,22 @@ pub enum ProverType {
 pub enum SP1VerificationError {
     #[error("Version mismatch")]
     VersionMismatch(String),
+    #[error("Proof metadata does not match the verifying key")]
+    MetadataMismatch,
     #[error("Core machine verification error: {0}")]
     Core(MachineVerificationError<CoreSC>),
     #[error("Recursion verification error: {0}")]
     Recursion(MachineVerificationError<InnerSC>),
     #[error("Plonk verification error: {0}")]
     Plonk(anyhow::Error),
+    #[error("Public values do not match the digest committed in the proof")]
+    PublicValuesMismatch,
+    #[error("Public values decoding error: {0}")]
+    PublicValues(DecodeError),
+    #[error("Nonce verification error: {0}")]
+    Nonce(NonceError),
+    #[error("Public values footer error: {0}")]
+    Footer(FooterError),
 }
 
 /// An implementation of [crate::ProverClient].
@@ -64,6 +77,7 @@ pub trait Prover: Send + Sync {
                 proof.sp1_version.clone(),
             ));
         }
+        verify_metadata(proof, vkey)?;
         self.sp1_prover()
             .verify(&SP1CoreProofData(proof.proof.clone()), vkey)
             .map_err(SP1VerificationError::Core)
@@ -80,10 +94,12 @@ pub trait Prover: Send + Sync {
                 proof.sp1_version.clone(),
             ));
         }
+        verify_metadata(proof, vkey)?;
         self.sp1_prover()
             .verify_compressed(
                 &SP1ReduceProof {
                     proof: proof.proof.clone(),
+                    tree_shape: proof.tree_shape().unwrap_or_default(),
                 },
                 vkey,
             )
@@ -102,6 +118,7 @@ pub trait Prover: Send + Sync {
                 proof.sp1_version.clone(),
             ));
         }
+        verify_metadata(proof, vkey)?;
         let sp1_prover = self.sp1_prover();
 
         let plonk_bn254_aritfacts = if sp1_prover::build::sp1_dev_mode() {
@@ -121,3 +138,44 @@ pub trait Prover: Send + Sync {
         Ok(())
     }
 }
+
+/// Checks that the metadata of a proof, if any, belongs to a proof of the program of `vkey`.
+fn verify_metadata<P>(
+    proof: &SP1ProofWithPublicValues<P>,
+    vkey: &SP1VerifyingKey,
+) -> Result<(), SP1VerificationError> {
+    match &proof.metadata {
+        Some(metadata) if !metadata.matches_vkey(vkey) => {
+            Err(SP1VerificationError::MetadataMismatch)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the commitment scheme under which `public_values` hash to the digest committed by the
+/// program, which is the same in all the shards, or `None` if there is no such scheme.
+pub(crate) fn commitment_scheme(
+    shard_proofs: &[ShardProof<CoreSC>],
+    public_values: &SP1PublicValues,
+) -> Option<CommitmentScheme> {
+    let shard_proof = shard_proofs.last()?;
+    let committed = PublicValues::from_vec(shard_proof.public_values.clone());
+    let mut digest = [0u8; 32];
+    for (byte, committed) in digest.iter_mut().zip(
+        committed
+            .committed_value_digest
+            .iter()
+            .flat_map(|word| word.0),
+    ) {
+        *byte = committed.as_canonical_u32() as u8;
+    }
+    public_values.commitment_scheme(&digest)
+}
+
+/// Checks that the public values of a proof hash to the digest committed by the program with one
+/// of the commitment schemes, which is then the scheme the program selected.
+pub(crate) fn verify_public_values_digest(proof: &SP1Proof) -> Result<(), SP1VerificationError> {
+    commitment_scheme(&proof.proof, &proof.public_values)
+        .map(|_| ())
+        .ok_or(SP1VerificationError::PublicValuesMismatch)
+}
@@ -0,0 +1,405 @@
+use std::fmt::{Debug, Display};
+
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::SP1ProofWithPublicValues;
+
+/// The magic bytes at the start of a serialized proof.
+pub const PROOF_MAGIC: [u8; 8] = *b"SP1PROOF";
+
+/// The version of the format of serialized proofs, which follows the magic bytes.
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+/// The number of bytes of the checksum of each section.
+const CHECKSUM_SIZE: usize = 4;
+
+/// The number of bytes before the data of a section: its length and its checksum.
+const SECTION_HEADER_SIZE: usize = 8 + CHECKSUM_SIZE;
+
+/// A section of a serialized proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofSection {
+    /// The magic bytes and the format version.
+    Header,
+    /// The proof itself.
+    Proof,
+    /// The inputs of the program.
+    Stdin,
+    /// The public values committed by the program.
+    PublicValues,
+    /// The version of SP1 which generated the proof.
+    Version,
+    /// The provenance metadata of the proof.
+    Metadata,
+}
+
+impl Display for ProofSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// An error returned when a serialized proof is corrupted, with the section which failed and the
+/// offset at which it starts in the bytes.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DeserializeError {
+    #[error("invalid magic bytes {0:?}")]
+    InvalidMagic([u8; 8]),
+    #[error("unsupported proof format version {0}")]
+    UnsupportedVersion(u32),
+    #[error("{section} section at offset {offset} is truncated")]
+    Truncated {
+        section: ProofSection,
+        offset: usize,
+    },
+    #[error("checksum mismatch in {section} section at offset {offset}")]
+    ChecksumMismatch {
+        section: ProofSection,
+        offset: usize,
+    },
+    #[error("failed to decode {section} section at offset {offset}: {message}")]
+    Malformed {
+        section: ProofSection,
+        offset: usize,
+        message: String,
+    },
+    #[error("{len} trailing bytes at offset {offset}")]
+    TrailingBytes { offset: usize, len: usize },
+}
+
+/// Returns the checksum of the data of a section.
+fn checksum(data: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    Sha256::digest(data)[..CHECKSUM_SIZE].try_into().unwrap()
+}
+
+/// Appends a section holding the encoding of `value` to `bytes`.
+fn write_section<T: Serialize>(bytes: &mut Vec<u8>, value: &T) {
+    let data = bincode::serialize(value).expect("serialization failed");
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&checksum(&data));
+    bytes.extend_from_slice(&data);
+}
+
+/// Reads serialized proof sections one after the other, keeping track of the offset.
+struct SectionReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> SectionReader<'a> {
+    /// Returns the next `len` bytes of `section`, which starts at `start`.
+    fn take(
+        &mut self,
+        section: ProofSection,
+        start: usize,
+        len: usize,
+    ) -> Result<&'a [u8], DeserializeError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(DeserializeError::Truncated {
+                section,
+                offset: start,
+            })?;
+        let data = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(data)
+    }
+
+    /// Reads the magic bytes and the format version.
+    fn read_header(&mut self) -> Result<(), DeserializeError> {
+        let magic = self.take(ProofSection::Header, 0, PROOF_MAGIC.len())?;
+        if magic != PROOF_MAGIC {
+            return Err(DeserializeError::InvalidMagic(magic.try_into().unwrap()));
+        }
+        let version = self.take(ProofSection::Header, 0, 4)?;
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != PROOF_FORMAT_VERSION {
+            return Err(DeserializeError::UnsupportedVersion(version));
+        }
+        Ok(())
+    }
+
+    /// Reads a section, checks its checksum and decodes its data.
+    fn read_section<T: DeserializeOwned>(
+        &mut self,
+        section: ProofSection,
+    ) -> Result<T, DeserializeError> {
+        let start = self.offset;
+        let header = self.take(section, start, SECTION_HEADER_SIZE)?;
+        let (len, expected_checksum) = header.split_at(8);
+        let len = u64::from_le_bytes(len.try_into().unwrap());
+        let len = usize::try_from(len).map_err(|_| DeserializeError::Truncated {
+            section,
+            offset: start,
+        })?;
+        let data = self.take(section, start, len)?;
+        if checksum(data) != expected_checksum {
+            return Err(DeserializeError::ChecksumMismatch {
+                section,
+                offset: start,
+            });
+        }
+        bincode::deserialize(data).map_err(|err| DeserializeError::Malformed {
+            section,
+            offset: start,
+            message: err.to_string(),
+        })
+    }
+}
+
+impl<P: Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
+    /// Serializes the proof into bytes which can be read back with [Self::from_bytes].
+    ///
+    /// The bytes start with [PROOF_MAGIC] and [PROOF_FORMAT_VERSION], followed by a section for
+    /// each field of the proof. Each section is the length of its data, the first bytes of the
+    /// SHA-256 hash of its data, and its data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&PROOF_MAGIC);
+        bytes.extend_from_slice(&PROOF_FORMAT_VERSION.to_le_bytes());
+        write_section(&mut bytes, &self.proof);
+        write_section(&mut bytes, &self.stdin);
+        write_section(&mut bytes, &self.public_values);
+        write_section(&mut bytes, &self.sp1_version);
+        write_section(&mut bytes, &self.metadata);
+        bytes
+    }
+
+    /// Deserializes a proof serialized with [Self::to_bytes].
+    ///
+    /// Corrupted bytes are reported as a [DeserializeError] with the section which failed and its
+    /// offset, rather than a panic, to help diagnose corruption in storage or transmission. The
+    /// header is checked first, so that bytes which are not a proof, or a proof of another format
+    /// version, are not decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DeserializeError> {
+        let mut reader = SectionReader { bytes, offset: 0 };
+        reader.read_header()?;
+        let proof = Self {
+            proof: reader.read_section(ProofSection::Proof)?,
+            stdin: reader.read_section(ProofSection::Stdin)?,
+            public_values: reader.read_section(ProofSection::PublicValues)?,
+            sp1_version: reader.read_section(ProofSection::Version)?,
+            metadata: reader.read_section(ProofSection::Metadata)?,
+        };
+        if reader.offset != bytes.len() {
+            return Err(DeserializeError::TrailingBytes {
+                offset: reader.offset,
+                len: bytes.len() - reader.offset,
+            });
+        }
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommitmentScheme, ProofMetadata, SP1PublicValues, SP1Stdin, TreeShape};
+    use serde::Deserialize;
+
+    /// The layout of proofs before they had metadata.
+    #[derive(Serialize, Deserialize)]
+    struct LegacyProof {
+        proof: Vec<u64>,
+        stdin: SP1Stdin,
+        public_values: SP1PublicValues,
+        sp1_version: String,
+    }
+
+    fn test_proof() -> SP1ProofWithPublicValues<Vec<u64>> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10u32);
+        let mut public_values = SP1PublicValues::new();
+        public_values.write(&(10u32, 55u32, 89u32));
+        SP1ProofWithPublicValues {
+            proof: vec![1, 2, 3, 4],
+            stdin,
+            public_values,
+            sp1_version: "v1.0.0".to_string(),
+            metadata: None,
+        }
+    }
+
+    /// Returns the offset of each section of the serialized proof, after the header.
+    fn section_offsets(bytes: &[u8]) -> Vec<usize> {
+        let mut offsets = vec![];
+        let mut offset = PROOF_MAGIC.len() + 4;
+        while offset < bytes.len() {
+            offsets.push(offset);
+            let len = u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+            offset += SECTION_HEADER_SIZE + len as usize;
+        }
+        offsets
+    }
+
+    #[test]
+    fn test_proof_bytes_roundtrip() {
+        let proof = test_proof();
+        let bytes = proof.to_bytes();
+        let decoded = SP1ProofWithPublicValues::<Vec<u64>>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.proof, proof.proof);
+        assert_eq!(decoded.stdin.buffer, proof.stdin.buffer);
+        assert_eq!(
+            decoded.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+        assert_eq!(decoded.sp1_version, proof.sp1_version);
+        assert_eq!(decoded.metadata, proof.metadata);
+    }
+
+    #[test]
+    fn test_proof_legacy_layout() {
+        let mut proof = test_proof();
+        proof.metadata = Some(ProofMetadata {
+            program_hash: [7; 32],
+            sp1_version: proof.sp1_version.clone(),
+            created_at: 1,
+            num_shards: 2,
+            tree_shape: TreeShape {
+                layers: vec![vec![2], vec![1]],
+            },
+            commitment_scheme: CommitmentScheme::Keccak256,
+        });
+        let legacy = LegacyProof {
+            proof: proof.proof.clone(),
+            stdin: proof.stdin.clone(),
+            public_values: proof.public_values.clone(),
+            sp1_version: proof.sp1_version.clone(),
+        };
+
+        // The bincode encoding of a proof is unchanged by its metadata.
+        let legacy_bytes = bincode::serialize(&legacy).unwrap();
+        assert_eq!(bincode::serialize(&proof).unwrap(), legacy_bytes);
+        let decoded: SP1ProofWithPublicValues<Vec<u64>> =
+            bincode::deserialize(&legacy_bytes).unwrap();
+        assert_eq!(decoded.proof, proof.proof);
+        assert_eq!(decoded.metadata, None);
+
+        // The versioned format keeps the metadata.
+        let decoded = SP1ProofWithPublicValues::<Vec<u64>>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded.metadata, proof.metadata);
+
+        // Files saved in the legacy layout still load.
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &legacy_bytes).unwrap();
+        let loaded = SP1ProofWithPublicValues::<Vec<u64>>::load(file.path()).unwrap();
+        assert_eq!(loaded.proof, proof.proof);
+        assert_eq!(
+            loaded.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+        assert_eq!(loaded.metadata, None);
+    }
+
+    #[test]
+    fn test_proof_save_load() {
+        let proof = test_proof();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        proof.save(file.path()).unwrap();
+        assert_eq!(std::fs::read(file.path()).unwrap(), proof.to_bytes());
+
+        let loaded = SP1ProofWithPublicValues::<Vec<u64>>::load(file.path()).unwrap();
+        assert_eq!(loaded.proof, proof.proof);
+        assert_eq!(
+            loaded.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+
+        // A corrupted file reports the section which failed.
+        let mut bytes = proof.to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x10;
+        std::fs::write(file.path(), bytes).unwrap();
+        let err = SP1ProofWithPublicValues::<Vec<u64>>::load(file.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DeserializeError>(),
+            Some(DeserializeError::ChecksumMismatch {
+                section: ProofSection::Metadata,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_proof_bytes_truncated() {
+        let bytes = test_proof().to_bytes();
+        let offsets = section_offsets(&bytes);
+        let from_bytes = SP1ProofWithPublicValues::<Vec<u64>>::from_bytes;
+
+        assert_eq!(
+            from_bytes(&bytes[..6]).unwrap_err(),
+            DeserializeError::Truncated {
+                section: ProofSection::Header,
+                offset: 0,
+            }
+        );
+        assert_eq!(
+            from_bytes(&bytes[..offsets[0] + SECTION_HEADER_SIZE + 3]).unwrap_err(),
+            DeserializeError::Truncated {
+                section: ProofSection::Proof,
+                offset: offsets[0],
+            }
+        );
+        assert_eq!(
+            from_bytes(&bytes[..offsets[2] + 5]).unwrap_err(),
+            DeserializeError::Truncated {
+                section: ProofSection::PublicValues,
+                offset: offsets[2],
+            }
+        );
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert_eq!(
+            from_bytes(&extended).unwrap_err(),
+            DeserializeError::TrailingBytes {
+                offset: bytes.len(),
+                len: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_proof_bytes_bit_flipped() {
+        let bytes = test_proof().to_bytes();
+        let offsets = section_offsets(&bytes);
+        let flip = |i: usize| {
+            let mut bytes = bytes.clone();
+            bytes[i] ^= 0x10;
+            SP1ProofWithPublicValues::<Vec<u64>>::from_bytes(&bytes).unwrap_err()
+        };
+
+        assert!(matches!(flip(0), DeserializeError::InvalidMagic(_)));
+        assert_eq!(
+            flip(PROOF_MAGIC.len()),
+            DeserializeError::UnsupportedVersion(PROOF_FORMAT_VERSION ^ 0x10)
+        );
+        assert_eq!(
+            flip(offsets[0] + SECTION_HEADER_SIZE + 8),
+            DeserializeError::ChecksumMismatch {
+                section: ProofSection::Proof,
+                offset: offsets[0],
+            }
+        );
+        assert_eq!(
+            flip(offsets[2] + SECTION_HEADER_SIZE),
+            DeserializeError::ChecksumMismatch {
+                section: ProofSection::PublicValues,
+                offset: offsets[2],
+            }
+        );
+
+        // A flipped length makes the section overrun the bytes.
+        assert_eq!(
+            flip(offsets[4] + 7),
+            DeserializeError::Truncated {
+                section: ProofSection::Metadata,
+                offset: offsets[4],
+            }
+        );
+    }
+}
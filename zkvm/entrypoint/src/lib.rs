@@ -29,6 +29,49 @@ macro_rules! entrypoint {
     };
 }
 
+/// Declares the precompiles used by the program in a manifest section of the ELF.
+///
+/// When a program has a manifest, the prover only includes the chips of the declared precompiles
+/// and execution fails if the program invokes any other precompile.
+///
+/// ```ignore
+/// sp1_zkvm::manifest!(UINT256_MUL, SHA_EXTEND);
+/// ```
+#[macro_export]
+macro_rules! manifest {
+    ($($syscall:ident),* $(,)?) => {
+        #[used]
+        #[link_section = ".sp1_manifest"]
+        static ZKVM_MANIFEST: [u32; [$($crate::syscalls::$syscall),*].len()] =
+            [$($crate::syscalls::$syscall),*];
+    };
+}
+
+/// Asserts an invariant of the program, identified by `tag`, which becomes part of the proof.
+///
+/// If `cond` holds, `tag` is recorded, and the tags of the checked invariants are committed to the
+/// footer of the public values on halt, so the verifier knows the assertions were checked. The host
+/// reads them back from `PublicValuesFooter::invariants`. Otherwise, `tag` is committed as the
+/// error code and the program halts with exit code 1, like a panic.
+#[cfg(feature = "footer")]
+pub fn prove_assert(cond: bool, tag: u32) {
+    if cond {
+        syscalls::syscall_record_invariant(tag);
+    } else {
+        syscalls::syscall_set_error_code(tag);
+        syscalls::syscall_halt(1);
+    }
+}
+
+/// Ends the current shard, so that execution continues in a new shard.
+///
+/// The runtime also starts new shards once they are full, but the program author may know better
+/// where to split the execution, e.g. between iterations of a loop, to balance the sizes of the
+/// shards. This does not change the result of the program, only how its proof is split.
+pub fn force_shard_boundary() {
+    syscalls::syscall_shard_boundary();
+}
+
 #[cfg(all(target_os = "zkvm", feature = "libm"))]
 mod libm;
 
@@ -53,13 +96,73 @@ mod zkvm {
         }
     }
 
-    pub static mut PUBLIC_VALUES_HASHER: Option<Sha256> = None;
+    use sp1_precompiles::io::CommitmentScheme;
+    use sp1_precompiles::keccak::Keccak256;
+
+    /// The hasher of the public values, with the hash function of the commitment scheme selected
+    /// by the program.
+    pub enum PublicValuesHasher {
+        Sha256(Sha256),
+        /// Poseidon2 is not incremental over bytes, so the public values are buffered until the
+        /// program halts.
+        Poseidon2(Vec<u8>),
+        Keccak256(Keccak256),
+    }
+
+    impl PublicValuesHasher {
+        pub fn new(scheme: CommitmentScheme) -> Self {
+            match scheme {
+                CommitmentScheme::Sha256 => Self::Sha256(Sha256::new()),
+                CommitmentScheme::Poseidon2 => Self::Poseidon2(Vec::new()),
+                CommitmentScheme::Keccak256 => Self::Keccak256(Keccak256::new()),
+            }
+        }
+
+        pub fn update(&mut self, data: &[u8]) {
+            match self {
+                Self::Sha256(hasher) => Digest::update(hasher, data),
+                Self::Poseidon2(buffer) => buffer.extend_from_slice(data),
+                Self::Keccak256(hasher) => hasher.update(data),
+            }
+        }
+
+        pub fn finalize(self) -> [u8; 32] {
+            match self {
+                Self::Sha256(hasher) => hasher.finalize().into(),
+                Self::Poseidon2(buffer) => sp1_primitives::poseidon2_hash_bytes(&buffer),
+                Self::Keccak256(hasher) => hasher.finalize(),
+            }
+        }
+    }
+
+    pub static mut PUBLIC_VALUES_HASHER: Option<PublicValuesHasher> = None;
+
+    /// Whether the program wrote to the public values, after which it can no longer select the
+    /// commitment scheme.
+    pub static mut PUBLIC_VALUES_WRITTEN: bool = false;
+
+    cfg_if! {
+        if #[cfg(feature = "footer")] {
+            use sp1_precompiles::io::PublicValuesFooter;
+
+            /// The hasher of the inputs read by the program, until it commits their digest.
+            pub static mut INPUT_HASHER: Option<Sha256> = None;
+
+            /// The values committed by the program to the footer, which is appended to the public
+            /// values on halt.
+            pub static mut FOOTER: PublicValuesFooter = PublicValuesFooter::new();
+        }
+    }
 
     #[cfg(not(feature = "interface"))]
     #[no_mangle]
     unsafe extern "C" fn __start() {
         {
-            PUBLIC_VALUES_HASHER = Some(Sha256::new());
+            PUBLIC_VALUES_HASHER = Some(PublicValuesHasher::new(CommitmentScheme::default()));
+            #[cfg(feature = "footer")]
+            {
+                INPUT_HASHER = Some(Sha256::new());
+            }
             #[cfg(feature = "verify")]
             {
                 DEFERRED_PROOFS_DIGEST = Some([BabyBear::zero(); 8]);
@@ -0,0 +1,23 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Encrypts an AES block in place.
+///
+/// The `round_keys` buffer holds the number of rounds (10, 12 or 14) followed by the
+/// `num_rounds + 1` round keys of the expanded key schedule.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_aes_encrypt_block(state: *mut u32, round_keys: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::AES_ENCRYPT_BLOCK,
+            in("a0") state,
+            in("a1") round_keys,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
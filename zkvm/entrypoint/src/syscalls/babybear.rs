@@ -0,0 +1,47 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Packs `len` bytes into BabyBear elements written to `out`, three little-endian bytes per
+/// element.
+///
+/// Every element is below `2^24`, so it never overflows the BabyBear prime. If `len` is not a
+/// multiple of three, the last element is padded with zero bytes. `out` must have room for
+/// `len.div_ceil(3)` elements.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bytes_to_fields(bytes: *const u8, len: usize, out: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile packs 48 bytes into 16 elements at a time. Each chunk is copied into a
+        // zeroed, aligned buffer, which both pads the last chunk and allows unaligned inputs.
+        let num_elements = len.div_ceil(3);
+        let mut offset = 0;
+        while offset < len {
+            let chunk_len = core::cmp::min(48, len - offset);
+            let mut input = [0u32; 12];
+            core::ptr::copy_nonoverlapping(
+                bytes.add(offset),
+                input.as_mut_ptr() as *mut u8,
+                chunk_len,
+            );
+            let mut output = [0u32; 16];
+            asm!(
+                "ecall",
+                in("t0") crate::syscalls::BYTES_TO_FIELDS,
+                in("a0") input.as_ptr(),
+                in("a1") output.as_mut_ptr(),
+            );
+            let first_element = offset / 3;
+            let chunk_elements = core::cmp::min(16, num_elements - first_element);
+            core::ptr::copy_nonoverlapping(
+                output.as_ptr(),
+                out.add(first_element),
+                chunk_elements,
+            );
+            offset += chunk_len;
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
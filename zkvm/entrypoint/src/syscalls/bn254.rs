@@ -40,3 +40,67 @@ pub extern "C" fn syscall_bn254_double(p: *mut u32) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Inverts an element of the Bn254 scalar field.
+///
+/// The input must be reduced modulo the scalar field order. The result is stored in place, and
+/// zero is mapped to zero.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_fr_inv(x: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_FR_INV,
+            in("a0") x,
+            in("a1") 0,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Inverts an element of the Bn254 base field.
+///
+/// The input must be reduced modulo the base field modulus. The result is stored in place, and
+/// zero is mapped to zero.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_fp_inv(x: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_FP_INV,
+            in("a0") x,
+            in("a1") 0,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Multiplies two elements `c0 + c1 i` of the quadratic extension of the Bn254 base field, where
+/// `i^2 = -1`.
+///
+/// Each element is 16 words, `c0` then `c1`, and both coefficients must be reduced modulo the base
+/// field modulus. The result is stored in the first element.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_bn254_fp2_mul(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::BN254_FP2_MUL,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
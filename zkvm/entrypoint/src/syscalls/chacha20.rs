@@ -0,0 +1,31 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Writes the 16-word block of the ChaCha20 block function of RFC 8439 to `out`.
+///
+/// The key is 8 words and the nonce 3 words, each word read from 4 little-endian bytes, so that
+/// the bytes of `out` are the keystream of the block at `counter`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_chacha20_block(
+    key: *const u32,
+    nonce: *const u32,
+    counter: u32,
+    out: *mut u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointers to the key and the nonce, and the counter, from a
+        // single header.
+        let header = [key as u32, nonce as u32, counter];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CHACHA20_BLOCK,
+            in("a0") out,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,36 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Recombines two residues with the Chinese remainder theorem.
+///
+/// Writes to `out` the unique `x < m1 * m2` with `x = r1 (mod m1)` and `x = r2 (mod m2)`. All
+/// values are little-endian uint256s. The residues must be reduced, and the moduli must be coprime
+/// with a product below `2^256`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_crt_combine(
+    r1: *const u32,
+    m1: *const u32,
+    r2: *const u32,
+    m2: *const u32,
+    out: *mut u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads r1, m1, r2 and m2 contiguously, so copy them into one buffer.
+        let mut buf = [0u32; 32];
+        core::ptr::copy_nonoverlapping(r1, buf.as_mut_ptr(), 8);
+        core::ptr::copy_nonoverlapping(m1, buf.as_mut_ptr().add(8), 8);
+        core::ptr::copy_nonoverlapping(r2, buf.as_mut_ptr().add(16), 8);
+        core::ptr::copy_nonoverlapping(m2, buf.as_mut_ptr().add(24), 8);
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CRT_COMBINE,
+            in("a0") out,
+            in("a1") buf.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,27 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Reads the entry at `index` of the table of `table_len` words into `out`, with the `CT_LOOKUP`
+/// precompile.
+///
+/// Every entry of the table is read, so the memory accesses of the lookup do not depend on the
+/// index. The index must be below `table_len`, otherwise execution panics.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ct_lookup(table: *const u32, table_len: u32, index: u32, out: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the table pointer, the number of entries and the index from a
+        // header.
+        let header = [table as u32, table_len, index];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CT_LOOKUP,
+            in("a0") out,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
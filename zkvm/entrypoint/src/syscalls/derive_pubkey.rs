@@ -0,0 +1,81 @@
+use sp1_precompiles::secp256k1::Secp256k1Operations;
+use sp1_precompiles::utils::{bytes_to_words_le, AffinePoint, CurveOperations};
+
+use crate::syscalls::{syscall_scalar_in_range, syscall_uint256_mulmod};
+
+type Secp256k1Point = AffinePoint<Secp256k1Operations, 16>;
+
+/// The order `n` of the secp256k1 group, in little-endian words.
+pub(crate) const SECP256K1_N: [u32; 8] = [
+    0xd036_4141, 0xbfd2_5e8c, 0xaf48_a03b, 0xbaae_dce6, 0xffff_fffe, 0xffff_ffff, 0xffff_ffff,
+    0xffff_ffff,
+];
+
+/// The coefficient `b` of the secp256k1 curve `y^2 = x^3 + b`.
+const SECP256K1_B: u32 = 7;
+
+/// Derives the secp256k1 public key `[d]G` of the private key `d`, and writes it to `pubkey`.
+///
+/// The private key is 8 little-endian words, and the public key is the 16 little-endian words of
+/// `x` followed by `y`. Returns whether the private key is valid, that is `0 < d < n`, and the
+/// public key is a point on the curve other than the identity. Otherwise the all-zero point, which
+/// is not on the curve, is written to `pubkey`.
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_derive_pubkey(privkey: *const u32, pubkey: *mut u32) -> bool {
+    let privkey = unsafe { &*(privkey as *const [u32; 8]) };
+    let pubkey = unsafe { &mut *(pubkey as *mut [u32; 16]) };
+
+    // The scalar multiplication below takes a scalar in `[1, n)`, so the zero scalar and the
+    // multiples of `n`, whose products are the identity, are rejected before it.
+    if !syscall_scalar_in_range(privkey.as_ptr(), SECP256K1_N.as_ptr()) {
+        *pubkey = [0; 16];
+        return false;
+    }
+
+    let mut point = Secp256k1Point::generator_in_affine();
+    point.mul_assign(privkey);
+    let limbs: [u32; 16] = bytes_to_words_le(&point.to_le_bytes()).try_into().unwrap();
+    let (x, y) = limbs.split_at(8);
+    let (x, y): (&[u32; 8], &[u32; 8]) = (x.try_into().unwrap(), y.try_into().unwrap());
+
+    if !is_on_curve(x, y) {
+        *pubkey = [0; 16];
+        return false;
+    }
+    *pubkey = limbs;
+    true
+}
+
+/// Returns whether `(x, y)` is on the curve, i.e. `y^2 = x^3 + b` modulo `p`. The identity, the
+/// all-zero point, is not on the curve since `b` is nonzero.
+fn is_on_curve(x: &[u32; 8], y: &[u32; 8]) -> bool {
+    mul_mod_p(y, y) == curve_rhs(x)
+}
+
+/// Returns `x^3 + b` modulo `p`, the square of the `y` of the points with the given `x`.
+pub(crate) fn curve_rhs(x: &[u32; 8]) -> [u32; 8] {
+    let x_cubed = mul_mod_p(&mul_mod_p(x, x), x);
+
+    // `x^3` is below `p`, so adding `b` does not overflow 256 bits, and multiplying the sum by one
+    // reduces it.
+    let mut rhs = [0u32; 8];
+    let mut carry = SECP256K1_B as u64;
+    for (limb, &x_cubed_limb) in rhs.iter_mut().zip(x_cubed.iter()) {
+        let sum = x_cubed_limb as u64 + carry;
+        *limb = sum as u32;
+        carry = sum >> 32;
+    }
+    let mut one = [0u32; 8];
+    one[0] = 1;
+    mul_mod_p(&rhs, &one)
+}
+
+/// Returns `x * y` modulo the secp256k1 base field modulus, with the `UINT256_MUL` precompile.
+pub(crate) fn mul_mod_p(x: &[u32; 8], y: &[u32; 8]) -> [u32; 8] {
+    let mut result = *x;
+    let mut y_and_modulus = [0u32; 16];
+    y_and_modulus[..8].copy_from_slice(y);
+    y_and_modulus[8..].copy_from_slice(Secp256k1Operations::BASE_FIELD_MODULUS);
+    syscall_uint256_mulmod(result.as_mut_ptr(), y_and_modulus.as_ptr());
+    result
+}
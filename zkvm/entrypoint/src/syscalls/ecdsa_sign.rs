@@ -0,0 +1,118 @@
+use sp1_precompiles::secp256k1::Secp256k1Operations;
+use sp1_precompiles::utils::{bytes_to_words_le, AffinePoint, CurveOperations};
+
+use crate::syscalls::derive_pubkey::SECP256K1_N;
+use crate::syscalls::{syscall_scalar_in_range, syscall_uint256_mulmod};
+
+type Secp256k1Point = AffinePoint<Secp256k1Operations, 16>;
+
+/// The exponent `n - 2`, with which `k^{-1} = k^{n - 2}` modulo the prime `n`.
+const SECP256K1_N_MINUS_TWO: [u32; 8] = [
+    0xd036_413f, 0xbfd2_5e8c, 0xaf48_a03b, 0xbaae_dce6, 0xffff_fffe, 0xffff_ffff, 0xffff_ffff,
+    0xffff_ffff,
+];
+
+/// Computes the secp256k1 ECDSA signature `(r, s)` of the message hash `z` with the private key `d`
+/// and the nonce `k`, and writes it to `sig`.
+///
+/// The private key, the message hash and the nonce are each 8 little-endian words, where the
+/// message hash is the integer of its big-endian bytes, and the signature is the 16 little-endian
+/// words of `r` followed by `s`. The signature is `r = ([k]G).x mod n` and
+/// `s = k^{-1} (z + r d) mod n`. Deriving the nonce, e.g. with RFC 6979, is left to the caller.
+///
+/// Returns whether the signature is valid, that is `0 < d < n`, `0 < k < n` and both `r` and `s`
+/// are nonzero. Otherwise the all-zero signature is written to `sig`, and the caller should retry
+/// with another nonce.
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_ecdsa_sign(
+    privkey: *const u32,
+    msg_hash: *const u32,
+    nonce: *const u32,
+    sig: *mut u32,
+) -> bool {
+    let privkey = unsafe { &*(privkey as *const [u32; 8]) };
+    let msg_hash = unsafe { &*(msg_hash as *const [u32; 8]) };
+    let nonce = unsafe { &*(nonce as *const [u32; 8]) };
+    let sig = unsafe { &mut *(sig as *mut [u32; 16]) };
+
+    if !syscall_scalar_in_range(privkey.as_ptr(), SECP256K1_N.as_ptr())
+        || !syscall_scalar_in_range(nonce.as_ptr(), SECP256K1_N.as_ptr())
+    {
+        *sig = [0; 16];
+        return false;
+    }
+
+    // `k` is in `[1, n)`, so `[k]G` is not the identity and its `x` is a base field element.
+    let mut point = Secp256k1Point::generator_in_affine();
+    point.mul_assign(nonce);
+    let limbs = bytes_to_words_le(&point.to_le_bytes());
+    let x: [u32; 8] = limbs[..8].try_into().unwrap();
+
+    let mut one = [0u32; 8];
+    one[0] = 1;
+    let r = mul_mod_n(&x, &one);
+    let z = mul_mod_n(msg_hash, &one);
+    let z_plus_rd = add_mod_n(&z, &mul_mod_n(&r, privkey));
+    let s = mul_mod_n(&invert_mod_n(nonce), &z_plus_rd);
+
+    if r == [0; 8] || s == [0; 8] {
+        *sig = [0; 16];
+        return false;
+    }
+    sig[..8].copy_from_slice(&r);
+    sig[8..].copy_from_slice(&s);
+    true
+}
+
+/// Returns `x * y` modulo the secp256k1 group order, with the `UINT256_MUL` precompile.
+pub(crate) fn mul_mod_n(x: &[u32; 8], y: &[u32; 8]) -> [u32; 8] {
+    let mut result = *x;
+    let mut y_and_modulus = [0u32; 16];
+    y_and_modulus[..8].copy_from_slice(y);
+    y_and_modulus[8..].copy_from_slice(&SECP256K1_N);
+    syscall_uint256_mulmod(result.as_mut_ptr(), y_and_modulus.as_ptr());
+    result
+}
+
+/// Returns `x + y` modulo the secp256k1 group order, for `x` and `y` below it.
+fn add_mod_n(x: &[u32; 8], y: &[u32; 8]) -> [u32; 8] {
+    let mut sum = [0u32; 8];
+    let mut carry = 0u64;
+    for ((limb, &x_limb), &y_limb) in sum.iter_mut().zip(x.iter()).zip(y.iter()) {
+        let limb_sum = x_limb as u64 + y_limb as u64 + carry;
+        *limb = limb_sum as u32;
+        carry = limb_sum >> 32;
+    }
+
+    // The sum is below `2n`, so subtracting `n` once reduces it. The sum is at least `n` if it
+    // overflows 256 bits, or if it does not and subtracting `n` does not borrow.
+    let mut difference = [0u32; 8];
+    let mut borrow = 0i64;
+    for ((limb, &sum_limb), &n_limb) in
+        difference.iter_mut().zip(sum.iter()).zip(SECP256K1_N.iter())
+    {
+        let limb_difference = sum_limb as i64 - n_limb as i64 - borrow;
+        *limb = limb_difference as u32;
+        borrow = (limb_difference < 0) as i64;
+    }
+    if carry != 0 || borrow == 0 {
+        difference
+    } else {
+        sum
+    }
+}
+
+/// Returns `k^{-1}` modulo the prime secp256k1 group order, as `k^{n - 2}`, for `k` in `[1, n)`.
+pub(crate) fn invert_mod_n(k: &[u32; 8]) -> [u32; 8] {
+    let mut result = [0u32; 8];
+    result[0] = 1;
+    for limb in SECP256K1_N_MINUS_TWO.iter().rev() {
+        for bit in (0..32).rev() {
+            result = mul_mod_n(&result, &result);
+            if (limb >> bit) & 1 == 1 {
+                result = mul_mod_n(&result, k);
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,161 @@
+use sp1_precompiles::secp256k1::Secp256k1Operations;
+use sp1_precompiles::utils::{bytes_to_words_le, words_to_bytes_le, AffinePoint, CurveOperations};
+
+use crate::syscalls::derive_pubkey::{curve_rhs, mul_mod_p, SECP256K1_N};
+use crate::syscalls::ecdsa_sign::{invert_mod_n, mul_mod_n};
+use crate::syscalls::{syscall_scalar_in_range, syscall_secp256k1_decompress};
+
+type Secp256k1Point = AffinePoint<Secp256k1Operations, 16>;
+
+/// The exponent `(p - 1) / 2` of Euler's criterion modulo the secp256k1 base field modulus `p`.
+const SECP256K1_P_MINUS_ONE_HALF: [u32; 8] = [
+    0x7fff_fe17, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff,
+    0x7fff_ffff,
+];
+
+/// Recovers the secp256k1 public key which signed the message hash `z` with the ECDSA signature
+/// `(r, s)`, and writes it to `pubkey`.
+///
+/// The message hash is 8 little-endian words, the integer of its big-endian bytes, and the
+/// signature the 16 little-endian words of `r` followed by `s`, as written by
+/// `syscall_secp256k1_ecdsa_sign`. The public key is the 16 little-endian words of `x` followed by
+/// `y`. The recovery id selects the point `R` of the signature: its `x` is `r`, or `r + n` if the
+/// second bit of the recovery id is set, and its `y` is odd if the first bit is set. The public key
+/// is then `Q = r^{-1} (s R - z G)`.
+///
+/// Returns whether the public key was recovered, that is the recovery id is at most 3, `r` and `s`
+/// are in `[1, n)`, `R` is a point on the curve and `Q` is not the identity. Otherwise the all-zero
+/// point, which is not on the curve, is written to `pubkey`.
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_ecrecover(
+    msg_hash: *const u32,
+    sig: *const u32,
+    recovery_id: u32,
+    pubkey: *mut u32,
+) -> bool {
+    let msg_hash = unsafe { &*(msg_hash as *const [u32; 8]) };
+    let sig = unsafe { &*(sig as *const [u32; 16]) };
+    let pubkey = unsafe { &mut *(pubkey as *mut [u32; 16]) };
+
+    let r: &[u32; 8] = sig[..8].try_into().unwrap();
+    let s: &[u32; 8] = sig[8..].try_into().unwrap();
+    match recover(msg_hash, r, s, recovery_id) {
+        Some(limbs) => {
+            *pubkey = limbs;
+            true
+        }
+        None => {
+            *pubkey = [0; 16];
+            false
+        }
+    }
+}
+
+/// Returns the public key recovered from the signature, or `None` if the signature or the recovery
+/// id is invalid.
+fn recover(z: &[u32; 8], r: &[u32; 8], s: &[u32; 8], recovery_id: u32) -> Option<[u32; 16]> {
+    if recovery_id > 3
+        || !syscall_scalar_in_range(r.as_ptr(), SECP256K1_N.as_ptr())
+        || !syscall_scalar_in_range(s.as_ptr(), SECP256K1_N.as_ptr())
+    {
+        return None;
+    }
+
+    // `r` is below `n`, which is below `p`, so only `r + n` needs to be checked to be below `p`.
+    let x = if recovery_id & 2 == 0 {
+        *r
+    } else {
+        let x = add_n(r)?;
+        let modulus = Secp256k1Operations::BASE_FIELD_MODULUS;
+        if !syscall_scalar_in_range(x.as_ptr(), modulus.as_ptr()) {
+            return None;
+        }
+        x
+    };
+
+    // The decompress precompile requires `x^3 + b` to be a square, which is checked with Euler's
+    // criterion. It is nonzero, since the curve has no point of order two.
+    let mut one = [0u32; 8];
+    one[0] = 1;
+    if pow_mod_p(&curve_rhs(&x), &SECP256K1_P_MINUS_ONE_HALF) != one {
+        return None;
+    }
+
+    // The decompress precompile takes and returns the big-endian bytes of the coordinates.
+    let mut point = [0u8; 64];
+    point[..32].copy_from_slice(&words_to_bytes_le(&x));
+    point[..32].reverse();
+    syscall_secp256k1_decompress(&mut point, recovery_id & 1 == 1);
+    point[..32].reverse();
+    point[32..].reverse();
+    let big_r = Secp256k1Point::from(&point[..32], &point[32..]);
+
+    // `Q = u1 G + u2 R`, with `u1 = -z r^{-1}` and `u2 = s r^{-1}`, where `u2` is nonzero since `s`
+    // is. The scalar multiplication takes a scalar in `[1, n)`, so `u1 G` is only added if `u1` is
+    // nonzero.
+    let r_inv = invert_mod_n(r);
+    let u1 = neg_mod_n(&mul_mod_n(&mul_mod_n(z, &one), &r_inv));
+    let u2 = mul_mod_n(s, &r_inv);
+
+    let mut q = big_r;
+    q.mul_assign(&u2);
+    if u1 != [0; 8] {
+        let mut u1_g = Secp256k1Point::generator_in_affine();
+        u1_g.mul_assign(&u1);
+
+        // The add precompile does not support adding a point to itself or to its negation, whose
+        // sum is the identity.
+        let (q_bytes, u1_g_bytes) = (q.to_le_bytes(), u1_g.to_le_bytes());
+        if q_bytes == u1_g_bytes {
+            q.double();
+        } else if q_bytes[..32] == u1_g_bytes[..32] {
+            return None;
+        } else {
+            q.add_assign(&u1_g);
+        }
+    }
+
+    Some(bytes_to_words_le(&q.to_le_bytes()).try_into().unwrap())
+}
+
+/// Returns `r + n`, or `None` if it overflows 256 bits.
+fn add_n(r: &[u32; 8]) -> Option<[u32; 8]> {
+    let mut sum = [0u32; 8];
+    let mut carry = 0u64;
+    for ((limb, &r_limb), &n_limb) in sum.iter_mut().zip(r.iter()).zip(SECP256K1_N.iter()) {
+        let limb_sum = r_limb as u64 + n_limb as u64 + carry;
+        *limb = limb_sum as u32;
+        carry = limb_sum >> 32;
+    }
+    (carry == 0).then_some(sum)
+}
+
+/// Returns `-x` modulo the secp256k1 group order, for `x` below it.
+fn neg_mod_n(x: &[u32; 8]) -> [u32; 8] {
+    if *x == [0; 8] {
+        return *x;
+    }
+    let mut difference = [0u32; 8];
+    let mut borrow = 0i64;
+    for ((limb, &n_limb), &x_limb) in difference.iter_mut().zip(SECP256K1_N.iter()).zip(x.iter()) {
+        let limb_difference = n_limb as i64 - x_limb as i64 - borrow;
+        *limb = limb_difference as u32;
+        borrow = (limb_difference < 0) as i64;
+    }
+    difference
+}
+
+/// Returns `x^e` modulo the secp256k1 base field modulus.
+fn pow_mod_p(x: &[u32; 8], e: &[u32; 8]) -> [u32; 8] {
+    let mut result = [0u32; 8];
+    result[0] = 1;
+    for limb in e.iter().rev() {
+        for bit in (0..32).rev() {
+            result = mul_mod_p(&result, &result);
+            if (limb >> bit) & 1 == 1 {
+                result = mul_mod_p(&result, x);
+            }
+        }
+    }
+    result
+}
@@ -1,6 +1,8 @@
 #[cfg(target_os = "zkvm")]
 use core::arch::asm;
 
+use sp1_precompiles::ed25519::{self, Ed25519Point};
+
 /// Adds two Edwards points.
 ///
 /// The result is stored in the first point.
@@ -47,3 +49,33 @@ pub extern "C" fn syscall_ed_decompress(point: &mut [u8; 64]) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Verifies the EdDSA signature `sig` of a message by the ed25519 public key `pubkey`.
+///
+/// The signature is the encoding of the point `R` followed by the 32 little-endian bytes of the
+/// scalar `S`, the public key is the encoding of the point `A`, and `msg_hash` is the 32
+/// little-endian bytes of `k = SHA-512(R || A || M) mod L`. Returns whether `S < L` and
+/// `[S]B == R + [k]A`, computed with the twisted Edwards add and decompress precompiles, see
+/// [`sp1_precompiles::ed25519`]. The encodings of `R` and `A` must be points of the curve.
+#[no_mangle]
+pub extern "C" fn syscall_ed25519_verify(
+    sig: *const u8,
+    pubkey: *const u8,
+    msg_hash: *const u8,
+) -> bool {
+    let sig = unsafe { &*(sig as *const [u8; 64]) };
+    let pubkey = unsafe { &*(pubkey as *const [u8; 32]) };
+    let msg_hash = unsafe { &*(msg_hash as *const [u8; 32]) };
+
+    let s = ed25519::scalar_from_bytes(sig[32..].try_into().unwrap());
+    if !ed25519::scalar_is_canonical(&s) {
+        return false;
+    }
+    let k = ed25519::scalar_from_bytes(msg_hash);
+    let r = ed25519::decompress(sig[..32].try_into().unwrap());
+    let a = ed25519::decompress(pubkey);
+
+    let identity = Ed25519Point::new(ed25519::ED25519_IDENTITY);
+    let base = Ed25519Point::generator_in_affine();
+    ed25519::verify_equation(&identity, &base, &r, &a, &s, &k)
+}
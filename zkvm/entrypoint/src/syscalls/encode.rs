@@ -0,0 +1,87 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+#[cfg(target_os = "zkvm")]
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[cfg(target_os = "zkvm")]
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Calls the encoding precompile `code` on `len` bytes of word-aligned data, writing to a
+/// word-aligned output.
+#[cfg(target_os = "zkvm")]
+unsafe fn encode(code: u32, data: *const u8, len: usize, out: *mut u8) {
+    // The precompile reads the data pointer and its length from a header.
+    let header = [data as u32, len as u32];
+    asm!(
+        "ecall",
+        in("t0") code,
+        in("a0") out,
+        in("a1") header.as_ptr(),
+    );
+}
+
+/// Writes the lowercase hex encoding of the `len` bytes at `data` to the `2 * len` bytes at `out`.
+///
+/// When both buffers are word-aligned, the bytes are encoded with the `HEX_ENCODE` precompile.
+/// Otherwise, they are encoded in software.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_hex_encode(data: *const u8, len: usize, out: *mut u8) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        if len == 0 {
+            return;
+        }
+        if data as usize % 4 == 0 && out as usize % 4 == 0 {
+            encode(crate::syscalls::HEX_ENCODE, data, len, out);
+            return;
+        }
+        for i in 0..len {
+            let byte = *data.add(i);
+            *out.add(2 * i) = HEX_DIGITS[(byte >> 4) as usize];
+            *out.add(2 * i + 1) = HEX_DIGITS[(byte & 0xf) as usize];
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Writes the base64 encoding of the `len` bytes at `data`, with the standard alphabet and
+/// padding, to the `4 * len.div_ceil(3)` bytes at `out`.
+///
+/// When both buffers are word-aligned, the bytes are encoded with the `BASE64_ENCODE`
+/// precompile. Otherwise, they are encoded in software.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_base64_encode(data: *const u8, len: usize, out: *mut u8) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        if len == 0 {
+            return;
+        }
+        if data as usize % 4 == 0 && out as usize % 4 == 0 {
+            encode(crate::syscalls::BASE64_ENCODE, data, len, out);
+            return;
+        }
+        for (g, i) in (0..len).step_by(3).enumerate() {
+            let n = (len - i).min(3);
+            let mut group = [0u8; 3];
+            core::ptr::copy_nonoverlapping(data.add(i), group.as_mut_ptr(), n);
+            let chars = [
+                group[0] >> 2,
+                ((group[0] & 0x3) << 4) | (group[1] >> 4),
+                ((group[1] & 0xf) << 2) | (group[2] >> 6),
+                group[2] & 0x3f,
+            ];
+            for (j, sextet) in chars.into_iter().enumerate() {
+                let char = if j <= n { BASE64_CHARS[sextet as usize] } else { b'=' };
+                *out.add(4 * g + j) = char;
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,167 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Multiplies a uint256 field element by a small constant `k < 2^16` modulo `modulus`.
+///
+/// The result is written over `x`. A zero modulus is interpreted as `2^256`. The element `x` must
+/// be reduced modulo the modulus, otherwise execution fails.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_field_mul_small(x: *mut u32, k: u32, modulus: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointer to the modulus and k from a single header.
+        let header = [modulus as u32, k];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FIELD_MUL_SMALL,
+            in("a0") x,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Inverts `len` consecutive uint256 field elements at `ptr` in place, modulo `modulus`.
+///
+/// The inverses are computed with Montgomery's trick, which inverts only the product of all the
+/// elements. The elements must be reduced and invertible, and the modulus must be greater than one.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_field_batch_inv(ptr: *mut u32, len: u32, modulus: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        if len == 0 {
+            return;
+        }
+        // The precompile reads the modulus and the length from a single header.
+        let mut header = [0u32; 9];
+        core::ptr::copy_nonoverlapping(modulus, header.as_mut_ptr(), 8);
+        header[8] = len;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FIELD_BATCH_INV,
+            in("a0") ptr,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Computes the multiply-accumulate `acc = (acc + a * b) mod modulus` in place.
+///
+/// Each value is 8 little-endian words, and a zero modulus is interpreted as `2^256`. The operands
+/// may overlap each other and the accumulator, e.g. to accumulate a square.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_field_mac(
+    acc: *mut u32,
+    a: *const u32,
+    b: *const u32,
+    modulus: *const u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointers to a, b and the modulus from a single header.
+        let header = [a as u32, b as u32, modulus as u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FIELD_MAC,
+            in("a0") acc,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Computes the affine combination `x = (a * x + b) mod modulus` in place.
+///
+/// Each value is 8 little-endian words, and a zero modulus is interpreted as `2^256`. The operands
+/// may overlap each other and x.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_field_affine(
+    x: *mut u32,
+    a: *const u32,
+    b: *const u32,
+    modulus: *const u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointers to a, b and the modulus from a single header.
+        let header = [a as u32, b as u32, modulus as u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FIELD_AFFINE,
+            in("a0") x,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Writes the Montgomery reduction `t * 2^-256 mod modulus` of the 512-bit `t` to `out`.
+///
+/// The modulus must be odd, `n_prime` must be `-modulus^-1 mod 2^32`, and `t` must be below
+/// `modulus * 2^256`, which holds for the product of two reduced elements. The output is reduced,
+/// and may overlap the inputs.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_montgomery_reduce(
+    t: *const u32,
+    modulus: *const u32,
+    n_prime: u32,
+    out: *mut u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointers to the inputs and n_prime from a single header.
+        let header = [t as u32, modulus as u32, n_prime];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::MONTGOMERY_REDUCE,
+            in("a0") out,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Performs the number theoretic transform butterfly `(a, b) -> (a + b, (a - b) * twiddle)` in
+/// place, modulo `modulus`.
+///
+/// `a` and `b` must be reduced and must not overlap. The twiddle factor and the modulus may
+/// overlap them.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ntt_butterfly(
+    a: *mut u32,
+    b: *mut u32,
+    twiddle: *const u32,
+    modulus: *const u32,
+) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the pointers to the other operands from a single header.
+        let header = [b as u32, twiddle as u32, modulus as u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::NTT_BUTTERFLY,
+            in("a0") a,
+            in("a1") header.as_ptr(),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
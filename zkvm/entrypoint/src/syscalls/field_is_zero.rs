@@ -0,0 +1,25 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Returns whether the reduced field element `x`, given as 8 little-endian words, is zero.
+///
+/// The precompile writes the result to a word in memory, since the return register of a
+/// precompile is left unchanged.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_field_is_zero(x: *const u32) -> bool {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let mut result = 0u32;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::FIELD_IS_ZERO,
+            in("a0") x,
+            in("a1") &mut result as *mut u32,
+        );
+        result != 0
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
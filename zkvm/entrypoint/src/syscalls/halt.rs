@@ -3,9 +3,16 @@ use cfg_if::cfg_if;
 cfg_if! {
     if #[cfg(target_os = "zkvm")] {
         use core::arch::asm;
-        use sha2::Digest;
         use crate::zkvm;
         use crate::{PV_DIGEST_NUM_WORDS, POSEIDON_NUM_WORDS};
+        use sp1_precompiles::io::CommitmentScheme;
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(target_os = "zkvm", feature = "footer"))] {
+        use crate::syscalls::syscall_write;
+        use sp1_precompiles::io::FD_PUBLIC_VALUES;
     }
 }
 
@@ -15,11 +22,23 @@ cfg_if! {
     }
 }
 
+#[cfg(all(target_os = "zkvm", feature = "footer"))]
+use sha2::Digest;
+
 /// Halts the program.
 #[allow(unused_variables)]
 pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
     #[cfg(target_os = "zkvm")]
     unsafe {
+        // If the program was built with the footer, append it to the public values. Since this is
+        // the only place the footer is written, and the host reads it from the end of the public
+        // values, the program cannot forge its entries.
+        #[cfg(feature = "footer")]
+        {
+            let footer = core::mem::take(&mut zkvm::FOOTER).encode();
+            syscall_write(FD_PUBLIC_VALUES, footer.as_ptr(), footer.len());
+        }
+
         // When we halt, we retrieve the public values finalized digest.  This is the hash of all
         // the bytes written to the public values fd.
         let pv_digest_bytes = core::mem::take(&mut zkvm::PUBLIC_VALUES_HASHER)
@@ -29,7 +48,6 @@ pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
         // Convert the digest bytes into words, since we will be calling COMMIT ecall with
         // the words as a parameter.
         let pv_digest_words: [u32; PV_DIGEST_NUM_WORDS] = pv_digest_bytes
-            .as_slice()
             .chunks_exact(4)
             .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
             .collect::<Vec<_>>()
@@ -72,3 +90,119 @@ pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Selects the commitment scheme, identified by `scheme`, of the digest of the public values which
+/// is committed when the program halts. Nothing must have been written to the public values yet.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_set_commitment_scheme(scheme: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        assert!(
+            !zkvm::PUBLIC_VALUES_WRITTEN,
+            "the commitment scheme must be selected before committing public values"
+        );
+        let scheme = CommitmentScheme::from_id(scheme).expect("unknown commitment scheme");
+        zkvm::PUBLIC_VALUES_HASHER = Some(zkvm::PublicValuesHasher::new(scheme));
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Sets the error code which is committed to the footer when the program halts.
+#[cfg(feature = "footer")]
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_set_error_code(code: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        zkvm::FOOTER.error_code = Some(code);
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Marks the program as panicked in the footer which is committed when the program halts.
+#[cfg(feature = "footer")]
+#[no_mangle]
+pub extern "C" fn syscall_mark_panicked() {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        zkvm::FOOTER.panicked = true;
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Sets the nonce which is committed to the footer when the program halts.
+#[cfg(feature = "footer")]
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_commit_nonce(nonce: u64) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        assert!(
+            zkvm::FOOTER.nonce.is_none(),
+            "the nonce must be committed at most once"
+        );
+        zkvm::FOOTER.nonce = Some(nonce);
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Sets the 32 byte schema id at `schema_id`, which is committed to the footer when the program
+/// halts.
+#[cfg(feature = "footer")]
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_commit_schema_id(schema_id: *const u8) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        assert!(
+            zkvm::FOOTER.schema_id.is_none(),
+            "the schema id must be committed at most once"
+        );
+        let schema_id = core::slice::from_raw_parts(schema_id, 32);
+        zkvm::FOOTER.schema_id = Some(schema_id.try_into().unwrap());
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Sets the digest of the inputs read so far, which is committed to the footer when the program
+/// halts. Later inputs are not hashed.
+#[cfg(feature = "footer")]
+#[no_mangle]
+pub extern "C" fn syscall_commit_input_digest() {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let hasher = zkvm::INPUT_HASHER
+            .take()
+            .expect("the input digest must be committed at most once");
+        zkvm::FOOTER.input_digest = Some(hasher.finalize().into());
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Records that the invariant identified by `tag` was checked. The tags of the checked invariants
+/// are committed to the footer when the program halts.
+#[cfg(feature = "footer")]
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_record_invariant(tag: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        zkvm::FOOTER.invariants.push(tag);
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
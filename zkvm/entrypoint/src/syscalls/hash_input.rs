@@ -0,0 +1,77 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// The mode flag reversing the bytes of the input.
+pub const HASH_INPUT_REVERSE: u32 = 1;
+
+/// The mode flag prepending the length of the input in bytes, as a little-endian word.
+pub const HASH_INPUT_LENGTH_PREFIX: u32 = 2;
+
+/// The mode flag padding the output with zeros to a multiple of the block length, which is given
+/// in bytes by the bits of the mode above the first byte.
+pub const HASH_INPUT_PAD: u32 = 4;
+
+/// Returns the mode padding the output to blocks of `block_len` bytes, a nonzero multiple of four.
+pub const fn hash_input_pad(block_len: u32) -> u32 {
+    HASH_INPUT_PAD | (block_len << 8)
+}
+
+/// Returns the number of bytes of the input of `len` bytes prepared with `mode`.
+pub const fn prepared_hash_input_len(len: usize, mode: u32) -> usize {
+    let mut out_len = len.next_multiple_of(4);
+    if mode & HASH_INPUT_LENGTH_PREFIX != 0 {
+        out_len += 4;
+    }
+    if mode & HASH_INPUT_PAD != 0 {
+        out_len = out_len.next_multiple_of((mode >> 8) as usize);
+    }
+    out_len
+}
+
+/// Prepares the `len` bytes at `input` for hashing as selected by `mode`, and returns the number
+/// of bytes written to `out`.
+///
+/// The output is the length of the input as a little-endian word if `HASH_INPUT_LENGTH_PREFIX` is
+/// set, followed by the input, reversed if `HASH_INPUT_REVERSE` is set, padded with zeros to a
+/// whole word, and padded with zeros to a multiple of the block length if `HASH_INPUT_PAD` is set.
+/// When both buffers are word-aligned and the input is nonempty, the input is prepared with the
+/// `PREPARE_HASH_INPUT` precompile. Otherwise, it is prepared in software.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_prepare_hash_input(
+    input: *const u8,
+    len: usize,
+    out: *mut u8,
+    mode: u32,
+) -> usize {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let out_len = prepared_hash_input_len(len, mode);
+        if len != 0 && input as usize % 4 == 0 && out as usize % 4 == 0 {
+            // The precompile reads the input pointer, its length and the mode from a header.
+            let header = [input as u32, len as u32, mode];
+            asm!(
+                "ecall",
+                in("t0") crate::syscalls::PREPARE_HASH_INPUT,
+                in("a0") out,
+                in("a1") header.as_ptr(),
+            );
+            return out_len;
+        }
+
+        // Move the input first, so that the prefix does not overwrite it.
+        let offset = if mode & HASH_INPUT_LENGTH_PREFIX != 0 { 4 } else { 0 };
+        core::ptr::copy(input, out.add(offset), len);
+        if mode & HASH_INPUT_REVERSE != 0 {
+            core::slice::from_raw_parts_mut(out.add(offset), len).reverse();
+        }
+        if offset != 0 {
+            core::ptr::copy((len as u32).to_le_bytes().as_ptr(), out, 4);
+        }
+        core::ptr::write_bytes(out.add(offset + len), 0, out_len - offset - len);
+        out_len
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
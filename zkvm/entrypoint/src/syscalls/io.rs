@@ -2,7 +2,6 @@ cfg_if::cfg_if! {
     if #[cfg(target_os = "zkvm")] {
         use core::arch::asm;
         use crate::zkvm;
-        use sha2::digest::Update;
         use sp1_precompiles::io::FD_PUBLIC_VALUES;
     }
 }
@@ -28,7 +27,10 @@ pub extern "C" fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize) {
             // version of this hash.
             if fd == FD_PUBLIC_VALUES {
                 let pi_slice: &[u8] = unsafe { core::slice::from_raw_parts(write_buf, nbytes) };
-                unsafe { zkvm::PUBLIC_VALUES_HASHER.as_mut().unwrap().update(pi_slice) };
+                unsafe {
+                    zkvm::PUBLIC_VALUES_HASHER.as_mut().unwrap().update(pi_slice);
+                    zkvm::PUBLIC_VALUES_WRITTEN = true;
+                }
             }
         } else {
             unreachable!()
@@ -54,6 +56,30 @@ pub extern "C" fn syscall_hint_len() -> usize {
     unreachable!()
 }
 
+/// Returns an upper bound on the number of clock cycles left before the runtime moves to the next
+/// shard.
+///
+/// The value is an unproven hint from the prover, and shards may close earlier. The syscall can
+/// only be called inside `unconstrained!`, and the value must be passed back through the hint
+/// stream. Programs must produce the same outputs whatever it returns.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_cycles_remaining() -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let remaining;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CYCLES_REMAINING,
+            lateout("t0") remaining,
+        );
+        remaining
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
 #[allow(unused_variables)]
 #[no_mangle]
 pub extern "C" fn syscall_hint_read(ptr: *mut u8, len: usize) {
@@ -65,6 +91,80 @@ pub extern "C" fn syscall_hint_read(ptr: *mut u8, len: usize) {
             in("a0") ptr,
             in("a1") len,
         );
+
+        // Hash every input with its length, so that the committed digest identifies the inputs the
+        // program read, however they are deserialized, until the program commits the digest.
+        #[cfg(feature = "footer")]
+        if let Some(hasher) = zkvm::INPUT_HASHER.as_mut() {
+            let input: &[u8] = core::slice::from_raw_parts(ptr, len);
+            hasher.update(&(len as u32).to_le_bytes());
+            hasher.update(input);
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Reads the next private hint, which must be `len` bytes long, into the uninitialized memory at
+/// `out`.
+///
+/// Unlike `syscall_hint_read`, the hint is not hashed into the digest of the inputs committed with
+/// `commit_input_digest`. Nothing constrains its value, so the program must verify it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_read_hint(out: *mut u8, len: usize) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::READ_HINT,
+            in("a0") out,
+            in("a1") len,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Asks the runtime to end the current shard after this syscall, so the next instruction starts a
+/// new shard.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_shard_boundary() {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SHARD_BOUNDARY,
+            in("a0") 0,
+            in("a1") 0,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Returns whether the runtime supports the syscall with the given code, so that programs can fall
+/// back to a software implementation when a precompile is not available.
+///
+/// A precompile is supported if the runtime implements it and, when the program declares a
+/// manifest, the manifest includes it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_has_precompile(code: u32) -> bool {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let supported: u32;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::HAS_PRECOMPILE,
+            in("a0") code,
+            lateout("t0") supported,
+        );
+        supported == 1
     }
 
     #[cfg(not(target_os = "zkvm"))]
@@ -18,3 +18,13 @@ pub extern "C" fn syscall_keccak_permute(state: *mut u64) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Applies one keccak-f[1600] permutation in place to the 200-byte state at `state_ptr`, as 25
+/// little-endian 64-bit lanes.
+///
+/// No padding is absorbed nor output squeezed, unlike keccak256, so that other sponges such as
+/// SHAKE can be built on the permutation.
+#[no_mangle]
+pub extern "C" fn syscall_keccak_f(state_ptr: *mut u64) {
+    syscall_keccak_permute(state_ptr)
+}
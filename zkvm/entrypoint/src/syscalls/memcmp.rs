@@ -0,0 +1,40 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Returns whether the `len` bytes at `a` and `b` are equal, in a number of cycles which only
+/// depends on `len`, e.g. to compare MACs or hashes without leaking where they differ.
+///
+/// When both buffers are word aligned, they are compared with the `CT_MEMCMP` precompile, which
+/// writes the result to memory after the header, since the return register of a precompile is
+/// left unchanged. Otherwise, the bytes are compared in software by OR-ing the XOR of every pair
+/// of bytes, without branching on them.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ct_memcmp(a: *const u8, b: *const u8, len: usize) -> bool {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        if len == 0 {
+            return true;
+        }
+        if a as usize % 4 != 0 || b as usize % 4 != 0 {
+            let mut diff = 0u8;
+            for i in 0..len {
+                diff |= core::ptr::read_volatile(a.add(i)) ^ core::ptr::read_volatile(b.add(i));
+            }
+            return diff == 0;
+        }
+
+        // The precompile reads the pointer to `b` and the number of bytes from a header.
+        let mut header = [b as u32, len as u32, 0u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CT_MEMCMP,
+            in("a0") a,
+            in("a1") header.as_mut_ptr(),
+        );
+        header[2] != 0
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
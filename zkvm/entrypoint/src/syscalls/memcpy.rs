@@ -0,0 +1,46 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Copies `len` bytes from `src` to `dst`, which may overlap, like `memmove`.
+///
+/// When the buffers have the same alignment, the words between their unaligned ends are copied
+/// with the `MEMCPY` precompile. The ends are copied byte by byte, before or after the words
+/// depending on the direction of the copy, so that no byte is overwritten before it is read.
+/// Otherwise, the bytes are copied in software.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_memcpy(dst: *mut u8, src: *const u8, len: usize) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let head = (4 - dst as usize % 4) % 4;
+        if dst as usize % 4 != src as usize % 4 || len < head + 4 {
+            core::ptr::copy(src, dst, len);
+            return;
+        }
+        let num_words = (len - head) / 4;
+        let tail = head + 4 * num_words;
+
+        // The precompile reads the source pointer and the number of words from a header.
+        let header = [src.add(head) as u32, num_words as u32];
+        let copy_words = || {
+            asm!(
+                "ecall",
+                in("t0") crate::syscalls::MEMCPY,
+                in("a0") dst.add(head),
+                in("a1") header.as_ptr(),
+            );
+        };
+        if (dst as usize) < (src as usize) {
+            core::ptr::copy(src, dst, head);
+            copy_words();
+            core::ptr::copy(src.add(tail), dst.add(tail), len - tail);
+        } else {
+            core::ptr::copy(src.add(tail), dst.add(tail), len - tail);
+            copy_words();
+            core::ptr::copy(src, dst, head);
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -1,32 +1,86 @@
+mod aes;
+mod babybear;
 mod bigint;
 mod bls12381;
 mod bn254;
+mod chacha20;
+mod crt;
+mod ct_lookup;
+mod derive_pubkey;
+mod ecdsa_sign;
+mod ecrecover;
 mod ed25519;
+mod encode;
+mod field;
+mod field_is_zero;
 mod halt;
+mod hash_input;
 mod io;
 mod keccak_permute;
+mod memcmp;
+mod memcpy;
 mod memory;
+mod msm;
+mod pedersen;
+mod poseidon2;
 mod secp256k1;
 mod sha_compress;
 mod sha_extend;
 mod sys;
+mod table;
+mod uint256_cmov;
+mod uint256_gcd;
+mod uint256_mod_small;
 mod uint256_mul;
+mod uint256_scalar_in_range;
+mod uint256_scalar_to_naf;
+mod uint256_shift;
+mod uint256_sqr;
+mod uint256_to_decimal;
+mod uint256_wnaf;
 mod unconstrained;
 #[cfg(feature = "verify")]
 mod verify;
 
+pub use aes::*;
+pub use babybear::*;
 pub use bls12381::*;
 pub use bn254::*;
+pub use chacha20::*;
+pub use crt::*;
+pub use ct_lookup::*;
+pub use derive_pubkey::*;
+pub use ecdsa_sign::*;
+pub use ecrecover::*;
 pub use ed25519::*;
+pub use encode::*;
+pub use field::*;
+pub use field_is_zero::*;
 pub use halt::*;
+pub use hash_input::*;
 pub use io::*;
 pub use keccak_permute::*;
+pub use memcmp::*;
+pub use memcpy::*;
 pub use memory::*;
+pub use msm::*;
+pub use pedersen::*;
+pub use poseidon2::*;
 pub use secp256k1::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
 pub use sys::*;
+pub use table::*;
+pub use uint256_cmov::*;
+pub use uint256_gcd::*;
+pub use uint256_mod_small::*;
 pub use uint256_mul::*;
+pub use uint256_scalar_in_range::*;
+pub use uint256_scalar_to_naf::*;
+pub use uint256_shift::*;
+pub use uint256_sqr::*;
+pub use uint256_to_decimal::*;
+pub use uint256_wnaf::*;
 pub use unconstrained::*;
 #[cfg(feature = "verify")]
 pub use verify::*;
@@ -91,6 +145,18 @@ pub const HINT_LEN: u32 = 0x00_00_00_F0;
 /// Executes `HINT_READ`.
 pub const HINT_READ: u32 = 0x00_00_00_F1;
 
+/// Executes `CYCLES_REMAINING`.
+pub const CYCLES_REMAINING: u32 = 0x00_00_00_F2;
+
+/// Executes `READ_HINT`.
+pub const READ_HINT: u32 = 0x00_00_00_F3;
+
+/// Executes `SHARD_BOUNDARY`.
+pub const SHARD_BOUNDARY: u32 = 0x00_00_00_F4;
+
+/// Executes `HAS_PRECOMPILE`.
+pub const HAS_PRECOMPILE: u32 = 0x00_00_00_F5;
+
 /// Executes `BLS12381_DECOMPRESS`.
 pub const BLS12381_DECOMPRESS: u32 = 0x00_00_01_1C;
 
@@ -102,3 +168,108 @@ pub const BLS12381_ADD: u32 = 0x00_01_01_1E;
 
 /// Executes the `BLS12381_DOUBLE` precompile.
 pub const BLS12381_DOUBLE: u32 = 0x00_00_01_1F;
+
+/// Executes the `FIELD_MUL_SMALL` precompile.
+pub const FIELD_MUL_SMALL: u32 = 0x00_00_01_20;
+
+/// Executes the `BN254_FR_INV` precompile.
+pub const BN254_FR_INV: u32 = 0x00_00_01_21;
+
+/// Executes the `AES_ENCRYPT_BLOCK` precompile.
+pub const AES_ENCRYPT_BLOCK: u32 = 0x00_00_01_22;
+
+/// Executes the `CRT_COMBINE` precompile.
+pub const CRT_COMBINE: u32 = 0x00_00_01_23;
+
+/// Executes the `UINT256_CMOV` precompile.
+pub const UINT256_CMOV: u32 = 0x00_00_01_24;
+
+/// Executes the `POSEIDON2_COMPRESS` precompile.
+pub const POSEIDON2_COMPRESS: u32 = 0x00_00_01_25;
+
+/// Executes the `FIELD_BATCH_INV` precompile.
+pub const FIELD_BATCH_INV: u32 = 0x00_00_01_26;
+
+/// Executes the `BYTES_TO_FIELDS` precompile.
+pub const BYTES_TO_FIELDS: u32 = 0x00_00_01_27;
+
+/// Executes the `TABLE_LOOKUP` precompile.
+pub const TABLE_LOOKUP: u32 = 0x00_00_01_28;
+
+/// Executes the `UINT256_TO_DECIMAL` precompile.
+pub const UINT256_TO_DECIMAL: u32 = 0x00_00_01_29;
+
+/// Executes the `SECP256K1_NORMALIZE_S` precompile.
+pub const SECP256K1_NORMALIZE_S: u32 = 0x00_00_01_2A;
+
+/// Executes the `UINT256_MOD_SMALL` precompile.
+pub const UINT256_MOD_SMALL: u32 = 0x00_00_01_2B;
+
+/// Executes the `UINT256_WNAF` precompile.
+pub const UINT256_WNAF: u32 = 0x00_00_01_2C;
+
+/// Executes the `SECP256K1_COMPRESS` precompile.
+pub const SECP256K1_COMPRESS: u32 = 0x00_01_01_2D;
+
+/// Executes the `MEMCPY` precompile.
+pub const MEMCPY: u32 = 0x00_01_01_2E;
+
+/// Executes the `HEX_ENCODE` precompile.
+pub const HEX_ENCODE: u32 = 0x00_01_01_2F;
+
+/// Executes the `BASE64_ENCODE` precompile.
+pub const BASE64_ENCODE: u32 = 0x00_01_01_30;
+
+/// Executes the `PREPARE_HASH_INPUT` precompile.
+pub const PREPARE_HASH_INPUT: u32 = 0x00_01_01_31;
+
+/// Executes the `MONTGOMERY_REDUCE` precompile.
+pub const MONTGOMERY_REDUCE: u32 = 0x00_01_01_32;
+
+/// Executes the `NTT_BUTTERFLY` precompile.
+pub const NTT_BUTTERFLY: u32 = 0x00_01_01_33;
+
+/// Executes the `SCALAR_IN_RANGE` precompile.
+pub const SCALAR_IN_RANGE: u32 = 0x00_00_01_34;
+
+/// Executes the `FIELD_IS_ZERO` precompile.
+pub const FIELD_IS_ZERO: u32 = 0x00_00_01_35;
+
+/// Executes the `UINT256_SHL` precompile.
+pub const UINT256_SHL: u32 = 0x00_00_01_36;
+
+/// Executes the `UINT256_SHR` precompile.
+pub const UINT256_SHR: u32 = 0x00_00_01_37;
+
+/// Executes the `UINT256_GCD` precompile.
+pub const UINT256_GCD: u32 = 0x00_00_01_38;
+
+/// Executes the `CT_LOOKUP` precompile.
+pub const CT_LOOKUP: u32 = 0x00_00_01_39;
+
+/// Executes the `SCALAR_TO_NAF` precompile.
+pub const SCALAR_TO_NAF: u32 = 0x00_00_01_3A;
+
+/// Executes the `BN254_FP_INV` precompile.
+pub const BN254_FP_INV: u32 = 0x00_00_01_3B;
+
+/// Executes the `FIELD_MAC` precompile.
+pub const FIELD_MAC: u32 = 0x00_00_01_3C;
+
+/// Executes the `POSEIDON2_PERMUTE` precompile.
+pub const POSEIDON2_PERMUTE: u32 = 0x00_00_01_3D;
+
+/// Executes the `FIELD_AFFINE` precompile.
+pub const FIELD_AFFINE: u32 = 0x00_00_01_3E;
+
+/// Executes the `CHACHA20_BLOCK` precompile.
+pub const CHACHA20_BLOCK: u32 = 0x00_00_01_3F;
+
+/// Executes the `CT_MEMCMP` precompile.
+pub const CT_MEMCMP: u32 = 0x00_01_01_40;
+
+/// Executes the `UINT256_SQR` precompile.
+pub const UINT256_SQR: u32 = 0x00_00_01_41;
+
+/// Executes the `BN254_FP2_MUL` precompile.
+pub const BN254_FP2_MUL: u32 = 0x00_00_01_42;
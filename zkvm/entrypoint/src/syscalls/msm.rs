@@ -0,0 +1,38 @@
+use sp1_precompiles::bn254::Bn254;
+use sp1_precompiles::msm::{msm, MSM_SCALAR_WORDS};
+use sp1_precompiles::utils::{bytes_to_words_le, AffinePoint};
+
+type Bn254Point = AffinePoint<Bn254, 16>;
+
+/// Computes the multi-scalar multiplication `sum_i [scalars[i]] points[i]` of `n` bn254 points, and
+/// writes it to `out`.
+///
+/// The scalars are 8 little-endian words each, and the points are the 16 little-endian words of
+/// `x` followed by `y`. The identity is the all-zero point, which is not on the curve, both in the
+/// inputs and in `out`. The sum is computed with Pippenger's bucket method over the bn254 add and
+/// double precompiles, see [`sp1_precompiles::msm`].
+#[no_mangle]
+pub extern "C" fn syscall_bn254_msm(
+    scalars: *const u32,
+    points: *const u32,
+    n: usize,
+    out: *mut u32,
+) {
+    let scalars =
+        unsafe { core::slice::from_raw_parts(scalars as *const [u32; MSM_SCALAR_WORDS], n) };
+    let points = unsafe { core::slice::from_raw_parts(points as *const [u32; 16], n) };
+
+    // Terms with a zero scalar or the identity as point are the identity, so they are skipped.
+    let (scalars, points): (Vec<_>, Vec<_>) = scalars
+        .iter()
+        .zip(points)
+        .filter(|(scalar, point)| scalar.iter().any(|&w| w != 0) && point.iter().any(|&w| w != 0))
+        .map(|(scalar, point)| (*scalar, Bn254Point::new(*point)))
+        .unzip();
+
+    let sum = msm(&scalars, &points)
+        .map_or(vec![0; 16], |sum| bytes_to_words_le(&sum.to_le_bytes()));
+    unsafe {
+        core::ptr::copy_nonoverlapping(sum.as_ptr(), out, sum.len());
+    }
+}
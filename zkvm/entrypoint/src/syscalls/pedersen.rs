@@ -0,0 +1,45 @@
+use sp1_precompiles::pedersen::{
+    pedersen_scalar, PEDERSEN_CHUNK_SIZE, PEDERSEN_GENERATORS, PEDERSEN_MAX_LEN,
+};
+use sp1_precompiles::secp256k1::Secp256k1Operations;
+use sp1_precompiles::utils::AffinePoint;
+
+type Secp256k1Point = AffinePoint<Secp256k1Operations, 16>;
+
+/// Computes the Pedersen hash over secp256k1 of the `len` bytes at `msg`, and writes the point to
+/// `out` as the little-endian words of `x` followed by `y`.
+///
+/// The hash is accumulated with the secp256k1 add and double precompiles. See
+/// [`sp1_precompiles::pedersen`] for its definition.
+#[no_mangle]
+pub extern "C" fn syscall_pedersen_hash(msg: *const u8, len: usize, out: *mut u32) {
+    assert!(
+        len <= PEDERSEN_MAX_LEN,
+        "message of {} bytes is too long for the Pedersen hash",
+        len
+    );
+    let msg = unsafe { core::slice::from_raw_parts(msg, len) };
+
+    // The scalar of the length term is never zero, so the sum starts from a point.
+    let mut hash = Secp256k1Point::new(PEDERSEN_GENERATORS[0]);
+    hash.mul_assign(&pedersen_scalar(&(len as u32 + 1).to_le_bytes()));
+
+    for (chunk, generator) in msg
+        .chunks(PEDERSEN_CHUNK_SIZE)
+        .zip(PEDERSEN_GENERATORS[1..].iter())
+    {
+        // A zero chunk adds the point at infinity, which has no affine representation.
+        let scalar = pedersen_scalar(chunk);
+        if scalar.iter().all(|&word| word == 0) {
+            continue;
+        }
+        let mut term = Secp256k1Point::new(*generator);
+        term.mul_assign(&scalar);
+        hash.add_assign(&term);
+    }
+
+    let bytes = hash.to_le_bytes();
+    unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), out as *mut u8, bytes.len());
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Compresses the two 8-word digests `left` and `right` into `out` with Poseidon2.
+///
+/// The words are interpreted as BabyBear elements, and the output is the first half of the
+/// width-16 permutation of `left || right`, which matches the 2-to-1 compression used by the
+/// recursion layer.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_poseidon2_compress(left: *const u32, right: *const u32, out: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the two digests contiguously, so copy both into one buffer.
+        let mut buf = [0u32; 16];
+        core::ptr::copy_nonoverlapping(left, buf.as_mut_ptr(), 8);
+        core::ptr::copy_nonoverlapping(right, buf.as_mut_ptr().add(8), 8);
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::POSEIDON2_COMPRESS,
+            in("a0") buf.as_ptr(),
+            in("a1") out,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Applies the width-16 Poseidon2 permutation to the BabyBear elements at `state` in place.
+///
+/// The input words are reduced modulo the BabyBear prime, and the output words are canonical.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_poseidon2_permute(state: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::POSEIDON2_PERMUTE,
+            in("a0") state,
+            in("a1") 0,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// The number of words of the state of a Poseidon2 sponge: the 16 elements of the permutation,
+/// followed by the number of elements absorbed since the last permutation and the number of
+/// elements which can still be squeezed from it.
+pub const POSEIDON2_SPONGE_STATE_WORDS: usize = 18;
+
+/// The number of elements absorbed or squeezed per permutation.
+const SPONGE_RATE: usize = 8;
+
+const SPONGE_ABSORBED: usize = 16;
+const SPONGE_SQUEEZABLE: usize = 17;
+
+/// Initializes the sponge state of `POSEIDON2_SPONGE_STATE_WORDS` words at `state_ptr`.
+#[no_mangle]
+pub extern "C" fn syscall_poseidon2_sponge_init(state_ptr: *mut u32) {
+    let state = unsafe { &mut *(state_ptr as *mut [u32; POSEIDON2_SPONGE_STATE_WORDS]) };
+    *state = [0; POSEIDON2_SPONGE_STATE_WORDS];
+}
+
+/// Absorbs the `len` BabyBear elements at `data_ptr` into the sponge at `state_ptr`.
+///
+/// The sponge is a duplex in overwrite mode with a rate of 8, like Plonky3's `DuplexChallenger`:
+/// absorbed elements overwrite the rate part of the state, which is permuted once it is full, and
+/// absorbing discards the elements left to squeeze.
+#[no_mangle]
+pub extern "C" fn syscall_poseidon2_sponge_absorb(
+    state_ptr: *mut u32,
+    data_ptr: *const u32,
+    len: usize,
+) {
+    let state = unsafe { &mut *(state_ptr as *mut [u32; POSEIDON2_SPONGE_STATE_WORDS]) };
+    let data = unsafe { core::slice::from_raw_parts(data_ptr, len) };
+    for &element in data {
+        state[SPONGE_SQUEEZABLE] = 0;
+        let absorbed = state[SPONGE_ABSORBED] as usize;
+        state[absorbed] = element;
+        state[SPONGE_ABSORBED] += 1;
+        if absorbed + 1 == SPONGE_RATE {
+            sponge_duplex(state);
+        }
+    }
+}
+
+/// Squeezes `len` BabyBear elements from the sponge at `state_ptr` into `out_ptr`.
+///
+/// The state is permuted first if elements were absorbed since the last permutation, or once the
+/// rate part of the state is exhausted. Like `DuplexChallenger`, the elements of the rate part are
+/// squeezed from last to first.
+#[no_mangle]
+pub extern "C" fn syscall_poseidon2_sponge_squeeze(
+    state_ptr: *mut u32,
+    out_ptr: *mut u32,
+    len: usize,
+) {
+    let state = unsafe { &mut *(state_ptr as *mut [u32; POSEIDON2_SPONGE_STATE_WORDS]) };
+    let out = unsafe { core::slice::from_raw_parts_mut(out_ptr, len) };
+    for element in out.iter_mut() {
+        if state[SPONGE_ABSORBED] != 0 || state[SPONGE_SQUEEZABLE] == 0 {
+            sponge_duplex(state);
+        }
+        state[SPONGE_SQUEEZABLE] -= 1;
+        *element = state[state[SPONGE_SQUEEZABLE] as usize];
+    }
+}
+
+/// Permutes the state of a sponge, after which the whole rate part can be squeezed.
+fn sponge_duplex(state: &mut [u32; POSEIDON2_SPONGE_STATE_WORDS]) {
+    syscall_poseidon2_permute(state.as_mut_ptr());
+    state[SPONGE_ABSORBED] = 0;
+    state[SPONGE_SQUEEZABLE] = SPONGE_RATE as u32;
+}
@@ -67,3 +67,51 @@ pub extern "C" fn syscall_secp256k1_decompress(point: &mut [u8; 64], is_odd: boo
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Normalizes the `s` value of a Secp256k1 signature to the lower half of the curve order.
+///
+/// If `s > n / 2`, it is replaced with `n - s`. The value must be below the curve order `n`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_normalize_s(s: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SECP256K1_NORMALIZE_S,
+            in("a0") s,
+            in("a1") 0
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Compresses a Secp256k1 point.
+///
+/// The point is the little-endian words of x followed by the words of y. The 33 bytes of the SEC1
+/// compressed form are written to `out`: the prefix `0x02` or `0x03` for an even or odd y,
+/// followed by x in big-endian format.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_compress(point: *const u32, out: *mut u8) {
+    #[cfg(target_os = "zkvm")]
+    {
+        // The precompile writes whole words, so compress into an aligned buffer with room for the
+        // three bytes of padding.
+        let mut buf = [0u32; 9];
+        unsafe {
+            asm!(
+                "ecall",
+                in("t0") crate::syscalls::SECP256K1_COMPRESS,
+                in("a0") point,
+                in("a1") buf.as_mut_ptr()
+            );
+            core::ptr::copy_nonoverlapping(buf.as_ptr() as *const u8, out, 33);
+        }
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
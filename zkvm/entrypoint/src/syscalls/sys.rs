@@ -39,6 +39,18 @@ pub unsafe extern "C" fn sys_rand(recv_buf: *mut u8, words: usize) {
 #[no_mangle]
 pub unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     sys_write(2, msg_ptr, len);
+
+    // Commit the error code registered for the panic message, if the program registered any, and
+    // halt successfully so that the panic can be proven, with the footer marking it as a panic.
+    #[cfg(feature = "footer")]
+    {
+        let msg = String::from_utf8_lossy(core::slice::from_raw_parts(msg_ptr, len));
+        if let Some(code) = sp1_precompiles::io::panic_code(&msg) {
+            crate::syscalls::syscall_set_error_code(code);
+            crate::syscalls::syscall_mark_panicked();
+            syscall_halt(0);
+        }
+    }
     syscall_halt(1);
 }
 
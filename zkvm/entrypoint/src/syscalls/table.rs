@@ -0,0 +1,26 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Returns the value of `key` in the lookup table `table_id` of the program.
+///
+/// The tables are supplied with the program when it is set up, and the lookup is proven against
+/// their committed entries. Looking up a key which is not in the table fails execution.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_table_lookup(table_id: u32, key: u32) -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile overwrites the key with its value in memory.
+        let mut word = key;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::TABLE_LOOKUP,
+            in("a0") table_id,
+            in("a1") &mut word as *mut u32,
+        );
+        word
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,29 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Copies the uint256 `src` into `dst` if `cond` is nonzero, in constant time.
+///
+/// The source is read and the destination written whatever the value of `cond`, so the condition
+/// does not change the execution trace.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_cmov(dst: *mut u32, src: *const u32, cond: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads the source right after the destination, so copy both into one
+        // buffer.
+        let mut buf = [0u32; 16];
+        core::ptr::copy_nonoverlapping(dst, buf.as_mut_ptr(), 8);
+        core::ptr::copy_nonoverlapping(src, buf.as_mut_ptr().add(8), 8);
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_CMOV,
+            in("a0") buf.as_mut_ptr(),
+            in("a1") cond,
+        );
+        core::ptr::copy_nonoverlapping(buf.as_ptr(), dst, 8);
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
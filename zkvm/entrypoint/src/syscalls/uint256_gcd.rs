@@ -0,0 +1,27 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Computes the gcd of the uint256 values `x` and `y`, given as 8 little-endian words each.
+///
+/// Writes the gcd to the first 8 words of `out`, followed by coefficients `a` and `b` such that
+/// `a * x = b * y + gcd`. `x` must not be zero unless `y` is, as the gcd of zero and `y` is `y`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_gcd(x: *const u32, y: *const u32, out: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile reads y right after x, so copy both into one buffer.
+        let mut input = [0u32; 16];
+        core::ptr::copy_nonoverlapping(x, input.as_mut_ptr(), 8);
+        core::ptr::copy_nonoverlapping(y, input.as_mut_ptr().add(8), 8);
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_GCD,
+            in("a0") input.as_ptr(),
+            in("a1") out,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,25 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Reduces the uint256 `x` modulo the nonzero word `m`, returning the remainder.
+///
+/// The precompile writes the remainder to memory right after `m`, since the return register of a
+/// precompile is left unchanged. Panics in the runtime if `m` is zero.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_mod_small(x: *const u32, m: u32) -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let mut io = [m, 0u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_MOD_SMALL,
+            in("a0") x,
+            in("a1") io.as_mut_ptr(),
+        );
+        io[1]
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
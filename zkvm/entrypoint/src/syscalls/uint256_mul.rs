@@ -3,7 +3,8 @@ use core::arch::asm;
 
 /// Uint256 multiplication operation.
 ///
-/// The result is written over the first input.
+/// The result is written over the first input. `x` may be equal to `y` to square it, but must
+/// otherwise not overlap `y` and the modulus after it.
 #[allow(unused_variables)]
 #[no_mangle]
 pub extern "C" fn syscall_uint256_mulmod(x: *mut u32, y: *const u32) {
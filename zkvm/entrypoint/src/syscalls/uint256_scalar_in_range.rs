@@ -0,0 +1,25 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Returns whether the uint256 `x` is strictly between zero and the uint256 `modulus`.
+///
+/// The precompile writes the result to memory right after the pointer to the modulus, since the
+/// return register of a precompile is left unchanged.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_scalar_in_range(x: *const u32, modulus: *const u32) -> bool {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let mut io = [modulus as u32, 0u32];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SCALAR_IN_RANGE,
+            in("a0") x,
+            in("a1") io.as_mut_ptr(),
+        );
+        io[1] != 0
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
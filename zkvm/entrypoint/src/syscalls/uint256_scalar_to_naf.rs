@@ -0,0 +1,25 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Recodes the uint256 `scalar` into its width-4 NAF, 257 signed digits `d_i` with
+/// `scalar = sum d_i * 2^i`.
+///
+/// Every nonzero digit is odd, lies in `[-7, 7]` and is followed by at least three zero digits.
+/// The digits are written to the 65 words at `naf` as two's complement bytes, with the unused
+/// bytes zeroed.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_scalar_to_naf(scalar: *const u32, naf: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SCALAR_TO_NAF,
+            in("a0") scalar,
+            in("a1") naf,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
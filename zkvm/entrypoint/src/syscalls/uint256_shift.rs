@@ -0,0 +1,46 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Shifts the uint256 `x`, given as 8 little-endian words, left by `shift` bits in place.
+///
+/// Shifts of 256 bits or more give zero.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_shl(x: *mut u32, shift: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile only accepts shifts below 2^16, and any shift of 256 bits or more gives
+        // the same result.
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_SHL,
+            in("a0") x,
+            in("a1") shift.min(256),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Shifts the uint256 `x`, given as 8 little-endian words, right by `shift` bits in place.
+///
+/// Shifts of 256 bits or more give zero.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_shr(x: *mut u32, shift: u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile only accepts shifts below 2^16, and any shift of 256 bits or more gives
+        // the same result.
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_SHR,
+            in("a0") x,
+            in("a1") shift.min(256),
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,26 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Uint256 square operation.
+///
+/// Computes `x^2` modulo the modulus, or modulo `2^256` if the modulus is zero, and writes the
+/// result over `x`. The result is the same as `syscall_uint256_mulmod` with `x` as both operands,
+/// but the modulus is passed separately and must not overlap `x`. Unless the modulus is zero, `x`
+/// must be below it, so that the quotient `x^2 / modulus` fits in 256 bits, and the execution fails
+/// otherwise.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_sqr(x: *mut u32, modulus: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_SQR,
+            in("a0") x,
+            in("a1") modulus,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
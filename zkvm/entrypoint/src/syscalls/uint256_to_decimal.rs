@@ -0,0 +1,38 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// The number of digits written by the precompile, padded with leading zeros.
+#[cfg(target_os = "zkvm")]
+const NUM_DECIMAL_DIGITS: usize = 80;
+
+/// Writes the ASCII decimal digits of the uint256 `x` to `out`, most significant first and without
+/// leading zeros, and writes the number of digits to `out_len`.
+///
+/// The output buffer must hold at least 78 bytes, which is enough for any uint256.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_to_decimal(x: *const u32, out: *mut u8, out_len: *mut usize) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        // The precompile writes a fixed number of digits padded with leading zeros, so convert into
+        // a local buffer and strip them.
+        let mut buf = [0u32; NUM_DECIMAL_DIGITS / 4];
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_TO_DECIMAL,
+            in("a0") x,
+            in("a1") buf.as_mut_ptr(),
+        );
+        let digits = core::slice::from_raw_parts(buf.as_ptr() as *const u8, NUM_DECIMAL_DIGITS);
+        let start = digits
+            .iter()
+            .position(|&digit| digit != b'0')
+            .unwrap_or(NUM_DECIMAL_DIGITS - 1);
+        let len = NUM_DECIMAL_DIGITS - start;
+        core::ptr::copy_nonoverlapping(digits[start..].as_ptr(), out, len);
+        *out_len = len;
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,23 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Recodes the uint256 `scalar` into 65 signed digits `d_i` with `scalar = sum d_i * 16^i`.
+///
+/// Every digit but the last lies in `[-8, 8)`, and the last one is zero or one. The digits are
+/// written to the 17 words at `digits` as two's complement bytes, with the unused bytes zeroed.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint256_wnaf(scalar: *const u32, digits: *mut u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT256_WNAF,
+            in("a0") scalar,
+            in("a1") digits,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,32 @@
+use crate::syscall_bytes_to_fields;
+
+/// The number of bytes packed into each BabyBear element.
+pub const BYTES_PER_ELEMENT: usize = 3;
+
+/// Packs bytes into BabyBear elements, three little-endian bytes per element.
+///
+/// The last element is padded with zero bytes if the length is not a multiple of three, so the
+/// length must be known to unpack the elements with [`fields_to_bytes`].
+pub fn bytes_to_fields(bytes: &[u8]) -> Vec<u32> {
+    let mut fields = vec![0u32; bytes.len().div_ceil(BYTES_PER_ELEMENT)];
+    if !bytes.is_empty() {
+        unsafe {
+            syscall_bytes_to_fields(bytes.as_ptr(), bytes.len(), fields.as_mut_ptr());
+        }
+    }
+    fields
+}
+
+/// Unpacks the first `len` bytes from elements produced by [`bytes_to_fields`].
+pub fn fields_to_bytes(fields: &[u32], len: usize) -> Vec<u8> {
+    assert!(
+        len <= fields.len() * BYTES_PER_ELEMENT,
+        "not enough elements to unpack {} bytes",
+        len
+    );
+    fields
+        .iter()
+        .flat_map(|field| field.to_le_bytes().into_iter().take(BYTES_PER_ELEMENT))
+        .take(len)
+        .collect()
+}
@@ -20,6 +20,11 @@ impl CurveOperations<NUM_WORDS> for Bls12381 {
         3819610353, 146011265,
     ];
 
+    const BASE_FIELD_MODULUS: &'static [u32] = &[
+        4294945451, 3120496639, 2975072255, 514588670, 4138792484, 1731252896, 4085584575,
+        1685539716, 1129032919, 1260103606, 964683418, 436277738,
+    ];
+
     fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]) {
         unsafe {
             syscall_bls12381_add(limbs.as_mut_ptr(), other.as_ptr());
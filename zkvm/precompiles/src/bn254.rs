@@ -1,5 +1,6 @@
+use crate::msm::MSM_SCALAR_WORDS;
 use crate::utils::CurveOperations;
-use crate::{syscall_bn254_add, syscall_bn254_double};
+use crate::{syscall_bn254_add, syscall_bn254_double, syscall_bn254_msm};
 
 #[derive(Copy, Clone)]
 pub struct Bn254;
@@ -11,6 +12,11 @@ impl CurveOperations<NUM_WORDS> for Bn254 {
     // https://github.com/ethereum/py_pairing/blob/5f609da/py_ecc/bn128/bn128_field_elements.py
     const GENERATOR: [u32; NUM_WORDS] = [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0];
 
+    const BASE_FIELD_MODULUS: &'static [u32] = &[
+        3632069959, 1008765974, 1752287885, 2541841041, 2172737629, 3092268470, 3778125865,
+        811880050,
+    ];
+
     fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]) {
         unsafe {
             syscall_bn254_add(limbs.as_mut_ptr(), other.as_ptr());
@@ -23,3 +29,20 @@ impl CurveOperations<NUM_WORDS> for Bn254 {
         }
     }
 }
+
+/// Computes the multi-scalar multiplication `sum_i [scalars[i]] points[i]` with the
+/// `syscall_bn254_msm` precompile. The points are the little-endian words of `x` followed by `y`,
+/// and the identity is the all-zero point, both in the inputs and in the result.
+pub fn msm(scalars: &[[u32; MSM_SCALAR_WORDS]], points: &[[u32; NUM_WORDS]]) -> [u32; NUM_WORDS] {
+    assert_eq!(scalars.len(), points.len(), "one scalar is needed per point");
+    let mut out = [0u32; NUM_WORDS];
+    unsafe {
+        syscall_bn254_msm(
+            scalars.as_ptr() as *const u32,
+            points.as_ptr() as *const u32,
+            points.len(),
+            out.as_mut_ptr(),
+        );
+    }
+    out
+}
@@ -0,0 +1,157 @@
+//! EdDSA verification over ed25519.
+//!
+//! A signature `(R, S)` of a message `M` by the public key `A` is valid when `S < L` and
+//! `[S]B == R + [k]A`, where `B` is the base point, `L` its order, and `k = SHA-512(R || A || M)`
+//! reduced modulo `L`. Both scalar multiplications are computed with double-and-add over the
+//! complete addition of the twisted Edwards precompile, so that the identity and the doublings need
+//! no special case.
+
+use crate::utils::{bytes_to_words_le, AffinePoint, CurveOperations};
+use crate::{syscall_ed25519_verify, syscall_ed_add, syscall_ed_decompress};
+
+#[derive(Copy, Clone)]
+pub struct Ed25519;
+
+const NUM_WORDS: usize = 16;
+
+/// The number of words of a scalar.
+pub const ED25519_SCALAR_WORDS: usize = 8;
+
+/// The order `L` of the base point, in little endian words.
+pub const ED25519_GROUP_ORDER: [u32; ED25519_SCALAR_WORDS] = [
+    0x5cf5_d3ed, 0x5812_631a, 0xa2f7_9cd6, 0x14de_f9de, 0, 0, 0, 0x1000_0000,
+];
+
+/// The identity `(0, 1)`, in little endian words.
+pub const ED25519_IDENTITY: [u32; NUM_WORDS] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0];
+
+/// A point of ed25519.
+///
+/// The negation of `AffinePoint` negates `y`, which is that of short Weierstrass curves, so
+/// `AffinePoint::mul_assign` does not apply to ed25519 points, which use [`edwards_mul`] instead.
+pub type Ed25519Point = AffinePoint<Ed25519, NUM_WORDS>;
+
+impl CurveOperations<NUM_WORDS> for Ed25519 {
+    // The base point, whose y is 4/5.
+    const GENERATOR: [u32; NUM_WORDS] = [
+        0x8f25_d51a, 0xc956_2d60, 0x9525_a7b2, 0x692c_c760, 0xfdd6_dc5c, 0xc0a4_e231, 0xcd6e_53fe,
+        0x2169_36d3, 0x6666_6658, 0x6666_6666, 0x6666_6666, 0x6666_6666, 0x6666_6666, 0x6666_6666,
+        0x6666_6666, 0x6666_6666,
+    ];
+
+    const BASE_FIELD_MODULUS: &'static [u32] = &[
+        0xffff_ffed, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff, 0xffff_ffff,
+        0x7fff_ffff,
+    ];
+
+    fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]) {
+        let mut other = *other;
+        unsafe {
+            syscall_ed_add(limbs.as_mut_ptr(), other.as_mut_ptr());
+        }
+    }
+
+    fn double(limbs: &mut [u32; NUM_WORDS]) {
+        let other = *limbs;
+        Self::add_assign(limbs, &other);
+    }
+}
+
+/// A point on which the verification equation can be computed, with a complete addition such as
+/// that of the twisted Edwards precompile.
+pub trait EdwardsPoint: Clone {
+    /// Adds `other` to the point, which may be equal, opposite or the identity.
+    fn add_assign(&mut self, other: &Self);
+
+    /// Doubles the point.
+    fn double(&mut self);
+
+    /// Returns whether the points are equal.
+    fn is_equal(&self, other: &Self) -> bool;
+}
+
+impl EdwardsPoint for Ed25519Point {
+    fn add_assign(&mut self, other: &Self) {
+        AffinePoint::add_assign(self, other);
+    }
+
+    fn double(&mut self) {
+        AffinePoint::double(self);
+    }
+
+    fn is_equal(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+/// Returns `[scalar] point` with double-and-add from the most significant bit, where `identity` is
+/// the identity. The scalar is in little endian words.
+pub fn edwards_mul<P: EdwardsPoint>(
+    identity: &P,
+    point: &P,
+    scalar: &[u32; ED25519_SCALAR_WORDS],
+) -> P {
+    let mut res = identity.clone();
+    let mut started = false;
+    for bit in (0..ED25519_SCALAR_WORDS * 32).rev() {
+        if started {
+            res.double();
+        }
+        if (scalar[bit / 32] >> (bit % 32)) & 1 == 1 {
+            res.add_assign(point);
+            started = true;
+        }
+    }
+    res
+}
+
+/// Returns whether `scalar < L`, as required of the scalar `S` of a signature.
+pub fn scalar_is_canonical(scalar: &[u32; ED25519_SCALAR_WORDS]) -> bool {
+    for (word, order) in scalar.iter().zip(ED25519_GROUP_ORDER.iter()).rev() {
+        if word != order {
+            return word < order;
+        }
+    }
+    false
+}
+
+/// Returns whether `[s] base == r + [k] a`, the verification equation of a signature `(r, s)` by
+/// the public key `a` of a message with the scalar `k`.
+pub fn verify_equation<P: EdwardsPoint>(
+    identity: &P,
+    base: &P,
+    r: &P,
+    a: &P,
+    s: &[u32; ED25519_SCALAR_WORDS],
+    k: &[u32; ED25519_SCALAR_WORDS],
+) -> bool {
+    let lhs = edwards_mul(identity, base, s);
+    let mut rhs = edwards_mul(identity, a, k);
+    rhs.add_assign(r);
+    lhs.is_equal(&rhs)
+}
+
+/// Decompresses the point whose encoding is `y` in little endian bytes, with the sign of `x` as its
+/// most significant bit. The encoding must be that of a point of the curve.
+pub fn decompress(compressed: &[u8; 32]) -> Ed25519Point {
+    let mut point = [0u8; 64];
+    point[32..].copy_from_slice(compressed);
+    unsafe {
+        syscall_ed_decompress(&mut point);
+    }
+    Ed25519Point::new(bytes_to_words_le(&point).try_into().unwrap())
+}
+
+/// Returns the scalar whose little endian bytes are `bytes`.
+pub fn scalar_from_bytes(bytes: &[u8; 32]) -> [u32; ED25519_SCALAR_WORDS] {
+    bytes_to_words_le(bytes).try_into().unwrap()
+}
+
+/// Verifies the signature `sig` of a message by the public key `pubkey` with the
+/// `syscall_ed25519_verify` precompile.
+///
+/// The signature is the encoding of `R` followed by the little endian bytes of `S`, and `msg_hash`
+/// is the little endian bytes of `k = SHA-512(R || A || M) mod L`.
+pub fn verify(sig: &[u8; 64], pubkey: &[u8; 32], msg_hash: &[u8; 32]) -> bool {
+    unsafe { syscall_ed25519_verify(sig.as_ptr(), pubkey.as_ptr(), msg_hash.as_ptr()) }
+}
@@ -1,17 +1,103 @@
 #![allow(unused_unsafe)]
-use crate::syscall_write;
-use crate::{syscall_hint_len, syscall_hint_read};
+use crate::merkle::{leaf_digest, merkle_root};
+#[cfg(feature = "footer")]
+use crate::{
+    syscall_commit_input_digest, syscall_commit_nonce, syscall_commit_schema_id,
+    syscall_set_error_code,
+};
+use crate::{syscall_hint_len, syscall_hint_read, syscall_read_hint};
+use crate::{syscall_set_commitment_scheme, syscall_write};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::alloc::Layout;
 use std::io::Write;
 
+pub use sp1_primitives::io::CommitmentScheme;
+#[cfg(feature = "footer")]
+pub use sp1_primitives::io::{FooterEntryKind, FOOTER_MAGIC, FOOTER_VERSION};
+
 const FD_HINT: u32 = 4;
 pub const FD_PUBLIC_VALUES: u32 = 3;
 // Runtime hook file descriptors. Make sure these match the FDs in the HookRegistry.
 // The default hooks can be found in `core/src/runtime/hooks.rs`.
 pub const FD_ECRECOVER_HOOK: u32 = 5;
 
+/// The values which a program commits through the footer of its public values.
+///
+/// Only `syscall_halt` writes the footer, after everything the program committed, so the program
+/// cannot forge its entries. The host decodes it with `SP1PublicValues::split_footer`.
+#[cfg(feature = "footer")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublicValuesFooter {
+    /// The schema id set with `commit_with_schema`.
+    pub schema_id: Option<[u8; 32]>,
+    /// The nonce committed with `commit_nonce`.
+    pub nonce: Option<u64>,
+    /// The digest of the inputs committed with `commit_input_digest`.
+    pub input_digest: Option<[u8; 32]>,
+    /// The tags of the invariants checked with `prove_assert`, in the order they were checked.
+    pub invariants: Vec<u32>,
+    /// The error code set with `set_error_code`, or registered for a panic message with
+    /// `register_panic_code`.
+    pub error_code: Option<u32>,
+    /// Whether the program panicked after registering a code with `register_panic_code`.
+    pub panicked: bool,
+}
+
+#[cfg(feature = "footer")]
+impl PublicValuesFooter {
+    /// Creates an empty footer.
+    pub const fn new() -> Self {
+        Self {
+            schema_id: None,
+            nonce: None,
+            input_digest: None,
+            invariants: Vec::new(),
+            error_code: None,
+            panicked: false,
+        }
+    }
+
+    /// Encodes the footer as its entries, followed by the length of the entries and the version as
+    /// little-endian u32s, and `FOOTER_MAGIC`.
+    ///
+    /// Each entry is its kind and the length of its payload as little-endian u32s, followed by the
+    /// payload. The entries are in the order of their kinds, and unset values have no entry.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut entries = Vec::new();
+        let mut push = |kind: FooterEntryKind, payload: &[u8]| {
+            entries.extend_from_slice(&(kind as u32).to_le_bytes());
+            entries.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            entries.extend_from_slice(payload);
+        };
+        if let Some(schema_id) = &self.schema_id {
+            push(FooterEntryKind::SchemaId, schema_id);
+        }
+        if let Some(nonce) = self.nonce {
+            push(FooterEntryKind::Nonce, &nonce.to_le_bytes());
+        }
+        if let Some(input_digest) = &self.input_digest {
+            push(FooterEntryKind::InputDigest, input_digest);
+        }
+        if !self.invariants.is_empty() {
+            let tags = self.invariants.iter().flat_map(|tag| tag.to_le_bytes());
+            push(FooterEntryKind::Invariants, &tags.collect::<Vec<_>>());
+        }
+        if let Some(code) = self.error_code {
+            push(FooterEntryKind::ErrorCode, &code.to_le_bytes());
+        }
+        if self.panicked {
+            push(FooterEntryKind::Panicked, &[]);
+        }
+
+        let entries_len = entries.len() as u32;
+        entries.extend_from_slice(&entries_len.to_le_bytes());
+        entries.extend_from_slice(&FOOTER_VERSION.to_le_bytes());
+        entries.extend_from_slice(&FOOTER_MAGIC);
+        entries
+    }
+}
+
 pub struct SyscallWriter {
     fd: u32,
 }
@@ -33,6 +119,33 @@ impl std::io::Write for SyscallWriter {
 
 pub fn read_vec() -> Vec<u8> {
     let len = unsafe { syscall_hint_len() };
+    let mut vec = alloc_vec(len);
+    // Read the vec into uninitialized memory. The syscall assumes the memory is uninitialized,
+    // which should be true because the allocator does not dealloc, so a new alloc should be fresh.
+    unsafe {
+        syscall_hint_read(vec.as_mut_ptr(), len);
+        vec.set_len(len);
+    }
+    vec
+}
+
+/// Reads a private hint of `len` bytes, written by the host with `SP1Stdin::write_hint`.
+///
+/// Hints are not part of the digest of the inputs committed with `commit_input_digest`, which
+/// makes them suitable for nondeterministic advice. Nothing constrains their value, so the program
+/// MUST verify every hint it reads, e.g. check that a hinted square root squares to its input.
+pub fn read_hint_vec(len: usize) -> Vec<u8> {
+    let mut vec = alloc_vec(len);
+    // Like `read_vec`, the syscall writes the hint into the fresh, uninitialized allocation.
+    unsafe {
+        syscall_read_hint(vec.as_mut_ptr(), len);
+        vec.set_len(len);
+    }
+    vec
+}
+
+/// Allocates an empty vec with room for `len` bytes in fresh, word-aligned memory.
+fn alloc_vec(len: usize) -> Vec<u8> {
     // Round up to the nearest multiple of 4 so that the memory allocated is in whole words
     let capacity = (len + 3) / 4 * 4;
 
@@ -45,14 +158,7 @@ pub fn read_vec() -> Vec<u8> {
     // 3/6. Size is correct from above
     // 4/5. Length is 0
     // 7. Layout::from_size_align already checks this
-    let mut vec = unsafe { Vec::from_raw_parts(ptr, 0, capacity) };
-    // Read the vec into uninitialized memory. The syscall assumes the memory is uninitialized,
-    // which should be true because the allocator does not dealloc, so a new alloc should be fresh.
-    unsafe {
-        syscall_hint_read(ptr, len);
-        vec.set_len(len);
-    }
-    vec
+    unsafe { Vec::from_raw_parts(ptr, 0, capacity) }
 }
 
 pub fn read<T: DeserializeOwned>() -> T {
@@ -74,6 +180,163 @@ pub fn commit_slice(buf: &[u8]) {
     my_writer.write_all(buf).unwrap();
 }
 
+/// Selects the hash function of the digest of the public values which the program commits to,
+/// SHA-256 by default.
+///
+/// Keccak256 is cheaper to check on Ethereum, and Poseidon2 in a recursive proof. The verifier
+/// does not need to be told: the digest only matches the public values under the scheme the
+/// program selected, which `SP1PublicValues::commitment_scheme` recovers. The scheme must be
+/// selected before anything is committed, and the program panics otherwise.
+pub fn set_commitment_scheme(scheme: CommitmentScheme) {
+    unsafe {
+        syscall_set_commitment_scheme(scheme as u32);
+    }
+}
+
+/// The last slice committed with `commit_slice_assert_sorted`. The program is single-threaded, so
+/// it needs no lock.
+static mut LAST_SORTED_COMMIT: Option<Vec<u8>> = None;
+
+/// Commits `buf` to the public values, asserting that it is strictly greater, in lexicographic
+/// order, than the slice committed by the previous call.
+///
+/// This is a guest-only convenience, not a constraint of the zkVM: the ordering check is ordinary
+/// program code, so it only binds the proof because it is part of the program, and the verifier
+/// must know the program commits its set with this function. An unsorted or duplicate slice makes
+/// the program panic, so no proof of a successful execution exists, and a set committed this way
+/// has a single, sorted and deduplicated, encoding. The slices should have the same length, or be
+/// length-prefixed, so that their concatenation in the public values is unambiguous.
+pub fn commit_slice_assert_sorted(buf: &[u8]) {
+    advance_sorted(unsafe { &mut LAST_SORTED_COMMIT }, buf);
+    commit_slice(buf);
+}
+
+/// Checks that `buf` is strictly greater than `last`, if any, and makes it the new `last`.
+fn advance_sorted(last: &mut Option<Vec<u8>>, buf: &[u8]) {
+    if let Some(last) = last.as_deref() {
+        assert!(last < buf, "committed values are not sorted and unique");
+    }
+    *last = Some(buf.to_vec());
+}
+
+/// Commits `buf` to the public values, and a schema id identifying its layout to the footer.
+///
+/// The schema id is typically a hash of a description of the output type. The verifier checks it
+/// with `SP1PublicValues::verify_schema`, so that a change to the layout of the public values is
+/// not silently misinterpreted. A program sets at most one schema id, and panics otherwise.
+#[cfg(feature = "footer")]
+pub fn commit_with_schema(schema_id: &[u8; 32], buf: &[u8]) {
+    unsafe {
+        syscall_commit_schema_id(schema_id.as_ptr());
+    }
+    commit_slice(buf);
+}
+
+/// Commits only the root of a Poseidon2 Merkle tree over `values` to the public values.
+///
+/// The root is committed as eight little-endian words. The host rebuilds the tree with
+/// `MerkleizedValues` and proves single values against the root, so a verifier can check one
+/// output without learning the others.
+pub fn commit_merkleized(values: &[&[u8]]) {
+    let root = merkle_root(values);
+    let bytes = root.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<_>>();
+    commit_slice(&bytes);
+}
+
+/// Commits only the Poseidon2 hash of `buf` to the public values, binding the program to `buf`
+/// without revealing it.
+///
+/// The hash is the leaf digest of `commit_merkleized`, committed as eight little-endian words. The
+/// host recomputes it from a revealed preimage with `hashed_commitment`, and a composed proof can
+/// show knowledge of the preimage by recomputing the hash in the guest.
+pub fn commit_hashed(buf: &[u8]) {
+    let digest = leaf_digest(buf);
+    let bytes = digest.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<_>>();
+    commit_slice(&bytes);
+}
+
+/// Sets an error code which is committed to the footer of the public values when the program
+/// halts.
+///
+/// Unlike a panic, the program keeps running and halts successfully, so the proof shows that the
+/// program ran and reported the error. The host reads it back from
+/// `PublicValuesFooter::error_code`. Later calls overwrite the code.
+#[cfg(feature = "footer")]
+pub fn set_error_code(code: u32) {
+    unsafe {
+        syscall_set_error_code(code);
+    }
+}
+
+/// Commits a nonce to the footer of the public values when the program halts, binding the proof to
+/// it.
+///
+/// The nonce should be read from the inputs, e.g. with `read`, so that the verifier supplies a
+/// fresh one for each proof it requests and checks it with `PublicValuesFooter::verify_nonce`,
+/// which rejects a proof replayed with a stale nonce. A program commits at most one nonce, and
+/// panics otherwise.
+#[cfg(feature = "footer")]
+pub fn commit_nonce(nonce: u64) {
+    unsafe {
+        syscall_commit_nonce(nonce);
+    }
+}
+
+/// Commits the digest of the inputs the program read so far to the footer of the public values,
+/// so that the verifier knows which inputs the proof is about.
+///
+/// Every input read with `read`, `read_vec` or `read_slice` is hashed with its length, so that the
+/// host can compute the same digest with `SP1Stdin::input_digest_of` the number of inputs read.
+/// Inputs read after the call are not part of the digest. A program commits at most one digest,
+/// and panics otherwise.
+#[cfg(feature = "footer")]
+pub fn commit_input_digest() {
+    unsafe {
+        syscall_commit_input_digest();
+    }
+}
+
+/// The error code committed when the program panics with a message which has no registered code,
+/// once a panic code is registered with `register_panic_code`.
+#[cfg(feature = "footer")]
+pub const DEFAULT_PANIC_CODE: u32 = u32::MAX;
+
+/// The panic messages registered with `register_panic_code` and their error codes. The program is
+/// single-threaded, so it needs no lock.
+#[cfg(feature = "footer")]
+static mut PANIC_CODES: Vec<(&str, u32)> = Vec::new();
+
+/// Registers the error code which is committed to the public values, like with `set_error_code`,
+/// when the program panics with a message containing `message`.
+///
+/// Once a code is registered, panics with other messages commit `DEFAULT_PANIC_CODE`, so that the
+/// host can classify the failures of the program with `PublicValuesFooter::error_code`. A panic
+/// then also commits `PublicValuesFooter::panicked` and halts with exit code 0, so that the failed
+/// execution can be proven: the verifier must check the footer to tell it from a success. Later
+/// registrations take precedence.
+#[cfg(feature = "footer")]
+pub fn register_panic_code(message: &'static str, code: u32) {
+    unsafe {
+        PANIC_CODES.push((message, code));
+    }
+}
+
+/// Returns the error code of a panic with `message` registered with `register_panic_code`,
+/// `DEFAULT_PANIC_CODE` if no registered message matches, or `None` if no code is registered.
+#[cfg(feature = "footer")]
+pub fn panic_code(message: &str) -> Option<u32> {
+    let panic_codes = unsafe { &PANIC_CODES };
+    if panic_codes.is_empty() {
+        return None;
+    }
+    let code = panic_codes
+        .iter()
+        .rev()
+        .find(|(registered, _)| message.contains(registered))
+        .map_or(DEFAULT_PANIC_CODE, |(_, code)| *code);
+    Some(code)
+}
+
 pub fn hint<T: Serialize>(value: &T) {
     let writer = SyscallWriter { fd: FD_HINT };
     bincode::serialize_into(writer, value).expect("serialization failed");
@@ -88,3 +351,33 @@ pub fn hint_slice(buf: &[u8]) {
 pub fn write(fd: u32, buf: &[u8]) {
     SyscallWriter { fd }.write_all(buf).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::advance_sorted;
+
+    #[test]
+    fn test_advance_sorted() {
+        let mut last = None;
+        for value in [[0u8; 32], [1; 32], [2; 32]] {
+            advance_sorted(&mut last, &value);
+        }
+        assert_eq!(last, Some(vec![2; 32]));
+    }
+
+    #[test]
+    #[should_panic(expected = "committed values are not sorted and unique")]
+    fn test_advance_sorted_duplicate() {
+        let mut last = None;
+        advance_sorted(&mut last, &[1; 32]);
+        advance_sorted(&mut last, &[1; 32]);
+    }
+
+    #[test]
+    #[should_panic(expected = "committed values are not sorted and unique")]
+    fn test_advance_sorted_unsorted() {
+        let mut last = None;
+        advance_sorted(&mut last, &[2; 32]);
+        advance_sorted(&mut last, &[1; 32]);
+    }
+}
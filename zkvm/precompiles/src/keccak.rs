@@ -0,0 +1,77 @@
+use crate::syscall_keccak_f;
+
+/// The number of 64-bit lanes of the keccak-f[1600] state.
+pub const KECCAK_STATE_LANES: usize = 25;
+
+/// Applies one keccak-f[1600] permutation to `state` in place with the keccak permute precompile.
+pub fn keccak_f(state: &mut [u64; KECCAK_STATE_LANES]) {
+    unsafe {
+        syscall_keccak_f(state.as_mut_ptr());
+    }
+}
+
+/// The number of bytes absorbed by each permutation of keccak256.
+const KECCAK256_RATE: usize = 136;
+
+/// An incremental keccak256 hasher, the hash function of Ethereum, which applies the permutation
+/// with the keccak permute precompile.
+#[derive(Debug, Clone)]
+pub struct Keccak256 {
+    state: [u64; KECCAK_STATE_LANES],
+    buffer: [u8; KECCAK256_RATE],
+    buffer_len: usize,
+}
+
+impl Keccak256 {
+    pub const fn new() -> Self {
+        Self {
+            state: [0; KECCAK_STATE_LANES],
+            buffer: [0; KECCAK256_RATE],
+            buffer_len: 0,
+        }
+    }
+
+    /// Absorbs `data` into the hasher.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = data.len().min(KECCAK256_RATE - self.buffer_len);
+            self.buffer[self.buffer_len..self.buffer_len + len].copy_from_slice(&data[..len]);
+            self.buffer_len += len;
+            data = &data[len..];
+            if self.buffer_len == KECCAK256_RATE {
+                self.absorb_buffer();
+            }
+        }
+    }
+
+    /// Pads the absorbed data and returns its digest.
+    pub fn finalize(mut self) -> [u8; 32] {
+        // Keccak pads with a one bit, zeros and a final one bit, without the domain separation
+        // bits of SHA-3.
+        self.buffer[self.buffer_len..].fill(0);
+        self.buffer[self.buffer_len] ^= 0x01;
+        self.buffer[KECCAK256_RATE - 1] ^= 0x80;
+        self.absorb_buffer();
+
+        let mut digest = [0u8; 32];
+        for (bytes, lane) in digest.chunks_exact_mut(8).zip(self.state.iter()) {
+            bytes.copy_from_slice(&lane.to_le_bytes());
+        }
+        digest
+    }
+
+    /// XORs the full buffer into the state and permutes it.
+    fn absorb_buffer(&mut self) {
+        for (lane, bytes) in self.state.iter_mut().zip(self.buffer.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccak_f(&mut self.state);
+        self.buffer_len = 0;
+    }
+}
+
+impl Default for Keccak256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -5,9 +5,15 @@
 //! function impls must live in sp1-zkvm, which is only imported into the end user program crate.
 //! In contrast, sp1-precompiles can be imported into any crate in the dependency tree.
 
+pub mod babybear;
 pub mod bls12381;
 pub mod bn254;
+pub mod ed25519;
 pub mod io;
+pub mod keccak;
+pub mod merkle;
+pub mod msm;
+pub mod pedersen;
 pub mod secp256k1;
 pub mod unconstrained;
 pub mod utils;
@@ -19,6 +25,17 @@ pub const BIGINT_WIDTH_WORDS: usize = 8;
 extern "C" {
     pub fn syscall_halt(exit_code: u8) -> !;
     pub fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize);
+    pub fn syscall_set_commitment_scheme(scheme: u32);
+    #[cfg(feature = "footer")]
+    pub fn syscall_set_error_code(code: u32);
+    #[cfg(feature = "footer")]
+    pub fn syscall_commit_nonce(nonce: u64);
+    #[cfg(feature = "footer")]
+    pub fn syscall_commit_schema_id(schema_id: *const u8);
+    #[cfg(feature = "footer")]
+    pub fn syscall_record_invariant(tag: u32);
+    #[cfg(feature = "footer")]
+    pub fn syscall_commit_input_digest();
     pub fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize);
     pub fn syscall_sha256_extend(w: *mut u32);
     pub fn syscall_sha256_compress(w: *mut u32, state: *mut u32);
@@ -29,15 +46,23 @@ extern "C" {
     pub fn syscall_secp256k1_decompress(point: &mut [u8; 64], is_odd: bool);
     pub fn syscall_bn254_add(p: *mut u32, q: *const u32);
     pub fn syscall_bn254_double(p: *mut u32);
+    pub fn syscall_bn254_fr_inv(x: *mut u32);
+    pub fn syscall_bn254_fp_inv(x: *mut u32);
+    pub fn syscall_bn254_fp2_mul(x: *mut u32, y: *const u32);
     pub fn syscall_bls12381_add(p: *mut u32, q: *const u32);
     pub fn syscall_bls12381_double(p: *mut u32);
     pub fn syscall_keccak_permute(state: *mut u64);
     pub fn syscall_uint256_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint256_sqr(x: *mut u32, modulus: *const u32);
     pub fn syscall_enter_unconstrained() -> bool;
     pub fn syscall_exit_unconstrained();
     pub fn syscall_verify_sp1_proof(vkey: &[u32; 8], pv_digest: &[u8; 32]);
     pub fn syscall_hint_len() -> usize;
     pub fn syscall_hint_read(ptr: *mut u8, len: usize);
+    pub fn syscall_read_hint(out: *mut u8, len: usize);
+    pub fn syscall_cycles_remaining() -> u32;
+    pub fn syscall_shard_boundary();
+    pub fn syscall_has_precompile(code: u32) -> bool;
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
     pub fn syscall_bls12381_decompress(point: &mut [u8; 96], is_odd: bool);
     pub fn sys_bigint(
@@ -47,4 +72,76 @@ extern "C" {
         y: *const [u32; BIGINT_WIDTH_WORDS],
         modulus: *const [u32; BIGINT_WIDTH_WORDS],
     );
+    pub fn syscall_field_mul_small(x: *mut u32, k: u32, modulus: *const u32);
+    pub fn syscall_aes_encrypt_block(state: *mut u32, round_keys: *const u32);
+    pub fn syscall_crt_combine(
+        r1: *const u32,
+        m1: *const u32,
+        r2: *const u32,
+        m2: *const u32,
+        out: *mut u32,
+    );
+    pub fn syscall_uint256_cmov(dst: *mut u32, src: *const u32, cond: u32);
+    pub fn syscall_poseidon2_compress(left: *const u32, right: *const u32, out: *mut u32);
+    pub fn syscall_poseidon2_permute(state: *mut u32);
+    pub fn syscall_poseidon2_sponge_init(state_ptr: *mut u32);
+    pub fn syscall_poseidon2_sponge_absorb(state_ptr: *mut u32, data_ptr: *const u32, len: usize);
+    pub fn syscall_poseidon2_sponge_squeeze(state_ptr: *mut u32, out_ptr: *mut u32, len: usize);
+    pub fn syscall_field_batch_inv(ptr: *mut u32, len: u32, modulus: *const u32);
+    pub fn syscall_bytes_to_fields(bytes: *const u8, len: usize, out: *mut u32);
+    pub fn syscall_pedersen_hash(msg: *const u8, len: usize, out: *mut u32);
+    pub fn syscall_table_lookup(table_id: u32, key: u32) -> u32;
+    pub fn syscall_uint256_to_decimal(x: *const u32, out: *mut u8, out_len: *mut usize);
+    pub fn syscall_secp256k1_normalize_s(s: *mut u32);
+    pub fn syscall_uint256_mod_small(x: *const u32, m: u32) -> u32;
+    pub fn syscall_uint256_wnaf(scalar: *const u32, digits: *mut u32);
+    pub fn syscall_scalar_to_naf(scalar: *const u32, naf: *mut u32);
+    pub fn syscall_bn254_msm(scalars: *const u32, points: *const u32, n: usize, out: *mut u32);
+    pub fn syscall_keccak_f(state_ptr: *mut u64);
+    pub fn syscall_secp256k1_compress(point: *const u32, out: *mut u8);
+    pub fn syscall_memcpy(dst: *mut u8, src: *const u8, len: usize);
+    pub fn syscall_ct_memcmp(a: *const u8, b: *const u8, len: usize) -> bool;
+    pub fn syscall_ct_lookup(table: *const u32, table_len: u32, index: u32, out: *mut u32);
+    pub fn syscall_ed25519_verify(sig: *const u8, pubkey: *const u8, msg_hash: *const u8) -> bool;
+    pub fn syscall_hex_encode(data: *const u8, len: usize, out: *mut u8);
+    pub fn syscall_base64_encode(data: *const u8, len: usize, out: *mut u8);
+    pub fn syscall_prepare_hash_input(
+        input: *const u8,
+        len: usize,
+        out: *mut u8,
+        mode: u32,
+    ) -> usize;
+    pub fn syscall_field_mac(acc: *mut u32, a: *const u32, b: *const u32, modulus: *const u32);
+    pub fn syscall_field_affine(x: *mut u32, a: *const u32, b: *const u32, modulus: *const u32);
+    pub fn syscall_chacha20_block(key: *const u32, nonce: *const u32, counter: u32, out: *mut u32);
+    pub fn syscall_montgomery_reduce(
+        t: *const u32,
+        modulus: *const u32,
+        n_prime: u32,
+        out: *mut u32,
+    );
+    pub fn syscall_ntt_butterfly(
+        a: *mut u32,
+        b: *mut u32,
+        twiddle: *const u32,
+        modulus: *const u32,
+    );
+    pub fn syscall_scalar_in_range(x: *const u32, modulus: *const u32) -> bool;
+    pub fn syscall_secp256k1_derive_pubkey(privkey: *const u32, pubkey: *mut u32) -> bool;
+    pub fn syscall_secp256k1_ecdsa_sign(
+        privkey: *const u32,
+        msg_hash: *const u32,
+        nonce: *const u32,
+        sig: *mut u32,
+    ) -> bool;
+    pub fn syscall_secp256k1_ecrecover(
+        msg_hash: *const u32,
+        sig: *const u32,
+        recovery_id: u32,
+        pubkey: *mut u32,
+    ) -> bool;
+    pub fn syscall_field_is_zero(x: *const u32) -> bool;
+    pub fn syscall_uint256_shl(x: *mut u32, shift: u32);
+    pub fn syscall_uint256_shr(x: *mut u32, shift: u32);
+    pub fn syscall_uint256_gcd(x: *const u32, y: *const u32, out: *mut u32);
 }
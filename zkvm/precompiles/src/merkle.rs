@@ -0,0 +1,51 @@
+use crate::babybear::bytes_to_fields;
+use crate::syscall_poseidon2_compress;
+
+/// The number of BabyBear elements of a digest.
+pub const DIGEST_SIZE: usize = 8;
+
+/// A node of the Poseidon2 Merkle tree built by [`merkle_root`].
+pub type MerkleDigest = [u32; DIGEST_SIZE];
+
+/// Compresses two digests into one with the Poseidon2 precompile.
+pub fn compress(left: &MerkleDigest, right: &MerkleDigest) -> MerkleDigest {
+    let mut out = [0; DIGEST_SIZE];
+    unsafe {
+        syscall_poseidon2_compress(left.as_ptr(), right.as_ptr(), out.as_mut_ptr());
+    }
+    out
+}
+
+/// Hashes a value into a leaf of the tree.
+///
+/// The value is packed into BabyBear elements and absorbed in zero-padded blocks of eight elements
+/// by compressing them with the running digest, which starts as the length of the value. Make sure
+/// this matches `merkle_leaf_digest` in `core/src/utils/merkle.rs`.
+pub fn leaf_digest(value: &[u8]) -> MerkleDigest {
+    let mut fields = bytes_to_fields(value);
+    fields.resize(fields.len().div_ceil(DIGEST_SIZE).max(1) * DIGEST_SIZE, 0);
+    let mut digest = [0; DIGEST_SIZE];
+    digest[0] = value.len() as u32;
+    for block in fields.chunks_exact(DIGEST_SIZE) {
+        digest = compress(&digest, block.try_into().unwrap());
+    }
+    digest
+}
+
+/// Computes the root of the Merkle tree over `values`, whose leaves are padded with zero digests
+/// to a power of two.
+pub fn merkle_root(values: &[&[u8]]) -> MerkleDigest {
+    assert!(!values.is_empty(), "cannot merkleize an empty list of values");
+    let mut layer = values
+        .iter()
+        .map(|value| leaf_digest(value))
+        .collect::<Vec<_>>();
+    layer.resize(values.len().next_power_of_two(), [0; DIGEST_SIZE]);
+    while layer.len() > 1 {
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| compress(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer[0]
+}
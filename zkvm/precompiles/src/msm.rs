@@ -0,0 +1,99 @@
+//! Multi-scalar multiplication with Pippenger's bucket method.
+//!
+//! The sum `sum_i [k_i] P_i` is computed window by window, from the most significant one. In each
+//! window of `c` bits, every point is added to the bucket of its digit, and the buckets are summed
+//! with their weights by two running sums. This takes `n + 2^(c + 1)` additions and `c` doublings
+//! per window, against about `128` additions and `256` doublings per term for independent scalar
+//! multiplications.
+
+/// The number of words of a scalar.
+pub const MSM_SCALAR_WORDS: usize = 8;
+
+/// The number of bits of a scalar.
+const MSM_SCALAR_BITS: usize = MSM_SCALAR_WORDS * 32;
+
+/// A point on which a multi-scalar multiplication can be computed, with incomplete addition
+/// formulas such as those of the elliptic curve precompiles.
+pub trait MsmPoint: Clone {
+    /// Adds `other` to the point. The points are neither equal nor opposite.
+    fn add_assign(&mut self, other: &Self);
+
+    /// Doubles the point.
+    fn double(&mut self);
+
+    /// Returns whether the points are equal.
+    fn is_equal(&self, other: &Self) -> bool;
+
+    /// Returns whether the points are opposite, i.e. sum to the identity.
+    fn is_opposite(&self, other: &Self) -> bool;
+}
+
+/// Adds `point` to `sum`, where `None` is the identity, handling the equal and opposite points
+/// which the addition of [MsmPoint] does not support.
+pub fn msm_add_assign<P: MsmPoint>(sum: &mut Option<P>, point: &P) {
+    match sum.as_mut() {
+        None => *sum = Some(point.clone()),
+        Some(sum_point) if sum_point.is_equal(point) => sum_point.double(),
+        Some(sum_point) if sum_point.is_opposite(point) => *sum = None,
+        Some(sum_point) => sum_point.add_assign(point),
+    }
+}
+
+/// Adds `other` to `sum`, where `None` is the identity for both.
+fn msm_add_assign_option<P: MsmPoint>(sum: &mut Option<P>, other: &Option<P>) {
+    if let Some(point) = other {
+        msm_add_assign(sum, point);
+    }
+}
+
+/// Returns the window size, in bits, for a multi-scalar multiplication of `n` terms, which is about
+/// `ln(n)` to balance the additions into the buckets against the additions of the running sums.
+pub fn msm_window_bits(n: usize) -> usize {
+    (n.max(1).ilog2() as usize * 7 / 10 + 1).clamp(2, 16)
+}
+
+/// Returns the `index`th window of `bits` bits of the scalar, from the least significant one.
+fn scalar_window(scalar: &[u32; MSM_SCALAR_WORDS], index: usize, bits: usize) -> usize {
+    let mut digit = 0;
+    for bit in (index * bits..((index + 1) * bits).min(MSM_SCALAR_BITS)).rev() {
+        digit = (digit << 1) | ((scalar[bit / 32] >> (bit % 32)) & 1) as usize;
+    }
+    digit
+}
+
+/// Computes `sum_i [scalars[i]] points[i]` with Pippenger's bucket method, where `None` is the
+/// identity. The scalars are little-endian words.
+pub fn msm<P: MsmPoint>(scalars: &[[u32; MSM_SCALAR_WORDS]], points: &[P]) -> Option<P> {
+    assert_eq!(scalars.len(), points.len(), "one scalar is needed per point");
+    let bits = msm_window_bits(points.len());
+    let num_windows = MSM_SCALAR_BITS.div_ceil(bits);
+
+    let mut sum: Option<P> = None;
+    for window in (0..num_windows).rev() {
+        if let Some(sum) = sum.as_mut() {
+            for _ in 0..bits {
+                sum.double();
+            }
+        }
+
+        // The bucket of the digit `d` is at `d - 1`, since points of digit zero are skipped.
+        let mut buckets: Vec<Option<P>> = vec![None; (1 << bits) - 1];
+        for (scalar, point) in scalars.iter().zip(points) {
+            let digit = scalar_window(scalar, window, bits);
+            if digit != 0 {
+                msm_add_assign(&mut buckets[digit - 1], point);
+            }
+        }
+
+        // Sum the buckets with their digits as weights: the running sum of the buckets of digits
+        // at least `d` is added once for each `d`.
+        let mut running: Option<P> = None;
+        let mut window_sum: Option<P> = None;
+        for bucket in buckets.iter().rev() {
+            msm_add_assign_option(&mut running, bucket);
+            msm_add_assign_option(&mut window_sum, &running);
+        }
+        msm_add_assign_option(&mut sum, &window_sum);
+    }
+    sum
+}
@@ -0,0 +1,91 @@
+//! A Pedersen hash over secp256k1.
+//!
+//! A message of at most [`PEDERSEN_MAX_LEN`] bytes is split into chunks of
+//! [`PEDERSEN_CHUNK_SIZE`] bytes, and each chunk `m_i`, read as a little-endian scalar, is
+//! multiplied by its own fixed generator. The length of the message is bound with the first
+//! generator, so messages that only differ by trailing zero bytes hash differently:
+//!
+//! `H = [len + 1] G_0 + sum_i [m_i] G_{i + 1}`
+//!
+//! The generators are hashed to the curve with try-and-increment, so nobody knows the discrete
+//! logarithm relations between them: `G_i` has the smallest counter `c` for which the
+//! little-endian `x = sha256("SP1 Pedersen generator" || i || c)`, with `i` and `c` single bytes,
+//! is on the curve, and it has an even `y`.
+
+use crate::syscall_pedersen_hash;
+
+/// The number of message bytes in each chunk. A chunk is always below the group order, so distinct
+/// chunks are distinct scalars.
+pub const PEDERSEN_CHUNK_SIZE: usize = 31;
+
+/// The maximum number of chunks of a message.
+pub const PEDERSEN_MAX_CHUNKS: usize = 8;
+
+/// The maximum length of a message, in bytes.
+pub const PEDERSEN_MAX_LEN: usize = PEDERSEN_CHUNK_SIZE * PEDERSEN_MAX_CHUNKS;
+
+/// The generators of the hash, as little-endian words of `x` followed by `y`. The first one is
+/// used for the length of the message, and the others for its chunks.
+pub const PEDERSEN_GENERATORS: [[u32; 16]; PEDERSEN_MAX_CHUNKS + 1] = [
+    [
+        284985350, 4009502045, 459829923, 2810490682, 1458101470, 1541051251, 2747038847,
+        1345552010, 2168318700, 1816846412, 3592897361, 2178606690, 3923372548, 1887507567,
+        418110761, 3990318908,
+    ],
+    [
+        2907913454, 1967919229, 2707265243, 1448084653, 2185781160, 2787296794, 2820514560,
+        4137283969, 2368851704, 1048061730, 2950228172, 458465831, 3209903055, 2007854951,
+        1176602388, 1137071712,
+    ],
+    [
+        2491924381, 4117444430, 1753320936, 1992018114, 1551326270, 192074015, 3945904081,
+        1191910138, 2524002458, 1407199499, 3204024677, 3998566287, 4111310406, 1158536845,
+        3120414910, 579183481,
+    ],
+    [
+        3360488095, 409606334, 569188930, 1602852044, 1259327178, 1902237663, 3099278611,
+        483316922, 3221043846, 550472006, 3130468876, 2148533256, 4070186456, 666766056, 745775767,
+        971012576,
+    ],
+    [
+        4110552744, 1828734638, 4209959104, 2454127767, 4023025673, 211677280, 2509360342,
+        4083266530, 1443938302, 2977517514, 2517054017, 1852372750, 3562872736, 3727228384,
+        2419145101, 2433981827,
+    ],
+    [
+        137238636, 2154676603, 1260082484, 4032882448, 1124153296, 337397009, 618760332, 51564140,
+        3739921288, 2696826849, 313352251, 2359324617, 725052300, 4234957322, 3676155389,
+        2559455624,
+    ],
+    [
+        2185098627, 3062761853, 1490865650, 283282902, 1425014120, 1040497464, 3115779726,
+        1596346071, 3097559494, 3776555818, 890655327, 280275265, 1364094543, 807355578, 577379252,
+        2936736482,
+    ],
+    [
+        1596190257, 1151087981, 1279021502, 3191307067, 2248409809, 47219412, 1630094618,
+        1296362439, 2099351890, 3718028582, 1471470734, 2842444408, 1564830333, 1428906875,
+        1449233202, 2838529174,
+    ],
+    [
+        2414340832, 2453048051, 831760439, 2813822232, 2823380243, 420948405, 3293104017,
+        4006242084, 4041623026, 789345935, 1949579180, 3308922572, 1101459721, 1544134379,
+        76706387, 249620530,
+    ],
+];
+
+/// Returns the scalar of a chunk of at most 32 bytes, as little-endian words.
+pub fn pedersen_scalar(chunk: &[u8]) -> [u32; 8] {
+    let mut bytes = [0u8; 32];
+    bytes[..chunk.len()].copy_from_slice(chunk);
+    core::array::from_fn(|i| u32::from_le_bytes(bytes[i * 4..(i + 1) * 4].try_into().unwrap()))
+}
+
+/// Computes the Pedersen hash of a message, as the little-endian words of `x` followed by `y`.
+pub fn pedersen_hash(msg: &[u8]) -> [u32; 16] {
+    let mut out = [0u32; 16];
+    unsafe {
+        syscall_pedersen_hash(msg.as_ptr(), msg.len(), out.as_mut_ptr());
+    }
+    out
+}
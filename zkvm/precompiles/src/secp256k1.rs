@@ -1,15 +1,20 @@
 #![allow(unused)]
 
 use crate::utils::{AffinePoint, CurveOperations};
-use crate::{syscall_secp256k1_add, syscall_secp256k1_decompress, syscall_secp256k1_double};
+use crate::{
+    syscall_secp256k1_add, syscall_secp256k1_compress, syscall_secp256k1_decompress,
+    syscall_secp256k1_double,
+};
 use anyhow::Context;
 use anyhow::{anyhow, Result};
 use core::convert::TryInto;
+use core::fmt;
 use k256::ecdsa::hazmat::bits2field;
 use k256::ecdsa::signature::hazmat::PrehashVerifier;
 use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
 use k256::elliptic_curve::ff::PrimeFieldBits;
 use k256::elliptic_curve::ops::Invert;
+use k256::elliptic_curve::scalar::IsHigh;
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use k256::elliptic_curve::PrimeField;
 use k256::{PublicKey, Scalar, Secp256k1};
@@ -29,6 +34,13 @@ impl CurveOperations<NUM_WORDS> for Secp256k1Operations {
         4212184248, 2621952143, 2793755673, 4246189128, 235997352, 1571093500, 648266853,
         1211816567,
     ];
+
+    // The modulus is 2^256 - 2^32 - 977.
+    const BASE_FIELD_MODULUS: &'static [u32] = &[
+        4294966319, 4294967294, 4294967295, 4294967295, 4294967295, 4294967295, 4294967295,
+        4294967295,
+    ];
+
     fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]) {
         unsafe {
             syscall_secp256k1_add(limbs.as_mut_ptr(), other.as_ptr());
@@ -71,6 +83,40 @@ pub fn decompress_pubkey(compressed_key: &[u8; 33]) -> Result<[u8; 65]> {
     }
 }
 
+/// Compresses an uncompressed public key using secp256k1_compress precompile.
+pub fn compress_pubkey(decompressed_key: &[u8; 65]) -> Result<[u8; 33]> {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            if decompressed_key[0] != 4 {
+                return Err(anyhow!("Invalid decompressed key"));
+            }
+
+            // The precompile takes the coordinates as little-endian words.
+            let mut point = [0u32; NUM_WORDS];
+            for (i, coordinate) in decompressed_key[1..].chunks_exact(32).enumerate() {
+                let mut bytes: [u8; 32] = coordinate.try_into().unwrap();
+                bytes.reverse();
+                for (j, word) in bytes.chunks_exact(4).enumerate() {
+                    point[8 * i + j] = u32::from_le_bytes(word.try_into().unwrap());
+                }
+            }
+
+            let mut result: [u8; 33] = [0; 33];
+            unsafe {
+                syscall_secp256k1_compress(point.as_ptr(), result.as_mut_ptr());
+            }
+            Ok(result)
+        } else {
+            let public_key =
+                PublicKey::from_sec1_bytes(decompressed_key).context("invalid pubkey")?;
+            let bytes = public_key.to_encoded_point(true).to_bytes();
+            let mut result: [u8; 33] = [0; 33];
+            result.copy_from_slice(&bytes);
+            Ok(result)
+        }
+    }
+}
+
 /// Verifies a secp256k1 signature using the public key and the message hash. If the s_inverse is
 /// provided, it will be validated and used to verify the signature. Otherwise, the inverse of s
 /// will be computed and used.
@@ -148,6 +194,62 @@ pub fn verify_signature(
     }
 }
 
+/// The reason an ECDSA signature is rejected by [`ecdsa_verify_canonical`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcdsaError {
+    /// The signature is neither a 64-byte compact signature nor a DER signature, or `r` or `s` is
+    /// zero or not below the curve order.
+    InvalidEncoding,
+    /// `s` is above `n / 2`. The signature with `n - s` is just as valid, so accepting both would
+    /// make signatures malleable.
+    HighS,
+    /// The signature does not verify against the public key and the message hash.
+    VerificationFailed,
+}
+
+impl fmt::Display for EcdsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EcdsaError::InvalidEncoding => write!(f, "invalid signature encoding"),
+            EcdsaError::HighS => write!(f, "signature is not canonical: s is above n / 2"),
+            EcdsaError::VerificationFailed => write!(f, "signature verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for EcdsaError {}
+
+/// Verifies a canonical secp256k1 signature, as required by Bitcoin and Ethereum.
+///
+/// The signature is either the 64-byte compact encoding of `r` and `s`, or a DER encoding. Unlike
+/// [`verify_signature`], signatures with a high `s`, above `n / 2`, are rejected, since the
+/// signature with `n - s` verifies too.
+///
+/// Warning: like [`verify_signature`], this function does not check if the key is actually on the
+/// curve.
+pub fn ecdsa_verify_canonical(
+    msg_hash: &[u8; 32],
+    sig: &[u8],
+    pubkey: &[u8; 65],
+) -> Result<(), EcdsaError> {
+    let signature = if sig.len() == 64 {
+        Signature::from_slice(sig)
+    } else {
+        Signature::from_der(sig)
+    }
+    .map_err(|_| EcdsaError::InvalidEncoding)?;
+
+    if bool::from(signature.s().is_high()) {
+        return Err(EcdsaError::HighS);
+    }
+
+    if verify_signature(pubkey, msg_hash, &signature, None) {
+        Ok(())
+    } else {
+        Err(EcdsaError::VerificationFailed)
+    }
+}
+
 #[allow(non_snake_case)]
 fn double_and_add_base(
     a: &Scalar,
@@ -224,3 +326,71 @@ pub fn ecrecover(sig: &[u8; 65], msg_hash: &[u8; 32]) -> Result<[u8; 65]> {
         Err(anyhow!("failed to verify signature"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+    use k256::ecdsa::{Signature, SigningKey};
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    use super::{ecdsa_verify_canonical, EcdsaError};
+
+    const MSG_HASH: [u8; 32] = [0x42; 32];
+
+    /// Returns an uncompressed public key and a canonical signature of `MSG_HASH` with it.
+    fn sign() -> ([u8; 65], Signature) {
+        let signing_key = SigningKey::from_slice(&[0x17; 32]).unwrap();
+        let pubkey = signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap();
+        let signature: Signature = signing_key.sign_prehash(&MSG_HASH).unwrap();
+        (pubkey, signature.normalize_s().unwrap_or(signature))
+    }
+
+    #[test]
+    fn test_ecdsa_verify_canonical() {
+        let (pubkey, signature) = sign();
+        let compact = signature.to_bytes();
+        assert_eq!(ecdsa_verify_canonical(&MSG_HASH, &compact, &pubkey), Ok(()));
+        let der = signature.to_der();
+        assert_eq!(ecdsa_verify_canonical(&MSG_HASH, der.as_bytes(), &pubkey), Ok(()));
+    }
+
+    #[test]
+    fn test_ecdsa_verify_canonical_high_s() {
+        let (pubkey, signature) = sign();
+        let (r, s) = signature.split_scalars();
+        let high_s = Signature::from_scalars(r, -*s).unwrap();
+        assert_eq!(
+            ecdsa_verify_canonical(&MSG_HASH, &high_s.to_bytes(), &pubkey),
+            Err(EcdsaError::HighS)
+        );
+        assert_eq!(
+            ecdsa_verify_canonical(&MSG_HASH, high_s.to_der().as_bytes(), &pubkey),
+            Err(EcdsaError::HighS)
+        );
+    }
+
+    #[test]
+    fn test_ecdsa_verify_canonical_tampered() {
+        let (pubkey, signature) = sign();
+        let mut msg_hash = MSG_HASH;
+        msg_hash[0] ^= 1;
+        assert_eq!(
+            ecdsa_verify_canonical(&msg_hash, &signature.to_bytes(), &pubkey),
+            Err(EcdsaError::VerificationFailed)
+        );
+
+        let mut compact = signature.to_bytes();
+        compact[63] ^= 1;
+        assert!(ecdsa_verify_canonical(&MSG_HASH, &compact, &pubkey).is_err());
+
+        assert_eq!(
+            ecdsa_verify_canonical(&MSG_HASH, &compact[..63], &pubkey),
+            Err(EcdsaError::InvalidEncoding)
+        );
+    }
+}
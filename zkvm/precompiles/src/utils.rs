@@ -1,6 +1,21 @@
+use crate::msm::MsmPoint;
+use crate::{syscall_scalar_to_naf, syscall_uint256_wnaf};
+
+/// The number of signed digits of the recoding of a 256-bit scalar by `syscall_uint256_wnaf`.
+pub const NUM_WNAF_DIGITS: usize = 65;
+
+/// The number of words of a scalar recoded by `syscall_uint256_wnaf`.
+const WNAF_SCALAR_WORDS: usize = 8;
+
+/// The number of signed digits of the width-4 NAF of a 256-bit scalar by `syscall_scalar_to_naf`.
+pub const NUM_NAF_DIGITS: usize = 257;
+
 pub trait CurveOperations<const NUM_WORDS: usize> {
     const GENERATOR: [u32; NUM_WORDS];
 
+    /// The modulus of the base field, in little endian words.
+    const BASE_FIELD_MODULUS: &'static [u32];
+
     fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]);
     fn double(limbs: &mut [u32; NUM_WORDS]);
 }
@@ -53,9 +68,29 @@ impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> AffinePoint<C
         C::double(&mut self.limbs);
     }
 
+    /// Negates the point, by replacing `y` with `p - y`.
+    pub fn negate(&mut self) {
+        let y = &mut self.limbs[NUM_WORDS / 2..];
+        if y.iter().all(|&limb| limb == 0) {
+            return;
+        }
+        let mut borrow = false;
+        for (limb, &modulus) in y.iter_mut().zip(C::BASE_FIELD_MODULUS) {
+            let (diff, borrow_modulus) = modulus.overflowing_sub(*limb);
+            let (diff, borrow_diff) = diff.overflowing_sub(borrow as u32);
+            *limb = diff;
+            borrow = borrow_modulus || borrow_diff;
+        }
+    }
+
     pub fn mul_assign(&mut self, scalar: &[u32]) {
         debug_assert!(scalar.len() == NUM_WORDS / 2);
 
+        if scalar.len() == WNAF_SCALAR_WORDS {
+            self.mul_assign_wnaf(scalar.try_into().unwrap());
+            return;
+        }
+
         let mut res: Option<Self> = None;
         let mut temp = *self;
 
@@ -75,6 +110,45 @@ impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> AffinePoint<C
         *self = res.unwrap();
     }
 
+    /// Multiplies the point by a 256-bit scalar, with one addition per window of four bits.
+    ///
+    /// The scalar is recoded into signed digits in `[-8, 8)` by the `UINT256_WNAF` precompile, so
+    /// only the multiples `P, ..., 8P` are precomputed, and a negative digit adds the negation of a
+    /// multiple. The partial sums are positive multiples of sixteen before each addition, so they
+    /// never equal plus or minus the added multiple, which the add precompile does not support.
+    fn mul_assign_wnaf(&mut self, scalar: &[u32; WNAF_SCALAR_WORDS]) {
+        let base = *self;
+        let mut table = [base; 8];
+        table[1].double();
+        for i in 2..table.len() {
+            table[i] = table[i - 1];
+            table[i].add_assign(&base);
+        }
+
+        let mut res: Option<Self> = None;
+        for &digit in wnaf_digits(scalar).iter().rev() {
+            if let Some(res) = res.as_mut() {
+                for _ in 0..4 {
+                    res.double();
+                }
+            }
+            if digit == 0 {
+                continue;
+            }
+
+            let mut term = table[digit.unsigned_abs() as usize - 1];
+            if digit < 0 {
+                term.negate();
+            }
+            match res.as_mut() {
+                Some(res) => res.add_assign(&term),
+                None => res = Some(term),
+            };
+        }
+
+        *self = res.unwrap();
+    }
+
     pub fn from_le_bytes(limbs: &[u8]) -> Self {
         let u32_limbs = bytes_to_words_le(limbs);
         debug_assert!(u32_limbs.len() == NUM_WORDS);
@@ -92,6 +166,56 @@ impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> AffinePoint<C
     }
 }
 
+impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> MsmPoint
+    for AffinePoint<C, NUM_WORDS>
+{
+    fn add_assign(&mut self, other: &Self) {
+        AffinePoint::add_assign(self, other);
+    }
+
+    fn double(&mut self) {
+        AffinePoint::double(self);
+    }
+
+    fn is_equal(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+
+    fn is_opposite(&self, other: &Self) -> bool {
+        // Points with the same `x` are either equal or opposite.
+        self.limbs[..NUM_WORDS / 2] == other.limbs[..NUM_WORDS / 2] && !self.is_equal(other)
+    }
+}
+
+/// Recodes a 256-bit scalar into signed digits `d_i` with `scalar = sum d_i * 16^i`, using the
+/// `UINT256_WNAF` precompile.
+pub fn wnaf_digits(scalar: &[u32; WNAF_SCALAR_WORDS]) -> [i8; NUM_WNAF_DIGITS] {
+    let mut words = [0u32; NUM_WNAF_DIGITS.div_ceil(4)];
+    unsafe {
+        syscall_uint256_wnaf(scalar.as_ptr(), words.as_mut_ptr());
+    }
+    let mut digits = [0i8; NUM_WNAF_DIGITS];
+    for (digit, byte) in digits.iter_mut().zip(words_to_bytes_le(&words)) {
+        *digit = byte as i8;
+    }
+    digits
+}
+
+/// Recodes a 256-bit scalar into its width-4 NAF, signed digits `d_i` with
+/// `scalar = sum d_i * 2^i` such that every nonzero digit is odd, lies in `[-7, 7]` and is followed
+/// by at least three zero digits, using the `SCALAR_TO_NAF` precompile.
+pub fn naf_digits(scalar: &[u32; WNAF_SCALAR_WORDS]) -> [i8; NUM_NAF_DIGITS] {
+    let mut words = [0u32; NUM_NAF_DIGITS.div_ceil(4)];
+    unsafe {
+        syscall_scalar_to_naf(scalar.as_ptr(), words.as_mut_ptr());
+    }
+    let mut digits = [0i8; NUM_NAF_DIGITS];
+    for (digit, byte) in digits.iter_mut().zip(words_to_bytes_le(&words)) {
+        *digit = byte as i8;
+    }
+    digits
+}
+
 /// Converts a slice of words to a byte array in little endian.
 pub fn words_to_bytes_le(words: &[u32]) -> Vec<u8> {
     words